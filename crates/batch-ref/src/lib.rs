@@ -18,62 +18,242 @@ fn decode_pointer_token(token: &str) -> Result<String, String> {
     Ok(out)
 }
 
-fn resolve_json_pointer<'a>(
-    root: &'a serde_json::Value,
-    pointer: &str,
-) -> Result<&'a serde_json::Value, String> {
+fn pointer_tokens(pointer: &str, wrapper: &str) -> Result<Vec<String>, String> {
     let pointer = pointer.strip_prefix('#').unwrap_or(pointer);
     if pointer.is_empty() {
-        return Ok(root);
+        return Ok(Vec::new());
     }
     if !pointer.starts_with('/') {
         return Err(format!(
-            "$ref must be a JSON pointer starting with '#/' or '/': got {pointer:?}"
+            "{wrapper} must be a JSON pointer starting with '#/' or '/': got {pointer:?}"
         ));
     }
+    pointer
+        .split('/')
+        .skip(1)
+        .map(decode_pointer_token)
+        .collect()
+}
 
-    let mut tokens = Vec::new();
-    for raw in pointer.split('/').skip(1) {
-        tokens.push(decode_pointer_token(raw)?);
-    }
-
-    if tokens.len() >= 3 && tokens[0] == "items" && tokens[2] == "data" {
-        if let Some(item) = root.get("items").and_then(|v| v.get(&tokens[1])) {
-            if item.get("status").and_then(|v| v.as_str()) == Some("error") {
+fn ensure_item_ok(root: &serde_json::Value, id: &str, wrapper: &str) -> Result<(), String> {
+    if let Some(item) = root.get("items").and_then(|v| v.get(id)) {
+        match item.get("status").and_then(|v| v.as_str()) {
+            Some("error") => {
                 let msg = item
                     .get("message")
                     .and_then(|v| v.as_str())
                     .unwrap_or("unknown error");
-                return Err(format!("$ref points to failed item '{}': {msg}", tokens[1]));
+                return Err(format!("{wrapper} points to failed item '{id}': {msg}"));
             }
+            Some("skipped") => {
+                return Err(format!("{wrapper} points to skipped item '{id}'"));
+            }
+            _ => {}
         }
     }
+    Ok(())
+}
+
+fn resolve_json_pointer<'a>(
+    root: &'a serde_json::Value,
+    pointer: &str,
+) -> Result<&'a serde_json::Value, String> {
+    let tokens = pointer_tokens(pointer, "$ref")?;
+    if tokens.len() >= 3 && tokens[0] == "items" && tokens[2] == "data" {
+        ensure_item_ok(root, &tokens[1], "$ref")?;
+    }
 
     let mut current = root;
     for token in tokens {
-        match current {
-            serde_json::Value::Object(map) => {
-                current = map
-                    .get(&token)
-                    .ok_or_else(|| format!("$ref path {pointer:?} not found at key {token:?}"))?;
+        current = step(current, &token, pointer, "$ref")?;
+    }
+
+    Ok(current)
+}
+
+fn step<'a>(
+    current: &'a serde_json::Value,
+    token: &str,
+    pointer: &str,
+    wrapper: &str,
+) -> Result<&'a serde_json::Value, String> {
+    match current {
+        serde_json::Value::Object(map) => map
+            .get(token)
+            .ok_or_else(|| format!("{wrapper} path {pointer:?} not found at key {token:?}")),
+        serde_json::Value::Array(arr) => {
+            let idx: usize = token.parse().map_err(|_| {
+                format!("{wrapper} path {pointer:?} expected array index, got {token:?}")
+            })?;
+            arr.get(idx).ok_or_else(|| {
+                format!("{wrapper} path {pointer:?} array index out of bounds: {idx}")
+            })
+        }
+        _ => Err(format!(
+            "{wrapper} path {pointer:?} reached non-container before token {token:?}"
+        )),
+    }
+}
+
+/// Resolve a pointer whose tokens may be `*` (every array element / object value, objects in
+/// key order) into the flat list of matched values.
+fn resolve_json_pointer_list<'a>(
+    root: &'a serde_json::Value,
+    pointer: &str,
+) -> Result<Vec<&'a serde_json::Value>, String> {
+    let tokens = pointer_tokens(pointer, "$refList")?;
+    let checks_items = tokens.len() >= 3 && tokens[0] == "items" && tokens[2] == "data";
+
+    let mut current = vec![root];
+    for (pos, token) in tokens.iter().enumerate() {
+        let mut next = Vec::new();
+        for value in current {
+            if token == "*" {
+                match value {
+                    serde_json::Value::Array(arr) => next.extend(arr.iter()),
+                    serde_json::Value::Object(map) => next.extend(map.values()),
+                    _ => {
+                        return Err(format!(
+                            "$refList path {pointer:?} wildcard reached a non-container"
+                        ));
+                    }
+                }
+            } else {
+                next.push(step(value, token, pointer, "$refList")?);
             }
-            serde_json::Value::Array(arr) => {
-                let idx: usize = token.parse().map_err(|_| {
-                    format!("$ref path {pointer:?} expected array index, got {token:?}")
-                })?;
-                current = arr.get(idx).ok_or_else(|| {
-                    format!("$ref path {pointer:?} array index out of bounds: {idx}")
+        }
+        current = next;
+
+        // `#/items/*/data/...` checks every matched item, not just a named one.
+        if checks_items && pos == 1 {
+            let ids: Vec<String> = if token == "*" {
+                root.get("items")
+                    .and_then(|v| v.as_object())
+                    .map(|items| items.keys().cloned().collect())
+                    .unwrap_or_default()
+            } else {
+                vec![token.clone()]
+            };
+            for id in ids {
+                ensure_item_ok(root, &id, "$refList")?;
+            }
+        }
+    }
+
+    Ok(current)
+}
+
+fn parse_slice(value: &serde_json::Value) -> Result<(usize, Option<usize>), String> {
+    let bound = |v: &serde_json::Value| {
+        v.as_u64()
+            .and_then(|n| usize::try_from(n).ok())
+            .ok_or_else(|| "$slice bounds must be non-negative integers".to_string())
+    };
+    match value.as_array().map(Vec::as_slice) {
+        Some([start]) => Ok((bound(start)?, None)),
+        Some([start, end]) => Ok((bound(start)?, Some(bound(end)?))),
+        _ => Err("$slice must be [start] or [start, end]".to_string()),
+    }
+}
+
+fn resolve_ref_list(
+    map: &serde_json::Map<String, serde_json::Value>,
+    ctx: &serde_json::Value,
+) -> Result<Vec<serde_json::Value>, String> {
+    let pointer = map
+        .get("$refList")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "$refList must be a string".to_string())?;
+    let unique = match map.get("$unique") {
+        None => false,
+        Some(v) => v
+            .as_bool()
+            .ok_or_else(|| "$unique must be a boolean".to_string())?,
+    };
+    let slice = map.get("$slice").map(parse_slice).transpose()?;
+
+    let mut values: Vec<serde_json::Value> = Vec::new();
+    for found in resolve_json_pointer_list(ctx, pointer)? {
+        if unique && values.contains(found) {
+            continue;
+        }
+        values.push(found.clone());
+    }
+
+    if let Some((start, end)) = slice {
+        let end = end.unwrap_or(values.len()).min(values.len());
+        let start = start.min(end);
+        values.truncate(end);
+        values.drain(..start);
+    }
+    Ok(values)
+}
+
+fn format_arg(index: usize, value: &serde_json::Value) -> Result<String, String> {
+    match value {
+        serde_json::Value::String(s) => Ok(s.clone()),
+        serde_json::Value::Number(n) => Ok(n.to_string()),
+        other => {
+            let kind = match other {
+                serde_json::Value::Null => "null",
+                serde_json::Value::Bool(_) => "a boolean",
+                serde_json::Value::Array(_) => "an array",
+                _ => "an object",
+            };
+            Err(format!(
+                "$format args must be strings or numbers (arg {index} is {kind})"
+            ))
+        }
+    }
+}
+
+/// Substitute `{N}` placeholders with positional args; `{{` and `}}` are literal braces.
+fn render_format(template: &str, args: &[serde_json::Value]) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '{' => {
+                let mut digits = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(d) if d.is_ascii_digit() => digits.push(d),
+                        _ => {
+                            return Err(format!(
+                                "$format placeholder must be {{N}} (use {{{{ / }}}} for literal braces): {template:?}"
+                            ));
+                        }
+                    }
+                }
+                let index: usize = digits
+                    .parse()
+                    .map_err(|_| format!("$format placeholder must be {{N}}, got {{{digits}}}"))?;
+                let arg = args.get(index).ok_or_else(|| {
+                    format!(
+                        "$format placeholder {{{index}}} has no matching arg ({} given)",
+                        args.len()
+                    )
                 })?;
+                out.push_str(&format_arg(index, arg)?);
             }
-            _ => {
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '}' => {
                 return Err(format!(
-                    "$ref path {pointer:?} reached non-container before token {token:?}"
+                    "$format has an unmatched '}}' (use }}}} for a literal brace): {template:?}"
                 ));
             }
+            other => out.push(other),
         }
     }
-
-    Ok(current)
+    Ok(out)
 }
 
 fn resolve_inner(
@@ -95,6 +275,47 @@ fn resolve_inner(
         }
         serde_json::Value::Object(map) => {
             let default_value = map.get("$default").cloned();
+            let is_format_wrapper = map.contains_key("$format")
+                && map.keys().all(|k| matches!(k.as_str(), "$format" | "args"));
+            if is_format_wrapper {
+                let template = map
+                    .get("$format")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| "$format must be a string".to_string())?
+                    .to_string();
+                let args = match map.get("args").cloned() {
+                    None => Vec::new(),
+                    Some(serde_json::Value::Array(args)) => {
+                        let mut out = Vec::with_capacity(args.len());
+                        for arg in args {
+                            out.push(resolve_inner(arg, ctx, depth + 1)?);
+                        }
+                        out
+                    }
+                    Some(_) => return Err("$format args must be an array".to_string()),
+                };
+                return render_format(&template, &args).map(serde_json::Value::String);
+            }
+
+            let is_ref_list_wrapper = map.contains_key("$refList")
+                && map
+                    .keys()
+                    .all(|k| matches!(k.as_str(), "$refList" | "$slice" | "$unique" | "$default"));
+            if is_ref_list_wrapper {
+                let resolved = resolve_ref_list(&map, ctx);
+                // An empty list falls back to `$default` just like a missing `$ref` target.
+                if let Some(default) = default_value {
+                    if resolved.as_ref().map_or(true, Vec::is_empty) {
+                        return resolve_inner(default, ctx, depth + 1);
+                    }
+                }
+                let mut out = Vec::new();
+                for value in resolved? {
+                    out.push(resolve_inner(value, ctx, depth + 1)?);
+                }
+                return Ok(serde_json::Value::Array(out));
+            }
+
             let is_ref_wrapper = map.contains_key("$ref")
                 && (map.len() == 1 || (map.len() == 2 && default_value.is_some()));
 
@@ -125,6 +346,85 @@ fn resolve_inner(
     }
 }
 
+fn when_pointer<'a>(
+    ctx: &'a serde_json::Value,
+    op: &str,
+    arg: &serde_json::Value,
+) -> Result<Option<&'a serde_json::Value>, String> {
+    let pointer = arg
+        .as_str()
+        .ok_or_else(|| format!("when.{op} expects a JSON pointer string"))?;
+    // Missing paths, failed and skipped items all read as "absent" here.
+    Ok(resolve_json_pointer(ctx, pointer).ok())
+}
+
+fn is_empty_value(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::String(s) => s.is_empty(),
+        serde_json::Value::Array(arr) => arr.is_empty(),
+        serde_json::Value::Object(map) => map.is_empty(),
+        serde_json::Value::Bool(_) | serde_json::Value::Number(_) => false,
+    }
+}
+
+fn evaluate_when_inner(
+    when: &serde_json::Value,
+    ctx: &serde_json::Value,
+    depth: usize,
+) -> Result<bool, String> {
+    if depth > MAX_DEPTH {
+        return Err("when predicate exceeded max depth".to_string());
+    }
+    let Some((op, arg)) = when
+        .as_object()
+        .filter(|map| map.len() == 1)
+        .and_then(|map| map.iter().next())
+    else {
+        return Err(
+            "when must be an object with exactly one operator (exists, empty, eq, not, all, any)"
+                .to_string(),
+        );
+    };
+
+    match op.as_str() {
+        "exists" => Ok(when_pointer(ctx, op, arg)?.is_some_and(|v| !v.is_null())),
+        "empty" => Ok(when_pointer(ctx, op, arg)?.is_none_or(is_empty_value)),
+        "eq" => {
+            let Some([pointer, expected]) = arg.as_array().map(Vec::as_slice) else {
+                return Err("when.eq expects [pointer, value]".to_string());
+            };
+            Ok(when_pointer(ctx, op, pointer)? == Some(expected))
+        }
+        "not" => Ok(!evaluate_when_inner(arg, ctx, depth + 1)?),
+        "all" | "any" => {
+            let preds = arg
+                .as_array()
+                .ok_or_else(|| format!("when.{op} expects an array of predicates"))?;
+            let mut results = Vec::with_capacity(preds.len());
+            for pred in preds {
+                results.push(evaluate_when_inner(pred, ctx, depth + 1)?);
+            }
+            Ok(if op == "all" {
+                results.into_iter().all(|v| v)
+            } else {
+                results.into_iter().any(|v| v)
+            })
+        }
+        other => Err(format!(
+            "Unknown when operator '{other}' (expected exists, empty, eq, not, all, any)"
+        )),
+    }
+}
+
+/// Evaluate a batch item's `when` predicate against the ref context.
+///
+/// Operators: `{"exists": ptr}` (resolves to a non-null value), `{"empty": ptr}` (missing, null,
+/// `""`, `[]` or `{}`), `{"eq": [ptr, value]}`, plus `not`, `all` and `any` to combine them.
+pub fn evaluate_when(when: &serde_json::Value, ctx: &serde_json::Value) -> Result<bool, String> {
+    evaluate_when_inner(when, ctx, 0)
+}
+
 pub fn resolve_batch_refs(
     input: serde_json::Value,
     ctx: &serde_json::Value,
@@ -132,9 +432,102 @@ pub fn resolve_batch_refs(
     resolve_inner(input, ctx, 0)
 }
 
+/// Earlier batch items a payload may read, found by scanning `$ref`/`$refList` pointers and
+/// `when` predicate pointers for `#/items/<id>/...`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ItemRefs {
+    /// Ids addressed by name.
+    pub ids: std::collections::BTreeSet<String>,
+    /// Set when a pointer may read any item (`#/items/*/...`, `#/items`, or the whole context).
+    pub any: bool,
+}
+
+impl ItemRefs {
+    /// Whether the payload may read the item with this id.
+    pub fn depends_on(&self, id: &str) -> bool {
+        self.any || self.ids.contains(id)
+    }
+
+    fn add_pointer(&mut self, pointer: &str) {
+        // Malformed pointers fail at resolution time; treat them as depending on nothing.
+        let Ok(tokens) = pointer_tokens(pointer, "$ref") else {
+            return;
+        };
+        match tokens.first().map(String::as_str) {
+            None => self.any = true,
+            Some("items") => match tokens.get(1) {
+                Some(id) if id != "*" => {
+                    self.ids.insert(id.clone());
+                }
+                _ => self.any = true,
+            },
+            Some(_) => {}
+        }
+    }
+
+    fn scan_input(&mut self, value: &serde_json::Value, depth: usize) {
+        if depth > MAX_DEPTH {
+            self.any = true;
+            return;
+        }
+        match value {
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.scan_input(item, depth + 1);
+                }
+            }
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    match (key.as_str(), value) {
+                        ("$ref" | "$refList", serde_json::Value::String(pointer)) => {
+                            self.add_pointer(pointer);
+                        }
+                        _ => self.scan_input(value, depth + 1),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn scan_when(&mut self, value: &serde_json::Value, depth: usize) {
+        if depth > MAX_DEPTH {
+            self.any = true;
+            return;
+        }
+        match value {
+            serde_json::Value::String(pointer) => self.add_pointer(pointer),
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.scan_when(item, depth + 1);
+                }
+            }
+            serde_json::Value::Object(map) => {
+                for value in map.values() {
+                    self.scan_when(value, depth + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collect the items a batch item's `input` and `when` predicate refer to.
+///
+/// The scan is conservative: any string inside `when` is read as a pointer, and `$ref` keys count
+/// even on objects that are not pure ref wrappers. Over-reporting only costs parallelism.
+pub fn referenced_items(input: &serde_json::Value, when: Option<&serde_json::Value>) -> ItemRefs {
+    let mut refs = ItemRefs::default();
+    refs.scan_input(input, 0);
+    if let Some(when) = when {
+        refs.scan_when(when, 0);
+    }
+    refs
+}
+
 #[cfg(test)]
 mod tests {
-    use super::resolve_batch_refs;
+    use super::{evaluate_when, referenced_items, resolve_batch_refs};
 
     #[test]
     fn resolves_simple_pointer() {
@@ -180,4 +573,235 @@ mod tests {
         assert_eq!(out["x"]["$ref"], "#/items/a/data/value");
         assert_eq!(out["x"]["other"], 1);
     }
+
+    #[test]
+    fn ref_list_wildcard_over_array_with_slice_and_unique() {
+        let ctx = serde_json::json!({
+            "items": {
+                "search1": { "status": "ok", "data": { "results": [
+                    { "file": "src/a.rs" },
+                    { "file": "src/a.rs" },
+                    { "file": "src/b.rs" },
+                    { "file": "src/c.rs" },
+                    { "file": "src/d.rs" }
+                ] } }
+            }
+        });
+        let input = serde_json::json!({
+            "files": {
+                "$refList": "#/items/search1/data/results/*/file",
+                "$slice": [0, 3],
+                "$unique": true
+            },
+            "all": { "$refList": "#/items/search1/data/results/*/file" }
+        });
+        let out = resolve_batch_refs(input, &ctx).expect("ok");
+        assert_eq!(
+            out["files"],
+            serde_json::json!(["src/a.rs", "src/b.rs", "src/c.rs"])
+        );
+        assert_eq!(out["all"].as_array().map(Vec::len), Some(5));
+    }
+
+    #[test]
+    fn ref_list_wildcard_over_object_yields_values_in_key_order() {
+        let ctx = serde_json::json!({
+            "items": {
+                "b": { "status": "ok", "data": { "n": 2 } },
+                "a": { "status": "ok", "data": { "n": 1 } },
+                "c": { "status": "ok", "data": { "n": 3 } }
+            }
+        });
+        let input = serde_json::json!({ "$refList": "#/items/*/data/n", "$slice": [1] });
+        let out = resolve_batch_refs(input, &ctx).expect("ok");
+        assert_eq!(out, serde_json::json!([2, 3]));
+    }
+
+    #[test]
+    fn ref_list_checks_every_matched_item_for_failure() {
+        let ctx = serde_json::json!({
+            "items": {
+                "a": { "status": "ok", "data": { "n": 1 } },
+                "bad": { "status": "error", "message": "nope", "data": null }
+            }
+        });
+        let input = serde_json::json!({ "$refList": "#/items/*/data/n" });
+        let err = resolve_batch_refs(input, &ctx).expect_err("expected error");
+        assert!(err.contains("points to failed item 'bad'"));
+    }
+
+    #[test]
+    fn ref_list_uses_default_when_empty() {
+        let ctx = serde_json::json!({
+            "items": {
+                "s": { "status": "ok", "data": { "results": [] } }
+            }
+        });
+        let input = serde_json::json!({
+            "x": { "$refList": "#/items/s/data/results/*/file", "$default": ["README.md"] },
+            "y": { "$refList": "#/items/s/data/results/*/file" },
+            "z": { "$refList": "#/items/missing/data/*", "$default": [] }
+        });
+        let out = resolve_batch_refs(input, &ctx).expect("ok");
+        assert_eq!(out["x"], serde_json::json!(["README.md"]));
+        assert_eq!(out["y"], serde_json::json!([]));
+        assert_eq!(out["z"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn ref_list_elements_resolve_nested_refs_under_depth_limit() {
+        let ctx = serde_json::json!({
+            "items": {
+                "a": { "status": "ok", "data": { "v": 7, "list": [
+                    { "$ref": "#/items/a/data/v" }
+                ] } }
+            }
+        });
+        let input = serde_json::json!({ "$refList": "#/items/a/data/list/*" });
+        let out = resolve_batch_refs(input, &ctx).expect("ok");
+        assert_eq!(out, serde_json::json!([7]));
+    }
+
+    #[test]
+    fn format_interpolates_resolved_args() {
+        let ctx = serde_json::json!({
+            "items": {
+                "sym": { "status": "ok", "data": { "symbols": [ { "name": "greet", "line": 3 } ] } }
+            }
+        });
+        let input = serde_json::json!({
+            "pattern": {
+                "$format": "fn {0}\\( // line {1}, {{literal}} {0}",
+                "args": [
+                    { "$ref": "#/items/sym/data/symbols/0/name" },
+                    { "$ref": "#/items/sym/data/symbols/0/line" }
+                ]
+            }
+        });
+        let out = resolve_batch_refs(input, &ctx).expect("ok");
+        assert_eq!(out["pattern"], "fn greet\\( // line 3, {literal} greet");
+    }
+
+    #[test]
+    fn format_rejects_non_scalar_args_and_bad_placeholders() {
+        let ctx = serde_json::json!({
+            "items": { "a": { "status": "ok", "data": { "obj": { "k": 1 } } } }
+        });
+
+        let err = resolve_batch_refs(
+            serde_json::json!({ "$format": "x {0}", "args": [{ "$ref": "#/items/a/data/obj" }] }),
+            &ctx,
+        )
+        .expect_err("object arg");
+        assert!(err.contains("arg 0 is an object"), "{err}");
+
+        let err = resolve_batch_refs(
+            serde_json::json!({ "$format": "x {1}", "args": ["a"] }),
+            &ctx,
+        )
+        .expect_err("missing arg");
+        assert!(err.contains("no matching arg"), "{err}");
+
+        let err = resolve_batch_refs(
+            serde_json::json!({ "$format": "x {name}", "args": [] }),
+            &ctx,
+        )
+        .expect_err("named placeholder");
+        assert!(err.contains("{N}"), "{err}");
+
+        let err = resolve_batch_refs(serde_json::json!({ "$format": "x }", "args": [] }), &ctx)
+            .expect_err("stray brace");
+        assert!(err.contains("unmatched"), "{err}");
+    }
+
+    #[test]
+    fn when_predicates_evaluate_against_context() {
+        let ctx = serde_json::json!({
+            "items": {
+                "a": { "status": "ok", "data": { "results": [ { "file": "x" } ], "none": [] } },
+                "bad": { "status": "error", "message": "nope", "data": null }
+            }
+        });
+        let eval = |when: serde_json::Value| evaluate_when(&when, &ctx).expect("valid predicate");
+
+        assert!(eval(
+            serde_json::json!({ "exists": "#/items/a/data/results/0" })
+        ));
+        assert!(!eval(
+            serde_json::json!({ "exists": "#/items/a/data/results/1" })
+        ));
+        assert!(!eval(serde_json::json!({ "exists": "#/items/bad/data" })));
+        assert!(eval(serde_json::json!({ "empty": "#/items/a/data/none" })));
+        assert!(eval(serde_json::json!({ "empty": "#/items/missing/data" })));
+        assert!(!eval(
+            serde_json::json!({ "empty": "#/items/a/data/results" })
+        ));
+        assert!(eval(
+            serde_json::json!({ "eq": ["#/items/a/status", "ok"] })
+        ));
+        assert!(eval(
+            serde_json::json!({ "eq": ["#/items/bad/status", "error"] })
+        ));
+        assert!(eval(
+            serde_json::json!({ "not": { "eq": ["#/items/a/status", "error"] } })
+        ));
+        assert!(eval(serde_json::json!({ "all": [
+            { "exists": "#/items/a/data/results/0" },
+            { "eq": ["#/items/a/data/results/0/file", "x"] }
+        ] })));
+        assert!(!eval(serde_json::json!({ "any": [] })));
+
+        let err = evaluate_when(&serde_json::json!({ "exists": 1 }), &ctx).expect_err("bad arg");
+        assert!(err.contains("JSON pointer"), "{err}");
+        let err = evaluate_when(&serde_json::json!({ "maybe": "#/x" }), &ctx).expect_err("bad op");
+        assert!(err.contains("Unknown when operator"), "{err}");
+    }
+
+    #[test]
+    fn refs_to_skipped_items_fall_back_to_default() {
+        let ctx = serde_json::json!({
+            "items": { "grep": { "status": "skipped", "data": null } }
+        });
+        let err = resolve_batch_refs(
+            serde_json::json!({ "$ref": "#/items/grep/data/file" }),
+            &ctx,
+        )
+        .expect_err("skipped item");
+        assert!(err.contains("skipped item 'grep'"), "{err}");
+
+        let out = resolve_batch_refs(
+            serde_json::json!({ "$ref": "#/items/grep/data/file", "$default": "src/lib.rs" }),
+            &ctx,
+        )
+        .expect("default");
+        assert_eq!(out, "src/lib.rs");
+    }
+
+    #[test]
+    fn referenced_items_collects_ref_and_when_pointers() {
+        let input = serde_json::json!({
+            "file": { "$ref": "#/items/hits/data/matches/0/file", "$default": "x" },
+            "pattern": { "$format": "{0}", "args": [{ "$ref": "#/items/sym~1bol/data/name" }] },
+            "path": { "$ref": "#/path" },
+        });
+        let when = serde_json::json!({ "not": { "empty": "#/items/gate/data" } });
+        let refs = referenced_items(&input, Some(&when));
+        assert!(!refs.any);
+        assert_eq!(
+            refs.ids.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["gate", "hits", "sym/bol"]
+        );
+        assert!(refs.depends_on("hits"));
+        assert!(!refs.depends_on("other"));
+
+        let wildcard = serde_json::json!({ "files": { "$refList": "#/items/*/data/file" } });
+        let refs = referenced_items(&wildcard, None);
+        assert!(refs.any);
+        assert!(refs.depends_on("anything"));
+
+        assert_eq!(
+            referenced_items(&serde_json::json!({ "query": "plain" }), None),
+            Default::default()
+        );
+    }
 }
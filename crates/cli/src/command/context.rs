@@ -2,6 +2,7 @@ use crate::command::domain::{
     config_bool_path, config_string_path, merge_configs, normalize_config, Hint, HintKind,
 };
 use anyhow::{anyhow, Context as AnyhowContext, Result};
+use context_protocol::{CodedError, ErrorCode};
 use context_search::SearchProfile;
 use context_vector_store::current_model_id;
 use serde_json::Value;
@@ -144,6 +145,10 @@ impl CommandContext {
                             candidate.display()
                         )
                     })?;
+                hints.extend(profile.path_weight_overlaps().into_iter().map(|text| Hint {
+                    kind: HintKind::Warn,
+                    text: format!("Profile {}: {text}", candidate.display()),
+                }));
                 return Ok((profile, Some(candidate.display().to_string()), hints));
             }
         }
@@ -268,7 +273,11 @@ fn find_git_root(start: &Path) -> Option<PathBuf> {
 
 fn canonicalize_root(path: PathBuf) -> Result<PathBuf> {
     if !path.exists() {
-        anyhow::bail!("Project path does not exist: {}", path.display());
+        return Err(CodedError::new(
+            ErrorCode::ProjectNotFound,
+            format!("Project path does not exist: {}", path.display()),
+        )
+        .into());
     }
     path.canonicalize()
         .with_context(|| format!("Failed to canonicalize {}", path.display()))
@@ -276,10 +285,14 @@ fn canonicalize_root(path: PathBuf) -> Result<PathBuf> {
 
 pub fn ensure_index_exists(path: &Path) -> Result<()> {
     if !path.exists() {
-        return Err(anyhow!(
-            "Index not found at {}. Run 'context-finder index' first.",
-            path.display()
-        ));
+        return Err(CodedError::new(
+            ErrorCode::IndexMissing,
+            format!(
+                "Index not found at {}. Run 'context-finder index' first.",
+                path.display()
+            ),
+        )
+        .into());
     }
     Ok(())
 }
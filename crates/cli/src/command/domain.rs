@@ -1,18 +1,28 @@
+use crate::models;
 use anyhow::Result;
+use context_graph::AssemblyBudget;
 use context_protocol::{
-    BudgetTruncation, Capabilities, DefaultBudgets, ErrorEnvelope, ToolNextAction,
+    BudgetTruncation, Capabilities, CodedError, DefaultBudgets, ErrorCode, ErrorEnvelope,
+    ToolNextAction,
+};
+pub use context_search::{
+    ContextPackBudget, ContextPackItem, ContextPackOmitted, ContextPackOutput, QueryType,
+    QueryWeights,
 };
-pub use context_search::{ContextPackBudget, ContextPackItem, ContextPackOutput};
 pub use context_search::{
     NextAction, NextActionKind, TaskPackItem, TaskPackOutput, TASK_PACK_VERSION,
 };
+use schemars::JsonSchema;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 pub const DEFAULT_LIMIT: usize = 10;
 pub const DEFAULT_CONTEXT_WINDOW: usize = 20;
 pub const BATCH_VERSION: u32 = 1;
+/// Message prefix of `CommandOutcome::failure`s raised by eval and index drift thresholds.
+pub const QUALITY_GATE_FAILED: &str = "Quality gate failed";
 
 #[derive(Debug, Deserialize)]
 pub struct CommandRequest {
@@ -29,7 +39,7 @@ fn empty_payload() -> Value {
     Value::Object(Default::default())
 }
 
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum CommandAction {
     Search,
@@ -48,9 +58,50 @@ pub enum CommandAction {
     RepoOnboardingPack,
     Eval,
     EvalCompare,
+    EvalGenerate,
+    Impact,
+    Trace,
+    Explain,
+    Overview,
+    ModelsList,
+    ModelsFetch,
+    ModelsVerify,
+    Warm,
+    TemplatePreview,
+    IndexVerify,
 }
 
 impl CommandAction {
+    pub const ALL: [CommandAction; 27] = [
+        CommandAction::Search,
+        CommandAction::SearchWithContext,
+        CommandAction::ContextPack,
+        CommandAction::TaskPack,
+        CommandAction::TextSearch,
+        CommandAction::Batch,
+        CommandAction::Capabilities,
+        CommandAction::Index,
+        CommandAction::GetContext,
+        CommandAction::ListSymbols,
+        CommandAction::ConfigRead,
+        CommandAction::CompareSearch,
+        CommandAction::Map,
+        CommandAction::RepoOnboardingPack,
+        CommandAction::Eval,
+        CommandAction::EvalCompare,
+        CommandAction::EvalGenerate,
+        CommandAction::Impact,
+        CommandAction::Trace,
+        CommandAction::Explain,
+        CommandAction::Overview,
+        CommandAction::ModelsList,
+        CommandAction::ModelsFetch,
+        CommandAction::ModelsVerify,
+        CommandAction::Warm,
+        CommandAction::TemplatePreview,
+        CommandAction::IndexVerify,
+    ];
+
     pub const fn as_str(self) -> &'static str {
         match self {
             CommandAction::Search => "search",
@@ -69,11 +120,22 @@ impl CommandAction {
             CommandAction::RepoOnboardingPack => "repo_onboarding_pack",
             CommandAction::Eval => "eval",
             CommandAction::EvalCompare => "eval_compare",
+            CommandAction::EvalGenerate => "eval_generate",
+            CommandAction::Impact => "impact",
+            CommandAction::Trace => "trace",
+            CommandAction::Explain => "explain",
+            CommandAction::Overview => "overview",
+            CommandAction::ModelsList => "models_list",
+            CommandAction::ModelsFetch => "models_fetch",
+            CommandAction::ModelsVerify => "models_verify",
+            CommandAction::Warm => "warm",
+            CommandAction::TemplatePreview => "template_preview",
+            CommandAction::IndexVerify => "index_verify",
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct BatchPayload {
     #[serde(default)]
     pub project: Option<PathBuf>,
@@ -81,18 +143,43 @@ pub struct BatchPayload {
     pub max_chars: Option<usize>,
     #[serde(default)]
     pub stop_on_error: bool,
+    /// Overall time budget; items not started before it passes are reported as skipped.
+    #[serde(default)]
+    pub deadline_ms: Option<u64>,
+    /// Default per-item time budget for items without their own `timeout_ms`
+    #[serde(default)]
+    pub item_timeout_ms: Option<u64>,
+    /// How items are cut when the result exceeds `max_chars`
+    #[serde(default)]
+    pub truncate: BatchTruncate,
     pub items: Vec<BatchItem>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchTruncate {
+    /// Drop whole items from the end
+    #[default]
+    Items,
+    /// Shorten the last item's longest strings (marked `...truncated`) before dropping it
+    Content,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct BatchItem {
     pub id: String,
     pub action: CommandAction,
     #[serde(default = "empty_payload")]
     pub payload: Value,
+    /// Predicate over prior item results; the item is skipped when it evaluates to false.
+    #[serde(default)]
+    pub when: Option<Value>,
+    /// Time budget for this item; exceeding it cancels the item (`index` runs to completion).
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Default, Clone)]
+#[derive(Debug, Serialize, Default, Clone, JsonSchema)]
 pub struct BatchBudget {
     pub max_chars: usize,
     pub used_chars: usize,
@@ -101,7 +188,7 @@ pub struct BatchBudget {
     pub truncation: Option<BudgetTruncation>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, JsonSchema)]
 pub struct BatchItemResult {
     pub id: String,
     pub status: CommandStatus,
@@ -113,20 +200,36 @@ pub struct BatchItemResult {
     pub hints: Vec<Hint>,
     #[serde(default)]
     pub data: Value,
+    /// `data` strings were shortened to fit the batch budget (`truncate: "content"`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
     #[serde(default)]
     pub meta: ResponseMeta,
 }
 
-#[derive(Debug, Serialize, Clone)]
+/// Batch-level context that survives item truncation.
+#[derive(Debug, Serialize, Default, Clone, JsonSchema)]
+pub struct BatchMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_state: Option<context_indexer::IndexState>,
+}
+
+#[derive(Debug, Serialize, Clone, JsonSchema)]
 pub struct BatchOutput {
     pub version: u32,
     pub items: Vec<BatchItemResult>,
     pub budget: BatchBudget,
     #[serde(default)]
     pub next_actions: Vec<ToolNextAction>,
+    #[serde(default)]
+    pub meta: BatchMeta,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct CommandResponse {
     pub status: CommandStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -149,14 +252,16 @@ impl CommandResponse {
     }
 }
 
-#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum CommandStatus {
     Ok,
     Error,
+    /// Batch item whose `when` predicate was false (never used for whole responses).
+    Skipped,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, JsonSchema)]
 pub struct Hint {
     #[serde(rename = "type")]
     pub kind: HintKind,
@@ -164,7 +269,7 @@ pub struct Hint {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum HintKind {
     Info,
@@ -176,7 +281,7 @@ pub enum HintKind {
 
 #[derive(Debug, Clone)]
 pub struct ErrorClassification {
-    pub code: String,
+    pub code: ErrorCode,
     pub hint: Option<String>,
     pub hints: Vec<Hint>,
     pub next_actions: Vec<ToolNextAction>,
@@ -228,14 +333,33 @@ pub enum StalePolicy {
     Fail,
 }
 
-#[derive(Debug, Serialize, Default, Clone)]
+#[derive(Debug, Serialize, Default, Clone, JsonSchema)]
 pub struct ResponseMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub graph_cache: Option<bool>,
+    /// Language the code graph was built for, or the detected dominant language that has no
+    /// graph builder (the results then carry no related code)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graph_language: Option<String>,
+    /// `graph_language` came from the indexed chunks rather than the payload or config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detected: Option<bool>,
+    /// Query type used for ranking (auto-classified or forced via `query_type`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_type: Option<QueryType>,
+    /// Fusion weights and candidate multiplier the query type selected (`trace` only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weights_used: Option<QueryWeights>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub index_updated: Option<bool>,
+    /// An auto-reindex ran, or was joined, before the command (stale_policy=auto)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reindex_attempted: Option<bool>,
+    /// The auto-reindex budget ran out before the index was refreshed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reindex_deadline_hit: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration_ms: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -252,6 +376,12 @@ pub struct ResponseMeta {
     pub index_size_bytes: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub graph_cache_size_bytes: Option<u64>,
+    /// Graph cache loads served from disk since this process started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graph_cache_hit_count: Option<u64>,
+    /// Graph cache loads that fell back to a rebuild (missing, stale or corrupted cache)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graph_cache_miss_count: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub health_last_success_ms: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -272,6 +402,12 @@ pub struct ResponseMeta {
     pub timing_graph_ms: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timing_search_ms: Option<u64>,
+    /// Query vectors served from the query embedding cache in this search
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_embedding_cache_hits: Option<usize>,
+    /// Per embedding model: query embedding and index lookup time, and fusion weight
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_timings: Option<Vec<context_search::ModelTiming>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub health_last_failure_ms: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -307,6 +443,9 @@ pub struct CommandOutcome {
     pub hints: Vec<Hint>,
     pub meta: ResponseMeta,
     pub next_actions: Vec<ToolNextAction>,
+    /// Set when the command ran but its result fails a requested gate (e.g. eval
+    /// `fail_under`); the response keeps `data` but reports an error status.
+    pub failure: Option<String>,
 }
 
 impl CommandOutcome {
@@ -316,6 +455,7 @@ impl CommandOutcome {
             hints: Vec::new(),
             meta: ResponseMeta::default(),
             next_actions: Vec::new(),
+            failure: None,
         })
     }
 }
@@ -381,11 +521,46 @@ pub fn config_usize_path(config: &Option<Value>, path: &[&str]) -> Option<usize>
         .map(|raw| raw as usize)
 }
 
+/// Non-empty trimmed strings of an array value; anything else yields an empty list.
+pub fn config_string_list_path(config: &Option<Value>, path: &[&str]) -> Vec<String> {
+    config_lookup(config, path)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// User synonym dictionary for query expansion: `{"synonyms": {"dao": ["repository"]}}`.
+/// A bare string value is accepted as a single synonym.
+pub fn config_synonyms(config: &Option<Value>) -> Option<HashMap<String, Vec<String>>> {
+    let map = config_lookup(config, &["synonyms"])?.as_object()?;
+    let synonyms: HashMap<String, Vec<String>> = map
+        .iter()
+        .filter_map(|(term, value)| {
+            let values = match value {
+                Value::String(single) => vec![single.clone()],
+                Value::Array(items) => items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect(),
+                _ => return None,
+            };
+            (!values.is_empty()).then(|| (term.clone(), values))
+        })
+        .collect();
+    (!synonyms.is_empty()).then_some(synonyms)
+}
+
 pub fn normalize_config(config: Option<Value>) -> Option<Value> {
     config.and_then(|value| if value.is_null() { None } else { Some(value) })
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct IndexPayload {
     #[serde(default)]
     pub path: Option<PathBuf>,
@@ -395,9 +570,34 @@ pub struct IndexPayload {
     pub models: Vec<String>,
     #[serde(default)]
     pub experts: bool,
+    /// Report the work an index run would do without embedding or saving
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Delete the on-disk graph cache so the next graph query rebuilds it
+    #[serde(default)]
+    pub clear_graph_cache: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Without thresholds any drift fails the command.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct IndexVerifyPayload {
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// Indexes to verify (default: the active embedding model)
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Re-embed only the drifted chunks, then verify again
+    #[serde(default)]
+    pub repair: bool,
+    /// Fail when more chunks than this drift in any index
+    #[serde(default)]
+    pub max_drift_chunks: Option<usize>,
+    /// Fail when drifted chunks exceed this fraction of the corpus in any index
+    #[serde(default)]
+    pub max_drift_ratio: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct EvalPayload {
     #[serde(default)]
     pub path: Option<PathBuf>,
@@ -410,22 +610,50 @@ pub struct EvalPayload {
     pub models: Vec<String>,
     #[serde(default)]
     pub cache_mode: Option<EvalCacheMode>,
+    /// Format of the per-case report written to `output_path` (default `json`, the full
+    /// `EvalOutput`)
+    #[serde(default)]
+    pub output_format: Option<EvalOutputFormat>,
+    /// File to write the per-case report to; relative paths resolve against the project root
+    #[serde(default)]
+    pub output_path: Option<PathBuf>,
+    /// Fail the command when any run's summary falls below these metrics
+    #[serde(default)]
+    pub fail_under: Option<EvalThresholds>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum EvalCacheMode {
     Warm,
     Cold,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EvalOutputFormat {
+    #[default]
+    Json,
+    Csv,
+    Junit,
+}
+
+/// Summary metric limits for CI gating; unset metrics are not checked.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, JsonSchema)]
+pub struct EvalThresholds {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_mrr: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_recall: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct EvalOutput {
     pub dataset: EvalDatasetMeta,
     pub runs: Vec<EvalRun>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct EvalDatasetMeta {
     pub schema_version: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -433,9 +661,12 @@ pub struct EvalDatasetMeta {
     pub cases: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct EvalRun {
     pub profile: String,
+    /// The profile's `path_weights`, in match order, so A/B runs show the tuning compared
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_weights: Vec<EvalPathWeight>,
     pub models: Vec<String>,
     pub limit: usize,
     pub cache_mode: EvalCacheMode,
@@ -443,38 +674,79 @@ pub struct EvalRun {
     pub cases: Vec<EvalCaseResult>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EvalPathWeight {
+    pub glob: String,
+    pub weight: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct EvalRunSummary {
     pub profile: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_weights: Vec<EvalPathWeight>,
     pub models: Vec<String>,
     pub limit: usize,
     pub cache_mode: EvalCacheMode,
     pub summary: EvalSummary,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EvalSummary {
+    /// Cases aggregated into this summary
+    #[serde(default)]
+    pub cases: usize,
     pub mean_mrr: f64,
     pub mean_recall: f64,
     pub mean_overlap_ratio: f64,
+    #[serde(default)]
+    pub mean_ndcg: f64,
+    /// Mean average precision over all cases
+    #[serde(default)]
+    pub map: f64,
     pub mean_latency_ms: f64,
     pub p50_latency_ms: u64,
     pub p95_latency_ms: u64,
     pub mean_bytes: f64,
+    /// Mean share of returned hits that fall under a case's `forbidden_paths`
+    #[serde(default)]
+    pub mean_forbidden_rate: f64,
+    /// Same metrics restricted to the cases tagged with each `intent` (untagged cases only
+    /// count towards the overall summary)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub by_intent: BTreeMap<String, EvalSummary>,
+    /// Same metrics restricted to the cases expecting a path under each prefix (the dataset's
+    /// `breakdown_prefixes`, or else the first path segment); a case can count towards several
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub by_prefix: BTreeMap<String, EvalSummary>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct EvalCaseResult {
     pub id: String,
     pub query: String,
     pub expected_paths: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub expected_symbols: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forbidden_paths: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub intent: Option<String>,
     pub mrr: f64,
     pub recall: f64,
     pub overlap_ratio: f64,
+    /// Hits within `limit` that fall under `forbidden_paths`
+    #[serde(default)]
+    pub forbidden_hits: usize,
+    /// `forbidden_hits` as a share of the returned hits; a precision penalty
+    #[serde(default)]
+    pub forbidden_rate: f64,
+    /// NDCG@limit with graded relevance: expected symbol hit (2) > expected path hit (1)
+    #[serde(default)]
+    pub ndcg: f64,
+    /// Average precision@limit over the expected symbols/paths
+    #[serde(default)]
+    pub average_precision: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub first_rank: Option<usize>,
     pub latency_ms: u64,
@@ -482,7 +754,7 @@ pub struct EvalCaseResult {
     pub hits: Vec<EvalHit>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct EvalHit {
     pub id: String,
     pub file: String,
@@ -491,7 +763,7 @@ pub struct EvalHit {
     pub score: f32,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct EvalComparePayload {
     #[serde(default)]
     pub path: Option<PathBuf>,
@@ -502,16 +774,19 @@ pub struct EvalComparePayload {
     pub b: EvalCompareConfig,
     #[serde(default)]
     pub cache_mode: Option<EvalCacheMode>,
+    /// Fail the command when B's summary metric trails A's by more than these deltas
+    #[serde(default)]
+    pub fail_if_b_worse_by: Option<EvalThresholds>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct EvalCompareConfig {
     pub profile: String,
     #[serde(default)]
     pub models: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct EvalCompareOutput {
     pub dataset: EvalDatasetMeta,
     pub cache_mode: EvalCacheMode,
@@ -521,7 +796,7 @@ pub struct EvalCompareOutput {
     pub cases: Vec<EvalCompareCase>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct EvalCompareSummary {
     pub delta_mean_mrr: f64,
     pub delta_mean_recall: f64,
@@ -532,9 +807,30 @@ pub struct EvalCompareSummary {
     pub a_wins: usize,
     pub b_wins: usize,
     pub ties: usize,
+    /// Two-sided sign test over per-case `delta_mrr` (ties dropped); small means the
+    /// A/B difference is unlikely to be noise
+    pub p_value: f64,
+    /// `1 - p_value`
+    pub confidence: f64,
+    #[serde(default)]
+    pub delta_mean_forbidden_rate: f64,
+    /// Per-prefix deltas, keyed like `EvalSummary::by_prefix`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub by_prefix: BTreeMap<String, EvalPrefixDelta>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EvalPrefixDelta {
+    pub cases: usize,
+    pub a_mean_mrr: f64,
+    pub b_mean_mrr: f64,
+    pub delta_mean_mrr: f64,
+    pub a_mean_recall: f64,
+    pub b_mean_recall: f64,
+    pub delta_mean_recall: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct EvalCompareCase {
     pub id: String,
     pub query: String,
@@ -560,7 +856,115 @@ pub struct EvalCompareCase {
     pub b_first_rank: Option<usize>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct EvalGeneratePayload {
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// Dataset file to write (default `.context-finder/eval/git_history.json`); relative paths
+    /// resolve against the project root
+    #[serde(default)]
+    pub output_path: Option<PathBuf>,
+    /// Cases to generate, newest commits first (default 50)
+    #[serde(default)]
+    pub max_cases: Option<usize>,
+    /// Only mine commits newer than this `git log --since` date, e.g. `"6 months ago"`
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Skip commits that change fewer files than this (default 1)
+    #[serde(default)]
+    pub min_files_changed: Option<usize>,
+    /// Touched paths matching any of these globs are left out of `expected_paths`
+    #[serde(default)]
+    pub exclude_path_globs: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EvalGenerateOutput {
+    pub output_path: String,
+    pub cases: usize,
+    pub commits_scanned: usize,
+    pub skipped: EvalGenerateSkipped,
+}
+
+/// Commits left out of a generated dataset, by reason.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EvalGenerateSkipped {
+    /// Fewer changed files than `min_files_changed`
+    pub too_few_files: usize,
+    /// Too many changed files or lines to point at a focused answer (vendoring, mass refactors)
+    pub too_large: usize,
+    /// Mostly renames, or line-for-line rewrites across several files (formatting)
+    pub rename_or_format: usize,
+    /// No touched path passed `exclude_path_globs` and still exists in the working tree
+    pub no_paths: usize,
+    /// Subject too short to be a query once tags like `[ABC-1]` or `fix:` are removed
+    pub vague_subject: usize,
+}
+
+/// Models are read from the directory named by `CONTEXT_FINDER_MODEL_DIR` (default `models`).
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct ModelsPayload {
+    /// Model ids from the manifest (default: all)
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// `models_fetch` only: download again even when the file's checksum already matches
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct WarmPayload {
+    #[serde(default)]
+    pub project: Option<PathBuf>,
+    /// Also load (or build and cache) the code graph for this language
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Also load the active embedding model and run a probe embedding
+    #[serde(default)]
+    pub embedder: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmStageKind {
+    /// Index load, Rust graph cache and embedding warmup shared by every action
+    Prewarm,
+    Graph,
+    Embedder,
+}
+
+impl WarmStageKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            WarmStageKind::Prewarm => "prewarm",
+            WarmStageKind::Graph => "graph",
+            WarmStageKind::Embedder => "embedder",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WarmStage {
+    pub stage: WarmStageKind,
+    pub duration_ms: u64,
+    /// `prewarm`: the process was already warm; `graph`: the graph came from the on-disk cache
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_hit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Timings of each requested warm-up stage, in the order they ran.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WarmReport {
+    pub project: String,
+    pub stages: Vec<WarmStage>,
+    pub total_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_probe: Option<context_vector_store::EmbeddingProbe>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct SearchPayload {
     pub query: String,
     #[serde(default)]
@@ -569,9 +973,16 @@ pub struct SearchPayload {
     pub project: Option<PathBuf>,
     #[serde(default)]
     pub trace: Option<bool>,
+    /// Skip auto-classification and rank as this query type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_type: Option<QueryType>,
+    /// Search these embedding models and rank-fuse their results instead of routing by the
+    /// profile's experts; each needs an index (`index` with `models`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SearchWithContextPayload {
     pub query: String,
     #[serde(default)]
@@ -590,7 +1001,66 @@ pub struct SearchWithContextPayload {
     pub reuse_graph: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ImpactPayload {
+    pub symbol: String,
+    /// Hops of transitive usages to follow (1-3, default 2)
+    #[serde(default)]
+    pub depth: Option<usize>,
+    #[serde(default)]
+    pub project: Option<PathBuf>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub reuse_graph: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TracePayload {
+    pub from: String,
+    pub to: String,
+    /// Symbol every returned path must pass through
+    #[serde(default)]
+    pub via: Option<String>,
+    /// Distinct paths to return, cheapest first (default 1, max 5)
+    #[serde(default)]
+    pub max_paths: Option<usize>,
+    #[serde(default)]
+    pub project: Option<PathBuf>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub reuse_graph: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExplainPayload {
+    pub symbol: String,
+    /// Documentation budget in characters (default 4000)
+    #[serde(default)]
+    pub doc_max_chars: Option<usize>,
+    #[serde(default)]
+    pub project: Option<PathBuf>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub reuse_graph: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OverviewPayload {
+    /// Leading directory components that identify a module in `module_graph` (default 2)
+    #[serde(default)]
+    pub module_depth: Option<usize>,
+    #[serde(default)]
+    pub project: Option<PathBuf>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub reuse_graph: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct TextSearchPayload {
     pub pattern: String,
     #[serde(default)]
@@ -599,31 +1069,45 @@ pub struct TextSearchPayload {
     pub case_sensitive: Option<bool>,
     #[serde(default)]
     pub whole_word: Option<bool>,
+    /// Lines of context before/after each match (default 0, max 10); corpus matches stay
+    /// within their chunk
+    #[serde(default)]
+    pub context_lines: Option<usize>,
     #[serde(default)]
     pub project: Option<PathBuf>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TextSearchMatch {
     pub file: String,
     pub line: usize,
     pub column: usize,
     pub text: String,
+    /// Lines directly before `line`, oldest first (only with `context_lines`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub before: Vec<String>,
+    /// Lines directly after `line` (only with `context_lines`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TextSearchOutput {
     pub pattern: String,
     pub source: String,
     pub scanned_files: usize,
     pub matched_files: usize,
     pub skipped_large_files: usize,
+    /// Filesystem scan only: entries skipped by ignore rules (an ignored directory counts once)
+    pub ignored_files: usize,
+    /// Filesystem scan only: entries that could not be read as text
+    pub unreadable_files: usize,
     pub returned: usize,
     pub truncated: bool,
     pub matches: Vec<TextSearchMatch>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ContextPackPayload {
     pub query: String,
     #[serde(default)]
@@ -634,8 +1118,14 @@ pub struct ContextPackPayload {
     pub strategy: Option<SearchStrategy>,
     #[serde(default)]
     pub max_chars: Option<usize>,
+    /// Estimated-token cap for the pack; the tighter of this and `max_chars` wins.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
     #[serde(default)]
     pub max_related_per_primary: Option<usize>,
+    /// Per-relationship halo budget; replaces `max_related_per_primary` when present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub related_budget: Option<AssemblyBudget>,
     /// Prefer code results over markdown docs (implementation-first).
     #[serde(default)]
     pub prefer_code: Option<bool>,
@@ -651,9 +1141,13 @@ pub struct ContextPackPayload {
     pub language: Option<String>,
     #[serde(default)]
     pub reuse_graph: Option<bool>,
+    /// Search these embedding models and rank-fuse their results instead of routing by the
+    /// profile's experts; each needs an index (`index` with `models`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TaskPackPayload {
     pub intent: String,
     #[serde(default)]
@@ -666,6 +1160,9 @@ pub struct TaskPackPayload {
     pub max_chars: Option<usize>,
     #[serde(default)]
     pub max_related_per_primary: Option<usize>,
+    /// Per-relationship halo budget, as in `context_pack`; also bounds graph-derived next actions.
+    #[serde(default)]
+    pub related_budget: Option<AssemblyBudget>,
     /// Prefer code results over markdown docs (implementation-first).
     #[serde(default)]
     pub prefer_code: Option<bool>,
@@ -683,7 +1180,7 @@ pub struct TaskPackPayload {
     pub reuse_graph: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CompareSearchPayload {
     #[serde(default)]
     pub queries: Vec<String>,
@@ -705,7 +1202,7 @@ pub struct CompareSearchPayload {
     pub invalidate_cache: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[derive(Default)]
 pub enum SearchStrategy {
@@ -742,7 +1239,7 @@ impl SearchStrategy {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetContextPayload {
     pub file: String,
     pub line: usize,
@@ -756,25 +1253,83 @@ fn default_window() -> usize {
     DEFAULT_CONTEXT_WINDOW
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct ListSymbolsPayload {
     pub file: String,
     #[serde(default)]
     pub project: Option<PathBuf>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+/// Templates come from the active profile; `config.embedding` in the request (or project config)
+/// overrides any of their fields for this preview only.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TemplatePreviewPayload {
+    pub file: String,
+    /// Line inside the chunk to render (1-based)
+    pub line: usize,
+    /// Also render this query with every query template
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub project: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TemplatePreviewOutput {
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// `config.embedding` overrides were applied on top of the profile templates
+    pub overridden: bool,
+    #[serde(flatten)]
+    pub preview: context_vector_store::RenderedPreview,
+}
+
+#[derive(Debug, Deserialize, Default, JsonSchema)]
 pub struct ConfigReadPayload {
     #[serde(default)]
     pub project: Option<PathBuf>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct IndexResponse {
     pub stats: context_indexer::IndexStats,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
+pub struct IndexVerifyOutput {
+    pub models: Vec<IndexVerifyModel>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct IndexVerifyModel {
+    pub model_id: String,
+    pub index_path: String,
+    /// Drift found before any repair
+    pub report: context_vector_store::DriftReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repair: Option<IndexRepair>,
+}
+
+/// What `repair` changed; thresholds apply to `remaining`.
+#[derive(Serialize, JsonSchema)]
+pub struct IndexRepair {
+    /// Chunks that went through the embedder (or the embedding cache)
+    pub embedded: usize,
+    /// Chunks of drifted files that kept their vector
+    pub reused: usize,
+    pub remaining: context_vector_store::DriftTotals,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct IndexPlanResponse {
+    pub dry_run: bool,
+    pub plan: context_indexer::IndexPlan,
+}
+
+#[derive(Serialize, JsonSchema)]
 pub struct ConfigReadResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config: Option<Value>,
@@ -783,13 +1338,27 @@ pub struct ConfigReadResponse {
 #[allow(dead_code)]
 pub type CapabilitiesResponse = Capabilities;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct SearchOutput {
     pub query: String,
     pub results: Vec<SearchResultOutput>,
+    /// Earliest `indexed_at_ms` among the results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oldest_result_ms: Option<u64>,
+}
+
+impl SearchOutput {
+    pub fn new(query: String, results: Vec<SearchResultOutput>) -> Self {
+        let oldest_result_ms = results.iter().filter_map(|r| r.indexed_at_ms).min();
+        Self {
+            query,
+            results,
+            oldest_result_ms,
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
 pub struct ComparisonOutput {
     pub project: String,
     pub limit: usize,
@@ -799,7 +1368,7 @@ pub struct ComparisonOutput {
     pub summary: ComparisonSummary,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
 pub struct QueryComparison {
     pub query: String,
     pub limit: usize,
@@ -808,11 +1377,17 @@ pub struct QueryComparison {
     pub overlap: usize,
     pub overlap_ratio: f32,
     pub context_related: usize,
+    /// `file:start:end` keys of baseline results that context search did not return
+    #[serde(default)]
+    pub only_in_baseline: Vec<String>,
+    /// `file:start:end` keys that context search returned and baseline search did not
+    #[serde(default)]
+    pub only_in_context: Vec<String>,
     pub baseline: Vec<SearchResultOutput>,
     pub context: Vec<SearchResultOutput>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
 pub struct ComparisonSummary {
     pub avg_baseline_ms: f32,
     pub avg_context_ms: f32,
@@ -820,7 +1395,7 @@ pub struct ComparisonSummary {
     pub avg_related_chunks: f32,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
 pub struct SearchResultOutput {
     pub file: String,
     pub start_line: usize,
@@ -839,9 +1414,15 @@ pub struct SearchResultOutput {
     pub graph: Option<Vec<RelationshipOutput>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rationale: Option<String>,
+    /// When the result's file was last indexed (unix ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexed_at_ms: Option<u64>,
+    /// File mtime (unix ms) seen when it was indexed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_mtime_ms: Option<u64>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
 pub struct RelatedCodeOutput {
     pub file: String,
     pub start_line: usize,
@@ -856,14 +1437,14 @@ pub struct RelatedCodeOutput {
     pub reason: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
 pub struct RelationshipOutput {
     pub from: String,
     pub to: String,
     pub relationship: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct ContextOutput {
     pub file: String,
     pub line: usize,
@@ -876,13 +1457,13 @@ pub struct ContextOutput {
     pub window: WindowOutput,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct WindowOutput {
     pub before: String,
     pub after: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct SymbolsOutput {
     /// File name (for single-file mode) or pattern used
     pub file: String,
@@ -893,7 +1474,7 @@ pub struct SymbolsOutput {
     pub files_count: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct SymbolInfo {
     pub name: String,
     #[serde(rename = "type")]
@@ -906,7 +1487,7 @@ pub struct SymbolInfo {
     pub file: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct MapPayload {
     #[serde(default)]
     pub project: Option<PathBuf>,
@@ -914,13 +1495,19 @@ pub struct MapPayload {
     pub depth: usize,
     #[serde(default)]
     pub limit: Option<usize>,
+    /// Drop `top_symbols` whose coverage score (sum of per-file line share) is below this
+    #[serde(default)]
+    pub min_symbol_score: Option<f32>,
+    /// Only list public/exported symbols in `top_symbols`
+    #[serde(default)]
+    pub public_symbols_only: bool,
 }
 
 fn map_default_depth() -> usize {
     2
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct MapOutput {
     pub nodes: Vec<MapNode>,
     pub total_files: usize,
@@ -935,7 +1522,7 @@ pub struct MapOutput {
     pub coverage_lines_pct: Option<f32>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct MapNode {
     pub path: String,
     pub files: usize,
@@ -950,9 +1537,12 @@ pub struct MapNode {
     pub top_symbols: Option<Vec<SymbolInfo>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub avg_symbol_coverage: Option<f32>,
+    /// Chunk counts per language (`unknown` when the chunker could not tell)
+    #[serde(default)]
+    pub languages: HashMap<String, usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RepoOnboardingPackPayload {
     #[serde(default, alias = "path")]
     pub project: Option<PathBuf>,
@@ -976,7 +1566,7 @@ pub struct RepoOnboardingPackPayload {
     pub auto_index_budget_ms: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RepoOnboardingDocsReason {
     DocsLimitZero,
@@ -985,7 +1575,7 @@ pub enum RepoOnboardingDocsReason {
     MaxChars,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RepoOnboardingPackBudget {
     pub max_chars: usize,
     pub used_chars: usize,
@@ -994,7 +1584,7 @@ pub struct RepoOnboardingPackBudget {
     pub truncation: Option<BudgetTruncation>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RepoOnboardingDocSlice {
     pub file: String,
     pub start_line: usize,
@@ -1012,7 +1602,7 @@ pub struct RepoOnboardingDocSlice {
     pub content: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RepoOnboardingPackOutput {
     pub version: u32,
     pub root: String,
@@ -1024,136 +1614,220 @@ pub struct RepoOnboardingPackOutput {
     pub budget: RepoOnboardingPackBudget,
 }
 
+/// Code carried by a typed error in `err`'s chain (outermost first), if any layer knows one.
+pub fn typed_error_code(err: &anyhow::Error) -> Option<ErrorCode> {
+    err.chain().find_map(|cause| {
+        let code = if let Some(err) = cause.downcast_ref::<CodedError>() {
+            err.code
+        } else if let Some(err) = cause.downcast_ref::<context_indexer::IndexerError>() {
+            err.code()
+        } else if let Some(err) = cause.downcast_ref::<context_search::SearchError>() {
+            err.code()
+        } else if let Some(err) = cause.downcast_ref::<context_graph::GraphError>() {
+            err.code()
+        } else if let Some(err) = cause.downcast_ref::<context_vector_store::VectorStoreError>() {
+            err.code()
+        } else {
+            return None;
+        };
+        (code != ErrorCode::Internal).then_some(code)
+    })
+}
+
+/// Best-effort code for an untyped error message (plain `anyhow!` chains and
+/// `CommandOutcome::failure` strings).
+fn error_code_from_message(message: &str) -> ErrorCode {
+    let lower = message.to_lowercase();
+    if message.starts_with(QUALITY_GATE_FAILED) {
+        return ErrorCode::QualityGateFailed;
+    }
+    for (prefix, code) in [
+        (models::MODEL_VERIFY_FAILED, ErrorCode::ModelVerifyFailed),
+        (
+            models::MODEL_CHECKSUM_MISMATCH,
+            ErrorCode::ModelChecksumMismatch,
+        ),
+        (
+            models::MODEL_DOWNLOAD_REJECTED,
+            ErrorCode::ModelDownloadRejected,
+        ),
+        (models::MODEL_NETWORK_ERROR, ErrorCode::ModelNetworkError),
+        (models::MODEL_NOT_IN_MANIFEST, ErrorCode::ModelUnknown),
+        (
+            models::MODEL_MANIFEST_UNAVAILABLE,
+            ErrorCode::ModelManifestMissing,
+        ),
+    ] {
+        if message.contains(prefix) {
+            return code;
+        }
+    }
+    if message.contains("Model files for") && message.contains("are missing") {
+        ErrorCode::ModelMissing
+    } else if message.contains("Index budget exceeded") {
+        ErrorCode::Timeout
+    } else if message.contains("budget exceeded") || message.contains("max_chars too small") {
+        ErrorCode::BudgetExceeded
+    } else if message.contains("File not found") {
+        ErrorCode::FileNotFound
+    } else if message.contains("Project path does not exist") {
+        ErrorCode::ProjectNotFound
+    } else if lower.contains("config") {
+        ErrorCode::ConfigError
+    } else if lower.contains("unsupported graph language") {
+        ErrorCode::InvalidRequest
+    } else if lower.contains("graph language") {
+        ErrorCode::GraphLanguageMissing
+    } else if message.contains("Failed to read metadata") {
+        ErrorCode::FilesystemError
+    } else if message.contains("Failed to load vector store") {
+        ErrorCode::IndexCorrupt
+    } else if message.contains("Index not found") {
+        ErrorCode::IndexMissing
+    } else {
+        ErrorCode::Internal
+    }
+}
+
+/// Code, hints and recovery actions for an error. `code` is the typed code when the caller
+/// has one (see [`typed_error_code`]); otherwise it is guessed from `message`.
 pub fn classify_error(
     message: &str,
+    code: Option<ErrorCode>,
     action: Option<CommandAction>,
     payload: Option<&Value>,
 ) -> ErrorClassification {
-    let mut hints = Vec::new();
+    let code = code.unwrap_or_else(|| error_code_from_message(message));
     let mut next_actions = Vec::new();
-    let mut code = "internal".to_string();
-    let mut hint = None;
-
-    if message.contains("Index not found") {
-        code = "index_missing".to_string();
-        hint = Some(
-            "Index missing — run action=index with payload.path set to the project root."
-                .to_string(),
-        );
-        hints.push(Hint {
-            kind: HintKind::Action,
-            text: hint.clone().expect("hint"),
-        });
-        let path = extract_project_path(payload).unwrap_or_else(|| ".".to_string());
-        if action != Some(CommandAction::Index) {
-            next_actions.push(ToolNextAction {
-                tool: CommandAction::Index.as_str().to_string(),
-                args: json!({ "path": path }),
-                reason: "Build the semantic index (required for search/context/context_pack)."
-                    .to_string(),
-            });
-        }
-    }
-
-    if message.contains("Failed to load vector store") {
-        code = "index_corrupt".to_string();
-        hints.push(Hint {
-            kind: HintKind::Action,
-            text: "Index file looks corrupted — delete .context-finder/indexes/<model_id>/index.json and rerun the index action.".to_string(),
-        });
-    }
-
-    if message.contains("Failed to read metadata") {
-        code = "filesystem_error".to_string();
-        hints.push(Hint {
-            kind: HintKind::Warn,
-            text: "Filesystem metadata unavailable — check permissions or run from inside the project directory.".to_string(),
-        });
-    }
-
-    if message.to_lowercase().contains("graph language") {
-        code = "invalid_request".to_string();
-        hints.push(Hint {
-            kind: HintKind::Action,
-            text: "Specify graph_language in config or payload to enable context graph assembly."
-                .to_string(),
-        });
-    }
 
-    if message.to_lowercase().contains("config") {
-        code = "config_error".to_string();
-        hints.push(Hint {
-            kind: HintKind::Warn,
-            text: "Config issue detected — verify .context-finder/config.json or remove it."
-                .to_string(),
-        });
-    }
-
-    if message.contains("Project path does not exist") {
-        code = "invalid_request".to_string();
-        hints.push(Hint {
-            kind: HintKind::Action,
-            text: "Check payload.path/project or run from the repository root.".to_string(),
-        });
-    }
-
-    if message.contains("File not found") {
-        code = "invalid_request".to_string();
-        hints.push(Hint {
-            kind: HintKind::Action,
-            text: "Verify the 'file' value is relative to project root and exists on disk."
-                .to_string(),
-        });
-    }
-
-    if message.contains("max_chars too small") {
-        code = "invalid_request".to_string();
-        hints.push(Hint {
-            kind: HintKind::Action,
-            text: "Increase max_chars; current budget is too small for the response envelope."
-                .to_string(),
-        });
-        let min_chars = parse_min_chars(message);
-        if let Some(action) = action {
-            if let Some(mut max_chars) = default_max_chars_for_action(action) {
-                if let Some(min_chars) = min_chars {
-                    max_chars = max_chars.max(min_chars);
+    let hint: Option<(HintKind, &str)> = match code {
+        ErrorCode::IndexMissing => {
+            let path = extract_project_path(payload).unwrap_or_else(|| ".".to_string());
+            if action != Some(CommandAction::Index) {
+                next_actions.push(ToolNextAction::new(
+                    CommandAction::Index.as_str(),
+                    json!({ "path": path }),
+                    "Build the semantic index (required for search/context/context_pack).",
+                ));
+            }
+            Some((
+                HintKind::Action,
+                "Index missing — run action=index with payload.path set to the project root.",
+            ))
+        }
+        ErrorCode::IndexCorrupt => Some((
+            HintKind::Action,
+            "Index file looks corrupted — delete .context-finder/indexes/<model_id>/index.json and rerun the index action.",
+        )),
+        ErrorCode::Stale => {
+            let path = extract_project_path(payload).unwrap_or_else(|| ".".to_string());
+            next_actions.push(ToolNextAction::new(
+                CommandAction::Index.as_str(),
+                json!({ "path": path }),
+                "Rebuild the stale index.",
+            ));
+            Some((
+                HintKind::Action,
+                "Index is stale — rerun action=index or set options.stale_policy to 'warn'/'auto'.",
+            ))
+        }
+        ErrorCode::FilesystemError => Some((
+            HintKind::Warn,
+            "Filesystem metadata unavailable — check permissions or run from inside the project directory.",
+        )),
+        ErrorCode::GraphLanguageMissing => Some((
+            HintKind::Action,
+            "Specify graph_language in config or payload to enable context graph assembly.",
+        )),
+        ErrorCode::ConfigError => Some((
+            HintKind::Warn,
+            "Config issue detected — verify .context-finder/config.json or remove it.",
+        )),
+        ErrorCode::ProjectNotFound => Some((
+            HintKind::Action,
+            "Check payload.path/project or run from the repository root.",
+        )),
+        ErrorCode::FileNotFound => Some((
+            HintKind::Action,
+            "Verify the 'file' value is relative to project root and exists on disk.",
+        )),
+        ErrorCode::BudgetExceeded => {
+            if let Some(action) = action {
+                if let Some(mut max_chars) = default_max_chars_for_action(action) {
+                    if let Some(min_chars) = parse_min_chars(message) {
+                        max_chars = max_chars.max(min_chars);
+                    }
+                    next_actions.push(ToolNextAction::new(
+                        action.as_str(),
+                        build_retry_args(payload, max_chars),
+                        format!("Retry {} with max_chars={}.", action.as_str(), max_chars),
+                    ));
                 }
-                let args = build_retry_args(payload, max_chars);
-                next_actions.push(ToolNextAction {
-                    tool: action.as_str().to_string(),
-                    args,
-                    reason: format!("Retry {} with max_chars={}.", action.as_str(), max_chars),
-                });
             }
+            Some((
+                HintKind::Action,
+                "Increase max_chars; the response budget is too small for the result envelope.",
+            ))
         }
-    }
-
-    if message.contains("budget exceeded") {
-        code = "invalid_request".to_string();
-        hints.push(Hint {
-            kind: HintKind::Action,
-            text: "Increase max_chars; response budget was exceeded.".to_string(),
-        });
-        if let Some(action) = action {
-            if let Some(max_chars) = default_max_chars_for_action(action) {
-                let args = build_retry_args(payload, max_chars);
-                next_actions.push(ToolNextAction {
-                    tool: action.as_str().to_string(),
-                    args,
-                    reason: format!("Retry {} with max_chars={}.", action.as_str(), max_chars),
-                });
+        ErrorCode::ModelManifestMissing => Some((
+            HintKind::Action,
+            "No readable models/manifest.json — set CONTEXT_FINDER_MODEL_DIR to the directory holding it.",
+        )),
+        ErrorCode::ModelUnknown => Some((HintKind::Action, "Use an id listed by action=models_list.")),
+        ErrorCode::ModelMissing => Some((
+            HintKind::Action,
+            "Model files are missing — run action=models_fetch, or set CONTEXT_FINDER_EMBEDDING_MODE=stub for offline use.",
+        )),
+        ErrorCode::ModelNetworkError => {
+            if action == Some(CommandAction::ModelsFetch) {
+                next_actions.push(ToolNextAction::new(
+                    CommandAction::ModelsFetch.as_str(),
+                    payload.cloned().unwrap_or_else(empty_payload),
+                    "Retry the download once the network is reachable.",
+                ));
             }
+            Some((
+                HintKind::Action,
+                "The model host is unreachable — check network access or proxy settings, then retry.",
+            ))
         }
-    }
-
-    if hint.is_none() {
-        hint = hints.first().map(|h| h.text.clone());
-    }
-
+        ErrorCode::ModelDownloadRejected => Some((
+            HintKind::Action,
+            "The model host refused the download — check the asset source in manifest.json.",
+        )),
+        ErrorCode::ModelChecksumMismatch => Some((
+            HintKind::Action,
+            "The downloaded file does not match the manifest sha256 — retry, or fix the manifest entry.",
+        )),
+        ErrorCode::ModelVerifyFailed => Some((
+            HintKind::Action,
+            "A model failed to load or embed; see data.models for the per-model error.",
+        )),
+        ErrorCode::QualityGateFailed => Some((
+            HintKind::Info,
+            "Results are in data; the requested quality thresholds were not met.",
+        )),
+        ErrorCode::Timeout | ErrorCode::Cancelled => Some((
+            HintKind::Action,
+            "Raise the time budget or narrow the request, then retry.",
+        )),
+        ErrorCode::Internal
+        | ErrorCode::InvalidRequest
+        | ErrorCode::InvalidCursor
+        | ErrorCode::MissingField
+        | ErrorCode::ToolError => None,
+    };
+
+    let hints: Vec<Hint> = hint
+        .into_iter()
+        .map(|(kind, text)| Hint {
+            kind,
+            text: text.to_string(),
+        })
+        .collect();
     ErrorClassification {
         code,
-        hint,
+        hint: hints.first().map(|h| h.text.clone()),
         hints,
         next_actions,
     }
@@ -1194,3 +1868,124 @@ fn default_max_chars_for_action(action: CommandAction) -> Option<usize> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_graph::GraphError;
+    use context_indexer::IndexerError;
+    use context_search::SearchError;
+    use context_vector_store::VectorStoreError;
+
+    fn code_of(err: &anyhow::Error) -> ErrorCode {
+        classify_error(&format!("{err:#}"), typed_error_code(err), None, None).code
+    }
+
+    #[test]
+    fn representative_errors_map_to_codes() {
+        let typed: Vec<(anyhow::Error, ErrorCode)> = vec![
+            (
+                CodedError::new(ErrorCode::IndexMissing, "no index").into(),
+                ErrorCode::IndexMissing,
+            ),
+            (
+                anyhow::Error::new(CodedError::new(ErrorCode::FileNotFound, "src/a.rs"))
+                    .context("loading context"),
+                ErrorCode::FileNotFound,
+            ),
+            (
+                IndexerError::InvalidPath("Path does not exist: /nope".to_string()).into(),
+                ErrorCode::ProjectNotFound,
+            ),
+            (IndexerError::BudgetExceeded.into(), ErrorCode::Timeout),
+            (
+                IndexerError::IoError(std::io::ErrorKind::NotFound.into()).into(),
+                ErrorCode::FileNotFound,
+            ),
+            (
+                IndexerError::VectorStoreError(VectorStoreError::ModelMissing(
+                    "Model files for 'bge-small' are missing.".to_string(),
+                ))
+                .into(),
+                ErrorCode::ModelMissing,
+            ),
+            (
+                VectorStoreError::InvalidDimension {
+                    expected: 384,
+                    actual: 3,
+                }
+                .into(),
+                ErrorCode::IndexCorrupt,
+            ),
+            (SearchError::EmptyQuery.into(), ErrorCode::InvalidRequest),
+            (
+                SearchError::GraphError(GraphError::SymbolNotFound("main".to_string())).into(),
+                ErrorCode::InvalidRequest,
+            ),
+            // A typed code wins over what the message text suggests.
+            (
+                CodedError::new(ErrorCode::Stale, "Index not found in config").into(),
+                ErrorCode::Stale,
+            ),
+        ];
+        for (err, expected) in &typed {
+            assert_eq!(code_of(err), *expected, "{err:#}");
+        }
+
+        let quality = format!("{QUALITY_GATE_FAILED}: mean_mrr 0.10 < 0.50");
+        let network = format!(
+            "{}: GET https://example.invalid",
+            models::MODEL_NETWORK_ERROR
+        );
+        let untyped = [
+            (
+                "Index not found at /p/index.json. Run 'context-finder index' first.",
+                ErrorCode::IndexMissing,
+            ),
+            (
+                "Failed to load vector store for baseline: eof",
+                ErrorCode::IndexCorrupt,
+            ),
+            (quality.as_str(), ErrorCode::QualityGateFailed),
+            (network.as_str(), ErrorCode::ModelNetworkError),
+            (
+                "max_chars too small (min_chars=1200)",
+                ErrorCode::BudgetExceeded,
+            ),
+            (
+                "Context depth > 0 but no graph language specified",
+                ErrorCode::GraphLanguageMissing,
+            ),
+            (
+                "Unsupported graph language: cobol",
+                ErrorCode::InvalidRequest,
+            ),
+            (
+                "Failed to read config /p/config.json: denied",
+                ErrorCode::ConfigError,
+            ),
+            ("unexpected end of input", ErrorCode::Internal),
+        ];
+        for (message, expected) in untyped {
+            let err = anyhow::anyhow!(message.to_string());
+            assert_eq!(typed_error_code(&err), None, "{message}");
+            assert_eq!(code_of(&err), expected, "{message}");
+        }
+    }
+
+    #[test]
+    fn index_missing_suggests_indexing_the_project() {
+        let payload = json!({ "project": "/repo", "query": "auth" });
+        let classification = classify_error(
+            "no index",
+            Some(ErrorCode::IndexMissing),
+            Some(CommandAction::Search),
+            Some(&payload),
+        );
+        assert_eq!(classification.code.as_str(), "index_missing");
+        assert!(classification.hint.is_some());
+        assert_eq!(classification.next_actions.len(), 1);
+        assert_eq!(classification.next_actions[0].tool, "index");
+        assert_eq!(classification.next_actions[0].args["path"], "/repo");
+    }
+}
@@ -1,13 +1,16 @@
-use crate::command::context::{index_path, load_store_mtime, unix_ms};
+use crate::command::context::{index_path_for_model, load_store_mtime, unix_ms};
 use crate::command::domain::{Hint, HintKind, RequestOptions, StalePolicy};
 use anyhow::Result;
 use context_indexer::{
-    assess_staleness, compute_project_watermark, read_index_watermark, IndexSnapshot, IndexState,
-    IndexerError, PersistedIndexWatermark, ProjectIndexer, ReindexAttempt, ReindexResult,
-    StaleReason, Watermark, INDEX_STATE_SCHEMA_VERSION,
+    assess_staleness, compute_project_watermark, current_index_provenance, read_index_watermark,
+    shared_reindex, IndexSnapshot, IndexState, IndexerError, PersistedIndexWatermark,
+    ProjectIndexer, ReindexAttempt, ReindexResult, SharedReindex, StaleReason, Watermark,
+    INDEX_STATE_SCHEMA_VERSION,
 };
+use context_protocol::ErrorCode;
 use context_search::SearchProfile;
 use context_vector_store::current_model_id;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
@@ -16,10 +19,15 @@ pub struct FreshnessGate {
     pub index_state: IndexState,
     pub hints: Vec<Hint>,
     pub index_updated: bool,
+    /// An auto-reindex ran, or was joined, for this request
+    pub reindex_attempted: bool,
+    /// The auto-reindex budget ran out before the index was refreshed
+    pub reindex_deadline_hit: bool,
 }
 
 #[derive(Debug)]
 pub struct FreshnessBlock {
+    pub code: ErrorCode,
     pub message: String,
     pub hints: Vec<Hint>,
     pub index_state: IndexState,
@@ -37,6 +45,10 @@ pub fn action_requires_index(action: &crate::command::domain::CommandAction) ->
             | A::Map
             | A::Eval
             | A::EvalCompare
+            | A::Impact
+            | A::Trace
+            | A::Explain
+            | A::Overview
     )
 }
 
@@ -53,18 +65,56 @@ pub fn extract_project_path(payload: &serde_json::Value) -> Option<PathBuf> {
         })
 }
 
-pub async fn gather_index_state(project_root: &Path, profile_name: &str) -> Result<IndexState> {
+/// Explicit `models` of a search/context_pack payload, which the gate checks on top of the
+/// primary index.
+pub fn extract_models(payload: &serde_json::Value) -> Vec<String> {
+    payload
+        .get("models")
+        .and_then(|v| v.as_array())
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub async fn gather_index_state(
+    project_root: &Path,
+    profile_name: &str,
+    profile: &SearchProfile,
+) -> Result<IndexState> {
     let project_watermark = compute_project_watermark(project_root).await?;
-    gather_index_state_with_project_mark(project_root, profile_name, project_watermark).await
+    gather_index_state_with_project_mark(
+        project_root,
+        profile_name,
+        profile,
+        &primary_model_id(),
+        project_watermark,
+    )
+    .await
+}
+
+fn primary_model_id() -> String {
+    current_model_id().unwrap_or_else(|_| "bge-small".to_string())
 }
 
 async fn gather_index_state_with_project_mark(
     project_root: &Path,
     profile_name: &str,
+    profile: &SearchProfile,
+    model_id: &str,
     project_watermark: Watermark,
 ) -> Result<IndexState> {
-    let model_id = current_model_id().unwrap_or_else(|_| "bge-small".to_string());
-    let store_path = index_path(project_root);
+    let model_id = model_id.to_string();
+    let current_provenance =
+        current_index_provenance(project_root, &model_id, profile.name(), profile.embedding())
+            .await;
+    let store_path = index_path_for_model(project_root, &model_id);
     let index_exists = store_path.exists();
 
     let mut index_corrupt = false;
@@ -82,13 +132,16 @@ async fn gather_index_state_with_project_mark(
 
     let mut watermark = None;
     let mut built_at_unix_ms = None;
+    let mut provenance = None;
     match read_index_watermark(&store_path).await {
         Ok(Some(PersistedIndexWatermark {
             built_at_unix_ms: built_at,
             watermark: mark,
+            provenance: built_by,
         })) => {
             built_at_unix_ms = Some(built_at);
             watermark = Some(mark);
+            provenance = built_by;
         }
         Ok(None) => {}
         Err(_) => {
@@ -101,6 +154,8 @@ async fn gather_index_state_with_project_mark(
         index_exists,
         index_corrupt,
         watermark.as_ref(),
+        provenance.as_ref(),
+        Some(&current_provenance),
     );
 
     let snapshot = IndexSnapshot {
@@ -109,6 +164,7 @@ async fn gather_index_state_with_project_mark(
         mtime_ms: index_mtime_ms,
         built_at_unix_ms,
         watermark,
+        provenance,
     };
 
     Ok(IndexState {
@@ -121,38 +177,56 @@ async fn gather_index_state_with_project_mark(
         stale: assessment.stale,
         stale_reasons: assessment.reasons,
         reindex: None,
+        reindex_attempts: Vec::new(),
     })
 }
 
+/// Checks the primary index, and then every extra model in `models`, against
+/// `options.stale_policy`. Only the primary index is auto-reindexed.
 pub async fn enforce_stale_policy(
     project_root: &Path,
     profile_name: &str,
     profile: &SearchProfile,
+    models: &[String],
     options: &RequestOptions,
 ) -> Result<std::result::Result<FreshnessGate, FreshnessBlock>> {
     let project_mark = compute_project_watermark(project_root).await?;
+    let primary_model = primary_model_id();
     let mut gate = FreshnessGate {
-        index_state: gather_index_state_with_project_mark(project_root, profile_name, project_mark)
-            .await?,
+        index_state: gather_index_state_with_project_mark(
+            project_root,
+            profile_name,
+            profile,
+            &primary_model,
+            project_mark.clone(),
+        )
+        .await?,
         hints: Vec::new(),
         index_updated: false,
+        reindex_attempted: false,
+        reindex_deadline_hit: false,
     };
 
     match options.stale_policy {
         StalePolicy::Auto => {
             if gate.index_state.stale || !gate.index_state.index.exists {
-                let attempt = attempt_reindex(project_root, profile, options.max_reindex_ms).await;
-                gate.hints.push(render_reindex_hint(&attempt));
-                gate.index_updated |= attempt.performed;
+                let reindex =
+                    shared_auto_reindex(project_root, profile, options.max_reindex_ms).await;
+                gate.hints.push(render_reindex_hint(&reindex));
+                gate.index_updated |= reindex.attempt.performed;
+                gate.reindex_attempted = true;
+                gate.reindex_deadline_hit = reindex.deadline_hit;
 
-                if let Ok(refreshed) = gather_index_state(project_root, profile_name).await {
+                if let Ok(refreshed) = gather_index_state(project_root, profile_name, profile).await
+                {
                     gate.index_state = refreshed;
                 }
-                gate.index_state.reindex = Some(attempt);
+                gate.index_state.reindex = Some(reindex.attempt);
             }
 
             if !gate.index_state.index.exists {
                 return Ok(Err(FreshnessBlock {
+                    code: ErrorCode::IndexMissing,
                     message: missing_index_message(&gate.index_state),
                     hints: gate.hints,
                     index_state: gate.index_state,
@@ -172,6 +246,7 @@ pub async fn enforce_stale_policy(
         StalePolicy::Warn => {
             if !gate.index_state.index.exists {
                 return Ok(Err(FreshnessBlock {
+                    code: ErrorCode::IndexMissing,
                     message: missing_index_message(&gate.index_state),
                     hints: gate.hints,
                     index_state: gate.index_state,
@@ -190,6 +265,7 @@ pub async fn enforce_stale_policy(
         StalePolicy::Fail => {
             if !gate.index_state.index.exists {
                 return Ok(Err(FreshnessBlock {
+                    code: ErrorCode::IndexMissing,
                     message: missing_index_message(&gate.index_state),
                     hints: gate.hints,
                     index_state: gate.index_state,
@@ -197,6 +273,7 @@ pub async fn enforce_stale_policy(
             }
             if gate.index_state.stale {
                 return Ok(Err(FreshnessBlock {
+                    code: ErrorCode::Stale,
                     message: format!(
                         "Index is stale ({}). Rebuild it or set options.stale_policy to 'warn'/'auto'.",
                         format_stale_reasons(&gate.index_state.stale_reasons)
@@ -208,10 +285,78 @@ pub async fn enforce_stale_policy(
         }
     }
 
+    let mut seen = HashSet::from([primary_model]);
+    for model_id in models {
+        if !seen.insert(model_id.clone()) {
+            continue;
+        }
+        let state = gather_index_state_with_project_mark(
+            project_root,
+            profile_name,
+            profile,
+            model_id,
+            project_mark.clone(),
+        )
+        .await?;
+        if !state.index.exists {
+            return Ok(Err(FreshnessBlock {
+                code: ErrorCode::IndexMissing,
+                message: format!(
+                    "Index for model '{model_id}' not found. Run 'context-finder index --models {model_id}' first."
+                ),
+                hints: gate.hints,
+                index_state: gate.index_state,
+            }));
+        }
+        if !state.stale {
+            continue;
+        }
+        let reasons = format_stale_reasons(&state.stale_reasons);
+        if options.stale_policy == StalePolicy::Fail {
+            return Ok(Err(FreshnessBlock {
+                code: ErrorCode::Stale,
+                message: format!(
+                    "Index for model '{model_id}' is stale ({reasons}). Rebuild it or set options.stale_policy to 'warn'/'auto'."
+                ),
+                hints: gate.hints,
+                index_state: gate.index_state,
+            }));
+        }
+        gate.index_state.stale = true;
+        for reason in state.stale_reasons {
+            if !gate.index_state.stale_reasons.contains(&reason) {
+                gate.index_state.stale_reasons.push(reason);
+            }
+        }
+        let policy = match options.stale_policy {
+            StalePolicy::Auto => "auto",
+            StalePolicy::Warn | StalePolicy::Fail => "warn",
+        };
+        gate.hints.push(Hint {
+            kind: HintKind::Warn,
+            text: format!(
+                "Index for model '{model_id}' appears stale ({reasons}). Proceeding due to stale_policy={policy}."
+            ),
+        });
+    }
+
     Ok(Ok(gate))
 }
 
-pub async fn attempt_reindex(
+/// Auto-reindex within `max_reindex_ms`. Requests for the same root share one in-flight
+/// reindex: later callers wait for it up to their own budget, then proceed with the stale index.
+pub async fn shared_auto_reindex(
+    project_root: &Path,
+    profile: &SearchProfile,
+    max_reindex_ms: u64,
+) -> SharedReindex {
+    shared_reindex(project_root, Duration::from_millis(max_reindex_ms), || {
+        attempt_reindex(project_root, profile, max_reindex_ms)
+    })
+    .await
+}
+
+async fn attempt_reindex(
     project_root: &Path,
     profile: &SearchProfile,
     max_reindex_ms: u64,
@@ -231,7 +376,7 @@ pub async fn attempt_reindex(
     let templates = profile.embedding().clone();
     let indexer = match ProjectIndexer::new_with_embedding_templates(project_root, templates).await
     {
-        Ok(i) => i,
+        Ok(i) => i.with_profile(profile.name()),
         Err(err) => {
             attempt.duration_ms = Some(start.elapsed().as_millis() as u64);
             attempt.result = Some(ReindexResult::Failed);
@@ -258,7 +403,8 @@ pub async fn attempt_reindex(
     attempt
 }
 
-pub fn render_reindex_hint(attempt: &ReindexAttempt) -> Hint {
+pub fn render_reindex_hint(reindex: &SharedReindex) -> Hint {
+    let attempt = &reindex.attempt;
     let budget = attempt
         .budget_ms
         .map(|v| format!("{v}ms"))
@@ -268,6 +414,21 @@ pub fn render_reindex_hint(attempt: &ReindexAttempt) -> Hint {
         .map(|v| format!("{v}ms"))
         .unwrap_or_else(|| "unknown".to_string());
 
+    if reindex.joined && reindex.deadline_hit {
+        return Hint {
+            kind: HintKind::Warn,
+            text: format!(
+                "Auto reindex already in progress for this project; stopped waiting after {duration} (budget {budget}) and proceeding with the current index"
+            ),
+        };
+    }
+    if reindex.joined {
+        return Hint {
+            kind: HintKind::Info,
+            text: format!("Waited {duration} for an auto reindex already in progress"),
+        };
+    }
+
     match attempt.result {
         Some(ReindexResult::Ok) => Hint {
             kind: HintKind::Cache,
@@ -291,6 +452,51 @@ pub fn render_reindex_hint(attempt: &ReindexAttempt) -> Hint {
     }
 }
 
+/// Warn when returned files were modified on disk after their chunks were indexed,
+/// which the project watermark misses until the next reindex.
+pub fn changed_since_indexed_hint<'a>(
+    root: &Path,
+    results: impl IntoIterator<Item = (&'a str, Option<u64>)>,
+) -> Option<Hint> {
+    let mut changed: Vec<&str> = Vec::new();
+    for (file, indexed_at_ms) in results {
+        let Some(indexed_at_ms) = indexed_at_ms else {
+            continue;
+        };
+        if changed.contains(&file) {
+            continue;
+        }
+        let mtime_ms = std::fs::metadata(root.join(file))
+            .and_then(|meta| meta.modified())
+            .map(unix_ms);
+        if mtime_ms.is_ok_and(|mtime_ms| mtime_ms > indexed_at_ms) {
+            changed.push(file);
+        }
+    }
+    if changed.is_empty() {
+        return None;
+    }
+    let shown = changed
+        .iter()
+        .take(3)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ");
+    let more = changed.len().saturating_sub(3);
+    let suffix = if more > 0 {
+        format!(" (+{more} more)")
+    } else {
+        String::new()
+    };
+    Some(Hint {
+        kind: HintKind::Warn,
+        text: format!(
+            "{} returned file(s) changed on disk since they were indexed: {shown}{suffix}; results may be outdated",
+            changed.len()
+        ),
+    })
+}
+
 fn missing_index_message(state: &IndexState) -> String {
     let path = state
         .index
@@ -308,6 +514,9 @@ fn stale_reason_name(reason: &StaleReason) -> &'static str {
         StaleReason::GitHeadMismatch => "git_head_mismatch",
         StaleReason::GitDirtyMismatch => "git_dirty_mismatch",
         StaleReason::FilesystemChanged => "filesystem_changed",
+        StaleReason::ToolOrTemplateChanged => "tool_or_template_changed",
+        StaleReason::ConfigChanged => "config_changed",
+        StaleReason::ModelMismatch => "model_mismatch",
     }
 }
 
@@ -321,3 +530,32 @@ fn format_stale_reasons(reasons: &[StaleReason]) -> String {
         .collect::<Vec<_>>()
         .join(", ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warns_only_for_files_modified_after_indexing() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("a.rs"), "fn a() {}").unwrap();
+        std::fs::write(temp.path().join("b.rs"), "fn b() {}").unwrap();
+
+        let hint = changed_since_indexed_hint(
+            temp.path(),
+            [
+                ("a.rs", Some(0)),
+                ("a.rs", Some(0)),
+                ("b.rs", Some(u64::MAX)),
+                ("missing.rs", Some(0)),
+                ("b.rs", None),
+            ],
+        )
+        .expect("a.rs changed after indexing");
+        assert!(matches!(hint.kind, HintKind::Warn));
+        assert!(hint.text.starts_with("1 returned file(s)"), "{}", hint.text);
+        assert!(hint.text.contains("a.rs") && !hint.text.contains("b.rs"));
+
+        assert!(changed_since_indexed_hint(temp.path(), [("b.rs", Some(u64::MAX))]).is_none());
+    }
+}
@@ -1,5 +1,9 @@
 use crate::graph_cache::GraphCache;
+use anyhow::Result;
+use context_indexer::IndexUpdate;
 use std::path::Path;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
 
 #[derive(Clone, Default)]
 pub struct GraphCacheFactory;
@@ -8,4 +12,162 @@ impl GraphCacheFactory {
     pub fn for_root(&self, root: &Path) -> GraphCache {
         GraphCache::new(root)
     }
+
+    /// Drop the graph cache entries of `root` that `update` touched: the nodes of its changed
+    /// and deleted files, or the whole cache when those lists were truncated.
+    pub async fn invalidate(&self, root: &Path, update: &IndexUpdate) -> Result<()> {
+        if !update.success {
+            return Ok(());
+        }
+        let cache = self.for_root(root);
+        if update.changed_truncated {
+            cache.clear().await?;
+            return Ok(());
+        }
+
+        let touched: Vec<String> = update
+            .changed_files
+            .iter()
+            .chain(&update.deleted_files)
+            .cloned()
+            .collect();
+        if !touched.is_empty() {
+            let dropped = cache.drop_files(&touched).await?;
+            if dropped > 0 {
+                log::debug!(
+                    "Dropped {dropped} cached graph nodes for {}",
+                    root.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Invalidate `root`'s graph cache after every index update until the watcher goes away.
+    /// Missed updates clear the whole cache, since their file lists are gone.
+    pub fn subscribe(
+        &self,
+        root: &Path,
+        mut updates: broadcast::Receiver<IndexUpdate>,
+    ) -> JoinHandle<()> {
+        let factory = self.clone();
+        let root = root.to_path_buf();
+        tokio::spawn(async move {
+            loop {
+                let result = match updates.recv().await {
+                    Ok(update) => factory.invalidate(&root, &update).await,
+                    Err(RecvError::Lagged(_)) => factory.for_root(&root).clear().await.map(drop),
+                    Err(RecvError::Closed) => break,
+                };
+                if let Err(err) = result {
+                    log::warn!(
+                        "Failed to invalidate graph cache for {}: {err:#}",
+                        root.display()
+                    );
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_code_chunker::{ChunkMetadata, CodeChunk};
+    use context_graph::{
+        CodeGraph, ContextAssembler, GraphLanguage, GraphNode, Symbol, SymbolType,
+    };
+    use std::collections::HashMap;
+    use std::time::{Duration, SystemTime};
+
+    fn node(file: &str, name: &str) -> GraphNode {
+        GraphNode {
+            symbol: Symbol {
+                name: name.to_string(),
+                qualified_name: None,
+                file_path: file.to_string(),
+                start_line: 1,
+                end_line: 3,
+                symbol_type: SymbolType::Function,
+            },
+            chunk_id: format!("{file}:{name}"),
+            chunk: None,
+            signature: None,
+            documentation: None,
+        }
+    }
+
+    fn update(changed: &[&str], deleted: &[&str]) -> IndexUpdate {
+        IndexUpdate {
+            completed_at: SystemTime::now(),
+            duration_ms: 1,
+            stats: None,
+            success: true,
+            reason: "test".to_string(),
+            store_size_bytes: None,
+            changed_files: changed.iter().map(|f| (*f).to_string()).collect(),
+            deleted_files: deleted.iter().map(|f| (*f).to_string()).collect(),
+            changed_truncated: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn index_updates_drop_only_the_touched_files_from_the_graph_cache() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+        let factory = GraphCacheFactory;
+        let cache = factory.for_root(root);
+
+        let mut graph = CodeGraph::new();
+        for (file, name) in [("a.rs", "alpha"), ("b.rs", "beta"), ("c.rs", "gamma")] {
+            graph.add_node(node(file, name));
+        }
+        let chunks: Vec<CodeChunk> = ["a.rs:alpha", "b.rs:beta", "c.rs:gamma"]
+            .into_iter()
+            .map(|id| {
+                CodeChunk::new(
+                    id.to_string(),
+                    1,
+                    3,
+                    String::new(),
+                    ChunkMetadata::default(),
+                )
+            })
+            .collect();
+        let chunk_index: HashMap<String, usize> = chunks
+            .iter()
+            .enumerate()
+            .map(|(idx, chunk)| (chunk.file_path.clone(), idx))
+            .collect();
+        let store_mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(42);
+        cache
+            .save(
+                store_mtime,
+                GraphLanguage::Rust,
+                &ContextAssembler::new(graph),
+            )
+            .await
+            .expect("save");
+
+        let (tx, rx) = broadcast::channel(4);
+        let watcher = factory.subscribe(root, rx);
+        tx.send(update(&["a.rs"], &["c.rs"])).expect("send");
+        drop(tx);
+        watcher.await.expect("watcher");
+
+        let assembler = cache
+            .load(store_mtime, GraphLanguage::Rust, &chunks, &chunk_index)
+            .await
+            .expect("load")
+            .expect("cache survives a partial invalidation");
+        let graph = assembler.graph();
+        assert!(graph.find_node("beta").is_some());
+        assert!(graph.find_node("alpha").is_none());
+        assert!(graph.find_node("gamma").is_none());
+
+        let mut truncated = update(&[], &[]);
+        truncated.changed_truncated = true;
+        factory.invalidate(root, &truncated).await.expect("clear");
+        assert!(cache.size_bytes().await.is_none());
+    }
 }
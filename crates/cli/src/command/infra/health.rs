@@ -1,7 +1,10 @@
 use crate::command::context::index_path;
 use crate::command::domain::{CommandOutcome, Hint, HintKind};
 use anyhow::Result;
-use context_indexer::{read_health_snapshot, write_health_snapshot, HealthSnapshot, IndexStats};
+use context_graph::GRAPH_CACHE_FILE_NAME;
+use context_indexer::{
+    read_health_snapshot, write_health_snapshot, FailureReasonCount, HealthSnapshot, IndexStats,
+};
 use serde::Serialize;
 use std::path::Path;
 use tokio::fs;
@@ -27,6 +30,8 @@ pub struct HealthReport {
     pub graph_cache_size_bytes: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub failure_count: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failure_reason_counts: Vec<FailureReasonCount>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stale_ms: Option<u64>,
 }
@@ -56,7 +61,7 @@ impl HealthPort {
     pub async fn probe(&self, root: &Path) -> Result<HealthReport> {
         let snapshot = read_health_snapshot(root).await.ok().flatten();
         let index_path = index_path(root);
-        let graph_path = root.join(".context-finder").join("graph_cache.json");
+        let graph_path = root.join(".context-finder").join(GRAPH_CACHE_FILE_NAME);
 
         let index_size_bytes = fs::metadata(&index_path).await.ok().map(|m| m.len());
         let graph_cache_size_bytes = fs::metadata(&graph_path).await.ok().map(|m| m.len());
@@ -79,6 +84,9 @@ impl HealthPort {
             index_size_bytes,
             graph_cache_size_bytes,
             failure_count: snapshot_ref.and_then(|s| s.failure_count),
+            failure_reason_counts: snapshot_ref
+                .map(|s| s.failure_reason_counts.clone())
+                .unwrap_or_default(),
             stale_ms,
         })
     }
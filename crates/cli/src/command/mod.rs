@@ -3,18 +3,21 @@ pub mod domain;
 mod freshness;
 pub mod infra;
 mod path_filters;
+pub mod schema;
 mod services;
 pub mod warm;
 
 #[allow(unused_imports)]
 pub use domain::{
-    classify_error, CommandAction, CommandRequest, CommandResponse, CommandStatus,
-    ContextPackOutput, ContextPackPayload, EvalCacheMode, EvalCaseResult, EvalCompareCase,
-    EvalCompareConfig, EvalCompareOutput, EvalComparePayload, EvalCompareSummary, EvalDatasetMeta,
-    EvalHit, EvalOutput, EvalPayload, EvalRun, EvalRunSummary, EvalSummary, Hint, HintKind,
-    IndexPayload, IndexResponse, ListSymbolsPayload, MapOutput, MapPayload, ResponseMeta,
-    SearchOutput, SearchPayload, SearchStrategy, SearchWithContextPayload, SymbolsOutput,
-    TaskPackOutput, TaskPackPayload, TextSearchOutput, TextSearchPayload,
+    classify_error, typed_error_code, CommandAction, CommandRequest, CommandResponse,
+    CommandStatus, ContextPackOutput, ContextPackPayload, EvalCacheMode, EvalCaseResult,
+    EvalCompareCase, EvalCompareConfig, EvalCompareOutput, EvalComparePayload, EvalCompareSummary,
+    EvalDatasetMeta, EvalGenerateOutput, EvalGeneratePayload, EvalGenerateSkipped, EvalHit,
+    EvalOutput, EvalOutputFormat, EvalPathWeight, EvalPayload, EvalPrefixDelta, EvalRun,
+    EvalRunSummary, EvalSummary, EvalThresholds, Hint, HintKind, IndexPayload, IndexPlanResponse,
+    IndexResponse, ListSymbolsPayload, MapOutput, MapPayload, ResponseMeta, SearchOutput,
+    SearchPayload, SearchStrategy, SearchWithContextPayload, SymbolsOutput, TaskPackOutput,
+    TaskPackPayload, TextSearchOutput, TextSearchPayload,
 };
 
 use crate::cache::CacheConfig;
@@ -51,6 +54,7 @@ impl CommandHandler {
         let mut guard_index_state = None;
         let mut guard_hints = Vec::new();
         let mut guard_index_updated = false;
+        let mut guard_reindex_deadline_hit = None;
 
         if freshness::action_requires_index(&action) {
             match ctx
@@ -62,6 +66,7 @@ impl CommandHandler {
                         &project_ctx.root,
                         &project_ctx.profile_name,
                         &project_ctx.profile,
+                        &freshness::extract_models(&payload),
                         &request_options,
                     )
                     .await
@@ -70,6 +75,8 @@ impl CommandHandler {
                             guard_index_state = Some(gate.index_state);
                             guard_hints.extend(gate.hints);
                             guard_index_updated |= gate.index_updated;
+                            guard_reindex_deadline_hit =
+                                gate.reindex_attempted.then_some(gate.reindex_deadline_hit);
                         }
                         Ok(Err(block)) => {
                             let meta = ResponseMeta {
@@ -84,6 +91,7 @@ impl CommandHandler {
 
                             let classification = classify_error(
                                 &block.message,
+                                Some(block.code),
                                 Some(action),
                                 Some(&payload_for_meta),
                             );
@@ -95,7 +103,7 @@ impl CommandHandler {
                                 .or_else(|| hints.first().map(|h| h.text.clone()));
 
                             let error = ErrorEnvelope {
-                                code: classification.code,
+                                code: classification.code.to_string(),
                                 message: block.message.clone(),
                                 details: None,
                                 hint,
@@ -131,6 +139,10 @@ impl CommandHandler {
                 if guard_index_updated {
                     outcome.meta.index_updated = Some(true);
                 }
+                if let Some(deadline_hit) = guard_reindex_deadline_hit {
+                    outcome.meta.reindex_attempted = Some(true);
+                    outcome.meta.reindex_deadline_hit = Some(deadline_hit);
+                }
                 outcome.hints.extend(guard_hints);
                 if outcome.meta.index_state.is_none() {
                     outcome.meta.index_state = guard_index_state;
@@ -140,20 +152,48 @@ impl CommandHandler {
                     .duration_ms
                     .or_else(|| Some(started.elapsed().as_millis() as u64));
 
-                CommandResponse {
-                    status: CommandStatus::Ok,
-                    message: None,
-                    error: None,
-                    hints: outcome.hints,
-                    next_actions: outcome.next_actions,
-                    data: outcome.data,
-                    meta: outcome.meta,
+                match outcome.failure {
+                    None => CommandResponse {
+                        status: CommandStatus::Ok,
+                        message: None,
+                        error: None,
+                        hints: outcome.hints,
+                        next_actions: outcome.next_actions,
+                        data: outcome.data,
+                        meta: outcome.meta,
+                    },
+                    Some(message) => {
+                        let classification =
+                            classify_error(&message, None, Some(action), Some(&payload_for_meta));
+                        let mut hints = classification.hints;
+                        hints.extend(outcome.hints);
+                        let error = ErrorEnvelope {
+                            code: classification.code.to_string(),
+                            message: message.clone(),
+                            details: None,
+                            hint: classification.hint,
+                            next_actions: classification.next_actions,
+                        };
+                        CommandResponse {
+                            status: CommandStatus::Error,
+                            message: Some(message),
+                            error: Some(error),
+                            hints,
+                            next_actions: outcome.next_actions,
+                            data: outcome.data,
+                            meta: outcome.meta,
+                        }
+                    }
                 }
             }
             Err(err) => {
                 let message = format!("{err:#}");
-                let classification =
-                    classify_error(&message, Some(action), Some(&payload_for_meta));
+                let classification = classify_error(
+                    &message,
+                    typed_error_code(&err),
+                    Some(action),
+                    Some(&payload_for_meta),
+                );
                 let mut hints = classification.hints;
                 if guard_index_updated {
                     hints.push(Hint {
@@ -166,7 +206,7 @@ impl CommandHandler {
                     .hint
                     .or_else(|| hints.first().map(|h| h.text.clone()));
                 let error = ErrorEnvelope {
-                    code: classification.code,
+                    code: classification.code.to_string(),
                     message: message.clone(),
                     details: None,
                     hint,
@@ -175,6 +215,8 @@ impl CommandHandler {
                 let meta = ResponseMeta {
                     duration_ms: Some(started.elapsed().as_millis() as u64),
                     index_state: guard_index_state,
+                    reindex_attempted: guard_reindex_deadline_hit.map(|_| true),
+                    reindex_deadline_hit: guard_reindex_deadline_hit,
                     ..Default::default()
                 };
 
@@ -195,9 +237,12 @@ impl CommandHandler {
                 .resolve_project(freshness::extract_project_path(&payload_for_meta))
                 .await
             {
-                if let Ok(state) =
-                    freshness::gather_index_state(&project_ctx.root, &project_ctx.profile_name)
-                        .await
+                if let Ok(state) = freshness::gather_index_state(
+                    &project_ctx.root,
+                    &project_ctx.profile_name,
+                    &project_ctx.profile,
+                )
+                .await
                 {
                     response.meta.index_state = Some(state);
                 }
@@ -210,13 +255,13 @@ impl CommandHandler {
 
 fn error_response(err: anyhow::Error, duration_ms: u64) -> CommandResponse {
     let message = format!("{err:#}");
-    let classification = classify_error(&message, None, None);
+    let classification = classify_error(&message, typed_error_code(&err), None, None);
     let hints = classification.hints;
     let hint = classification
         .hint
         .or_else(|| hints.first().map(|h| h.text.clone()));
     let error = ErrorEnvelope {
-        code: classification.code,
+        code: classification.code.to_string(),
         message: message.clone(),
         details: None,
         hint,
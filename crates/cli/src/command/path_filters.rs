@@ -57,7 +57,7 @@ fn path_prefix_matches(prefix: &str, path: &str) -> bool {
     path == prefix || path.starts_with(&format!("{prefix}/"))
 }
 
-fn matches_file_pattern(path: &str, pattern: Option<&str>) -> bool {
+pub fn matches_file_pattern(path: &str, pattern: Option<&str>) -> bool {
     let Some(pattern) = pattern else {
         return true;
     };
@@ -0,0 +1,137 @@
+//! JSON Schemas of the Command API, one payload/response pair per action
+//! (`context-finder command --print-schema [action]`).
+//!
+//! `response` describes the envelope's `data` field; the envelope itself is
+//! `contracts/command/v1/command_response.schema.json`.
+
+use super::domain::{
+    BatchOutput, BatchPayload, CommandAction, CompareSearchPayload, ComparisonOutput,
+    ConfigReadPayload, ConfigReadResponse, ContextOutput, ContextPackOutput, ContextPackPayload,
+    EvalCompareOutput, EvalComparePayload, EvalGenerateOutput, EvalGeneratePayload, EvalOutput,
+    EvalPayload, ExplainPayload, GetContextPayload, ImpactPayload, IndexPayload, IndexPlanResponse,
+    IndexResponse, IndexVerifyOutput, IndexVerifyPayload, ListSymbolsPayload, MapOutput,
+    MapPayload, ModelsPayload, OverviewPayload, RepoOnboardingPackOutput,
+    RepoOnboardingPackPayload, SearchOutput, SearchPayload, SearchWithContextPayload,
+    SymbolsOutput, TaskPackOutput, TaskPackPayload, TemplatePreviewOutput, TemplatePreviewPayload,
+    TextSearchOutput, TextSearchPayload, TracePayload, WarmPayload, WarmReport,
+};
+use crate::models::{InstallModelsReport, ModelsListReport, ModelsVerifyReport};
+use context_graph::analysis::{ExplainReport, ImpactReport, OverviewReport, TraceReport};
+use context_protocol::Capabilities;
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// `capabilities` ignores its payload.
+#[derive(Deserialize, JsonSchema)]
+struct EmptyPayload {}
+
+/// `index` reports the plan instead of stats when `dry_run` is set.
+#[derive(Serialize, JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum IndexData {
+    Stats(IndexResponse),
+    Plan(IndexPlanResponse),
+}
+
+fn payload<T: JsonSchema>() -> Value {
+    SchemaSettings::default()
+        .for_deserialize()
+        .into_generator()
+        .into_root_schema_for::<T>()
+        .to_value()
+}
+
+fn response<T: JsonSchema>() -> Value {
+    SchemaSettings::default()
+        .for_serialize()
+        .into_generator()
+        .into_root_schema_for::<T>()
+        .to_value()
+}
+
+/// `{"action", "payload", "response"}` schemas for one action.
+pub fn action_schema(action: CommandAction) -> Value {
+    let (payload, response) = match action {
+        CommandAction::Search => (payload::<SearchPayload>(), response::<SearchOutput>()),
+        CommandAction::SearchWithContext => (
+            payload::<SearchWithContextPayload>(),
+            response::<SearchOutput>(),
+        ),
+        CommandAction::ContextPack => (
+            payload::<ContextPackPayload>(),
+            response::<ContextPackOutput>(),
+        ),
+        CommandAction::TaskPack => (payload::<TaskPackPayload>(), response::<TaskPackOutput>()),
+        CommandAction::TextSearch => (
+            payload::<TextSearchPayload>(),
+            response::<TextSearchOutput>(),
+        ),
+        CommandAction::Batch => (payload::<BatchPayload>(), response::<BatchOutput>()),
+        CommandAction::Capabilities => (payload::<EmptyPayload>(), response::<Capabilities>()),
+        CommandAction::Index => (payload::<IndexPayload>(), response::<IndexData>()),
+        CommandAction::GetContext => (payload::<GetContextPayload>(), response::<ContextOutput>()),
+        CommandAction::ListSymbols => {
+            (payload::<ListSymbolsPayload>(), response::<SymbolsOutput>())
+        }
+        CommandAction::ConfigRead => (
+            payload::<ConfigReadPayload>(),
+            response::<ConfigReadResponse>(),
+        ),
+        CommandAction::CompareSearch => (
+            payload::<CompareSearchPayload>(),
+            response::<ComparisonOutput>(),
+        ),
+        CommandAction::Map => (payload::<MapPayload>(), response::<MapOutput>()),
+        CommandAction::RepoOnboardingPack => (
+            payload::<RepoOnboardingPackPayload>(),
+            response::<RepoOnboardingPackOutput>(),
+        ),
+        CommandAction::Eval => (payload::<EvalPayload>(), response::<EvalOutput>()),
+        CommandAction::EvalCompare => (
+            payload::<EvalComparePayload>(),
+            response::<EvalCompareOutput>(),
+        ),
+        CommandAction::EvalGenerate => (
+            payload::<EvalGeneratePayload>(),
+            response::<EvalGenerateOutput>(),
+        ),
+        CommandAction::Impact => (payload::<ImpactPayload>(), response::<ImpactReport>()),
+        CommandAction::Trace => (payload::<TracePayload>(), response::<TraceReport>()),
+        CommandAction::Explain => (payload::<ExplainPayload>(), response::<ExplainReport>()),
+        CommandAction::Overview => (payload::<OverviewPayload>(), response::<OverviewReport>()),
+        CommandAction::ModelsList => (payload::<ModelsPayload>(), response::<ModelsListReport>()),
+        CommandAction::ModelsFetch => (
+            payload::<ModelsPayload>(),
+            response::<InstallModelsReport>(),
+        ),
+        CommandAction::ModelsVerify => {
+            (payload::<ModelsPayload>(), response::<ModelsVerifyReport>())
+        }
+        CommandAction::Warm => (payload::<WarmPayload>(), response::<WarmReport>()),
+        CommandAction::TemplatePreview => (
+            payload::<TemplatePreviewPayload>(),
+            response::<TemplatePreviewOutput>(),
+        ),
+        CommandAction::IndexVerify => (
+            payload::<IndexVerifyPayload>(),
+            response::<IndexVerifyOutput>(),
+        ),
+    };
+    json!({
+        "action": action.as_str(),
+        "payload": payload,
+        "response": response,
+    })
+}
+
+/// Schemas of every action, keyed by action name.
+pub fn all_action_schemas() -> Value {
+    let actions: Map<String, Value> = CommandAction::ALL
+        .into_iter()
+        .map(|action| (action.as_str().to_string(), action_schema(action)))
+        .collect();
+    Value::Object(actions)
+}
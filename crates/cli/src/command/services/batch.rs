@@ -1,16 +1,21 @@
 use super::Services;
 use crate::command::context::CommandContext;
 use crate::command::domain::{
-    classify_error, parse_payload, BatchBudget, BatchItemResult, BatchOutput, BatchPayload,
-    CommandAction, CommandOutcome, CommandStatus, Hint, HintKind, ResponseMeta, BATCH_VERSION,
+    classify_error, parse_payload, typed_error_code, BatchBudget, BatchItemResult, BatchMeta,
+    BatchOutput, BatchPayload, BatchTruncate, CommandAction, CommandOutcome, CommandStatus, Hint,
+    HintKind, ResponseMeta, BATCH_VERSION,
 };
 use crate::command::freshness;
 use anyhow::Result;
-use context_batch_ref::resolve_batch_refs;
-use context_protocol::{enforce_max_chars, finalize_used_chars, BudgetTruncation, ErrorEnvelope};
+use context_batch_ref::{evaluate_when, resolve_batch_refs};
+use context_protocol::{
+    enforce_max_chars, finalize_used_chars, truncate_largest_string, BudgetTruncation, ErrorCode,
+    ErrorEnvelope,
+};
 use serde_json::{json, Map, Value};
 use std::collections::HashSet;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 const DEFAULT_BATCH_MAX_CHARS: usize = 20_000;
 const MAX_BATCH_MAX_CHARS: usize = 500_000;
@@ -37,6 +42,7 @@ pub async fn run(
             truncation: None,
         },
         next_actions: Vec::new(),
+        meta: BatchMeta::default(),
     };
     let min_chars = {
         let mut min_output = BatchOutput {
@@ -49,6 +55,7 @@ pub async fn run(
                 truncation: Some(BudgetTruncation::MaxChars),
             },
             next_actions: Vec::new(),
+            meta: BatchMeta::default(),
         };
         finalize_batch_budget(&mut min_output)?
     };
@@ -61,6 +68,9 @@ pub async fn run(
     let mut inferred_project: Option<PathBuf> = payload.project;
     let mut gate: Option<freshness::FreshnessGate> = None;
     let mut seen_ids: HashSet<String> = HashSet::new();
+    let deadline = payload
+        .deadline_ms
+        .map(|ms| (Instant::now() + Duration::from_millis(ms), ms));
     let mut ref_context = json!({
         "project": inferred_project.as_ref().map(|p| p.display().to_string()),
         "path": inferred_project.as_ref().map(|p| p.display().to_string()),
@@ -72,11 +82,12 @@ pub async fn run(
         if id.is_empty() {
             let rejected = error_item(
                 item.id,
+                ErrorCode::InvalidRequest,
                 "Batch item id must not be empty".to_string(),
                 Vec::new(),
                 ResponseMeta::default(),
             );
-            if !push_item_or_truncate(&mut output, rejected)? {
+            if !push_item_or_truncate(&mut output, rejected, payload.truncate)? {
                 break;
             }
             if payload.stop_on_error {
@@ -88,11 +99,12 @@ pub async fn run(
         if !seen_ids.insert(id.clone()) {
             let rejected = error_item(
                 id.clone(),
+                ErrorCode::InvalidRequest,
                 format!("Duplicate batch item id is not supported: '{id}'"),
                 Vec::new(),
                 ResponseMeta::default(),
             );
-            if !push_item_or_truncate(&mut output, rejected.clone())? {
+            if !push_item_or_truncate(&mut output, rejected.clone(), payload.truncate)? {
                 break;
             }
 
@@ -111,11 +123,12 @@ pub async fn run(
         if matches!(item.action, CommandAction::Batch) {
             let rejected = error_item(
                 id.clone(),
+                ErrorCode::InvalidRequest,
                 "Nested batch actions are not supported".to_string(),
                 Vec::new(),
                 ResponseMeta::default(),
             );
-            if !push_item_or_truncate(&mut output, rejected.clone())? {
+            if !push_item_or_truncate(&mut output, rejected.clone(), payload.truncate)? {
                 break;
             }
 
@@ -131,6 +144,68 @@ pub async fn run(
             continue;
         }
 
+        if let Some((deadline_at, deadline_ms)) = deadline {
+            if Instant::now() >= deadline_at {
+                if !push_item_or_truncate(
+                    &mut output,
+                    deadline_skipped_item(id.clone(), deadline_ms),
+                    payload.truncate,
+                )? {
+                    break;
+                }
+                ref_context["items"][id.clone()] = json!({
+                    "status": "skipped",
+                    "message": null,
+                    "data": null,
+                });
+                continue;
+            }
+        }
+
+        if let Some(when) = item.when.as_ref() {
+            match evaluate_when(when, &ref_context) {
+                Ok(true) => {}
+                Ok(false) => {
+                    if !push_item_or_truncate(
+                        &mut output,
+                        skipped_item(id.clone()),
+                        payload.truncate,
+                    )? {
+                        break;
+                    }
+                    ref_context["items"][id.clone()] = json!({
+                        "status": "skipped",
+                        "message": null,
+                        "data": null,
+                    });
+                    continue;
+                }
+                Err(err) => {
+                    let rejected = error_item(
+                        id.clone(),
+                        ErrorCode::InvalidRequest,
+                        format!("Invalid when predicate: {err}"),
+                        Vec::new(),
+                        ResponseMeta::default(),
+                    );
+                    if !push_item_or_truncate(&mut output, rejected.clone(), payload.truncate)? {
+                        break;
+                    }
+
+                    ref_context["items"][id.clone()] = json!({
+                        "status": "error",
+                        "message": rejected.message,
+                        "data": rejected.data,
+                    });
+
+                    if payload.stop_on_error {
+                        break;
+                    }
+                    continue;
+                }
+            }
+        }
+
         ref_context["project"] = inferred_project
             .as_ref()
             .map(|p| Value::String(p.display().to_string()))
@@ -142,11 +217,12 @@ pub async fn run(
             Err(err) => {
                 let rejected = error_item(
                     id.clone(),
+                    ErrorCode::InvalidRequest,
                     format!("Ref resolution error: {err}"),
                     Vec::new(),
                     ResponseMeta::default(),
                 );
-                if !push_item_or_truncate(&mut output, rejected.clone())? {
+                if !push_item_or_truncate(&mut output, rejected.clone(), payload.truncate)? {
                     break;
                 }
 
@@ -171,6 +247,7 @@ pub async fn run(
             if batch_project != &item_project {
                 let rejected = error_item(
                     id.clone(),
+                    ErrorCode::InvalidRequest,
                     format!(
                         "Batch project mismatch: batch uses '{}', item uses '{}'",
                         batch_project.display(),
@@ -179,7 +256,7 @@ pub async fn run(
                     Vec::new(),
                     ResponseMeta::default(),
                 );
-                if !push_item_or_truncate(&mut output, rejected.clone())? {
+                if !push_item_or_truncate(&mut output, rejected.clone(), payload.truncate)? {
                     break;
                 }
 
@@ -202,14 +279,23 @@ pub async fn run(
             .unwrap_or(Value::Null);
         ref_context["path"] = ref_context["project"].clone();
 
+        // Auto-indexing in the freshness gate counts against the item budget, and its own
+        // budget is capped by what is left of it.
+        let time_budget = item_time_budget(item.timeout_ms.or(payload.item_timeout_ms), deadline);
+        let started = Instant::now();
         let requires_index = freshness::action_requires_index(&item.action);
         if requires_index && gate.is_none() {
             let project_ctx = ctx.resolve_project(inferred_project.clone()).await?;
+            let mut options = ctx.request_options();
+            if let Some(budget) = time_budget {
+                options.max_reindex_ms = options.max_reindex_ms.min(budget.as_millis() as u64);
+            }
             match freshness::enforce_stale_policy(
                 &project_ctx.root,
                 &project_ctx.profile_name,
                 &project_ctx.profile,
-                &ctx.request_options(),
+                &freshness::extract_models(&resolved_payload),
+                &options,
             )
             .await?
             {
@@ -219,6 +305,7 @@ pub async fn run(
                     hints.extend(project_ctx.hints);
                     let rejected = error_item_with_context(
                         id.clone(),
+                        Some(block.code),
                         block.message,
                         hints,
                         ResponseMeta {
@@ -232,7 +319,7 @@ pub async fn run(
                         Some(item.action),
                         Some(&resolved_payload),
                     );
-                    if !push_item_or_truncate(&mut output, rejected.clone())? {
+                    if !push_item_or_truncate(&mut output, rejected.clone(), payload.truncate)? {
                         break;
                     }
 
@@ -262,13 +349,30 @@ pub async fn run(
         );
 
         let item_payload_for_meta = item_payload.clone();
-        let item_outcome = match services.route_item(item.action, item_payload, ctx).await {
-            Ok(mut outcome) => {
+        let routed = match time_budget {
+            // Cancelling `index` midway could leave a half-written index; let it finish instead.
+            Some(limit) if !matches!(item.action, CommandAction::Index) => {
+                let remaining = limit.saturating_sub(started.elapsed());
+                tokio::time::timeout(
+                    remaining,
+                    services.route_item(item.action, item_payload, ctx),
+                )
+                .await
+                .map_err(|_| limit)
+            }
+            _ => Ok(services.route_item(item.action, item_payload, ctx).await),
+        };
+        let mut item_outcome = match routed {
+            Err(limit) => timeout_item(id.clone(), limit, Value::Null, ResponseMeta::default()),
+            Ok(Ok(mut outcome)) => {
                 if matches!(item.action, CommandAction::Index) {
                     let project_ctx = ctx.resolve_project(inferred_project.clone()).await?;
-                    if let Ok(state) =
-                        freshness::gather_index_state(&project_ctx.root, &project_ctx.profile_name)
-                            .await
+                    if let Ok(state) = freshness::gather_index_state(
+                        &project_ctx.root,
+                        &project_ctx.profile_name,
+                        &project_ctx.profile,
+                    )
+                    .await
                     {
                         outcome.meta.index_state = Some(state);
                     }
@@ -281,24 +385,53 @@ pub async fn run(
                         if gate.index_updated {
                             outcome.meta.index_updated = Some(true);
                         }
+                        if gate.reindex_attempted {
+                            outcome.meta.reindex_attempted = Some(true);
+                            outcome.meta.reindex_deadline_hit = Some(gate.reindex_deadline_hit);
+                        }
                         outcome.hints.extend(gate.hints.clone());
                     }
                 }
 
+                let error = outcome.failure.map(|message| {
+                    let classification = classify_error(
+                        &message,
+                        None,
+                        Some(item.action),
+                        Some(&item_payload_for_meta),
+                    );
+                    ErrorEnvelope {
+                        code: classification.code.to_string(),
+                        message,
+                        details: None,
+                        hint: classification.hint,
+                        next_actions: classification.next_actions,
+                    }
+                });
+
                 BatchItemResult {
                     id: id.clone(),
-                    status: CommandStatus::Ok,
-                    message: None,
-                    error: None,
+                    status: if error.is_some() {
+                        CommandStatus::Error
+                    } else {
+                        CommandStatus::Ok
+                    },
+                    message: error.as_ref().map(|error| error.message.clone()),
+                    error,
                     hints: outcome.hints,
                     data: outcome.data,
+                    truncated: false,
                     meta: outcome.meta,
                 }
             }
-            Err(err) => {
+            Ok(Err(err)) => {
                 let message = format!("{err:#}");
-                let classification =
-                    classify_error(&message, Some(item.action), Some(&item_payload_for_meta));
+                let classification = classify_error(
+                    &message,
+                    typed_error_code(&err),
+                    Some(item.action),
+                    Some(&item_payload_for_meta),
+                );
                 let mut hints = classification.hints;
                 let mut meta = ResponseMeta::default();
                 if requires_index {
@@ -314,7 +447,7 @@ pub async fn run(
                     .hint
                     .or_else(|| hints.first().map(|h| h.text.clone()));
                 let error = ErrorEnvelope {
-                    code: classification.code,
+                    code: classification.code.to_string(),
                     message: message.clone(),
                     details: None,
                     hint,
@@ -328,18 +461,26 @@ pub async fn run(
                     error: Some(error),
                     hints,
                     data: Value::Null,
+                    truncated: false,
                     meta,
                 }
             }
         };
+        if let Some(limit) = time_budget {
+            if item_outcome.status == CommandStatus::Ok && started.elapsed() > limit {
+                item_outcome =
+                    timeout_item(id.clone(), limit, item_outcome.data, item_outcome.meta);
+            }
+        }
 
-        if !push_item_or_truncate(&mut output, item_outcome.clone())? {
+        if !push_item_or_truncate(&mut output, item_outcome.clone(), payload.truncate)? {
             break;
         }
 
         let status = match item_outcome.status {
             CommandStatus::Ok => "ok",
             CommandStatus::Error => "error",
+            CommandStatus::Skipped => "skipped",
         };
         ref_context["items"][id.clone()] = json!({
             "status": status,
@@ -357,7 +498,8 @@ pub async fn run(
         }
     }
 
-    trim_batch_output(&mut output)?;
+    output.meta = batch_meta(ctx, inferred_project, gate.as_ref()).await;
+    trim_batch_output(&mut output, payload.truncate)?;
 
     let mut outcome = CommandOutcome::from_value(output.clone())?;
     if output.budget.truncated {
@@ -372,6 +514,32 @@ pub async fn run(
     Ok(outcome)
 }
 
+/// Project, profile and index freshness for the whole batch, kept even when items are dropped.
+async fn batch_meta(
+    ctx: &CommandContext,
+    project: Option<PathBuf>,
+    gate: Option<&freshness::FreshnessGate>,
+) -> BatchMeta {
+    let Ok(project_ctx) = ctx.resolve_project(project).await else {
+        return BatchMeta::default();
+    };
+    let index_state = match gate {
+        Some(gate) => Some(gate.index_state.clone()),
+        None => freshness::gather_index_state(
+            &project_ctx.root,
+            &project_ctx.profile_name,
+            &project_ctx.profile,
+        )
+        .await
+        .ok(),
+    };
+    BatchMeta {
+        project: Some(project_ctx.root.display().to_string()),
+        profile: Some(project_ctx.profile_name),
+        index_state,
+    }
+}
+
 fn prepare_item_payload(
     payload: Value,
     project: Option<&PathBuf>,
@@ -403,18 +571,26 @@ fn prepare_item_payload(
     payload
 }
 
-fn push_item_or_truncate(output: &mut BatchOutput, item: BatchItemResult) -> Result<bool> {
+fn push_item_or_truncate(
+    output: &mut BatchOutput,
+    item: BatchItemResult,
+    truncate: BatchTruncate,
+) -> Result<bool> {
     output.items.push(item);
     let used = finalize_batch_budget(output)?;
 
     if used > output.budget.max_chars {
-        let rejected = output.items.pop().expect("just pushed");
         output.budget.truncated = true;
         output.budget.truncation = Some(BudgetTruncation::MaxChars);
+        if truncate == BatchTruncate::Content && truncate_last_item(output)? {
+            return Ok(false);
+        }
+        let rejected = output.items.pop().expect("just pushed");
 
         if output.items.is_empty() {
             output.items.push(error_item(
                 rejected.id,
+                ErrorCode::BudgetExceeded,
                 format!(
                     "Batch budget exceeded (max_chars={}). Reduce payload sizes or raise max_chars.",
                     output.budget.max_chars
@@ -425,7 +601,7 @@ fn push_item_or_truncate(output: &mut BatchOutput, item: BatchItemResult) -> Res
         } else {
             output.items.shrink_to_fit();
         }
-        trim_batch_output(output)?;
+        trim_batch_output(output, truncate)?;
         return Ok(false);
     }
 
@@ -433,35 +609,123 @@ fn push_item_or_truncate(output: &mut BatchOutput, item: BatchItemResult) -> Res
     Ok(true)
 }
 
+/// Shortens the last item's `data` until the batch fits; `false` if it cannot fit at all.
+fn truncate_last_item(output: &mut BatchOutput) -> Result<bool> {
+    loop {
+        let used = finalize_batch_budget(output)?;
+        if used <= output.budget.max_chars {
+            return Ok(true);
+        }
+        let Some(last) = output.items.last_mut() else {
+            return Ok(false);
+        };
+        if !truncate_largest_string(&mut last.data) {
+            return Ok(false);
+        }
+        last.truncated = true;
+    }
+}
+
 fn finalize_batch_budget(output: &mut BatchOutput) -> Result<usize> {
     finalize_used_chars(output, |inner, used| inner.budget.used_chars = used)
 }
 
+/// Placeholder for an item whose `when` predicate was false: keeps the id addressable.
+fn skipped_item(id: String) -> BatchItemResult {
+    BatchItemResult {
+        id,
+        status: CommandStatus::Skipped,
+        message: None,
+        error: None,
+        hints: Vec::new(),
+        data: Value::Null,
+        truncated: false,
+        meta: ResponseMeta::default(),
+    }
+}
+
+/// Placeholder for an item that was not started because the batch deadline had passed.
+fn deadline_skipped_item(id: String, deadline_ms: u64) -> BatchItemResult {
+    BatchItemResult {
+        message: Some(format!(
+            "Batch deadline (deadline_ms={deadline_ms}) passed before this item started"
+        )),
+        hints: vec![Hint {
+            kind: HintKind::Action,
+            text: "Rerun the remaining items in a new batch or raise deadline_ms.".to_string(),
+        }],
+        ..skipped_item(id)
+    }
+}
+
+/// Time an item may run: its own `timeout_ms` (or the batch default), capped by what is left
+/// of the batch deadline.
+fn item_time_budget(timeout_ms: Option<u64>, deadline: Option<(Instant, u64)>) -> Option<Duration> {
+    let remaining = deadline.map(|(at, _)| at.saturating_duration_since(Instant::now()));
+    match (timeout_ms.map(Duration::from_millis), remaining) {
+        (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+        (timeout, remaining) => timeout.or(remaining),
+    }
+}
+
+/// Error item for an item that ran past its time budget. `data`/`meta` are kept for items that
+/// were allowed to finish (`index`), so callers can still see what was done.
+fn timeout_item(id: String, limit: Duration, data: Value, meta: ResponseMeta) -> BatchItemResult {
+    let limit_ms = limit.as_millis();
+    let message = if data.is_null() {
+        format!("Item exceeded its time budget ({limit_ms} ms) and was cancelled")
+    } else {
+        format!("Item exceeded its time budget ({limit_ms} ms); it ran to completion to keep the index consistent")
+    };
+    let hint = "Raise timeout_ms/deadline_ms or run the item in its own batch.".to_string();
+    BatchItemResult {
+        id,
+        status: CommandStatus::Error,
+        message: Some(message.clone()),
+        error: Some(ErrorEnvelope {
+            code: ErrorCode::Timeout.to_string(),
+            message,
+            details: None,
+            hint: Some(hint.clone()),
+            next_actions: Vec::new(),
+        }),
+        hints: vec![Hint {
+            kind: HintKind::Action,
+            text: hint,
+        }],
+        data,
+        truncated: false,
+        meta,
+    }
+}
+
 fn error_item(
     id: String,
+    code: ErrorCode,
     message: String,
     hints: Vec<Hint>,
     meta: ResponseMeta,
 ) -> BatchItemResult {
-    error_item_with_context(id, message, hints, meta, None, None)
+    error_item_with_context(id, Some(code), message, hints, meta, None, None)
 }
 
 fn error_item_with_context(
     id: String,
+    code: Option<ErrorCode>,
     message: String,
     hints: Vec<Hint>,
     meta: ResponseMeta,
     action: Option<CommandAction>,
     payload: Option<&Value>,
 ) -> BatchItemResult {
-    let classification = classify_error(&message, action, payload);
+    let classification = classify_error(&message, code, action, payload);
     let mut out_hints = classification.hints;
     out_hints.extend(hints);
     let hint = classification
         .hint
         .or_else(|| out_hints.first().map(|h| h.text.clone()));
     let error = ErrorEnvelope {
-        code: classification.code,
+        code: classification.code.to_string(),
         message: message.clone(),
         details: None,
         hint,
@@ -474,11 +738,12 @@ fn error_item_with_context(
         error: Some(error),
         hints: out_hints,
         data: Value::Null,
+        truncated: false,
         meta,
     }
 }
 
-fn trim_batch_output(output: &mut BatchOutput) -> Result<()> {
+fn trim_batch_output(output: &mut BatchOutput, truncate: BatchTruncate) -> Result<()> {
     let max_chars = output.budget.max_chars;
     let used = enforce_max_chars(
         output,
@@ -488,12 +753,24 @@ fn trim_batch_output(output: &mut BatchOutput) -> Result<()> {
             inner.budget.truncated = true;
             inner.budget.truncation = Some(BudgetTruncation::MaxChars);
         },
+        // Batch meta outlives all but the last item; its index_state goes before that item.
         |inner| {
-            if !inner.items.is_empty() {
+            if truncate == BatchTruncate::Content {
+                if let Some(last) = inner.items.last_mut() {
+                    if truncate_largest_string(&mut last.data) {
+                        last.truncated = true;
+                        return true;
+                    }
+                }
+            }
+            if inner.items.len() > 1 {
                 inner.items.pop();
                 return true;
             }
-            false
+            if inner.meta.index_state.take().is_some() {
+                return true;
+            }
+            inner.items.pop().is_some()
         },
     )?;
     output.budget.used_chars = used;
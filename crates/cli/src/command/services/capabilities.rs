@@ -35,11 +35,11 @@ impl CapabilitiesService {
                 index_state: INDEX_STATE_SCHEMA_VERSION,
             },
             default_budgets: budgets,
-            start_route: ToolNextAction {
-                tool: "repo_onboarding_pack".to_string(),
-                args: start_args,
-                reason: "Start with a compact repo map + key docs (onboarding pack).".to_string(),
-            },
+            start_route: ToolNextAction::new(
+                "repo_onboarding_pack",
+                start_args,
+                "Start with a compact repo map + key docs (onboarding pack).",
+            ),
         };
 
         CommandOutcome::from_value(output)
@@ -1,6 +1,6 @@
 use super::search::{
     collect_chunks, dedup_results, format_basic_output, format_enriched_output, key_for,
-    overlap_ratio, parse_graph_language,
+    keys_missing_from, overlap_ratio, parse_graph_language,
 };
 use crate::command::context::{
     ensure_index_exists, index_path, load_store_mtime, unix_ms, CommandContext,
@@ -12,11 +12,12 @@ use crate::command::domain::{
 };
 use crate::command::infra::{CompareCacheAdapter, GraphCacheFactory, HealthPort};
 use crate::command::warm;
+use crate::graph_cache::graph_cache_stats;
 use anyhow::{Context as AnyhowContext, Result};
 use context_graph::GraphLanguage;
 use context_search::ContextSearch;
 use context_search::HybridSearch;
-use context_vector_store::VectorStore;
+use context_vector_store::{QueryEmbeddingCache, VectorStore};
 use log::warn;
 use serde_json::Value;
 use std::collections::HashSet;
@@ -162,7 +163,8 @@ impl CompareService {
 
         let store_baseline = VectorStore::load(&store_path)
             .await
-            .context("Failed to load vector store for baseline")?;
+            .context("Failed to load vector store for baseline")?
+            .with_query_embedding_cache(QueryEmbeddingCache::shared());
         let (baseline_chunks, _) = collect_chunks(&store_baseline);
         let mut baseline_search =
             HybridSearch::with_profile(store_baseline, baseline_chunks, profile.clone())
@@ -170,7 +172,8 @@ impl CompareService {
 
         let store_context = VectorStore::load(&store_path)
             .await
-            .context("Failed to load vector store for context")?;
+            .context("Failed to load vector store for context")?
+            .with_query_embedding_cache(QueryEmbeddingCache::shared());
         let (context_chunks, chunk_lookup) = collect_chunks(&store_context);
 
         let graph_cache = self.graph.for_root(&project_ctx.root);
@@ -265,6 +268,8 @@ impl CompareService {
                 overlap: baseline_keys.intersection(&context_keys).count(),
                 overlap_ratio,
                 context_related: context_related_total,
+                only_in_baseline: keys_missing_from(&baseline_outputs, &context_keys),
+                only_in_context: keys_missing_from(&context_outputs, &baseline_keys),
                 baseline: baseline_outputs,
                 context: context_outputs,
             });
@@ -335,6 +340,9 @@ impl CompareService {
             });
         }
         outcome.meta.graph_cache_size_bytes = graph_cache.size_bytes().await;
+        let graph_cache_stats = graph_cache_stats();
+        outcome.meta.graph_cache_hit_count = Some(graph_cache_stats.hits);
+        outcome.meta.graph_cache_miss_count = Some(graph_cache_stats.misses);
         outcome.hints.extend(project_ctx.hints);
         if invalidate_cache {
             outcome.hints.push(Hint {
@@ -5,7 +5,9 @@ use crate::command::domain::{
 };
 use crate::command::warm;
 use anyhow::{anyhow, Context as AnyhowContext, Result};
-use context_code_chunker::{Chunker, ChunkerConfig};
+use context_code_chunker::Chunker;
+use context_indexer::load_chunker_config;
+use context_protocol::{CodedError, ErrorCode};
 use std::collections::{HashMap, HashSet};
 use tokio::fs;
 
@@ -15,6 +17,7 @@ struct SymAgg {
     symbol_type: String,
     parent: Option<String>,
     line: usize,
+    exported: bool, // any defining chunk looks public/exported
 }
 
 #[derive(Default)]
@@ -33,7 +36,11 @@ impl ContextService {
         let file_path = project_ctx.root.join(&payload.file);
 
         if !file_path.exists() {
-            return Err(anyhow!("File not found: {}", file_path.display()));
+            return Err(CodedError::new(
+                ErrorCode::FileNotFound,
+                format!("File not found: {}", file_path.display()),
+            )
+            .into());
         }
 
         let content = fs::read_to_string(&file_path)
@@ -49,7 +56,7 @@ impl ContextService {
             ));
         }
 
-        let chunker = Chunker::new(ChunkerConfig::for_embeddings());
+        let chunker = Chunker::new(load_chunker_config(&project_ctx.root).await);
         let chunks = chunker
             .chunk_str(&content, Some(&payload.file))
             .context("Failed to chunk file")?;
@@ -184,14 +191,18 @@ impl ContextService {
         let file_path = project_ctx.root.join(&payload.file);
 
         if !file_path.exists() {
-            return Err(anyhow!("File not found: {}", file_path.display()));
+            return Err(CodedError::new(
+                ErrorCode::FileNotFound,
+                format!("File not found: {}", file_path.display()),
+            )
+            .into());
         }
 
         let content = fs::read_to_string(&file_path)
             .await
             .context("Failed to read file")?;
 
-        let chunker = Chunker::new(ChunkerConfig::for_embeddings());
+        let chunker = Chunker::new(load_chunker_config(&project_ctx.root).await);
         let chunks = chunker
             .chunk_str(&content, Some(&payload.file))
             .context("Failed to chunk file")?;
@@ -245,11 +256,17 @@ impl ContextService {
         crate::command::context::ensure_index_exists(&store_path)?;
         let store = context_vector_store::VectorStore::load(&store_path).await?;
 
+        let symbol_filter = SymbolFilter {
+            min_score: payload.min_symbol_score,
+            exported_only: payload.public_symbols_only,
+        };
+
         // Aggregate by top-level path up to depth
         let mut tree_files: HashMap<String, HashSet<String>> = HashMap::new();
         let mut tree_chunks: HashMap<String, usize> = HashMap::new();
         let mut tree_symbols: HashMap<String, HashMap<String, SymAgg>> = HashMap::new();
         let mut tree_lines: HashMap<String, usize> = HashMap::new();
+        let mut tree_languages: HashMap<String, HashMap<String, usize>> = HashMap::new();
         let mut total_lines: usize = 0;
         let mut all_files: HashSet<String> = HashSet::new();
         let mut file_lines: HashMap<String, usize> = HashMap::new();
@@ -270,6 +287,17 @@ impl ContextService {
                     .entry(key.clone())
                     .or_default()
                     .insert(chunk.chunk.file_path.clone());
+                let language = chunk
+                    .chunk
+                    .metadata
+                    .language
+                    .as_deref()
+                    .unwrap_or("unknown");
+                *tree_languages
+                    .entry(key.clone())
+                    .or_default()
+                    .entry(language.to_string())
+                    .or_insert(0) += 1;
                 all_files.insert(chunk.chunk.file_path.clone());
                 let lines = chunk.chunk.content.lines().count().max(1);
                 total_lines += lines;
@@ -288,6 +316,11 @@ impl ContextService {
                         .chunk_type
                         .map(|ct| ct.as_str().to_string())
                         .unwrap_or_else(|| "unknown".to_string());
+                    let exported = is_exported_symbol(
+                        chunk.chunk.metadata.language.as_deref(),
+                        sym,
+                        &chunk.chunk.content,
+                    );
                     let sym_map = tree_symbols.entry(key).or_default();
                     sym_map
                         .entry(sym.clone())
@@ -295,6 +328,7 @@ impl ContextService {
                             let entry = agg.files.entry(chunk.chunk.file_path.clone()).or_insert(0);
                             *entry = (*entry).max(lines);
                             agg.line = agg.line.min(chunk.chunk.start_line);
+                            agg.exported |= exported;
                         })
                         .or_insert(SymAgg {
                             files: {
@@ -305,6 +339,7 @@ impl ContextService {
                             symbol_type: sym_type,
                             parent: chunk.chunk.metadata.parent_scope.clone(),
                             line: chunk.chunk.start_line,
+                            exported,
                         });
                 }
             }
@@ -321,8 +356,9 @@ impl ContextService {
                 coverage_lines_pct: None,
                 top_symbols: tree_symbols
                     .get(&path)
-                    .map(|m| top_symbols(m, 5, &file_lines)),
+                    .map(|m| top_symbols(m, 5, &file_lines, &symbol_filter)),
                 avg_symbol_coverage: None,
+                languages: tree_languages.remove(&path).unwrap_or_default(),
             })
             .collect();
         nodes.sort_by(|a, b| b.chunks.cmp(&a.chunks));
@@ -410,12 +446,31 @@ impl ContextService {
     }
 }
 
+struct SymbolFilter {
+    min_score: Option<f32>,
+    exported_only: bool,
+}
+
+impl SymbolFilter {
+    fn keeps(&self, agg: &SymAgg, file_lines: &HashMap<String, usize>) -> bool {
+        if self.exported_only && !agg.exported {
+            return false;
+        }
+        self.min_score
+            .is_none_or(|min| symbol_score(agg, file_lines) >= min)
+    }
+}
+
 fn top_symbols(
     counts: &std::collections::HashMap<String, SymAgg>,
     limit: usize,
     file_lines: &HashMap<String, usize>,
+    filter: &SymbolFilter,
 ) -> Vec<SymbolInfo> {
-    let mut items: Vec<(&String, &SymAgg)> = counts.iter().collect();
+    let mut items: Vec<(&String, &SymAgg)> = counts
+        .iter()
+        .filter(|(_, agg)| filter.keeps(agg, file_lines))
+        .collect();
     items.sort_by(|a, b| {
         let a_score = symbol_score(a.1, file_lines);
         let b_score = symbol_score(b.1, file_lines);
@@ -437,6 +492,35 @@ fn top_symbols(
         .collect()
 }
 
+/// Chunk metadata carries no visibility, so infer it from the declaration line
+/// (`pub`, `export`, `public`) or the language's naming convention.
+fn is_exported_symbol(language: Option<&str>, name: &str, content: &str) -> bool {
+    let declaration = content
+        .lines()
+        .map(str::trim_start)
+        .find(|line| {
+            !line.is_empty()
+                && !line.starts_with("//")
+                && !line.starts_with("/*")
+                && !line.starts_with('*')
+                && !line.starts_with("#[")
+                && !line.starts_with('@')
+        })
+        .unwrap_or("");
+    match language.unwrap_or("") {
+        "rust" => declaration.starts_with("pub "),
+        "python" => !name.starts_with('_'),
+        "go" => name.chars().next().is_some_and(char::is_uppercase),
+        "javascript" | "typescript" => declaration.starts_with("export "),
+        "java" | "csharp" => declaration.starts_with("public "),
+        "kotlin" | "swift" => !["private ", "internal ", "fileprivate "]
+            .iter()
+            .any(|keyword| declaration.starts_with(keyword)),
+        // No visibility notion we can read: keep the symbol.
+        _ => true,
+    }
+}
+
 fn symbol_score(agg: &SymAgg, file_lines: &HashMap<String, usize>) -> f32 {
     let mut score = 0f32;
     for (file, sym_lines) in &agg.files {
@@ -1,25 +1,33 @@
+use super::eval_generate::generate_dataset;
 use super::search::format_basic_output;
 use crate::command::context::{
     ensure_index_exists, index_path, index_path_for_model, CommandContext,
 };
 use crate::command::domain::{
     parse_payload, CommandOutcome, EvalCacheMode, EvalCaseResult, EvalCompareCase,
-    EvalCompareOutput, EvalComparePayload, EvalCompareSummary, EvalDatasetMeta, EvalHit,
-    EvalOutput, EvalPayload, EvalRun, EvalRunSummary, EvalSummary, SearchOutput,
+    EvalCompareOutput, EvalComparePayload, EvalCompareSummary, EvalDatasetMeta,
+    EvalGeneratePayload, EvalHit, EvalOutput, EvalOutputFormat, EvalPathWeight, EvalPayload,
+    EvalPrefixDelta, EvalRun, EvalRunSummary, EvalSummary, EvalThresholds, Hint, HintKind,
+    SearchOutput, QUALITY_GATE_FAILED,
 };
+use crate::report;
 use anyhow::{anyhow, Context as AnyhowContext, Result};
 use context_search::{MultiModelHybridSearch, SearchProfile};
 use context_vector_store::{
     corpus_path_for_project_root, current_model_id, ChunkCorpus, QueryKind, VectorIndex,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 pub struct EvalService;
 
+/// Dataset files may use any schema version up to this one; version 2 added
+/// `forbidden_paths` and `breakdown_prefixes`.
+pub(super) const EVAL_DATASET_SCHEMA_VERSION: u32 = 2;
+
 impl EvalService {
     pub async fn run(&self, payload: Value, ctx: &CommandContext) -> Result<CommandOutcome> {
         let payload: EvalPayload = parse_payload(payload)?;
@@ -64,14 +72,34 @@ impl EvalService {
             );
         }
 
-        CommandOutcome::from_value(EvalOutput {
+        let output = EvalOutput {
             dataset: EvalDatasetMeta {
                 schema_version: dataset.schema_version,
                 name: dataset.name.clone(),
                 cases: dataset.cases.len(),
             },
             runs,
-        })
+        };
+        let written = write_eval_output(
+            &project_ctx.root,
+            payload.output_format.unwrap_or_default(),
+            payload.output_path.as_deref(),
+            &output,
+        )
+        .await?;
+        let failure = payload
+            .fail_under
+            .and_then(|thresholds| fail_under_failure(&output.runs, thresholds));
+
+        let mut outcome = CommandOutcome::from_value(output)?;
+        outcome.failure = failure;
+        if let Some(path) = written {
+            outcome.hints.push(Hint {
+                kind: HintKind::Info,
+                text: format!("Eval report written to {}", path.display()),
+            });
+        }
+        Ok(outcome)
     }
 
     pub async fn compare(&self, payload: Value, ctx: &CommandContext) -> Result<CommandOutcome> {
@@ -116,7 +144,11 @@ impl EvalService {
 
         let (summary, cases) = compare_runs(&run_a, &run_b)?;
 
-        CommandOutcome::from_value(EvalCompareOutput {
+        let failure = payload
+            .fail_if_b_worse_by
+            .and_then(|thresholds| b_worse_failure(&summary, thresholds));
+
+        let mut outcome = CommandOutcome::from_value(EvalCompareOutput {
             dataset: EvalDatasetMeta {
                 schema_version: dataset.schema_version,
                 name: dataset.name.clone(),
@@ -127,38 +159,62 @@ impl EvalService {
             b: run_summary(&run_b),
             summary,
             cases,
-        })
+        })?;
+        outcome.failure = failure;
+        Ok(outcome)
+    }
+
+    /// Mines the project's git history into a dataset file that `eval` can run.
+    pub async fn generate(&self, payload: Value, ctx: &CommandContext) -> Result<CommandOutcome> {
+        let payload: EvalGeneratePayload = parse_payload(payload)?;
+        let project_ctx = ctx.resolve_project(payload.path.clone()).await?;
+        let output = generate_dataset(&project_ctx.root, &payload).await?;
+        let mut outcome = CommandOutcome::from_value(output)?;
+        outcome.hints.extend(project_ctx.hints);
+        Ok(outcome)
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct EvalDatasetFile {
-    schema_version: u32,
-    #[serde(default)]
-    name: Option<String>,
-    cases: Vec<EvalDatasetCase>,
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct EvalDatasetFile {
+    pub(super) schema_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) name: Option<String>,
+    /// Path prefixes for `summary.by_prefix` (default: first segment of each expected path)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) breakdown_prefixes: Vec<String>,
+    pub(super) cases: Vec<EvalDatasetCase>,
 }
 
-#[derive(Debug, Deserialize)]
-struct EvalDatasetCase {
-    id: String,
-    query: String,
-    #[serde(default)]
-    expected_paths: Vec<String>,
-    #[serde(default)]
-    expected_symbols: Vec<String>,
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct EvalDatasetCase {
+    pub(super) id: String,
+    pub(super) query: String,
     #[serde(default)]
-    intent: Option<String>,
+    pub(super) expected_paths: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) expected_symbols: Vec<String>,
+    /// Files or directories that must not be returned, e.g. generated code
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) forbidden_paths: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) intent: Option<String>,
+    /// Commit a generated case was mined from, kept for auditing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) source_commit: Option<String>,
 }
 
 impl EvalDatasetFile {
     fn validate(&self) -> Result<()> {
-        if self.schema_version != 1 {
+        if !(1..=EVAL_DATASET_SCHEMA_VERSION).contains(&self.schema_version) {
             anyhow::bail!(
-                "Unsupported eval dataset schema_version {} (expected 1)",
+                "Unsupported eval dataset schema_version {} (expected 1 to {EVAL_DATASET_SCHEMA_VERSION})",
                 self.schema_version
             );
         }
+        if self.schema_version < 2 && !self.breakdown_prefixes.is_empty() {
+            anyhow::bail!("Eval dataset breakdown_prefixes requires schema_version 2");
+        }
         if self.cases.is_empty() {
             anyhow::bail!("Eval dataset must contain at least one case");
         }
@@ -179,6 +235,12 @@ impl EvalDatasetFile {
                     case.id
                 );
             }
+            if self.schema_version < 2 && !case.forbidden_paths.is_empty() {
+                anyhow::bail!(
+                    "Eval dataset case '{}' forbidden_paths requires schema_version 2",
+                    case.id
+                );
+            }
         }
         Ok(())
     }
@@ -369,11 +431,6 @@ async fn evaluate_run_warm(
     .context("Failed to create search engine")?;
 
     let mut case_results = Vec::with_capacity(dataset.cases.len());
-    let mut latencies = Vec::with_capacity(dataset.cases.len());
-    let mut bytes = Vec::with_capacity(dataset.cases.len());
-    let mut mrrs = Vec::with_capacity(dataset.cases.len());
-    let mut recalls = Vec::with_capacity(dataset.cases.len());
-    let mut overlaps = Vec::with_capacity(dataset.cases.len());
 
     for case in &dataset.cases {
         let start = Instant::now();
@@ -402,27 +459,23 @@ async fn evaluate_run_warm(
             .take(limit)
             .map(format_basic_output)
             .collect::<Vec<_>>();
-        let bytes_len = serde_json::to_vec(&SearchOutput {
-            query: case.query.clone(),
-            results: formatted,
-        })?
-        .len();
-
-        latencies.push(latency_ms);
-        bytes.push(bytes_len);
-        mrrs.push(metrics.mrr);
-        recalls.push(metrics.recall);
-        overlaps.push(metrics.overlap_ratio);
+        let bytes_len =
+            serde_json::to_vec(&SearchOutput::new(case.query.clone(), formatted))?.len();
 
         case_results.push(EvalCaseResult {
             id: case.id.clone(),
             query: case.query.clone(),
             expected_paths: case.expected_paths.clone(),
             expected_symbols: case.expected_symbols.clone(),
+            forbidden_paths: case.forbidden_paths.clone(),
             intent: case.intent.clone(),
             mrr: metrics.mrr,
             recall: metrics.recall,
             overlap_ratio: metrics.overlap_ratio,
+            forbidden_hits: metrics.forbidden_hits,
+            forbidden_rate: metrics.forbidden_rate,
+            ndcg: metrics.ndcg,
+            average_precision: metrics.average_precision,
             first_rank: metrics.first_rank,
             latency_ms,
             bytes: bytes_len,
@@ -432,18 +485,11 @@ async fn evaluate_run_warm(
 
     Ok(EvalRun {
         profile: profile_name.to_string(),
+        path_weights: eval_path_weights(profile),
         models,
         limit,
         cache_mode: EvalCacheMode::Warm,
-        summary: EvalSummary {
-            mean_mrr: mean_f64(&mrrs),
-            mean_recall: mean_f64(&recalls),
-            mean_overlap_ratio: mean_f64(&overlaps),
-            mean_latency_ms: mean_u64(&latencies),
-            p50_latency_ms: percentile_u64(&mut latencies, 0.50),
-            p95_latency_ms: percentile_u64(&mut latencies, 0.95),
-            mean_bytes: mean_usize(&bytes),
-        },
+        summary: summarize_cases(&case_results, &dataset.breakdown_prefixes),
         cases: case_results,
     })
 }
@@ -464,11 +510,6 @@ async fn evaluate_run_cold(
     drop(sources);
 
     let mut case_results = Vec::with_capacity(dataset.cases.len());
-    let mut latencies = Vec::with_capacity(dataset.cases.len());
-    let mut bytes = Vec::with_capacity(dataset.cases.len());
-    let mut mrrs = Vec::with_capacity(dataset.cases.len());
-    let mut recalls = Vec::with_capacity(dataset.cases.len());
-    let mut overlaps = Vec::with_capacity(dataset.cases.len());
 
     for case in &dataset.cases {
         let start = Instant::now();
@@ -505,27 +546,23 @@ async fn evaluate_run_cold(
             .take(limit)
             .map(format_basic_output)
             .collect::<Vec<_>>();
-        let bytes_len = serde_json::to_vec(&SearchOutput {
-            query: case.query.clone(),
-            results: formatted,
-        })?
-        .len();
-
-        latencies.push(latency_ms);
-        bytes.push(bytes_len);
-        mrrs.push(metrics.mrr);
-        recalls.push(metrics.recall);
-        overlaps.push(metrics.overlap_ratio);
+        let bytes_len =
+            serde_json::to_vec(&SearchOutput::new(case.query.clone(), formatted))?.len();
 
         case_results.push(EvalCaseResult {
             id: case.id.clone(),
             query: case.query.clone(),
             expected_paths: case.expected_paths.clone(),
             expected_symbols: case.expected_symbols.clone(),
+            forbidden_paths: case.forbidden_paths.clone(),
             intent: case.intent.clone(),
             mrr: metrics.mrr,
             recall: metrics.recall,
             overlap_ratio: metrics.overlap_ratio,
+            forbidden_hits: metrics.forbidden_hits,
+            forbidden_rate: metrics.forbidden_rate,
+            ndcg: metrics.ndcg,
+            average_precision: metrics.average_precision,
             first_rank: metrics.first_rank,
             latency_ms,
             bytes: bytes_len,
@@ -535,18 +572,11 @@ async fn evaluate_run_cold(
 
     Ok(EvalRun {
         profile: profile_name.to_string(),
+        path_weights: eval_path_weights(profile),
         models,
         limit,
         cache_mode: EvalCacheMode::Cold,
-        summary: EvalSummary {
-            mean_mrr: mean_f64(&mrrs),
-            mean_recall: mean_f64(&recalls),
-            mean_overlap_ratio: mean_f64(&overlaps),
-            mean_latency_ms: mean_u64(&latencies),
-            p50_latency_ms: percentile_u64(&mut latencies, 0.50),
-            p95_latency_ms: percentile_u64(&mut latencies, 0.95),
-            mean_bytes: mean_usize(&bytes),
-        },
+        summary: summarize_cases(&case_results, &dataset.breakdown_prefixes),
         cases: case_results,
     })
 }
@@ -555,6 +585,10 @@ struct CaseMetrics {
     mrr: f64,
     recall: f64,
     overlap_ratio: f64,
+    forbidden_hits: usize,
+    forbidden_rate: f64,
+    ndcg: f64,
+    average_precision: f64,
     first_rank: Option<usize>,
 }
 
@@ -573,11 +607,37 @@ fn score_case(
         return Err(anyhow!("Eval case '{}' has empty expected_paths", case.id));
     }
 
+    let expected_symbols: HashSet<&str> = case
+        .expected_symbols
+        .iter()
+        .map(|symbol| symbol.trim())
+        .filter(|symbol| !symbol.is_empty())
+        .collect();
+
     let mut found: HashSet<&str> = HashSet::new();
     let mut predicted: HashSet<&str> = HashSet::new();
     let mut first_rank: Option<usize> = None;
+    let mut credited_symbols: HashSet<&str> = HashSet::new();
+    let mut credited_paths: HashSet<&str> = HashSet::new();
+    let mut gains = Vec::with_capacity(limit);
+    let mut forbidden_hits = 0usize;
     for (idx, hit) in results.iter().take(limit).enumerate() {
         let file = hit.chunk.file_path.as_str();
+        if case
+            .forbidden_paths
+            .iter()
+            .any(|prefix| path_under(file, prefix))
+        {
+            forbidden_hits += 1;
+        }
+        gains.push(relevance_grade(
+            file,
+            hit.chunk.metadata.symbol_name.as_deref(),
+            &expected,
+            &expected_symbols,
+            &mut credited_paths,
+            &mut credited_symbols,
+        ));
         predicted.insert(file);
         if expected.contains(file) {
             found.insert(file);
@@ -591,31 +651,200 @@ fn score_case(
     let union_size = expected.union(&predicted).count().max(1);
     let overlap_ratio = found.len() as f64 / union_size as f64;
     let mrr = first_rank.map(|rank| 1.0 / (rank as f64)).unwrap_or(0.0);
+    let ideal = ideal_gains(expected_symbols.len(), expected.len(), limit);
+
+    let returned = gains.len();
+    let forbidden_rate = if returned == 0 {
+        0.0
+    } else {
+        forbidden_hits as f64 / returned as f64
+    };
 
     Ok(CaseMetrics {
         mrr,
         recall,
         overlap_ratio,
+        forbidden_hits,
+        forbidden_rate,
+        ndcg: ndcg(&gains, &ideal),
+        average_precision: average_precision(&gains, ideal.len()),
         first_rank,
     })
 }
 
+/// Whether `path` is `prefix` itself or lies under it as a directory.
+fn path_under(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim().trim_end_matches('/');
+    if prefix.is_empty() {
+        return false;
+    }
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Graded relevance of one hit. Each expected symbol/path earns credit once, so repeated
+/// chunks of the same file do not inflate the score: a new expected symbol is worth 2 (and
+/// uses up its file), a new expected path 1, anything else 0.
+fn relevance_grade<'a>(
+    file: &'a str,
+    symbol: Option<&'a str>,
+    expected_paths: &HashSet<&str>,
+    expected_symbols: &HashSet<&str>,
+    credited_paths: &mut HashSet<&'a str>,
+    credited_symbols: &mut HashSet<&'a str>,
+) -> u8 {
+    if let Some(symbol) = symbol.filter(|s| expected_symbols.contains(s)) {
+        if credited_symbols.insert(symbol) {
+            if expected_paths.contains(file) {
+                credited_paths.insert(file);
+            }
+            return 2;
+        }
+    }
+    if expected_paths.contains(file) && credited_paths.insert(file) {
+        return 1;
+    }
+    0
+}
+
+/// Best achievable grades within `limit`, assuming each expected symbol lives in its own
+/// expected path.
+fn ideal_gains(expected_symbols: usize, expected_paths: usize, limit: usize) -> Vec<u8> {
+    let paths_without_symbol = expected_paths.saturating_sub(expected_symbols);
+    std::iter::repeat_n(2, expected_symbols)
+        .chain(std::iter::repeat_n(1, paths_without_symbol))
+        .take(limit)
+        .collect()
+}
+
+fn discounted_gain(gains: &[u8]) -> f64 {
+    gains
+        .iter()
+        .enumerate()
+        .map(|(idx, &grade)| (2f64.powi(i32::from(grade)) - 1.0) / (idx as f64 + 2.0).log2())
+        .sum()
+}
+
+fn ndcg(gains: &[u8], ideal: &[u8]) -> f64 {
+    let ideal_dcg = discounted_gain(ideal);
+    if ideal_dcg <= 0.0 {
+        return 0.0;
+    }
+    (discounted_gain(gains) / ideal_dcg).min(1.0)
+}
+
+/// Precision at each relevant rank, averaged over the `relevant` items reachable within the
+/// cutoff (binary: any credited hit counts).
+fn average_precision(gains: &[u8], relevant: usize) -> f64 {
+    if relevant == 0 {
+        return 0.0;
+    }
+    let mut hits = 0usize;
+    let mut sum = 0.0;
+    for (idx, &grade) in gains.iter().enumerate() {
+        if grade > 0 {
+            hits += 1;
+            sum += hits as f64 / (idx + 1) as f64;
+        }
+    }
+    sum / relevant as f64
+}
+
+fn eval_path_weights(profile: &SearchProfile) -> Vec<EvalPathWeight> {
+    profile
+        .path_weights()
+        .iter()
+        .map(|rule| EvalPathWeight {
+            glob: rule.glob.clone(),
+            weight: rule.weight,
+        })
+        .collect()
+}
+
 fn run_summary(run: &EvalRun) -> EvalRunSummary {
     EvalRunSummary {
         profile: run.profile.clone(),
+        path_weights: run.path_weights.clone(),
         models: run.models.clone(),
         limit: run.limit,
         cache_mode: run.cache_mode,
-        summary: EvalSummary {
-            mean_mrr: run.summary.mean_mrr,
-            mean_recall: run.summary.mean_recall,
-            mean_overlap_ratio: run.summary.mean_overlap_ratio,
-            mean_latency_ms: run.summary.mean_latency_ms,
-            p50_latency_ms: run.summary.p50_latency_ms,
-            p95_latency_ms: run.summary.p95_latency_ms,
-            mean_bytes: run.summary.mean_bytes,
-        },
+        summary: run.summary.clone(),
+    }
+}
+
+/// Writes `out` in `format` to `path` (relative to `root`). Only the JSON format may go
+/// without a path, since the response data already carries it.
+async fn write_eval_output(
+    root: &Path,
+    format: EvalOutputFormat,
+    path: Option<&Path>,
+    out: &EvalOutput,
+) -> Result<Option<PathBuf>> {
+    let Some(path) = path else {
+        if format != EvalOutputFormat::Json {
+            anyhow::bail!("output_path is required when output_format is csv or junit");
+        }
+        return Ok(None);
+    };
+    let path = root.join(path);
+    let body = match format {
+        EvalOutputFormat::Json => serde_json::to_string_pretty(out)?,
+        EvalOutputFormat::Csv => report::render_eval_csv(out),
+        EvalOutputFormat::Junit => report::render_eval_junit(out),
+    };
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    tokio::fs::write(&path, body)
+        .await
+        .with_context(|| format!("Failed to write eval output {}", path.display()))?;
+    Ok(Some(path))
+}
+
+/// Describes every run whose summary falls below `thresholds`, or `None` when all pass.
+fn fail_under_failure(runs: &[EvalRun], thresholds: EvalThresholds) -> Option<String> {
+    let mut violations = Vec::new();
+    for run in runs {
+        for (metric, value, min) in [
+            ("mean_mrr", run.summary.mean_mrr, thresholds.mean_mrr),
+            (
+                "mean_recall",
+                run.summary.mean_recall,
+                thresholds.mean_recall,
+            ),
+        ] {
+            if let Some(min) = min.filter(|min| value < *min) {
+                violations.push(format!(
+                    "profile '{}' {metric} {value:.3} is below {min:.3}",
+                    run.profile
+                ));
+            }
+        }
     }
+    (!violations.is_empty()).then(|| format!("{QUALITY_GATE_FAILED}: {}", violations.join("; ")))
+}
+
+/// Describes every metric where B trails A by more than `thresholds` allow, or `None`.
+fn b_worse_failure(summary: &EvalCompareSummary, thresholds: EvalThresholds) -> Option<String> {
+    let mut violations = Vec::new();
+    for (metric, delta, allowed) in [
+        ("mean_mrr", summary.delta_mean_mrr, thresholds.mean_mrr),
+        (
+            "mean_recall",
+            summary.delta_mean_recall,
+            thresholds.mean_recall,
+        ),
+    ] {
+        if let Some(allowed) = allowed.filter(|allowed| -delta > *allowed) {
+            violations.push(format!(
+                "B {metric} trails A by {:.3}, more than {allowed:.3}",
+                -delta
+            ));
+        }
+    }
+    (!violations.is_empty()).then(|| format!("{QUALITY_GATE_FAILED}: {}", violations.join("; ")))
 }
 
 fn compare_runs(
@@ -674,6 +903,27 @@ fn compare_runs(
         });
     }
 
+    let p_value = sign_test_p_value(cases.iter().map(|case| case.delta_mrr));
+    let by_prefix = run_a
+        .summary
+        .by_prefix
+        .iter()
+        .filter_map(|(prefix, a)| {
+            let b = run_b.summary.by_prefix.get(prefix)?;
+            Some((
+                prefix.clone(),
+                EvalPrefixDelta {
+                    cases: a.cases,
+                    a_mean_mrr: a.mean_mrr,
+                    b_mean_mrr: b.mean_mrr,
+                    delta_mean_mrr: b.mean_mrr - a.mean_mrr,
+                    a_mean_recall: a.mean_recall,
+                    b_mean_recall: b.mean_recall,
+                    delta_mean_recall: b.mean_recall - a.mean_recall,
+                },
+            ))
+        })
+        .collect();
     let summary = EvalCompareSummary {
         delta_mean_mrr: run_b.summary.mean_mrr - run_a.summary.mean_mrr,
         delta_mean_recall: run_b.summary.mean_recall - run_a.summary.mean_recall,
@@ -686,11 +936,123 @@ fn compare_runs(
         a_wins,
         b_wins,
         ties,
+        p_value,
+        confidence: 1.0 - p_value,
+        delta_mean_forbidden_rate: run_b.summary.mean_forbidden_rate
+            - run_a.summary.mean_forbidden_rate,
+        by_prefix,
     };
 
     Ok((summary, cases))
 }
 
+/// Exact two-sided sign test: under "no difference" every non-zero paired delta is positive
+/// with probability 1/2. Returns 1.0 when no case changed.
+fn sign_test_p_value(deltas: impl IntoIterator<Item = f64>) -> f64 {
+    let (mut positive, mut negative) = (0u64, 0u64);
+    for delta in deltas {
+        if delta > f64::EPSILON {
+            positive += 1;
+        } else if delta < -f64::EPSILON {
+            negative += 1;
+        }
+    }
+    let n = positive + negative;
+    if n == 0 {
+        return 1.0;
+    }
+
+    // P(X <= k) for X ~ Binomial(n, 1/2), summed in log space so large n does not underflow
+    let k = positive.min(negative);
+    let ln_half_pow_n = n as f64 * 0.5f64.ln();
+    let mut ln_choose = 0.0f64;
+    let mut tail = 0.0f64;
+    for i in 0..=k {
+        if i > 0 {
+            ln_choose += ((n - i + 1) as f64).ln() - (i as f64).ln();
+        }
+        tail += (ln_choose + ln_half_pow_n).exp();
+    }
+    (2.0 * tail).min(1.0)
+}
+
+fn summarize_cases(cases: &[EvalCaseResult], breakdown_prefixes: &[String]) -> EvalSummary {
+    let mut summary = summarize_metrics(cases.iter());
+
+    let mut by_intent: BTreeMap<&str, Vec<&EvalCaseResult>> = BTreeMap::new();
+    for case in cases {
+        if let Some(intent) = case
+            .intent
+            .as_deref()
+            .map(str::trim)
+            .filter(|i| !i.is_empty())
+        {
+            by_intent.entry(intent).or_default().push(case);
+        }
+    }
+    summary.by_intent = by_intent
+        .into_iter()
+        .map(|(intent, group)| (intent.to_string(), summarize_metrics(group.into_iter())))
+        .collect();
+
+    let mut by_prefix: BTreeMap<String, Vec<&EvalCaseResult>> = BTreeMap::new();
+    for case in cases {
+        for prefix in case_prefixes(case, breakdown_prefixes) {
+            by_prefix.entry(prefix).or_default().push(case);
+        }
+    }
+    summary.by_prefix = by_prefix
+        .into_iter()
+        .map(|(prefix, group)| (prefix, summarize_metrics(group.into_iter())))
+        .collect();
+    summary
+}
+
+/// Prefix groups a case belongs to: each configured prefix one of its expected paths lies
+/// under, or with none configured, the first segment of each expected path.
+fn case_prefixes(case: &EvalCaseResult, breakdown_prefixes: &[String]) -> BTreeSet<String> {
+    let expected = case
+        .expected_paths
+        .iter()
+        .map(|path| path.trim())
+        .filter(|path| !path.is_empty());
+    if breakdown_prefixes.is_empty() {
+        return expected
+            .map(|path| path.split('/').next().unwrap_or(path).to_string())
+            .collect();
+    }
+    let expected: Vec<&str> = expected.collect();
+    breakdown_prefixes
+        .iter()
+        .filter(|prefix| expected.iter().any(|path| path_under(path, prefix)))
+        .map(|prefix| prefix.trim().trim_end_matches('/').to_string())
+        .collect()
+}
+
+fn summarize_metrics<'a>(cases: impl Iterator<Item = &'a EvalCaseResult>) -> EvalSummary {
+    let cases: Vec<&EvalCaseResult> = cases.collect();
+    let metric =
+        |f: fn(&EvalCaseResult) -> f64| mean_f64(&cases.iter().map(|c| f(c)).collect::<Vec<_>>());
+    let mut latencies: Vec<u64> = cases.iter().map(|c| c.latency_ms).collect();
+    let bytes: Vec<usize> = cases.iter().map(|c| c.bytes).collect();
+
+    EvalSummary {
+        cases: cases.len(),
+        mean_mrr: metric(|c| c.mrr),
+        mean_recall: metric(|c| c.recall),
+        mean_overlap_ratio: metric(|c| c.overlap_ratio),
+        mean_ndcg: metric(|c| c.ndcg),
+        map: metric(|c| c.average_precision),
+        mean_latency_ms: mean_u64(&latencies),
+        p50_latency_ms: percentile_u64(&mut latencies, 0.50),
+        p95_latency_ms: percentile_u64(&mut latencies, 0.95),
+        mean_bytes: mean_usize(&bytes),
+        mean_forbidden_rate: metric(|c| c.forbidden_rate),
+        by_intent: BTreeMap::new(),
+        by_prefix: BTreeMap::new(),
+    }
+}
+
 fn mean_f64(values: &[f64]) -> f64 {
     if values.is_empty() {
         return 0.0;
@@ -735,11 +1097,196 @@ mod tests {
         let dataset = EvalDatasetFile {
             schema_version: 1,
             name: None,
+            breakdown_prefixes: Vec::new(),
             cases: Vec::new(),
         };
         assert!(dataset.validate().is_err());
     }
 
+    #[test]
+    fn dataset_validation_keeps_v2_fields_out_of_v1_files() {
+        let parse = |raw: &str| {
+            serde_json::from_str::<EvalDatasetFile>(raw)
+                .unwrap()
+                .validate()
+        };
+        let case = r#"{"id":"c","query":"q","expected_paths":["src/a.rs"]"#;
+
+        assert!(parse(&format!(r#"{{"schema_version":1,"cases":[{case}}}]}}"#)).is_ok());
+        assert!(parse(&format!(
+            r#"{{"schema_version":2,"breakdown_prefixes":["src"],"cases":[{case},"forbidden_paths":["gen"]}}]}}"#
+        ))
+        .is_ok());
+
+        let err = parse(&format!(
+            r#"{{"schema_version":1,"cases":[{case},"forbidden_paths":["gen"]}}]}}"#
+        ))
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("requires schema_version 2"),
+            "{err}"
+        );
+        assert!(parse(&format!(
+            r#"{{"schema_version":1,"breakdown_prefixes":["src"],"cases":[{case}}}]}}"#
+        ))
+        .is_err());
+        assert!(parse(&format!(r#"{{"schema_version":3,"cases":[{case}}}]}}"#)).is_err());
+    }
+
+    #[test]
+    fn sign_test_flags_consistent_wins_and_ignores_noise() {
+        let b_wins: Vec<f64> = (0..12).map(|i| 0.25 + f64::from(i) * 0.01).collect();
+        let p = sign_test_p_value(b_wins);
+        assert!(p < 0.001, "p={p}");
+
+        let equal = vec![0.0; 12];
+        assert!((sign_test_p_value(equal) - 1.0).abs() < f64::EPSILON);
+
+        let mixed = vec![0.5, -0.5, 0.25, -0.25, 0.1, -0.1];
+        assert!(sign_test_p_value(mixed) > 0.9);
+    }
+
+    #[test]
+    fn ndcg_and_average_precision_match_hand_computed_ranking() {
+        // Ranking: [miss, symbol hit (2), path hit (1), duplicate of the symbol's file]
+        let case = EvalDatasetCase {
+            id: "c".to_string(),
+            query: "q".to_string(),
+            expected_paths: vec!["src/a.rs".to_string(), "src/b.rs".to_string()],
+            expected_symbols: vec!["Alpha".to_string()],
+            forbidden_paths: vec!["src/other.rs".to_string(), "src/a".to_string()],
+            intent: None,
+            source_commit: None,
+        };
+        let hit = |file: &str, symbol: Option<&str>| {
+            let metadata = context_code_chunker::ChunkMetadata {
+                symbol_name: symbol.map(str::to_string),
+                ..Default::default()
+            };
+            context_vector_store::SearchResult {
+                id: format!("{file}:1:2"),
+                score: 1.0,
+                chunk: context_code_chunker::CodeChunk::new(
+                    file.to_string(),
+                    1,
+                    2,
+                    String::new(),
+                    metadata,
+                ),
+                freshness: None,
+            }
+        };
+        let results = vec![
+            hit("src/other.rs", None),
+            hit("src/a.rs", Some("Alpha")),
+            hit("src/b.rs", None),
+            hit("src/a.rs", Some("helper")),
+        ];
+
+        let metrics = score_case(&case, &results, 4).unwrap();
+
+        // DCG  = 3/log2(3) + 1/log2(4) = 1.892789 + 0.5
+        // IDCG = 3/log2(2) + 1/log2(3) = 3.0 + 0.630930
+        let dcg = 3.0 / 3f64.log2() + 1.0 / 4f64.log2();
+        let idcg = 3.0 + 1.0 / 3f64.log2();
+        assert!((metrics.ndcg - dcg / idcg).abs() < 1e-9);
+        assert!((metrics.ndcg - 0.659_002).abs() < 1e-6);
+        // AP = (1/2 + 2/3) / 2
+        assert!((metrics.average_precision - 7.0 / 12.0).abs() < 1e-9);
+        assert!((metrics.mrr - 0.5).abs() < 1e-9);
+        // Only `src/other.rs` is forbidden: `src/a` is not a directory of `src/a.rs`
+        assert_eq!(metrics.forbidden_hits, 1);
+        assert!((metrics.forbidden_rate - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn summary_breaks_metrics_down_by_intent() {
+        let case = |id: &str, intent: Option<&str>, mrr: f64, latency_ms: u64| EvalCaseResult {
+            id: id.to_string(),
+            query: format!("query {id}"),
+            expected_paths: vec!["src/lib.rs".to_string()],
+            expected_symbols: Vec::new(),
+            forbidden_paths: Vec::new(),
+            intent: intent.map(str::to_string),
+            mrr,
+            recall: mrr,
+            overlap_ratio: mrr,
+            forbidden_hits: 0,
+            forbidden_rate: 0.0,
+            ndcg: mrr,
+            average_precision: mrr,
+            first_rank: None,
+            latency_ms,
+            bytes: 100,
+            hits: Vec::new(),
+        };
+        let cases = vec![
+            case("d1", Some("find-definition"), 1.0, 10),
+            case("d2", Some("find-definition"), 0.5, 20),
+            case("u1", Some("find-usage"), 0.0, 30),
+            case("x1", None, 1.0, 40),
+        ];
+
+        let summary = summarize_cases(&cases, &[]);
+
+        assert_eq!(summary.cases, 4);
+        assert!((summary.mean_mrr - 2.5 / 4.0).abs() < 1e-9);
+        assert!((summary.mean_latency_ms - 25.0).abs() < 1e-9);
+
+        let intents: Vec<&str> = summary.by_intent.keys().map(String::as_str).collect();
+        assert_eq!(intents, vec!["find-definition", "find-usage"]);
+        let definition = &summary.by_intent["find-definition"];
+        assert_eq!(definition.cases, 2);
+        assert!((definition.mean_mrr - 0.75).abs() < 1e-9);
+        assert!((definition.mean_latency_ms - 15.0).abs() < 1e-9);
+        assert!(definition.by_intent.is_empty());
+        let usage = &summary.by_intent["find-usage"];
+        assert_eq!(usage.cases, 1);
+        assert!(usage.mean_mrr.abs() < 1e-9);
+    }
+
+    #[test]
+    fn summary_breaks_metrics_down_by_path_prefix() {
+        let case = |id: &str, paths: &[&str], mrr: f64, forbidden_rate: f64| EvalCaseResult {
+            id: id.to_string(),
+            query: format!("query {id}"),
+            expected_paths: paths.iter().map(|path| path.to_string()).collect(),
+            expected_symbols: Vec::new(),
+            forbidden_paths: Vec::new(),
+            intent: None,
+            mrr,
+            recall: mrr,
+            overlap_ratio: mrr,
+            forbidden_hits: 0,
+            forbidden_rate,
+            ndcg: mrr,
+            average_precision: mrr,
+            first_rank: None,
+            latency_ms: 10,
+            bytes: 100,
+            hits: Vec::new(),
+        };
+        let cases = vec![
+            case("s1", &["crates/search/src/lib.rs"], 1.0, 0.0),
+            case("s2", &["crates/search/src/rank.rs"], 0.5, 0.0),
+            case("f1", &["frontend/app.ts", "README.md"], 0.0, 0.5),
+        ];
+
+        let summary = summarize_cases(&cases, &[]);
+        let prefixes: Vec<&str> = summary.by_prefix.keys().map(String::as_str).collect();
+        assert_eq!(prefixes, vec!["README.md", "crates", "frontend"]);
+        assert_eq!(summary.by_prefix["crates"].cases, 2);
+        assert!((summary.by_prefix["crates"].mean_mrr - 0.75).abs() < 1e-9);
+        assert!((summary.mean_forbidden_rate - 0.5 / 3.0).abs() < 1e-9);
+        assert!((summary.by_prefix["frontend"].mean_forbidden_rate - 0.5).abs() < 1e-9);
+
+        let configured = vec!["crates/search/".to_string(), "crates/graph".to_string()];
+        let summary = summarize_cases(&cases, &configured);
+        let prefixes: Vec<&str> = summary.by_prefix.keys().map(String::as_str).collect();
+        assert_eq!(prefixes, vec!["crates/search"]);
+        assert_eq!(summary.by_prefix["crates/search"].cases, 2);
+    }
+
     #[test]
     fn percentile_uses_nearest_rank() {
         let mut values = vec![10, 20, 30, 40, 50];
@@ -747,4 +1294,66 @@ mod tests {
         let mut values = vec![10, 20, 30, 40, 50];
         assert_eq!(percentile_u64(&mut values, 0.95), 50);
     }
+
+    #[test]
+    fn quality_gates_name_only_the_breached_metrics() {
+        let summary = EvalSummary {
+            cases: 1,
+            mean_mrr: 0.4,
+            mean_recall: 0.9,
+            mean_overlap_ratio: 0.0,
+            mean_ndcg: 0.0,
+            map: 0.0,
+            mean_latency_ms: 0.0,
+            p50_latency_ms: 0,
+            p95_latency_ms: 0,
+            mean_bytes: 0.0,
+            mean_forbidden_rate: 0.0,
+            by_intent: BTreeMap::new(),
+            by_prefix: BTreeMap::new(),
+        };
+        let run = EvalRun {
+            profile: "quality".to_string(),
+            path_weights: Vec::new(),
+            models: Vec::new(),
+            limit: 10,
+            cache_mode: EvalCacheMode::Warm,
+            summary,
+            cases: Vec::new(),
+        };
+        let thresholds = EvalThresholds {
+            mean_mrr: Some(0.5),
+            mean_recall: Some(0.8),
+        };
+        let failure = fail_under_failure(std::slice::from_ref(&run), thresholds).expect("mrr");
+        assert_eq!(
+            failure,
+            "Quality gate failed: profile 'quality' mean_mrr 0.400 is below 0.500"
+        );
+        assert!(fail_under_failure(&[run], EvalThresholds::default()).is_none());
+
+        let compare = EvalCompareSummary {
+            delta_mean_mrr: -0.02,
+            delta_mean_recall: -0.2,
+            delta_mean_overlap_ratio: 0.0,
+            delta_mean_latency_ms: 0.0,
+            delta_p95_latency_ms: 0,
+            delta_mean_bytes: 0.0,
+            a_wins: 1,
+            b_wins: 0,
+            ties: 0,
+            p_value: 1.0,
+            confidence: 0.0,
+            delta_mean_forbidden_rate: 0.0,
+            by_prefix: Default::default(),
+        };
+        let thresholds = EvalThresholds {
+            mean_mrr: Some(0.05),
+            mean_recall: Some(0.1),
+        };
+        assert_eq!(
+            b_worse_failure(&compare, thresholds).expect("recall"),
+            "Quality gate failed: B mean_recall trails A by 0.200, more than 0.100"
+        );
+    }
 }
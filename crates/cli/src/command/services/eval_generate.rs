@@ -0,0 +1,434 @@
+use super::eval::{EvalDatasetCase, EvalDatasetFile, EVAL_DATASET_SCHEMA_VERSION};
+use crate::command::domain::{EvalGenerateOutput, EvalGeneratePayload, EvalGenerateSkipped};
+use anyhow::{Context as AnyhowContext, Result};
+use context_code_chunker::Chunker;
+use context_indexer::load_chunker_config;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_OUTPUT_PATH: &str = ".context-finder/eval/git_history.json";
+const DEFAULT_MAX_CASES: usize = 50;
+/// Commits examined per requested case, since many are filtered out.
+const COMMITS_PER_CASE: usize = 10;
+const MAX_COMMITS_SCANNED: usize = 2000;
+const MAX_FILES_CHANGED: usize = 40;
+const MAX_LINES_CHANGED: usize = 4000;
+/// Line-for-line rewrites across at least this many files look like formatting.
+const FORMAT_MIN_FILES: usize = 3;
+const MIN_QUERY_WORDS: usize = 3;
+/// Files per commit whose changed hunks are mapped to symbols.
+const MAX_SYMBOL_FILES: usize = 8;
+const MAX_SYMBOLS_PER_CASE: usize = 8;
+
+/// One `git log --numstat` record.
+#[derive(Debug, PartialEq)]
+struct CommitRecord {
+    hash: String,
+    subject: String,
+    files: Vec<FileStat>,
+}
+
+#[derive(Debug, PartialEq)]
+struct FileStat {
+    path: String,
+    /// `None` for binary files
+    lines: Option<(usize, usize)>,
+    renamed: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Skip {
+    TooFewFiles,
+    TooLarge,
+    RenameOrFormat,
+}
+
+/// Writes a dataset with one case per recent commit that passes the payload filters and the
+/// diff-size heuristics, newest first.
+pub(super) async fn generate_dataset(
+    root: &Path,
+    payload: &EvalGeneratePayload,
+) -> Result<EvalGenerateOutput> {
+    let max_cases = payload.max_cases.unwrap_or(DEFAULT_MAX_CASES).max(1);
+    let min_files = payload.min_files_changed.unwrap_or(1).max(1);
+    let excludes = payload
+        .exclude_path_globs
+        .iter()
+        .map(|raw| glob::Pattern::new(raw).with_context(|| format!("Invalid glob '{raw}'")))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut log_args = vec![
+        "log".to_string(),
+        "--no-merges".to_string(),
+        "-M".to_string(),
+        "--numstat".to_string(),
+        "--format=%x1e%H%x1f%s".to_string(),
+        format!(
+            "--max-count={}",
+            (max_cases * COMMITS_PER_CASE).min(MAX_COMMITS_SCANNED)
+        ),
+    ];
+    if let Some(since) = payload.since.as_deref().map(str::trim) {
+        if !since.is_empty() {
+            log_args.push(format!("--since={since}"));
+        }
+    }
+    let log = git(root, &log_args).await?;
+    let commits = parse_log(&log);
+
+    let mut skipped = EvalGenerateSkipped::default();
+    let mut cases = Vec::new();
+    for commit in &commits {
+        if cases.len() >= max_cases {
+            break;
+        }
+        match classify(commit, min_files) {
+            Some(Skip::TooFewFiles) => {
+                skipped.too_few_files += 1;
+                continue;
+            }
+            Some(Skip::TooLarge) => {
+                skipped.too_large += 1;
+                continue;
+            }
+            Some(Skip::RenameOrFormat) => {
+                skipped.rename_or_format += 1;
+                continue;
+            }
+            None => {}
+        }
+
+        let paths: Vec<String> = commit
+            .files
+            .iter()
+            .filter(|file| file.lines.is_some())
+            .map(|file| file.path.clone())
+            .filter(|path| !excludes.iter().any(|glob| glob.matches(path)))
+            .filter(|path| root.join(path).is_file())
+            .collect();
+        if paths.is_empty() {
+            skipped.no_paths += 1;
+            continue;
+        }
+        let Some(query) = query_from_subject(&commit.subject) else {
+            skipped.vague_subject += 1;
+            continue;
+        };
+
+        let expected_symbols = touched_symbols(root, &commit.hash, &paths).await;
+        cases.push(EvalDatasetCase {
+            id: format!("git-{}", &commit.hash[..commit.hash.len().min(12)]),
+            query,
+            expected_paths: paths,
+            expected_symbols,
+            forbidden_paths: Vec::new(),
+            intent: None,
+            source_commit: Some(commit.hash.clone()),
+        });
+    }
+
+    let commits_scanned = cases.len()
+        + skipped.too_few_files
+        + skipped.too_large
+        + skipped.rename_or_format
+        + skipped.no_paths
+        + skipped.vague_subject;
+    anyhow::ensure!(
+        !cases.is_empty(),
+        "No usable commits among the {commits_scanned} scanned (skipped: {skipped:?}); widen `since` or relax `min_files_changed`/`exclude_path_globs`"
+    );
+
+    let output_path = root.join(
+        payload
+            .output_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT_PATH)),
+    );
+    let dataset = EvalDatasetFile {
+        schema_version: EVAL_DATASET_SCHEMA_VERSION,
+        name: Some("git-history".to_string()),
+        breakdown_prefixes: Vec::new(),
+        cases,
+    };
+    if let Some(parent) = output_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    tokio::fs::write(&output_path, serde_json::to_string_pretty(&dataset)?)
+        .await
+        .with_context(|| format!("Failed to write eval dataset {}", output_path.display()))?;
+
+    Ok(EvalGenerateOutput {
+        output_path: output_path.to_string_lossy().into_owned(),
+        cases: dataset.cases.len(),
+        commits_scanned,
+        skipped,
+    })
+}
+
+async fn git(root: &Path, args: &[String]) -> Result<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "core.quotePath=false"])
+        .args(args)
+        .output()
+        .await
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().map_or("", String::as_str),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn parse_log(log: &str) -> Vec<CommitRecord> {
+    log.split('\u{1e}')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let (hash, subject) = lines.next()?.split_once('\u{1f}')?;
+            let files = lines
+                .filter_map(|line| {
+                    let mut parts = line.splitn(3, '\t');
+                    let added = parts.next()?;
+                    let deleted = parts.next()?;
+                    let raw_path = parts.next()?;
+                    let lines = added.parse().ok().zip(deleted.parse().ok());
+                    let (path, renamed) = match renamed_to(raw_path) {
+                        Some(path) => (path, true),
+                        None => (raw_path.to_string(), false),
+                    };
+                    Some(FileStat {
+                        path,
+                        lines,
+                        renamed,
+                    })
+                })
+                .collect();
+            Some(CommitRecord {
+                hash: hash.trim().to_string(),
+                subject: subject.trim().to_string(),
+                files,
+            })
+        })
+        .collect()
+}
+
+/// New path of a `--numstat -M` rename entry: `old => new` or `dir/{old => new}/file`.
+fn renamed_to(raw: &str) -> Option<String> {
+    if let (Some(open), Some(close)) = (raw.find('{'), raw.find('}')) {
+        let (_, new) = raw[open + 1..close].split_once(" => ")?;
+        let path = format!("{}{}{}", &raw[..open], new, &raw[close + 1..]);
+        return Some(path.replace("//", "/"));
+    }
+    raw.split_once(" => ").map(|(_, new)| new.to_string())
+}
+
+fn classify(commit: &CommitRecord, min_files: usize) -> Option<Skip> {
+    let files = commit.files.len();
+    if files < min_files {
+        return Some(Skip::TooFewFiles);
+    }
+    let changed: usize = commit
+        .files
+        .iter()
+        .filter_map(|file| file.lines)
+        .map(|(added, deleted)| added + deleted)
+        .sum();
+    if files > MAX_FILES_CHANGED || changed > MAX_LINES_CHANGED {
+        return Some(Skip::TooLarge);
+    }
+    let renamed = commit.files.iter().filter(|file| file.renamed).count();
+    let line_for_line = files >= FORMAT_MIN_FILES
+        && commit
+            .files
+            .iter()
+            .all(|file| file.lines.is_some_and(|(added, deleted)| added == deleted));
+    if renamed * 2 > files || line_for_line {
+        return Some(Skip::RenameOrFormat);
+    }
+    None
+}
+
+/// Commit subject without leading `[tag]`s or a conventional-commit type, if enough words are
+/// left to make a query.
+fn query_from_subject(subject: &str) -> Option<String> {
+    let mut query = subject.trim();
+    while let Some(rest) = query.strip_prefix('[') {
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        query = rest[end + 1..].trim_start();
+    }
+    if let Some((kind, rest)) = query.split_once(": ") {
+        let is_type = kind.starts_with(|c: char| c.is_ascii_lowercase())
+            && kind.len() <= 24
+            && !kind.contains(' ')
+            && kind.chars().all(|c| {
+                c.is_ascii_alphanumeric() || matches!(c, '(' | ')' | '-' | '_' | '!' | '/')
+            });
+        if is_type {
+            query = rest.trim_start();
+        }
+    }
+    (query.split_whitespace().count() >= MIN_QUERY_WORDS).then(|| query.to_string())
+}
+
+/// Names of the symbols whose chunks (in the commit's version of each file) overlap the lines
+/// the commit changed. Best effort: files that cannot be read or chunked add nothing.
+async fn touched_symbols(root: &Path, hash: &str, paths: &[String]) -> Vec<String> {
+    let paths = &paths[..paths.len().min(MAX_SYMBOL_FILES)];
+    let mut args = vec![
+        "show".to_string(),
+        "--unified=0".to_string(),
+        "--format=".to_string(),
+        "--no-color".to_string(),
+        "--no-ext-diff".to_string(),
+        "-M".to_string(),
+        hash.to_string(),
+        "--".to_string(),
+    ];
+    args.extend(paths.iter().cloned());
+    let Ok(diff) = git(root, &args).await else {
+        return Vec::new();
+    };
+
+    let chunker = Chunker::new(load_chunker_config(root).await);
+    let mut symbols: Vec<String> = Vec::new();
+    for (path, ranges) in changed_line_ranges(&diff) {
+        let Ok(content) = git(root, &["show".to_string(), format!("{hash}:{path}")]).await else {
+            continue;
+        };
+        let Ok(chunks) = chunker.chunk_str(&content, Some(&path)) else {
+            continue;
+        };
+        for chunk in chunks {
+            let Some(symbol) = chunk.metadata.symbol_name.as_deref() else {
+                continue;
+            };
+            let overlaps = ranges
+                .iter()
+                .any(|&(start, end)| start <= chunk.end_line && chunk.start_line <= end);
+            if overlaps && !symbols.iter().any(|s| s == symbol) {
+                symbols.push(symbol.to_string());
+                if symbols.len() >= MAX_SYMBOLS_PER_CASE {
+                    return symbols;
+                }
+            }
+        }
+    }
+    symbols
+}
+
+/// New-side line ranges per file from `--unified=0` output; pure deletions are anchored on the
+/// line they followed.
+fn changed_line_ranges(diff: &str) -> BTreeMap<String, Vec<(usize, usize)>> {
+    let mut ranges: BTreeMap<String, Vec<(usize, usize)>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("+++ ") {
+            current = rest.strip_prefix("b/").map(str::to_string);
+            continue;
+        }
+        let (Some(file), Some(hunk)) = (current.as_ref(), line.strip_prefix("@@ ")) else {
+            continue;
+        };
+        let Some(new_side) = hunk
+            .split_whitespace()
+            .find_map(|part| part.strip_prefix('+'))
+        else {
+            continue;
+        };
+        let (start, count) = match new_side.split_once(',') {
+            Some((start, count)) => (start.parse::<usize>().ok(), count.parse::<usize>().ok()),
+            None => (new_side.parse::<usize>().ok(), Some(1)),
+        };
+        let (Some(start), Some(count)) = (start, count) else {
+            continue;
+        };
+        let start = start.max(1);
+        let end = start + count.max(1) - 1;
+        ranges.entry(file.clone()).or_default().push((start, end));
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(path: &str, added: usize, deleted: usize) -> FileStat {
+        FileStat {
+            path: path.to_string(),
+            lines: Some((added, deleted)),
+            renamed: false,
+        }
+    }
+
+    #[test]
+    fn numstat_log_parses_renames_and_binaries() {
+        let log = "\u{1e}abc123\u{1f}Add parser\n\n3\t1\tsrc/parser.rs\n-\t-\tassets/logo.png\n0\t0\tsrc/{old => new}/mod.rs\n\u{1e}def456\u{1f}Move docs\n\n0\t0\tREADME.md => docs/README.md\n";
+        let commits = parse_log(log);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "abc123");
+        assert_eq!(commits[0].subject, "Add parser");
+        assert_eq!(commits[0].files[0], stat("src/parser.rs", 3, 1));
+        assert_eq!(commits[0].files[1].lines, None);
+        assert_eq!(commits[0].files[2].path, "src/new/mod.rs");
+        assert!(commits[0].files[2].renamed);
+        assert_eq!(commits[1].files[0].path, "docs/README.md");
+    }
+
+    #[test]
+    fn diff_size_heuristics_skip_renames_and_formatting() {
+        let commit = |files: Vec<FileStat>| CommitRecord {
+            hash: "h".to_string(),
+            subject: "s".to_string(),
+            files,
+        };
+        let focused = commit(vec![stat("src/a.rs", 12, 3), stat("src/b.rs", 2, 2)]);
+        assert_eq!(classify(&focused, 1), None);
+        assert_eq!(classify(&focused, 3), Some(Skip::TooFewFiles));
+
+        let formatting = commit(vec![
+            stat("src/a.rs", 4, 4),
+            stat("src/b.rs", 9, 9),
+            stat("src/c.rs", 1, 1),
+        ]);
+        assert_eq!(classify(&formatting, 1), Some(Skip::RenameOrFormat));
+
+        let mut renamed = stat("src/new.rs", 0, 0);
+        renamed.renamed = true;
+        assert_eq!(
+            classify(&commit(vec![renamed]), 1),
+            Some(Skip::RenameOrFormat)
+        );
+
+        let huge = commit(vec![stat("vendor/lib.js", MAX_LINES_CHANGED, 1)]);
+        assert_eq!(classify(&huge, 1), Some(Skip::TooLarge));
+    }
+
+    #[test]
+    fn subjects_lose_tags_and_commit_types() {
+        assert_eq!(
+            query_from_subject("[ABC-12] feat(search): rank exact symbol hits first").as_deref(),
+            Some("rank exact symbol hits first")
+        );
+        assert_eq!(
+            query_from_subject("Note: cache graph builds").as_deref(),
+            Some("Note: cache graph builds")
+        );
+        assert_eq!(query_from_subject("fix: typo"), None);
+    }
+
+    #[test]
+    fn unified_zero_hunks_become_new_side_ranges() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n@@ -3,0 +4,2 @@ fn a()\n+x\n+y\n@@ -10 +12 @@\n-z\n+w\n@@ -20,2 +21,0 @@\n-q\n-r\n";
+        let ranges = changed_line_ranges(diff);
+        assert_eq!(ranges["src/a.rs"], vec![(4, 5), (12, 12), (21, 21)]);
+    }
+}
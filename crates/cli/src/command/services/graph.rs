@@ -0,0 +1,196 @@
+use super::search::{load_graph_engine, parse_graph_language, GraphEngine};
+use crate::command::context::{
+    graph_language_from_config, unix_ms, CommandContext, ProjectContext,
+};
+use crate::command::domain::{
+    parse_payload, CommandOutcome, ExplainPayload, Hint, HintKind, ImpactPayload, OverviewPayload,
+    TracePayload,
+};
+use crate::command::infra::{GraphCacheFactory, HealthPort};
+use crate::graph_cache::graph_cache_stats;
+use anyhow::{anyhow, Result};
+use context_graph::analysis::{self, DEFAULT_DOC_MAX_CHARS};
+use context_graph::CodeGraph;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::PathBuf;
+
+const DEFAULT_IMPACT_DEPTH: usize = 2;
+const DEFAULT_TRACE_PATHS: usize = 1;
+
+/// Symbol analyses over the code graph; responses share their JSON shape with the MCP tools
+/// of the same names.
+pub struct GraphService {
+    graph: GraphCacheFactory,
+    health: HealthPort,
+}
+
+impl GraphService {
+    pub fn new(graph: GraphCacheFactory, health: HealthPort) -> Self {
+        Self { graph, health }
+    }
+
+    pub async fn impact(&self, payload: Value, ctx: &CommandContext) -> Result<CommandOutcome> {
+        let payload: ImpactPayload = parse_payload(payload)?;
+        let symbol = require_symbol(&payload.symbol, "symbol")?;
+        let depth = payload.depth.unwrap_or(DEFAULT_IMPACT_DEPTH).clamp(1, 3);
+        self.run(
+            ctx,
+            payload.project,
+            payload.language,
+            payload.reuse_graph,
+            |engine, _| {
+                let chunks = engine.context_search.hybrid().chunks();
+                let graph = engine.context_search.assembler().map(|a| a.graph());
+                Ok(analysis::impact(graph, chunks, symbol, depth))
+            },
+        )
+        .await
+    }
+
+    pub async fn trace(&self, payload: Value, ctx: &CommandContext) -> Result<CommandOutcome> {
+        let payload: TracePayload = parse_payload(payload)?;
+        let from = require_symbol(&payload.from, "from")?;
+        let to = require_symbol(&payload.to, "to")?;
+        let via = payload
+            .via
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty());
+        let max_paths = payload.max_paths.unwrap_or(DEFAULT_TRACE_PATHS);
+        self.run(
+            ctx,
+            payload.project,
+            payload.language,
+            payload.reuse_graph,
+            |engine, _| {
+                Ok(analysis::trace(
+                    graph_of(engine)?,
+                    from,
+                    to,
+                    via,
+                    max_paths,
+                )?)
+            },
+        )
+        .await
+    }
+
+    pub async fn explain(&self, payload: Value, ctx: &CommandContext) -> Result<CommandOutcome> {
+        let payload: ExplainPayload = parse_payload(payload)?;
+        let symbol = require_symbol(&payload.symbol, "symbol")?;
+        let doc_max_chars = payload.doc_max_chars.unwrap_or(DEFAULT_DOC_MAX_CHARS);
+        self.run(
+            ctx,
+            payload.project,
+            payload.language,
+            payload.reuse_graph,
+            |engine, _| Ok(analysis::explain(graph_of(engine)?, symbol, doc_max_chars)?),
+        )
+        .await
+    }
+
+    pub async fn overview(&self, payload: Value, ctx: &CommandContext) -> Result<CommandOutcome> {
+        let payload: OverviewPayload = parse_payload(payload)?;
+        let module_depth = payload.module_depth;
+        self.run(
+            ctx,
+            payload.project,
+            payload.language,
+            payload.reuse_graph,
+            |engine, project_ctx| {
+                let name = project_ctx.root.file_name().map_or_else(
+                    || "unknown".to_string(),
+                    |s| s.to_string_lossy().to_string(),
+                );
+                Ok(analysis::overview(
+                    graph_of(engine)?,
+                    name,
+                    engine.context_search.hybrid().chunks(),
+                    module_depth,
+                ))
+            },
+        )
+        .await
+    }
+
+    /// Loads the project's graph, runs `analyze` on it and wraps the report with graph and
+    /// index metadata.
+    async fn run<T: Serialize>(
+        &self,
+        ctx: &CommandContext,
+        project: Option<PathBuf>,
+        language: Option<String>,
+        reuse_graph: Option<bool>,
+        analyze: impl FnOnce(&GraphEngine, &ProjectContext) -> Result<T>,
+    ) -> Result<CommandOutcome> {
+        let project_ctx = ctx.resolve_project(project).await?;
+        let language = language
+            .or_else(|| graph_language_from_config(&project_ctx.config))
+            .as_deref()
+            .map(parse_graph_language)
+            .transpose()?;
+        let reuse_graph = reuse_graph.unwrap_or(true);
+
+        let engine = load_graph_engine(
+            &self.graph,
+            &project_ctx.root,
+            &project_ctx.profile,
+            language,
+            reuse_graph,
+        )
+        .await?;
+        let report = analyze(&engine, &project_ctx)?;
+
+        let mut outcome = CommandOutcome::from_value(report)?;
+        outcome.meta.config_path = project_ctx.config_path.clone();
+        outcome.meta.profile = Some(project_ctx.profile_name.clone());
+        outcome.meta.profile_path = project_ctx.profile_path.clone();
+        outcome.meta.index_updated = Some(false);
+        outcome.meta.index_mtime_ms = Some(unix_ms(engine.store_mtime));
+        outcome.meta.index_size_bytes = engine.index_size_bytes;
+        outcome.meta.timing_load_index_ms = Some(engine.timing_load_index_ms);
+        outcome.meta.timing_graph_ms = Some(engine.timing_graph_ms);
+        outcome.meta.graph_cache = Some(engine.graph_cache_used);
+        outcome.meta.graph_cache_size_bytes = engine.graph_cache_size_bytes;
+        if let Some((nodes, edges)) = engine.context_search.graph_stats() {
+            outcome.meta.graph_nodes = Some(nodes);
+            outcome.meta.graph_edges = Some(edges);
+        }
+        let graph_cache_stats = graph_cache_stats();
+        outcome.meta.graph_cache_hit_count = Some(graph_cache_stats.hits);
+        outcome.meta.graph_cache_miss_count = Some(graph_cache_stats.misses);
+
+        outcome.hints.extend(project_ctx.hints);
+        outcome.hints.push(if engine.graph_cache_used {
+            Hint {
+                kind: HintKind::Cache,
+                text: format!("Graph cache hit ({:?})", engine.language),
+            }
+        } else {
+            Hint {
+                kind: HintKind::Info,
+                text: format!("Graph built for language {:?}", engine.language),
+            }
+        });
+
+        self.health.attach(&project_ctx.root, &mut outcome).await;
+        Ok(outcome)
+    }
+}
+
+fn graph_of(engine: &GraphEngine) -> Result<&CodeGraph> {
+    engine
+        .context_search
+        .assembler()
+        .map(|assembler| assembler.graph())
+        .ok_or_else(|| anyhow!("Graph build error: missing assembler after build"))
+}
+
+fn require_symbol<'a>(value: &'a str, field: &str) -> Result<&'a str> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(anyhow!("{field} must not be empty"));
+    }
+    Ok(value)
+}
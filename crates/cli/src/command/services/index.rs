@@ -1,22 +1,27 @@
 use crate::command::context::CommandContext;
 use crate::command::domain::{
-    parse_payload, CommandOutcome, Hint, HintKind, IndexPayload, IndexResponse,
+    parse_payload, CommandOutcome, Hint, HintKind, IndexPayload, IndexPlanResponse, IndexRepair,
+    IndexResponse, IndexVerifyModel, IndexVerifyOutput, IndexVerifyPayload, QUALITY_GATE_FAILED,
 };
-use crate::command::infra::HealthPort;
+use crate::command::infra::{GraphCacheFactory, HealthPort};
 use crate::command::warm;
-use anyhow::Result;
-use context_indexer::{ModelIndexSpec, MultiModelProjectIndexer};
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use context_indexer::{ModelIndexSpec, MultiModelProjectIndexer, ProjectIndexer};
 use context_protocol::{DefaultBudgets, ToolNextAction};
-use context_vector_store::{current_model_id, ModelRegistry, QueryKind};
+use context_vector_store::{
+    corpus_path_for_project_root, current_model_id, reconcile_index_file, ChunkCorpus, DriftTotals,
+    ModelRegistry, QueryKind, VectorStore,
+};
 use std::collections::HashSet;
 
 pub struct IndexService {
+    graph: GraphCacheFactory,
     health: HealthPort,
 }
 
 impl IndexService {
-    pub fn new(health: HealthPort) -> Self {
-        Self { health }
+    pub fn new(graph: GraphCacheFactory, health: HealthPort) -> Self {
+        Self { graph, health }
     }
 
     pub async fn run(
@@ -65,12 +70,65 @@ impl IndexService {
             })?;
         }
 
+        let graph_cache = self.graph.for_root(&project_ctx.root);
+        let mut cache_hints = Vec::new();
+        if payload.clear_graph_cache && !payload.dry_run && graph_cache.clear().await? {
+            cache_hints.push(Hint {
+                kind: HintKind::Cache,
+                text: "Graph cache cleared; the next graph query rebuilds it".to_string(),
+            });
+        }
+
+        if payload.dry_run {
+            let indexer = ProjectIndexer::new_for_model_with_embedding_templates(
+                &project_ctx.root,
+                primary_model_id.clone(),
+                templates,
+            )
+            .await?;
+            let plan = if payload.full {
+                indexer.plan_full().await?
+            } else {
+                indexer.plan().await?
+            };
+            let mut outcome = CommandOutcome::from_value(IndexPlanResponse {
+                dry_run: true,
+                plan,
+            })?;
+            outcome.meta.index_updated = Some(false);
+            outcome.meta.config_path = project_ctx.config_path;
+            outcome.meta.profile = Some(project_ctx.profile_name.clone());
+            outcome.meta.profile_path = project_ctx.profile_path.clone();
+            outcome.hints.push(Hint {
+                kind: HintKind::Info,
+                text: format!(
+                    "Dry run for model {primary_model_id}: nothing was embedded or saved"
+                ),
+            });
+            outcome.hints.extend(project_ctx.hints);
+            return Ok(outcome);
+        }
+
         let specs: Vec<ModelIndexSpec> = models
             .iter()
             .map(|model_id| ModelIndexSpec::new(model_id.clone(), templates.clone()))
             .collect();
-        let indexer = MultiModelProjectIndexer::new(&project_ctx.root).await?;
-        let stats = indexer.index_models(&specs, payload.full).await?;
+        let indexer = MultiModelProjectIndexer::new(&project_ctx.root)
+            .await?
+            .with_profile(project_ctx.profile.name());
+        let indexed = indexer.index_models(&specs, payload.full).await?;
+        let failed_models: Vec<String> = indexed
+            .failures()
+            .into_iter()
+            .map(|(model_id, err)| format!("{model_id}: {err}"))
+            .collect();
+        if indexed.all_failed() {
+            bail!(
+                "Indexing failed for every model: {}",
+                failed_models.join("; ")
+            );
+        }
+        let stats = indexed.stats;
         let primary_index_path =
             crate::command::context::index_path_for_model(&project_ctx.root, &primary_model_id);
         let reason = if payload.full {
@@ -96,41 +154,41 @@ impl IndexService {
             .await
             .ok()
             .map(|m| m.len());
-        let graph_cache_path = project_ctx
-            .root
-            .join(".context-finder")
-            .join("graph_cache.json");
-        outcome.meta.graph_cache_size_bytes = tokio::fs::metadata(graph_cache_path)
-            .await
-            .ok()
-            .map(|m| m.len());
+        outcome.meta.graph_cache_size_bytes = graph_cache.size_bytes().await;
+        outcome.hints.extend(cache_hints);
         outcome.meta.warm = Some(warm.warmed);
         outcome.meta.warm_cost_ms = Some(warm.warm_cost_ms);
         outcome.meta.warm_graph_cache_hit = Some(warm.graph_cache_hit);
         let budgets = DefaultBudgets::default();
-        outcome.next_actions.push(ToolNextAction {
-            tool: "repo_onboarding_pack".to_string(),
-            args: serde_json::json!({
+        outcome.next_actions.push(ToolNextAction::new(
+            "repo_onboarding_pack",
+            serde_json::json!({
                 "project": project_ctx.root.display().to_string(),
                 "max_chars": budgets.repo_onboarding_pack_max_chars
             }),
-            reason: "Start with a compact repo map + key docs after indexing.".to_string(),
-        });
-        outcome.next_actions.push(ToolNextAction {
-            tool: "context_pack".to_string(),
-            args: serde_json::json!({
+            "Start with a compact repo map + key docs after indexing.",
+        ));
+        outcome.next_actions.push(ToolNextAction::new(
+            "context_pack",
+            serde_json::json!({
                 "project": project_ctx.root.display().to_string(),
                 "query": "project overview",
                 "max_chars": budgets.context_pack_max_chars
             }),
-            reason: "Build a bounded semantic overview after indexing.".to_string(),
-        });
+            "Build a bounded semantic overview after indexing.",
+        ));
         if models.len() > 1 {
             outcome.hints.push(Hint {
                 kind: HintKind::Info,
                 text: format!("Indexed {} models: {}", models.len(), models.join(", ")),
             });
         }
+        for failure in failed_models {
+            outcome.hints.push(Hint {
+                kind: HintKind::Warn,
+                text: format!("Model not indexed ({failure}); other models were updated"),
+            });
+        }
         outcome.hints.extend(project_ctx.hints);
         match health_snapshot {
             Ok(snapshot) => {
@@ -150,4 +208,133 @@ impl IndexService {
         }
         Ok(outcome)
     }
+
+    /// Reconcile each model's index with the chunk corpus, optionally re-embedding the drifted
+    /// chunks, and fail with [`QUALITY_GATE_FAILED`] when drift exceeds the thresholds.
+    pub async fn verify(
+        &self,
+        payload: serde_json::Value,
+        ctx: &CommandContext,
+    ) -> Result<CommandOutcome> {
+        let payload: IndexVerifyPayload = parse_payload(payload)?;
+        let project_ctx = ctx.resolve_project(payload.path).await?;
+
+        let corpus_path = corpus_path_for_project_root(&project_ctx.root);
+        if !corpus_path.exists() {
+            bail!(
+                "Corpus not found at {}. Run `context-finder index` first.",
+                corpus_path.display()
+            );
+        }
+        let corpus = ChunkCorpus::load(&corpus_path)
+            .await
+            .with_context(|| format!("Failed to load corpus {}", corpus_path.display()))?;
+
+        let mut models: Vec<String> = Vec::new();
+        for model_id in payload.models {
+            if !models.contains(&model_id) {
+                models.push(model_id);
+            }
+        }
+        if models.is_empty() {
+            models.push(current_model_id().unwrap_or_else(|_| "bge-small".to_string()));
+        }
+
+        let mut verified = Vec::with_capacity(models.len());
+        let mut violations = Vec::new();
+        let mut repaired_any = false;
+        for model_id in models {
+            let index_path =
+                crate::command::context::index_path_for_model(&project_ctx.root, &model_id);
+            if !index_path.exists() {
+                return Err(anyhow!(
+                    "Index for model {model_id} not found. Run `context-finder index --models {model_id}` first."
+                ));
+            }
+            let report = reconcile_index_file(&corpus, &index_path)
+                .await
+                .with_context(|| format!("Failed to reconcile index for model {model_id}"))?;
+
+            let repair = if payload.repair && !report.is_clean() {
+                let mut store = VectorStore::load_for_model(&index_path, &model_id).await?;
+                let stats = store.repair_drift(&corpus, &report).await?;
+                store.save().await?;
+                repaired_any = true;
+                let remaining = reconcile_index_file(&corpus, &index_path).await?.totals;
+                Some(IndexRepair {
+                    embedded: stats.embedded,
+                    reused: stats.reused,
+                    remaining,
+                })
+            } else {
+                None
+            };
+
+            let totals = repair
+                .as_ref()
+                .map_or(report.totals, |repair| repair.remaining);
+            if let Some(violation) = drift_violation(
+                &model_id,
+                totals,
+                report.corpus_chunks,
+                payload.max_drift_chunks,
+                payload.max_drift_ratio,
+            ) {
+                violations.push(violation);
+            }
+
+            verified.push(IndexVerifyModel {
+                model_id,
+                index_path: index_path.display().to_string(),
+                report,
+                repair,
+            });
+        }
+
+        let mut outcome = CommandOutcome::from_value(IndexVerifyOutput { models: verified })?;
+        outcome.meta.index_updated = Some(repaired_any);
+        outcome.meta.config_path = project_ctx.config_path;
+        outcome.meta.profile = Some(project_ctx.profile_name);
+        outcome.meta.profile_path = project_ctx.profile_path;
+        if !violations.is_empty() {
+            if !payload.repair {
+                outcome.hints.push(Hint {
+                    kind: HintKind::Action,
+                    text:
+                        "Rerun index_verify with `repair: true` to re-embed only the drifted chunks"
+                            .to_string(),
+                });
+            }
+            outcome.failure = Some(format!("{QUALITY_GATE_FAILED}: {}", violations.join("; ")));
+        }
+        outcome.hints.extend(project_ctx.hints);
+        Ok(outcome)
+    }
+}
+
+/// Describes how `totals` exceeds the allowed drift, or `None`. Without thresholds any drift
+/// is a violation.
+fn drift_violation(
+    model_id: &str,
+    totals: DriftTotals,
+    corpus_chunks: usize,
+    max_chunks: Option<usize>,
+    max_ratio: Option<f64>,
+) -> Option<String> {
+    let drifted = totals.total();
+    let ratio = drifted as f64 / corpus_chunks.max(1) as f64;
+    let max_chunks = max_chunks.or(max_ratio.is_none().then_some(0));
+    let mut exceeded = Vec::new();
+    if let Some(max_chunks) = max_chunks.filter(|&max| drifted > max) {
+        exceeded.push(format!("more than {max_chunks} chunks"));
+    }
+    if let Some(max_ratio) = max_ratio.filter(|&max| ratio > max) {
+        exceeded.push(format!("more than {max_ratio:.3} of the corpus"));
+    }
+    (!exceeded.is_empty()).then(|| {
+        format!(
+            "index {model_id} has {drifted} drifted chunks ({ratio:.3} of the corpus), {}",
+            exceeded.join(" and ")
+        )
+    })
 }
@@ -4,10 +4,15 @@ mod compare;
 mod config;
 mod context;
 mod eval;
+mod eval_generate;
+mod graph;
 mod index;
+mod models;
 mod repo_onboarding_pack;
 mod search;
+mod template_preview;
 mod text_search;
+mod warm;
 
 pub(crate) use search::collect_chunks;
 
@@ -24,10 +29,14 @@ pub struct Services {
     config: config::ConfigService,
     context: context::ContextService,
     eval: eval::EvalService,
+    graph: graph::GraphService,
     index: index::IndexService,
+    models: models::ModelsService,
     repo_onboarding_pack: repo_onboarding_pack::RepoOnboardingPackService,
     search: search::SearchService,
+    template_preview: template_preview::TemplatePreviewService,
     text_search: text_search::TextSearchService,
+    warm: warm::WarmService,
 }
 
 impl Services {
@@ -42,10 +51,14 @@ impl Services {
             config: config::ConfigService,
             context: context::ContextService,
             eval: eval::EvalService,
-            index: index::IndexService::new(health.clone()),
+            graph: graph::GraphService::new(graph.clone(), health.clone()),
+            index: index::IndexService::new(graph.clone(), health.clone()),
+            models: models::ModelsService,
             repo_onboarding_pack: repo_onboarding_pack::RepoOnboardingPackService,
-            search: search::SearchService::new(graph, health, cache),
+            search: search::SearchService::new(graph.clone(), health, cache),
+            template_preview: template_preview::TemplatePreviewService,
             text_search: text_search::TextSearchService,
+            warm: warm::WarmService::new(graph),
         }
     }
 
@@ -84,6 +97,17 @@ impl Services {
             CommandAction::RepoOnboardingPack => self.repo_onboarding_pack.run(payload, ctx).await,
             CommandAction::Eval => self.eval.run(payload, ctx).await,
             CommandAction::EvalCompare => self.eval.compare(payload, ctx).await,
+            CommandAction::EvalGenerate => self.eval.generate(payload, ctx).await,
+            CommandAction::Impact => self.graph.impact(payload, ctx).await,
+            CommandAction::Trace => self.graph.trace(payload, ctx).await,
+            CommandAction::Explain => self.graph.explain(payload, ctx).await,
+            CommandAction::Overview => self.graph.overview(payload, ctx).await,
+            CommandAction::ModelsList => self.models.list(payload, ctx).await,
+            CommandAction::ModelsFetch => self.models.fetch(payload, ctx).await,
+            CommandAction::ModelsVerify => self.models.verify(payload, ctx).await,
+            CommandAction::Warm => self.warm.run(payload, ctx).await,
+            CommandAction::TemplatePreview => self.template_preview.preview(payload, ctx).await,
+            CommandAction::IndexVerify => self.index.verify(payload, ctx).await,
         }
     }
 }
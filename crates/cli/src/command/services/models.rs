@@ -0,0 +1,92 @@
+use crate::command::context::CommandContext;
+use crate::command::domain::{parse_payload, CommandOutcome, Hint, HintKind, ModelsPayload};
+use crate::models::{self, MODEL_VERIFY_FAILED};
+use anyhow::Result;
+use serde_json::Value;
+
+/// Lists, downloads and verifies the embedding models of the model manifest.
+pub struct ModelsService;
+
+impl ModelsService {
+    pub async fn list(&self, payload: Value, _ctx: &CommandContext) -> Result<CommandOutcome> {
+        let _: ModelsPayload = parse_payload(payload)?;
+        let report = models::list_models(&models::resolve_model_dir())?;
+        let missing: Vec<&str> = report
+            .models
+            .iter()
+            .filter(|model| !model.installed)
+            .map(|model| model.id.as_str())
+            .collect();
+        let hint = (!missing.is_empty()).then(|| Hint {
+            kind: HintKind::Action,
+            text: format!(
+                "Run action=models_fetch with payload.models={missing:?} to download the missing assets."
+            ),
+        });
+
+        let mut outcome = CommandOutcome::from_value(report)?;
+        outcome.hints.extend(hint);
+        Ok(outcome)
+    }
+
+    pub async fn fetch(&self, payload: Value, _ctx: &CommandContext) -> Result<CommandOutcome> {
+        let payload: ModelsPayload = parse_payload(payload)?;
+        let report = models::install_models(
+            &models::resolve_model_dir(),
+            &payload.models,
+            payload.force,
+            false,
+            &mut |progress| {
+                if progress.done {
+                    log::info!(
+                        "fetched {} for model {} ({} bytes)",
+                        progress.asset,
+                        progress.model,
+                        progress.downloaded_bytes
+                    );
+                } else {
+                    log::debug!(
+                        "fetching {}: {}/{} bytes",
+                        progress.asset,
+                        progress.downloaded_bytes,
+                        progress
+                            .total_bytes
+                            .map_or_else(|| "?".to_string(), |total| total.to_string())
+                    );
+                }
+            },
+        )
+        .await?;
+
+        let mut outcome = CommandOutcome::from_value(report)?;
+        outcome.hints.push(Hint {
+            kind: HintKind::Info,
+            text: "Run action=models_verify to confirm the runtime can load the models."
+                .to_string(),
+        });
+        Ok(outcome)
+    }
+
+    pub async fn verify(&self, payload: Value, _ctx: &CommandContext) -> Result<CommandOutcome> {
+        let payload: ModelsPayload = parse_payload(payload)?;
+        let report = models::verify_models(&models::resolve_model_dir(), &payload.models).await?;
+        let failed: Vec<String> = report
+            .models
+            .iter()
+            .filter(|model| !model.ok)
+            .map(|model| {
+                format!(
+                    "{} ({})",
+                    model.id,
+                    model.error.as_deref().unwrap_or("unknown error")
+                )
+            })
+            .collect();
+
+        let mut outcome = CommandOutcome::from_value(report)?;
+        if !failed.is_empty() {
+            outcome.failure = Some(format!("{MODEL_VERIFY_FAILED}: {}", failed.join("; ")));
+        }
+        Ok(outcome)
+    }
+}
@@ -1,11 +1,13 @@
 use crate::command::context::{unix_ms, CommandContext};
 use crate::command::domain::{
-    parse_payload, CommandOutcome, Hint, MapOutput, MapPayload, RepoOnboardingDocSlice,
-    RepoOnboardingDocsReason, RepoOnboardingPackBudget, RepoOnboardingPackOutput,
-    RepoOnboardingPackPayload,
+    config_string_list_path, config_usize_path, parse_payload, CommandOutcome, Hint, MapOutput,
+    MapPayload, RepoOnboardingDocSlice, RepoOnboardingDocsReason, RepoOnboardingPackBudget,
+    RepoOnboardingPackOutput, RepoOnboardingPackPayload,
 };
 use crate::command::freshness;
+use crate::command::path_filters::matches_file_pattern;
 use anyhow::{Context as AnyhowContext, Result};
+use context_indexer::FileScanner;
 use context_protocol::{enforce_max_chars, finalize_used_chars, BudgetTruncation, DefaultBudgets};
 use sha2::{Digest, Sha256};
 use std::collections::HashSet;
@@ -36,6 +38,7 @@ const MAX_AUTO_INDEX_BUDGET_MS: u64 = 120_000;
 const DEFAULT_DOC_CANDIDATES: &[&str] = &[
     "AGENTS.md",
     "README.md",
+    "ARCHITECTURE.md",
     "docs/QUICK_START.md",
     "contracts/README.md",
     "docs/README.md",
@@ -75,25 +78,35 @@ impl RepoOnboardingPackService {
 
         let policy =
             AutoIndexPolicy::from_request(payload.auto_index, payload.auto_index_budget_ms);
-        let mut index_state =
-            freshness::gather_index_state(&project_ctx.root, &project_ctx.profile_name).await?;
+        let mut index_state = freshness::gather_index_state(
+            &project_ctx.root,
+            &project_ctx.profile_name,
+            &project_ctx.profile,
+        )
+        .await?;
         let mut reindex_hints = Vec::new();
         let mut index_updated = false;
+        let mut reindex_deadline_hit = None;
         if policy.enabled && (index_state.stale || !index_state.index.exists) {
-            let attempt = freshness::attempt_reindex(
+            let reindex = freshness::shared_auto_reindex(
                 &project_ctx.root,
                 &project_ctx.profile,
                 policy.budget_ms,
             )
             .await;
-            reindex_hints.push(freshness::render_reindex_hint(&attempt));
-            index_updated = attempt.performed;
-            if let Ok(refreshed) =
-                freshness::gather_index_state(&project_ctx.root, &project_ctx.profile_name).await
+            reindex_hints.push(freshness::render_reindex_hint(&reindex));
+            index_updated = reindex.attempt.performed;
+            reindex_deadline_hit = Some(reindex.deadline_hit);
+            if let Ok(refreshed) = freshness::gather_index_state(
+                &project_ctx.root,
+                &project_ctx.profile_name,
+                &project_ctx.profile,
+            )
+            .await
             {
                 index_state = refreshed;
             }
-            index_state.reindex = Some(attempt);
+            index_state.reindex = Some(reindex.attempt);
         }
 
         let map_depth = payload.map_depth.unwrap_or(DEFAULT_MAP_DEPTH).clamp(1, 4);
@@ -119,7 +132,17 @@ impl RepoOnboardingPackService {
             .clamp(MIN_MAX_CHARS, MAX_MAX_CHARS);
 
         let has_index = index_state.index.exists;
-        let doc_candidates = collect_doc_candidates(&payload);
+        let key_docs_globs =
+            config_string_list_path(&project_ctx.config, &["onboarding", "key_docs_globs"]);
+        let max_doc_bytes =
+            config_usize_path(&project_ctx.config, &["onboarding", "max_doc_bytes"])
+                .filter(|bytes| *bytes > 0);
+        let project_files = if key_docs_globs.is_empty() || payload.doc_paths.is_some() {
+            Vec::new()
+        } else {
+            scan_project_files(&project_ctx.root)
+        };
+        let doc_candidates = collect_doc_candidates(&payload, &key_docs_globs, &project_files);
         let root_display = project_ctx.root.display().to_string();
 
         let mut result = RepoOnboardingPackOutput {
@@ -144,6 +167,7 @@ impl RepoOnboardingPackService {
             docs_limit,
             doc_max_lines,
             doc_max_chars,
+            max_doc_bytes,
         )?;
         trim_to_budget(&mut result)?;
         if result.docs.is_empty() {
@@ -167,6 +191,8 @@ impl RepoOnboardingPackService {
         outcome.meta.profile = Some(project_ctx.profile_name.clone());
         outcome.meta.profile_path = project_ctx.profile_path;
         outcome.meta.index_updated = Some(index_updated);
+        outcome.meta.reindex_attempted = reindex_deadline_hit.map(|_| true);
+        outcome.meta.reindex_deadline_hit = reindex_deadline_hit;
         outcome.meta.index_state = Some(index_state);
         Ok(outcome)
     }
@@ -188,6 +214,8 @@ async fn build_map_output(
         project: Some(root.to_path_buf()),
         depth,
         limit: Some(limit),
+        min_symbol_score: None,
+        public_symbols_only: false,
     };
     let context_service = ContextService;
     let map_outcome = context_service
@@ -210,43 +238,56 @@ fn build_next_actions(
     let mut next_actions = Vec::new();
 
     if !has_index {
-        next_actions.push(context_protocol::ToolNextAction {
-            tool: "index".to_string(),
-            args: serde_json::json!({ "path": root_display }),
-            reason: "Build the semantic index (required for semantic search and packs)."
-                .to_string(),
-        });
+        next_actions.push(context_protocol::ToolNextAction::new(
+            "index",
+            serde_json::json!({ "path": root_display }),
+            "Build the semantic index (required for semantic search and packs).",
+        ));
     }
 
-    next_actions.push(context_protocol::ToolNextAction {
-        tool: "text_search".to_string(),
-        args: serde_json::json!({
+    next_actions.push(context_protocol::ToolNextAction::new(
+        "text_search",
+        serde_json::json!({
             "project": root_display,
             "pattern": "TODO",
             "max_results": 200
         }),
-        reason: "Scan for TODO markers across the repo (literal search).".to_string(),
-    });
+        "Scan for TODO markers across the repo (literal search).",
+    ));
 
     if has_index {
-        next_actions.push(context_protocol::ToolNextAction {
-            tool: "context_pack".to_string(),
-            args: serde_json::json!({
+        next_actions.push(context_protocol::ToolNextAction::new(
+            "context_pack",
+            serde_json::json!({
                 "project": root_display,
                 "query": "describe what you want to change",
                 "max_chars": budgets.context_pack_max_chars
             }),
-            reason: "One-shot semantic onboarding pack for a concrete question.".to_string(),
-        });
+            "One-shot semantic onboarding pack for a concrete question.",
+        ));
     }
 
     next_actions
 }
 
-fn collect_doc_candidates(request: &RepoOnboardingPackPayload) -> Vec<String> {
+fn scan_project_files(root: &Path) -> Vec<String> {
+    let mut files: Vec<String> = FileScanner::new(root)
+        .scan()
+        .iter()
+        .filter_map(|file| normalize_relative_path(root, file))
+        .collect();
+    files.sort();
+    files
+}
+
+fn collect_doc_candidates(
+    request: &RepoOnboardingPackPayload,
+    key_docs_globs: &[String],
+    project_files: &[String],
+) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut doc_candidates: Vec<String> = Vec::new();
     if let Some(custom) = request.doc_paths.as_ref() {
-        let mut seen = HashSet::new();
-        let mut doc_candidates: Vec<String> = Vec::new();
         for rel in custom {
             let rel = rel.trim();
             if rel.is_empty() {
@@ -260,10 +301,20 @@ fn collect_doc_candidates(request: &RepoOnboardingPackPayload) -> Vec<String> {
         return doc_candidates;
     }
 
-    DEFAULT_DOC_CANDIDATES
-        .iter()
-        .map(|&rel| rel.to_owned())
-        .collect()
+    // Configured globs come first, in config order, so project-specific docs win the docs_limit.
+    for glob in key_docs_globs {
+        for file in project_files {
+            if matches_file_pattern(file, Some(glob)) && seen.insert(file.clone()) {
+                doc_candidates.push(file.clone());
+            }
+        }
+    }
+    for &rel in DEFAULT_DOC_CANDIDATES {
+        if seen.insert(rel.to_string()) {
+            doc_candidates.push(rel.to_string());
+        }
+    }
+    doc_candidates
 }
 
 fn add_docs_best_effort(
@@ -273,18 +324,29 @@ fn add_docs_best_effort(
     docs_limit: usize,
     doc_max_lines: usize,
     doc_max_chars: usize,
+    max_doc_bytes: Option<usize>,
 ) -> Result<()> {
+    let mut doc_bytes = 0usize;
     for rel in doc_candidates {
-        if result.docs.len() >= docs_limit {
+        let remaining_bytes = max_doc_bytes.map(|cap| cap.saturating_sub(doc_bytes));
+        if result.docs.len() >= docs_limit || remaining_bytes == Some(0) {
             result.budget.truncated = true;
             result.budget.truncation = Some(BudgetTruncation::DocsLimit);
             break;
         }
 
-        let Ok(slice) = compute_onboarding_doc_slice(root, rel, 1, doc_max_lines, doc_max_chars)
-        else {
+        let max_chars = remaining_bytes.map_or(doc_max_chars, |left| doc_max_chars.min(left));
+        let Ok(slice) = compute_onboarding_doc_slice(root, rel, 1, doc_max_lines, max_chars) else {
             continue;
         };
+        // Chars under-count multi-byte text; only the first doc may overshoot the byte cap.
+        if remaining_bytes.is_some_and(|left| slice.content.len() > left) && !result.docs.is_empty()
+        {
+            result.budget.truncated = true;
+            result.budget.truncation = Some(BudgetTruncation::DocsLimit);
+            break;
+        }
+        doc_bytes += slice.content.len();
         result.docs.push(slice);
 
         finalize_repo_onboarding_budget(result)?;
@@ -3,23 +3,35 @@ use crate::command::context::{
     unix_ms, CommandContext,
 };
 use crate::command::domain::{
-    config_bool_path, config_string_path, config_usize_path, parse_payload, CommandOutcome,
-    ContextPackBudget, ContextPackItem, ContextPackOutput, ContextPackPayload, Hint, HintKind,
-    NextAction, NextActionKind, RelatedCodeOutput, SearchOutput, SearchPayload, SearchResultOutput,
-    SearchStrategy, SearchWithContextPayload, TaskPackItem, TaskPackOutput, TaskPackPayload,
-    TASK_PACK_VERSION,
+    config_bool_path, config_string_path, config_synonyms, config_usize_path, parse_payload,
+    CommandOutcome, ContextPackBudget, ContextPackItem, ContextPackOmitted, ContextPackOutput,
+    ContextPackPayload, Hint, HintKind, NextAction, NextActionKind, RelatedCodeOutput,
+    SearchOutput, SearchPayload, SearchResultOutput, SearchStrategy, SearchWithContextPayload,
+    TaskPackItem, TaskPackOutput, TaskPackPayload, TASK_PACK_VERSION,
 };
+use crate::command::freshness::changed_since_indexed_hint;
 use crate::command::infra::{GraphCacheFactory, HealthPort};
 use crate::command::warm;
+use crate::graph_cache::graph_cache_stats;
 use anyhow::{anyhow, Context as AnyhowContext, Result};
+use context_graph::analysis::{
+    detect_graph_language, detect_language, unsupported_language_hint, DetectedLanguage,
+};
 use context_graph::{
-    build_graph_docs, ContextAssembler, GraphDocConfig, GraphLanguage, GRAPH_DOC_VERSION,
+    build_graph_docs, AssemblyBudget, ContextAssembler, GraphDocConfig, GraphLanguage,
+    RelationshipType, GRAPH_DOC_VERSION,
+};
+use context_protocol::{
+    enforce_max_chars, enforce_max_tokens, finalize_used_chars, finalize_used_tokens,
+    BudgetTruncation, ToolNextAction,
+};
+use context_search::{
+    find_overlapping_item, graph_next_actions, merge_overlapping_span, oldest_indexed_at_ms,
+    pack_order, EnrichedResult, OmittedItems, PackBudget, RelatedContext,
 };
-use context_protocol::{enforce_max_chars, finalize_used_chars, BudgetTruncation, ToolNextAction};
-use context_search::{EnrichedResult, RelatedContext};
 use context_search::{
-    MultiModelContextSearch, MultiModelHybridSearch, QueryClassifier, QueryType, SearchProfile,
-    CONTEXT_PACK_VERSION,
+    parse_query, MultiModelContextSearch, MultiModelHybridSearch, QueryClassifier, QueryExpander,
+    QueryType, SearchProfile, CONTEXT_PACK_VERSION,
 };
 use context_vector_store::{
     classify_path_kind, corpus_path_for_project_root, current_model_id, ChunkCorpus, DocumentKind,
@@ -78,9 +90,10 @@ impl SearchService {
             .or_else(|| config_bool_path(&project_ctx.config, &["defaults", "search", "trace"]))
             .unwrap_or(false);
         let load_index_start = Instant::now();
-        let loaded = load_semantic_indexes(&project_ctx.root, &project_ctx.profile)
-            .await
-            .context("Failed to load semantic indices")?;
+        let loaded =
+            load_semantic_indexes(&project_ctx.root, &project_ctx.profile, &payload.models)
+                .await
+                .context("Failed to load semantic indices")?;
         let timing_load_index_ms = load_index_start.elapsed().as_millis() as u64;
         let store_path = loaded.store_path;
         let store_mtime = loaded.store_mtime;
@@ -95,25 +108,55 @@ impl SearchService {
             MultiModelHybridSearch::from_env(sources, profile)
         }
         .context("Failed to create search engine")?;
+        if let Some(expander) = configured_query_expander(&project_ctx.config) {
+            search.set_query_expander(expander);
+        }
+        let scope_hint = apply_path_scope(&mut search, &ctx.request_options());
+        search.set_query_type(payload.query_type);
+        if !payload.models.is_empty() {
+            search.set_models(Some(payload.models.clone()));
+        }
+        let query_type = search.query_type(payload.query.trim());
+        let weights = search.query_weights(payload.query.trim());
         let search_start = Instant::now();
         let results = search
             .search(&payload.query, limit)
             .await
             .context("Search failed")?;
         let timing_search_ms = search_start.elapsed().as_millis() as u64;
+        let model_timings = search.model_timings().to_vec();
+        let query_embedding_cache_hits = search.query_embedding_cache_hits();
 
         let mut formatted: Vec<_> = results.into_iter().map(format_basic_output).collect();
         annotate_reasons(&payload.query, &mut formatted);
         let (deduped, dropped) = dedup_results(formatted, &project_ctx.profile);
 
         if trace {
+            let source = if payload.query_type.is_some() {
+                "override".to_string()
+            } else if let Some(rule) = project_ctx.profile.classifier_rule(payload.query.trim()) {
+                format!("rule `{}`", rule.pattern)
+            } else {
+                "auto".to_string()
+            };
+            eprintln!(
+                "[trace] query_type={} ({source}) semantic={} fuzzy={} candidate_multiplier={}",
+                query_type.as_str(),
+                weights.semantic,
+                weights.fuzzy,
+                weights.candidate_multiplier
+            );
             trace_results(&payload.query, &deduped);
         }
 
-        let mut outcome = CommandOutcome::from_value(SearchOutput {
-            query: payload.query.clone(),
-            results: deduped,
-        })?;
+        let changed_hint = changed_since_indexed_hint(
+            &project_ctx.root,
+            deduped.iter().map(|r| (r.file.as_str(), r.indexed_at_ms)),
+        );
+        let mut outcome =
+            CommandOutcome::from_value(SearchOutput::new(payload.query.clone(), deduped))?;
+        outcome.meta.query_type = Some(query_type);
+        outcome.meta.weights_used = trace.then_some(weights);
         outcome.meta.config_path = project_ctx.config_path;
         outcome.meta.profile = Some(project_ctx.profile_name.clone());
         outcome.meta.profile_path = project_ctx.profile_path.clone();
@@ -122,6 +165,9 @@ impl SearchService {
         outcome.meta.index_size_bytes = index_size_bytes;
         outcome.meta.timing_load_index_ms = Some(timing_load_index_ms);
         outcome.meta.timing_search_ms = Some(timing_search_ms);
+        outcome.meta.query_embedding_cache_hits =
+            (!model_timings.is_empty()).then_some(query_embedding_cache_hits);
+        outcome.meta.model_timings = (!model_timings.is_empty()).then_some(model_timings);
         outcome.meta.warm = Some(warm.warmed);
         outcome.meta.warm_cost_ms = Some(warm.warm_cost_ms);
         outcome.meta.warm_graph_cache_hit = Some(warm.graph_cache_hit);
@@ -144,6 +190,9 @@ impl SearchService {
                 text: h,
             });
         }
+        if let Some(hint) = scope_hint {
+            outcome.hints.push(hint);
+        }
         if dropped > 0 {
             outcome.meta.duplicates_dropped = Some(dropped);
             outcome.hints.push(Hint {
@@ -151,6 +200,9 @@ impl SearchService {
                 text: format!("Deduplicated {dropped} overlapping results"),
             });
         }
+        if let Some(hint) = changed_hint {
+            outcome.hints.push(hint);
+        }
         outcome.hints.extend(project_ctx.hints.into_iter());
         outcome.hints.push(Hint {
             kind: HintKind::Cache,
@@ -179,6 +231,7 @@ impl SearchService {
             ));
         }
         let project_ctx = ctx.resolve_project(payload.project).await?;
+        let request_options = ctx.request_options();
         let warm = warm::global_warmer().prewarm(&project_ctx.root).await;
         let (task_hint, reason_hint) = choose_task_hint(&payload.query);
         let limit = payload
@@ -201,7 +254,7 @@ impl SearchService {
                 {
                     (cfg, None)
                 } else {
-                    choose_strategy(&payload.query)
+                    choose_strategy(&parse_query(&payload.query).text, &project_ctx.profile)
                 }
             }
         };
@@ -234,7 +287,7 @@ impl SearchService {
             .unwrap_or(true);
 
         let load_index_start = Instant::now();
-        let loaded = load_semantic_indexes(&project_ctx.root, &project_ctx.profile)
+        let loaded = load_semantic_indexes(&project_ctx.root, &project_ctx.profile, &[])
             .await
             .context("Failed to load semantic indices")?;
         let timing_load_index_ms = load_index_start.elapsed().as_millis() as u64;
@@ -249,11 +302,10 @@ impl SearchService {
             )
             .or_else(|| crate::command::context::graph_language_from_config(&project_ctx.config))
         });
-        let language = language_pref
+        let requested_language = language_pref
             .as_deref()
             .map(parse_graph_language)
-            .transpose()?
-            .unwrap_or(GraphLanguage::Rust);
+            .transpose()?;
 
         let graph_cache = self.graph.for_root(&project_ctx.root);
         let mut graph_cache_used = false;
@@ -266,20 +318,27 @@ impl SearchService {
         available_semantic_models.sort();
         let profile = project_ctx.profile.clone();
         let corpus = load_chunk_corpus(&project_ctx.root).await?;
-        let hybrid = if let Some(corpus) = corpus {
+        let mut hybrid = if let Some(corpus) = corpus {
             MultiModelHybridSearch::from_env_with_corpus(sources, profile, corpus)
         } else {
             MultiModelHybridSearch::from_env(sources, profile)
         }
         .context("Failed to create search engine")?;
+        if let Some(expander) = configured_query_expander(&project_ctx.config) {
+            hybrid.set_query_expander(expander);
+        }
+        let scope_hint = apply_path_scope(&mut hybrid, &request_options);
+        let graph_language = resolve_graph_language(requested_language, hybrid.chunks());
+        let language_detected = requested_language.is_none();
         let chunk_lookup = build_chunk_lookup(hybrid.chunks());
 
-        let cached_assembler = if reuse_graph {
-            graph_cache
-                .load(store_mtime, language, hybrid.chunks(), &chunk_lookup)
-                .await?
-        } else {
-            None
+        let cached_assembler = match &graph_language {
+            Ok(language) if reuse_graph => {
+                graph_cache
+                    .load(store_mtime, *language, hybrid.chunks(), &chunk_lookup)
+                    .await?
+            }
+            _ => None,
         };
 
         let mut context_search =
@@ -290,7 +349,12 @@ impl SearchService {
             graph_cache_used = true;
         }
 
-        if context_search.assembler().is_none() {
+        let build_language = graph_language
+            .as_ref()
+            .ok()
+            .copied()
+            .filter(|_| context_search.assembler().is_none());
+        if let Some(language) = build_language {
             context_search
                 .build_graph(language)
                 .context("Failed to build code graph")?;
@@ -305,10 +369,11 @@ impl SearchService {
         let timing_graph_ms = graph_start.elapsed().as_millis() as u64;
 
         let search_start = Instant::now();
-        let enriched_results = context_search
+        let mut enriched_results = context_search
             .search_with_context(&payload.query, limit, strategy.to_assembly())
             .await
             .context("Context search failed")?;
+        let related_filtered = retain_related_in_scope(&mut enriched_results, &request_options);
         let timing_search_ms = search_start.elapsed().as_millis() as u64;
 
         let mut formatted: Vec<_> = enriched_results
@@ -318,10 +383,7 @@ impl SearchService {
         annotate_reasons(&payload.query, &mut formatted);
         let (results, dropped) = dedup_results(formatted, &project_ctx.profile);
 
-        let output = SearchOutput {
-            query: payload.query.clone(),
-            results: results.clone(),
-        };
+        let output = SearchOutput::new(payload.query.clone(), results.clone());
 
         if trace {
             trace_results(&payload.query, &results);
@@ -347,6 +409,25 @@ impl SearchService {
         outcome.meta.timing_load_index_ms = Some(timing_load_index_ms);
         outcome.meta.timing_graph_ms = Some(timing_graph_ms);
         outcome.meta.timing_search_ms = Some(timing_search_ms);
+        outcome.meta.graph_language = Some(match &graph_language {
+            Ok(language) => language.as_str().to_string(),
+            Err(name) => name.clone(),
+        });
+        outcome.meta.detected = Some(language_detected);
+        match &graph_language {
+            Ok(language) if language_detected => outcome.hints.push(Hint {
+                kind: HintKind::Info,
+                text: format!(
+                    "Graph language {} detected from indexed chunks",
+                    language.as_str()
+                ),
+            }),
+            Ok(_) => {}
+            Err(name) => outcome.hints.push(Hint {
+                kind: HintKind::Warn,
+                text: unsupported_language_hint(name),
+            }),
+        }
         if let Some(hint) = strategy_hint {
             outcome.hints.push(Hint {
                 kind: HintKind::Info,
@@ -407,6 +488,15 @@ impl SearchService {
                 text: format!("graph paths: {}", related_paths.join("; ")),
             });
         }
+        if let Some(hint) = scope_hint {
+            outcome.hints.push(hint);
+        }
+        if related_filtered > 0 {
+            outcome.hints.push(Hint {
+                kind: HintKind::Info,
+                text: format!("Path filters excluded {related_filtered} related chunks"),
+            });
+        }
         if dropped > 0 {
             outcome.meta.duplicates_dropped = Some(dropped);
             outcome.hints.push(Hint {
@@ -414,11 +504,20 @@ impl SearchService {
                 text: format!("Deduplicated {dropped} overlapping results"),
             });
         }
+        if let Some(hint) = changed_since_indexed_hint(
+            &project_ctx.root,
+            results.iter().map(|r| (r.file.as_str(), r.indexed_at_ms)),
+        ) {
+            outcome.hints.push(hint);
+        }
         if let Some((nodes, edges)) = context_search.graph_stats() {
             outcome.meta.graph_nodes = Some(nodes);
             outcome.meta.graph_edges = Some(edges);
         }
         outcome.meta.graph_cache_size_bytes = graph_cache.size_bytes().await;
+        let graph_cache_stats = graph_cache_stats();
+        outcome.meta.graph_cache_hit_count = Some(graph_cache_stats.hits);
+        outcome.meta.graph_cache_miss_count = Some(graph_cache_stats.misses);
         outcome.hints.extend(project_ctx.hints.into_iter());
         outcome.hints.push(Hint {
             kind: HintKind::Cache,
@@ -429,29 +528,30 @@ impl SearchService {
             ),
         });
 
-        if graph_cache_used {
-            if let Some((nodes, edges)) = context_search.graph_stats() {
-                outcome.hints.push(Hint {
-                    kind: HintKind::Cache,
-                    text: format!(
-                        "Graph cache hit ({:?}) — {} nodes / {} edges reused",
-                        language, nodes, edges
-                    ),
-                });
+        match graph_language {
+            Err(_) => {}
+            Ok(language) if graph_cache_used => {
+                if let Some((nodes, edges)) = context_search.graph_stats() {
+                    outcome.hints.push(Hint {
+                        kind: HintKind::Cache,
+                        text: format!(
+                            "Graph cache hit ({:?}) — {} nodes / {} edges reused",
+                            language, nodes, edges
+                        ),
+                    });
+                }
             }
-        } else if reuse_graph {
-            outcome.hints.push(Hint {
+            Ok(language) if reuse_graph => outcome.hints.push(Hint {
                 kind: HintKind::Info,
                 text: format!(
                     "Graph rebuilt for language {:?}; future runs will reuse cache unless reuse_graph=false",
                     language
                 ),
-            });
-        } else {
-            outcome.hints.push(Hint {
+            }),
+            Ok(_) => outcome.hints.push(Hint {
                 kind: HintKind::Warn,
                 text: "Graph caching disabled for this request (reuse_graph=false)".to_string(),
-            });
+            }),
         }
 
         self.health.attach(&project_ctx.root, &mut outcome).await;
@@ -464,6 +564,18 @@ impl SearchService {
         ctx: &CommandContext,
     ) -> Result<CommandOutcome> {
         let payload: ContextPackPayload = parse_payload(payload)?;
+        let (outcome, _) = self.build_context_pack(payload, ctx, 0).await?;
+        Ok(outcome)
+    }
+
+    /// Run context_pack; with `max_graph_actions > 0` also derive next actions from the graph
+    /// neighbours of the packed primaries, using the same assembler and related budget.
+    async fn build_context_pack(
+        &self,
+        payload: ContextPackPayload,
+        ctx: &CommandContext,
+        max_graph_actions: usize,
+    ) -> Result<(CommandOutcome, Vec<NextAction>)> {
         if payload.query.trim().is_empty() {
             return Err(anyhow!("Query must not be empty"));
         }
@@ -489,6 +601,13 @@ impl SearchService {
             })
             .unwrap_or(20_000);
 
+        let max_tokens = payload.max_tokens.or_else(|| {
+            config_usize_path(
+                &project_ctx.config,
+                &["defaults", "context_pack", "max_tokens"],
+            )
+        });
+
         let max_related_per_primary = payload
             .max_related_per_primary
             .or_else(|| {
@@ -516,6 +635,8 @@ impl SearchService {
             })
             .unwrap_or(true);
 
+        // `-term` exclusions and quote marks say nothing about what kind of query this is.
+        let query_text = parse_query(&payload.query).text;
         let (strategy, strategy_hint) = match payload.strategy {
             Some(s) => (s, None),
             None => {
@@ -527,23 +648,24 @@ impl SearchService {
                 {
                     (cfg, None)
                 } else {
-                    choose_strategy(&payload.query)
+                    choose_strategy(&query_text, &project_ctx.profile)
                 }
             }
         };
 
-        let query_type = QueryClassifier::classify(&payload.query);
-        let docs_intent = QueryClassifier::is_docs_intent(&payload.query);
+        let query_type = project_ctx.profile.classify_query(&query_text);
+        let docs_intent = QueryClassifier::is_docs_intent(&query_text);
         let include_docs = payload.include_docs.unwrap_or(true);
         let prefer_code = payload.prefer_code.unwrap_or(!docs_intent);
         let related_mode =
             parse_related_mode(payload.related_mode.as_deref(), docs_intent, query_type)?;
-        let query_tokens = tokenize_focus_query(&payload.query);
+        let query_tokens = tokenize_focus_query(&query_text);
 
         let load_index_start = Instant::now();
-        let loaded = load_semantic_indexes(&project_ctx.root, &project_ctx.profile)
-            .await
-            .context("Failed to load semantic indices")?;
+        let loaded =
+            load_semantic_indexes(&project_ctx.root, &project_ctx.profile, &payload.models)
+                .await
+                .context("Failed to load semantic indices")?;
         let timing_load_index_ms = load_index_start.elapsed().as_millis() as u64;
         let _store_path = loaded.store_path;
         let store_mtime = loaded.store_mtime;
@@ -573,12 +695,19 @@ impl SearchService {
         available_semantic_models.sort();
         let profile = project_ctx.profile.clone();
         let corpus = load_chunk_corpus(&project_ctx.root).await?;
-        let hybrid = if let Some(corpus) = corpus {
+        let mut hybrid = if let Some(corpus) = corpus {
             MultiModelHybridSearch::from_env_with_corpus(sources, profile, corpus)
         } else {
             MultiModelHybridSearch::from_env(sources, profile)
         }
         .context("Failed to create search engine")?;
+        if let Some(expander) = configured_query_expander(&project_ctx.config) {
+            hybrid.set_query_expander(expander);
+        }
+        let scope_hint = apply_path_scope(&mut hybrid, &request_options);
+        if !payload.models.is_empty() {
+            hybrid.set_models(Some(payload.models.clone()));
+        }
         let chunk_lookup = build_chunk_lookup(hybrid.chunks());
 
         let cached_assembler = if reuse_graph {
@@ -623,6 +752,8 @@ impl SearchService {
             .await
             .context("Context search failed")?;
         let timing_search_ms = search_start.elapsed().as_millis() as u64;
+        let model_timings = context_search.hybrid().model_timings().to_vec();
+        let query_embedding_cache_hits = context_search.hybrid().query_embedding_cache_hits();
 
         let graph_nodes_cfg = project_ctx.profile.graph_nodes();
         let mut graph_nodes_hint: Option<String> = None;
@@ -701,7 +832,12 @@ impl SearchService {
                                 else {
                                     continue;
                                 };
-                                if project_ctx.profile.is_rejected(&chunk.file_path) {
+                                if project_ctx.profile.is_rejected(&chunk.file_path)
+                                    || !crate::command::path_filters::path_allowed(
+                                        &chunk.file_path,
+                                        &request_options,
+                                    )
+                                {
                                     continue;
                                 }
 
@@ -714,16 +850,7 @@ impl SearchService {
                                     related = assembled
                                         .related_chunks
                                         .into_iter()
-                                        .map(|rc| context_search::RelatedContext {
-                                            chunk: rc.chunk,
-                                            relationship_path: rc
-                                                .relationship
-                                                .iter()
-                                                .map(|r| format!("{r:?}"))
-                                                .collect(),
-                                            distance: rc.distance,
-                                            relevance_score: rc.relevance_score,
-                                        })
+                                        .map(context_search::RelatedContext::from)
                                         .collect();
                                 }
 
@@ -733,6 +860,9 @@ impl SearchService {
                                         // Will be replaced by fused normalization below.
                                         score: 0.0,
                                         id: hit.chunk_id.clone(),
+                                        freshness: context_search
+                                            .hybrid()
+                                            .chunk_freshness(chunk_idx),
                                     },
                                     related,
                                     total_lines,
@@ -762,17 +892,7 @@ impl SearchService {
                                 }
                             }
 
-                            enriched_results.sort_by(|a, b| {
-                                b.primary
-                                    .score
-                                    .total_cmp(&a.primary.score)
-                                    .then_with(|| {
-                                        a.primary.chunk.file_path.cmp(&b.primary.chunk.file_path)
-                                    })
-                                    .then_with(|| {
-                                        a.primary.chunk.start_line.cmp(&b.primary.chunk.start_line)
-                                    })
-                            });
+                            enriched_results.sort_by(|a, b| pack_order(&a.primary, &b.primary));
                             enriched_results.truncate(candidate_limit);
                         }
                     }
@@ -786,11 +906,12 @@ impl SearchService {
         let enriched_results =
             prepare_context_pack_enriched(enriched_results, limit, prefer_code, include_docs);
 
-        let (items, budget, filtered_out) = pack_enriched_results(
+        let (items, budget, omitted, filtered_out, merge_spans_dropped) = pack_enriched_results(
             enriched_results,
             &project_ctx.profile,
-            max_chars,
+            PackBudget::new(max_chars, max_tokens),
             max_related_per_primary,
+            payload.related_budget.as_ref(),
             &request_options,
             related_mode,
             &query_tokens,
@@ -805,11 +926,24 @@ impl SearchService {
             profile: project_ctx.profile_name.clone(),
             items,
             budget,
+            omitted,
+            oldest_result_ms: None,
             next_actions: Vec::new(),
-            meta: context_indexer::ToolMeta { index_state: None },
+            meta: context_indexer::ToolMeta::default(),
         };
+        output.oldest_result_ms = oldest_indexed_at_ms(&output.items);
         enforce_context_pack_budget(&mut output)?;
 
+        let graph_actions = match context_search.assembler() {
+            Some(assembler) if max_graph_actions > 0 => graph_next_actions(
+                assembler,
+                &payload.related_budget.clone().unwrap_or_default(),
+                &output.items,
+                max_graph_actions,
+            ),
+            _ => Vec::new(),
+        };
+
         let debug_hints = if trace {
             let query_kind = match query_type {
                 QueryType::Identifier => QueryKind::Identifier,
@@ -864,10 +998,18 @@ impl SearchService {
                 Hint {
                     kind: HintKind::Info,
                     text: format!(
-                        "debug: pack items={} chars={}/{} truncated={} dropped={}",
+                        "debug: pack items={} chars={}/{} tokens={}/{} truncated={} dropped={}",
                         output.items.len(),
                         output.budget.used_chars,
                         output.budget.max_chars,
+                        output
+                            .budget
+                            .used_tokens
+                            .map_or_else(|| "-".to_string(), |t| t.to_string()),
+                        output
+                            .budget
+                            .max_tokens
+                            .map_or_else(|| "-".to_string(), |t| t.to_string()),
                         output.budget.truncated,
                         output.budget.dropped_items
                     ),
@@ -888,21 +1030,37 @@ impl SearchService {
 
         let budget_truncated = output.budget.truncated;
         let next_max_chars = output.budget.max_chars.saturating_mul(2).min(500_000);
-        let retry_action = ToolNextAction {
-            tool: "context_pack".to_string(),
-            args: serde_json::json!({
-                "project": project_root,
-                "query": query,
-                "max_chars": next_max_chars
-            }),
-            reason: "Retry context_pack with a larger max_chars budget.".to_string(),
+        let mut retry_args = serde_json::json!({
+            "project": project_root,
+            "query": query,
+            "max_chars": next_max_chars
+        });
+        let retry_reason = match output.budget.max_tokens {
+            Some(max_tokens) => {
+                retry_args["max_tokens"] = serde_json::json!(max_tokens.saturating_mul(2));
+                "Retry context_pack with larger max_chars/max_tokens budgets."
+            }
+            None => "Retry context_pack with a larger max_chars budget.",
         };
+        let retry_action = ToolNextAction::new("context_pack", retry_args, retry_reason);
         if budget_truncated {
             output.next_actions.push(retry_action.clone());
         }
+        let changed_hint = changed_since_indexed_hint(
+            &project_ctx.root,
+            output
+                .items
+                .iter()
+                .map(|item| (item.file.as_str(), item.indexed_at_ms)),
+        );
         let mut outcome = CommandOutcome::from_value(output)?;
         outcome.hints.extend(debug_hints);
+        outcome.hints.extend(changed_hint);
         outcome.meta.graph_cache = Some(graph_cache_used);
+        outcome.meta.graph_cache_size_bytes = graph_cache.size_bytes().await;
+        let graph_cache_stats = graph_cache_stats();
+        outcome.meta.graph_cache_hit_count = Some(graph_cache_stats.hits);
+        outcome.meta.graph_cache_miss_count = Some(graph_cache_stats.misses);
         outcome.meta.config_path = project_ctx.config_path;
         outcome.meta.profile = Some(project_ctx.profile_name.clone());
         outcome.meta.profile_path = project_ctx.profile_path.clone();
@@ -915,6 +1073,10 @@ impl SearchService {
         outcome.meta.timing_load_index_ms = Some(timing_load_index_ms);
         outcome.meta.timing_graph_ms = Some(timing_graph_ms);
         outcome.meta.timing_search_ms = Some(timing_search_ms);
+        outcome.meta.query_embedding_cache_hits =
+            (!model_timings.is_empty()).then_some(query_embedding_cache_hits);
+        outcome.meta.model_timings = (!model_timings.is_empty()).then_some(model_timings);
+        outcome.meta.merge_spans_dropped = Some(merge_spans_dropped);
         if let Some(hint) = strategy_hint {
             outcome.hints.push(Hint {
                 kind: HintKind::Info,
@@ -927,6 +1089,9 @@ impl SearchService {
                 text: hint,
             });
         }
+        if let Some(hint) = scope_hint {
+            outcome.hints.push(hint);
+        }
         if crate::command::path_filters::is_active(&request_options) && filtered_out > 0 {
             outcome.hints.push(Hint {
                 kind: HintKind::Info,
@@ -937,7 +1102,7 @@ impl SearchService {
             outcome.next_actions.push(retry_action);
         }
         self.health.attach(&project_ctx.root, &mut outcome).await;
-        Ok(outcome)
+        Ok((outcome, graph_actions))
     }
 
     pub async fn task_pack(&self, payload: Value, ctx: &CommandContext) -> Result<CommandOutcome> {
@@ -952,31 +1117,40 @@ impl SearchService {
             project: payload.project,
             strategy: payload.strategy,
             max_chars: payload.max_chars,
+            max_tokens: None,
             max_related_per_primary: payload.max_related_per_primary,
+            related_budget: payload.related_budget,
             prefer_code: payload.prefer_code,
             include_docs: payload.include_docs,
             related_mode: payload.related_mode,
             trace: payload.trace,
             language: payload.language,
             reuse_graph: payload.reuse_graph,
+            models: Vec::new(),
         };
 
-        let mut outcome = self
-            .context_pack(serde_json::to_value(ctx_payload)?, ctx)
+        let (mut outcome, graph_actions) = self
+            .build_context_pack(ctx_payload, ctx, MAX_GRAPH_ACTIONS)
             .await?;
 
         let pack: ContextPackOutput = serde_json::from_value(outcome.data.clone())
             .context("Invalid context_pack output (expected ContextPackOutput)")?;
 
-        let task_pack = build_task_pack(&payload.intent, pack);
+        let task_pack = build_task_pack(&payload.intent, pack, graph_actions);
         outcome.data = serde_json::to_value(task_pack)?;
         Ok(outcome)
     }
 }
 
-fn build_task_pack(intent: &str, pack: ContextPackOutput) -> TaskPackOutput {
-    let mut primary_files = Vec::new();
-    let mut seen = HashSet::new();
+/// Graph-derived next actions a task pack adds on top of the heuristic ones.
+const MAX_GRAPH_ACTIONS: usize = 6;
+
+fn build_task_pack(
+    intent: &str,
+    pack: ContextPackOutput,
+    graph_actions: Vec<NextAction>,
+) -> TaskPackOutput {
+    let mut primary_files: Vec<(String, f32)> = Vec::new();
     let mut primary = 0usize;
     let mut related = 0usize;
 
@@ -988,8 +1162,14 @@ fn build_task_pack(intent: &str, pack: ContextPackOutput) -> TaskPackOutput {
                 "primary" => primary += 1,
                 _ => related += 1,
             }
-            if item.role == "primary" && seen.insert(item.file.clone()) {
-                primary_files.push(item.file.clone());
+            if item.role == "primary" {
+                match primary_files
+                    .iter_mut()
+                    .find(|(file, _)| *file == item.file)
+                {
+                    Some((_, best)) => *best = best.max(item.score),
+                    None => primary_files.push((item.file.clone(), item.score)),
+                }
             }
             TaskPackItem {
                 why: explain_pack_item(&item),
@@ -999,7 +1179,11 @@ fn build_task_pack(intent: &str, pack: ContextPackOutput) -> TaskPackOutput {
         .collect();
 
     let digest = {
-        let files = primary_files.iter().take(3).cloned().collect::<Vec<_>>();
+        let files = primary_files
+            .iter()
+            .take(3)
+            .map(|(file, _)| file.clone())
+            .collect::<Vec<_>>();
         let files_hint = if files.is_empty() {
             String::new()
         } else {
@@ -1014,16 +1198,8 @@ fn build_task_pack(intent: &str, pack: ContextPackOutput) -> TaskPackOutput {
         ))
     };
 
-    let mut next_actions = Vec::new();
-    for file in primary_files.into_iter().take(3) {
-        next_actions.push(NextAction {
-            kind: NextActionKind::OpenFile,
-            reason: "Inspect primary context".to_string(),
-            file: Some(file),
-            command: None,
-            query: None,
-        });
-    }
+    let mut next_actions = rank_next_actions(intent, &items, &primary_files);
+    merge_graph_actions(&mut next_actions, graph_actions);
 
     TaskPackOutput {
         version: TASK_PACK_VERSION,
@@ -1037,6 +1213,115 @@ fn build_task_pack(intent: &str, pack: ContextPackOutput) -> TaskPackOutput {
     }
 }
 
+/// Follow-ups for a task pack, best first. Opening a primary file scores at most 0.5; an
+/// `impact` or `grep_context` suggestion outranks it when the pack leaves a visible gap:
+/// results clustered on one symbol (its callers are likely missing) or intent terms that no
+/// item contains (lexical recall looks low).
+fn rank_next_actions(
+    intent: &str,
+    items: &[TaskPackItem],
+    primary_files: &[(String, f32)],
+) -> Vec<NextAction> {
+    let mut actions: Vec<NextAction> = primary_files
+        .iter()
+        .take(3)
+        .map(|(file, score)| NextAction {
+            kind: NextActionKind::OpenFile,
+            reason: "Inspect primary context".to_string(),
+            score: 0.5 * score.clamp(0.0, 1.0),
+            file: Some(file.clone()),
+            start_line: None,
+            end_line: None,
+            command: None,
+            query: None,
+        })
+        .collect();
+
+    let mut symbol_counts: HashMap<&str, usize> = HashMap::new();
+    for entry in items {
+        if let Some(symbol) = entry.item.symbol.as_deref().filter(|s| !s.is_empty()) {
+            *symbol_counts.entry(symbol).or_default() += 1;
+        }
+    }
+    if let Some((symbol, count)) = symbol_counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+    {
+        #[allow(clippy::cast_precision_loss)]
+        let share = count as f32 / items.len() as f32;
+        if count >= 2 && share >= 0.5 {
+            actions.push(NextAction {
+                kind: NextActionKind::Impact,
+                reason: format!(
+                    "{count} of {} items center on `{symbol}`; trace its callers and dependents",
+                    items.len()
+                ),
+                score: 0.5 + 0.5 * share,
+                file: None,
+                start_line: None,
+                end_line: None,
+                command: None,
+                query: Some(symbol.to_string()),
+            });
+        }
+    }
+
+    let tokens = tokenize_focus_query(intent);
+    if !tokens.is_empty() {
+        let lowered: Vec<String> = items
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{}\n{}\n{}",
+                    entry.item.file,
+                    entry.item.symbol.as_deref().unwrap_or_default(),
+                    entry.item.content
+                )
+                .to_ascii_lowercase()
+            })
+            .collect();
+        let missing: Vec<&str> = tokens
+            .iter()
+            .filter(|token| !lowered.iter().any(|text| text.contains(token.as_str())))
+            .map(String::as_str)
+            .collect();
+        #[allow(clippy::cast_precision_loss)]
+        let missing_share = missing.len() as f32 / tokens.len() as f32;
+        if missing_share >= 0.5 {
+            actions.push(NextAction {
+                kind: NextActionKind::GrepContext,
+                reason: format!("Intent terms missing from the pack: {}", missing.join(", ")),
+                score: 0.5 + 0.5 * missing_share,
+                file: None,
+                start_line: None,
+                end_line: None,
+                command: None,
+                query: Some(missing.join("|")),
+            });
+        }
+    }
+
+    actions.sort_by(|a, b| b.score.total_cmp(&a.score));
+    actions
+}
+
+/// Add graph-derived actions, skipping files and impact targets already suggested, and keep
+/// the list sorted best first (ties keep their order).
+fn merge_graph_actions(actions: &mut Vec<NextAction>, graph_actions: Vec<NextAction>) {
+    for action in graph_actions {
+        let duplicate = actions.iter().any(|existing| {
+            (action.file.is_some() && existing.file == action.file)
+                || (action.query.is_some()
+                    && existing.query == action.query
+                    && matches!(existing.kind, NextActionKind::Impact))
+        });
+        if !duplicate {
+            actions.push(action);
+        }
+    }
+    actions.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
 fn explain_pack_item(item: &ContextPackItem) -> Vec<String> {
     let mut why = Vec::new();
     if item.role == "primary" {
@@ -1220,6 +1505,7 @@ fn prepare_related_contexts(
             .then_with(|| a.distance.cmp(&b.distance))
             .then_with(|| a.chunk.file_path.cmp(&b.chunk.file_path))
             .then_with(|| a.chunk.start_line.cmp(&b.chunk.start_line))
+            .then_with(|| a.chunk.end_line.cmp(&b.chunk.end_line))
     };
 
     match related_mode {
@@ -1269,6 +1555,7 @@ fn prepare_related_contexts(
                     .then_with(|| a.1.distance.cmp(&b.1.distance))
                     .then_with(|| a.1.chunk.file_path.cmp(&b.1.chunk.file_path))
                     .then_with(|| a.1.chunk.start_line.cmp(&b.1.chunk.start_line))
+                    .then_with(|| a.1.chunk.end_line.cmp(&b.1.chunk.end_line))
             });
 
             combined.into_iter().map(|(_, rc)| rc).collect()
@@ -1311,9 +1598,7 @@ fn prepare_context_pack_enriched(
         let b_kind = classify_path_kind(&b.primary.chunk.file_path);
         kind_rank(a_kind, prefer_code)
             .cmp(&kind_rank(b_kind, prefer_code))
-            .then_with(|| b.primary.score.total_cmp(&a.primary.score))
-            .then_with(|| a.primary.chunk.file_path.cmp(&b.primary.chunk.file_path))
-            .then_with(|| a.primary.chunk.start_line.cmp(&b.primary.chunk.start_line))
+            .then_with(|| pack_order(&a.primary, &b.primary))
     });
 
     if !include_docs {
@@ -1327,19 +1612,28 @@ fn prepare_context_pack_enriched(
     enriched
 }
 
+#[allow(clippy::too_many_arguments)]
 fn pack_enriched_results(
     enriched: Vec<EnrichedResult>,
     profile: &SearchProfile,
-    max_chars: usize,
+    mut budget: PackBudget,
     max_related_per_primary: usize,
+    related_budget: Option<&AssemblyBudget>,
     request_options: &crate::command::domain::RequestOptions,
     related_mode: RelatedMode,
     query_tokens: &[String],
-) -> (Vec<ContextPackItem>, ContextPackBudget, usize) {
-    let mut used_chars = 0usize;
-    let mut truncated = false;
+) -> (
+    Vec<ContextPackItem>,
+    ContextPackBudget,
+    Vec<ContextPackOmitted>,
+    usize,
+    usize,
+) {
+    let mut truncation: Option<BudgetTruncation> = None;
     let mut dropped_items = 0usize;
+    let mut omitted = OmittedItems::default();
     let mut filtered_out = 0usize;
+    let mut merge_spans_dropped = 0usize;
 
     let mut items: Vec<ContextPackItem> = Vec::new();
     let mut seen: HashSet<String> = HashSet::new();
@@ -1372,15 +1666,29 @@ fn pack_enriched_results(
             content: primary.chunk.content,
             relationship: None,
             distance: None,
+            bucket: None,
+            indexed_at_ms: primary.freshness.map(|f| f.indexed_at_ms),
+            file_mtime_ms: primary.freshness.map(|f| f.file_mtime_ms),
         };
-        let cost = estimate_item_chars(&primary_item);
-        if used_chars.saturating_add(cost) > max_chars {
-            truncated = true;
-            dropped_items += 1;
-            break;
+        if truncation.is_some() {
+            // Past the budget: only record which primaries were left out.
+            if find_overlapping_item(&items, &primary_item).is_none() {
+                omitted.push(&primary_item);
+            }
+            continue;
+        }
+        match merge_overlapping_span(&mut items, primary_item, &mut budget) {
+            None => merge_spans_dropped += 1,
+            Some(primary_item) => {
+                if let Err(limit) = budget.try_add(&primary_item) {
+                    truncation = Some(limit);
+                    dropped_items += 1;
+                    omitted.push(&primary_item);
+                    continue;
+                }
+                items.push(primary_item);
+            }
         }
-        used_chars += cost;
-        items.push(primary_item);
 
         let mut related = er.related;
         related.retain(|rc| !profile.is_rejected(&rc.chunk.file_path));
@@ -1403,10 +1711,17 @@ fn pack_enriched_results(
             }
         };
 
+        // An explicit budget replaces the single per-primary knob; buckets it doesn't
+        // mention keep the built-in caps. Like `AssemblyBudget::apply`, a budget without
+        // `max_related` leaves the total bounded by its buckets alone.
+        let max_related = related_budget.map_or(max_related_per_primary, |budget| {
+            budget.max_related.unwrap_or(usize::MAX)
+        });
+
         let mut selected_related = 0usize;
         let mut per_relationship: HashMap<String, usize> = HashMap::new();
         for rc in related {
-            if selected_related >= max_related_per_primary {
+            if selected_related >= max_related {
                 break;
             }
 
@@ -1415,7 +1730,13 @@ fn pack_enriched_results(
                 .first()
                 .cloned()
                 .unwrap_or_else(String::new);
-            let cap = relationship_cap(&kind);
+            if related_budget.is_some_and(|budget| !budget.allows_distance(rc.distance)) {
+                continue;
+            }
+            let cap = related_budget
+                .zip(kind.parse::<RelationshipType>().ok())
+                .and_then(|(budget, bucket)| budget.cap_for(bucket))
+                .unwrap_or_else(|| relationship_cap(&kind));
             let used = per_relationship.get(kind.as_str()).copied().unwrap_or(0);
             if used >= cap {
                 continue;
@@ -1446,58 +1767,66 @@ fn pack_enriched_results(
                 content: rc.chunk.content,
                 relationship: Some(rc.relationship_path),
                 distance: Some(rc.distance),
+                bucket: related_budget.map(|_| kind.clone()),
+                indexed_at_ms: None,
+                file_mtime_ms: None,
+            };
+            let Some(item) = merge_overlapping_span(&mut items, item, &mut budget) else {
+                merge_spans_dropped += 1;
+                continue;
             };
 
-            let cost = estimate_item_chars(&item);
-            if used_chars.saturating_add(cost) > max_chars {
-                truncated = true;
+            if let Err(limit) = budget.try_add(&item) {
+                truncation = Some(limit);
                 dropped_items += 1;
+                omitted.push(&item);
                 break;
             }
-            used_chars += cost;
             items.push(item);
             *per_relationship.entry(kind).or_insert(0) += 1;
             selected_related += 1;
         }
-
-        if truncated {
-            break;
-        }
     }
 
+    let omitted_items = omitted.count();
     (
         items,
-        ContextPackBudget {
-            max_chars,
-            used_chars,
-            truncated,
-            dropped_items,
-            truncation: truncated.then_some(BudgetTruncation::MaxChars),
-        },
+        budget.finish(truncation, dropped_items, omitted_items),
+        omitted.into_stubs(),
         filtered_out,
+        merge_spans_dropped,
     )
 }
 
+/// Drop the least important bucketed item, or the tail when nothing is bucketed, leaving a stub
+/// in its place; once no item is left, drop the stubs themselves.
+fn shrink_pack(output: &mut ContextPackOutput) -> bool {
+    let idx =
+        least_important_bucketed_item(&output.items).or_else(|| output.items.len().checked_sub(1));
+    match idx {
+        Some(idx) => {
+            output.omit_item(idx);
+            true
+        }
+        None => output.omitted.pop().is_some(),
+    }
+}
+
+fn mark_truncated(output: &mut ContextPackOutput, reason: BudgetTruncation) {
+    output.budget.truncated = true;
+    if output.budget.truncation.is_none() {
+        output.budget.truncation = Some(reason);
+    }
+}
+
 fn enforce_context_pack_budget(output: &mut ContextPackOutput) -> Result<()> {
     let max_chars = output.budget.max_chars;
     let used = enforce_max_chars(
         output,
         max_chars,
         |inner, used| inner.budget.used_chars = used,
-        |inner| {
-            inner.budget.truncated = true;
-            if inner.budget.truncation.is_none() {
-                inner.budget.truncation = Some(BudgetTruncation::MaxChars);
-            }
-        },
-        |inner| {
-            if !inner.items.is_empty() {
-                inner.items.pop();
-                inner.budget.dropped_items += 1;
-                return true;
-            }
-            false
-        },
+        |inner| mark_truncated(inner, BudgetTruncation::MaxChars),
+        shrink_pack,
     )
     .map_err(|_| {
         let min_chars = finalize_used_chars(output, |inner, used| inner.budget.used_chars = used)
@@ -1505,12 +1834,43 @@ fn enforce_context_pack_budget(output: &mut ContextPackOutput) -> Result<()> {
         anyhow!("max_chars too small for context_pack response (min_chars={min_chars})")
     })?;
     output.budget.used_chars = used;
+
+    let Some(max_tokens) = output.budget.max_tokens else {
+        return Ok(());
+    };
+    enforce_max_tokens(
+        output,
+        max_tokens,
+        |inner, used| inner.budget.used_tokens = Some(used),
+        |inner| mark_truncated(inner, BudgetTruncation::MaxTokens),
+        shrink_pack,
+    )
+    .map_err(|_| {
+        let min_tokens =
+            finalize_used_tokens(output, |inner, used| inner.budget.used_tokens = Some(used))
+                .unwrap_or_default();
+        anyhow!("max_tokens too small for context_pack response (min_tokens={min_tokens})")
+    })?;
+    // Items dropped for tokens only shrink the output, so the char count just needs a refresh.
+    finalize_used_chars(output, |inner, used| inner.budget.used_chars = used)?;
     Ok(())
 }
 
-fn estimate_item_chars(item: &ContextPackItem) -> usize {
-    let imports: usize = item.imports.iter().map(|s| s.len() + 1).sum();
-    item.content.len() + imports + 128
+/// Related item from the least important budget bucket (latest on ties), if any item
+/// carries a bucket. Unbudgeted packs have none and fall back to dropping the tail.
+fn least_important_bucketed_item(items: &[ContextPackItem]) -> Option<usize> {
+    items
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| {
+            let bucket = item.bucket.as_deref()?;
+            let rank = bucket
+                .parse::<RelationshipType>()
+                .map_or(u8::MAX, RelationshipType::relevance_rank);
+            Some((rank, idx))
+        })
+        .max()
+        .map(|(_, idx)| idx)
 }
 
 async fn load_or_build_graph_nodes_store(
@@ -1581,9 +1941,12 @@ struct LoadedSemanticIndexes {
     index_size_bytes: Option<u64>,
 }
 
+/// Loads the primary index plus every profile expert that has been indexed. With an explicit
+/// `models` list only those models are loaded, and each of them must have an index.
 async fn load_semantic_indexes(
     root: &Path,
     profile: &SearchProfile,
+    models: &[String],
 ) -> Result<LoadedSemanticIndexes> {
     let store_path = index_path(root);
     ensure_index_exists(&store_path)?;
@@ -1591,11 +1954,31 @@ async fn load_semantic_indexes(
     let store_mtime = load_store_mtime(&store_path).await?;
     let index_size_bytes = tokio::fs::metadata(&store_path).await.ok().map(|m| m.len());
 
-    let default_model_id = current_model_id().unwrap_or_else(|_| "bge-small".to_string());
-
-    let mut requested: Vec<String> = Vec::new();
-    requested.push(default_model_id.clone());
-    requested.extend(semantic_model_roster(profile));
+    let explicit: Vec<String> = models
+        .iter()
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect();
+    let requested: Vec<String> = if explicit.is_empty() {
+        let default_model_id = current_model_id().unwrap_or_else(|_| "bge-small".to_string());
+        std::iter::once(default_model_id)
+            .chain(semantic_model_roster(profile))
+            .collect()
+    } else {
+        let missing: Vec<&str> = explicit
+            .iter()
+            .filter(|id| !index_path_for_model(root, id).exists())
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            let missing = missing.join(",");
+            return Err(anyhow!(
+                "Missing requested indices: {missing}. Run `context-finder index --models {missing}` first."
+            ));
+        }
+        explicit
+    };
 
     let mut sources = Vec::new();
     let mut seen = HashSet::new();
@@ -1628,6 +2011,86 @@ async fn load_semantic_indexes(
     })
 }
 
+/// Semantic index of a project with its code graph attached, as the graph actions use it.
+pub(super) struct GraphEngine {
+    pub(super) context_search: MultiModelContextSearch,
+    pub(super) language: GraphLanguage,
+    pub(super) graph_cache_used: bool,
+    pub(super) graph_cache_size_bytes: Option<u64>,
+    pub(super) store_mtime: SystemTime,
+    pub(super) index_size_bytes: Option<u64>,
+    pub(super) timing_load_index_ms: u64,
+    pub(super) timing_graph_ms: u64,
+}
+
+/// Loads the index under `root` and attaches its code graph, reusing the graph cache unless
+/// `reuse_graph` is false. Without an explicit `language` the graph is built for the language
+/// most chunks are written in.
+pub(super) async fn load_graph_engine(
+    graph: &GraphCacheFactory,
+    root: &Path,
+    profile: &SearchProfile,
+    language: Option<GraphLanguage>,
+    reuse_graph: bool,
+) -> Result<GraphEngine> {
+    let load_index_start = Instant::now();
+    let loaded = load_semantic_indexes(root, profile, &[])
+        .await
+        .context("Failed to load semantic indices")?;
+    let corpus = load_chunk_corpus(root).await?;
+    let hybrid = if let Some(corpus) = corpus {
+        MultiModelHybridSearch::from_env_with_corpus(loaded.sources, profile.clone(), corpus)
+    } else {
+        MultiModelHybridSearch::from_env(loaded.sources, profile.clone())
+    }
+    .context("Failed to create search engine")?;
+    let timing_load_index_ms = load_index_start.elapsed().as_millis() as u64;
+
+    let graph_start = Instant::now();
+    let language = language.unwrap_or_else(|| detect_language(hybrid.chunks()));
+    let graph_cache = graph.for_root(root);
+    let cached_assembler = if reuse_graph {
+        let chunk_lookup = build_chunk_lookup(hybrid.chunks());
+        graph_cache
+            .load(loaded.store_mtime, language, hybrid.chunks(), &chunk_lookup)
+            .await?
+    } else {
+        None
+    };
+
+    let mut context_search =
+        MultiModelContextSearch::new(hybrid).context("Failed to create context search")?;
+    let graph_cache_used = cached_assembler.is_some();
+    if let Some(assembler) = cached_assembler {
+        context_search.set_assembler(assembler);
+    } else {
+        context_search
+            .build_graph(language)
+            .context("Failed to build code graph")?;
+        if reuse_graph {
+            if let Some(assembler) = context_search.assembler() {
+                if let Err(err) = graph_cache
+                    .save(loaded.store_mtime, language, assembler)
+                    .await
+                {
+                    warn!("Failed to store graph cache: {err}");
+                }
+            }
+        }
+    }
+
+    Ok(GraphEngine {
+        context_search,
+        language,
+        graph_cache_used,
+        graph_cache_size_bytes: graph_cache.size_bytes().await,
+        store_mtime: loaded.store_mtime,
+        index_size_bytes: loaded.index_size_bytes,
+        timing_load_index_ms,
+        timing_graph_ms: graph_start.elapsed().as_millis() as u64,
+    })
+}
+
 async fn load_chunk_corpus(root: &Path) -> Result<Option<ChunkCorpus>> {
     let path = corpus_path_for_project_root(root);
     if !path.exists() {
@@ -1656,6 +2119,21 @@ fn semantic_model_roster(profile: &SearchProfile) -> Vec<String> {
     out
 }
 
+/// `requested`, else the dominant code language of `chunks`, the same way the MCP graph tools
+/// choose; `Err` names a dominant language without a graph builder, answered graph-less.
+fn resolve_graph_language(
+    requested: Option<GraphLanguage>,
+    chunks: &[context_code_chunker::CodeChunk],
+) -> std::result::Result<GraphLanguage, String> {
+    if let Some(language) = requested {
+        return Ok(language);
+    }
+    match detect_graph_language(chunks) {
+        DetectedLanguage::Supported(language) => Ok(language),
+        DetectedLanguage::Unsupported(name) => Err(name),
+    }
+}
+
 fn build_chunk_lookup(chunks: &[context_code_chunker::CodeChunk]) -> HashMap<String, usize> {
     let mut lookup = HashMap::new();
     for (idx, chunk) in chunks.iter().enumerate() {
@@ -1704,6 +2182,8 @@ pub(crate) fn format_basic_output(result: SearchResult) -> SearchResultOutput {
         related: None,
         graph: None,
         rationale: None,
+        indexed_at_ms: result.freshness.map(|f| f.indexed_at_ms),
+        file_mtime_ms: result.freshness.map(|f| f.file_mtime_ms),
     }
 }
 
@@ -1717,6 +2197,13 @@ pub(crate) fn format_enriched_output(
         ref related,
         ..
     } = enriched;
+    let primary_symbol = primary
+        .chunk
+        .metadata
+        .symbol_name
+        .as_deref()
+        .unwrap_or("unknown")
+        .to_string();
 
     let related_outputs: Option<Vec<RelatedCodeOutput>> = if related.is_empty() {
         None
@@ -1737,7 +2224,7 @@ pub(crate) fn format_enriched_output(
                     relationship: rc.relationship_path.clone(),
                     distance: rc.distance,
                     relevance: rc.relevance_score,
-                    graph_path: Some(rc.relationship_path.join(" -> ")),
+                    graph_path: Some(graph_path_label(&primary_symbol, rc)),
                     reason: Some("graph".to_string()),
                 })
                 .collect(),
@@ -1745,13 +2232,6 @@ pub(crate) fn format_enriched_output(
     };
 
     let graph = if show_graph && !related.is_empty() {
-        let primary_symbol = primary
-            .chunk
-            .metadata
-            .symbol_name
-            .as_deref()
-            .unwrap_or("unknown")
-            .to_string();
         Some(
             related
                 .iter()
@@ -1806,6 +2286,8 @@ pub(crate) fn format_enriched_output(
         related: related_outputs,
         graph,
         rationale,
+        indexed_at_ms: primary.freshness.map(|f| f.indexed_at_ms),
+        file_mtime_ms: primary.freshness.map(|f| f.file_mtime_ms),
     }
 }
 
@@ -1953,12 +2435,28 @@ fn annotate_reasons(query: &str, results: &mut [SearchResultOutput]) {
     }
 }
 
+/// Symbol chain from the primary hit ("main → handle → parse"); falls back to the edge kinds
+/// when the assembler could not resolve the hops to symbols.
+fn graph_path_label(primary_symbol: &str, rc: &RelatedContext) -> String {
+    if rc.symbol_path.is_empty() {
+        return rc.relationship_path.join(" -> ");
+    }
+    std::iter::once(primary_symbol)
+        .chain(rc.symbol_path.iter().map(String::as_str))
+        .join(" → ")
+}
+
 fn truncate_path(path: &str, max_segments: usize) -> String {
-    let parts: Vec<&str> = path.split(" -> ").collect();
+    let separator = if path.contains(" → ") {
+        " → "
+    } else {
+        " -> "
+    };
+    let parts: Vec<&str> = path.split(separator).collect();
     if parts.len() <= max_segments {
         path.to_string()
     } else {
-        let mut out = parts[..max_segments].join(" -> ");
+        let mut out = parts[..max_segments].join(separator);
         out.push_str(" …");
         out
     }
@@ -1981,8 +2479,8 @@ fn relation_priority(path: &[String]) -> std::cmp::Reverse<u8> {
     std::cmp::Reverse(score)
 }
 
-fn choose_strategy(query: &str) -> (SearchStrategy, Option<String>) {
-    let query_type = QueryClassifier::classify(query);
+fn choose_strategy(query: &str, profile: &SearchProfile) -> (SearchStrategy, Option<String>) {
+    let query_type = profile.classify_query(query);
     let docs_intent = QueryClassifier::is_docs_intent(query);
     if !docs_intent && matches!(query_type, QueryType::Identifier | QueryType::Path) {
         return (
@@ -2085,17 +2583,88 @@ pub(crate) fn overlap_ratio(
     }
 }
 
+/// Query expander extended with the project's `synonyms` dictionary, if configured
+fn configured_query_expander(config: &Option<Value>) -> Option<QueryExpander> {
+    config_synonyms(config).map(QueryExpander::with_synonyms)
+}
+
+/// Restrict `search` to the request's path filters, returning a hint describing the scope when
+/// any filter is set. Filters intersect with the profile's own rejection rules.
+fn apply_path_scope(
+    search: &mut MultiModelHybridSearch,
+    options: &crate::command::domain::RequestOptions,
+) -> Option<Hint> {
+    if !crate::command::path_filters::is_active(options) {
+        return None;
+    }
+    let remaining =
+        search.restrict_paths(|path| crate::command::path_filters::path_allowed(path, options));
+    Some(if remaining == 0 {
+        Hint {
+            kind: HintKind::Warn,
+            text: "Path filters match no indexed chunks".to_string(),
+        }
+    } else {
+        Hint {
+            kind: HintKind::Info,
+            text: format!("Path filters restrict search to {remaining} indexed chunks"),
+        }
+    })
+}
+
+/// Drop related chunks outside the request's path filters so the graph halo stays in scope.
+/// Returns how many were removed.
+fn retain_related_in_scope(
+    results: &mut [EnrichedResult],
+    options: &crate::command::domain::RequestOptions,
+) -> usize {
+    if !crate::command::path_filters::is_active(options) {
+        return 0;
+    }
+    let mut removed = 0usize;
+    for er in results {
+        let before = er.related.len();
+        er.related
+            .retain(|rc| crate::command::path_filters::path_allowed(&rc.chunk.file_path, options));
+        removed += before - er.related.len();
+    }
+    removed
+}
+
 pub(crate) fn key_for(result: &SearchResultOutput) -> String {
     format!("{}:{}:{}", result.file, result.start_line, result.end_line)
 }
 
+/// Keys of `results` that are not in `other`, in rank order.
+pub(crate) fn keys_missing_from(
+    results: &[SearchResultOutput],
+    other: &HashSet<String>,
+) -> Vec<String> {
+    results
+        .iter()
+        .map(key_for)
+        .filter(|key| !other.contains(key))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{pack_enriched_results, prepare_context_pack_enriched, RelatedMode};
+    use super::{
+        choose_strategy, format_basic_output, key_for, keys_missing_from,
+        least_important_bucketed_item, pack_enriched_results, prepare_context_pack_enriched,
+        rank_next_actions, resolve_graph_language, retain_related_in_scope, RelatedMode,
+    };
+    use crate::command::domain::SearchStrategy;
     use context_code_chunker::{ChunkMetadata, CodeChunk};
-    use context_graph::AssemblyStrategy;
-    use context_search::{EnrichedResult, RelatedContext, SearchProfile};
+    use context_graph::{AssemblyBudget, AssemblyStrategy, GraphLanguage, RelationshipType};
+    use context_protocol::BudgetTruncation;
+    use context_search::{
+        estimate_item_chars, parse_query, ContextPackItem, EnrichedResult, NextActionKind,
+        PackBudget, QueryClassifier, RelatedContext, SearchProfile, TaskPackItem,
+        MAX_OMITTED_STUBS,
+    };
     use context_vector_store::SearchResult;
+    use std::collections::{HashMap, HashSet};
 
     fn chunk(path: &str, line: usize, content: &str) -> CodeChunk {
         CodeChunk::new(
@@ -2116,12 +2685,14 @@ mod tests {
             id: "src/main.rs:1:1".to_string(),
             chunk: primary_chunk,
             score: 1.0,
+            freshness: None,
         };
 
         let related: Vec<RelatedContext> = (0..5)
             .map(|idx| RelatedContext {
                 chunk: chunk(&format!("src/imp{idx}.rs"), 1, "use x;"),
                 relationship_path: vec!["Imports".to_string()],
+                symbol_path: Vec::new(),
                 distance: 1,
                 relevance_score: 10.0 - idx as f32,
             })
@@ -2136,11 +2707,12 @@ mod tests {
 
         let request_options = crate::command::domain::RequestOptions::default();
         let query_tokens = Vec::new();
-        let (items, budget, _filtered_out) = pack_enriched_results(
+        let (items, budget, _omitted, _filtered_out, _merged) = pack_enriched_results(
             enriched,
             &profile,
-            50_000,
+            PackBudget::new(50_000, None),
             100,
+            None,
             &request_options,
             RelatedMode::Explore,
             &query_tokens,
@@ -2159,6 +2731,350 @@ mod tests {
         assert_eq!(related_ids[1], "src/imp1.rs:1:1");
     }
 
+    #[test]
+    fn packer_related_budget_replaces_default_caps() {
+        let profile = SearchProfile::general();
+
+        let primary = SearchResult {
+            id: "src/main.rs:1:1".to_string(),
+            chunk: chunk("src/main.rs", 1, "fn main() {}"),
+            score: 1.0,
+            freshness: None,
+        };
+
+        let mut related: Vec<RelatedContext> = (0..5)
+            .map(|idx| RelatedContext {
+                chunk: chunk(&format!("src/imp{idx}.rs"), 1, "use x;"),
+                relationship_path: vec!["Imports".to_string()],
+                symbol_path: Vec::new(),
+                distance: 1,
+                relevance_score: 10.0 - idx as f32,
+            })
+            .collect();
+        related.push(RelatedContext {
+            chunk: chunk("src/far.rs", 1, "fn far() {}"),
+            relationship_path: vec!["Calls".to_string(), "Calls".to_string()],
+            symbol_path: Vec::new(),
+            distance: 2,
+            relevance_score: 20.0,
+        });
+
+        let enriched = vec![EnrichedResult {
+            primary,
+            related,
+            total_lines: 1,
+            strategy: AssemblyStrategy::Extended,
+        }];
+
+        let budget = AssemblyBudget {
+            per_relationship: HashMap::from([(RelationshipType::Imports, 4)]),
+            max_related: Some(3),
+            max_distance: Some(1),
+        };
+        let request_options = crate::command::domain::RequestOptions::default();
+        let query_tokens = Vec::new();
+        let (items, _budget, _omitted, _filtered_out, _merged) = pack_enriched_results(
+            enriched,
+            &profile,
+            PackBudget::new(50_000, None),
+            1,
+            Some(&budget),
+            &request_options,
+            RelatedMode::Explore,
+            &query_tokens,
+        );
+
+        let related: Vec<(&str, Option<&str>)> = items
+            .iter()
+            .filter(|i| i.role == "related")
+            .map(|i| (i.file.as_str(), i.bucket.as_deref()))
+            .collect();
+        // Budget lifts the built-in Imports cap (2) and the per-primary knob (1),
+        // while max_distance drops the distance-2 call.
+        assert_eq!(
+            related,
+            vec![
+                ("src/imp0.rs", Some("Imports")),
+                ("src/imp1.rs", Some("Imports")),
+                ("src/imp2.rs", Some("Imports")),
+            ]
+        );
+    }
+
+    #[test]
+    fn packer_related_budget_without_max_related_ignores_per_primary_knob() {
+        let profile = SearchProfile::general();
+
+        let primary = SearchResult {
+            id: "src/main.rs:1:1".to_string(),
+            chunk: chunk("src/main.rs", 1, "fn main() {}"),
+            score: 1.0,
+            freshness: None,
+        };
+        let related: Vec<RelatedContext> = (0..5)
+            .map(|idx| RelatedContext {
+                chunk: chunk(&format!("src/imp{idx}.rs"), 1, "use x;"),
+                relationship_path: vec!["Imports".to_string()],
+                symbol_path: Vec::new(),
+                distance: 1,
+                relevance_score: 10.0 - idx as f32,
+            })
+            .collect();
+        let enriched = vec![EnrichedResult {
+            primary,
+            related,
+            total_lines: 1,
+            strategy: AssemblyStrategy::Extended,
+        }];
+
+        let budget = AssemblyBudget {
+            per_relationship: HashMap::from([(RelationshipType::Imports, 4)]),
+            ..AssemblyBudget::default()
+        };
+        let request_options = crate::command::domain::RequestOptions::default();
+        let query_tokens = Vec::new();
+        let (items, _budget, _omitted, _filtered_out, _merged) = pack_enriched_results(
+            enriched,
+            &profile,
+            PackBudget::new(50_000, None),
+            1,
+            Some(&budget),
+            &request_options,
+            RelatedMode::Explore,
+            &query_tokens,
+        );
+
+        let related = items.iter().filter(|i| i.role == "related").count();
+        assert_eq!(related, 4);
+    }
+
+    fn span_chunk(path: &str, start_line: usize, end_line: usize) -> CodeChunk {
+        let content = (start_line..=end_line)
+            .map(|line| format!("line {line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        CodeChunk::new(
+            path.to_string(),
+            start_line,
+            end_line,
+            content,
+            ChunkMetadata::default(),
+        )
+    }
+
+    fn calls(chunk: CodeChunk, relevance_score: f32) -> RelatedContext {
+        RelatedContext {
+            chunk,
+            relationship_path: vec!["Calls".to_string()],
+            symbol_path: Vec::new(),
+            distance: 1,
+            relevance_score,
+        }
+    }
+
+    #[test]
+    fn packer_merges_related_spans_overlapping_packed_items() {
+        let profile = SearchProfile::general();
+        let primary = SearchResult {
+            id: "src/main.rs:1:10".to_string(),
+            chunk: span_chunk("src/main.rs", 1, 10),
+            score: 1.0,
+            freshness: None,
+        };
+        let second = SearchResult {
+            id: "src/lib.rs:3:5".to_string(),
+            chunk: span_chunk("src/lib.rs", 3, 5),
+            score: 0.8,
+            freshness: None,
+        };
+        let enriched = vec![
+            EnrichedResult {
+                primary,
+                related: vec![
+                    calls(span_chunk("src/main.rs", 3, 5), 9.0),
+                    calls(span_chunk("src/other.rs", 1, 2), 8.0),
+                ],
+                total_lines: 10,
+                strategy: AssemblyStrategy::Extended,
+            },
+            EnrichedResult {
+                primary: second,
+                related: vec![calls(span_chunk("src/lib.rs", 1, 20), 7.0)],
+                total_lines: 3,
+                strategy: AssemblyStrategy::Extended,
+            },
+        ];
+
+        let request_options = crate::command::domain::RequestOptions::default();
+        let (items, budget, _omitted, _filtered_out, merged) = pack_enriched_results(
+            enriched,
+            &profile,
+            PackBudget::new(50_000, None),
+            3,
+            None,
+            &request_options,
+            RelatedMode::Explore,
+            &[],
+        );
+
+        let spans: Vec<(&str, &str, usize, usize)> = items
+            .iter()
+            .map(|i| (i.role.as_str(), i.file.as_str(), i.start_line, i.end_line))
+            .collect();
+        // The contained sub-span is dropped; the larger related span replaces the smaller
+        // primary but keeps its primary role.
+        assert_eq!(
+            spans,
+            vec![
+                ("primary", "src/main.rs", 1, 10),
+                ("related", "src/other.rs", 1, 2),
+                ("primary", "src/lib.rs", 1, 20),
+            ]
+        );
+        assert_eq!(merged, 2);
+        let expected_chars: usize = items.iter().map(estimate_item_chars).sum();
+        assert_eq!(budget.used_chars, expected_chars);
+    }
+
+    #[test]
+    fn token_budget_packs_no_more_than_char_budget_on_dense_content() {
+        let profile = SearchProfile::general();
+        // Punctuation-heavy code costs roughly one token per char, far above chars / 4.
+        let dense = "a[i]=(b+c)*d;".repeat(20);
+        let enriched = || -> Vec<EnrichedResult> {
+            (0..10)
+                .map(|idx| {
+                    let path = format!("src/dense{idx}.rs");
+                    EnrichedResult {
+                        primary: SearchResult {
+                            id: format!("{path}:1:1"),
+                            chunk: chunk(&path, 1, &dense),
+                            score: 1.0 - idx as f32 * 0.01,
+                            freshness: None,
+                        },
+                        related: Vec::new(),
+                        total_lines: 1,
+                        strategy: AssemblyStrategy::Direct,
+                    }
+                })
+                .collect()
+        };
+        let request_options = crate::command::domain::RequestOptions::default();
+        let max_chars = 2_000;
+
+        let (by_chars, chars_budget, _, _, _) = pack_enriched_results(
+            enriched(),
+            &profile,
+            PackBudget::new(max_chars, None),
+            0,
+            None,
+            &request_options,
+            RelatedMode::Explore,
+            &[],
+        );
+        let (by_tokens, tokens_budget, _, _, _) = pack_enriched_results(
+            enriched(),
+            &profile,
+            PackBudget::new(max_chars, Some(max_chars / 4)),
+            0,
+            None,
+            &request_options,
+            RelatedMode::Explore,
+            &[],
+        );
+
+        assert!(!by_tokens.is_empty());
+        assert!(by_tokens.len() < by_chars.len());
+        assert_eq!(tokens_budget.truncation, Some(BudgetTruncation::MaxTokens));
+        assert!(tokens_budget
+            .used_tokens
+            .is_some_and(|used| used <= max_chars / 4));
+        assert_eq!(chars_budget.truncation, Some(BudgetTruncation::MaxChars));
+        assert!(chars_budget.used_tokens.is_none());
+    }
+
+    #[test]
+    fn truncated_pack_stubs_candidates_left_out_in_rank_order() {
+        let profile = SearchProfile::general();
+        let body = "fn body() {}\n".repeat(20);
+        let mut enriched: Vec<EnrichedResult> = (0..12)
+            .map(|idx| {
+                let path = format!("src/m{idx:02}.rs");
+                EnrichedResult {
+                    primary: SearchResult {
+                        id: format!("{path}:1:1"),
+                        chunk: chunk(&path, 1, &body),
+                        score: 0.5,
+                        freshness: None,
+                    },
+                    related: Vec::new(),
+                    total_lines: 1,
+                    strategy: AssemblyStrategy::Direct,
+                }
+            })
+            .collect();
+        enriched.reverse();
+        let enriched = prepare_context_pack_enriched(enriched, 12, true, true);
+        let request_options = crate::command::domain::RequestOptions::default();
+
+        let (items, budget, omitted, _, _) = pack_enriched_results(
+            enriched,
+            &profile,
+            PackBudget::new(1_000, None),
+            0,
+            None,
+            &request_options,
+            RelatedMode::Explore,
+            &[],
+        );
+
+        // Equal scores fall back to path order, so the pack is the same whatever the input order.
+        let packed: Vec<&str> = items.iter().map(|item| item.file.as_str()).collect();
+        assert_eq!(packed, vec!["src/m00.rs", "src/m01.rs"]);
+        assert_eq!(budget.omitted_items, 10);
+        assert_eq!(omitted.len(), MAX_OMITTED_STUBS);
+        assert_eq!(omitted[0].file, "src/m02.rs");
+        assert_eq!(omitted[7].file, "src/m09.rs");
+        assert!(omitted
+            .iter()
+            .all(|stub| stub.omitted && stub.reason == "budget" && stub.role == "primary"));
+    }
+
+    #[test]
+    fn truncation_drops_least_important_bucket_first() {
+        let item = |id: &str, bucket: Option<&str>| ContextPackItem {
+            id: id.to_string(),
+            role: if bucket.is_some() {
+                "related"
+            } else {
+                "primary"
+            }
+            .to_string(),
+            file: format!("src/{id}.rs"),
+            start_line: 1,
+            end_line: 1,
+            symbol: None,
+            chunk_type: None,
+            score: 1.0,
+            imports: Vec::new(),
+            content: String::new(),
+            relationship: None,
+            distance: None,
+            bucket: bucket.map(str::to_string),
+            indexed_at_ms: None,
+            file_mtime_ms: None,
+        };
+
+        let items = vec![
+            item("main", None),
+            item("test", Some("TestedBy")),
+            item("callee", Some("Calls")),
+            item("import", Some("Imports")),
+        ];
+        assert_eq!(least_important_bucketed_item(&items), Some(1));
+        assert_eq!(least_important_bucketed_item(&items[..1]), None);
+    }
+
     #[test]
     fn packer_applies_path_filters_to_primary_items() {
         let profile = SearchProfile::general();
@@ -2167,11 +3083,13 @@ mod tests {
             id: "src/main.rs:1:1".to_string(),
             chunk: chunk("src/main.rs", 1, "fn main() {}"),
             score: 1.0,
+            freshness: None,
         };
         let primary_b = SearchResult {
             id: "docs/readme.md:1:1".to_string(),
             chunk: chunk("docs/readme.md", 1, "# docs"),
             score: 0.9,
+            freshness: None,
         };
 
         let enriched = vec![
@@ -2194,11 +3112,12 @@ mod tests {
             ..Default::default()
         };
         let query_tokens = Vec::new();
-        let (items, budget, filtered_out) = pack_enriched_results(
+        let (items, budget, _omitted, filtered_out, _merged) = pack_enriched_results(
             enriched,
             &profile,
-            50_000,
+            PackBudget::new(50_000, None),
             100,
+            None,
             &request_options,
             RelatedMode::Explore,
             &query_tokens,
@@ -2214,6 +3133,103 @@ mod tests {
         assert_eq!(primary_files, vec!["src/main.rs"]);
     }
 
+    #[test]
+    fn path_scope_drops_related_chunks_outside_filters() {
+        let mut enriched = vec![EnrichedResult {
+            primary: SearchResult {
+                id: "crates/search/src/lib.rs:1:2".to_string(),
+                chunk: span_chunk("crates/search/src/lib.rs", 1, 2),
+                score: 1.0,
+                freshness: None,
+            },
+            related: vec![
+                calls(span_chunk("crates/search/src/multi.rs", 1, 2), 0.9),
+                calls(span_chunk("crates/cli/src/main.rs", 1, 2), 0.8),
+            ],
+            total_lines: 2,
+            strategy: AssemblyStrategy::Extended,
+        }];
+
+        let unfiltered = crate::command::domain::RequestOptions::default();
+        assert_eq!(retain_related_in_scope(&mut enriched, &unfiltered), 0);
+        assert_eq!(enriched[0].related.len(), 2);
+
+        let request_options = crate::command::domain::RequestOptions {
+            include_paths: vec!["crates/search".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(retain_related_in_scope(&mut enriched, &request_options), 1);
+        let related: Vec<&str> = enriched[0]
+            .related
+            .iter()
+            .map(|rc| rc.chunk.file_path.as_str())
+            .collect();
+        assert_eq!(related, vec!["crates/search/src/multi.rs"]);
+    }
+
+    fn task_item(file: &str, symbol: &str, content: &str, score: f32) -> TaskPackItem {
+        TaskPackItem {
+            item: ContextPackItem {
+                id: format!("{file}:1:1"),
+                role: "primary".to_string(),
+                file: file.to_string(),
+                start_line: 1,
+                end_line: 1,
+                symbol: Some(symbol.to_string()),
+                chunk_type: None,
+                score,
+                imports: Vec::new(),
+                content: content.to_string(),
+                relationship: None,
+                distance: None,
+                bucket: None,
+                indexed_at_ms: None,
+                file_mtime_ms: None,
+            },
+            why: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn next_actions_rank_impact_first_when_results_center_on_one_symbol() {
+        let items = vec![
+            task_item("src/limiter.rs", "RateLimiter", "struct RateLimiter", 1.0),
+            task_item("src/limiter.rs", "RateLimiter", "impl RateLimiter", 0.9),
+            task_item("src/server.rs", "RateLimiter", "RateLimiter::new()", 0.8),
+            task_item("src/config.rs", "Config", "limiter: RateLimiter", 0.4),
+        ];
+        let primary_files = vec![
+            ("src/limiter.rs".to_string(), 1.0),
+            ("src/server.rs".to_string(), 0.8),
+            ("src/config.rs".to_string(), 0.4),
+        ];
+
+        let actions = rank_next_actions("ratelimiter", &items, &primary_files);
+
+        assert!(matches!(actions[0].kind, NextActionKind::Impact));
+        assert_eq!(actions[0].query.as_deref(), Some("RateLimiter"));
+        assert!(actions
+            .iter()
+            .all(|a| !matches!(a.kind, NextActionKind::GrepContext)));
+        assert!(actions.windows(2).all(|w| w[0].score >= w[1].score));
+        assert_eq!(actions.len(), 4);
+    }
+
+    #[test]
+    fn next_actions_suggest_grep_when_intent_terms_are_missing() {
+        let items = vec![
+            task_item("src/a.rs", "alpha", "fn alpha() {}", 0.9),
+            task_item("src/b.rs", "beta", "fn beta() {}", 0.7),
+        ];
+        let primary_files = vec![("src/a.rs".to_string(), 0.9), ("src/b.rs".to_string(), 0.7)];
+
+        let actions = rank_next_actions("alpha token bucket", &items, &primary_files);
+
+        assert!(matches!(actions[0].kind, NextActionKind::GrepContext));
+        assert_eq!(actions[0].query.as_deref(), Some("token|bucket"));
+        assert!(matches!(actions[1].kind, NextActionKind::OpenFile));
+    }
+
     #[test]
     fn packer_focus_prefers_query_hits_over_raw_relevance() {
         let profile = SearchProfile::general();
@@ -2222,17 +3238,20 @@ mod tests {
             id: "src/main.rs:1:1".to_string(),
             chunk: chunk("src/main.rs", 1, "fn main() {}"),
             score: 1.0,
+            freshness: None,
         };
 
         let related_miss = RelatedContext {
             chunk: chunk("src/miss.rs", 1, "fn unrelated() {}"),
             relationship_path: vec!["Calls".to_string()],
+            symbol_path: Vec::new(),
             distance: 1,
             relevance_score: 100.0,
         };
         let related_hit = RelatedContext {
             chunk: chunk("src/hit.rs", 1, "fn target() {}"),
             relationship_path: vec!["Calls".to_string()],
+            symbol_path: Vec::new(),
             distance: 1,
             relevance_score: 0.1,
         };
@@ -2246,11 +3265,12 @@ mod tests {
 
         let request_options = crate::command::domain::RequestOptions::default();
         let query_tokens = vec!["target".to_string()];
-        let (items, budget, _filtered_out) = pack_enriched_results(
+        let (items, budget, _omitted, _filtered_out, _merged) = pack_enriched_results(
             enriched,
             &profile,
-            50_000,
+            PackBudget::new(50_000, None),
             100,
+            None,
             &request_options,
             RelatedMode::Focus,
             &query_tokens,
@@ -2271,11 +3291,13 @@ mod tests {
             id: "src/main.rs:1:1".to_string(),
             chunk: chunk("src/main.rs", 1, "fn main() {}"),
             score: 0.9,
+            freshness: None,
         };
         let primary_b = SearchResult {
             id: "docs/readme.md:1:1".to_string(),
             chunk: chunk("docs/readme.md", 1, "# docs"),
             score: 1.0,
+            freshness: None,
         };
 
         let enriched = vec![
@@ -2307,11 +3329,13 @@ mod tests {
             id: "src/main.rs:1:1".to_string(),
             chunk: chunk("src/main.rs", 1, "fn main() {}"),
             score: 0.9,
+            freshness: None,
         };
         let primary_b = SearchResult {
             id: "docs/readme.md:1:1".to_string(),
             chunk: chunk("docs/readme.md", 1, "# docs"),
             score: 1.0,
+            freshness: None,
         };
 
         let enriched = vec![
@@ -2336,4 +3360,63 @@ mod tests {
             .collect();
         assert_eq!(files, vec!["src/main.rs", "docs/readme.md"]);
     }
+
+    #[test]
+    fn result_set_diff_lists_each_side_in_rank_order() {
+        let output = |path: &str, line: usize| {
+            format_basic_output(SearchResult {
+                id: format!("{path}:{line}:{line}"),
+                chunk: chunk(path, line, "fn f() {}"),
+                score: 1.0,
+                freshness: None,
+            })
+        };
+        let baseline = vec![output("src/a.rs", 1), output("src/b.rs", 5)];
+        let context = vec![output("src/c.rs", 2), output("src/d.rs", 9)];
+        let baseline_keys: HashSet<_> = baseline.iter().map(key_for).collect();
+        let context_keys: HashSet<_> = context.iter().map(key_for).collect();
+
+        assert_eq!(
+            keys_missing_from(&baseline, &context_keys),
+            vec!["src/a.rs:1:1", "src/b.rs:5:5"]
+        );
+        assert_eq!(
+            keys_missing_from(&context, &baseline_keys),
+            vec!["src/c.rs:2:2", "src/d.rs:9:9"]
+        );
+
+        let shared = vec![output("src/b.rs", 5), output("src/c.rs", 2)];
+        let shared_keys: HashSet<_> = shared.iter().map(key_for).collect();
+        assert_eq!(
+            keys_missing_from(&baseline, &shared_keys),
+            vec!["src/a.rs:1:1"]
+        );
+        assert!(keys_missing_from(&shared, &shared_keys).is_empty());
+    }
+
+    #[test]
+    fn exclusion_terms_do_not_steer_query_classification() {
+        let profile = SearchProfile::general();
+        let query = "parse_config -docs";
+        assert!(QueryClassifier::is_docs_intent(query));
+
+        let text = parse_query(query).text;
+        assert!(!QueryClassifier::is_docs_intent(&text));
+        assert_eq!(choose_strategy(&text, &profile).0, SearchStrategy::Direct);
+        assert_ne!(choose_strategy(query, &profile).0, SearchStrategy::Direct);
+    }
+
+    #[test]
+    fn unsupported_dominant_language_resolves_graph_less() {
+        let chunks = vec![
+            chunk("cmd/main.go", 1, "func main() { serve() }"),
+            chunk("cmd/serve.go", 1, "func serve() {}"),
+            chunk("tools/gen.py", 1, "def gen(): pass"),
+        ];
+        assert_eq!(resolve_graph_language(None, &chunks), Err("go".to_string()));
+        assert_eq!(
+            resolve_graph_language(Some(GraphLanguage::Python), &chunks),
+            Ok(GraphLanguage::Python)
+        );
+    }
 }
@@ -0,0 +1,98 @@
+use crate::command::context::CommandContext;
+use crate::command::domain::{
+    merge_configs, parse_payload, CommandOutcome, TemplatePreviewOutput, TemplatePreviewPayload,
+};
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use context_code_chunker::Chunker;
+use context_indexer::load_chunker_config;
+use context_protocol::{CodedError, ErrorCode};
+use context_vector_store::EmbeddingTemplates;
+use serde_json::Value;
+use tokio::fs;
+
+#[derive(Default)]
+pub struct TemplatePreviewService;
+
+impl TemplatePreviewService {
+    /// Render the embedding document (and optional query) for the chunk at `file:line`
+    /// without loading the embedder or touching the index.
+    pub async fn preview(&self, payload: Value, ctx: &CommandContext) -> Result<CommandOutcome> {
+        let payload: TemplatePreviewPayload = parse_payload(payload)?;
+        let project_ctx = ctx.resolve_project(payload.project).await?;
+        let file_path = project_ctx.root.join(&payload.file);
+
+        if !file_path.exists() {
+            return Err(CodedError::new(
+                ErrorCode::FileNotFound,
+                format!("File not found: {}", file_path.display()),
+            )
+            .into());
+        }
+
+        let content = fs::read_to_string(&file_path)
+            .await
+            .context("Failed to read file")?;
+        let total_lines = content.lines().count();
+        if payload.line == 0 || payload.line > total_lines {
+            return Err(anyhow!(
+                "Line {} out of range (file has {} lines)",
+                payload.line,
+                total_lines
+            ));
+        }
+
+        let chunker = Chunker::new(load_chunker_config(&project_ctx.root).await);
+        let chunks = chunker
+            .chunk_str(&content, Some(&payload.file))
+            .context("Failed to chunk file")?;
+        let chunk = chunks
+            .iter()
+            .find(|chunk| payload.line >= chunk.start_line && payload.line <= chunk.end_line)
+            .ok_or_else(|| anyhow!("No chunk covers line {} of {}", payload.line, payload.file))?;
+
+        let overrides = project_ctx
+            .config
+            .as_ref()
+            .and_then(|config| config.get("embedding"))
+            .filter(|value| !value.is_null())
+            .cloned();
+        let overridden = overrides.is_some();
+        let templates = templates_with_overrides(project_ctx.profile.embedding(), overrides)?;
+        let preview = templates
+            .preview(chunk, payload.query.as_deref())
+            .context("Failed to render embedding templates")?;
+
+        let mut outcome = CommandOutcome::from_value(TemplatePreviewOutput {
+            file: payload.file,
+            start_line: chunk.start_line,
+            end_line: chunk.end_line,
+            symbol: chunk.metadata.symbol_name.clone(),
+            overridden,
+            preview,
+        })?;
+        outcome.meta.config_path = project_ctx.config_path;
+        outcome.meta.profile = Some(project_ctx.profile_name);
+        outcome.meta.profile_path = project_ctx.profile_path;
+        outcome.meta.index_updated = Some(false);
+        outcome.hints.extend(project_ctx.hints);
+        Ok(outcome)
+    }
+}
+
+/// Overlay `config.embedding` onto the profile templates field by field.
+fn templates_with_overrides(
+    base: &EmbeddingTemplates,
+    overrides: Option<Value>,
+) -> Result<EmbeddingTemplates> {
+    let Some(overrides) = overrides else {
+        return Ok(base.clone());
+    };
+    let merged =
+        merge_configs(Some(serde_json::to_value(base)?), Some(overrides)).unwrap_or(Value::Null);
+    let templates: EmbeddingTemplates = serde_json::from_value(merged)
+        .context("Invalid embedding template overrides in config.embedding")?;
+    templates
+        .validate()
+        .context("Invalid embedding template overrides in config.embedding")?;
+    Ok(templates)
+}
@@ -7,11 +7,14 @@ use crate::command::warm;
 use anyhow::{anyhow, Result};
 use context_indexer::FileScanner;
 use context_protocol::ToolNextAction;
+use context_search::match_in_line;
 use context_vector_store::{corpus_path_for_project_root, ChunkCorpus};
 use serde_json::{Map, Value};
 use std::collections::HashSet;
 use std::path::Path;
 
+const MAX_CONTEXT_LINES: usize = 10;
+
 #[derive(Default)]
 pub struct TextSearchService;
 
@@ -31,6 +34,7 @@ impl TextSearchService {
         let max_results = payload.max_results.unwrap_or(50).clamp(1, 1000);
         let case_sensitive = payload.case_sensitive.unwrap_or(true);
         let whole_word = payload.whole_word.unwrap_or(false);
+        let context_lines = payload.context_lines.unwrap_or(0).min(MAX_CONTEXT_LINES);
 
         let request_options = ctx.request_options();
         let file_pattern = request_options
@@ -49,10 +53,16 @@ impl TextSearchService {
         let mut matched_files: HashSet<String> = HashSet::new();
         let mut scanned_files = 0usize;
         let mut skipped_large_files = 0usize;
+        let mut ignored_files = 0usize;
+        let mut unreadable_files = 0usize;
         let mut truncated = false;
         let source: String;
 
-        let corpus = load_chunk_corpus(&project_ctx.root).await?;
+        // Only the corpus shards holding files that pass the path filters are read.
+        let corpus = load_chunk_corpus(&project_ctx.root, |file| {
+            crate::command::path_filters::path_allowed(file, &request_options)
+        })
+        .await?;
         if let Some(corpus) = corpus {
             source = "corpus".to_string();
 
@@ -60,18 +70,16 @@ impl TextSearchService {
                 corpus.files().iter().collect();
             files.sort_by(|a, b| a.0.cmp(b.0));
 
-            'outer_corpus: for (file, chunks) in files {
+            'outer_corpus: for (_file, chunks) in files {
                 if matches.len() >= max_results {
                     truncated = true;
                     break 'outer_corpus;
                 }
-                if !crate::command::path_filters::path_allowed(file, &request_options) {
-                    continue;
-                }
                 scanned_files += 1;
 
                 for chunk in chunks {
-                    for (offset, line_text) in chunk.content.lines().enumerate() {
+                    let lines: Vec<&str> = chunk.content.lines().collect();
+                    for (offset, line_text) in lines.iter().copied().enumerate() {
                         if matches.len() >= max_results {
                             truncated = true;
                             break 'outer_corpus;
@@ -84,12 +92,15 @@ impl TextSearchService {
 
                         let line = chunk.start_line + offset;
                         let column = line_text[..col_byte].chars().count() + 1;
+                        let (before, after) = context_around(&lines, offset, context_lines);
                         matched_files.insert(chunk.file_path.clone());
                         matches.push(TextSearchMatch {
                             file: chunk.file_path.clone(),
                             line,
                             column,
                             text: line_text.to_string(),
+                            before,
+                            after,
                         });
                     }
                 }
@@ -102,8 +113,9 @@ impl TextSearchService {
                 ));
             }
 
-            let scanner = FileScanner::new(&project_ctx.root);
-            let files = scanner.scan();
+            let (files, scan_stats) = FileScanner::new(&project_ctx.root).scan_with_stats();
+            ignored_files = scan_stats.ignored_files;
+            unreadable_files = scan_stats.unreadable_files;
 
             'outer_fs: for file in files {
                 if matches.len() >= max_results {
@@ -120,17 +132,22 @@ impl TextSearchService {
                 scanned_files += 1;
                 let meta = match std::fs::metadata(&file) {
                     Ok(m) => m,
-                    Err(_) => continue,
+                    Err(_) => {
+                        unreadable_files += 1;
+                        continue;
+                    }
                 };
                 if meta.len() > MAX_FILE_BYTES {
                     skipped_large_files += 1;
                     continue;
                 }
                 let Ok(content) = std::fs::read_to_string(&file) else {
+                    unreadable_files += 1;
                     continue;
                 };
 
-                for (offset, line_text) in content.lines().enumerate() {
+                let lines: Vec<&str> = content.lines().collect();
+                for (offset, line_text) in lines.iter().copied().enumerate() {
                     if matches.len() >= max_results {
                         truncated = true;
                         break 'outer_fs;
@@ -141,12 +158,15 @@ impl TextSearchService {
                         continue;
                     };
                     let column = line_text[..col_byte].chars().count() + 1;
+                    let (before, after) = context_around(&lines, offset, context_lines);
                     matched_files.insert(rel_path.clone());
                     matches.push(TextSearchMatch {
                         file: rel_path.clone(),
                         line: offset + 1,
                         column,
                         text: line_text.to_string(),
+                        before,
+                        after,
                     });
                 }
             }
@@ -158,6 +178,8 @@ impl TextSearchService {
             scanned_files,
             matched_files: matched_files.len(),
             skipped_large_files,
+            ignored_files,
+            unreadable_files,
             returned: matches.len(),
             truncated,
             matches,
@@ -202,25 +224,34 @@ impl TextSearchService {
             );
             args.insert("case_sensitive".to_string(), Value::Bool(case_sensitive));
             args.insert("whole_word".to_string(), Value::Bool(whole_word));
+            if context_lines > 0 {
+                args.insert(
+                    "context_lines".to_string(),
+                    Value::Number(serde_json::Number::from(context_lines as u64)),
+                );
+            }
             if let Some(pat) = file_pattern {
                 args.insert("file_pattern".to_string(), Value::String(pat.to_string()));
             }
-            outcome.next_actions.push(ToolNextAction {
-                tool: "text_search".to_string(),
-                args: Value::Object(args),
-                reason: "Retry text_search with a higher max_results budget.".to_string(),
-            });
+            outcome.next_actions.push(ToolNextAction::new(
+                "text_search",
+                Value::Object(args),
+                "Retry text_search with a higher max_results budget.",
+            ));
         }
         Ok(outcome)
     }
 }
 
-async fn load_chunk_corpus(root: &Path) -> Result<Option<ChunkCorpus>> {
+async fn load_chunk_corpus(
+    root: &Path,
+    keep: impl Fn(&str) -> bool,
+) -> Result<Option<ChunkCorpus>> {
     let path = corpus_path_for_project_root(root);
     if !path.exists() {
         return Ok(None);
     }
-    Ok(Some(ChunkCorpus::load(&path).await?))
+    Ok(Some(ChunkCorpus::load_matching(&path, keep).await?))
 }
 
 fn normalize_relative_path(root: &Path, path: &Path) -> Option<String> {
@@ -229,49 +260,21 @@ fn normalize_relative_path(root: &Path, path: &Path) -> Option<String> {
     Some(rel.replace('\\', "/"))
 }
 
-fn find_word_boundary(haystack: &str, needle: &str) -> Option<usize> {
-    let needle_is_ident = needle.bytes().all(is_ident_byte);
-    if !needle_is_ident {
-        return haystack.find(needle);
-    }
-
-    let bytes = haystack.as_bytes();
-    for (idx, _) in haystack.match_indices(needle) {
-        let left_ok = idx == 0 || !is_ident_byte(bytes[idx - 1]);
-        let right_idx = idx + needle.len();
-        let right_ok = right_idx >= bytes.len() || !is_ident_byte(bytes[right_idx]);
-        if left_ok && right_ok {
-            return Some(idx);
-        }
-    }
-    None
-}
-
-const fn is_ident_byte(b: u8) -> bool {
-    matches!(b, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_')
-}
-
-fn match_in_line(
-    line: &str,
-    pattern: &str,
-    case_sensitive: bool,
-    whole_word: bool,
-) -> Option<usize> {
-    if case_sensitive {
-        if whole_word {
-            find_word_boundary(line, pattern)
-        } else {
-            line.find(pattern)
-        }
-    } else {
-        let line_lower = line.to_ascii_lowercase();
-        let pat_lower = pattern.to_ascii_lowercase();
-        if whole_word {
-            find_word_boundary(&line_lower, &pat_lower)
-        } else {
-            line_lower.find(&pat_lower)
-        }
+/// Up to `context_lines` lines on each side of `lines[idx]`.
+fn context_around(lines: &[&str], idx: usize, context_lines: usize) -> (Vec<String>, Vec<String>) {
+    if context_lines == 0 {
+        return (Vec::new(), Vec::new());
     }
+    let before = lines[idx.saturating_sub(context_lines)..idx]
+        .iter()
+        .map(|line| (*line).to_string())
+        .collect();
+    let after_end = idx.saturating_add(1 + context_lines).min(lines.len());
+    let after = lines[idx + 1..after_end]
+        .iter()
+        .map(|line| (*line).to_string())
+        .collect();
+    (before, after)
 }
 
 fn join_limited(items: &[String], max: usize) -> String {
@@ -0,0 +1,108 @@
+use super::search::{load_graph_engine, parse_graph_language};
+use crate::command::context::CommandContext;
+use crate::command::domain::{
+    parse_payload, CommandOutcome, Hint, HintKind, WarmPayload, WarmReport, WarmStage,
+    WarmStageKind,
+};
+use crate::command::infra::GraphCacheFactory;
+use crate::command::warm;
+use anyhow::Result;
+use serde_json::Value;
+use std::time::Instant;
+
+/// Pays the cold-start cost (index load, graph build, embedder session) ahead of real queries.
+pub struct WarmService {
+    graph: GraphCacheFactory,
+}
+
+impl WarmService {
+    pub fn new(graph: GraphCacheFactory) -> Self {
+        Self { graph }
+    }
+
+    pub async fn run(&self, payload: Value, ctx: &CommandContext) -> Result<CommandOutcome> {
+        let payload: WarmPayload = parse_payload(payload)?;
+        let project_ctx = ctx.resolve_project(payload.project).await?;
+        let language = payload
+            .language
+            .as_deref()
+            .map(parse_graph_language)
+            .transpose()?;
+        let started = Instant::now();
+        let mut stages = Vec::new();
+
+        let stage_started = Instant::now();
+        let was_warm = warm::global_warmer().is_warm().await;
+        let meta = warm::global_warmer().prewarm(&project_ctx.root).await;
+        stages.push(WarmStage {
+            stage: WarmStageKind::Prewarm,
+            duration_ms: stage_started.elapsed().as_millis() as u64,
+            cache_hit: Some(was_warm),
+            error: (!meta.warmed).then(|| "index or embedder could not be loaded".to_string()),
+        });
+
+        if let Some(language) = language {
+            let stage_started = Instant::now();
+            let engine = load_graph_engine(
+                &self.graph,
+                &project_ctx.root,
+                &project_ctx.profile,
+                Some(language),
+                true,
+            )
+            .await;
+            stages.push(WarmStage {
+                stage: WarmStageKind::Graph,
+                duration_ms: stage_started.elapsed().as_millis() as u64,
+                cache_hit: engine.as_ref().ok().map(|engine| engine.graph_cache_used),
+                error: engine.err().map(|err| format!("{err:#}")),
+            });
+        }
+
+        let mut embedding_probe = None;
+        if payload.embedder {
+            let stage_started = Instant::now();
+            let probe = match context_vector_store::current_model_id() {
+                Ok(model_id) => context_vector_store::probe_embedding(&model_id).await,
+                Err(err) => Err(err),
+            };
+            stages.push(WarmStage {
+                stage: WarmStageKind::Embedder,
+                duration_ms: stage_started.elapsed().as_millis() as u64,
+                cache_hit: None,
+                error: probe.as_ref().err().map(ToString::to_string),
+            });
+            embedding_probe = probe.ok();
+        }
+
+        let failed: Vec<&str> = stages
+            .iter()
+            .filter(|stage| stage.error.is_some())
+            .map(|stage| stage.stage.as_str())
+            .collect();
+        let report = WarmReport {
+            project: project_ctx.root.display().to_string(),
+            stages,
+            total_ms: started.elapsed().as_millis() as u64,
+            embedding_probe,
+        };
+
+        let mut outcome = CommandOutcome::from_value(report)?;
+        outcome.meta.config_path = project_ctx.config_path;
+        outcome.meta.index_updated = Some(false);
+        outcome.meta.warm = Some(meta.warmed);
+        outcome.meta.warm_cost_ms = Some(meta.warm_cost_ms);
+        outcome.meta.warm_graph_cache_hit = Some(meta.graph_cache_hit);
+        outcome.hints.extend(project_ctx.hints);
+        if !failed.is_empty() {
+            outcome.hints.push(Hint {
+                kind: HintKind::Warn,
+                text: format!(
+                    "Warm-up stages failed: {}. Run action=index first if the project has no index.",
+                    failed.join(", ")
+                ),
+            });
+        }
+        Ok(outcome)
+    }
+}
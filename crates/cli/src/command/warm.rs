@@ -28,6 +28,11 @@ pub fn global_warmer() -> Warmer {
 }
 
 impl Warmer {
+    /// Whether a prewarm already ran in this process.
+    pub async fn is_warm(&self) -> bool {
+        self.inner.lock().await.is_some()
+    }
+
     /// Start prewarm if not already done; returns warm meta (may be cached).
     pub async fn prewarm(&self, project_root: &Path) -> WarmMeta {
         {
@@ -1,16 +1,30 @@
 use anyhow::{Context, Result};
 use context_code_chunker::CodeChunk;
-use context_graph::GraphEdge;
-use context_graph::GraphNode;
-use context_graph::{CodeGraph, ContextAssembler, GraphLanguage, RelationshipType, Symbol};
+use context_graph::{ContextAssembler, GraphCacheFile, GraphLanguage, GRAPH_CACHE_FILE_NAME};
 use log::{debug, warn};
-use petgraph::visit::EdgeRef;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::fs;
 
+static GRAPH_CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static GRAPH_CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// Process-wide graph cache hit/miss counters (across all project roots).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GraphCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+pub fn graph_cache_stats() -> GraphCacheStats {
+    GraphCacheStats {
+        hits: GRAPH_CACHE_HITS.load(Ordering::Relaxed),
+        misses: GRAPH_CACHE_MISSES.load(Ordering::Relaxed),
+    }
+}
+
 #[derive(Clone)]
 pub struct GraphCache {
     path: PathBuf,
@@ -20,7 +34,7 @@ impl GraphCache {
     pub fn new(project_root: &Path) -> Self {
         let path = project_root
             .join(".context-finder")
-            .join("graph_cache.json");
+            .join(GRAPH_CACHE_FILE_NAME);
         Self { path }
     }
 
@@ -28,6 +42,16 @@ impl GraphCache {
         tokio::fs::metadata(&self.path).await.ok().map(|m| m.len())
     }
 
+    /// Remove the cache file; returns whether there was one to remove.
+    pub async fn clear(&self) -> Result<bool> {
+        match fs::remove_file(&self.path).await {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err)
+                .with_context(|| format!("Failed to remove graph cache {}", self.path.display())),
+        }
+    }
+
     pub async fn load(
         &self,
         store_mtime: SystemTime,
@@ -35,85 +59,65 @@ impl GraphCache {
         chunks: &[CodeChunk],
         chunk_index: &HashMap<String, usize>,
     ) -> Result<Option<ContextAssembler>> {
+        let loaded = self
+            .load_inner(store_mtime, language, chunks, chunk_index)
+            .await;
+        let counter = if loaded.is_some() {
+            &GRAPH_CACHE_HITS
+        } else {
+            &GRAPH_CACHE_MISSES
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        Ok(loaded)
+    }
+
+    async fn load_inner(
+        &self,
+        store_mtime: SystemTime,
+        language: GraphLanguage,
+        chunks: &[CodeChunk],
+        chunk_index: &HashMap<String, usize>,
+    ) -> Option<ContextAssembler> {
         if !self.path.exists() {
-            return Ok(None);
+            return None;
         }
 
         let data = match fs::read(&self.path).await {
             Ok(bytes) => bytes,
             Err(err) => {
                 warn!("Failed to read graph cache {}: {err}", self.path.display());
-                return Ok(None);
+                return None;
             }
         };
 
-        let cached: CachedGraph = match serde_json::from_slice(&data) {
+        let cached = match GraphCacheFile::decode(&data) {
             Ok(cached) => cached,
             Err(err) => {
-                warn!("Graph cache corrupted ({}): {err}", self.path.display());
-                return Ok(None);
+                warn!(
+                    "Ignoring graph cache {} ({err}); rebuilding",
+                    self.path.display()
+                );
+                return None;
             }
         };
 
-        if cached.language != language {
+        if !cached.is_fresh_for(language, to_unix_ms(store_mtime)) {
             debug!(
-                "Graph cache language mismatch (cache={:?}, requested={:?})",
-                cached.language, language
+                "Graph cache stale (cache={:?}@{}, requested={:?})",
+                cached.language, cached.store_mtime_ms, language
             );
-            return Ok(None);
-        }
-
-        if cached.index_mtime_ms != to_unix_ms(store_mtime) {
-            debug!("Graph cache stale (mtime mismatch)");
-            return Ok(None);
-        }
-
-        let mut graph = CodeGraph::new();
-        let mut node_indices = Vec::new();
-
-        for node in cached.nodes {
-            let Some(&idx) = chunk_index.get(&node.chunk_id) else {
-                debug!(
-                    "Graph cache chunk {} missing in vector store, forcing rebuild",
-                    node.chunk_id
-                );
-                return Ok(None);
-            };
-            let Some(chunk) = chunks.get(idx) else {
-                debug!(
-                    "Graph cache chunk {} index out of bounds, forcing rebuild",
-                    node.chunk_id
-                );
-                return Ok(None);
-            };
-
-            let graph_node = GraphNode {
-                symbol: node.symbol,
-                chunk_id: node.chunk_id,
-                chunk: Some(chunk.clone()),
-            };
-            let idx = graph.add_node(graph_node);
-            node_indices.push(idx);
+            return None;
         }
 
-        for edge in cached.edges {
-            let Some(&from_idx) = node_indices.get(edge.from) else {
-                return Ok(None);
-            };
-            let Some(&to_idx) = node_indices.get(edge.to) else {
-                return Ok(None);
-            };
-            graph.add_edge(
-                from_idx,
-                to_idx,
-                GraphEdge {
-                    relationship: edge.relationship,
-                    weight: edge.weight,
-                },
-            );
-        }
+        let graph = cached.into_graph(|chunk_id| {
+            let chunk = chunk_index.get(chunk_id).and_then(|&idx| chunks.get(idx));
+            if chunk.is_none() {
+                debug!("Graph cache chunk {chunk_id} missing in vector store, forcing rebuild");
+            }
+            chunk.cloned()
+        })?;
 
-        Ok(Some(ContextAssembler::new(graph)))
+        Some(ContextAssembler::new(graph))
     }
 
     pub async fn save(
@@ -126,76 +130,46 @@ impl GraphCache {
             fs::create_dir_all(parent).await?;
         }
 
-        let cached = CachedGraph::from_assembler(store_mtime, language, assembler);
-        let data = serde_json::to_vec_pretty(&cached)?;
-        fs::write(&self.path, data)
-            .await
-            .with_context(|| format!("Failed to write graph cache {}", self.path.display()))
+        let cached =
+            GraphCacheFile::from_graph(assembler.graph(), language, to_unix_ms(store_mtime));
+        self.write(&cached).await
     }
-}
-
-#[derive(Serialize, Deserialize)]
-struct CachedGraph {
-    index_mtime_ms: u64,
-    language: GraphLanguage,
-    nodes: Vec<CachedNode>,
-    edges: Vec<CachedEdge>,
-}
-
-#[derive(Serialize, Deserialize)]
-struct CachedNode {
-    symbol: Symbol,
-    chunk_id: String,
-}
-
-#[derive(Serialize, Deserialize)]
-struct CachedEdge {
-    from: usize,
-    to: usize,
-    relationship: RelationshipType,
-    weight: f32,
-}
 
-impl CachedGraph {
-    fn from_assembler(
-        store_mtime: SystemTime,
-        language: GraphLanguage,
-        assembler: &ContextAssembler,
-    ) -> Self {
-        let graph = assembler.graph();
-        let mut node_map = HashMap::new();
-        let mut nodes = Vec::new();
-
-        for (idx, node) in graph.graph.node_indices().enumerate() {
-            if let Some(data) = graph.graph.node_weight(node) {
-                node_map.insert(node, idx);
-                nodes.push(CachedNode {
-                    symbol: data.symbol.clone(),
-                    chunk_id: data.chunk_id.clone(),
-                });
+    /// Drop the cached nodes of `files` (and their edges), rewriting the cache only when
+    /// something was dropped. Returns the number of dropped nodes.
+    pub async fn drop_files(&self, files: &[String]) -> Result<usize> {
+        let data = match fs::read(&self.path).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("Failed to read graph cache {}", self.path.display()))
             }
-        }
+        };
+        // An unreadable cache is rebuilt on the next load anyway.
+        let Ok(mut cached) = GraphCacheFile::decode(&data) else {
+            return Ok(0);
+        };
 
-        let mut edges = Vec::new();
-        for edge in graph.graph.edge_references() {
-            if let (Some(&from), Some(&to)) =
-                (node_map.get(&edge.source()), node_map.get(&edge.target()))
-            {
-                edges.push(CachedEdge {
-                    from,
-                    to,
-                    relationship: edge.weight().relationship,
-                    weight: edge.weight().weight,
-                });
-            }
+        let files: HashSet<&str> = files.iter().map(String::as_str).collect();
+        let dropped = cached.drop_files(&files);
+        if dropped > 0 {
+            self.write(&cached).await?;
         }
+        Ok(dropped)
+    }
 
-        Self {
-            index_mtime_ms: to_unix_ms(store_mtime),
-            language,
-            nodes,
-            edges,
+    async fn write(&self, cached: &GraphCacheFile) -> Result<()> {
+        let tmp = self.path.with_extension("bin.tmp");
+        fs::write(&tmp, cached.encode())
+            .await
+            .with_context(|| format!("Failed to write graph cache {}", tmp.display()))?;
+        if let Err(err) = fs::rename(&tmp, &self.path).await {
+            let _ = fs::remove_file(&tmp).await;
+            return Err(err)
+                .with_context(|| format!("Failed to write graph cache {}", self.path.display()));
         }
+        Ok(())
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::command::infra::GraphCacheFactory;
 use anyhow::{Context, Result};
 use context_indexer::{
     ModelIndexSpec, MultiModelProjectIndexer, MultiModelStreamingIndexer, StreamingIndexerConfig,
@@ -14,6 +15,7 @@ use tokio::sync::{watch, Mutex};
 
 const DEFAULT_TTL: Duration = Duration::from_secs(300);
 const DEFAULT_CLEANUP_INTERVAL: Duration = Duration::from_secs(30);
+const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
 const MAX_DAEMON_LINE_BYTES: usize = if cfg!(test) { 1024 } else { 1024 * 1024 };
 
 async fn read_line_limited<R: tokio::io::AsyncRead + Unpin>(
@@ -66,6 +68,10 @@ fn daemon_cleanup_interval() -> Duration {
     duration_from_env_ms("CONTEXT_FINDER_DAEMON_CLEANUP_MS").unwrap_or(DEFAULT_CLEANUP_INTERVAL)
 }
 
+fn daemon_drain_timeout() -> Duration {
+    duration_from_env_ms("CONTEXT_FINDER_DAEMON_DRAIN_MS").unwrap_or(DEFAULT_DRAIN_TIMEOUT)
+}
+
 #[derive(Serialize, Deserialize)]
 struct PingRequest {
     cmd: String,
@@ -92,6 +98,8 @@ struct StatusProject {
     project: String,
     age_ms: u64,
     ttl_ms: u64,
+    /// An index cycle is running for this project right now
+    indexing: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -136,6 +144,15 @@ pub async fn run_daemon(socket: Option<PathBuf>) -> Result<()> {
     let listener_shared = shared.clone();
     let listener_activity = last_activity.clone();
 
+    tokio::spawn({
+        let shutdown_tx = shutdown_tx.clone();
+        async move {
+            shutdown_signal().await;
+            log::info!("daemon received shutdown signal");
+            let _ = shutdown_tx.send(true);
+        }
+    });
+
     // cleanup task
     tokio::spawn({
         let state = state.clone();
@@ -176,8 +193,9 @@ pub async fn run_daemon(socket: Option<PathBuf>) -> Result<()> {
                 let st = listener_state.clone();
                 let shared = listener_shared.clone();
                 let activity = listener_activity.clone();
+                let shutdown = shutdown_tx.clone();
                 tokio::spawn(async move {
-                    if let Err(err) = handle_conn(stream, st, shared, activity).await {
+                    if let Err(err) = handle_conn(stream, st, shared, activity, shutdown).await {
                         log::warn!("daemon connection error: {err:#}");
                     }
                 });
@@ -185,15 +203,65 @@ pub async fn run_daemon(socket: Option<PathBuf>) -> Result<()> {
         }
     }
 
+    // Stop accepting work first, then let in-flight index cycles finish so no store is cut
+    // off mid-save.
+    drop(listener);
     let _ = tokio::fs::remove_file(&socket_path).await;
+    drain_workers(&state, daemon_drain_timeout()).await;
     Ok(())
 }
 
+/// Resolves on SIGTERM or SIGINT.
+async fn shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(stream) => stream,
+        Err(err) => {
+            log::warn!("failed to install SIGTERM handler: {err}");
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        }
+    };
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+}
+
+/// Shut down every worker, waiting (within one shared `timeout`) for running index cycles.
+/// A worker cut off by the deadline records the interruption in its project's health.
+async fn drain_workers(state: &Mutex<HashMap<PathBuf, Worker>>, timeout: Duration) {
+    let workers = std::mem::take(&mut *state.lock().await);
+    let deadline = Instant::now() + timeout;
+    for (project, worker) in workers {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if worker.streamer.shutdown(remaining).await {
+            continue;
+        }
+        log::warn!(
+            "daemon shutdown timed out waiting for the index of {}",
+            project.display()
+        );
+        if let Err(err) = context_indexer::append_failure_reason(
+            &project,
+            "daemon",
+            "shutdown interrupted an index cycle",
+            None,
+        )
+        .await
+        {
+            log::warn!("Failed to persist shutdown failure reason: {err}");
+        }
+    }
+}
+
 async fn handle_conn(
     stream: UnixStream,
     state: std::sync::Arc<Mutex<HashMap<PathBuf, Worker>>>,
     shared: std::sync::Arc<DaemonShared>,
     last_activity: std::sync::Arc<Mutex<Instant>>,
+    shutdown: watch::Sender<bool>,
 ) -> Result<()> {
     let mut reader = BufReader::new(stream);
     let line = read_line_limited(&mut reader, MAX_DAEMON_LINE_BYTES).await?;
@@ -208,13 +276,22 @@ async fn handle_conn(
                 .unwrap_or_else(daemon_ttl);
             let project = PathBuf::from(req.project);
 
-            {
-                let mut guard = state.lock().await;
+            let mut guard = state.lock().await;
+            // Checked under the state lock, so no worker can slip in after the drain took them.
+            if *shutdown.borrow() {
+                drop(guard);
+                serde_json::to_string(&PingResponse {
+                    status: "error".to_string(),
+                    message: Some("daemon is shutting down".to_string()),
+                })?
+            } else {
                 if let Some(w) = guard.get_mut(&project) {
                     w.last_ping = Instant::now();
                     // already running
                 } else {
-                    let indexer = MultiModelProjectIndexer::new(&project).await?;
+                    let indexer = MultiModelProjectIndexer::new(&project)
+                        .await?
+                        .with_profile(shared.profile.clone());
                     let cfg = StreamingIndexerConfig {
                         max_batch_wait: Duration::from_secs(2),
                         ..Default::default()
@@ -224,7 +301,7 @@ async fn handle_conn(
                         shared.model_specs.clone(),
                         cfg,
                     )?;
-                    let worker = Worker::new(streamer);
+                    let worker = Worker::new(&project, streamer);
                     // trigger immediate incremental index to warm
                     let _ = worker.streamer.trigger("bootstrap").await;
                     guard.insert(project.clone(), worker);
@@ -233,12 +310,13 @@ async fn handle_conn(
                     w.ttl = ttl;
                     w.last_ping = Instant::now();
                 }
-            }
+                drop(guard);
 
-            serde_json::to_string(&PingResponse {
-                status: "ok".to_string(),
-                message: None,
-            })?
+                serde_json::to_string(&PingResponse {
+                    status: "ok".to_string(),
+                    message: None,
+                })?
+            }
         }
         "status" => {
             let now = Instant::now();
@@ -250,6 +328,7 @@ async fn handle_conn(
                         project: path.to_string_lossy().to_string(),
                         age_ms: now.duration_since(worker.last_ping).as_millis() as u64,
                         ttl_ms: worker.ttl.as_millis() as u64,
+                        indexing: worker.streamer.health_snapshot().indexing,
                     });
                 }
                 out
@@ -260,6 +339,13 @@ async fn handle_conn(
                 projects,
             })?
         }
+        "shutdown" => {
+            let _ = shutdown.send(true);
+            serde_json::to_string(&PingResponse {
+                status: "ok".to_string(),
+                message: Some("draining".to_string()),
+            })?
+        }
         other => serde_json::to_string(&PingResponse {
             status: "error".to_string(),
             message: Some(format!("unknown command '{other}'")),
@@ -275,21 +361,31 @@ async fn handle_conn(
 
 struct Worker {
     streamer: MultiModelStreamingIndexer,
+    graph_invalidation: tokio::task::JoinHandle<()>,
     ttl: Duration,
     last_ping: Instant,
 }
 
 impl Worker {
-    fn new(streamer: MultiModelStreamingIndexer) -> Self {
+    fn new(project: &Path, streamer: MultiModelStreamingIndexer) -> Self {
+        let graph_invalidation = GraphCacheFactory.subscribe(project, streamer.subscribe_updates());
         Self {
             streamer,
+            graph_invalidation,
             ttl: daemon_ttl(),
             last_ping: Instant::now(),
         }
     }
 }
 
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.graph_invalidation.abort();
+    }
+}
+
 struct DaemonShared {
+    profile: String,
     model_specs: Vec<ModelIndexSpec>,
 }
 
@@ -332,7 +428,10 @@ impl DaemonShared {
             .map(|model_id| ModelIndexSpec::new(model_id, templates.clone()))
             .collect();
 
-        Ok(Self { model_specs: specs })
+        Ok(Self {
+            profile: profile.name().to_string(),
+            model_specs: specs,
+        })
     }
 }
 
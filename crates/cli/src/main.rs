@@ -11,11 +11,12 @@ use clap::{Args, Parser, Subcommand, ValueEnum};
 use command::{
     CommandAction, CommandRequest, CommandResponse, CommandStatus, ContextPackOutput,
     ContextPackPayload, EvalCacheMode, EvalCompareOutput, EvalComparePayload, EvalOutput,
-    EvalPayload, IndexPayload, IndexResponse, ListSymbolsPayload, MapOutput, MapPayload,
-    ResponseMeta, SearchOutput, SearchPayload, SearchStrategy, SearchWithContextPayload,
-    SymbolsOutput,
+    EvalOutputFormat, EvalPayload, EvalThresholds, IndexPayload, IndexPlanResponse, IndexResponse,
+    ListSymbolsPayload, MapOutput, MapPayload, ResponseMeta, SearchOutput, SearchPayload,
+    SearchStrategy, SearchWithContextPayload, SymbolsOutput,
 };
-use context_protocol::{serialize_json, ErrorEnvelope};
+use context_protocol::{serialize_json, ErrorCode, ErrorEnvelope};
+use context_search::QueryType;
 use std::collections::HashSet;
 use std::env;
 use std::fs;
@@ -155,6 +156,17 @@ struct CommandArgs {
     #[arg(long)]
     pretty: bool,
 
+    /// Print the JSON Schema of an action's payload and response data, or of every action
+    /// when no name is given, and exit
+    #[arg(
+        long,
+        value_name = "ACTION",
+        num_args = 0..=1,
+        default_missing_value = "all",
+        conflicts_with_all = ["json", "file"]
+    )]
+    print_schema: Option<String>,
+
     /// Quiet mode (only warn/error logs to stderr; stdout remains pure JSON)
     #[arg(long, global = true)]
     quiet: bool,
@@ -203,6 +215,14 @@ struct IndexArgs {
     #[arg(long, value_delimiter = ',', num_args = 1..)]
     models: Vec<String>,
 
+    /// Report files/chunks that would be indexed without embedding or saving
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Delete the cached code graph so the next graph query rebuilds it
+    #[arg(long)]
+    clear_graph_cache: bool,
+
     /// Output JSON format
     #[arg(long)]
     json: bool,
@@ -225,6 +245,14 @@ struct SearchArgs {
     #[arg(long)]
     with_graph: bool,
 
+    /// Force the query type instead of auto-classifying (identifier, path, conceptual)
+    #[arg(long)]
+    query_type: Option<QueryType>,
+
+    /// Embedding models to search and rank-fuse (comma-separated; each must be indexed)
+    #[arg(long, value_delimiter = ',', num_args = 1..)]
+    models: Vec<String>,
+
     /// Output JSON format
     #[arg(long)]
     json: bool,
@@ -262,6 +290,14 @@ struct MapArgs {
     #[arg(long, short = 'n')]
     limit: Option<usize>,
 
+    /// Hide top symbols whose coverage score is below this value
+    #[arg(long)]
+    min_symbol_score: Option<f32>,
+
+    /// Only list public/exported symbols as top symbols
+    #[arg(long)]
+    public_only: bool,
+
     /// Output JSON format
     #[arg(long)]
     json: bool,
@@ -318,10 +354,18 @@ struct ContextPackArgs {
     #[arg(long)]
     max_chars: Option<usize>,
 
+    /// Maximum estimated tokens for the pack (the tighter of this and --max-chars wins)
+    #[arg(long)]
+    max_tokens: Option<usize>,
+
     /// Related chunks per primary (graph halo cap)
     #[arg(long)]
     max_related_per_primary: Option<usize>,
 
+    /// Per-relationship halo budget as JSON, e.g. '{"per_relationship":{"Calls":2},"max_related":4}'
+    #[arg(long)]
+    related_budget: Option<String>,
+
     /// Prefer code results over markdown docs (implementation-first)
     #[arg(long, conflicts_with = "prefer_docs")]
     prefer_code: bool,
@@ -342,6 +386,10 @@ struct ContextPackArgs {
     #[arg(long, short = 'l')]
     language: Option<String>,
 
+    /// Embedding models to search and rank-fuse (comma-separated; each must be indexed)
+    #[arg(long, value_delimiter = ',', num_args = 1..)]
+    models: Vec<String>,
+
     /// Include debug hints and trace output in the JSON CommandResponse
     #[arg(long)]
     trace: bool,
@@ -411,6 +459,22 @@ struct EvalArgs {
     #[arg(long)]
     out_md: Option<PathBuf>,
 
+    /// Print per-case rows (one per case per run) to stdout as csv or jsonl
+    #[arg(long, value_enum, default_value_t = EvalFormatFlag::Json, conflicts_with = "json")]
+    format: EvalFormatFlag,
+
+    /// Write a JUnit XML report (one test case per dataset case) to this path
+    #[arg(long)]
+    out_junit: Option<PathBuf>,
+
+    /// Exit non-zero when any run's mean MRR is below this value
+    #[arg(long)]
+    fail_under_mrr: Option<f64>,
+
+    /// Exit non-zero when any run's mean recall is below this value
+    #[arg(long)]
+    fail_under_recall: Option<f64>,
+
     /// Output JSON format
     #[arg(long)]
     json: bool,
@@ -458,6 +522,14 @@ struct EvalCompareArgs {
     #[arg(long)]
     out_md: Option<PathBuf>,
 
+    /// Exit non-zero when B's mean MRR trails A's by more than this
+    #[arg(long)]
+    fail_if_b_worse_by_mrr: Option<f64>,
+
+    /// Exit non-zero when B's mean recall trails A's by more than this
+    #[arg(long)]
+    fail_if_b_worse_by_recall: Option<f64>,
+
     /// Output JSON format
     #[arg(long)]
     json: bool,
@@ -494,6 +566,13 @@ enum EvalCacheModeFlag {
     Cold,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum EvalFormatFlag {
+    Json,
+    Csv,
+    Jsonl,
+}
+
 impl EvalCacheModeFlag {
     const fn as_domain(self) -> EvalCacheMode {
         match self {
@@ -554,6 +633,13 @@ fn command_action_requires_embeddings(action: &CommandAction) -> bool {
             | CommandAction::CompareSearch
             | CommandAction::Eval
             | CommandAction::EvalCompare
+            | CommandAction::Impact
+            | CommandAction::Trace
+            | CommandAction::Explain
+            | CommandAction::Overview
+            | CommandAction::ModelsVerify
+            | CommandAction::Warm
+            | CommandAction::IndexVerify
     )
 }
 
@@ -684,6 +770,9 @@ async fn run_eval(args: EvalArgs, cache_cfg: CacheConfig) -> Result<()> {
         profiles: args.profiles.clone(),
         models: args.models.clone(),
         cache_mode: Some(args.cache_mode.as_domain()),
+        output_format: args.out_junit.as_ref().map(|_| EvalOutputFormat::Junit),
+        output_path: args.out_junit.clone(),
+        fail_under: eval_thresholds(args.fail_under_mrr, args.fail_under_recall),
     };
     let request = CommandRequest {
         action: CommandAction::Eval,
@@ -694,7 +783,7 @@ async fn run_eval(args: EvalArgs, cache_cfg: CacheConfig) -> Result<()> {
 
     let response = command::execute(request, cache_cfg).await;
 
-    let eval_out = if response.is_error() {
+    let eval_out = if response.data.is_null() {
         None
     } else {
         Some(
@@ -720,25 +809,27 @@ async fn run_eval(args: EvalArgs, cache_cfg: CacheConfig) -> Result<()> {
 
     if args.json {
         println!("{}", serde_json::to_string_pretty(&response)?);
-    } else if response.is_error() {
-        eprintln!(
-            "Error: {}",
-            response.message.as_deref().unwrap_or("Unknown error")
-        );
-        std::process::exit(1);
-    } else if let Some(out) = eval_out {
+    } else if let Some(out) = &eval_out {
+        match args.format {
+            EvalFormatFlag::Json => {}
+            EvalFormatFlag::Csv => print!("{}", report::render_eval_csv(out)),
+            EvalFormatFlag::Jsonl => print!("{}", report::render_eval_jsonl(out)?),
+        }
         for run in &out.runs {
             eprintln!(
-                "profile={} models={} mean_mrr={:.3} mean_recall={:.3} p95_ms={} mean_bytes={:.1}",
+                "profile={} models={} mean_mrr={:.3} mean_recall={:.3} mean_ndcg={:.3} map={:.3} p95_ms={} mean_bytes={:.1}",
                 run.profile,
                 run.models.join(","),
                 run.summary.mean_mrr,
                 run.summary.mean_recall,
+                run.summary.mean_ndcg,
+                run.summary.map,
                 run.summary.p95_latency_ms,
                 run.summary.mean_bytes
             );
         }
     }
+    exit_on_error_response(&response, args.json);
 
     Ok(())
 }
@@ -765,6 +856,10 @@ async fn run_eval_compare(args: EvalCompareArgs, cache_cfg: CacheConfig) -> Resu
             models: args.b_models.clone(),
         },
         cache_mode: Some(args.cache_mode.as_domain()),
+        fail_if_b_worse_by: eval_thresholds(
+            args.fail_if_b_worse_by_mrr,
+            args.fail_if_b_worse_by_recall,
+        ),
     };
     let request = CommandRequest {
         action: CommandAction::EvalCompare,
@@ -775,7 +870,7 @@ async fn run_eval_compare(args: EvalCompareArgs, cache_cfg: CacheConfig) -> Resu
 
     let response = command::execute(request, cache_cfg).await;
 
-    let compare_out = if response.is_error() {
+    let compare_out = if response.data.is_null() {
         None
     } else {
         Some(
@@ -804,15 +899,9 @@ async fn run_eval_compare(args: EvalCompareArgs, cache_cfg: CacheConfig) -> Resu
 
     if args.json {
         println!("{}", serde_json::to_string_pretty(&response)?);
-    } else if response.is_error() {
+    } else if let Some(out) = &compare_out {
         eprintln!(
-            "Error: {}",
-            response.message.as_deref().unwrap_or("Unknown error")
-        );
-        std::process::exit(1);
-    } else if let Some(out) = compare_out {
-        eprintln!(
-            "A={} B={} Δmrr={:.3} Δrecall={:.3} Δp95_ms={} (wins: A={} B={} ties={})",
+            "A={} B={} Δmrr={:.3} Δrecall={:.3} Δp95_ms={} (wins: A={} B={} ties={}, p={:.3})",
             out.a.profile,
             out.b.profile,
             out.summary.delta_mean_mrr,
@@ -820,14 +909,42 @@ async fn run_eval_compare(args: EvalCompareArgs, cache_cfg: CacheConfig) -> Resu
             out.summary.delta_p95_latency_ms,
             out.summary.a_wins,
             out.summary.b_wins,
-            out.summary.ties
+            out.summary.ties,
+            out.summary.p_value
         );
     }
+    exit_on_error_response(&response, args.json);
 
     Ok(())
 }
 
+fn eval_thresholds(mean_mrr: Option<f64>, mean_recall: Option<f64>) -> Option<EvalThresholds> {
+    (mean_mrr.is_some() || mean_recall.is_some()).then_some(EvalThresholds {
+        mean_mrr,
+        mean_recall,
+    })
+}
+
+/// Exits with status 1 for error responses, including eval quality gates that failed after
+/// the results were printed.
+fn exit_on_error_response(response: &CommandResponse, json: bool) {
+    if !response.is_error() {
+        return;
+    }
+    if !json {
+        eprintln!(
+            "Error: {}",
+            response.message.as_deref().unwrap_or("Unknown error")
+        );
+    }
+    std::process::exit(1);
+}
+
 async fn run_command(args: CommandArgs, cache_cfg: CacheConfig) -> Result<()> {
+    if let Some(name) = &args.print_schema {
+        return print_command_schema(name, args.pretty);
+    }
+
     let raw = read_payload(&args)?;
     let request: CommandRequest =
         serde_json::from_str(&raw).context("Invalid JSON passed to --json/--file")?;
@@ -859,6 +976,24 @@ async fn run_command(args: CommandArgs, cache_cfg: CacheConfig) -> Result<()> {
     Ok(())
 }
 
+fn print_command_schema(name: &str, pretty: bool) -> Result<()> {
+    let schema = if name == "all" {
+        command::schema::all_action_schemas()
+    } else {
+        let action: CommandAction =
+            serde_json::from_value(serde_json::Value::String(name.to_string()))
+                .with_context(|| format!("Unknown action '{name}'"))?;
+        command::schema::action_schema(action)
+    };
+    let output = if pretty {
+        serde_json::to_string_pretty(&schema)?
+    } else {
+        serialize_json(&schema)?
+    };
+    println!("{output}");
+    Ok(())
+}
+
 fn read_payload(args: &CommandArgs) -> Result<String> {
     if let Some(raw) = &args.json {
         return Ok(raw.clone());
@@ -888,6 +1023,8 @@ async fn run_index(args: IndexArgs, cache_cfg: CacheConfig) -> Result<()> {
         full: args.force,
         models: args.models.clone(),
         experts: args.experts,
+        dry_run: args.dry_run,
+        clear_graph_cache: args.clear_graph_cache,
     };
     let request = CommandRequest {
         action: CommandAction::Index,
@@ -906,6 +1043,14 @@ async fn run_index(args: IndexArgs, cache_cfg: CacheConfig) -> Result<()> {
             response.message.as_deref().unwrap_or("Unknown error")
         );
         std::process::exit(1);
+    } else if args.dry_run {
+        if let Ok(plan_resp) = serde_json::from_value::<IndexPlanResponse>(response.data) {
+            let plan = plan_resp.plan;
+            eprintln!(
+                "Dry run: would index {} of {} files (~{} chunks, {} unchanged)",
+                plan.files, plan.total_files, plan.estimated_chunks, plan.unchanged_files
+            );
+        }
     } else if let Ok(index_resp) = serde_json::from_value::<IndexResponse>(response.data) {
         eprintln!(
             "Indexed {} files, {} chunks in {}ms",
@@ -923,6 +1068,8 @@ async fn run_search(args: SearchArgs, cache_cfg: CacheConfig) -> Result<()> {
         limit: Some(args.limit),
         project: Some(path.clone()),
         trace: None,
+        query_type: args.query_type,
+        models: args.models.clone(),
     };
     let request = CommandRequest {
         action: CommandAction::Search,
@@ -969,6 +1116,8 @@ async fn run_get_context(args: GetContextArgs, cache_cfg: CacheConfig) -> Result
             limit: Some(args.limit),
             project: Some(path.clone()),
             trace: None,
+            query_type: None,
+            models: Vec::new(),
         };
         let request = CommandRequest {
             action: CommandAction::Search,
@@ -1081,6 +1230,8 @@ async fn run_map(args: MapArgs, cache_cfg: CacheConfig) -> Result<()> {
         project: Some(path.clone()),
         depth: args.depth,
         limit: args.limit,
+        min_symbol_score: args.min_symbol_score,
+        public_symbols_only: args.public_only,
     };
     let request = CommandRequest {
         action: CommandAction::Map,
@@ -1228,19 +1379,28 @@ async fn run_context_pack(args: ContextPackArgs, cache_cfg: CacheConfig) -> Resu
         None
     };
     let include_docs = if args.exclude_docs { Some(false) } else { None };
+    let related_budget = args
+        .related_budget
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()
+        .context("Invalid --related-budget JSON")?;
     let payload = ContextPackPayload {
         query: args.query.clone(),
         limit: Some(args.limit),
         project: Some(path.clone()),
         strategy,
         max_chars: args.max_chars,
+        max_tokens: args.max_tokens,
         max_related_per_primary: args.max_related_per_primary,
+        related_budget,
         prefer_code,
         include_docs,
         related_mode: args.related_mode.clone(),
         trace: if args.trace { Some(true) } else { None },
         language: args.language.clone(),
         reuse_graph: Some(true),
+        models: args.models.clone(),
     };
     let request = CommandRequest {
         action: CommandAction::ContextPack,
@@ -1274,7 +1434,22 @@ async fn run_context_pack(args: ContextPackArgs, cache_cfg: CacheConfig) -> Resu
 
 async fn run_install_models(args: InstallModelsArgs) -> Result<()> {
     let model_dir = models::resolve_model_dir();
-    let report = models::install_models(&model_dir, &args.models, args.force, args.dry_run).await?;
+    let quiet = args.json;
+    let report = models::install_models(
+        &model_dir,
+        &args.models,
+        args.force,
+        args.dry_run,
+        &mut |progress| {
+            if progress.done && !quiet {
+                eprintln!(
+                    "Fetched {} ({} bytes)",
+                    progress.asset, progress.downloaded_bytes
+                );
+            }
+        },
+    )
+    .await?;
 
     if args.json {
         println!("{}", serde_json::to_string_pretty(&report)?);
@@ -1296,7 +1471,8 @@ async fn run_install_models(args: InstallModelsArgs) -> Result<()> {
 
 async fn run_doctor(args: DoctorArgs) -> Result<()> {
     let model_dir = models::resolve_model_dir();
-    let report = models::doctor(&model_dir);
+    let mut report = models::doctor(&model_dir);
+    models::probe_embedding_device(&mut report).await;
 
     let ok = report.manifest_ok
         && report.models.iter().all(|m| m.ok)
@@ -1325,6 +1501,15 @@ async fn run_doctor(args: DoctorArgs) -> Result<()> {
         } else if let Some(err) = &report.gpu_error {
             eprintln!("GPU/runtime: error ({err})");
         }
+        if let Some(probe) = &report.embedding_probe {
+            eprintln!(
+                "Embedding device: {} (model {}, load {} ms, embed {} ms)",
+                probe.device.as_str(),
+                probe.model_id,
+                probe.load_ms,
+                probe.embed_ms
+            );
+        }
 
         for model in &report.models {
             if model.ok {
@@ -1397,8 +1582,10 @@ async fn http_handler(
     let request: CommandRequest = match serde_json::from_slice(&body) {
         Ok(request) => request,
         Err(err) => {
-            let response =
-                http_error_response("invalid_request", format!("Invalid JSON request: {err}"));
+            let response = http_error_response(
+                ErrorCode::InvalidRequest,
+                format!("Invalid JSON request: {err}"),
+            );
             return build_http_response(StatusCode::BAD_REQUEST, response);
         }
     };
@@ -1406,7 +1593,7 @@ async fn http_handler(
     build_http_response(StatusCode::OK, response)
 }
 
-fn http_error_response(code: &str, message: String) -> CommandResponse {
+fn http_error_response(code: ErrorCode, message: String) -> CommandResponse {
     CommandResponse {
         status: CommandStatus::Error,
         message: Some(message.clone()),
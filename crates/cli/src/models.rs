@@ -8,6 +8,15 @@ use std::io::{Read, Write};
 use std::path::{Component, Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Message prefixes of model errors; `classify_error` maps each to its own error code so
+/// agents can tell a network outage from a corrupt download.
+pub const MODEL_MANIFEST_UNAVAILABLE: &str = "Model manifest unavailable";
+pub const MODEL_NOT_IN_MANIFEST: &str = "Model not in manifest";
+pub const MODEL_NETWORK_ERROR: &str = "Model download network error";
+pub const MODEL_DOWNLOAD_REJECTED: &str = "Model download rejected";
+pub const MODEL_CHECKSUM_MISMATCH: &str = "Model checksum mismatch";
+pub const MODEL_VERIFY_FAILED: &str = "Model verification failed";
+
 #[derive(Debug, Deserialize)]
 pub struct ModelsManifest {
     pub schema_version: u32,
@@ -58,7 +67,7 @@ impl AssetSource {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct InstallModelsReport {
     pub model_dir: String,
     pub selected_models: Vec<String>,
@@ -66,6 +75,53 @@ pub struct InstallModelsReport {
     pub downloaded: Vec<String>,
 }
 
+/// Progress of one asset download, reported after every received chunk and once more with
+/// `done` set when the asset is verified.
+#[derive(Debug)]
+pub struct FetchProgress<'a> {
+    pub model: &'a str,
+    pub asset: &'a str,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub done: bool,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ModelsListReport {
+    pub model_dir: String,
+    pub models: Vec<ModelListItem>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ModelListItem {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub dimension: usize,
+    /// Every asset is present (checksums are not verified; see `models_verify`/`doctor`)
+    pub installed: bool,
+    pub present_assets: Vec<String>,
+    pub missing_assets: Vec<String>,
+    /// Total size of the present assets
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ModelsVerifyReport {
+    pub model_dir: String,
+    pub models: Vec<ModelVerifyItem>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ModelVerifyItem {
+    pub id: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub probe: Option<context_vector_store::EmbeddingProbe>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct DoctorReport {
     pub model_dir: String,
@@ -75,6 +131,9 @@ pub struct DoctorReport {
     pub allow_cpu_fallback: bool,
     pub gpu_ok: bool,
     pub gpu_error: Option<String>,
+    /// Device that ran a probe embedding with `embedding_model` (only when the model loads)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_probe: Option<context_vector_store::EmbeddingProbe>,
     pub manifest_ok: bool,
     pub manifest_error: Option<String>,
     pub models: Vec<ModelDoctorItem>,
@@ -148,19 +207,125 @@ pub(crate) fn safe_join_asset_path(model_dir: &Path, asset_path: &str) -> Result
     Ok(model_dir.join(rel))
 }
 
+/// The manifest's models restricted to `selected` (all when empty); unknown ids are an error.
+fn select_models<'a>(
+    manifest: &'a ModelsManifest,
+    selected: &[String],
+) -> Result<Vec<&'a ModelSpec>> {
+    let unknown: Vec<&str> = selected
+        .iter()
+        .map(String::as_str)
+        .filter(|id| !manifest.models.iter().any(|model| model.id == *id))
+        .collect();
+    if !unknown.is_empty() {
+        let known: Vec<&str> = manifest.models.iter().map(|m| m.id.as_str()).collect();
+        anyhow::bail!(
+            "{MODEL_NOT_IN_MANIFEST}: {} (known: {})",
+            unknown.join(", "),
+            known.join(", ")
+        );
+    }
+    let selected: HashSet<&str> = selected.iter().map(String::as_str).collect();
+    Ok(manifest
+        .models
+        .iter()
+        .filter(|model| selected.is_empty() || selected.contains(model.id.as_str()))
+        .collect())
+}
+
+fn load_manifest_for_action(model_dir: &Path) -> Result<ModelsManifest> {
+    load_manifest(model_dir).context(MODEL_MANIFEST_UNAVAILABLE)
+}
+
+/// Lists the manifest's models with the assets present under `model_dir`, without hashing.
+pub fn list_models(model_dir: &Path) -> Result<ModelsListReport> {
+    let manifest = load_manifest_for_action(model_dir)?;
+    let models = manifest
+        .models
+        .iter()
+        .map(|model| {
+            let mut item = ModelListItem {
+                id: model.id.clone(),
+                description: model.description.clone(),
+                dimension: model.dimension,
+                installed: true,
+                present_assets: Vec::new(),
+                missing_assets: Vec::new(),
+                size_bytes: 0,
+            };
+            for asset in &model.assets {
+                let size = safe_join_asset_path(model_dir, &asset.path)
+                    .ok()
+                    .and_then(|path| std::fs::metadata(path).ok())
+                    .filter(|meta| meta.is_file())
+                    .map(|meta| meta.len());
+                match size {
+                    Some(size) => {
+                        item.size_bytes += size;
+                        item.present_assets.push(asset.path.clone());
+                    }
+                    None => {
+                        item.installed = false;
+                        item.missing_assets.push(asset.path.clone());
+                    }
+                }
+            }
+            item
+        })
+        .collect();
+    Ok(ModelsListReport {
+        model_dir: model_dir.display().to_string(),
+        models,
+    })
+}
+
+/// Loads each selected model (all when empty) and embeds a probe text with it. Models with
+/// missing assets fail without loading.
+pub async fn verify_models(model_dir: &Path, selected: &[String]) -> Result<ModelsVerifyReport> {
+    let manifest = load_manifest_for_action(model_dir)?;
+    let listing = list_models(model_dir)?;
+    let mut models = Vec::new();
+    for model in select_models(&manifest, selected)? {
+        let missing = listing
+            .models
+            .iter()
+            .find(|item| item.id == model.id)
+            .map(|item| item.missing_assets.clone())
+            .unwrap_or_default();
+        let mut item = ModelVerifyItem {
+            id: model.id.clone(),
+            ok: false,
+            probe: None,
+            error: None,
+        };
+        if !missing.is_empty() {
+            item.error = Some(format!("missing assets: {}", missing.join(", ")));
+        } else {
+            match context_vector_store::probe_embedding(&model.id).await {
+                Ok(probe) => {
+                    item.ok = true;
+                    item.probe = Some(probe);
+                }
+                Err(err) => item.error = Some(format!("{err:#}")),
+            }
+        }
+        models.push(item);
+    }
+    Ok(ModelsVerifyReport {
+        model_dir: model_dir.display().to_string(),
+        models,
+    })
+}
+
 pub async fn install_models(
     model_dir: &Path,
     selected: &[String],
     force: bool,
     dry_run: bool,
+    on_progress: &mut (dyn FnMut(&FetchProgress) + Send),
 ) -> Result<InstallModelsReport> {
-    let manifest = load_manifest(model_dir)?;
-
-    let selected_set: Option<HashSet<&str>> = if selected.is_empty() {
-        None
-    } else {
-        Some(selected.iter().map(String::as_str).collect())
-    };
+    let manifest = load_manifest_for_action(model_dir)?;
+    let models = select_models(&manifest, selected)?;
 
     let mut selected_models = Vec::new();
     let mut skipped = Vec::new();
@@ -170,12 +335,7 @@ pub async fn install_models(
         .build()
         .context("Failed to build HTTP client")?;
 
-    for model in &manifest.models {
-        if let Some(set) = &selected_set {
-            if !set.contains(model.id.as_str()) {
-                continue;
-            }
-        }
+    for model in models {
         selected_models.push(model.id.clone());
 
         for asset in &model.assets {
@@ -205,7 +365,22 @@ pub async fn install_models(
             let url = asset.source.url();
             let tmp_path = temp_path_for(&local_path);
 
-            download_with_sha256(&client, &url, &tmp_path, expected.as_str()).await?;
+            download_with_sha256(
+                &client,
+                &url,
+                &tmp_path,
+                expected.as_str(),
+                &mut |downloaded_bytes, total_bytes, done| {
+                    on_progress(&FetchProgress {
+                        model: &model.id,
+                        asset: &asset.path,
+                        downloaded_bytes,
+                        total_bytes,
+                        done,
+                    });
+                },
+            )
+            .await?;
 
             // Atomic swap: move aside existing file if present.
             if local_path.exists() {
@@ -251,6 +426,7 @@ pub fn doctor(model_dir: &Path) -> DoctorReport {
             .unwrap_or(false),
         gpu_ok: false,
         gpu_error: None,
+        embedding_probe: None,
         manifest_ok: false,
         manifest_error: None,
         models: Vec::new(),
@@ -329,6 +505,21 @@ pub fn doctor(model_dir: &Path) -> DoctorReport {
     report
 }
 
+/// Records which device runs `report.embedding_model` by embedding one text. Skipped unless
+/// the model already loaded in [`doctor`]; a failed probe counts as a GPU/runtime error.
+pub async fn probe_embedding_device(report: &mut DoctorReport) {
+    if !report.gpu_ok {
+        return;
+    }
+    match context_vector_store::probe_embedding(&report.embedding_model).await {
+        Ok(probe) => report.embedding_probe = Some(probe),
+        Err(e) => {
+            report.gpu_ok = false;
+            report.gpu_error = Some(format!("Probe embedding failed: {e:#}"));
+        }
+    }
+}
+
 fn sha256_file(path: &Path) -> std::io::Result<String> {
     let mut file = File::open(path)?;
     let mut hasher = Sha256::new();
@@ -344,32 +535,42 @@ fn sha256_file(path: &Path) -> std::io::Result<String> {
     Ok(to_lower_hex(&digest))
 }
 
+/// Streams `url` into `dest`, calling `progress(downloaded, total, done)` per chunk. Errors
+/// start with [`MODEL_NETWORK_ERROR`], [`MODEL_DOWNLOAD_REJECTED`] (HTTP error status) or
+/// [`MODEL_CHECKSUM_MISMATCH`].
 async fn download_with_sha256(
     client: &Client,
     url: &str,
     dest: &Path,
     expected: &str,
+    progress: &mut (dyn FnMut(u64, Option<u64>, bool) + Send),
 ) -> Result<()> {
-    let mut response = client
+    let response = client
         .get(url)
         .send()
         .await
-        .with_context(|| format!("Download failed: GET {url}"))?
-        .error_for_status()
-        .with_context(|| format!("Download failed: GET {url}"))?;
+        .map_err(|err| anyhow::anyhow!("{MODEL_NETWORK_ERROR}: GET {url}: {err}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("{MODEL_DOWNLOAD_REJECTED}: GET {url} returned {status}");
+    }
+    let mut response = response;
+    let total = response.content_length();
 
     let mut file = std::fs::File::create(dest)
         .with_context(|| format!("Failed to create {}", dest.display()))?;
     let mut hasher = Sha256::new();
+    let mut downloaded = 0u64;
 
-    while let Some(chunk) = response
-        .chunk()
-        .await
-        .with_context(|| format!("Failed while reading HTTP body from {url}"))?
-    {
+    while let Some(chunk) = response.chunk().await.map_err(|err| {
+        std::fs::remove_file(dest).ok();
+        anyhow::anyhow!("{MODEL_NETWORK_ERROR}: reading HTTP body from {url}: {err}")
+    })? {
         file.write_all(&chunk)
             .with_context(|| format!("Failed to write {}", dest.display()))?;
         hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        progress(downloaded, total, false);
     }
     file.flush()
         .with_context(|| format!("Failed to flush {}", dest.display()))?;
@@ -379,7 +580,7 @@ async fn download_with_sha256(
         if actual != expected.to_ascii_lowercase() {
             std::fs::remove_file(dest).ok();
             anyhow::bail!(
-                "SHA256 mismatch for {}: expected {} actual {}",
+                "{MODEL_CHECKSUM_MISMATCH}: {} from {url}: expected sha256 {} actual {}",
                 dest.display(),
                 expected,
                 actual
@@ -387,6 +588,7 @@ async fn download_with_sha256(
         }
     }
 
+    progress(downloaded, total, true);
     Ok(())
 }
 
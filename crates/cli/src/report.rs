@@ -1,5 +1,6 @@
-use crate::command::{EvalCompareOutput, EvalOutput};
+use crate::command::{EvalCacheMode, EvalCompareOutput, EvalOutput, EvalPathWeight};
 use anyhow::Result;
+use serde::Serialize;
 use std::path::Path;
 use std::process::Command;
 
@@ -18,16 +19,18 @@ pub fn render_eval_report(project_root: &Path, out: &EvalOutput) -> Result<Strin
     md.push_str(&format!("- Cases: `{}`\n\n", out.dataset.cases));
 
     md.push_str("## Runs\n\n");
-    md.push_str("| profile | cache | models | mean_mrr | mean_recall | mean_overlap | p95_ms | mean_bytes |\n");
-    md.push_str("|---|---|---:|---:|---:|---:|---:|---:|\n");
+    md.push_str("| profile | cache | models | mean_mrr | mean_recall | mean_ndcg | map | mean_overlap | p95_ms | mean_bytes |\n");
+    md.push_str("|---|---|---:|---:|---:|---:|---:|---:|---:|---:|\n");
     for run in &out.runs {
         md.push_str(&format!(
-            "| `{}` | `{}` | `{}` | `{:.3}` | `{:.3}` | `{:.3}` | `{}` | `{:.1}` |\n",
+            "| `{}` | `{}` | `{}` | `{:.3}` | `{:.3}` | `{:.3}` | `{:.3}` | `{:.3}` | `{}` | `{:.1}` |\n",
             run.profile,
             format!("{:?}", run.cache_mode).to_lowercase(),
             run.models.len(),
             run.summary.mean_mrr,
             run.summary.mean_recall,
+            run.summary.mean_ndcg,
+            run.summary.map,
             run.summary.mean_overlap_ratio,
             run.summary.p95_latency_ms,
             run.summary.mean_bytes
@@ -35,6 +38,53 @@ pub fn render_eval_report(project_root: &Path, out: &EvalOutput) -> Result<Strin
     }
     md.push('\n');
 
+    for run in out
+        .runs
+        .iter()
+        .filter(|run| !run.summary.by_intent.is_empty())
+    {
+        md.push_str(&format!("## By intent (profile `{}`)\n\n", run.profile));
+        md.push_str("| intent | cases | mean_mrr | mean_recall | mean_ndcg | map |\n");
+        md.push_str("|---|---:|---:|---:|---:|---:|\n");
+        for (intent, summary) in &run.summary.by_intent {
+            md.push_str(&format!(
+                "| `{}` | `{}` | `{:.3}` | `{:.3}` | `{:.3}` | `{:.3}` |\n",
+                escape_cell(intent),
+                summary.cases,
+                summary.mean_mrr,
+                summary.mean_recall,
+                summary.mean_ndcg,
+                summary.map
+            ));
+        }
+        md.push('\n');
+    }
+
+    for run in out
+        .runs
+        .iter()
+        .filter(|run| !run.summary.by_prefix.is_empty())
+    {
+        md.push_str(&format!(
+            "## By path prefix (profile `{}`)\n\n",
+            run.profile
+        ));
+        md.push_str("| prefix | cases | mean_mrr | mean_recall | mean_ndcg | forbidden_rate |\n");
+        md.push_str("|---|---:|---:|---:|---:|---:|\n");
+        for (prefix, summary) in &run.summary.by_prefix {
+            md.push_str(&format!(
+                "| `{}` | `{}` | `{:.3}` | `{:.3}` | `{:.3}` | `{:.3}` |\n",
+                escape_cell(prefix),
+                summary.cases,
+                summary.mean_mrr,
+                summary.mean_recall,
+                summary.mean_ndcg,
+                summary.mean_forbidden_rate
+            ));
+        }
+        md.push('\n');
+    }
+
     for run in &out.runs {
         let mut cases: Vec<_> = run.cases.iter().collect();
         cases.sort_by(|a, b| {
@@ -62,6 +112,174 @@ pub fn render_eval_report(project_root: &Path, out: &EvalOutput) -> Result<Strin
     Ok(md)
 }
 
+/// One flattened `EvalCaseResult` (per case per run) for spreadsheet/notebook analysis.
+#[derive(Serialize)]
+struct EvalCaseRow<'a> {
+    profile: &'a str,
+    models: String,
+    limit: usize,
+    cache_mode: EvalCacheMode,
+    id: &'a str,
+    query: &'a str,
+    intent: Option<&'a str>,
+    mrr: f64,
+    recall: f64,
+    overlap_ratio: f64,
+    ndcg: f64,
+    average_precision: f64,
+    first_rank: Option<usize>,
+    latency_ms: u64,
+    bytes: usize,
+}
+
+const EVAL_CSV_COLUMNS: [&str; 15] = [
+    "profile",
+    "models",
+    "limit",
+    "cache_mode",
+    "id",
+    "query",
+    "intent",
+    "mrr",
+    "recall",
+    "overlap_ratio",
+    "ndcg",
+    "average_precision",
+    "first_rank",
+    "latency_ms",
+    "bytes",
+];
+
+fn eval_case_rows(out: &EvalOutput) -> impl Iterator<Item = EvalCaseRow<'_>> {
+    out.runs.iter().flat_map(|run| {
+        run.cases.iter().map(move |case| EvalCaseRow {
+            profile: &run.profile,
+            models: run.models.join(","),
+            limit: run.limit,
+            cache_mode: run.cache_mode,
+            id: &case.id,
+            query: &case.query,
+            intent: case.intent.as_deref(),
+            mrr: case.mrr,
+            recall: case.recall,
+            overlap_ratio: case.overlap_ratio,
+            ndcg: case.ndcg,
+            average_precision: case.average_precision,
+            first_rank: case.first_rank,
+            latency_ms: case.latency_ms,
+            bytes: case.bytes,
+        })
+    })
+}
+
+pub fn render_eval_csv(out: &EvalOutput) -> String {
+    let mut csv = EVAL_CSV_COLUMNS.join(",");
+    csv.push('\n');
+    for row in eval_case_rows(out) {
+        let cache_mode = match row.cache_mode {
+            EvalCacheMode::Warm => "warm",
+            EvalCacheMode::Cold => "cold",
+        };
+        let fields = [
+            escape_csv_field(row.profile),
+            escape_csv_field(&row.models),
+            row.limit.to_string(),
+            cache_mode.to_string(),
+            escape_csv_field(row.id),
+            escape_csv_field(row.query),
+            escape_csv_field(row.intent.unwrap_or_default()),
+            row.mrr.to_string(),
+            row.recall.to_string(),
+            row.overlap_ratio.to_string(),
+            row.ndcg.to_string(),
+            row.average_precision.to_string(),
+            row.first_rank
+                .map(|rank| rank.to_string())
+                .unwrap_or_default(),
+            row.latency_ms.to_string(),
+            row.bytes.to_string(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn render_eval_jsonl(out: &EvalOutput) -> Result<String> {
+    let mut jsonl = String::new();
+    for row in eval_case_rows(out) {
+        jsonl.push_str(&serde_json::to_string(&row)?);
+        jsonl.push('\n');
+    }
+    Ok(jsonl)
+}
+
+/// JUnit XML with one test suite per run and one test case per dataset case; a case fails
+/// when none of its expected results made the top `limit`.
+pub fn render_eval_junit(out: &EvalOutput) -> String {
+    let total: usize = out.runs.iter().map(|run| run.cases.len()).sum();
+    let failed = |run: &crate::command::EvalRun| {
+        run.cases
+            .iter()
+            .filter(|case| case.first_rank.is_none())
+            .count()
+    };
+    let total_failures: usize = out.runs.iter().map(failed).sum();
+    let suite_name = escape_xml(out.dataset.name.as_deref().unwrap_or("eval"));
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"{suite_name}\" tests=\"{total}\" failures=\"{total_failures}\">\n"
+    ));
+    for run in &out.runs {
+        let profile = escape_xml(&run.profile);
+        let time_s = run.cases.iter().map(|case| case.latency_ms).sum::<u64>() as f64 / 1000.0;
+        xml.push_str(&format!(
+            "  <testsuite name=\"{suite_name}.{profile}\" tests=\"{}\" failures=\"{}\" time=\"{time_s:.3}\">\n",
+            run.cases.len(),
+            failed(run)
+        ));
+        xml.push_str("    <properties>\n");
+        for (name, value) in [
+            ("models", run.models.join(",")),
+            ("limit", run.limit.to_string()),
+            ("mean_mrr", format!("{:.4}", run.summary.mean_mrr)),
+            ("mean_recall", format!("{:.4}", run.summary.mean_recall)),
+            ("mean_ndcg", format!("{:.4}", run.summary.mean_ndcg)),
+            ("map", format!("{:.4}", run.summary.map)),
+            ("p95_latency_ms", run.summary.p95_latency_ms.to_string()),
+        ] {
+            xml.push_str(&format!(
+                "      <property name=\"{name}\" value=\"{}\"/>\n",
+                escape_xml(&value)
+            ));
+        }
+        xml.push_str("    </properties>\n");
+        for case in &run.cases {
+            xml.push_str(&format!(
+                "    <testcase classname=\"{suite_name}.{profile}\" name=\"{}\" time=\"{:.3}\">\n",
+                escape_xml(&case.id),
+                case.latency_ms as f64 / 1000.0
+            ));
+            if case.first_rank.is_none() {
+                xml.push_str(&format!(
+                    "      <failure message=\"no expected result in top {}\">{}</failure>\n",
+                    run.limit,
+                    escape_xml(&case.query)
+                ));
+            }
+            xml.push_str(&format!(
+                "      <system-out>mrr={:.4} recall={:.4} bytes={}</system-out>\n",
+                case.mrr, case.recall, case.bytes
+            ));
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
 pub fn render_eval_compare_report(project_root: &Path, out: &EvalCompareOutput) -> Result<String> {
     let git = git_head(project_root);
 
@@ -82,13 +300,15 @@ pub fn render_eval_compare_report(project_root: &Path, out: &EvalCompareOutput)
 
     md.push_str("## Summary (B - A)\n\n");
     md.push_str(&format!(
-        "- A: profile `{}`, models `{}`\n",
+        "- A: profile `{}`{}, models `{}`\n",
         out.a.profile,
+        describe_path_weights(&out.a.path_weights),
         out.a.models.join(", ")
     ));
     md.push_str(&format!(
-        "- B: profile `{}`, models `{}`\n",
+        "- B: profile `{}`{}, models `{}`\n",
         out.b.profile,
+        describe_path_weights(&out.b.path_weights),
         out.b.models.join(", ")
     ));
     md.push_str(&format!(
@@ -96,10 +316,30 @@ pub fn render_eval_compare_report(project_root: &Path, out: &EvalCompareOutput)
         out.summary.delta_mean_mrr, out.summary.delta_mean_recall, out.summary.delta_p95_latency_ms
     ));
     md.push_str(&format!(
-        "- Wins: A `{}`, B `{}`, ties `{}`\n\n",
-        out.summary.a_wins, out.summary.b_wins, out.summary.ties
+        "- Wins: A `{}`, B `{}`, ties `{}` (sign test p=`{:.3}`)\n\n",
+        out.summary.a_wins, out.summary.b_wins, out.summary.ties, out.summary.p_value
     ));
 
+    if !out.summary.by_prefix.is_empty() {
+        md.push_str("## By path prefix (B - A)\n\n");
+        md.push_str("| prefix | cases | A mrr | B mrr | Δmrr | A recall | B recall | Δrecall |\n");
+        md.push_str("|---|---:|---:|---:|---:|---:|---:|---:|\n");
+        for (prefix, delta) in &out.summary.by_prefix {
+            md.push_str(&format!(
+                "| `{}` | `{}` | `{:.3}` | `{:.3}` | `{:.3}` | `{:.3}` | `{:.3}` | `{:.3}` |\n",
+                escape_cell(prefix),
+                delta.cases,
+                delta.a_mean_mrr,
+                delta.b_mean_mrr,
+                delta.delta_mean_mrr,
+                delta.a_mean_recall,
+                delta.b_mean_recall,
+                delta.delta_mean_recall
+            ));
+        }
+        md.push('\n');
+    }
+
     let mut regressions: Vec<_> = out.cases.iter().filter(|c| c.delta_mrr < 0.0).collect();
     regressions.sort_by(|a, b| {
         a.delta_mrr
@@ -176,16 +416,44 @@ fn truncate_one_line(text: &str, max_chars: usize) -> String {
     format!("{truncated}…")
 }
 
+/// ` with path_weights `glob` ×weight, …` for a run whose profile sets any, else empty.
+fn describe_path_weights(weights: &[EvalPathWeight]) -> String {
+    if weights.is_empty() {
+        return String::new();
+    }
+    let rules: Vec<String> = weights
+        .iter()
+        .map(|rule| format!("`{}` ×{}", rule.glob, rule.weight))
+        .collect();
+    format!(" with path_weights {}", rules.join(", "))
+}
+
 fn escape_cell(text: &str) -> String {
     text.replace('|', "\\|")
 }
 
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn escape_csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::command::{
         EvalCacheMode, EvalCaseResult, EvalCompareCase, EvalCompareOutput, EvalCompareSummary,
-        EvalDatasetMeta, EvalHit, EvalOutput, EvalRun, EvalRunSummary, EvalSummary,
+        EvalDatasetMeta, EvalHit, EvalOutput, EvalPathWeight, EvalRun, EvalRunSummary, EvalSummary,
     };
 
     #[test]
@@ -198,27 +466,39 @@ mod tests {
             },
             runs: vec![EvalRun {
                 profile: "general".to_string(),
+                path_weights: Vec::new(),
                 models: vec!["bge-small".to_string()],
                 limit: 5,
                 cache_mode: EvalCacheMode::Warm,
                 summary: EvalSummary {
+                    cases: 1,
                     mean_mrr: 1.0,
                     mean_recall: 1.0,
                     mean_overlap_ratio: 1.0,
+                    mean_ndcg: 1.0,
+                    map: 1.0,
                     mean_latency_ms: 5.0,
                     p50_latency_ms: 5,
                     p95_latency_ms: 6,
                     mean_bytes: 123.0,
+                    mean_forbidden_rate: 0.0,
+                    by_intent: Default::default(),
+                    by_prefix: Default::default(),
                 },
                 cases: vec![EvalCaseResult {
                     id: "case1".to_string(),
                     query: "q".to_string(),
                     expected_paths: vec!["src/lib.rs".to_string()],
                     expected_symbols: Vec::new(),
+                    forbidden_paths: Vec::new(),
                     intent: None,
                     mrr: 1.0,
                     recall: 1.0,
                     overlap_ratio: 1.0,
+                    forbidden_hits: 0,
+                    forbidden_rate: 0.0,
+                    ndcg: 1.0,
+                    average_precision: 1.0,
                     first_rank: Some(1),
                     latency_ms: 5,
                     bytes: 100,
@@ -250,32 +530,49 @@ mod tests {
             cache_mode: EvalCacheMode::Warm,
             a: EvalRunSummary {
                 profile: "a".to_string(),
+                path_weights: Vec::new(),
                 models: vec!["bge-small".to_string()],
                 limit: 5,
                 cache_mode: EvalCacheMode::Warm,
                 summary: EvalSummary {
+                    cases: 1,
                     mean_mrr: 0.5,
                     mean_recall: 1.0,
                     mean_overlap_ratio: 0.5,
+                    mean_ndcg: 0.6,
+                    map: 0.5,
                     mean_latency_ms: 10.0,
                     p50_latency_ms: 10,
                     p95_latency_ms: 11,
                     mean_bytes: 100.0,
+                    mean_forbidden_rate: 0.0,
+                    by_intent: Default::default(),
+                    by_prefix: Default::default(),
                 },
             },
             b: EvalRunSummary {
                 profile: "b".to_string(),
+                path_weights: vec![EvalPathWeight {
+                    glob: "crates/core/**".to_string(),
+                    weight: 2.0,
+                }],
                 models: vec!["bge-small".to_string()],
                 limit: 5,
                 cache_mode: EvalCacheMode::Warm,
                 summary: EvalSummary {
+                    cases: 1,
                     mean_mrr: 1.0,
                     mean_recall: 1.0,
                     mean_overlap_ratio: 1.0,
+                    mean_ndcg: 1.0,
+                    map: 1.0,
                     mean_latency_ms: 9.0,
                     p50_latency_ms: 9,
                     p95_latency_ms: 9,
                     mean_bytes: 110.0,
+                    mean_forbidden_rate: 0.0,
+                    by_intent: Default::default(),
+                    by_prefix: Default::default(),
                 },
             },
             summary: EvalCompareSummary {
@@ -288,6 +585,22 @@ mod tests {
                 a_wins: 0,
                 b_wins: 1,
                 ties: 0,
+                p_value: 1.0,
+                confidence: 0.0,
+                delta_mean_forbidden_rate: 0.0,
+                by_prefix: [(
+                    "src".to_string(),
+                    crate::command::EvalPrefixDelta {
+                        cases: 1,
+                        a_mean_mrr: 0.5,
+                        b_mean_mrr: 1.0,
+                        delta_mean_mrr: 0.5,
+                        a_mean_recall: 1.0,
+                        b_mean_recall: 1.0,
+                        delta_mean_recall: 0.0,
+                    },
+                )]
+                .into(),
             },
             cases: vec![EvalCompareCase {
                 id: "case1".to_string(),
@@ -316,7 +629,95 @@ mod tests {
         let md = render_eval_compare_report(Path::new("/tmp"), &out).expect("report");
         assert!(md.contains("# Context Finder eval compare report"));
         assert!(md.contains("Summary (B - A)"));
+        assert!(md.contains("- B: profile `b` with path_weights `crates/core/**` ×2, models"));
         assert!(md.contains("Top regressions"));
         assert!(md.contains("Top improvements"));
+        assert!(md.contains(
+            "| `src` | `1` | `0.500` | `1.000` | `0.500` | `1.000` | `1.000` | `0.000` |"
+        ));
+    }
+
+    #[test]
+    fn eval_csv_has_header_and_one_row_per_case() {
+        let case = |id: &str, query: &str, first_rank: Option<usize>| EvalCaseResult {
+            id: id.to_string(),
+            query: query.to_string(),
+            expected_paths: vec!["src/lib.rs".to_string()],
+            expected_symbols: Vec::new(),
+            forbidden_paths: Vec::new(),
+            intent: Some("lookup".to_string()),
+            mrr: first_rank.map_or(0.0, |rank| 1.0 / rank as f64),
+            recall: if first_rank.is_some() { 1.0 } else { 0.0 },
+            overlap_ratio: 0.5,
+            forbidden_hits: 0,
+            forbidden_rate: 0.0,
+            ndcg: 0.25,
+            average_precision: 0.5,
+            first_rank,
+            latency_ms: 7,
+            bytes: 42,
+            hits: Vec::new(),
+        };
+        let out = EvalOutput {
+            dataset: EvalDatasetMeta {
+                schema_version: 1,
+                name: None,
+                cases: 2,
+            },
+            runs: vec![EvalRun {
+                profile: "general".to_string(),
+                path_weights: Vec::new(),
+                models: vec!["bge-small".to_string(), "minilm".to_string()],
+                limit: 5,
+                cache_mode: EvalCacheMode::Cold,
+                summary: EvalSummary {
+                    cases: 2,
+                    mean_mrr: 0.25,
+                    mean_recall: 0.5,
+                    mean_overlap_ratio: 0.5,
+                    mean_ndcg: 0.25,
+                    map: 0.5,
+                    mean_latency_ms: 7.0,
+                    p50_latency_ms: 7,
+                    p95_latency_ms: 7,
+                    mean_bytes: 42.0,
+                    mean_forbidden_rate: 0.0,
+                    by_intent: Default::default(),
+                    by_prefix: Default::default(),
+                },
+                cases: vec![
+                    case("case1", "where is \"parse\", exactly", Some(2)),
+                    case("case2", "missing", None),
+                ],
+            }],
+        };
+
+        let csv = render_eval_csv(&out);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "profile,models,limit,cache_mode,id,query,intent,mrr,recall,overlap_ratio,ndcg,average_precision,first_rank,latency_ms,bytes",
+                "general,\"bge-small,minilm\",5,cold,case1,\"where is \"\"parse\"\", exactly\",lookup,0.5,1,0.5,0.25,0.5,2,7,42",
+                "general,\"bge-small,minilm\",5,cold,case2,missing,lookup,0,0,0.5,0.25,0.5,,7,42",
+            ]
+        );
+
+        let jsonl = render_eval_jsonl(&out).expect("jsonl");
+        let rows: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("jsonl row"))
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["query"], "where is \"parse\", exactly");
+        assert_eq!(rows[1]["first_rank"], serde_json::Value::Null);
+
+        let junit = render_eval_junit(&out);
+        assert!(junit.contains(r#"<testsuites name="eval" tests="2" failures="1">"#));
+        assert!(junit.contains(r#"<testcase classname="eval.general" name="case1" time="0.007">"#));
+        assert_eq!(junit.matches("<failure ").count(), 1);
+        assert!(
+            junit.contains(r#"<failure message="no expected result in top 5">missing</failure>"#)
+        );
     }
 }
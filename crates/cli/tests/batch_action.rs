@@ -174,6 +174,107 @@ fn batch_respects_max_chars_and_truncates() {
     assert_eq!(items[0]["id"], "index");
 }
 
+#[test]
+fn batch_content_truncation_keeps_an_oversized_item() {
+    let temp = setup_repo();
+    let root = temp.path();
+    let mut big = String::from("pub fn big() {\n");
+    for idx in 0..800 {
+        big.push_str(&format!("    let value_{idx} = {idx};\n"));
+    }
+    big.push_str("}\n");
+    fs::write(root.join("src/big.rs"), big).unwrap();
+    assert_eq!(
+        run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#)["status"],
+        "ok"
+    );
+
+    let request = |truncate: &str| {
+        format!(
+            r#"{{
+                "action":"batch",
+                "payload":{{
+                    "project":".",
+                    "max_chars":4000,
+                    "truncate":"{truncate}",
+                    "items":[
+                        {{"id":"ctx","action":"get_context","payload":{{"file":"src/big.rs","line":400,"window":400}}}}
+                    ]
+                }}
+            }}"#
+        )
+    };
+
+    let dropped = run_cli(root, &request("items"));
+    let items = dropped["data"]["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1, "{dropped}");
+    assert_eq!(items[0]["error"]["code"], "budget_exceeded");
+
+    let response = run_cli(root, &request("content"));
+    assert_eq!(response["status"], "ok");
+    let budget = &response["data"]["budget"];
+    assert_eq!(budget["truncated"], true);
+    assert!(budget["used_chars"].as_u64().unwrap() <= 4000, "{budget}");
+
+    let items = response["data"]["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1, "{response}");
+    let item = &items[0];
+    assert_eq!(item["id"], "ctx");
+    assert_eq!(item["status"], "ok", "{item}");
+    assert_eq!(item["truncated"], true);
+    assert_eq!(item["data"]["file"], "src/big.rs");
+    let after = item["data"]["window"]["after"].as_str().unwrap();
+    assert!(after.ends_with("...truncated"), "{after}");
+    assert!(after.starts_with("    let value_399 = 399;"), "{after}");
+}
+
+#[test]
+fn batch_content_truncation_keeps_rejected_and_skipped_items() {
+    let temp = setup_repo();
+    let root = temp.path();
+    let mut big = String::from("pub fn big() {\n");
+    for idx in 0..800 {
+        big.push_str(&format!("    let value_{idx} = {idx};\n"));
+    }
+    big.push_str("}\n");
+    fs::write(root.join("src/big.rs"), big).unwrap();
+    assert_eq!(
+        run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#)["status"],
+        "ok"
+    );
+
+    let request = r##"{
+        "action":"batch",
+        "payload":{
+            "project":".",
+            "max_chars":4000,
+            "truncate":"content",
+            "items":[
+                {"id":"","action":"text_search","payload":{"pattern":"big"}},
+                {"id":"narrow","action":"text_search",
+                 "when":{"exists":"#/items/missing/data"},
+                 "payload":{"pattern":"big"}},
+                {"id":"ctx","action":"get_context","payload":{"file":"src/big.rs","line":400,"window":400}}
+            ]
+        }
+    }"##;
+
+    let response = run_cli(root, request);
+    let budget = &response["data"]["budget"];
+    assert_eq!(budget["truncated"], true);
+    assert!(budget["used_chars"].as_u64().unwrap() <= 4000, "{budget}");
+
+    let items = response["data"]["items"].as_array().unwrap();
+    assert_eq!(items.len(), 3, "{response}");
+    assert_eq!(items[0]["status"], "error");
+    assert_eq!(items[0]["error"]["code"], "invalid_request");
+    assert_eq!(items[1]["id"], "narrow");
+    assert_eq!(items[1]["status"], "skipped");
+    assert_eq!(items[2]["id"], "ctx");
+    assert_eq!(items[2]["status"], "ok", "{response}");
+    assert_eq!(items[2]["truncated"], true);
+}
+
 #[test]
 fn batch_resolves_refs_between_items() {
     let temp = setup_repo();
@@ -224,3 +325,228 @@ fn batch_resolves_refs_between_items() {
         search["data"]["matches"][0]["line"]
     );
 }
+
+#[test]
+fn batch_interpolates_format_templates_from_refs() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let request = r##"{
+        "action":"batch",
+        "payload":{
+            "project":".",
+            "items":[
+                {"id":"search","action":"text_search","payload":{"pattern":"greet","max_results":1}},
+                {"id":"def","action":"text_search","payload":{
+                    "pattern": { "$format": "fn {0}(", "args": [{ "$ref": "#/items/search/data/pattern" }] },
+                    "max_results": 1
+                }},
+                {"id":"bad","action":"text_search","payload":{
+                    "pattern": { "$format": "{0}", "args": [{ "$ref": "#/items/search/data" }] }
+                }}
+            ]
+        }
+    }"##;
+
+    let response = run_cli(root, request);
+    let items = response["data"]["items"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+
+    let def = items
+        .iter()
+        .find(|item| item["id"].as_str() == Some("def"))
+        .expect("def item");
+    assert_eq!(def["status"], "ok");
+    assert_eq!(def["data"]["pattern"], "fn greet(");
+    assert!(def["data"]["matches"][0]["text"]
+        .as_str()
+        .is_some_and(|text| text.contains("pub fn greet(")));
+
+    let bad = items
+        .iter()
+        .find(|item| item["id"].as_str() == Some("bad"))
+        .expect("bad item");
+    assert_eq!(bad["status"], "error");
+    assert!(bad["message"]
+        .as_str()
+        .is_some_and(|msg| msg.contains("strings or numbers")));
+}
+
+#[test]
+fn batch_skips_items_by_when_and_refs_fall_back_to_default() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let request = r##"{
+        "action":"batch",
+        "payload":{
+            "project":".",
+            "items":[
+                {"id":"search","action":"text_search","payload":{"pattern":"no_such_symbol","max_results":1}},
+                {"id":"narrow","action":"text_search",
+                 "when":{"exists":"#/items/search/data/matches/0"},
+                 "payload":{"pattern":"println","max_results":1}},
+                {"id":"broad","action":"text_search",
+                 "when":{"empty":"#/items/search/data/matches"},
+                 "payload":{"pattern":"greet","max_results":1}},
+                {"id":"ctx","action":"get_context","payload":{
+                    "file": { "$ref": "#/items/narrow/data/matches/0/file", "$default": "src/lib.rs" },
+                    "line": { "$ref": "#/items/narrow/data/matches/0/line", "$default": 2 },
+                    "window": 0
+                }},
+                {"id":"bad","action":"text_search","when":{"maybe":"#/x"},"payload":{"pattern":"x"}}
+            ]
+        }
+    }"##;
+
+    let response = run_cli(root, request);
+    let items = response["data"]["items"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let item = |id: &str| {
+        items
+            .iter()
+            .find(|item| item["id"].as_str() == Some(id))
+            .cloned()
+            .unwrap_or_else(|| panic!("missing item {id}"))
+    };
+
+    let narrow = item("narrow");
+    assert_eq!(narrow["status"], "skipped");
+    assert!(narrow["data"].is_null());
+    assert!(narrow.get("message").is_none());
+
+    assert_eq!(item("broad")["status"], "ok");
+
+    let ctx = item("ctx");
+    assert_eq!(ctx["status"], "ok", "{ctx}");
+    assert_eq!(ctx["data"]["file"], "src/lib.rs");
+    assert_eq!(ctx["data"]["line"], 2);
+
+    let bad = item("bad");
+    assert_eq!(bad["status"], "error");
+    assert!(bad["message"]
+        .as_str()
+        .is_some_and(|msg| msg.contains("Invalid when predicate")));
+}
+
+#[test]
+fn batch_item_timeout_ms_applies_to_items_without_their_own() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let request = r#"{
+        "action":"batch",
+        "payload":{
+            "project":".",
+            "item_timeout_ms":1,
+            "items":[
+                {"id":"index","action":"index","payload":{}},
+                {"id":"grep","action":"text_search","timeout_ms":60000,"payload":{"pattern":"greet"}}
+            ]
+        }
+    }"#;
+
+    let response = run_cli(root, request);
+    let items = response["data"]["items"].as_array().expect("items");
+    assert_eq!(items.len(), 2);
+
+    let index = &items[0];
+    assert_eq!(index["status"], "error");
+    assert_eq!(index["error"]["code"], "timeout");
+
+    let grep = &items[1];
+    assert_eq!(grep["status"], "ok", "{grep}");
+}
+
+#[test]
+fn batch_reports_item_timeouts_and_skips_items_past_deadline() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let request = r#"{
+        "action":"batch",
+        "payload":{
+            "project":".",
+            "deadline_ms":1,
+            "items":[
+                {"id":"index","action":"index","timeout_ms":1,"payload":{}},
+                {"id":"late","action":"text_search","payload":{"pattern":"greet"}}
+            ]
+        }
+    }"#;
+
+    let response = run_cli(root, request);
+    let items = response["data"]["items"].as_array().expect("items");
+    assert_eq!(items.len(), 2);
+
+    let index = &items[0];
+    assert_eq!(index["status"], "error");
+    assert_eq!(index["error"]["code"], "timeout");
+    assert!(
+        !index["data"].is_null(),
+        "index must run to completion: {index}"
+    );
+
+    let late = &items[1];
+    assert_eq!(late["status"], "skipped");
+    assert!(late["message"]
+        .as_str()
+        .is_some_and(|msg| msg.contains("deadline_ms=1")));
+    assert!(late["hints"]
+        .as_array()
+        .is_some_and(|hints| !hints.is_empty()));
+
+    let request = r#"{
+        "action":"batch",
+        "options":{"stale_policy":"fail"},
+        "payload":{
+            "project":".",
+            "items":[{"id":"search","action":"search","payload":{"query":"greet","limit":5}}]
+        }
+    }"#;
+    let response = run_cli(root, request);
+    assert_eq!(
+        response["data"]["items"][0]["status"], "ok",
+        "index left usable after timeout: {response}"
+    );
+}
+
+#[test]
+fn batch_keeps_meta_when_items_are_truncated() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let mut big = String::new();
+    for _ in 0..5000 {
+        big.push_str("// repeat_me\n");
+    }
+    fs::write(root.join("src/big.rs"), big).unwrap();
+
+    let request = r#"{
+        "action":"batch",
+        "payload":{
+            "project":".",
+            "max_chars":600,
+            "items":[
+                {"id":"huge","action":"text_search","payload":{"pattern":"repeat_me","max_results":1000}},
+                {"id":"again","action":"text_search","payload":{"pattern":"repeat_me","max_results":1000}}
+            ]
+        }
+    }"#;
+
+    let response = run_cli(root, request);
+    let data = &response["data"];
+    assert_eq!(data["budget"]["truncated"].as_bool(), Some(true));
+    assert!(
+        data["meta"]["project"].as_str().is_some(),
+        "meta.project kept: {response}"
+    );
+    assert!(
+        data["meta"]["profile"].as_str().is_some(),
+        "meta.profile kept: {response}"
+    );
+}
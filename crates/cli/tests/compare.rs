@@ -58,5 +58,7 @@ fn compare_search_returns_baseline_and_context() {
     let row = &queries[0];
     assert!(!row["baseline"].as_array().unwrap().is_empty());
     assert!(!row["context"].as_array().unwrap().is_empty());
+    assert!(row["only_in_baseline"].is_array(), "{row}");
+    assert!(row["only_in_context"].is_array(), "{row}");
     assert!(data["summary"]["avg_context_ms"].as_f64().unwrap() >= 0.0);
 }
@@ -0,0 +1,131 @@
+#![allow(deprecated)]
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tempfile::tempdir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+const FILES: usize = 300;
+
+async fn wait_for_socket(socket: &Path) -> Result<()> {
+    let started = tokio::time::Instant::now();
+    loop {
+        if UnixStream::connect(socket).await.is_ok() {
+            return Ok(());
+        }
+        if started.elapsed() > Duration::from_secs(2) {
+            anyhow::bail!("daemon socket did not become ready: {}", socket.display());
+        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+}
+
+async fn send_json(socket: &Path, payload: &serde_json::Value) -> Result<serde_json::Value> {
+    let mut stream = UnixStream::connect(socket)
+        .await
+        .with_context(|| format!("connect to {}", socket.display()))?;
+    let msg = serde_json::to_string(payload)? + "\n";
+    stream.write_all(msg.as_bytes()).await?;
+    stream.flush().await?;
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+fn index_files(root: &Path) -> Vec<PathBuf> {
+    let Ok(models) = std::fs::read_dir(root.join(".context-finder/indexes")) else {
+        return Vec::new();
+    };
+    models
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join("index.json"))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+#[tokio::test]
+async fn shutdown_command_drains_the_running_index_cycle() -> Result<()> {
+    let sock_dir = tempdir()?;
+    let socket = sock_dir.path().join("daemon.sock");
+    let project = tempdir()?;
+    let root = project.path();
+    std::fs::create_dir_all(root.join("src"))?;
+    for idx in 0..FILES {
+        std::fs::write(
+            root.join(format!("src/module_{idx}.rs")),
+            format!("pub fn handler_{idx}(input: u32) -> u32 {{\n    input + {idx}\n}}\n"),
+        )?;
+    }
+
+    let bin = assert_cmd::cargo::cargo_bin("context-finder");
+    let mut daemon = tokio::process::Command::new(bin)
+        .arg("daemon-loop")
+        .arg("--socket")
+        .arg(&socket)
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .env("CONTEXT_FINDER_DAEMON_TTL_MS", "60000")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .context("spawn daemon-loop")?;
+    wait_for_socket(&socket).await?;
+
+    let ping = serde_json::json!({"cmd": "ping", "project": root.to_string_lossy()});
+    let resp = send_json(&socket, &ping).await?;
+    anyhow::ensure!(resp["status"] == "ok", "ping failed: {resp}");
+
+    // Shut down as soon as the bootstrap cycle is running (or, on a fast machine, already done).
+    let status = serde_json::json!({"cmd": "status", "project": ""});
+    let started = tokio::time::Instant::now();
+    loop {
+        let resp = send_json(&socket, &status).await?;
+        if resp["projects"][0]["indexing"] == true || !index_files(root).is_empty() {
+            break;
+        }
+        anyhow::ensure!(
+            started.elapsed() < Duration::from_secs(10),
+            "bootstrap index never started: {resp}"
+        );
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    let resp = send_json(
+        &socket,
+        &serde_json::json!({"cmd": "shutdown", "project": ""}),
+    )
+    .await?;
+    anyhow::ensure!(resp["status"] == "ok", "shutdown failed: {resp}");
+
+    let exit = tokio::time::timeout(Duration::from_secs(30), daemon.wait())
+        .await
+        .context("daemon did not exit after shutdown")??;
+    anyhow::ensure!(exit.success(), "daemon exited with {exit}");
+    anyhow::ensure!(!socket.exists(), "socket left behind after shutdown");
+
+    let indexes = index_files(root);
+    anyhow::ensure!(!indexes.is_empty(), "no index written");
+    for path in indexes {
+        let index: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)
+            .with_context(|| format!("corrupt index {}", path.display()))?;
+        let files: HashSet<&str> = index["vectors"]
+            .as_object()
+            .context("index has no vectors")?
+            .keys()
+            .filter_map(|id| id.split(':').next())
+            .collect();
+        anyhow::ensure!(
+            files.len() == FILES,
+            "{} covers {} of {FILES} files",
+            path.display(),
+            files.len()
+        );
+    }
+    Ok(())
+}
@@ -87,3 +87,105 @@ fn eval_search_reports_mrr_and_recall() {
     assert!(mean_mrr > 0.0);
     assert!(mean_overlap > 0.0);
 }
+
+#[test]
+fn eval_fail_under_fails_the_command_but_keeps_results() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    fs::write(
+        root.join("dataset.json"),
+        r#"
+        {
+          "schema_version": 1,
+          "cases": [
+            {
+              "id": "path_query",
+              "query": "src/lib.rs",
+              "expected_paths": ["src/lib.rs"]
+            }
+          ]
+        }
+        "#,
+    )
+    .unwrap();
+
+    let index_response = run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    assert_eq!(index_response["status"], "ok");
+
+    let passing = r#"{"action":"eval","payload":{"path":".","dataset":"dataset.json","limit":5,
+        "output_format":"junit","output_path":"reports/eval.xml","fail_under":{"mean_recall":0.5}}}"#;
+    let response = run_cli(root, passing);
+    assert_eq!(response["status"], "ok", "{response}");
+    let junit = fs::read_to_string(root.join("reports/eval.xml")).unwrap();
+    assert!(junit.contains(r#"<testsuites name="eval" tests="1" failures="0">"#));
+    assert!(junit.contains(r#"name="path_query""#));
+
+    let failing = r#"{"action":"eval","payload":{"path":".","dataset":"dataset.json","limit":5,
+        "fail_under":{"mean_mrr":1.5}}}"#;
+    let (ok, response) = run_cli_raw(root, failing);
+    assert!(!ok, "quality gate must fail the process: {response}");
+    assert_eq!(response["status"], "error");
+    assert_eq!(response["error"]["code"], "quality_gate_failed");
+    assert!(response["message"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("mean_mrr"));
+    assert!(response["data"]["runs"][0]["summary"]["mean_mrr"].is_number());
+}
+
+#[test]
+fn eval_penalizes_forbidden_hits_and_breaks_down_by_prefix() {
+    let temp = setup_repo();
+    let root = temp.path();
+    fs::create_dir_all(root.join("gen")).unwrap();
+    fs::write(
+        root.join("gen/greet_bindings.rs"),
+        "pub fn greet_binding(name: &str) {\n    crate::greet(name);\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("dataset.json"),
+        r#"
+        {
+          "schema_version": 2,
+          "name": "negative",
+          "breakdown_prefixes": ["src", "gen"],
+          "cases": [
+            {
+              "id": "greet",
+              "query": "greet",
+              "expected_paths": ["src/lib.rs"],
+              "forbidden_paths": ["gen/"]
+            }
+          ]
+        }
+        "#,
+    )
+    .unwrap();
+
+    let index_response = run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    assert_eq!(index_response["status"], "ok");
+
+    let eval_response = run_cli(
+        root,
+        r#"{"action":"eval","payload":{"path":".","dataset":"dataset.json","limit":10}}"#,
+    );
+    assert_eq!(eval_response["data"]["dataset"]["schema_version"], 2);
+    let run = &eval_response["data"]["runs"][0];
+    let case = &run["cases"][0];
+    let hits = case["hits"].as_array().unwrap();
+    let forbidden = hits
+        .iter()
+        .filter(|hit| hit["file"].as_str().unwrap().starts_with("gen/"))
+        .count();
+    assert!(forbidden > 0, "{case}");
+    assert_eq!(case["forbidden_hits"], forbidden);
+    let rate = forbidden as f64 / hits.len() as f64;
+    assert!((case["forbidden_rate"].as_f64().unwrap() - rate).abs() < 1e-9);
+    assert!((run["summary"]["mean_forbidden_rate"].as_f64().unwrap() - rate).abs() < 1e-9);
+
+    let by_prefix = run["summary"]["by_prefix"].as_object().unwrap();
+    assert_eq!(by_prefix.keys().collect::<Vec<_>>(), vec!["src"]);
+    assert_eq!(by_prefix["src"]["cases"], 1);
+}
@@ -84,8 +84,69 @@ fn eval_compare_reports_deltas() {
     let compare_response = run_cli(root, compare_request);
     assert_eq!(compare_response["status"], "ok");
     assert!(compare_response["data"]["summary"]["delta_mean_mrr"].is_number());
+    let by_prefix = &compare_response["data"]["summary"]["by_prefix"];
+    assert_eq!(by_prefix["src"]["cases"], 1);
+    assert!(by_prefix["src"]["delta_mean_mrr"].as_f64().unwrap().abs() < 1e-9);
     let cases = compare_response["data"]["cases"]
         .as_array()
         .expect("cases array");
     assert_eq!(cases.len(), 1);
 }
+
+#[test]
+fn eval_compare_measures_profile_path_weights_against_the_baseline() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    let handler = "pub fn handle_request(id: u32) -> u32 {\n    id * 2\n}\n";
+    for file in ["crates/core/src/handler.rs", "examples/handler.rs"] {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, handler).unwrap();
+    }
+    fs::create_dir_all(root.join(".context-finder/profiles")).unwrap();
+    fs::write(
+        root.join(".context-finder/profiles/tuned.json"),
+        r#"{
+          "path_weights": [
+            { "glob": "crates/core/**", "weight": 5.0 },
+            { "glob": "examples/**", "weight": 0.1 }
+          ]
+        }"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("dataset.json"),
+        r#"{
+          "schema_version": 1,
+          "cases": [
+            { "id": "handler", "query": "handle_request", "expected_paths": ["crates/core/src/handler.rs"] }
+          ]
+        }"#,
+    )
+    .unwrap();
+
+    let index_response = run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    assert_eq!(index_response["status"], "ok");
+
+    let compare_response = run_cli(
+        root,
+        r#"{
+          "action": "eval_compare",
+          "payload": {
+            "path": ".",
+            "dataset": "dataset.json",
+            "limit": 5,
+            "a": { "profile": "general" },
+            "b": { "profile": "tuned" }
+          }
+        }"#,
+    );
+    assert_eq!(compare_response["status"], "ok", "{compare_response}");
+    let data = &compare_response["data"];
+    assert!(data["a"].get("path_weights").is_none(), "{data}");
+    assert_eq!(data["b"]["path_weights"][0]["glob"], "crates/core/**");
+    let weight = data["b"]["path_weights"][1]["weight"].as_f64().unwrap();
+    assert!((weight - 0.1).abs() < 1e-6, "{data}");
+    assert_eq!(data["b"]["summary"]["mean_mrr"], 1.0, "{data}");
+    assert!(data["summary"]["delta_mean_mrr"].as_f64().unwrap() >= 0.0);
+}
@@ -0,0 +1,121 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[allow(deprecated)]
+fn run_cli(workdir: &Path, request: &str) -> Value {
+    let output = Command::cargo_bin("context-finder")
+        .expect("binary")
+        .current_dir(workdir)
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .arg("command")
+        .arg("--json")
+        .arg(request)
+        .output()
+        .expect("command run");
+
+    let body: Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    assert!(
+        output.status.success(),
+        "stdout: {body}\nrequest: {request}"
+    );
+    body
+}
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .expect("spawn git");
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn commit(root: &Path, subject: &str) -> String {
+    git(root, &["add", "-A"]);
+    git(root, &["commit", "-q", "-m", subject]);
+    git(root, &["rev-parse", "HEAD"])
+}
+
+#[test]
+fn eval_generate_mines_commits_into_a_runnable_dataset() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    git(root, &["init", "-q"]);
+
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::create_dir_all(root.join("docs")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "pub fn greet(name: &str) -> String {\n    format!(\"hi {name}\")\n}\n",
+    )
+    .unwrap();
+    fs::write(root.join("src/a.rs"), "pub const A: u32 = 1;\n").unwrap();
+    fs::write(root.join("src/b.rs"), "pub const B: u32 = 2;\n").unwrap();
+    let greet_commit = commit(root, "Add greeting helper for users");
+
+    for (file, content) in [
+        (
+            "src/lib.rs",
+            "pub fn greet(name: &str) -> String {\n    format!(\"hi {}\", name)\n}\n",
+        ),
+        ("src/a.rs", "pub const A: u32 = 1 ;\n"),
+        ("src/b.rs", "pub const B: u32 = 2 ;\n"),
+    ] {
+        fs::write(root.join(file), content).unwrap();
+    }
+    commit(root, "Reformat sources with new style");
+
+    fs::write(root.join("docs/notes.md"), "# Notes\n\nGreeting usage.\n").unwrap();
+    commit(root, "Document how greetings are used");
+
+    fs::write(root.join("src/a.rs"), "pub const A: u32 = 1 ;\n// one\n").unwrap();
+    commit(root, "fix: typo");
+
+    let mut lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+    lib.push_str("\npub fn farewell(name: &str) -> String {\n    format!(\"bye {name}\")\n}\n");
+    fs::write(root.join("src/lib.rs"), lib).unwrap();
+    let farewell_commit = commit(root, "[GREET-7] feat(lib): add farewell next to greet");
+
+    let request = r#"{"action":"eval_generate","payload":{"path":".","max_cases":5,
+        "exclude_path_globs":["docs/**"],"output_path":"datasets/history.json"}}"#;
+    let response = run_cli(root, request);
+    assert_eq!(response["status"], "ok", "{response}");
+    assert_eq!(response["data"]["cases"], 2);
+    assert_eq!(response["data"]["commits_scanned"], 5);
+    assert_eq!(response["data"]["skipped"]["rename_or_format"], 1);
+    assert_eq!(response["data"]["skipped"]["no_paths"], 1);
+    assert_eq!(response["data"]["skipped"]["vague_subject"], 1);
+
+    let dataset: Value =
+        serde_json::from_str(&fs::read_to_string(root.join("datasets/history.json")).unwrap())
+            .unwrap();
+    assert_eq!(dataset["schema_version"], 2);
+    let cases = dataset["cases"].as_array().unwrap();
+    assert_eq!(cases[0]["query"], "add farewell next to greet");
+    assert_eq!(cases[0]["source_commit"], farewell_commit.as_str());
+    assert_eq!(
+        cases[0]["expected_paths"],
+        serde_json::json!(["src/lib.rs"])
+    );
+    assert_eq!(
+        cases[0]["expected_symbols"],
+        serde_json::json!(["farewell"])
+    );
+    assert_eq!(cases[1]["query"], "Add greeting helper for users");
+    assert_eq!(cases[1]["source_commit"], greet_commit.as_str());
+
+    let index_response = run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    assert_eq!(index_response["status"], "ok");
+    let eval_response = run_cli(
+        root,
+        r#"{"action":"eval","payload":{"path":".","dataset":"datasets/history.json","limit":5}}"#,
+    );
+    assert_eq!(eval_response["status"], "ok", "{eval_response}");
+    assert_eq!(eval_response["data"]["dataset"]["cases"], 2);
+}
@@ -0,0 +1,163 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+#[allow(deprecated)]
+fn run_cli(workdir: &std::path::Path, request: &str) -> Value {
+    let output = Command::cargo_bin("context-finder")
+        .expect("binary")
+        .current_dir(workdir)
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .arg("command")
+        .arg("--json")
+        .arg(request)
+        .output()
+        .expect("command run");
+
+    let body: Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    assert!(
+        output.status.success(),
+        "stdout: {body}\nrequest: {request}"
+    );
+    body
+}
+
+fn setup_repo() -> tempfile::TempDir {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        r#"
+/// Builds the greeting line.
+pub fn format_greeting(name: &str) -> String {
+    format!("hi {name}")
+}
+
+pub fn greet(name: &str) {
+    let line = format_greeting(name);
+    println!("{line}");
+}
+
+pub fn greet_all(names: &[&str]) {
+    for name in names {
+        greet(name);
+    }
+}
+"#,
+    )
+    .unwrap();
+    temp
+}
+
+fn item<'a>(response: &'a Value, id: &str) -> &'a Value {
+    response["data"]["items"]
+        .as_array()
+        .and_then(|items| items.iter().find(|item| item["id"].as_str() == Some(id)))
+        .unwrap_or_else(|| panic!("missing item {id}: {response}"))
+}
+
+#[test]
+fn graph_actions_return_mcp_shaped_reports_with_graph_meta() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let request = r#"{
+        "action":"batch",
+        "payload":{
+            "project":".",
+            "items":[
+                {"id":"index","action":"index","payload":{}},
+                {"id":"impact","action":"impact","payload":{"symbol":"format_greeting","depth":2}},
+                {"id":"trace","action":"trace","payload":{"from":"greet_all","to":"format_greeting"}},
+                {"id":"explain","action":"explain","payload":{"symbol":"format_greeting"}},
+                {"id":"overview","action":"overview","payload":{}},
+                {"id":"missing","action":"explain","payload":{"symbol":"no_such_symbol"}}
+            ]
+        }
+    }"#;
+    let response = run_cli(root, request);
+    assert_eq!(response["status"], "ok");
+
+    let impact = item(&response, "impact");
+    assert_eq!(impact["status"], "ok", "{impact}");
+    assert_eq!(impact["data"]["symbol"], "format_greeting");
+    assert_eq!(impact["data"]["definition"]["file"], "src/lib.rs");
+    let direct = impact["data"]["direct"].as_array().unwrap();
+    assert!(
+        direct.iter().any(|usage| usage["symbol"] == "greet"),
+        "{direct:?}"
+    );
+    assert!(impact["data"]["mermaid"]
+        .as_str()
+        .unwrap()
+        .starts_with("graph LR"));
+    assert!(impact["meta"]["graph_nodes"].as_u64().unwrap_or(0) > 0);
+    assert!(impact["meta"]["graph_cache"].is_boolean());
+    assert!(impact["meta"]["graph_cache_hit_count"].is_u64());
+
+    let trace = item(&response, "trace");
+    assert_eq!(trace["data"]["found"], true, "{trace}");
+    let steps: Vec<&str> = trace["data"]["path"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|step| step["symbol"].as_str())
+        .collect();
+    assert_eq!(steps, vec!["greet_all", "greet", "format_greeting"]);
+
+    let explain = item(&response, "explain");
+    assert_eq!(explain["data"]["kind"], "Function");
+    assert!(explain["data"]["documentation"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("Builds the greeting line"));
+
+    let overview = item(&response, "overview");
+    assert!(
+        overview["data"]["graph_stats"]["nodes"]
+            .as_u64()
+            .unwrap_or(0)
+            > 0
+    );
+    assert!(overview["data"]["module_graph"]["nodes"].is_array());
+
+    let missing = item(&response, "missing");
+    assert_eq!(missing["status"], "error");
+    assert!(missing["message"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("Symbol 'no_such_symbol' not found"));
+}
+
+#[test]
+fn impact_takes_its_symbol_from_a_search_result_via_ref() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let request = r##"{
+        "action":"batch",
+        "payload":{
+            "project":".",
+            "items":[
+                {"id":"index","action":"index","payload":{}},
+                {"id":"hit","action":"search","payload":{"query":"format_greeting","limit":1}},
+                {"id":"usages","action":"impact","payload":{
+                    "symbol":{"$ref":"#/items/hit/data/results/0/symbol","$default":"format_greeting"},
+                    "depth":1
+                }}
+            ]
+        }
+    }"##;
+    let response = run_cli(root, request);
+
+    let hit = item(&response, "hit");
+    let usages = item(&response, "usages");
+    assert_eq!(usages["status"], "ok", "{usages}");
+    let expected = hit["data"]["results"][0]["symbol"]
+        .as_str()
+        .unwrap_or("format_greeting");
+    assert_eq!(usages["data"]["symbol"], expected);
+    assert!(usages["data"]["transitive"].as_array().unwrap().is_empty());
+}
@@ -102,3 +102,35 @@ fn responses_include_index_state_and_stale_is_detected() {
         "expected at least one warn hint when index is stale"
     );
 }
+
+#[test]
+fn search_results_report_when_their_file_was_indexed() {
+    let temp = setup_repo();
+    let root = temp.path();
+    run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+
+    let search_request = r#"{"action":"search","options":{"stale_policy":"warn"},"payload":{"query":"greet","limit":3,"project":"."}}"#;
+    let response = run_cli(root, search_request);
+    let results = response["data"]["results"].as_array().expect("results");
+    assert!(!results.is_empty(), "{response}");
+    let indexed_at: Vec<u64> = results
+        .iter()
+        .map(|r| r["indexed_at_ms"].as_u64().expect("indexed_at_ms"))
+        .collect();
+    assert!(results.iter().all(|r| r["file_mtime_ms"].is_u64()));
+    assert_eq!(
+        response["data"]["oldest_result_ms"].as_u64(),
+        indexed_at.iter().copied().min()
+    );
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    fs::write(root.join("src/lib.rs"), "pub fn greet() {}\n").unwrap();
+    let response = run_cli(root, search_request);
+    let hints = response["hints"].as_array().cloned().unwrap_or_default();
+    assert!(
+        hints.iter().any(|h| h["text"]
+            .as_str()
+            .is_some_and(|t| t.contains("changed on disk since they were indexed: src/lib.rs"))),
+        "expected a changed-file hint, got {hints:?}"
+    );
+}
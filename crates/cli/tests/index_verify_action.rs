@@ -0,0 +1,97 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+#[allow(deprecated)]
+fn run_cli(workdir: &std::path::Path, request: &str) -> Value {
+    let output = Command::cargo_bin("context-finder")
+        .expect("binary")
+        .current_dir(workdir)
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .arg("command")
+        .arg("--json")
+        .arg(request)
+        .output()
+        .expect("command run");
+
+    serde_json::from_slice(&output.stdout).expect("valid json")
+}
+
+#[test]
+fn index_verify_reports_gates_and_repairs_drift() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/a.rs"),
+        "/// Alpha constant used by the parser.\npub fn alpha() -> u32 {\n    let base = 40;\n    base + 1\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/b.rs"),
+        "/// Beta constant used by the lexer.\npub fn beta() -> u32 {\n    let base = 40;\n    base + 2\n}\n",
+    )
+    .unwrap();
+
+    let response = run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    assert_eq!(response["status"], "ok", "{response}");
+
+    let response = run_cli(root, r#"{"action":"index_verify","payload":{"path":"."}}"#);
+    assert_eq!(response["status"], "ok", "{response}");
+    let model = &response["data"]["models"][0];
+    assert_eq!(model["report"]["totals"]["unembedded"], 0);
+    assert!(model["report"]["files"].as_array().unwrap().is_empty());
+
+    // Drop the vector of src/a.rs, mark src/b.rs stale and add an orphan.
+    let index_path = root.join(".context-finder/indexes/bge-small/index.json");
+    let mut index: Value = serde_json::from_str(&fs::read_to_string(&index_path).unwrap()).unwrap();
+    let vectors = index["vectors"].as_object_mut().unwrap();
+    let a_id = vectors
+        .keys()
+        .find(|id| id.starts_with("src/a.rs:"))
+        .unwrap()
+        .clone();
+    let b_id = vectors
+        .keys()
+        .find(|id| id.starts_with("src/b.rs:"))
+        .unwrap()
+        .clone();
+    let mut orphan = vectors.remove(&a_id).unwrap();
+    vectors.get_mut(&b_id).unwrap()["doc_hash"] = Value::from(1u64);
+    orphan["doc_hash"] = Value::from(0u64);
+    vectors.insert("src/gone.rs:1:3".to_string(), orphan);
+    fs::write(&index_path, serde_json::to_vec(&index).unwrap()).unwrap();
+
+    let response = run_cli(root, r#"{"action":"index_verify","payload":{"path":"."}}"#);
+    assert_eq!(response["status"], "error", "{response}");
+    assert_eq!(
+        response["error"]["code"], "quality_gate_failed",
+        "{response}"
+    );
+    let totals = &response["data"]["models"][0]["report"]["totals"];
+    assert_eq!(totals["unembedded"], 1);
+    assert_eq!(totals["orphaned"], 1);
+    assert_eq!(totals["stale"], 1);
+    assert_eq!(totals["dimension_mismatch"], 0);
+
+    let response = run_cli(
+        root,
+        r#"{"action":"index_verify","payload":{"path":".","max_drift_chunks":3}}"#,
+    );
+    assert_eq!(response["status"], "ok", "{response}");
+
+    let response = run_cli(
+        root,
+        r#"{"action":"index_verify","payload":{"path":".","repair":true}}"#,
+    );
+    assert_eq!(response["status"], "ok", "{response}");
+    let repair = &response["data"]["models"][0]["repair"];
+    assert_eq!(repair["embedded"], 2, "{response}");
+    assert_eq!(repair["remaining"]["unembedded"], 0);
+    assert_eq!(repair["remaining"]["orphaned"], 0);
+    assert_eq!(repair["remaining"]["stale"], 0);
+
+    let response = run_cli(root, r#"{"action":"index_verify","payload":{"path":"."}}"#);
+    assert_eq!(response["status"], "ok", "{response}");
+}
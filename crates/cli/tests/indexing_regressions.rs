@@ -103,9 +103,41 @@ fn incremental_index_purges_deleted_files() {
     assert!(corpus_path.exists(), "chunk corpus should exist");
     let corpus_raw = fs::read_to_string(corpus_path).unwrap();
     let corpus: Value = serde_json::from_str(&corpus_raw).unwrap();
-    let files = corpus["files"].as_object().expect("corpus files map");
+    let shards = corpus["shards"].as_object().expect("corpus shard manifest");
+    let files: Vec<&str> = shards
+        .values()
+        .flat_map(|shard| shard["files"].as_array().expect("shard files"))
+        .filter_map(Value::as_str)
+        .collect();
+    assert!(files.contains(&"src/lib.rs"), "{files:?}");
     assert!(
-        !files.contains_key("src/dead.rs"),
+        !files.contains(&"src/dead.rs"),
         "stale corpus file entry was not purged"
     );
 }
+
+#[test]
+fn index_dry_run_reports_plan_without_writing_index() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let dry_run = run_cli(
+        root,
+        r#"{"action":"index","payload":{"path":".","dry_run":true}}"#,
+    );
+    assert_eq!(dry_run["status"], "ok");
+    assert_eq!(dry_run["data"]["dry_run"], true);
+    assert_eq!(dry_run["data"]["plan"]["total_files"], 1);
+    assert!(
+        !root
+            .join(".context-finder/indexes/bge-small/index.json")
+            .exists(),
+        "dry run must not write the index"
+    );
+
+    let index_response = run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    assert_eq!(
+        index_response["data"]["stats"]["files"],
+        dry_run["data"]["plan"]["files"]
+    );
+}
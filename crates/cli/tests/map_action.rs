@@ -0,0 +1,115 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+#[allow(deprecated)]
+fn run_cli(workdir: &std::path::Path, request: &str) -> Value {
+    let output = Command::cargo_bin("context-finder")
+        .expect("binary")
+        .current_dir(workdir)
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .arg("command")
+        .arg("--json")
+        .arg(request)
+        .output()
+        .expect("command run");
+
+    let body: Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    assert!(
+        output.status.success(),
+        "stdout: {body}\nrequest: {request}"
+    );
+    body
+}
+
+#[test]
+fn map_reports_language_mix_per_directory() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "pub fn greet(name: &str) {\n    println!(\"hi {name}\");\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/tool.py"),
+        "def greet(name):\n    print(f\"hi {name}\")\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/app.ts"),
+        "export function greet(name: string) {\n  console.log(`hi ${name}`);\n}\n",
+    )
+    .unwrap();
+
+    run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    let response = run_cli(
+        root,
+        r#"{"action":"map","payload":{"project":".","depth":1}}"#,
+    );
+
+    let nodes = response["data"]["nodes"].as_array().expect("nodes");
+    let src = nodes
+        .iter()
+        .find(|node| node["path"] == "src")
+        .expect("src node");
+    let languages = src["languages"].as_object().expect("languages map");
+    let summed: u64 = languages.values().filter_map(Value::as_u64).sum();
+    assert_eq!(Some(summed), src["chunks"].as_u64(), "{src}");
+    assert!(languages.contains_key("rust"), "{languages:?}");
+    assert!(languages.contains_key("python"), "{languages:?}");
+}
+
+#[test]
+fn map_filters_top_symbols_by_score_and_visibility() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    let mut body = String::from("pub fn render_report(items: &[u32]) -> String {\n");
+    body.push_str("    let mut out = String::new();\n");
+    for idx in 0..30 {
+        body.push_str(&format!(
+            "    out.push_str(&format!(\"line {idx}: {{}}\\n\", items.get({idx}).copied().unwrap_or_default()));\n"
+        ));
+    }
+    body.push_str("    out\n}\n\n");
+    body.push_str("fn tiny_helper(seed: u32) -> u32 {\n");
+    for idx in 0..6 {
+        body.push_str(&format!("    let seed = seed.wrapping_mul({idx} + 3);\n"));
+    }
+    body.push_str("    seed\n}\n");
+    fs::write(root.join("src/lib.rs"), body).unwrap();
+
+    run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    let names = |request: &str| -> Vec<String> {
+        let response = run_cli(root, request);
+        let nodes = response["data"]["nodes"].as_array().expect("nodes");
+        let src = nodes
+            .iter()
+            .find(|node| node["path"] == "src")
+            .expect("src node");
+        src["top_symbols"]
+            .as_array()
+            .map(|symbols| {
+                symbols
+                    .iter()
+                    .filter_map(|symbol| symbol["name"].as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let all = names(r#"{"action":"map","payload":{"project":".","depth":1}}"#);
+    assert!(all.contains(&"tiny_helper".to_string()), "{all:?}");
+    assert!(all.contains(&"render_report".to_string()), "{all:?}");
+
+    let scored =
+        names(r#"{"action":"map","payload":{"project":".","depth":1,"min_symbol_score":0.5}}"#);
+    assert_eq!(scored, vec!["render_report".to_string()]);
+
+    let public =
+        names(r#"{"action":"map","payload":{"project":".","depth":1,"public_symbols_only":true}}"#);
+    assert_eq!(public, vec!["render_report".to_string()]);
+}
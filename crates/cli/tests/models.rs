@@ -0,0 +1,180 @@
+use assert_cmd::Command;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use tempfile::tempdir;
+
+const WEIGHTS: &[u8] = b"fake onnx weights";
+
+#[allow(deprecated)]
+fn run_cli_raw(model_dir: &Path, request: &Value) -> (bool, Value) {
+    let output = Command::cargo_bin("context-finder")
+        .expect("binary")
+        .current_dir(model_dir)
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .env("CONTEXT_FINDER_MODEL_DIR", model_dir)
+        .arg("command")
+        .arg("--json")
+        .arg(request.to_string())
+        .output()
+        .expect("command run");
+
+    let body: Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    (output.status.success(), body)
+}
+
+/// Serves `WEIGHTS` at `/weights` and 404 elsewhere, one connection at a time.
+fn serve_assets() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let (status, body) = if request.starts_with("GET /weights ") {
+                ("200 OK", WEIGHTS)
+            } else {
+                ("404 Not Found", &b""[..])
+            };
+            let head = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(head.as_bytes()).ok();
+            stream.write_all(body).ok();
+        }
+    });
+    format!("http://{addr}")
+}
+
+fn write_manifest(model_dir: &Path, url: &str, sha256: &str) {
+    let manifest = json!({
+        "schema_version": 1,
+        "models": [{
+            "id": "bge-small",
+            "dimension": 384,
+            "max_length": 512,
+            "max_batch": 8,
+            "assets": [{
+                "path": "bge-small/model.onnx",
+                "sha256": sha256,
+                "source": { "type": "url", "url": url }
+            }]
+        }]
+    });
+    fs::write(model_dir.join("manifest.json"), manifest.to_string()).unwrap();
+}
+
+fn weights_sha256() -> String {
+    Sha256::digest(WEIGHTS)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[test]
+fn models_list_fetch_and_verify_install_a_model() {
+    let temp = tempdir().unwrap();
+    let model_dir = temp.path();
+    let base = serve_assets();
+    write_manifest(model_dir, &format!("{base}/weights"), &weights_sha256());
+
+    let (ok, listed) = run_cli_raw(model_dir, &json!({"action": "models_list"}));
+    assert!(ok, "{listed}");
+    let model = &listed["data"]["models"][0];
+    assert_eq!(model["id"], "bge-small");
+    assert_eq!(model["installed"], false);
+    assert_eq!(model["missing_assets"], json!(["bge-small/model.onnx"]));
+    assert!(listed["hints"].to_string().contains("models_fetch"));
+
+    let (ok, verified) = run_cli_raw(model_dir, &json!({"action": "models_verify"}));
+    assert!(!ok);
+    assert_eq!(verified["error"]["code"], "model_verify_failed");
+    assert_eq!(verified["data"]["models"][0]["ok"], false);
+
+    let (ok, fetched) = run_cli_raw(
+        model_dir,
+        &json!({"action": "models_fetch", "payload": {"models": ["bge-small"]}}),
+    );
+    assert!(ok, "{fetched}");
+    assert_eq!(
+        fetched["data"]["downloaded"],
+        json!(["bge-small/model.onnx"])
+    );
+    assert_eq!(
+        fs::read(model_dir.join("bge-small/model.onnx")).unwrap(),
+        WEIGHTS
+    );
+
+    let (ok, listed) = run_cli_raw(model_dir, &json!({"action": "models_list"}));
+    assert!(ok);
+    assert_eq!(listed["data"]["models"][0]["installed"], true);
+    assert_eq!(listed["data"]["models"][0]["size_bytes"], WEIGHTS.len());
+
+    let (ok, refetched) = run_cli_raw(model_dir, &json!({"action": "models_fetch"}));
+    assert!(ok);
+    assert_eq!(
+        refetched["data"]["skipped"],
+        json!(["bge-small/model.onnx"])
+    );
+
+    let (ok, verified) = run_cli_raw(model_dir, &json!({"action": "models_verify"}));
+    assert!(ok, "{verified}");
+    let model = &verified["data"]["models"][0];
+    assert_eq!(model["ok"], true);
+    assert_eq!(model["probe"]["device"], "stub");
+}
+
+#[test]
+fn models_fetch_failures_have_distinct_error_codes() {
+    let base = serve_assets();
+    let fetch = json!({"action": "models_fetch"});
+    let code_for = |url: &str, sha256: &str, request: &Value| {
+        let temp = tempdir().unwrap();
+        write_manifest(temp.path(), url, sha256);
+        let (ok, response) = run_cli_raw(temp.path(), request);
+        assert!(!ok, "{response}");
+        assert!(!temp.path().join("bge-small/model.onnx").exists());
+        response["error"]["code"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let wrong_sha = "0".repeat(64);
+    assert_eq!(
+        code_for(&format!("{base}/weights"), &wrong_sha, &fetch),
+        "model_checksum_mismatch"
+    );
+    assert_eq!(
+        code_for(&format!("{base}/gone"), &weights_sha256(), &fetch),
+        "model_download_rejected"
+    );
+
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+    let unreachable = format!("http://{}/weights", closed.local_addr().unwrap());
+    drop(closed);
+    assert_eq!(
+        code_for(&unreachable, &weights_sha256(), &fetch),
+        "model_network_error"
+    );
+
+    assert_eq!(
+        code_for(
+            &format!("{base}/weights"),
+            &weights_sha256(),
+            &json!({"action": "models_fetch", "payload": {"models": ["nope"]}})
+        ),
+        "model_unknown"
+    );
+
+    let temp = tempdir().unwrap();
+    let (ok, response) = run_cli_raw(temp.path(), &json!({"action": "models_list"}));
+    assert!(!ok);
+    assert_eq!(response["error"]["code"], "model_manifest_missing");
+}
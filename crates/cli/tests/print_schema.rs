@@ -0,0 +1,51 @@
+use assert_cmd::Command;
+use serde_json::Value;
+
+#[allow(deprecated)]
+fn print_schema(args: &[&str]) -> std::process::Output {
+    Command::cargo_bin("context-finder")
+        .expect("binary")
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .arg("command")
+        .arg("--print-schema")
+        .args(args)
+        .output()
+        .expect("command run")
+}
+
+#[test]
+fn search_schema_requires_query() {
+    let output = print_schema(&["search"]);
+    assert!(output.status.success(), "{output:?}");
+    let schema: Value = serde_json::from_slice(&output.stdout).expect("valid json");
+
+    assert_eq!(schema["action"], "search");
+    let required: Vec<&str> = schema["payload"]["required"]
+        .as_array()
+        .expect("payload.required")
+        .iter()
+        .filter_map(Value::as_str)
+        .collect();
+    assert_eq!(required, vec!["query"], "{schema}");
+    assert!(schema["payload"]["properties"]["limit"].is_object());
+    assert!(schema["response"]["properties"]["results"].is_object());
+}
+
+#[test]
+fn schema_without_action_covers_every_action() {
+    let output = print_schema(&[]);
+    assert!(output.status.success(), "{output:?}");
+    let schemas: Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    let schemas = schemas.as_object().expect("object keyed by action");
+
+    assert_eq!(schemas.len(), 27);
+    for (name, schema) in schemas {
+        assert_eq!(schema["action"], name.as_str());
+        assert!(schema["payload"].is_object(), "{name}");
+        assert!(schema["response"].is_object(), "{name}");
+    }
+    assert!(schemas["context_pack"]["response"]["properties"]["budget"].is_object());
+
+    let output = print_schema(&["no_such_action"]);
+    assert!(!output.status.success());
+}
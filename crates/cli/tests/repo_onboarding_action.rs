@@ -0,0 +1,73 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+#[allow(deprecated)]
+fn run_cli(workdir: &std::path::Path, request: &str) -> Value {
+    let output = Command::cargo_bin("context-finder")
+        .expect("binary")
+        .current_dir(workdir)
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .arg("command")
+        .arg("--json")
+        .arg(request)
+        .output()
+        .expect("command run");
+
+    let body: Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    assert!(
+        output.status.success(),
+        "stdout: {body}\nrequest: {request}"
+    );
+    body
+}
+
+#[test]
+fn repo_onboarding_pack_uses_configured_key_docs_and_byte_cap() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "pub fn answer() -> u32 {\n    42\n}\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("handbook")).unwrap();
+    fs::write(root.join("handbook/setup.md"), "# Setup\nRun make.\n").unwrap();
+    fs::create_dir_all(root.join("scratch")).unwrap();
+    fs::write(root.join("scratch/todo.md"), "# Todo\n").unwrap();
+    fs::write(
+        root.join("README.md"),
+        format!("# Readme\n{}\n", "x".repeat(200)),
+    )
+    .unwrap();
+    fs::create_dir_all(root.join(".context-finder")).unwrap();
+    fs::write(
+        root.join(".context-finder/config.json"),
+        r#"{"onboarding": {"key_docs_globs": ["handbook/*.md"], "max_doc_bytes": 60}}"#,
+    )
+    .unwrap();
+
+    run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    let response = run_cli(
+        root,
+        r#"{"action":"repo_onboarding_pack","payload":{"project":".","auto_index":false}}"#,
+    );
+
+    let docs = response["data"]["docs"].as_array().expect("docs");
+    let files: Vec<&str> = docs.iter().filter_map(|d| d["file"].as_str()).collect();
+    assert_eq!(files.first(), Some(&"handbook/setup.md"), "{response}");
+    assert!(!files.contains(&"scratch/todo.md"), "{files:?}");
+    let doc_bytes: usize = docs
+        .iter()
+        .filter_map(|d| d["content"].as_str())
+        .map(str::len)
+        .sum();
+    assert!(doc_bytes <= 60, "docs exceed max_doc_bytes: {doc_bytes}");
+    let readme = docs
+        .iter()
+        .find(|d| d["file"] == "README.md")
+        .expect("README.md should get the remaining doc bytes");
+    assert_eq!(readme["truncated"], true);
+}
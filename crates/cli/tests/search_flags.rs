@@ -115,3 +115,171 @@ fn search_rejects_empty_query() {
         .to_ascii_lowercase();
     assert!(error_text.contains("empty"), "should mention empty query");
 }
+
+#[test]
+fn corrupted_graph_cache_is_rebuilt_and_can_be_cleared() {
+    let temp = setup_repo();
+    let root = temp.path();
+    let cache_path = root.join(".context-finder").join("graph_cache.bin");
+
+    let index_request = r#"{"action":"index","payload":{"path":"."}}"#;
+    assert_eq!(run_cli(root, index_request)["status"], "ok");
+
+    let search_request = r#"{"action":"search_with_context","payload":{"query":"hello","limit":5,"project":".","strategy":"extended"}}"#;
+    let first = run_cli(root, search_request);
+    assert_eq!(first["status"], "ok");
+    assert!(cache_path.exists(), "graph cache must be persisted");
+
+    let second = run_cli(root, search_request);
+    assert_eq!(second["meta"]["graph_cache"], true);
+    assert!(
+        second["meta"]["graph_cache_hit_count"]
+            .as_u64()
+            .unwrap_or(0)
+            >= 1
+    );
+
+    fs::write(&cache_path, b"CFGC garbage").unwrap();
+    let rebuilt = run_cli(root, search_request);
+    assert_eq!(rebuilt["status"], "ok");
+    assert_eq!(rebuilt["meta"]["graph_cache"], false);
+    assert!(
+        rebuilt["meta"]["graph_cache_miss_count"]
+            .as_u64()
+            .unwrap_or(0)
+            >= 1
+    );
+    assert!(
+        fs::metadata(&cache_path).unwrap().len() > 12,
+        "corrupted cache must be replaced by a fresh one"
+    );
+
+    let clear_request = r#"{"action":"index","payload":{"path":".","clear_graph_cache":true}}"#;
+    assert_eq!(run_cli(root, clear_request)["status"], "ok");
+    assert!(!cache_path.exists(), "graph cache must be removed");
+}
+
+#[test]
+fn search_reports_and_honors_query_type_override() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let index_request = r#"{"action":"index","payload":{"path":"."}}"#;
+    assert_eq!(run_cli(root, index_request)["status"], "ok");
+
+    let auto = run_cli(
+        root,
+        r#"{"action":"search","payload":{"query":"greet","limit":5,"project":"."}}"#,
+    );
+    assert_eq!(auto["meta"]["query_type"], "conceptual");
+
+    let forced = run_cli(
+        root,
+        r#"{"action":"search","payload":{"query":"greet","limit":5,"project":".","query_type":"identifier"}}"#,
+    );
+    assert_eq!(forced["status"], "ok");
+    assert_eq!(forced["meta"]["query_type"], "identifier");
+
+    let (_, invalid) = run_cli_raw(
+        root,
+        r#"{"action":"search","payload":{"query":"greet","project":".","query_type":"symbol"}}"#,
+    );
+    assert_eq!(invalid["status"], "error");
+}
+
+#[test]
+fn search_trace_reports_the_weights_an_override_selects() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let index_request = r#"{"action":"index","payload":{"path":"."}}"#;
+    assert_eq!(run_cli(root, index_request)["status"], "ok");
+
+    let auto = run_cli(
+        root,
+        r#"{"action":"search","payload":{"query":"greet","limit":5,"project":".","trace":true}}"#,
+    );
+    assert_eq!(auto["meta"]["query_type"], "conceptual");
+    let weights = &auto["meta"]["weights_used"];
+    assert_eq!(weights["candidate_multiplier"], 6, "{auto}");
+    assert!(weights["semantic"].as_f64() > weights["fuzzy"].as_f64());
+
+    let forced = run_cli(
+        root,
+        r#"{"action":"search","payload":{"query":"greet","limit":5,"project":".","trace":true,"query_type":"identifier"}}"#,
+    );
+    assert_eq!(forced["meta"]["query_type"], "identifier");
+    let weights = &forced["meta"]["weights_used"];
+    assert_eq!(weights["candidate_multiplier"], 3, "{forced}");
+    assert_eq!(weights["semantic"].as_f64(), Some(0.1));
+    assert_eq!(weights["fuzzy"].as_f64(), Some(0.9));
+
+    let quiet = run_cli(
+        root,
+        r#"{"action":"search","payload":{"query":"greet","limit":5,"project":".","query_type":"identifier"}}"#,
+    );
+    assert!(quiet["meta"].get("weights_used").is_none(), "{quiet}");
+}
+
+#[test]
+fn search_applies_profile_classifier_rules_before_the_builtins() {
+    let temp = setup_repo();
+    let root = temp.path();
+    fs::create_dir_all(root.join(".context-finder/profiles")).unwrap();
+    fs::write(
+        root.join(".context-finder/profiles/quality.json"),
+        r#"{ "classifier_rules": [{ "pattern": "^greet$", "type": "identifier" }] }"#,
+    )
+    .unwrap();
+
+    let index_request = r#"{"action":"index","payload":{"path":"."}}"#;
+    assert_eq!(run_cli(root, index_request)["status"], "ok");
+
+    let ruled = run_cli(
+        root,
+        r#"{"action":"search","payload":{"query":"greet","limit":5,"project":".","trace":true}}"#,
+    );
+    assert_eq!(ruled["meta"]["query_type"], "identifier", "{ruled}");
+    assert_eq!(ruled["meta"]["weights_used"]["candidate_multiplier"], 3);
+
+    let overridden = run_cli(
+        root,
+        r#"{"action":"search","payload":{"query":"greet","limit":5,"project":".","trace":true,"query_type":"conceptual"}}"#,
+    );
+    assert_eq!(overridden["meta"]["query_type"], "conceptual");
+    assert_eq!(
+        overridden["meta"]["weights_used"]["candidate_multiplier"],
+        6
+    );
+}
+
+#[test]
+fn search_with_explicit_models_reports_per_model_timing() {
+    let temp = setup_repo();
+    let root = temp.path();
+
+    let index_request = r#"{"action":"index","payload":{"path":"."}}"#;
+    assert_eq!(run_cli(root, index_request)["status"], "ok");
+
+    let response = run_cli(
+        root,
+        r#"{"action":"search","payload":{"query":"greet","limit":5,"project":".","models":["bge-small"]}}"#,
+    );
+    assert_eq!(response["status"], "ok", "{response}");
+    let timings = response["meta"]["model_timings"]
+        .as_array()
+        .expect("model_timings");
+    assert_eq!(timings.len(), 1);
+    assert_eq!(timings[0]["model_id"], "bge-small");
+    assert_eq!(timings[0]["weight"], 1.0);
+
+    let (_, missing) = run_cli_raw(
+        root,
+        r#"{"action":"search","payload":{"query":"greet","project":".","models":["bge-small","embeddinggemma-300m"]}}"#,
+    );
+    assert_eq!(missing["status"], "error");
+    assert!(missing["message"]
+        .as_str()
+        .unwrap()
+        .contains("embeddinggemma-300m"));
+}
@@ -0,0 +1,79 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+#[allow(deprecated)]
+fn run_cli(workdir: &std::path::Path, request: &str) -> Value {
+    let output = Command::cargo_bin("context-finder")
+        .expect("binary")
+        .current_dir(workdir)
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .arg("command")
+        .arg("--json")
+        .arg(request)
+        .output()
+        .expect("command run");
+
+    serde_json::from_slice(&output.stdout).expect("valid json")
+}
+
+#[test]
+fn task_pack_next_actions_open_graph_neighbours_outside_the_primaries() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/tool.rs"),
+        "/// Registers the tool with the global registry.\npub fn register_tool(name: &str) -> usize {\n    name.len()\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/router.rs"),
+        "use crate::tool::register_tool;\n\npub fn build_router() -> usize {\n    let id = register_tool(\"search\");\n    id + 1\n}\n",
+    )
+    .unwrap();
+    fs::write(root.join("src/lib.rs"), "pub mod router;\npub mod tool;\n").unwrap();
+
+    let response = run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    assert_eq!(response["status"], "ok", "{response}");
+
+    let response = run_cli(
+        root,
+        r#"{"action":"task_pack","payload":{"intent":"register_tool","limit":1,"max_related_per_primary":0}}"#,
+    );
+    assert_eq!(response["status"], "ok", "{response}");
+    let data = &response["data"];
+    let primary_files: Vec<&str> = data["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|item| item["role"] == "primary")
+        .filter_map(|item| item["file"].as_str())
+        .collect();
+    assert!(!primary_files.is_empty(), "{response}");
+
+    let actions = data["next_actions"].as_array().unwrap();
+    let graph_action = actions
+        .iter()
+        .find(|action| {
+            action["kind"] == "open_file"
+                && action["start_line"].is_u64()
+                && action["file"]
+                    .as_str()
+                    .is_some_and(|file| !primary_files.contains(&file))
+        })
+        .unwrap_or_else(|| panic!("no graph-derived open_file action: {response}"));
+    let file = graph_action["file"].as_str().unwrap();
+    assert!(
+        file.ends_with("router.rs") || file.ends_with("tool.rs"),
+        "{response}"
+    );
+    assert!(graph_action["end_line"].as_u64() >= graph_action["start_line"].as_u64());
+
+    let scores: Vec<f64> = actions
+        .iter()
+        .map(|action| action["score"].as_f64().unwrap_or(0.0))
+        .collect();
+    assert!(scores.windows(2).all(|w| w[0] >= w[1]), "{response}");
+}
@@ -0,0 +1,82 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+#[allow(deprecated)]
+fn run_cli(workdir: &std::path::Path, request: &str) -> Value {
+    let output = Command::cargo_bin("context-finder")
+        .expect("binary")
+        .current_dir(workdir)
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .arg("command")
+        .arg("--json")
+        .arg(request)
+        .output()
+        .expect("command run");
+
+    serde_json::from_slice(&output.stdout).expect("valid json")
+}
+
+#[test]
+fn template_preview_renders_chunk_with_request_overrides() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "pub fn greet() -> &'static str {\n    \"hi\"\n}\n",
+    )
+    .unwrap();
+
+    let response = run_cli(
+        root,
+        r#"{"action":"template_preview","payload":{"project":".","file":"src/lib.rs","line":2}}"#,
+    );
+    assert_eq!(response["status"], "ok", "{response}");
+    let data = &response["data"];
+    assert_eq!(data["doc_kind"], "code");
+    assert_eq!(data["overridden"], false);
+    assert_eq!(data["start_line"], 1);
+    assert!(data["document"]["text"]
+        .as_str()
+        .unwrap()
+        .contains("pub fn greet()"));
+    assert_eq!(data["document"]["truncated"], false);
+    assert!(data["queries"].as_array().unwrap().is_empty());
+
+    let response = run_cli(
+        root,
+        r#"{"action":"template_preview","payload":{"project":".","file":"src/lib.rs","line":2,"query":"greet"},
+            "config":{"embedding":{"max_chars":256,"document":{"code":"CODE {path}\n{text}"},"query":{"identifier":"id: {text}"}}}}"#,
+    );
+    assert_eq!(response["status"], "ok", "{response}");
+    let data = &response["data"];
+    assert_eq!(data["overridden"], true);
+    assert_eq!(data["max_chars"], 256);
+    assert!(data["document"]["text"]
+        .as_str()
+        .unwrap()
+        .starts_with("CODE src/lib.rs\npub fn greet()"));
+    let queries: Vec<(&str, &str)> = data["queries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|q| (q["kind"].as_str().unwrap(), q["text"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        queries,
+        vec![
+            ("identifier", "id: greet"),
+            ("path", "Path: greet"),
+            ("conceptual", "Question: greet")
+        ]
+    );
+
+    let response = run_cli(
+        root,
+        r#"{"action":"template_preview","payload":{"project":".","file":"src/lib.rs","line":2},
+            "config":{"embedding":{"document":{"code":"{nope}"}}}}"#,
+    );
+    assert_eq!(response["status"], "error", "{response}");
+}
@@ -88,3 +88,66 @@ fn text_search_uses_env_root_when_project_missing() {
         "expected src/lib.rs in matches"
     );
 }
+
+#[test]
+fn text_search_returns_context_lines_from_the_matching_chunk() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "pub fn compute(a: u32) -> u32 {\n    let doubled = a * 2;\n    let shifted = doubled + 1;\n    shifted * 3\n}\n",
+    )
+    .unwrap();
+
+    let (ok, resp) = run_cli_raw(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    assert!(ok, "index failed: {resp}");
+
+    let req = r#"{"action":"text_search","payload":{"pattern":"shifted = ","context_lines":2,"project":"."}}"#;
+    let (ok, resp) = run_cli_raw(root, req);
+    assert!(ok, "expected ok, got {resp}");
+    assert_eq!(resp["data"]["source"], "corpus");
+    let matches = resp["data"]["matches"].as_array().expect("matches array");
+    assert_eq!(matches.len(), 1, "{resp}");
+    let hit = &matches[0];
+    assert_eq!(hit["line"], 3);
+    assert_eq!(hit["column"], 9);
+    assert_eq!(
+        hit["before"],
+        serde_json::json!([
+            "pub fn compute(a: u32) -> u32 {",
+            "    let doubled = a * 2;"
+        ])
+    );
+    assert_eq!(hit["after"], serde_json::json!(["    shifted * 3", "}"]));
+
+    let req = r#"{"action":"text_search","payload":{"pattern":"shifted = ","project":"."}}"#;
+    let (ok, resp) = run_cli_raw(root, req);
+    assert!(ok, "expected ok, got {resp}");
+    let hit = &resp["data"]["matches"][0];
+    assert!(hit.get("before").is_none() && hit.get("after").is_none());
+}
+
+#[test]
+fn text_search_whole_word_respects_unicode_identifiers() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "let maxgröße = 1;\nlet größe_alt = 2;\nlet größe = maxgröße;\n",
+    )
+    .unwrap();
+
+    for req in [
+        r#"{"action":"text_search","payload":{"pattern":"größe","whole_word":true,"project":"."}}"#,
+        r#"{"action":"text_search","payload":{"pattern":"GRÖßE","whole_word":true,"case_sensitive":false,"project":"."}}"#,
+    ] {
+        let (ok, resp) = run_cli_raw(root, req);
+        assert!(ok, "expected ok, got {resp}");
+        let matches = resp["data"]["matches"].as_array().expect("matches array");
+        assert_eq!(matches.len(), 1, "{resp}");
+        assert_eq!(matches[0]["line"], 3);
+        assert_eq!(matches[0]["column"], 5);
+    }
+}
@@ -0,0 +1,63 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use tempfile::tempdir;
+
+#[allow(deprecated)]
+fn run_cli(workdir: &std::path::Path, request: &str) -> Value {
+    let output = Command::cargo_bin("context-finder")
+        .expect("binary")
+        .current_dir(workdir)
+        .env("CONTEXT_FINDER_EMBEDDING_MODE", "stub")
+        .arg("command")
+        .arg("--json")
+        .arg(request)
+        .output()
+        .expect("command run");
+
+    let body: Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    assert!(
+        output.status.success(),
+        "stdout: {body}\nrequest: {request}"
+    );
+    body
+}
+
+#[test]
+fn warm_reports_timings_for_each_requested_stage() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "pub fn greet() -> &'static str { helper() }\nfn helper() -> &'static str { \"hi\" }\n",
+    )
+    .unwrap();
+
+    let response = run_cli(root, r#"{"action":"index","payload":{"path":"."}}"#);
+    assert_eq!(response["status"], "ok", "{response}");
+
+    let response = run_cli(
+        root,
+        r#"{"action":"warm","payload":{"project":".","language":"rust","embedder":true}}"#,
+    );
+    assert_eq!(response["status"], "ok", "{response}");
+    let stages: Vec<&str> = response["data"]["stages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|stage| stage["stage"].as_str().unwrap())
+        .collect();
+    assert_eq!(stages, vec!["prewarm", "graph", "embedder"]);
+    for stage in response["data"]["stages"].as_array().unwrap() {
+        assert!(stage["duration_ms"].is_u64(), "{stage}");
+        assert!(stage.get("error").is_none(), "{stage}");
+    }
+    assert_eq!(response["data"]["embedding_probe"]["device"], "stub");
+    assert!(response["data"]["total_ms"].is_u64());
+
+    let response = run_cli(root, r#"{"action":"warm","payload":{"project":"."}}"#);
+    let stages = response["data"]["stages"].as_array().unwrap();
+    assert_eq!(stages.len(), 1, "{response}");
+    assert_eq!(stages[0]["stage"], "prewarm");
+}
@@ -147,6 +147,11 @@ impl AstAnalyzer {
     ) {
         // Get impl target name (struct/trait being implemented)
         let impl_target = Self::extract_impl_target(content, impl_node);
+        // `impl Trait for Type`: methods are tagged so the graph can link them to the trait
+        let impl_trait = impl_node
+            .child_by_field_name("trait")
+            .map(|node| Self::trait_name(content, node))
+            .filter(|name| !name.is_empty());
 
         // Find declaration_list (contains methods in Rust)
         let mut cursor = impl_node.walk();
@@ -175,6 +180,10 @@ impl AstAnalyzer {
                             }
                         }
 
+                        if let Some(ref trait_name) = impl_trait {
+                            chunk.metadata.tags.push(format!("implements:{trait_name}"));
+                        }
+
                         chunks.push(chunk);
                     } else if kind == "const_item" || kind == "type_item" {
                         // Associated constants and types
@@ -224,6 +233,18 @@ impl AstAnalyzer {
         candidate.map(|(_, node)| Self::type_repr(content, *node))
     }
 
+    /// Bare trait name of an impl block (`io::Write` -> `Write`, `From<T>` -> `From`)
+    fn trait_name(content: &str, node: Node) -> String {
+        let repr = Self::type_repr(content, node);
+        let without_generics = repr.split('<').next().unwrap_or_default();
+        without_generics
+            .rsplit("::")
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+
     /// Render a type node without losing scoped paths or references
     fn type_repr(content: &str, node: Node) -> String {
         content[node.start_byte()..node.end_byte()]
@@ -534,8 +555,19 @@ impl AstAnalyzer {
                 _ => false,
             };
 
+            // Attributes/decorators sit between the docs and the item; step over them
+            let is_attribute = match self.language {
+                Language::Rust => line.starts_with("#[") || line.starts_with("#!["),
+                Language::Python | Language::JavaScript | Language::TypeScript => {
+                    line.starts_with('@')
+                }
+                _ => false,
+            };
+
             if is_doc {
                 doc_lines.push(lines[line_idx]);
+            } else if is_attribute && doc_lines.is_empty() {
+                continue;
             } else if !line.is_empty() {
                 // Stop if we hit a non-empty, non-comment line
                 break;
@@ -660,6 +692,31 @@ struct Point {
         assert!(has_struct);
     }
 
+    #[test]
+    fn test_rust_doc_comments_skip_attributes() {
+        let config = ChunkerConfig::default();
+        let mut analyzer = AstAnalyzer::new(config, Language::Rust).unwrap();
+
+        let code = r#"
+/// Adds one.
+#[inline]
+#[must_use]
+fn add_one(x: i32) -> i32 {
+    x + 1
+}
+"#;
+
+        let chunks = analyzer.chunk(code, "test.rs").unwrap();
+        let chunk = chunks
+            .iter()
+            .find(|c| c.metadata.symbol_name.as_deref() == Some("add_one"))
+            .unwrap();
+        assert_eq!(
+            chunk.metadata.documentation.as_deref(),
+            Some("/// Adds one.")
+        );
+    }
+
     #[test]
     fn test_python_chunking() {
         let config = ChunkerConfig::default();
@@ -5,6 +5,7 @@ use crate::error::{ChunkerError, Result};
 use crate::language::Language;
 use crate::strategy::StrategyExecutor;
 use crate::types::{ChunkMetadata, CodeChunk};
+use std::borrow::Cow;
 use std::path::Path;
 
 /// Main chunker interface for processing code
@@ -65,6 +66,10 @@ impl Chunker {
             return Err(ChunkerError::unsupported_language(language.as_str()));
         }
 
+        if let Cow::Owned(config) = self.config.for_language(language.as_str()) {
+            return Self { config }.chunk_with_language(content, file_path, language);
+        }
+
         // Try AST-based chunking for supported languages
         if language.supports_ast()
             && self.config.strategy == crate::config::ChunkingStrategy::Semantic
@@ -123,6 +128,8 @@ impl Chunker {
 
         chunks = self.merge_small_adjacent_chunks(chunks);
         chunks = Self::drop_shadowed_untyped_chunks(chunks);
+        chunks = self.merge_tiny_sibling_chunks(chunks);
+        chunks = self.split_oversized_chunks(chunks);
         chunks = self.apply_overlap(chunks);
 
         let min_tokens = self.config.min_chunk_tokens;
@@ -340,55 +347,7 @@ impl Chunker {
                     && combined_tokens <= max_tokens
                     && small_enough
                 {
-                    if !prev.content.ends_with('\n') {
-                        prev.content.push('\n');
-                    }
-                    prev.content.push_str(&chunk.content);
-
-                    prev.end_line = prev.end_line.max(chunk.end_line);
-
-                    // Merge metadata collections (keep ordering deterministic).
-                    prev.metadata
-                        .context_imports
-                        .extend(chunk.metadata.context_imports);
-                    prev.metadata.context_imports.sort();
-                    prev.metadata.context_imports.dedup();
-
-                    prev.metadata.tags.extend(chunk.metadata.tags);
-                    prev.metadata.tags.sort();
-                    prev.metadata.tags.dedup();
-
-                    prev.metadata.bundle_tags.extend(chunk.metadata.bundle_tags);
-                    prev.metadata.bundle_tags.sort();
-                    prev.metadata.bundle_tags.dedup();
-
-                    prev.metadata
-                        .related_paths
-                        .extend(chunk.metadata.related_paths);
-                    prev.metadata.related_paths.sort();
-                    prev.metadata.related_paths.dedup();
-
-                    // Degrade scalar metadata when it no longer represents a single symbol.
-                    if prev.metadata.language != chunk.metadata.language {
-                        prev.metadata.language = None;
-                    }
-                    if prev.metadata.chunk_type != chunk.metadata.chunk_type {
-                        prev.metadata.chunk_type = None;
-                    }
-                    if prev.metadata.symbol_name != chunk.metadata.symbol_name {
-                        prev.metadata.symbol_name = None;
-                    }
-                    if prev.metadata.qualified_name != chunk.metadata.qualified_name {
-                        prev.metadata.qualified_name = None;
-                    }
-                    if prev.metadata.parent_scope != chunk.metadata.parent_scope {
-                        prev.metadata.parent_scope = None;
-                    }
-                    if prev.metadata.documentation != chunk.metadata.documentation {
-                        prev.metadata.documentation = None;
-                    }
-
-                    self.normalize_chunk_metadata(prev);
+                    self.absorb_chunk(prev, chunk);
                     continue;
                 }
             }
@@ -399,6 +358,142 @@ impl Chunker {
         out
     }
 
+    /// Merge runs of consecutive chunks from one file and parent scope that are each shorter
+    /// than `min_chunk_chars` characters or `min_chunk_lines` lines (one-line consts, getters)
+    /// into a single chunk spanning them all. A run ends once the merged chunk itself reaches
+    /// those minimums.
+    fn merge_tiny_sibling_chunks(&self, chunks: Vec<CodeChunk>) -> Vec<CodeChunk> {
+        let min_chars = self.config.min_chunk_chars;
+        let min_lines = self.config.min_chunk_lines;
+        if min_chars == 0 && min_lines == 0 {
+            return chunks;
+        }
+        let is_tiny = |chunk: &CodeChunk| {
+            chunk.content.chars().count() < min_chars || chunk.content.lines().count() < min_lines
+        };
+
+        let mut out: Vec<CodeChunk> = Vec::new();
+        for chunk in chunks {
+            if let Some(prev) = out.last_mut() {
+                if is_tiny(prev)
+                    && is_tiny(&chunk)
+                    && prev.file_path == chunk.file_path
+                    && prev.metadata.parent_scope == chunk.metadata.parent_scope
+                    && chunk.start_line > prev.end_line
+                {
+                    self.absorb_chunk(prev, chunk);
+                    continue;
+                }
+            }
+            out.push(chunk);
+        }
+        out
+    }
+
+    /// Append `chunk` to `prev`: the span becomes their union, collections are merged and
+    /// scalar metadata is dropped where the two disagree.
+    fn absorb_chunk(&self, prev: &mut CodeChunk, chunk: CodeChunk) {
+        if !prev.content.ends_with('\n') {
+            prev.content.push('\n');
+        }
+        prev.content.push_str(&chunk.content);
+
+        prev.end_line = prev.end_line.max(chunk.end_line);
+
+        // Merge metadata collections (keep ordering deterministic).
+        prev.metadata
+            .context_imports
+            .extend(chunk.metadata.context_imports);
+        prev.metadata.context_imports.sort();
+        prev.metadata.context_imports.dedup();
+
+        prev.metadata.tags.extend(chunk.metadata.tags);
+        prev.metadata.tags.sort();
+        prev.metadata.tags.dedup();
+
+        prev.metadata.bundle_tags.extend(chunk.metadata.bundle_tags);
+        prev.metadata.bundle_tags.sort();
+        prev.metadata.bundle_tags.dedup();
+
+        prev.metadata
+            .related_paths
+            .extend(chunk.metadata.related_paths);
+        prev.metadata.related_paths.sort();
+        prev.metadata.related_paths.dedup();
+
+        // Degrade scalar metadata when it no longer represents a single symbol.
+        if prev.metadata.language != chunk.metadata.language {
+            prev.metadata.language = None;
+        }
+        if prev.metadata.chunk_type != chunk.metadata.chunk_type {
+            prev.metadata.chunk_type = None;
+        }
+        if prev.metadata.symbol_name != chunk.metadata.symbol_name {
+            prev.metadata.symbol_name = None;
+        }
+        if prev.metadata.qualified_name != chunk.metadata.qualified_name {
+            prev.metadata.qualified_name = None;
+        }
+        if prev.metadata.parent_scope != chunk.metadata.parent_scope {
+            prev.metadata.parent_scope = None;
+        }
+        if prev.metadata.documentation != chunk.metadata.documentation {
+            prev.metadata.documentation = None;
+        }
+
+        self.normalize_chunk_metadata(prev);
+    }
+
+    /// Split chunks longer than `max_lines` lines or `max_chunk_chars` characters into
+    /// line-aligned windows that keep the original metadata. Each window after the first repeats
+    /// trailing lines of the previous one (at least `overlap_lines` / `overlap_chars` when that
+    /// fits) so text near a boundary stays retrievable.
+    fn split_oversized_chunks(&self, mut chunks: Vec<CodeChunk>) -> Vec<CodeChunk> {
+        if let Some(max_lines) = self.config.max_lines {
+            chunks = self.split_chunks(
+                chunks,
+                SplitUnit::Lines,
+                max_lines,
+                self.config.overlap_lines,
+            );
+        }
+        if let Some(max_chars) = self.config.max_chunk_chars {
+            chunks = self.split_chunks(
+                chunks,
+                SplitUnit::Chars,
+                max_chars,
+                self.config.overlap_chars,
+            );
+        }
+        chunks
+    }
+
+    fn split_chunks(
+        &self,
+        chunks: Vec<CodeChunk>,
+        unit: SplitUnit,
+        max: usize,
+        overlap: usize,
+    ) -> Vec<CodeChunk> {
+        let mut out = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            if unit.content_size(&chunk.content) <= max {
+                out.push(chunk);
+                continue;
+            }
+            let lines: Vec<&str> = chunk.content.lines().collect();
+            for (start, end) in line_windows(&lines, max, overlap, unit) {
+                let mut piece = chunk.clone();
+                piece.content = lines[start..end].join("\n");
+                piece.start_line = chunk.start_line + start;
+                piece.end_line = (chunk.start_line + end - 1).min(chunk.end_line);
+                piece.metadata.estimated_tokens = self.estimate_chunk_tokens(&piece);
+                out.push(piece);
+            }
+        }
+        out
+    }
+
     fn apply_overlap(&self, mut chunks: Vec<CodeChunk>) -> Vec<CodeChunk> {
         let max_tokens = self.config.max_chunk_tokens;
 
@@ -661,6 +756,50 @@ impl Point {
         assert_eq!(out[1].content, "b\nc\nd");
     }
 
+    #[test]
+    fn long_function_splits_into_windows_sharing_the_configured_overlap() {
+        let config = ChunkerConfig {
+            max_chunk_chars: Some(400),
+            overlap_chars: 80,
+            ..ChunkerConfig::for_embeddings()
+        };
+        let chunker = Chunker::new(config);
+
+        let body: String = (0..60)
+            .map(|i| format!("    let value_{i:02} = compute(input, {i});\n"))
+            .collect();
+        let code = format!("fn long_function(input: u32) -> u32 {{\n{body}    0\n}}\n");
+
+        let chunks = chunker.chunk_str(&code, Some("long.rs")).unwrap();
+        let pieces: Vec<&CodeChunk> = chunks
+            .iter()
+            .filter(|c| c.metadata.symbol_name.as_deref() == Some("long_function"))
+            .collect();
+        assert!(pieces.len() > 2, "expected the function to be split");
+
+        for piece in &pieces {
+            assert!(piece.content.chars().count() <= 400);
+            assert_eq!(
+                piece.content.lines().count(),
+                piece.end_line - piece.start_line + 1
+            );
+        }
+        for pair in pieces.windows(2) {
+            let (prev, next) = (pair[0], pair[1]);
+            assert!(next.start_line <= prev.end_line);
+            let shared_lines = prev.end_line - next.start_line + 1;
+            let shared: Vec<&str> = next.content.lines().take(shared_lines).collect();
+            let prev_tail: Vec<&str> = prev
+                .content
+                .lines()
+                .skip(prev.content.lines().count() - shared_lines)
+                .collect();
+            assert_eq!(shared, prev_tail);
+            let shared_chars: usize = shared.iter().map(|l| l.chars().count() + 1).sum();
+            assert!(shared_chars >= 80);
+        }
+    }
+
     #[test]
     fn post_process_merges_small_adjacent_chunks() {
         let config = ChunkerConfig {
@@ -689,10 +828,33 @@ impl Point {
         assert!(out[0].metadata.symbol_name.is_none());
     }
 
+    #[test]
+    fn tiny_consts_collapse_into_one_chunk_spanning_them_all() {
+        let config = ChunkerConfig {
+            min_chunk_tokens: 0,
+            min_chunk_chars: 120,
+            ..ChunkerConfig::for_embeddings()
+        };
+        let chunker = Chunker::new(config);
+
+        let code = "const A: u32 = 1;\nconst B: u32 = 2;\nconst C: u32 = 3;\nconst D: u32 = 4;\nconst E: u32 = 5;\n";
+        let chunks = chunker.chunk_str(code, Some("consts.rs")).unwrap();
+
+        assert_eq!(chunks.len(), 1, "{chunks:#?}");
+        assert_eq!(chunks[0].start_line, 1);
+        assert_eq!(chunks[0].end_line, 5);
+        for name in ["A", "B", "C", "D", "E"] {
+            assert!(chunks[0].content.contains(&format!("const {name}: u32")));
+        }
+    }
+
     #[test]
     fn post_process_merges_before_min_tokens_filter() {
         let config = ChunkerConfig {
             min_chunk_tokens: 10,
+            min_chunk_chars: 0,
+            max_chunk_chars: None,
+            overlap_chars: 0,
             target_chunk_tokens: 20,
             max_chunk_tokens: 1_000,
             overlap: OverlapStrategy::None,
@@ -702,6 +864,7 @@ impl Point {
             max_imports_per_chunk: 0,
             supported_languages: Vec::new(),
             strategy: crate::config::ChunkingStrategy::LineCount,
+            ..ChunkerConfig::default()
         };
         let chunker = Chunker::new(config);
 
@@ -734,6 +897,9 @@ impl Point {
     fn post_process_infers_imports_before_filtering_small_chunks() {
         let config = ChunkerConfig {
             min_chunk_tokens: 10,
+            min_chunk_chars: 0,
+            max_chunk_chars: None,
+            overlap_chars: 0,
             target_chunk_tokens: 50,
             max_chunk_tokens: 1_000,
             overlap: OverlapStrategy::Contextual,
@@ -743,6 +909,7 @@ impl Point {
             max_imports_per_chunk: 10,
             supported_languages: Vec::new(),
             strategy: crate::config::ChunkingStrategy::LineCount,
+            ..ChunkerConfig::default()
         };
         let chunker = Chunker::new(config);
 
@@ -781,6 +948,9 @@ impl Point {
     fn post_process_drops_untyped_chunks_shadowed_by_typed_ranges() {
         let config = ChunkerConfig {
             min_chunk_tokens: 0,
+            min_chunk_chars: 0,
+            max_chunk_chars: None,
+            overlap_chars: 0,
             target_chunk_tokens: 100,
             max_chunk_tokens: 1_000,
             overlap: OverlapStrategy::Contextual,
@@ -790,6 +960,7 @@ impl Point {
             max_imports_per_chunk: 0,
             supported_languages: Vec::new(),
             strategy: crate::config::ChunkingStrategy::Semantic,
+            ..ChunkerConfig::default()
         };
         let chunker = Chunker::new(config);
 
@@ -832,6 +1003,75 @@ enum OverlapSpec {
     SlidingWindow(u8),
 }
 
+/// What a split limit counts.
+#[derive(Clone, Copy)]
+enum SplitUnit {
+    Lines,
+    Chars,
+}
+
+impl SplitUnit {
+    fn content_size(self, content: &str) -> usize {
+        match self {
+            Self::Lines => content.lines().count(),
+            Self::Chars => content.chars().count(),
+        }
+    }
+
+    /// Size of one line inside a window; a character window counts its newline too.
+    fn line_cost(self, line: &str) -> usize {
+        match self {
+            Self::Lines => 1,
+            Self::Chars => line.chars().count() + 1,
+        }
+    }
+}
+
+/// Line ranges `[start, end)` covering `lines`, each at most `max` in `unit` (a single longer
+/// line forms its own window). Consecutive windows share trailing lines worth at least
+/// `overlap`, shrunk when needed to keep windows advancing.
+fn line_windows(
+    lines: &[&str],
+    max: usize,
+    overlap: usize,
+    unit: SplitUnit,
+) -> Vec<(usize, usize)> {
+    let cost = |line: &str| unit.line_cost(line);
+
+    let mut windows = Vec::new();
+    let mut start = 0usize;
+    while start < lines.len() {
+        let mut end = start;
+        let mut used = 0usize;
+        while end < lines.len() {
+            let line_cost = cost(lines[end]);
+            if end > start && used + line_cost > max {
+                break;
+            }
+            used += line_cost;
+            end += 1;
+        }
+        windows.push((start, end));
+        if end >= lines.len() {
+            break;
+        }
+
+        let next_cost = cost(lines[end]);
+        let mut next = end;
+        let mut shared = 0usize;
+        while next > start + 1 && shared < overlap {
+            let line_cost = cost(lines[next - 1]);
+            if shared + line_cost + next_cost > max {
+                break;
+            }
+            next -= 1;
+            shared += line_cost;
+        }
+        start = next;
+    }
+    windows
+}
+
 fn select_tail_lines_by_tokens<'a>(lines: &[&'a str], tokens: usize) -> Vec<&'a str> {
     if tokens == 0 || lines.is_empty() {
         return Vec::new();
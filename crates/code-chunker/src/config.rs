@@ -1,4 +1,7 @@
+use crate::language::Language;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 /// Configuration for code chunking behavior
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +21,40 @@ pub struct ChunkerConfig {
     /// Minimum chunk size in tokens (avoid too small chunks)
     pub min_chunk_tokens: usize,
 
+    /// Consecutive chunks of one file and parent scope that are each shorter than this many
+    /// characters are merged into one chunk (0 = no merging)
+    #[serde(default)]
+    pub min_chunk_chars: usize,
+
+    /// Maximum chunk size in characters; longer chunks are split into line-aligned windows
+    /// (`None` = no character limit)
+    #[serde(default)]
+    pub max_chunk_chars: Option<usize>,
+
+    /// Characters of trailing lines repeated at the start of the next window when a chunk is
+    /// split by `max_chunk_chars`
+    #[serde(default)]
+    pub overlap_chars: usize,
+
+    /// Maximum chunk size in lines; longer chunks are split into windows (`None` = no line
+    /// limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+
+    /// Lines repeated at the start of the next window when a chunk is split by `max_lines`
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub overlap_lines: usize,
+
+    /// Consecutive chunks of one file and parent scope that are each shorter than this many
+    /// lines are merged into one chunk (0 = no merging)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub min_chunk_lines: usize,
+
+    /// Size limits for files of one language, keyed by [`Language::as_str`]; they replace the
+    /// limits above for that language
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub languages: BTreeMap<String, ChunkSizeOverride>,
+
     /// Include imports as context in chunks
     pub include_imports: bool,
 
@@ -42,6 +79,13 @@ impl Default for ChunkerConfig {
             target_chunk_tokens: 512,
             max_chunk_tokens: 1024,
             min_chunk_tokens: 10,
+            min_chunk_chars: 0,
+            max_chunk_chars: None,
+            overlap_chars: 0,
+            max_lines: None,
+            overlap_lines: 0,
+            min_chunk_lines: 0,
+            languages: BTreeMap::new(),
             include_imports: true,
             include_parent_context: true,
             include_documentation: true,
@@ -90,8 +134,53 @@ impl ChunkerConfig {
         }
     }
 
+    /// The configuration for files of `language`: this one with the size limits of its
+    /// `languages` entry applied. The returned configuration has no `languages` entries.
+    #[must_use]
+    pub fn for_language(&self, language: &str) -> Cow<'_, Self> {
+        let Some(limits) = self.languages.get(language) else {
+            return Cow::Borrowed(self);
+        };
+        let mut config = Self {
+            languages: BTreeMap::new(),
+            ..self.clone()
+        };
+        if let Some(max_chars) = limits.max_chunk_chars {
+            config.max_chunk_chars = (max_chars > 0).then_some(max_chars);
+        }
+        if let Some(overlap_chars) = limits.overlap_chars {
+            config.overlap_chars = overlap_chars;
+        }
+        if let Some(min_chars) = limits.min_chunk_chars {
+            config.min_chunk_chars = min_chars;
+        }
+        if let Some(max_lines) = limits.max_lines {
+            config.max_lines = (max_lines > 0).then_some(max_lines);
+        }
+        if let Some(overlap_lines) = limits.overlap_lines {
+            config.overlap_lines = overlap_lines;
+        }
+        if let Some(min_lines) = limits.min_chunk_lines {
+            config.min_chunk_lines = min_lines;
+        }
+        Cow::Owned(config)
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> Result<(), String> {
+        self.validate_limits()?;
+        for language in self.languages.keys() {
+            if Language::from_name(language).is_none() {
+                return Err(format!("languages.{language}: unknown language"));
+            }
+            self.for_language(language)
+                .validate_limits()
+                .map_err(|err| format!("languages.{language}: {err}"))?;
+        }
+        Ok(())
+    }
+
+    fn validate_limits(&self) -> Result<(), String> {
         if self.min_chunk_tokens > self.target_chunk_tokens {
             return Err(format!(
                 "min_chunk_tokens ({}) cannot exceed target_chunk_tokens ({})",
@@ -110,10 +199,61 @@ impl ChunkerConfig {
             return Err("max_chunk_tokens must be > 0".to_string());
         }
 
+        if let Some(max_chars) = self.max_chunk_chars {
+            if self.min_chunk_chars > max_chars {
+                return Err(format!(
+                    "min_chunk_chars ({}) cannot exceed max_chunk_chars ({max_chars})",
+                    self.min_chunk_chars
+                ));
+            }
+            if self.overlap_chars >= max_chars {
+                return Err(format!(
+                    "overlap_chars ({}) must be smaller than max_chunk_chars ({max_chars})",
+                    self.overlap_chars
+                ));
+            }
+        }
+
+        if let Some(max_lines) = self.max_lines {
+            if max_lines == 0 {
+                return Err("max_lines must be > 0".to_string());
+            }
+            if self.min_chunk_lines > max_lines {
+                return Err(format!(
+                    "min_chunk_lines ({}) cannot exceed max_lines ({max_lines})",
+                    self.min_chunk_lines
+                ));
+            }
+            if self.overlap_lines >= max_lines {
+                return Err(format!(
+                    "overlap_lines ({}) must be smaller than max_lines ({max_lines})",
+                    self.overlap_lines
+                ));
+            }
+        }
+
         Ok(())
     }
 }
 
+/// Per-language replacements for the size limits of a [`ChunkerConfig`]; unset fields keep the
+/// base value and a `0` maximum removes that limit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChunkSizeOverride {
+    pub max_chunk_chars: Option<usize>,
+    pub overlap_chars: Option<usize>,
+    pub min_chunk_chars: Option<usize>,
+    pub max_lines: Option<usize>,
+    pub overlap_lines: Option<usize>,
+    pub min_chunk_lines: Option<usize>,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
 /// Strategy for chunking code
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChunkingStrategy {
@@ -197,6 +337,58 @@ mod tests {
         config.target_chunk_tokens = 512;
         config.max_chunk_tokens = 1024;
         assert!(config.validate().is_ok());
+
+        // Invalid: overlap would not let split windows advance
+        config.max_chunk_chars = Some(200);
+        config.overlap_chars = 200;
+        assert!(config.validate().is_err());
+        config.overlap_chars = 50;
+        assert!(config.validate().is_ok());
+
+        // Invalid: merged chunks could never stay under the split limit
+        config.min_chunk_chars = 300;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn language_limits_replace_the_base_limits() {
+        let mut config = ChunkerConfig {
+            max_lines: Some(40),
+            overlap_lines: 5,
+            ..Default::default()
+        };
+        config.languages.insert(
+            "sql".to_string(),
+            ChunkSizeOverride {
+                max_lines: Some(0),
+                min_chunk_lines: Some(3),
+                ..Default::default()
+            },
+        );
+        assert!(config.validate().is_ok());
+
+        let sql = config.for_language("sql");
+        assert_eq!(sql.max_lines, None);
+        assert_eq!(sql.overlap_lines, 5);
+        assert_eq!(sql.min_chunk_lines, 3);
+        assert!(sql.languages.is_empty());
+        assert!(matches!(config.for_language("rust"), Cow::Borrowed(_)));
+
+        config.languages.get_mut("sql").unwrap().max_lines = Some(4);
+        config.languages.get_mut("sql").unwrap().overlap_lines = Some(4);
+        assert_eq!(
+            config.validate().unwrap_err(),
+            "languages.sql: overlap_lines (4) must be smaller than max_lines (4)"
+        );
+
+        config.languages.clear();
+        config
+            .languages
+            .insert("sqll".to_string(), ChunkSizeOverride::default());
+        assert_eq!(
+            config.validate().unwrap_err(),
+            "languages.sqll: unknown language"
+        );
     }
 
     #[test]
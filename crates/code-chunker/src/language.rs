@@ -78,6 +78,35 @@ impl Language {
             .unwrap_or(Self::Unknown)
     }
 
+    /// Parse a name produced by [`Self::as_str`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "rust" => Self::Rust,
+            "python" => Self::Python,
+            "javascript" => Self::JavaScript,
+            "typescript" => Self::TypeScript,
+            "go" => Self::Go,
+            "java" => Self::Java,
+            "c" => Self::C,
+            "cpp" => Self::Cpp,
+            "csharp" => Self::CSharp,
+            "ruby" => Self::Ruby,
+            "swift" => Self::Swift,
+            "kotlin" => Self::Kotlin,
+            "markdown" => Self::Markdown,
+            "yaml" => Self::Yaml,
+            "json" => Self::Json,
+            "config" => Self::Config,
+            "sql" => Self::Sql,
+            "shell" => Self::Shell,
+            "terraform" => Self::Terraform,
+            "html" => Self::Html,
+            "css" => Self::Css,
+            "unknown" => Self::Unknown,
+            _ => return None,
+        })
+    }
+
     /// Get language name as string
     pub const fn as_str(self) -> &'static str {
         match self {
@@ -62,6 +62,7 @@ mod strategy;
 mod types;
 
 pub use chunker::Chunker;
-pub use config::{ChunkerConfig, ChunkingStrategy, OverlapStrategy};
+pub use config::{ChunkSizeOverride, ChunkerConfig, ChunkingStrategy, OverlapStrategy};
 pub use error::{ChunkerError, Result};
+pub use language::Language;
 pub use types::{ChunkMetadata, ChunkType, CodeChunk};
@@ -74,3 +74,24 @@ fn ast_analyzer_preserves_scoped_and_reference_targets() {
     assert_eq!(len_scope, "inner::Wrapper<'a>");
     assert_eq!(as_ref_scope, "&'a [u8]");
 }
+
+#[test]
+fn ast_analyzer_tags_trait_impl_methods_with_trait_name() {
+    let chunks = rust_chunks(RUST_IMPLS);
+    let method_tags = |name: &str| {
+        chunks
+            .iter()
+            .find(|chunk| {
+                chunk.metadata.chunk_type == Some(ChunkType::Method)
+                    && chunk.metadata.symbol_name.as_deref() == Some(name)
+            })
+            .map(|chunk| chunk.metadata.tags.clone())
+            .unwrap_or_else(|| panic!("missing method chunk for {name}"))
+    };
+
+    assert!(method_tags("fmt").contains(&"implements:Display".to_string()));
+    assert!(method_tags("as_ref").contains(&"implements:AsRef".to_string()));
+    assert!(method_tags("len")
+        .iter()
+        .all(|tag| !tag.starts_with("implements:")));
+}
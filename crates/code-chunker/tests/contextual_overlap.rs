@@ -26,11 +26,15 @@ fn contextual_infers_imports_for_line_chunking_without_mutating_content() {
         target_chunk_tokens: 20,
         max_chunk_tokens: 10_000,
         min_chunk_tokens: 0,
+        min_chunk_chars: 0,
+        max_chunk_chars: None,
+        overlap_chars: 0,
         include_imports: true,
         include_parent_context: false,
         include_documentation: false,
         max_imports_per_chunk: 10,
         supported_languages: Vec::new(),
+        ..ChunkerConfig::default()
     };
 
     let chunks = Chunker::new(config)
@@ -0,0 +1,113 @@
+use context_code_chunker::{ChunkSizeOverride, Chunker, ChunkerConfig};
+
+const SCHEMA_SQL: &str = "CREATE TABLE users (
+    id BIGINT PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE TABLE orders (
+    id BIGINT PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    total_cents BIGINT NOT NULL,
+    placed_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX orders_user_id_idx ON orders (user_id);
+
+CREATE VIEW user_totals AS
+SELECT users.id, users.email, SUM(orders.total_cents) AS total_cents
+FROM users
+JOIN orders ON orders.user_id = users.id
+GROUP BY users.id, users.email;
+
+GRANT SELECT ON user_totals TO reporting;
+GRANT SELECT, INSERT ON orders TO checkout;
+REVOKE ALL ON orders FROM public;";
+
+const SETTINGS_YAML: &str = "server:
+  host: 0.0.0.0
+  port: 8080
+  read_timeout_ms: 5000
+  write_timeout_ms: 5000
+database:
+  url: postgres://localhost/app
+  pool_size: 16
+  statement_timeout_ms: 30000
+cache:
+  backend: redis
+  url: redis://localhost:6379
+  ttl_seconds: 600
+logging:
+  level: info
+  format: json
+  include_spans: true
+features:
+  checkout_v2: true
+  search_suggestions: false
+  dark_mode: true
+limits:
+  max_upload_mb: 25
+  max_requests_per_minute: 600";
+
+fn boundaries(chunker: &Chunker, content: &str, path: &str) -> Vec<(usize, usize)> {
+    chunker
+        .chunk_str(content, Some(path))
+        .expect("chunking fixture")
+        .iter()
+        .map(|chunk| (chunk.start_line, chunk.end_line))
+        .collect()
+}
+
+#[test]
+fn language_override_changes_chunk_boundaries_for_that_language_only() {
+    let base = Chunker::new(ChunkerConfig::for_embeddings());
+    assert_eq!(boundaries(&base, SCHEMA_SQL, "schema.sql"), vec![(1, 24)]);
+    assert_eq!(
+        boundaries(&base, SETTINGS_YAML, "settings.yaml"),
+        vec![(1, 24)]
+    );
+
+    let mut config = ChunkerConfig::for_embeddings();
+    config.languages.insert(
+        "sql".to_string(),
+        ChunkSizeOverride {
+            max_lines: Some(10),
+            overlap_lines: Some(2),
+            ..Default::default()
+        },
+    );
+    let chunker = Chunker::new(config);
+    assert_eq!(
+        boundaries(&chunker, SCHEMA_SQL, "schema.sql"),
+        vec![(1, 10), (9, 18), (17, 24)]
+    );
+    assert_eq!(
+        boundaries(&chunker, SETTINGS_YAML, "settings.yaml"),
+        vec![(1, 24)]
+    );
+}
+
+#[test]
+fn override_can_lift_the_base_line_limit() {
+    let mut config = ChunkerConfig {
+        max_lines: Some(12),
+        ..ChunkerConfig::for_embeddings()
+    };
+    config.languages.insert(
+        "sql".to_string(),
+        ChunkSizeOverride {
+            max_lines: Some(0),
+            ..Default::default()
+        },
+    );
+    let chunker = Chunker::new(config);
+    assert_eq!(
+        boundaries(&chunker, SCHEMA_SQL, "schema.sql"),
+        vec![(1, 24)]
+    );
+    assert_eq!(
+        boundaries(&chunker, SETTINGS_YAML, "settings.yaml"),
+        vec![(1, 12), (13, 24)]
+    );
+}
@@ -0,0 +1,1086 @@
+//! Symbol analyses over a [`CodeGraph`]: impact, trace, explain and overview.
+//!
+//! Both the MCP tools and the CLI command actions build their responses from these reports, so
+//! the two surfaces return the same JSON shapes.
+
+use crate::builder::GraphLanguage;
+use crate::error::{GraphError, Result};
+use crate::graph::WeightedPath;
+use crate::module_graph::ModuleGraphConfig;
+use crate::symbol_docs::doc_examples;
+use crate::types::{CodeGraph, RelationshipType};
+use context_code_chunker::CodeChunk;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Cap on direct usages in an impact report
+pub const MAX_DIRECT_USAGES: usize = 200;
+/// Cap on transitive usages in an impact report
+pub const MAX_TRANSITIVE_USAGES: usize = 200;
+/// Default documentation budget for `explain`
+pub const DEFAULT_DOC_MAX_CHARS: usize = 4000;
+/// Upper bound on distinct paths a trace returns
+pub const MAX_TRACE_PATHS: usize = 5;
+
+const MAX_ENTRY_POINTS: usize = 10;
+const MAX_KEY_TYPES: usize = 10;
+const HOTSPOT_LIMIT: usize = 20;
+const MAX_MODULE_NODES: usize = 30;
+const MAX_MODULE_EDGES: usize = 40;
+const MAX_MODULE_CYCLES: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ImpactReport {
+    /// Symbol that was analyzed
+    pub symbol: String,
+    /// Definition location
+    pub definition: Option<SymbolLocation>,
+    /// Total usage count
+    pub total_usages: usize,
+    /// Number of files affected
+    pub files_affected: usize,
+    /// Direct usages
+    pub direct: Vec<UsageInfo>,
+    /// Transitive usages (if depth > 1)
+    pub transitive: Vec<UsageInfo>,
+    /// Related tests
+    pub tests: Vec<String>,
+    /// Is part of public API
+    pub public_api: bool,
+    /// Mermaid diagram
+    pub mermaid: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SymbolLocation {
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct UsageInfo {
+    pub file: String,
+    pub line: usize,
+    pub symbol: String,
+    pub relationship: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TraceReport {
+    /// Whether path was found
+    pub found: bool,
+    /// Call chain path
+    pub path: Vec<TraceStep>,
+    /// Path depth
+    pub depth: usize,
+    /// Weighted cost of `path` (calls are cheaper than uses/imports)
+    pub cost: u32,
+    /// Next-cheapest distinct paths (when more than one path was requested)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<TracePath>,
+    /// Mermaid sequence diagram
+    pub mermaid: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TracePath {
+    pub path: Vec<TraceStep>,
+    pub depth: usize,
+    pub cost: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TraceStep {
+    /// Symbol name
+    pub symbol: String,
+    /// File path
+    pub file: String,
+    /// Line number
+    pub line: usize,
+    /// Relationship of the hop that reaches this step (None for the start)
+    pub relationship: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ExplainReport {
+    /// Symbol name
+    pub symbol: String,
+    /// Symbol kind (function, struct, etc.)
+    pub kind: String,
+    /// File path
+    pub file: String,
+    /// Line number
+    pub line: usize,
+    /// Full signature, possibly spanning several lines (if available)
+    pub signature: Option<String>,
+    /// Documentation (if available)
+    pub documentation: Option<String>,
+    /// Code blocks from the documentation (doc-test examples)
+    pub examples: Vec<String>,
+    /// Dependencies (what this symbol uses/calls)
+    pub dependencies: Vec<String>,
+    /// Dependents (what uses/calls this symbol)
+    pub dependents: Vec<String>,
+    /// Related tests
+    pub tests: Vec<String>,
+    /// Code content
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct OverviewReport {
+    /// Project info
+    pub project: ProjectInfo,
+    /// Architecture layers
+    pub layers: Vec<LayerInfo>,
+    /// Entry points
+    pub entry_points: Vec<String>,
+    /// Key types (most connected)
+    pub key_types: Vec<KeyTypeInfo>,
+    /// Graph statistics
+    pub graph_stats: GraphStats,
+    /// Module-level dependency graph (symbol edges collapsed by directory)
+    pub module_graph: ModuleGraphInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ProjectInfo {
+    pub name: String,
+    pub files: usize,
+    pub chunks: usize,
+    pub lines: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LayerInfo {
+    pub name: String,
+    pub files: usize,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct KeyTypeInfo {
+    pub name: String,
+    pub kind: String,
+    pub file: String,
+    pub coupling: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GraphStats {
+    pub nodes: usize,
+    pub edges: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ModuleGraphInfo {
+    pub nodes: Vec<ModuleNodeInfo>,
+    /// Module dependencies, most symbol edges first
+    pub edges: Vec<ModuleEdgeInfo>,
+    /// Module dependency cycles, each hop backed by one symbol-level edge
+    pub cycles: Vec<ModuleCycleInfo>,
+    /// True when nodes/edges/cycles were capped to keep the response small
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ModuleNodeInfo {
+    pub module: String,
+    pub symbols: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ModuleEdgeInfo {
+    pub from: String,
+    pub to: String,
+    /// Number of symbol-level edges between the two modules
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ModuleCycleInfo {
+    /// Modules in cycle order; the last module depends on the first
+    pub modules: Vec<String>,
+    pub edges: Vec<ModuleCycleEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ModuleCycleEdge {
+    pub from_symbol: String,
+    pub to_symbol: String,
+    pub relationship: String,
+    pub file: String,
+    pub line: usize,
+}
+
+/// Chunk languages that describe docs, data or configuration rather than code; they never decide
+/// the graph language.
+const NON_CODE_LANGUAGES: &[&str] = &[
+    "markdown",
+    "yaml",
+    "json",
+    "config",
+    "sql",
+    "shell",
+    "terraform",
+    "html",
+    "css",
+    "unknown",
+];
+
+/// The dominant code language of a chunk set, as picked by [`detect_graph_language`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DetectedLanguage {
+    /// The dominant language has a graph builder (Rust when the chunks hold no code).
+    Supported(GraphLanguage),
+    /// The dominant language, by chunker name, has no graph builder.
+    Unsupported(String),
+}
+
+/// Most common code language among `chunks`, counted by `chunk.metadata.language` (the file
+/// extension when unset); ties go to the language with a graph builder, then by name.
+#[must_use]
+pub fn detect_graph_language(chunks: &[CodeChunk]) -> DetectedLanguage {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for chunk in chunks {
+        let language = chunk.metadata.language.clone().unwrap_or_else(|| {
+            if has_extension(&chunk.file_path, "jsx") {
+                GraphLanguage::JavaScript.as_str().to_string()
+            } else {
+                context_code_chunker::Language::from_path(&chunk.file_path)
+                    .as_str()
+                    .to_string()
+            }
+        });
+        if !NON_CODE_LANGUAGES.contains(&language.as_str()) {
+            *counts.entry(language).or_insert(0) += 1;
+        }
+    }
+
+    let dominant = counts
+        .into_iter()
+        .max_by(|(a_name, a_count), (b_name, b_count)| {
+            a_count
+                .cmp(b_count)
+                .then_with(|| {
+                    GraphLanguage::from_name(a_name)
+                        .is_some()
+                        .cmp(&GraphLanguage::from_name(b_name).is_some())
+                })
+                .then_with(|| b_name.cmp(a_name))
+        });
+    match dominant {
+        None => DetectedLanguage::Supported(GraphLanguage::Rust),
+        Some((name, _)) => GraphLanguage::from_name(&name).map_or(
+            DetectedLanguage::Unsupported(name),
+            DetectedLanguage::Supported,
+        ),
+    }
+}
+
+/// Error text for a dominant `language` without a graph builder, naming the supported set.
+#[must_use]
+pub fn unsupported_language_message(language: &str) -> String {
+    let supported: Vec<&str> = GraphLanguage::ALL
+        .iter()
+        .map(|language| language.as_str())
+        .collect();
+    format!(
+        "Dominant language '{language}' has no graph support; supported graph languages: {}",
+        supported.join(", ")
+    )
+}
+
+/// Hint for results answered without a code graph because the dominant `language` has no
+/// graph builder.
+#[must_use]
+pub fn unsupported_language_hint(language: &str) -> String {
+    format!(
+        "{}; answered without the code graph. Pass `language` to build the graph for part of the project.",
+        unsupported_language_message(language)
+    )
+}
+
+/// Primary graph language of `chunks`; Rust when [`detect_graph_language`] finds no supported
+/// one.
+#[must_use]
+pub fn detect_language(chunks: &[CodeChunk]) -> GraphLanguage {
+    match detect_graph_language(chunks) {
+        DetectedLanguage::Supported(language) => language,
+        DetectedLanguage::Unsupported(_) => GraphLanguage::Rust,
+    }
+}
+
+/// Usages of `symbol` up to `depth` hops (1-3). Without a graph, or when the symbol has no
+/// node, the report falls back to word-boundary text hits in `chunks`.
+#[must_use]
+pub fn impact(
+    graph: Option<&CodeGraph>,
+    chunks: &[CodeChunk],
+    symbol: &str,
+    depth: usize,
+) -> ImpactReport {
+    let Some((graph, node)) = graph.and_then(|g| g.find_node(symbol).map(|node| (g, node))) else {
+        let direct = find_text_usages(chunks, symbol, None, MAX_DIRECT_USAGES);
+        return ImpactReport {
+            symbol: symbol.to_string(),
+            definition: None,
+            total_usages: direct.len(),
+            files_affected: count_files_affected(&direct, &[]),
+            mermaid: impact_mermaid(symbol, &direct, &[]),
+            direct,
+            transitive: Vec::new(),
+            tests: Vec::new(),
+            public_api: false,
+        };
+    };
+
+    let definition = graph.get_node(node).map(|nd| SymbolLocation {
+        file: nd.symbol.file_path.clone(),
+        line: nd.symbol.start_line,
+    });
+
+    let (mut direct, mut seen_direct) = collect_direct_usages(graph, node);
+    let transitive = if depth > 1 {
+        collect_transitive_usages(graph, node, depth)
+    } else {
+        Vec::new()
+    };
+
+    let exclude_chunk_id = graph.get_node(node).map(|nd| nd.chunk_id.as_str());
+    let remaining = MAX_DIRECT_USAGES.saturating_sub(direct.len());
+    for usage in find_text_usages(chunks, symbol, exclude_chunk_id, remaining) {
+        if direct.len() >= MAX_DIRECT_USAGES {
+            break;
+        }
+        if seen_direct.insert((usage.file.clone(), usage.line)) {
+            direct.push(usage);
+        }
+    }
+
+    let mut tests: Vec<String> = graph
+        .find_related_tests(node)
+        .iter()
+        .filter_map(|n| {
+            graph
+                .get_node(*n)
+                .map(|nd| format!("{}:{}", nd.symbol.file_path, nd.symbol.start_line))
+        })
+        .collect();
+    tests.sort();
+    tests.dedup();
+
+    ImpactReport {
+        symbol: symbol.to_string(),
+        definition,
+        total_usages: direct.len() + transitive.len(),
+        files_affected: count_files_affected(&direct, &transitive),
+        mermaid: impact_mermaid(symbol, &direct, &transitive),
+        direct,
+        transitive,
+        tests,
+        public_api: graph.is_public_api(node),
+    }
+}
+
+/// Number of distinct files across `direct` and `transitive`.
+#[must_use]
+pub fn count_files_affected(direct: &[UsageInfo], transitive: &[UsageInfo]) -> usize {
+    direct
+        .iter()
+        .chain(transitive)
+        .map(|u| u.file.as_str())
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Cheapest paths from `from` to `to` (at most `max_paths`, capped at [`MAX_TRACE_PATHS`]),
+/// optionally forced through `via`.
+pub fn trace(
+    graph: &CodeGraph,
+    from: &str,
+    to: &str,
+    via: Option<&str>,
+    max_paths: usize,
+) -> Result<TraceReport> {
+    let from_node = find_symbol(graph, from)?;
+    let to_node = find_symbol(graph, to)?;
+    let via_node = via.map(|via| find_symbol(graph, via)).transpose()?;
+
+    let mut paths: Vec<TracePath> = graph
+        .find_weighted_paths(
+            from_node,
+            to_node,
+            max_paths.clamp(1, MAX_TRACE_PATHS),
+            via_node,
+        )
+        .iter()
+        .map(|path| to_trace_path(graph, path))
+        .collect();
+
+    let found = !paths.is_empty();
+    let (path, depth, cost) = if found {
+        let best = paths.remove(0);
+        (best.path, best.depth, best.cost)
+    } else {
+        (Vec::new(), 0, 0)
+    };
+
+    Ok(TraceReport {
+        found,
+        mermaid: trace_mermaid(&path),
+        path,
+        depth,
+        cost,
+        alternates: paths,
+    })
+}
+
+/// Definition, documentation (cut to `doc_max_chars`), relations and tests of `symbol`.
+pub fn explain(graph: &CodeGraph, symbol: &str, doc_max_chars: usize) -> Result<ExplainReport> {
+    let node = find_symbol(graph, symbol)?;
+
+    let (deps, dependents) = graph.get_symbol_relations(node);
+    let mut tests: Vec<String> = graph
+        .find_related_tests(node)
+        .iter()
+        .filter_map(|n| graph.get_node(*n).map(|nd| nd.symbol.name.clone()))
+        .collect();
+    tests.sort();
+    tests.dedup();
+
+    let mut report = ExplainReport {
+        symbol: symbol.to_string(),
+        kind: String::new(),
+        file: String::new(),
+        line: 0,
+        signature: None,
+        documentation: None,
+        examples: Vec::new(),
+        dependencies: format_symbol_relations(graph, &deps),
+        dependents: format_symbol_relations(graph, &dependents),
+        tests,
+        content: String::new(),
+    };
+
+    if let Some(nd) = graph.get_node(node) {
+        let doc = nd.documentation.clone().or_else(|| {
+            nd.chunk
+                .as_ref()
+                .and_then(|c| c.metadata.documentation.clone())
+                .filter(|doc| !doc.trim().is_empty())
+        });
+        let symbol_type = &nd.symbol.symbol_type;
+        report.kind = format!("{symbol_type:?}");
+        report.file = nd.symbol.file_path.clone();
+        report.line = nd.symbol.start_line;
+        report.signature = nd.signature.clone();
+        report.content = nd
+            .chunk
+            .as_ref()
+            .map_or_else(String::new, |c| c.content.clone());
+        if let Some(doc) = doc {
+            report.examples = budgeted_examples(&doc, doc_max_chars);
+            report.documentation = Some(truncate_documentation(&doc, doc_max_chars));
+        }
+    }
+
+    Ok(report)
+}
+
+/// Architecture summary of a project named `project_name`: size, top-level layers, entry
+/// points, most-coupled types and the module dependency graph at `module_depth` (default 2).
+#[must_use]
+pub fn overview(
+    graph: &CodeGraph,
+    project_name: String,
+    chunks: &[CodeChunk],
+    module_depth: Option<usize>,
+) -> OverviewReport {
+    let files: HashSet<&str> = chunks.iter().map(|c| c.file_path.as_str()).collect();
+    let project = ProjectInfo {
+        name: project_name,
+        files: files.len(),
+        chunks: chunks.len(),
+        lines: chunks.iter().map(|c| c.content.lines().count()).sum(),
+    };
+    let (nodes, edges) = graph.stats();
+
+    OverviewReport {
+        project,
+        layers: compute_layers(chunks),
+        entry_points: compute_entry_points(graph),
+        key_types: compute_key_types(graph),
+        graph_stats: GraphStats { nodes, edges },
+        module_graph: compute_module_graph(graph, module_depth),
+    }
+}
+
+/// Mermaid flowchart of the first direct and transitive usages pointing at `symbol`.
+#[must_use]
+pub fn impact_mermaid(symbol: &str, direct: &[UsageInfo], transitive: &[UsageInfo]) -> String {
+    let mut lines = vec!["graph LR".to_string()];
+
+    for usage in direct.iter().take(10) {
+        lines.push(format!(
+            "    {}-->|{}|{}",
+            mermaid_safe(&usage.symbol),
+            usage.relationship,
+            mermaid_safe(symbol)
+        ));
+    }
+
+    for usage in transitive.iter().take(5) {
+        lines.push(format!(
+            "    {}-.->|transitive|{}",
+            mermaid_safe(&usage.symbol),
+            mermaid_safe(symbol)
+        ));
+    }
+
+    lines.join("\n")
+}
+
+fn trace_mermaid(steps: &[TraceStep]) -> String {
+    if steps.is_empty() {
+        return "sequenceDiagram\n    Note over A: No path found".to_string();
+    }
+
+    let mut lines = vec!["sequenceDiagram".to_string()];
+    for window in steps.windows(2) {
+        let rel = window[1].relationship.as_deref().unwrap_or("calls");
+        lines.push(format!(
+            "    {}->>{}+: {}",
+            mermaid_safe(&window[0].symbol),
+            mermaid_safe(&window[1].symbol),
+            rel
+        ));
+    }
+    lines.join("\n")
+}
+
+fn mermaid_safe(s: &str) -> String {
+    s.replace("::", "_").replace(['<', '>', ' '], "_")
+}
+
+fn find_symbol(graph: &CodeGraph, symbol: &str) -> Result<NodeIndex> {
+    graph
+        .find_node(symbol)
+        .ok_or_else(|| GraphError::SymbolNotFound(symbol.to_string()))
+}
+
+fn has_extension(path: &str, ext: &str) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+}
+
+fn should_skip_graph_symbol(symbol_name: &str, file_path: &str) -> bool {
+    symbol_name == "unknown" || has_extension(file_path, "md")
+}
+
+fn usage_for(
+    graph: &CodeGraph,
+    node: NodeIndex,
+    relationship: String,
+    seen: &mut HashSet<(String, usize)>,
+) -> Option<UsageInfo> {
+    let nd = graph.get_node(node)?;
+    if should_skip_graph_symbol(&nd.symbol.name, &nd.symbol.file_path) {
+        return None;
+    }
+    if !seen.insert((nd.symbol.file_path.clone(), nd.symbol.start_line)) {
+        return None;
+    }
+    Some(UsageInfo {
+        file: nd.symbol.file_path.clone(),
+        line: nd.symbol.start_line,
+        symbol: nd.symbol.name.clone(),
+        relationship,
+    })
+}
+
+fn collect_direct_usages(
+    graph: &CodeGraph,
+    node: NodeIndex,
+) -> (Vec<UsageInfo>, HashSet<(String, usize)>) {
+    let mut seen: HashSet<(String, usize)> = HashSet::new();
+    let mut direct: Vec<UsageInfo> = graph
+        .get_all_usages(node)
+        .iter()
+        .filter_map(|(n, rel)| usage_for(graph, *n, format!("{rel:?}"), &mut seen))
+        .collect();
+    direct.truncate(MAX_DIRECT_USAGES);
+    (direct, seen)
+}
+
+fn collect_transitive_usages(graph: &CodeGraph, node: NodeIndex, depth: usize) -> Vec<UsageInfo> {
+    let mut seen: HashSet<(String, usize)> = HashSet::new();
+    let mut transitive: Vec<UsageInfo> = graph
+        .get_transitive_usages(node, depth)
+        .iter()
+        .filter(|(_, d, _)| *d > 1)
+        .filter_map(|(n, _, path)| {
+            let relationship = path
+                .iter()
+                .map(|r| format!("{r:?}"))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            usage_for(graph, *n, relationship, &mut seen)
+        })
+        .collect();
+    transitive.truncate(MAX_TRANSITIVE_USAGES);
+    transitive
+}
+
+/// Word-boundary text hits of `symbol` in `chunks` (markdown skipped), one per file line.
+fn find_text_usages(
+    chunks: &[CodeChunk],
+    symbol: &str,
+    exclude_chunk_id: Option<&str>,
+    max_results: usize,
+) -> Vec<UsageInfo> {
+    if symbol.is_empty() || max_results == 0 {
+        return Vec::new();
+    }
+
+    let mut out = Vec::new();
+    let mut seen: HashSet<(String, usize)> = HashSet::new();
+    for chunk in chunks {
+        if out.len() >= max_results {
+            break;
+        }
+        if has_extension(&chunk.file_path, "md") {
+            continue;
+        }
+        if let Some(exclude) = exclude_chunk_id {
+            let chunk_id = format!(
+                "{}:{}:{}",
+                chunk.file_path, chunk.start_line, chunk.end_line
+            );
+            if chunk_id == exclude {
+                continue;
+            }
+        }
+
+        let Some(hit_byte) = find_word_boundary(&chunk.content, symbol) else {
+            continue;
+        };
+        let line_offset = chunk.content[..hit_byte]
+            .bytes()
+            .filter(|b| *b == b'\n')
+            .count();
+        let line = chunk.start_line + line_offset;
+        if !seen.insert((chunk.file_path.clone(), line)) {
+            continue;
+        }
+
+        out.push(UsageInfo {
+            file: chunk.file_path.clone(),
+            line,
+            symbol: chunk
+                .metadata
+                .symbol_name
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+            relationship: "TextMatch".to_string(),
+        });
+    }
+    out
+}
+
+/// Byte offset of the first occurrence of `needle` in `haystack`. An identifier needle only
+/// matches where it is not part of a longer identifier.
+#[must_use]
+pub fn find_word_boundary(haystack: &str, needle: &str) -> Option<usize> {
+    const fn is_ident_byte(b: u8) -> bool {
+        matches!(b, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_')
+    }
+
+    if needle.is_empty() {
+        return None;
+    }
+    if !needle.bytes().all(is_ident_byte) {
+        return haystack.find(needle);
+    }
+
+    let bytes = haystack.as_bytes();
+    haystack
+        .match_indices(needle)
+        .map(|(idx, _)| idx)
+        .find(|&idx| {
+            let right_idx = idx + needle.len();
+            (idx == 0 || !is_ident_byte(bytes[idx - 1]))
+                && (right_idx >= bytes.len() || !is_ident_byte(bytes[right_idx]))
+        })
+}
+
+fn to_trace_path(graph: &CodeGraph, path: &WeightedPath) -> TracePath {
+    let steps: Vec<TraceStep> = path
+        .steps
+        .iter()
+        .map(|(n, rel)| {
+            let (symbol, file, line) = graph.get_node(*n).map_or_else(
+                || (String::new(), String::new(), 0),
+                |nd| {
+                    (
+                        nd.symbol.name.clone(),
+                        nd.symbol.file_path.clone(),
+                        nd.symbol.start_line,
+                    )
+                },
+            );
+            TraceStep {
+                symbol,
+                file,
+                line,
+                relationship: rel.map(|r| format!("{r:?}")),
+            }
+        })
+        .collect();
+    TracePath {
+        depth: steps.len().saturating_sub(1),
+        path: steps,
+        cost: path.cost,
+    }
+}
+
+fn format_symbol_relations(
+    graph: &CodeGraph,
+    rels: &[(NodeIndex, RelationshipType)],
+) -> Vec<String> {
+    let mut out: Vec<String> = rels
+        .iter()
+        .filter_map(|(n, rel)| {
+            let nd = graph.get_node(*n)?;
+            if should_skip_graph_symbol(&nd.symbol.name, &nd.symbol.file_path) {
+                return None;
+            }
+            // Impls of one trait method share its name; show `Type::method` to tell them apart
+            let name = match rel {
+                RelationshipType::Implements => nd
+                    .symbol
+                    .qualified_name
+                    .as_deref()
+                    .unwrap_or(&nd.symbol.name),
+                _ => &nd.symbol.name,
+            };
+            Some(format!("{name} ({rel:?})"))
+        })
+        .collect();
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Cut `doc` to `max_chars`, preferring a line break, and say how much was dropped.
+fn truncate_documentation(doc: &str, max_chars: usize) -> String {
+    let total = doc.chars().count();
+    if total <= max_chars {
+        return doc.to_string();
+    }
+    let cut = doc
+        .char_indices()
+        .nth(max_chars)
+        .map_or(doc.len(), |(idx, _)| idx);
+    let head = &doc[..cut];
+    let head = head
+        .rfind('\n')
+        .filter(|&idx| idx > 0)
+        .map_or(head, |idx| &head[..idx])
+        .trim_end();
+    let dropped = total - head.chars().count();
+    format!("{head}\n[… documentation truncated: {dropped} more chars]")
+}
+
+/// Whole doc-test blocks, in order, while they fit within `max_chars`.
+fn budgeted_examples(doc: &str, max_chars: usize) -> Vec<String> {
+    let mut used = 0usize;
+    doc_examples(doc)
+        .into_iter()
+        .take_while(|example| {
+            used += example.chars().count();
+            used <= max_chars
+        })
+        .collect()
+}
+
+fn guess_layer_role(name: &str) -> String {
+    match name.to_lowercase().as_str() {
+        "cli" | "cmd" | "bin" => "Command-line interface".to_string(),
+        "api" | "server" | "web" => "API/Server layer".to_string(),
+        "core" | "lib" | "src" => "Core library".to_string(),
+        "test" | "tests" => "Test suite".to_string(),
+        "crates" => "Workspace crates".to_string(),
+        "docs" | "doc" => "Documentation".to_string(),
+        _ => "Module".to_string(),
+    }
+}
+
+fn compute_layers(chunks: &[CodeChunk]) -> Vec<LayerInfo> {
+    let mut layer_files: HashMap<String, HashSet<&str>> = HashMap::new();
+    for chunk in chunks {
+        let parts: Vec<&str> = chunk.file_path.split('/').collect();
+        if parts.len() > 1 {
+            let layer = parts.first().copied().unwrap_or("root").to_string();
+            layer_files
+                .entry(layer)
+                .or_default()
+                .insert(chunk.file_path.as_str());
+        }
+    }
+
+    let mut layers: Vec<LayerInfo> = layer_files
+        .into_iter()
+        .map(|(name, files)| LayerInfo {
+            role: guess_layer_role(&name),
+            name,
+            files: files.len(),
+        })
+        .collect();
+    layers.sort_by(|a, b| b.files.cmp(&a.files));
+    layers
+}
+
+fn is_entry_point_candidate(symbol_name: &str, file_path: &str) -> bool {
+    if symbol_name == "unknown" || symbol_name.starts_with("test_") {
+        return false;
+    }
+    !file_path.contains("/tests/") && !has_extension(file_path, "md")
+}
+
+fn compute_entry_points(graph: &CodeGraph) -> Vec<String> {
+    let mut entry_points: Vec<String> = graph
+        .find_entry_points()
+        .iter()
+        .filter_map(|n| {
+            graph.get_node(*n).and_then(|nd| {
+                is_entry_point_candidate(&nd.symbol.name, &nd.symbol.file_path)
+                    .then(|| nd.symbol.name.clone())
+            })
+        })
+        .collect();
+    entry_points.sort();
+    entry_points.dedup();
+    entry_points.truncate(MAX_ENTRY_POINTS);
+    entry_points
+}
+
+fn compute_key_types(graph: &CodeGraph) -> Vec<KeyTypeInfo> {
+    let mut seen_names: HashSet<String> = HashSet::new();
+    graph
+        .find_hotspots(HOTSPOT_LIMIT)
+        .iter()
+        .filter_map(|(n, coupling)| {
+            let nd = graph.get_node(*n)?;
+            let name = &nd.symbol.name;
+            if name == "unknown"
+                || name == "tests"
+                || name.starts_with("test_")
+                || nd.symbol.file_path.contains("/tests/")
+                || !seen_names.insert(name.clone())
+            {
+                return None;
+            }
+            let symbol_type = &nd.symbol.symbol_type;
+            Some(KeyTypeInfo {
+                name: name.clone(),
+                kind: format!("{symbol_type:?}"),
+                file: nd.symbol.file_path.clone(),
+                coupling: *coupling,
+            })
+        })
+        .take(MAX_KEY_TYPES)
+        .collect()
+}
+
+fn compute_module_graph(graph: &CodeGraph, depth: Option<usize>) -> ModuleGraphInfo {
+    let mut config = ModuleGraphConfig::default();
+    if let Some(depth) = depth {
+        config.depth = depth.max(1);
+    }
+    let mut module_graph = graph.module_graph(config);
+    let truncated = module_graph.nodes.len() > MAX_MODULE_NODES
+        || module_graph.edges.len() > MAX_MODULE_EDGES
+        || module_graph.cycles.len() > MAX_MODULE_CYCLES;
+
+    module_graph.nodes.sort_by(|a, b| {
+        b.symbols
+            .cmp(&a.symbols)
+            .then_with(|| a.module.cmp(&b.module))
+    });
+    module_graph.nodes.truncate(MAX_MODULE_NODES);
+    module_graph.edges.truncate(MAX_MODULE_EDGES);
+    module_graph.cycles.truncate(MAX_MODULE_CYCLES);
+
+    ModuleGraphInfo {
+        nodes: module_graph
+            .nodes
+            .into_iter()
+            .map(|n| ModuleNodeInfo {
+                module: n.module,
+                symbols: n.symbols,
+            })
+            .collect(),
+        edges: module_graph
+            .edges
+            .into_iter()
+            .map(|e| ModuleEdgeInfo {
+                from: e.from,
+                to: e.to,
+                count: e.count,
+            })
+            .collect(),
+        cycles: module_graph
+            .cycles
+            .into_iter()
+            .map(|c| ModuleCycleInfo {
+                modules: c.modules,
+                edges: c
+                    .edges
+                    .into_iter()
+                    .map(|e| ModuleCycleEdge {
+                        from_symbol: e.from_symbol,
+                        to_symbol: e.to_symbol,
+                        relationship: format!("{:?}", e.relationship),
+                        file: e.file,
+                        line: e.line,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        truncated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_code_chunker::{ChunkMetadata, ChunkType};
+
+    #[test]
+    fn word_boundary_match_hits_only_whole_identifier() {
+        assert!(find_word_boundary("fn new() {}", "new").is_some());
+        assert!(find_word_boundary("renew", "new").is_none());
+        assert!(find_word_boundary("news", "new").is_none());
+        assert!(find_word_boundary("new_", "new").is_none());
+        assert!(find_word_boundary(" new ", "new").is_some());
+    }
+
+    #[test]
+    fn text_usages_compute_line_and_respect_exclusion() {
+        let chunk = CodeChunk::new(
+            "a.rs".to_string(),
+            10,
+            20,
+            "fn caller() {\n  touch_daemon_best_effort();\n}\n".to_string(),
+            ChunkMetadata::default()
+                .symbol_name("caller")
+                .chunk_type(ChunkType::Function),
+        );
+
+        let usages = find_text_usages(
+            std::slice::from_ref(&chunk),
+            "touch_daemon_best_effort",
+            None,
+            10,
+        );
+        assert_eq!(usages.len(), 1);
+        assert_eq!(usages[0].file, "a.rs");
+        assert_eq!(usages[0].line, 11);
+        assert_eq!(usages[0].symbol, "caller");
+        assert_eq!(usages[0].relationship, "TextMatch");
+
+        let exclude = format!(
+            "{}:{}:{}",
+            chunk.file_path, chunk.start_line, chunk.end_line
+        );
+        let excluded = find_text_usages(&[chunk], "touch_daemon_best_effort", Some(&exclude), 10);
+        assert!(excluded.is_empty());
+    }
+
+    #[test]
+    fn impact_without_graph_falls_back_to_text_hits() {
+        let chunks = vec![
+            CodeChunk::new(
+                "src/a.rs".to_string(),
+                1,
+                3,
+                "fn caller() {\n    helper();\n}\n".to_string(),
+                ChunkMetadata::default().symbol_name("caller"),
+            ),
+            CodeChunk::new(
+                "README.md".to_string(),
+                1,
+                1,
+                "Call helper() first.".to_string(),
+                ChunkMetadata::default(),
+            ),
+        ];
+
+        let report = impact(None, &chunks, "helper", 2);
+        assert!(report.definition.is_none());
+        assert_eq!(report.total_usages, 1);
+        assert_eq!(report.files_affected, 1);
+        assert_eq!(report.direct[0].line, 2);
+        assert!(report.mermaid.contains("caller-->|TextMatch|helper"));
+    }
+
+    #[test]
+    fn unknown_symbols_are_reported_by_name() {
+        let graph = CodeGraph::new();
+        let err = explain(&graph, "missing", DEFAULT_DOC_MAX_CHARS).unwrap_err();
+        assert!(matches!(err, GraphError::SymbolNotFound(ref name) if name == "missing"));
+        assert_eq!(err.to_string(), "Symbol 'missing' not found");
+        assert!(trace(&graph, "a", "b", None, 1).is_err());
+    }
+
+    #[test]
+    fn graph_language_follows_the_dominant_code_language() {
+        let chunk = |path: &str, language: Option<&str>| {
+            let metadata = ChunkMetadata {
+                language: language.map(ToString::to_string),
+                ..ChunkMetadata::default()
+            };
+            CodeChunk::new(path.to_string(), 1, 2, "x".to_string(), metadata)
+        };
+        let docs: Vec<CodeChunk> = (0..5)
+            .map(|idx| chunk(&format!("docs/{idx}.md"), Some("markdown")))
+            .collect();
+
+        let mut chunks = docs.clone();
+        chunks.push(chunk("web/app.tsx", Some("typescript")));
+        chunks.push(chunk("web/main.ts", None));
+        chunks.push(chunk("build.rs", Some("rust")));
+        assert_eq!(
+            detect_graph_language(&chunks),
+            DetectedLanguage::Supported(GraphLanguage::TypeScript)
+        );
+
+        let mut chunks = docs.clone();
+        chunks.push(chunk("cmd/main.go", None));
+        chunks.push(chunk("cmd/serve.go", Some("go")));
+        chunks.push(chunk("tools/gen.py", Some("python")));
+        assert_eq!(
+            detect_graph_language(&chunks),
+            DetectedLanguage::Unsupported("go".to_string())
+        );
+        assert_eq!(
+            unsupported_language_message("go"),
+            "Dominant language 'go' has no graph support; supported graph languages: rust, python, javascript, typescript"
+        );
+        assert_eq!(detect_language(&chunks), GraphLanguage::Rust);
+
+        // Ties prefer a language with a graph builder; no code at all keeps the Rust default.
+        let tied = vec![chunk("a.go", None), chunk("b.py", None)];
+        assert_eq!(
+            detect_graph_language(&tied),
+            DetectedLanguage::Supported(GraphLanguage::Python)
+        );
+        assert_eq!(
+            detect_graph_language(&docs),
+            DetectedLanguage::Supported(GraphLanguage::Rust)
+        );
+    }
+}
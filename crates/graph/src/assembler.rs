@@ -1,13 +1,22 @@
 use crate::error::Result;
-use crate::types::{CodeGraph, RelationshipType};
+use crate::types::{CodeGraph, RelationshipType, Symbol};
 use context_code_chunker::CodeChunk;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Multiplier for a related chunk's relevance by its file path (e.g. a search profile's
+/// per-directory weights)
+pub type PathWeightFn = Arc<dyn Fn(&str) -> f32 + Send + Sync>;
 
 /// Smart context assembler for AI agents
 ///
 /// Automatically gathers related code chunks based on graph relationships
 pub struct ContextAssembler {
     graph: CodeGraph,
+    path_weight: Option<PathWeightFn>,
 }
 
 /// Context assembly strategy
@@ -43,25 +52,82 @@ pub struct AssembledContext {
 pub struct RelatedChunk {
     pub chunk: CodeChunk,
     pub relationship: Vec<RelationshipType>,
+    /// Hops from the primary symbol (excluded) to this chunk's symbol (last): each step is the
+    /// symbol reached and the edge that reached it
+    pub path: Vec<(Symbol, RelationshipType)>,
     pub distance: usize,
     pub relevance_score: f32,
+    /// Budget bucket this chunk was counted against (first hop of `relationship`)
+    pub bucket: RelationshipType,
+}
+
+impl RelatedChunk {
+    /// Names of the symbols along [`Self::path`], in order
+    #[must_use]
+    pub fn symbol_path(&self) -> Vec<String> {
+        self.path
+            .iter()
+            .map(|(symbol, _)| symbol.name.clone())
+            .collect()
+    }
+}
+
+/// Limits on the related chunks gathered per primary.
+///
+/// Buckets are keyed by the first hop of the relationship path, so a
+/// `Calls -> Uses` chunk counts against `Calls`. The default has no caps and
+/// reproduces unbudgeted assembly exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct AssemblyBudget {
+    /// Max related chunks per bucket; buckets not listed are uncapped
+    #[serde(default)]
+    pub per_relationship: HashMap<RelationshipType, usize>,
+
+    /// Max related chunks overall
+    #[serde(default)]
+    pub max_related: Option<usize>,
+
+    /// Max graph distance from the primary (tightens the strategy depth)
+    #[serde(default)]
+    pub max_distance: Option<usize>,
 }
 
-const fn relationship_rank(rel: RelationshipType) -> u8 {
-    match rel {
-        RelationshipType::Calls => 0,
-        RelationshipType::Uses => 1,
-        RelationshipType::Contains => 2,
-        RelationshipType::Extends => 3,
-        RelationshipType::Imports => 4,
-        RelationshipType::TestedBy => 5,
+impl AssemblyBudget {
+    /// Cap for a bucket, if the budget sets one
+    #[must_use]
+    pub fn cap_for(&self, bucket: RelationshipType) -> Option<usize> {
+        self.per_relationship.get(&bucket).copied()
+    }
+
+    /// Whether a chunk at `distance` hops is within budget
+    #[must_use]
+    pub fn allows_distance(&self, distance: usize) -> bool {
+        self.max_distance.is_none_or(|max| distance <= max)
+    }
+
+    /// Keep the first chunks (in ranked order) that fit the per-bucket and overall caps
+    fn apply(&self, related: &mut Vec<RelatedChunk>) {
+        let mut used: HashMap<RelationshipType, usize> = HashMap::new();
+        let mut kept = 0usize;
+        related.retain(|rc| {
+            if self.max_related.is_some_and(|max| kept >= max) {
+                return false;
+            }
+            let count = used.entry(rc.bucket).or_insert(0);
+            if self.cap_for(rc.bucket).is_some_and(|cap| *count >= cap) {
+                return false;
+            }
+            *count += 1;
+            kept += 1;
+            true
+        });
     }
 }
 
 fn compare_relationship_paths(a: &[RelationshipType], b: &[RelationshipType]) -> Ordering {
     for (&left, &right) in a.iter().zip(b.iter()) {
-        let left = relationship_rank(left);
-        let right = relationship_rank(right);
+        let left = left.relevance_rank();
+        let right = right.relevance_rank();
         match left.cmp(&right) {
             Ordering::Equal => {}
             non_eq => return non_eq,
@@ -73,7 +139,15 @@ fn compare_relationship_paths(a: &[RelationshipType], b: &[RelationshipType]) ->
 impl ContextAssembler {
     #[must_use]
     pub const fn new(graph: CodeGraph) -> Self {
-        Self { graph }
+        Self {
+            graph,
+            path_weight: None,
+        }
+    }
+
+    /// Scale related chunks' relevance by `weight(file_path)` before they are ranked
+    pub fn set_path_weight(&mut self, weight: PathWeightFn) {
+        self.path_weight = Some(weight);
     }
 
     /// Assemble context for a symbol
@@ -82,12 +156,25 @@ impl ContextAssembler {
         symbol_name: &str,
         strategy: AssemblyStrategy,
     ) -> Result<AssembledContext> {
-        let max_depth = match strategy {
+        self.assemble_for_symbol_with_budget(symbol_name, strategy, &AssemblyBudget::default())
+    }
+
+    /// Assemble context for a symbol, keeping only related chunks that fit `budget`
+    pub fn assemble_for_symbol_with_budget(
+        &self,
+        symbol_name: &str,
+        strategy: AssemblyStrategy,
+        budget: &AssemblyBudget,
+    ) -> Result<AssembledContext> {
+        let strategy_depth = match strategy {
             AssemblyStrategy::Direct => 1,
             AssemblyStrategy::Extended => 2,
             AssemblyStrategy::Deep => 3,
             AssemblyStrategy::Custom(d) => d,
         };
+        let max_depth = budget
+            .max_distance
+            .map_or(strategy_depth, |max| strategy_depth.min(max));
 
         // Find primary node
         let node = self
@@ -106,19 +193,39 @@ impl ContextAssembler {
         })?;
 
         // Get related nodes
-        let related_nodes = self.graph.get_related_nodes(node, max_depth);
+        let mut related_nodes = self.graph.get_related_node_paths(node, max_depth);
+        self.extend_with_implementors(node, max_depth, &mut related_nodes);
 
         // Build related chunks with scores
         let mut related_chunks = Vec::new();
-        for (rel_node, distance, path) in related_nodes {
+        for (rel_node, hops) in related_nodes {
             if let Some(node_data) = self.graph.get_node(rel_node) {
-                if let Some(chunk) = &node_data.chunk {
-                    let relevance = Self::calculate_relevance(distance, &path);
+                // Trait method nodes share their trait's chunk; never echo the primary chunk
+                if node_data.chunk_id == primary_node.chunk_id {
+                    continue;
+                }
+                let distance = hops.len();
+                let relationship: Vec<RelationshipType> =
+                    hops.iter().map(|&(_, rel)| rel).collect();
+                if let (Some(chunk), Some(&bucket)) = (&node_data.chunk, relationship.first()) {
+                    let relevance = Self::calculate_relevance(distance, &relationship)
+                        * self
+                            .path_weight
+                            .as_ref()
+                            .map_or(1.0, |weight| weight(&chunk.file_path));
+                    let path = hops
+                        .iter()
+                        .filter_map(|&(hop, rel)| {
+                            self.graph.get_node(hop).map(|n| (n.symbol.clone(), rel))
+                        })
+                        .collect();
                     related_chunks.push(RelatedChunk {
                         chunk: chunk.clone(),
-                        relationship: path,
+                        relationship,
+                        path,
                         distance,
                         relevance_score: relevance,
+                        bucket,
                     });
                 }
             }
@@ -134,6 +241,15 @@ impl ContextAssembler {
                 .then_with(|| a.chunk.end_line.cmp(&b.chunk.end_line))
                 .then_with(|| compare_relationship_paths(&a.relationship, &b.relationship))
         });
+        let mut seen_chunks = HashSet::new();
+        related_chunks.retain(|rc| {
+            seen_chunks.insert((
+                rc.chunk.file_path.clone(),
+                rc.chunk.start_line,
+                rc.chunk.end_line,
+            ))
+        });
+        budget.apply(&mut related_chunks);
 
         // Calculate total lines
         let total_lines = primary_chunk.line_count()
@@ -154,6 +270,16 @@ impl ContextAssembler {
         &self,
         chunk_id: &str,
         strategy: AssemblyStrategy,
+    ) -> Result<AssembledContext> {
+        self.assemble_for_chunk_with_budget(chunk_id, strategy, &AssemblyBudget::default())
+    }
+
+    /// Assemble context for a chunk ID, keeping only related chunks that fit `budget`
+    pub fn assemble_for_chunk_with_budget(
+        &self,
+        chunk_id: &str,
+        strategy: AssemblyStrategy,
+        budget: &AssemblyBudget,
     ) -> Result<AssembledContext> {
         // Find nodes for this chunk
         let nodes = self.graph.find_nodes_by_chunk(chunk_id);
@@ -168,7 +294,44 @@ impl ContextAssembler {
             .get_node(nodes[0])
             .ok_or_else(|| crate::error::GraphError::NodeNotFound(chunk_id.to_string()))?;
 
-        self.assemble_for_symbol(&node.symbol.name, strategy)
+        self.assemble_for_symbol_with_budget(&node.symbol.name, strategy, budget)
+    }
+
+    /// Implementations point at their trait (impl -> trait), so outgoing traversal never
+    /// reaches them. When the query hits a trait or one of its methods, pull the impls in.
+    fn extend_with_implementors(
+        &self,
+        node: NodeIndex,
+        max_depth: usize,
+        related: &mut Vec<(NodeIndex, Vec<(NodeIndex, RelationshipType)>)>,
+    ) {
+        let mut seen: HashSet<NodeIndex> = related.iter().map(|(n, _)| *n).collect();
+        seen.insert(node);
+
+        // The trait itself plus its method declarations (reached via Contains only)
+        let mut seeds = vec![(node, Vec::new())];
+        seeds.extend(
+            related
+                .iter()
+                .filter(|(_, path)| {
+                    path.iter()
+                        .all(|(_, rel)| matches!(rel, RelationshipType::Contains))
+                })
+                .cloned(),
+        );
+
+        for (seed, path) in seeds {
+            if path.len() >= max_depth {
+                continue;
+            }
+            for implementor in self.graph.get_implementors(seed) {
+                if seen.insert(implementor) {
+                    let mut new_path = path.clone();
+                    new_path.push((implementor, RelationshipType::Implements));
+                    related.push((implementor, new_path));
+                }
+            }
+        }
     }
 
     /// Calculate relevance score based on distance and relationship path
@@ -181,11 +344,12 @@ impl ContextAssembler {
         let relationship_score: f32 = path
             .iter()
             .map(|rel| match rel {
-                RelationshipType::Calls => 1.0,    // Direct call = highest relevance
-                RelationshipType::Uses => 0.8,     // Type usage = high relevance
+                RelationshipType::Calls => 1.0, // Direct call = highest relevance
+                RelationshipType::Implements => 0.9, // Trait -> impl = near-direct relevance
+                RelationshipType::Uses => 0.8,  // Type usage = high relevance
                 RelationshipType::Contains => 0.7, // Parent-child = medium-high
-                RelationshipType::Imports => 0.5,  // Import = medium relevance
-                RelationshipType::Extends => 0.6,  // Inheritance = medium relevance
+                RelationshipType::Imports => 0.5, // Import = medium relevance
+                RelationshipType::Extends => 0.6, // Inheritance = medium relevance
                 RelationshipType::TestedBy => 0.4, // Test = lower relevance
             })
             .sum::<f32>()
@@ -279,6 +443,8 @@ mod tests {
             },
             chunk_id: format!("{path}:{start}:{end}"),
             chunk: Some(mk_chunk(path, start, end)),
+            signature: None,
+            documentation: None,
         };
 
         let primary = graph.add_node(mk_node("primary", "main.rs", 1, 10));
@@ -323,4 +489,286 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn path_weight_reranks_related_chunks() {
+        let mut graph = CodeGraph::new();
+        let mk_node = |name: &str, path: &str| GraphNode {
+            symbol: Symbol {
+                name: name.to_string(),
+                qualified_name: None,
+                file_path: path.to_string(),
+                start_line: 1,
+                end_line: 3,
+                symbol_type: SymbolType::Function,
+            },
+            chunk_id: format!("{path}:1:3"),
+            chunk: Some(CodeChunk::new(
+                path.to_string(),
+                1,
+                3,
+                format!("fn {name}() {{}}"),
+                ChunkMetadata::default(),
+            )),
+            signature: None,
+            documentation: None,
+        };
+
+        let main = graph.add_node(mk_node("main", "src/main.rs"));
+        for (name, path) in [("demo", "examples/demo.rs"), ("core", "src/core.rs")] {
+            let node = graph.add_node(mk_node(name, path));
+            graph.add_edge(
+                main,
+                node,
+                GraphEdge {
+                    relationship: RelationshipType::Calls,
+                    weight: 1.0,
+                },
+            );
+        }
+
+        let mut assembler = ContextAssembler::new(graph);
+        let files = |assembler: &ContextAssembler| -> Vec<String> {
+            assembler
+                .assemble_for_symbol("main", AssemblyStrategy::Direct)
+                .unwrap()
+                .related_chunks
+                .into_iter()
+                .map(|rc| rc.chunk.file_path)
+                .collect()
+        };
+        assert_eq!(files(&assembler), vec!["examples/demo.rs", "src/core.rs"]);
+
+        assembler.set_path_weight(Arc::new(|path: &str| {
+            if path.starts_with("examples/") {
+                0.5
+            } else {
+                1.0
+            }
+        }));
+        assert_eq!(files(&assembler), vec!["src/core.rs", "examples/demo.rs"]);
+    }
+
+    #[test]
+    fn related_chunks_record_the_symbol_chain_from_the_primary() {
+        let mut graph = CodeGraph::new();
+        let mk_node = |name: &str, path: &str| GraphNode {
+            symbol: Symbol {
+                name: name.to_string(),
+                qualified_name: None,
+                file_path: path.to_string(),
+                start_line: 1,
+                end_line: 3,
+                symbol_type: SymbolType::Function,
+            },
+            chunk_id: format!("{path}:1:3"),
+            chunk: Some(CodeChunk::new(
+                path.to_string(),
+                1,
+                3,
+                format!("fn {name}() {{}}"),
+                ChunkMetadata::default(),
+            )),
+            signature: None,
+            documentation: None,
+        };
+
+        let main = graph.add_node(mk_node("main", "main.rs"));
+        let handle = graph.add_node(mk_node("handle", "handle.rs"));
+        let parse = graph.add_node(mk_node("parse", "parse.rs"));
+        let token = graph.add_node(mk_node("Token", "token.rs"));
+        let edge = |relationship| GraphEdge {
+            relationship,
+            weight: 1.0,
+        };
+        graph.add_edge(main, handle, edge(RelationshipType::Calls));
+        graph.add_edge(handle, parse, edge(RelationshipType::Calls));
+        graph.add_edge(parse, token, edge(RelationshipType::Uses));
+
+        let assembler = ContextAssembler::new(graph);
+        let assembled = assembler
+            .assemble_for_symbol("main", AssemblyStrategy::Deep)
+            .unwrap();
+        let token = assembled
+            .related_chunks
+            .iter()
+            .find(|rc| rc.chunk.file_path == "token.rs")
+            .unwrap();
+
+        assert_eq!(token.distance, 3);
+        assert_eq!(token.symbol_path(), vec!["handle", "parse", "Token"]);
+        let relationships: Vec<RelationshipType> = token.path.iter().map(|(_, rel)| *rel).collect();
+        assert_eq!(
+            relationships,
+            vec![
+                RelationshipType::Calls,
+                RelationshipType::Calls,
+                RelationshipType::Uses
+            ]
+        );
+        assert_eq!(token.relationship, relationships);
+    }
+
+    #[test]
+    fn trait_queries_pull_in_implementations() {
+        let mut graph = CodeGraph::new();
+
+        let mk_node =
+            |name: &str, qualified: &str, path: &str, start: usize, end: usize| GraphNode {
+                symbol: Symbol {
+                    name: name.to_string(),
+                    qualified_name: Some(qualified.to_string()),
+                    file_path: path.to_string(),
+                    start_line: start,
+                    end_line: end,
+                    symbol_type: SymbolType::Method,
+                },
+                chunk_id: format!("{path}:{start}:{end}"),
+                chunk: Some(CodeChunk::new(
+                    path.to_string(),
+                    start,
+                    end,
+                    format!("// {qualified}"),
+                    ChunkMetadata::default(),
+                )),
+                signature: None,
+                documentation: None,
+            };
+
+        let trait_node = graph.add_node(mk_node("Renderer", "Renderer", "render.rs", 1, 4));
+        let trait_method = graph.add_node(mk_node("render", "Renderer::render", "render.rs", 1, 4));
+        let html = graph.add_node(mk_node("render", "Html::render", "html.rs", 3, 5));
+        let text = graph.add_node(mk_node("render", "Text::render", "text.rs", 3, 5));
+        let helper = graph.add_node(mk_node("helper", "helper", "util.rs", 1, 2));
+
+        let edge = |relationship| GraphEdge {
+            relationship,
+            weight: 1.0,
+        };
+        graph.add_edge(trait_node, trait_method, edge(RelationshipType::Contains));
+        graph.add_edge(html, trait_method, edge(RelationshipType::Implements));
+        graph.add_edge(text, trait_method, edge(RelationshipType::Implements));
+        graph.add_edge(trait_method, helper, edge(RelationshipType::Uses));
+
+        let assembler = ContextAssembler::new(graph);
+
+        let direct = assembler
+            .assemble_for_symbol("Renderer::render", AssemblyStrategy::Direct)
+            .unwrap();
+        let files: Vec<&str> = direct
+            .related_chunks
+            .iter()
+            .map(|rc| rc.chunk.file_path.as_str())
+            .collect();
+        assert_eq!(files, vec!["html.rs", "text.rs", "util.rs"]);
+        assert_eq!(
+            direct.related_chunks[0].relationship,
+            vec![RelationshipType::Implements]
+        );
+
+        // From the trait itself impls sit one Contains hop further; the trait's own chunk
+        // (shared with its method node) is never echoed back.
+        let extended = assembler
+            .assemble_for_symbol("Renderer", AssemblyStrategy::Extended)
+            .unwrap();
+        let files: Vec<&str> = extended
+            .related_chunks
+            .iter()
+            .map(|rc| rc.chunk.file_path.as_str())
+            .collect();
+        assert_eq!(files, vec!["html.rs", "text.rs", "util.rs"]);
+    }
+
+    #[test]
+    fn budget_caps_buckets_and_distance() {
+        let mut graph = CodeGraph::new();
+        let mk_node = |name: &str, path: &str| GraphNode {
+            symbol: Symbol {
+                name: name.to_string(),
+                qualified_name: None,
+                file_path: path.to_string(),
+                start_line: 1,
+                end_line: 2,
+                symbol_type: SymbolType::Function,
+            },
+            chunk_id: format!("{path}:1:2"),
+            chunk: Some(CodeChunk::new(
+                path.to_string(),
+                1,
+                2,
+                format!("// {name}"),
+                ChunkMetadata::default(),
+            )),
+            signature: None,
+            documentation: None,
+        };
+        let edge = |relationship| GraphEdge {
+            relationship,
+            weight: 1.0,
+        };
+
+        let primary = graph.add_node(mk_node("primary", "main.rs"));
+        let callee_a = graph.add_node(mk_node("a", "a.rs"));
+        let callee_b = graph.add_node(mk_node("b", "b.rs"));
+        let ty = graph.add_node(mk_node("Config", "config.rs"));
+        let deep = graph.add_node(mk_node("deep", "deep.rs"));
+        graph.add_edge(primary, callee_a, edge(RelationshipType::Calls));
+        graph.add_edge(primary, callee_b, edge(RelationshipType::Calls));
+        graph.add_edge(primary, ty, edge(RelationshipType::Uses));
+        graph.add_edge(callee_a, deep, edge(RelationshipType::Calls));
+
+        let assembler = ContextAssembler::new(graph);
+
+        let unbudgeted = assembler
+            .assemble_for_symbol("primary", AssemblyStrategy::Extended)
+            .unwrap();
+        let defaulted = assembler
+            .assemble_for_symbol_with_budget(
+                "primary",
+                AssemblyStrategy::Extended,
+                &AssemblyBudget::default(),
+            )
+            .unwrap();
+        let files = |ctx: &AssembledContext| -> Vec<String> {
+            ctx.related_chunks
+                .iter()
+                .map(|rc| rc.chunk.file_path.clone())
+                .collect()
+        };
+        assert_eq!(files(&unbudgeted), files(&defaulted));
+        assert_eq!(files(&unbudgeted).len(), 4);
+
+        let budget = AssemblyBudget {
+            per_relationship: HashMap::from([(RelationshipType::Calls, 1)]),
+            max_related: None,
+            max_distance: Some(1),
+        };
+        let budgeted = assembler
+            .assemble_for_symbol_with_budget("primary", AssemblyStrategy::Extended, &budget)
+            .unwrap();
+        let buckets: Vec<(String, RelationshipType)> = budgeted
+            .related_chunks
+            .iter()
+            .map(|rc| (rc.chunk.file_path.clone(), rc.bucket))
+            .collect();
+        assert_eq!(
+            buckets,
+            vec![
+                ("a.rs".to_string(), RelationshipType::Calls),
+                ("config.rs".to_string(), RelationshipType::Uses),
+            ]
+        );
+
+        let capped = assembler
+            .assemble_for_symbol_with_budget(
+                "primary",
+                AssemblyStrategy::Extended,
+                &AssemblyBudget {
+                    max_related: Some(1),
+                    ..AssemblyBudget::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(capped.related_chunks.len(), 1);
+    }
 }
@@ -1,11 +1,15 @@
 use crate::error::{GraphError, Result};
+use crate::symbol_docs;
 use crate::types::{CodeGraph, GraphEdge, GraphNode, RelationshipType, Symbol, SymbolType};
 use context_code_chunker::CodeChunk;
 use petgraph::graph::NodeIndex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tree_sitter::{Node, Parser};
 
+/// Tag set by the chunker on methods of an `impl Trait for Type` block
+const IMPLEMENTS_TAG_PREFIX: &str = "implements:";
+
 /// Supported languages for graph analysis
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GraphLanguage {
@@ -15,6 +19,29 @@ pub enum GraphLanguage {
     TypeScript,
 }
 
+impl GraphLanguage {
+    /// Every language with a graph builder.
+    pub const ALL: [Self; 4] = [Self::Rust, Self::Python, Self::JavaScript, Self::TypeScript];
+
+    /// Name used by the chunker (`chunk.metadata.language`) and the `language` tool argument.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.as_str() == name)
+    }
+}
+
 /// Build code graph from chunks
 pub struct GraphBuilder {
     language: GraphLanguage,
@@ -45,24 +72,91 @@ impl GraphBuilder {
 
         // Phase 1: Create nodes for all symbols
         let mut chunk_to_node: HashMap<String, NodeIndex> = HashMap::new();
+        let previous = Self::previous_chunks(chunks);
 
-        for chunk in chunks {
+        for (chunk_pos, chunk) in chunks.iter().enumerate() {
             let symbol = Self::extract_symbol(chunk);
             let chunk_id = format!(
                 "{}:{}:{}",
                 chunk.file_path, chunk.start_line, chunk.end_line
             );
+            let previous_chunk = previous.get(&chunk_pos).map(|&pos| &chunks[pos]);
 
             let node = GraphNode {
                 symbol,
                 chunk_id: chunk_id.clone(),
                 chunk: Some(chunk.clone()),
+                signature: symbol_docs::extract_signature(&chunk.content, 0, self.language),
+                documentation: symbol_docs::extract_documentation(
+                    chunk,
+                    0,
+                    self.language,
+                    previous_chunk,
+                ),
             };
 
             let idx = graph.add_node(node);
             chunk_to_node.insert(chunk_id, idx);
         }
 
+        // Phase 1b: Trait method declarations get their own nodes so impls can point at them
+        let mut traits: HashMap<String, NodeIndex> = HashMap::new();
+        let mut trait_methods: HashMap<(String, String), NodeIndex> = HashMap::new();
+        if self.language == GraphLanguage::Rust {
+            for chunk in chunks {
+                if chunk.metadata.chunk_type != Some(context_code_chunker::ChunkType::Interface) {
+                    continue;
+                }
+                let Some(trait_name) = chunk.metadata.symbol_name.clone() else {
+                    continue;
+                };
+                let chunk_id = format!(
+                    "{}:{}:{}",
+                    chunk.file_path, chunk.start_line, chunk.end_line
+                );
+                let Some(&trait_idx) = chunk_to_node.get(&chunk_id) else {
+                    continue;
+                };
+                traits.insert(trait_name.clone(), trait_idx);
+
+                for (method, start_row, end_row) in self.extract_trait_methods(chunk)? {
+                    let node = GraphNode {
+                        symbol: Symbol {
+                            name: method.clone(),
+                            qualified_name: Some(format!("{trait_name}::{method}")),
+                            file_path: chunk.file_path.clone(),
+                            start_line: chunk.start_line + start_row,
+                            end_line: chunk.start_line + end_row,
+                            symbol_type: SymbolType::Method,
+                        },
+                        chunk_id: chunk_id.clone(),
+                        chunk: Some(chunk.clone()),
+                        signature: symbol_docs::extract_signature(
+                            &chunk.content,
+                            start_row,
+                            self.language,
+                        ),
+                        documentation: symbol_docs::extract_documentation(
+                            chunk,
+                            start_row,
+                            self.language,
+                            None,
+                        ),
+                    };
+                    let method_idx = graph.add_node(node);
+                    graph.add_edge(
+                        trait_idx,
+                        method_idx,
+                        GraphEdge {
+                            relationship: RelationshipType::Contains,
+                            weight: 1.0,
+                        },
+                    );
+                    trait_methods.insert((trait_name.clone(), method), method_idx);
+                }
+            }
+        }
+
         // Phase 2: Analyze relationships and add edges
         for chunk in chunks {
             let chunk_id = format!(
@@ -97,6 +191,70 @@ impl GraphBuilder {
             }
         }
 
+        // Phase 3: Link trait impls (impl method -> trait method, impl type -> trait)
+        let mut linked_types: HashSet<(NodeIndex, NodeIndex)> = HashSet::new();
+        for chunk in chunks {
+            let Some(trait_name) = chunk
+                .metadata
+                .tags
+                .iter()
+                .find_map(|tag| tag.strip_prefix(IMPLEMENTS_TAG_PREFIX))
+            else {
+                continue;
+            };
+            let chunk_id = format!(
+                "{}:{}:{}",
+                chunk.file_path, chunk.start_line, chunk.end_line
+            );
+            let Some(&impl_idx) = chunk_to_node.get(&chunk_id) else {
+                continue;
+            };
+
+            if let Some(method) = chunk.metadata.symbol_name.as_deref() {
+                let key = (trait_name.to_string(), method.to_string());
+                if let Some(&trait_method_idx) = trait_methods.get(&key) {
+                    graph.add_edge(
+                        impl_idx,
+                        trait_method_idx,
+                        GraphEdge {
+                            relationship: RelationshipType::Implements,
+                            weight: 1.0,
+                        },
+                    );
+                }
+            }
+
+            let Some(&trait_idx) = traits.get(trait_name) else {
+                continue;
+            };
+            let type_idx = chunk
+                .metadata
+                .parent_scope
+                .as_deref()
+                .map(Self::bare_type_name)
+                .and_then(|type_name| graph.find_node(type_name))
+                .filter(|&idx| {
+                    graph.get_node(idx).is_some_and(|node| {
+                        matches!(
+                            node.symbol.symbol_type,
+                            SymbolType::Struct | SymbolType::Enum | SymbolType::Class
+                        )
+                    })
+                });
+            if let Some(type_idx) = type_idx {
+                if linked_types.insert((type_idx, trait_idx)) {
+                    graph.add_edge(
+                        type_idx,
+                        trait_idx,
+                        GraphEdge {
+                            relationship: RelationshipType::Implements,
+                            weight: 1.0,
+                        },
+                    );
+                }
+            }
+        }
+
         log::info!(
             "Built code graph: {} nodes, {} edges",
             graph.node_count(),
@@ -122,6 +280,8 @@ impl GraphBuilder {
                 context_code_chunker::ChunkType::Method => SymbolType::Method,
                 context_code_chunker::ChunkType::Class => SymbolType::Class,
                 context_code_chunker::ChunkType::Struct => SymbolType::Struct,
+                context_code_chunker::ChunkType::Enum => SymbolType::Enum,
+                context_code_chunker::ChunkType::Interface => SymbolType::Interface,
                 context_code_chunker::ChunkType::Variable => SymbolType::Variable,
                 _ => SymbolType::Function,
             });
@@ -136,6 +296,79 @@ impl GraphBuilder {
         }
     }
 
+    /// Map each chunk position to the chunk of the same file that ends closest before it
+    fn previous_chunks(chunks: &[CodeChunk]) -> HashMap<usize, usize> {
+        let mut by_file: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (pos, chunk) in chunks.iter().enumerate() {
+            by_file
+                .entry(chunk.file_path.as_str())
+                .or_default()
+                .push(pos);
+        }
+
+        let mut previous = HashMap::new();
+        for positions in by_file.values_mut() {
+            positions.sort_by_key(|&pos| (chunks[pos].start_line, chunks[pos].end_line));
+            for (i, &pos) in positions.iter().enumerate() {
+                let start = chunks[pos].start_line;
+                if let Some(&prev) = positions[..i]
+                    .iter()
+                    .filter(|&&p| chunks[p].end_line < start)
+                    .max_by_key(|&&p| chunks[p].end_line)
+                {
+                    previous.insert(pos, prev);
+                }
+            }
+        }
+        previous
+    }
+
+    /// Extract method declarations from a Rust trait chunk
+    /// Returns (name, start row, end row) with rows relative to the chunk start
+    fn extract_trait_methods(&mut self, chunk: &CodeChunk) -> Result<Vec<(String, usize, usize)>> {
+        let tree = self
+            .parser
+            .parse(&chunk.content, None)
+            .ok_or_else(|| GraphError::BuildError("Failed to parse chunk".to_string()))?;
+
+        let root = tree.root_node();
+        let mut methods = Vec::new();
+        let mut cursor = root.walk();
+        for item in root.children(&mut cursor) {
+            if item.kind() != "trait_item" {
+                continue;
+            }
+            let Some(body) = item.child_by_field_name("body") else {
+                continue;
+            };
+            let mut body_cursor = body.walk();
+            for decl in body.children(&mut body_cursor) {
+                if !matches!(decl.kind(), "function_signature_item" | "function_item") {
+                    continue;
+                }
+                if let Some(name) = decl.child_by_field_name("name") {
+                    methods.push((
+                        chunk.content[name.start_byte()..name.end_byte()].to_string(),
+                        decl.start_position().row,
+                        decl.end_position().row,
+                    ));
+                }
+            }
+        }
+
+        Ok(methods)
+    }
+
+    /// Strip module path and generics from an impl target (`crate::Foo<T>` -> `Foo`)
+    fn bare_type_name(type_repr: &str) -> &str {
+        let without_generics = type_repr.split('<').next().unwrap_or_default();
+        without_generics
+            .rsplit("::")
+            .next()
+            .unwrap_or_default()
+            .trim()
+    }
+
     /// Extract function calls from chunk (simplified)
     fn extract_function_calls(&mut self, chunk: &CodeChunk) -> Result<Vec<String>> {
         let tree = self
@@ -356,4 +589,77 @@ mod tests {
         let calls = graph.get_nodes_by_relationship(caller, RelationshipType::Calls);
         assert!(calls.contains(&foo));
     }
+
+    fn chunk_rust(path: &str, code: &str) -> Vec<CodeChunk> {
+        context_code_chunker::Chunker::new(context_code_chunker::ChunkerConfig::default())
+            .chunk_str(code, Some(path))
+            .expect("chunk rust fixture")
+    }
+
+    #[test]
+    fn build_graph_links_trait_methods_to_every_impl() {
+        let mut chunks = chunk_rust(
+            "src/render.rs",
+            "pub trait Renderer {\n    fn render(&self) -> String;\n    fn name(&self) -> &str { \"renderer\" }\n}\n",
+        );
+        chunks.extend(chunk_rust(
+            "src/html.rs",
+            "/// Renders markup.\npub struct Html {\n    pub title: String,\n    pub body: Vec<String>,\n}\n\nimpl Renderer for Html {\n    fn render(&self) -> String {\n        String::from(\"<p></p>\")\n    }\n}\n",
+        ));
+        chunks.extend(chunk_rust(
+            "src/text.rs",
+            "/// Renders plain text.\npub struct Text {\n    pub lines: Vec<String>,\n    pub width: usize,\n}\n\nimpl crate::render::Renderer for Text {\n    fn render(&self) -> String {\n        String::from(\"plain\")\n    }\n}\n",
+        ));
+
+        let mut builder = GraphBuilder::new(GraphLanguage::Rust).unwrap();
+        let graph = builder.build(&chunks).unwrap();
+
+        let trait_method = graph
+            .find_node("Renderer::render")
+            .expect("trait method node");
+        let mut impl_files: Vec<String> = graph
+            .get_implementors(trait_method)
+            .into_iter()
+            .filter_map(|idx| graph.get_node(idx).map(|n| n.symbol.file_path.clone()))
+            .collect();
+        impl_files.sort();
+        assert_eq!(impl_files, vec!["src/html.rs", "src/text.rs"]);
+
+        let html_render = graph.find_node("Html::render").expect("impl method node");
+        assert_eq!(
+            graph.get_nodes_by_relationship(html_render, RelationshipType::Implements),
+            vec![trait_method]
+        );
+
+        let trait_node = graph.find_node("Renderer").expect("trait node");
+        assert_eq!(graph.get_implementors(trait_node).len(), 2);
+        let trait_members = graph.get_nodes_by_relationship(trait_node, RelationshipType::Contains);
+        assert_eq!(trait_members.len(), 2);
+        assert!(graph.find_node("Renderer::name").is_some());
+    }
+
+    #[test]
+    fn build_graph_captures_signatures_and_doc_comments() {
+        let chunks = chunk_rust(
+            "src/report.rs",
+            "/// Writes the report.\n///\n/// ```\n/// let n = write_report(&mut out, &rows, true)?;\n/// ```\n#[inline]\npub fn write_report<W: std::io::Write>(\n    out: &mut W,\n    rows: &[String],\n    header: bool,\n) -> std::io::Result<usize> {\n    let mut written = 0;\n    if header {\n        written += out.write(b\"# report\\n\")?;\n    }\n    for row in rows {\n        written += out.write(row.as_bytes())?;\n    }\n    Ok(written)\n}\n",
+        );
+
+        let mut builder = GraphBuilder::new(GraphLanguage::Rust).unwrap();
+        let graph = builder.build(&chunks).unwrap();
+        let node = graph
+            .get_node(graph.find_node("write_report").expect("function node"))
+            .unwrap();
+
+        assert_eq!(
+            node.signature.as_deref(),
+            Some("pub fn write_report<W: std::io::Write>(\n    out: &mut W,\n    rows: &[String],\n    header: bool,\n) -> std::io::Result<usize>")
+        );
+        let doc = node.documentation.as_deref().expect("doc comment");
+        assert!(doc.starts_with("Writes the report."), "{doc}");
+        assert_eq!(
+            crate::doc_examples(doc),
+            vec!["let n = write_report(&mut out, &rows, true)?;"]
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use context_protocol::ErrorCode;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, GraphError>;
@@ -10,6 +11,9 @@ pub enum GraphError {
     #[error("Node not found: {0}")]
     NodeNotFound(String),
 
+    #[error("Symbol '{0}' not found")]
+    SymbolNotFound(String),
+
     #[error("Invalid symbol: {0}")]
     InvalidSymbol(String),
 
@@ -19,3 +23,15 @@ pub enum GraphError {
     #[error("{0}")]
     Other(String),
 }
+
+impl GraphError {
+    #[must_use]
+    pub const fn code(&self) -> ErrorCode {
+        match self {
+            Self::SymbolNotFound(_) | Self::NodeNotFound(_) | Self::InvalidSymbol(_) => {
+                ErrorCode::InvalidRequest
+            }
+            Self::BuildError(_) | Self::TraversalError(_) | Self::Other(_) => ErrorCode::Internal,
+        }
+    }
+}
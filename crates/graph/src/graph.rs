@@ -1,14 +1,37 @@
 use crate::error::Result;
 use crate::types::{CodeGraph, RelationshipType};
 use petgraph::algo::astar;
-use petgraph::graph::NodeIndex;
+use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
-use std::collections::HashSet;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 type SymbolRelation = (NodeIndex, RelationshipType);
 type SymbolRelations = Vec<SymbolRelation>;
 
+/// Path found by weighted search: each hop carries the relationship used to reach it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightedPath {
+    pub steps: Vec<(NodeIndex, Option<RelationshipType>)>,
+    /// Sum of [`RelationshipType::trace_cost`] over all hops
+    pub cost: u32,
+}
+
+#[derive(Clone)]
+struct RawPath {
+    nodes: Vec<NodeIndex>,
+    edges: Vec<EdgeIndex>,
+    cost: u32,
+}
+
+impl RawPath {
+    fn is_simple(&self) -> bool {
+        let mut seen = HashSet::new();
+        self.nodes.iter().all(|n| seen.insert(*n))
+    }
+}
+
 impl CodeGraph {
     /// Find all nodes that current node calls (outgoing Calls edges)
     #[must_use]
@@ -33,6 +56,16 @@ impl CodeGraph {
             .collect()
     }
 
+    /// Find all implementations of a trait or trait method (incoming Implements edges)
+    #[must_use]
+    pub fn get_implementors(&self, node: NodeIndex) -> Vec<NodeIndex> {
+        self.graph
+            .edges_directed(node, Direction::Incoming)
+            .filter(|e| matches!(e.weight().relationship, RelationshipType::Implements))
+            .map(|e| e.source())
+            .collect()
+    }
+
     /// Find all nodes that current node uses (outgoing Uses edges)
     #[must_use]
     pub fn get_dependencies(&self, node: NodeIndex) -> Vec<NodeIndex> {
@@ -51,11 +84,29 @@ impl CodeGraph {
         node: NodeIndex,
         max_depth: usize,
     ) -> Vec<(NodeIndex, usize, Vec<RelationshipType>)> {
+        self.get_related_node_paths(node, max_depth)
+            .into_iter()
+            .map(|(target, path)| {
+                let relationships = path.iter().map(|&(_, rel)| rel).collect();
+                (target, path.len(), relationships)
+            })
+            .collect()
+    }
+
+    /// Like [`Self::get_related_nodes`], but keeps the hops themselves: each step is the node
+    /// reached and the edge used to reach it, ending with the related node (start excluded)
+    #[must_use]
+    pub fn get_related_node_paths(
+        &self,
+        node: NodeIndex,
+        max_depth: usize,
+    ) -> Vec<(NodeIndex, Vec<(NodeIndex, RelationshipType)>)> {
         let mut visited = HashSet::new();
         let mut result = Vec::new();
-        let mut queue = vec![(node, 0, vec![])];
+        let mut queue = vec![(node, Vec::new())];
 
-        while let Some((current, depth, path)) = queue.pop() {
+        while let Some((current, path)) = queue.pop() {
+            let depth = path.len();
             if depth > max_depth || visited.contains(&current) {
                 continue;
             }
@@ -63,7 +114,7 @@ impl CodeGraph {
             visited.insert(current);
 
             if current != node {
-                result.push((current, depth, path.clone()));
+                result.push((current, path.clone()));
             }
 
             if depth < max_depth {
@@ -72,8 +123,8 @@ impl CodeGraph {
                     let target = edge.target();
                     if !visited.contains(&target) {
                         let mut new_path = path.clone();
-                        new_path.push(edge.weight().relationship);
-                        queue.push((target, depth + 1, new_path));
+                        new_path.push((target, edge.weight().relationship));
+                        queue.push((target, new_path));
                     }
                 }
             }
@@ -115,6 +166,177 @@ impl CodeGraph {
         Some(result)
     }
 
+    /// Find up to `max_paths` loop-free paths ordered by relationship-weighted cost
+    /// (Yen's k-shortest paths). When `via` is set, every path passes through it.
+    /// Used by: trace tool
+    #[must_use]
+    pub fn find_weighted_paths(
+        &self,
+        from: NodeIndex,
+        to: NodeIndex,
+        max_paths: usize,
+        via: Option<NodeIndex>,
+    ) -> Vec<WeightedPath> {
+        let max_paths = max_paths.max(1);
+        let raw = self.k_shortest_paths(from, to, max_paths, via);
+
+        raw.into_iter()
+            .map(|path| {
+                let mut steps = vec![(path.nodes[0], None)];
+                for (node, edge) in path.nodes[1..].iter().zip(&path.edges) {
+                    steps.push((*node, Some(self.graph[*edge].relationship)));
+                }
+                WeightedPath {
+                    steps,
+                    cost: path.cost,
+                }
+            })
+            .collect()
+    }
+
+    /// Yen's algorithm over a layered graph: a state is `(node, via already visited)`,
+    /// so the via constraint is part of the search rather than a join of two path lists.
+    /// Walks that reach a node twice (once per layer) are used for deviations but never
+    /// returned.
+    fn k_shortest_paths(
+        &self,
+        from: NodeIndex,
+        to: NodeIndex,
+        k: usize,
+        via: Option<NodeIndex>,
+    ) -> Vec<RawPath> {
+        let seen_via = |nodes: &[NodeIndex]| via.is_none_or(|via| nodes.contains(&via));
+        let Some(first) = self.cheapest_path(
+            from,
+            to,
+            via,
+            seen_via(&[from]),
+            &HashSet::new(),
+            &HashSet::new(),
+        ) else {
+            return Vec::new();
+        };
+        let max_explored = k.saturating_mul(8).saturating_add(16);
+        let mut found = vec![first];
+        let mut candidates: Vec<RawPath> = Vec::new();
+
+        while found.iter().filter(|p| p.is_simple()).count() < k && found.len() < max_explored {
+            let last = found[found.len() - 1].clone();
+            for i in 0..last.nodes.len().saturating_sub(1) {
+                let spur = last.nodes[i];
+                let root_nodes = &last.nodes[..=i];
+                let root_edges = &last.edges[..i];
+
+                let banned_edges: HashSet<EdgeIndex> = found
+                    .iter()
+                    .filter(|p| p.nodes.len() > i + 1 && p.nodes[..=i] == *root_nodes)
+                    .map(|p| p.edges[i])
+                    .collect();
+                let banned_nodes: HashSet<NodeIndex> = root_nodes[..i].iter().copied().collect();
+
+                let Some(spur_path) = self.cheapest_path(
+                    spur,
+                    to,
+                    via,
+                    seen_via(root_nodes),
+                    &banned_nodes,
+                    &banned_edges,
+                ) else {
+                    continue;
+                };
+
+                let root_cost: u32 = root_edges
+                    .iter()
+                    .map(|e| self.graph[*e].relationship.trace_cost())
+                    .sum();
+                let mut nodes = root_nodes.to_vec();
+                nodes.extend_from_slice(&spur_path.nodes[1..]);
+                let mut edges = root_edges.to_vec();
+                edges.extend_from_slice(&spur_path.edges);
+                let candidate = RawPath {
+                    nodes,
+                    edges,
+                    cost: root_cost + spur_path.cost,
+                };
+                let duplicate = found
+                    .iter()
+                    .chain(candidates.iter())
+                    .any(|p| p.edges == candidate.edges);
+                if !duplicate {
+                    candidates.push(candidate);
+                }
+            }
+
+            let Some(best) = candidates
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.cost.cmp(&b.cost).then(a.nodes.cmp(&b.nodes)))
+                .map(|(idx, _)| idx)
+            else {
+                break;
+            };
+            found.push(candidates.swap_remove(best));
+        }
+
+        found.retain(RawPath::is_simple);
+        found.truncate(k);
+        found
+    }
+
+    /// Dijkstra over relationship costs, skipping banned nodes/edges.
+    /// States are `(node, via visited)`; only `(to, true)` is accepted as the goal.
+    fn cheapest_path(
+        &self,
+        from: NodeIndex,
+        to: NodeIndex,
+        via: Option<NodeIndex>,
+        seen_via: bool,
+        banned_nodes: &HashSet<NodeIndex>,
+        banned_edges: &HashSet<EdgeIndex>,
+    ) -> Option<RawPath> {
+        let start = (from, seen_via);
+        let goal = (to, true);
+        let mut dist: HashMap<(NodeIndex, bool), u32> = HashMap::from([(start, 0)]);
+        let mut prev: HashMap<(NodeIndex, bool), ((NodeIndex, bool), EdgeIndex)> = HashMap::new();
+        let mut heap = BinaryHeap::from([Reverse((0u32, start))]);
+
+        while let Some(Reverse((cost, state))) = heap.pop() {
+            if state == goal {
+                break;
+            }
+            if dist.get(&state).is_some_and(|best| cost > *best) {
+                continue;
+            }
+            for edge in self.graph.edges(state.0) {
+                let next = edge.target();
+                if banned_nodes.contains(&next) || banned_edges.contains(&edge.id()) {
+                    continue;
+                }
+                let next_state = (next, state.1 || via == Some(next));
+                let next_cost = cost + edge.weight().relationship.trace_cost();
+                if dist.get(&next_state).is_none_or(|best| next_cost < *best) {
+                    dist.insert(next_state, next_cost);
+                    prev.insert(next_state, (state, edge.id()));
+                    heap.push(Reverse((next_cost, next_state)));
+                }
+            }
+        }
+
+        let cost = *dist.get(&goal)?;
+        let mut nodes = vec![to];
+        let mut edges = Vec::new();
+        let mut current = goal;
+        while current != start {
+            let (before, edge) = *prev.get(&current)?;
+            edges.push(edge);
+            nodes.push(before.0);
+            current = before;
+        }
+        nodes.reverse();
+        edges.reverse();
+        Some(RawPath { nodes, edges, cost })
+    }
+
     /// Get nodes by relationship type
     #[must_use]
     pub fn get_nodes_by_relationship(
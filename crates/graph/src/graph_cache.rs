@@ -0,0 +1,646 @@
+use crate::builder::GraphLanguage;
+use crate::graph_doc::fnv1a64;
+use crate::types::{CodeGraph, GraphEdge, GraphNode, RelationshipType, Symbol, SymbolType};
+use context_code_chunker::CodeChunk;
+use petgraph::visit::EdgeRef;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+/// Bump whenever the binary layout below changes; older files are rebuilt, never migrated.
+pub const GRAPH_CACHE_SCHEMA_VERSION: u32 = 2;
+
+/// File name of the graph cache inside `.context-finder/`.
+pub const GRAPH_CACHE_FILE_NAME: &str = "graph_cache.bin";
+
+const GRAPH_CACHE_MAGIC: &[u8; 4] = b"CFGC";
+
+// magic + schema_version + language + store_mtime_ms + node_count + edge_count + checksum
+const HEADER_LEN: usize = 4 + 4 + 1 + 8 + 8 + 8 + 8;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum GraphCacheError {
+    #[error("not a graph cache file (bad magic)")]
+    BadMagic,
+
+    #[error("unsupported graph cache schema version {found} (expected {expected})")]
+    UnsupportedVersion { found: u32, expected: u32 },
+
+    #[error("graph cache checksum mismatch (expected {expected:016x}, got {actual:016x})")]
+    ChecksumMismatch { expected: u64, actual: u64 },
+
+    #[error("graph cache truncated")]
+    Truncated,
+
+    #[error("graph cache malformed: {0}")]
+    Malformed(&'static str),
+}
+
+/// On-disk representation of a [`CodeGraph`].
+///
+/// The header carries everything needed to decide whether the cache is usable
+/// (schema version, language, store mtime) plus node/edge counts and an FNV-1a
+/// checksum of the payload, so a truncated or corrupted file is detected before
+/// any of it is turned back into a graph.
+#[derive(Debug, Clone)]
+pub struct GraphCacheFile {
+    pub schema_version: u32,
+    pub language: GraphLanguage,
+    pub store_mtime_ms: u64,
+    pub node_count: u64,
+    pub edge_count: u64,
+    pub checksum: u64,
+    nodes: Vec<CachedNode>,
+    edges: Vec<CachedEdge>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedNode {
+    symbol: Symbol,
+    chunk_id: String,
+    signature: Option<String>,
+    documentation: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedEdge {
+    from: u32,
+    to: u32,
+    relationship: RelationshipType,
+    weight: f32,
+}
+
+impl GraphCacheFile {
+    #[must_use]
+    pub fn from_graph(graph: &CodeGraph, language: GraphLanguage, store_mtime_ms: u64) -> Self {
+        let mut node_map = HashMap::new();
+        let mut nodes = Vec::new();
+        for node in graph.graph.node_indices() {
+            if let Some(data) = graph.graph.node_weight(node) {
+                #[allow(clippy::cast_possible_truncation)]
+                node_map.insert(node, nodes.len() as u32);
+                nodes.push(CachedNode {
+                    symbol: data.symbol.clone(),
+                    chunk_id: data.chunk_id.clone(),
+                    signature: data.signature.clone(),
+                    documentation: data.documentation.clone(),
+                });
+            }
+        }
+
+        let mut edges = Vec::new();
+        for edge in graph.graph.edge_references() {
+            if let (Some(&from), Some(&to)) =
+                (node_map.get(&edge.source()), node_map.get(&edge.target()))
+            {
+                edges.push(CachedEdge {
+                    from,
+                    to,
+                    relationship: edge.weight().relationship,
+                    weight: edge.weight().weight,
+                });
+            }
+        }
+
+        let checksum = fnv1a64(&encode_payload(&nodes, &edges));
+        Self {
+            schema_version: GRAPH_CACHE_SCHEMA_VERSION,
+            language,
+            store_mtime_ms,
+            node_count: nodes.len() as u64,
+            edge_count: edges.len() as u64,
+            checksum,
+            nodes,
+            edges,
+        }
+    }
+
+    /// Whether this cache was built for `language` from the store at `store_mtime_ms`.
+    #[must_use]
+    pub fn is_fresh_for(&self, language: GraphLanguage, store_mtime_ms: u64) -> bool {
+        self.language == language && self.store_mtime_ms == store_mtime_ms
+    }
+
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let payload = encode_payload(&self.nodes, &self.edges);
+        let mut out = Vec::with_capacity(HEADER_LEN + payload.len());
+        out.extend_from_slice(GRAPH_CACHE_MAGIC);
+        out.extend_from_slice(&GRAPH_CACHE_SCHEMA_VERSION.to_le_bytes());
+        out.push(language_code(self.language));
+        out.extend_from_slice(&self.store_mtime_ms.to_le_bytes());
+        out.extend_from_slice(&(self.nodes.len() as u64).to_le_bytes());
+        out.extend_from_slice(&(self.edges.len() as u64).to_le_bytes());
+        out.extend_from_slice(&fnv1a64(&payload).to_le_bytes());
+        out.extend_from_slice(&payload);
+        out
+    }
+
+    /// Decode a cache file, verifying magic, schema version, checksum and counts.
+    pub fn decode(bytes: &[u8]) -> Result<Self, GraphCacheError> {
+        if bytes.len() < 4 || &bytes[0..4] != GRAPH_CACHE_MAGIC {
+            return Err(GraphCacheError::BadMagic);
+        }
+        let mut header = Reader::new(&bytes[4..]);
+        let schema_version = header.u32()?;
+        if schema_version != GRAPH_CACHE_SCHEMA_VERSION {
+            return Err(GraphCacheError::UnsupportedVersion {
+                found: schema_version,
+                expected: GRAPH_CACHE_SCHEMA_VERSION,
+            });
+        }
+        let language = language_from_code(header.u8()?)?;
+        let store_mtime_ms = header.u64()?;
+        let node_count = header.u64()?;
+        let edge_count = header.u64()?;
+        let checksum = header.u64()?;
+
+        let payload = &bytes[HEADER_LEN..];
+        let actual = fnv1a64(payload);
+        if actual != checksum {
+            return Err(GraphCacheError::ChecksumMismatch {
+                expected: checksum,
+                actual,
+            });
+        }
+
+        let mut reader = Reader::new(payload);
+        let mut nodes = Vec::new();
+        for _ in 0..node_count {
+            nodes.push(reader.node()?);
+        }
+        let mut edges = Vec::new();
+        for _ in 0..edge_count {
+            edges.push(reader.edge()?);
+        }
+        if !reader.is_empty() {
+            return Err(GraphCacheError::Malformed("trailing bytes after edges"));
+        }
+        if edges
+            .iter()
+            .any(|e| u64::from(e.from.max(e.to)) >= node_count)
+        {
+            return Err(GraphCacheError::Malformed("edge references unknown node"));
+        }
+
+        Ok(Self {
+            schema_version,
+            language,
+            store_mtime_ms,
+            node_count,
+            edge_count,
+            checksum,
+            nodes,
+            edges,
+        })
+    }
+
+    /// Drop the nodes of `files` along with every edge touching them.
+    ///
+    /// Returns how many nodes were dropped; the header counts and checksum follow the
+    /// remaining payload, so the result encodes like a cache built without those files.
+    pub fn drop_files(&mut self, files: &HashSet<&str>) -> usize {
+        let mut remap = Vec::with_capacity(self.nodes.len());
+        let mut kept: u32 = 0;
+        for node in &self.nodes {
+            if files.contains(node.symbol.file_path.as_str()) {
+                remap.push(None);
+            } else {
+                remap.push(Some(kept));
+                kept += 1;
+            }
+        }
+        let dropped = self.nodes.len() - kept as usize;
+        if dropped == 0 {
+            return 0;
+        }
+
+        self.nodes
+            .retain(|node| !files.contains(node.symbol.file_path.as_str()));
+        self.edges.retain_mut(|edge| {
+            let from = remap.get(edge.from as usize).copied().flatten();
+            let to = remap.get(edge.to as usize).copied().flatten();
+            match (from, to) {
+                (Some(from), Some(to)) => {
+                    edge.from = from;
+                    edge.to = to;
+                    true
+                }
+                _ => false,
+            }
+        });
+        self.node_count = self.nodes.len() as u64;
+        self.edge_count = self.edges.len() as u64;
+        self.checksum = fnv1a64(&encode_payload(&self.nodes, &self.edges));
+        dropped
+    }
+
+    /// Rebuild the graph, attaching chunks via `chunk_for`.
+    ///
+    /// Returns `None` if any cached node refers to a chunk that no longer exists,
+    /// which callers treat as "rebuild from scratch".
+    pub fn into_graph(
+        self,
+        mut chunk_for: impl FnMut(&str) -> Option<CodeChunk>,
+    ) -> Option<CodeGraph> {
+        let mut graph = CodeGraph::new();
+        let mut node_indices = Vec::with_capacity(self.nodes.len());
+        for node in self.nodes {
+            let chunk = chunk_for(&node.chunk_id)?;
+            node_indices.push(graph.add_node(GraphNode {
+                symbol: node.symbol,
+                chunk_id: node.chunk_id,
+                chunk: Some(chunk),
+                signature: node.signature,
+                documentation: node.documentation,
+            }));
+        }
+        for edge in self.edges {
+            graph.add_edge(
+                *node_indices.get(edge.from as usize)?,
+                *node_indices.get(edge.to as usize)?,
+                GraphEdge {
+                    relationship: edge.relationship,
+                    weight: edge.weight,
+                },
+            );
+        }
+        Some(graph)
+    }
+}
+
+fn encode_payload(nodes: &[CachedNode], edges: &[CachedEdge]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for node in nodes {
+        let symbol = &node.symbol;
+        put_str(&mut out, &symbol.name);
+        put_opt_str(&mut out, symbol.qualified_name.as_deref());
+        put_str(&mut out, &symbol.file_path);
+        out.extend_from_slice(&(symbol.start_line as u64).to_le_bytes());
+        out.extend_from_slice(&(symbol.end_line as u64).to_le_bytes());
+        out.push(symbol_type_code(&symbol.symbol_type));
+        put_str(&mut out, &node.chunk_id);
+        put_opt_str(&mut out, node.signature.as_deref());
+        put_opt_str(&mut out, node.documentation.as_deref());
+    }
+    for edge in edges {
+        out.extend_from_slice(&edge.from.to_le_bytes());
+        out.extend_from_slice(&edge.to.to_le_bytes());
+        out.push(relationship_code(edge.relationship));
+        out.extend_from_slice(&edge.weight.to_le_bytes());
+    }
+    out
+}
+
+fn put_str(out: &mut Vec<u8>, value: &str) {
+    #[allow(clippy::cast_possible_truncation)]
+    let len = value.len() as u32;
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn put_opt_str(out: &mut Vec<u8>, value: Option<&str>) {
+    match value {
+        Some(value) => {
+            out.push(1);
+            put_str(out, value);
+        }
+        None => out.push(0),
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    const fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], GraphCacheError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .ok_or(GraphCacheError::Truncated)?;
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or(GraphCacheError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], GraphCacheError> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    fn u8(&mut self) -> Result<u8, GraphCacheError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, GraphCacheError> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, GraphCacheError> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Result<f32, GraphCacheError> {
+        self.array().map(f32::from_le_bytes)
+    }
+
+    fn usize(&mut self) -> Result<usize, GraphCacheError> {
+        usize::try_from(self.u64()?).map_err(|_| GraphCacheError::Malformed("line out of range"))
+    }
+
+    fn string(&mut self) -> Result<String, GraphCacheError> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| GraphCacheError::Malformed("invalid utf-8"))
+    }
+
+    fn opt_string(&mut self) -> Result<Option<String>, GraphCacheError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => self.string().map(Some),
+            _ => Err(GraphCacheError::Malformed("invalid option flag")),
+        }
+    }
+
+    fn node(&mut self) -> Result<CachedNode, GraphCacheError> {
+        let name = self.string()?;
+        let qualified_name = self.opt_string()?;
+        let file_path = self.string()?;
+        let start_line = self.usize()?;
+        let end_line = self.usize()?;
+        let symbol_type = symbol_type_from_code(self.u8()?)?;
+        let chunk_id = self.string()?;
+        let signature = self.opt_string()?;
+        let documentation = self.opt_string()?;
+        Ok(CachedNode {
+            symbol: Symbol {
+                name,
+                qualified_name,
+                file_path,
+                start_line,
+                end_line,
+                symbol_type,
+            },
+            chunk_id,
+            signature,
+            documentation,
+        })
+    }
+
+    fn edge(&mut self) -> Result<CachedEdge, GraphCacheError> {
+        Ok(CachedEdge {
+            from: self.u32()?,
+            to: self.u32()?,
+            relationship: relationship_from_code(self.u8()?)?,
+            weight: self.f32()?,
+        })
+    }
+}
+
+// Stable wire codes: never renumber, only append (and bump the schema version when removing).
+
+const fn language_code(language: GraphLanguage) -> u8 {
+    match language {
+        GraphLanguage::Rust => 0,
+        GraphLanguage::Python => 1,
+        GraphLanguage::JavaScript => 2,
+        GraphLanguage::TypeScript => 3,
+    }
+}
+
+const fn language_from_code(code: u8) -> Result<GraphLanguage, GraphCacheError> {
+    Ok(match code {
+        0 => GraphLanguage::Rust,
+        1 => GraphLanguage::Python,
+        2 => GraphLanguage::JavaScript,
+        3 => GraphLanguage::TypeScript,
+        _ => return Err(GraphCacheError::Malformed("unknown language")),
+    })
+}
+
+const fn symbol_type_code(symbol_type: &SymbolType) -> u8 {
+    match symbol_type {
+        SymbolType::Function => 0,
+        SymbolType::Method => 1,
+        SymbolType::Class => 2,
+        SymbolType::Struct => 3,
+        SymbolType::Enum => 4,
+        SymbolType::Interface => 5,
+        SymbolType::Variable => 6,
+        SymbolType::Constant => 7,
+        SymbolType::Module => 8,
+    }
+}
+
+const fn symbol_type_from_code(code: u8) -> Result<SymbolType, GraphCacheError> {
+    Ok(match code {
+        0 => SymbolType::Function,
+        1 => SymbolType::Method,
+        2 => SymbolType::Class,
+        3 => SymbolType::Struct,
+        4 => SymbolType::Enum,
+        5 => SymbolType::Interface,
+        6 => SymbolType::Variable,
+        7 => SymbolType::Constant,
+        8 => SymbolType::Module,
+        _ => return Err(GraphCacheError::Malformed("unknown symbol type")),
+    })
+}
+
+const fn relationship_code(relationship: RelationshipType) -> u8 {
+    match relationship {
+        RelationshipType::Calls => 0,
+        RelationshipType::Uses => 1,
+        RelationshipType::Imports => 2,
+        RelationshipType::Contains => 3,
+        RelationshipType::Extends => 4,
+        RelationshipType::Implements => 5,
+        RelationshipType::TestedBy => 6,
+    }
+}
+
+const fn relationship_from_code(code: u8) -> Result<RelationshipType, GraphCacheError> {
+    Ok(match code {
+        0 => RelationshipType::Calls,
+        1 => RelationshipType::Uses,
+        2 => RelationshipType::Imports,
+        3 => RelationshipType::Contains,
+        4 => RelationshipType::Extends,
+        5 => RelationshipType::Implements,
+        6 => RelationshipType::TestedBy,
+        _ => return Err(GraphCacheError::Malformed("unknown relationship")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_code_chunker::ChunkMetadata;
+
+    fn node(name: &str, qualified: Option<&str>, symbol_type: SymbolType) -> GraphNode {
+        GraphNode {
+            symbol: Symbol {
+                name: name.to_string(),
+                qualified_name: qualified.map(str::to_string),
+                file_path: "src/lib.rs".to_string(),
+                start_line: 1,
+                end_line: 10,
+                symbol_type,
+            },
+            chunk_id: format!("src/lib.rs:{name}"),
+            chunk: None,
+            signature: Some(format!("fn {name}()")),
+            documentation: qualified.map(|q| format!("Docs for {q}.")),
+        }
+    }
+
+    fn sample_graph() -> CodeGraph {
+        let mut graph = CodeGraph::new();
+        let a = graph.add_node(node("render", Some("Html::render"), SymbolType::Method));
+        let b = graph.add_node(node("Renderer", None, SymbolType::Interface));
+        let c = graph.add_node(node("helper", None, SymbolType::Function));
+        graph.add_edge(
+            a,
+            b,
+            GraphEdge {
+                relationship: RelationshipType::Implements,
+                weight: 1.0,
+            },
+        );
+        graph.add_edge(
+            a,
+            c,
+            GraphEdge {
+                relationship: RelationshipType::Calls,
+                weight: 0.5,
+            },
+        );
+        graph
+    }
+
+    fn chunk_for(id: &str) -> Option<CodeChunk> {
+        Some(CodeChunk::new(
+            id.to_string(),
+            1,
+            10,
+            String::new(),
+            ChunkMetadata::default(),
+        ))
+    }
+
+    #[test]
+    fn round_trips_nodes_edges_and_header() {
+        let file = GraphCacheFile::from_graph(&sample_graph(), GraphLanguage::Rust, 42);
+        let decoded = GraphCacheFile::decode(&file.encode()).expect("decode");
+        assert_eq!(decoded.schema_version, GRAPH_CACHE_SCHEMA_VERSION);
+        assert_eq!(decoded.node_count, 3);
+        assert_eq!(decoded.edge_count, 2);
+        assert_eq!(decoded.checksum, file.checksum);
+        assert!(decoded.is_fresh_for(GraphLanguage::Rust, 42));
+        assert!(!decoded.is_fresh_for(GraphLanguage::Python, 42));
+        assert!(!decoded.is_fresh_for(GraphLanguage::Rust, 43));
+
+        let graph = decoded.into_graph(chunk_for).expect("graph");
+        assert_eq!(graph.graph.node_count(), 3);
+        assert_eq!(graph.graph.edge_count(), 2);
+        let render = graph.find_node("Html::render").expect("qualified lookup");
+        let render_node = graph.get_node(render).expect("node");
+        assert_eq!(render_node.signature.as_deref(), Some("fn render()"));
+        assert_eq!(
+            render_node.documentation.as_deref(),
+            Some("Docs for Html::render.")
+        );
+        assert!(graph
+            .get_node(graph.find_node("helper").unwrap())
+            .unwrap()
+            .documentation
+            .is_none());
+        let target = graph.find_node("Renderer").expect("trait node");
+        let edge = graph.graph.find_edge(render, target).expect("edge");
+        assert_eq!(graph.graph[edge].relationship, RelationshipType::Implements);
+    }
+
+    #[test]
+    fn rejects_corrupted_truncated_and_foreign_versions() {
+        let bytes = GraphCacheFile::from_graph(&sample_graph(), GraphLanguage::Rust, 42).encode();
+
+        let mut flipped = bytes.clone();
+        let last = flipped.len() - 1;
+        flipped[last] ^= 0xff;
+        assert!(matches!(
+            GraphCacheFile::decode(&flipped),
+            Err(GraphCacheError::ChecksumMismatch { .. })
+        ));
+
+        assert!(GraphCacheFile::decode(&bytes[..bytes.len() - 3]).is_err());
+        assert!(GraphCacheFile::decode(&bytes[..10]).is_err());
+        assert_eq!(
+            GraphCacheFile::decode(b"{\"nodes\":[]}").unwrap_err(),
+            GraphCacheError::BadMagic
+        );
+
+        let mut future = bytes;
+        future[4..8].copy_from_slice(&(GRAPH_CACHE_SCHEMA_VERSION + 1).to_le_bytes());
+        assert_eq!(
+            GraphCacheFile::decode(&future).unwrap_err(),
+            GraphCacheError::UnsupportedVersion {
+                found: GRAPH_CACHE_SCHEMA_VERSION + 1,
+                expected: GRAPH_CACHE_SCHEMA_VERSION,
+            }
+        );
+    }
+
+    #[test]
+    fn dropping_files_removes_their_nodes_and_edges() {
+        let mut graph = sample_graph();
+        let mut other = node("main", None, SymbolType::Function);
+        other.symbol.file_path = "src/main.rs".to_string();
+        other.chunk_id = "src/main.rs:main".to_string();
+        let main = graph.add_node(other);
+        let helper = graph.find_node("helper").expect("helper");
+        graph.add_edge(
+            main,
+            helper,
+            GraphEdge {
+                relationship: RelationshipType::Calls,
+                weight: 1.0,
+            },
+        );
+
+        let mut file = GraphCacheFile::from_graph(&graph, GraphLanguage::Rust, 42);
+        assert_eq!(file.drop_files(&HashSet::from(["src/other.rs"])), 0);
+        assert_eq!(file.drop_files(&HashSet::from(["src/lib.rs"])), 3);
+        assert_eq!((file.node_count, file.edge_count), (1, 0));
+
+        let decoded = GraphCacheFile::decode(&file.encode()).expect("decode");
+        assert_eq!(decoded.checksum, file.checksum);
+        let graph = decoded.into_graph(chunk_for).expect("graph");
+        assert!(graph.find_node("main").is_some());
+        assert!(graph.find_node("helper").is_none());
+    }
+
+    #[test]
+    fn missing_chunks_force_a_rebuild() {
+        let file = GraphCacheFile::from_graph(&sample_graph(), GraphLanguage::Rust, 42);
+        assert!(file
+            .into_graph(|id| if id.ends_with("helper") {
+                None
+            } else {
+                chunk_for(id)
+            })
+            .is_none());
+    }
+}
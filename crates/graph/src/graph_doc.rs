@@ -144,13 +144,14 @@ fn node_key(node: &GraphNode) -> String {
     format!("{}#{}", node.chunk_id, display)
 }
 
-const fn rel_order() -> [RelationshipType; 6] {
+const fn rel_order() -> [RelationshipType; 7] {
     [
         RelationshipType::Calls,
         RelationshipType::Uses,
         RelationshipType::Imports,
         RelationshipType::Contains,
         RelationshipType::Extends,
+        RelationshipType::Implements,
         RelationshipType::TestedBy,
     ]
 }
@@ -176,11 +177,12 @@ const fn rel_name(rel: RelationshipType) -> &'static str {
         RelationshipType::Imports => "imports",
         RelationshipType::Contains => "contains",
         RelationshipType::Extends => "extends",
+        RelationshipType::Implements => "implements",
         RelationshipType::TestedBy => "tested_by",
     }
 }
 
-fn fnv1a64(bytes: &[u8]) -> u64 {
+pub(crate) fn fnv1a64(bytes: &[u8]) -> u64 {
     const OFFSET: u64 = 14_695_981_039_346_656_037;
     const PRIME: u64 = 1_099_511_628_211;
     let mut hash = OFFSET;
@@ -215,11 +217,15 @@ mod tests {
             symbol: mk_symbol("a", "a.rs", 1),
             chunk_id: "a.rs:1:2".to_string(),
             chunk: None,
+            signature: None,
+            documentation: None,
         };
         let b = GraphNode {
             symbol: mk_symbol("b", "b.rs", 10),
             chunk_id: "b.rs:10:11".to_string(),
             chunk: None,
+            signature: None,
+            documentation: None,
         };
 
         let ia = graph.add_node(a);
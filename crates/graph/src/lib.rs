@@ -30,15 +30,31 @@
 //!            └─ Return enriched context for AI agents
 //! ```
 
+pub mod analysis;
 mod assembler;
 mod builder;
 mod error;
 mod graph;
+mod graph_cache;
 mod graph_doc;
+mod module_graph;
+mod symbol_docs;
 mod types;
 
-pub use assembler::{AssembledContext, AssemblyStrategy, ContextAssembler, RelatedChunk};
+pub use assembler::{
+    AssembledContext, AssemblyBudget, AssemblyStrategy, ContextAssembler, PathWeightFn,
+    RelatedChunk,
+};
 pub use builder::{GraphBuilder, GraphLanguage};
 pub use error::{GraphError, Result};
+pub use graph::WeightedPath;
+pub use graph_cache::{
+    GraphCacheError, GraphCacheFile, GRAPH_CACHE_FILE_NAME, GRAPH_CACHE_SCHEMA_VERSION,
+};
 pub use graph_doc::{build_graph_docs, GraphDoc, GraphDocConfig, GRAPH_DOC_VERSION};
+pub use module_graph::{
+    module_for_path, ModuleCycle, ModuleEdge, ModuleGraph, ModuleGraphConfig, ModuleNode,
+    SymbolEdgeSample,
+};
+pub use symbol_docs::doc_examples;
 pub use types::{CodeGraph, GraphEdge, GraphNode, RelationshipType, Symbol, SymbolType};
@@ -0,0 +1,227 @@
+use crate::types::{CodeGraph, RelationshipType};
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// Configuration for collapsing symbol-level edges into module-level edges
+#[derive(Debug, Clone, Copy)]
+pub struct ModuleGraphConfig {
+    /// Number of leading directory components that identify a module
+    /// (e.g. depth 2 maps `crates/graph/src/lib.rs` to `crates/graph`)
+    pub depth: usize,
+}
+
+impl Default for ModuleGraphConfig {
+    fn default() -> Self {
+        Self { depth: 2 }
+    }
+}
+
+/// Module (directory) in the aggregated dependency graph
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleNode {
+    pub module: String,
+    pub symbols: usize,
+}
+
+/// One concrete symbol-level edge backing a module-level dependency
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolEdgeSample {
+    pub from_symbol: String,
+    pub to_symbol: String,
+    pub relationship: RelationshipType,
+    pub file: String,
+    pub line: usize,
+}
+
+/// Aggregated dependency between two modules
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleEdge {
+    pub from: String,
+    pub to: String,
+    /// Number of symbol-level edges collapsed into this edge
+    pub count: usize,
+}
+
+/// Dependency cycle between modules, with one symbol-level edge per hop
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleCycle {
+    /// Modules in cycle order; the last module depends on the first
+    pub modules: Vec<String>,
+    pub edges: Vec<SymbolEdgeSample>,
+}
+
+/// Directory/module-level view of a [`CodeGraph`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleGraph {
+    pub nodes: Vec<ModuleNode>,
+    pub edges: Vec<ModuleEdge>,
+    pub cycles: Vec<ModuleCycle>,
+}
+
+/// Map a file path to its module key (leading `depth` directory components)
+#[must_use]
+pub fn module_for_path(file_path: &str, depth: usize) -> String {
+    let mut parts: Vec<&str> = file_path.split('/').filter(|p| !p.is_empty()).collect();
+    parts.pop();
+    if parts.is_empty() {
+        return ".".to_string();
+    }
+    parts.truncate(depth.max(1));
+    parts.join("/")
+}
+
+impl CodeGraph {
+    /// Collapse symbol-level edges into module-level edges and detect cycles.
+    ///
+    /// `Contains` edges are ignored (they describe nesting, not dependencies),
+    /// as are edges between symbols of the same module.
+    /// Used by: overview tool
+    #[must_use]
+    pub fn module_graph(&self, config: ModuleGraphConfig) -> ModuleGraph {
+        let mut symbols: BTreeMap<String, usize> = BTreeMap::new();
+        for (_, node) in self.nodes() {
+            *symbols
+                .entry(module_for_path(&node.symbol.file_path, config.depth))
+                .or_insert(0) += 1;
+        }
+
+        let mut edges: BTreeMap<(String, String), (usize, SymbolEdgeSample)> = BTreeMap::new();
+        for edge in self.graph.edge_references() {
+            let relationship = edge.weight().relationship;
+            if relationship == RelationshipType::Contains {
+                continue;
+            }
+            let (Some(source), Some(target)) =
+                (self.get_node(edge.source()), self.get_node(edge.target()))
+            else {
+                continue;
+            };
+            let from = module_for_path(&source.symbol.file_path, config.depth);
+            let to = module_for_path(&target.symbol.file_path, config.depth);
+            if from == to {
+                continue;
+            }
+            edges
+                .entry((from, to))
+                .and_modify(|(count, _)| *count += 1)
+                .or_insert_with(|| {
+                    (
+                        1,
+                        SymbolEdgeSample {
+                            from_symbol: source.symbol.name.clone(),
+                            to_symbol: target.symbol.name.clone(),
+                            relationship,
+                            file: source.symbol.file_path.clone(),
+                            line: source.symbol.start_line,
+                        },
+                    )
+                });
+        }
+
+        let cycles = find_module_cycles(&edges);
+
+        let mut module_edges: Vec<ModuleEdge> = edges
+            .into_iter()
+            .map(|((from, to), (count, _))| ModuleEdge { from, to, count })
+            .collect();
+        module_edges.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.from.cmp(&b.from))
+                .then_with(|| a.to.cmp(&b.to))
+        });
+
+        ModuleGraph {
+            nodes: symbols
+                .into_iter()
+                .map(|(module, symbols)| ModuleNode { module, symbols })
+                .collect(),
+            edges: module_edges,
+            cycles,
+        }
+    }
+}
+
+/// Report one cycle per strongly connected component of the module graph
+fn find_module_cycles(
+    edges: &BTreeMap<(String, String), (usize, SymbolEdgeSample)>,
+) -> Vec<ModuleCycle> {
+    let mut graph: DiGraph<&str, ()> = DiGraph::new();
+    let mut index: HashMap<&str, NodeIndex> = HashMap::new();
+    for (from, to) in edges.keys() {
+        let a = *index
+            .entry(from.as_str())
+            .or_insert_with(|| graph.add_node(from.as_str()));
+        let b = *index
+            .entry(to.as_str())
+            .or_insert_with(|| graph.add_node(to.as_str()));
+        graph.add_edge(a, b, ());
+    }
+
+    let mut cycles = Vec::new();
+    for component in tarjan_scc(&graph) {
+        if component.len() < 2 {
+            continue;
+        }
+        let members: HashSet<NodeIndex> = component.iter().copied().collect();
+        let Some(&start) = component.iter().min_by_key(|idx| graph[**idx]) else {
+            continue;
+        };
+        let Some(path) = cycle_through(&graph, start, &members) else {
+            continue;
+        };
+
+        let modules: Vec<String> = path.iter().map(|idx| graph[*idx].to_string()).collect();
+        let edges = modules
+            .iter()
+            .zip(modules.iter().cycle().skip(1))
+            .filter_map(|(from, to)| {
+                edges
+                    .get(&(from.clone(), to.clone()))
+                    .map(|(_, sample)| sample.clone())
+            })
+            .collect();
+        cycles.push(ModuleCycle { modules, edges });
+    }
+
+    cycles.sort_by(|a, b| a.modules.cmp(&b.modules));
+    cycles
+}
+
+/// Shortest cycle through `start` staying inside one strongly connected component (BFS)
+fn cycle_through(
+    graph: &DiGraph<&str, ()>,
+    start: NodeIndex,
+    members: &HashSet<NodeIndex>,
+) -> Option<Vec<NodeIndex>> {
+    let mut parent: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(current) = queue.pop_front() {
+        let mut neighbors: Vec<NodeIndex> = graph
+            .neighbors(current)
+            .filter(|n| members.contains(n))
+            .collect();
+        neighbors.sort_by_key(|n| graph[*n]);
+        for next in neighbors {
+            if next == start {
+                let mut path = vec![current];
+                let mut node = current;
+                while let Some(&prev) = parent.get(&node) {
+                    path.push(prev);
+                    node = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if let Entry::Vacant(slot) = parent.entry(next) {
+                slot.insert(current);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
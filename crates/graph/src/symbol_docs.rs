@@ -0,0 +1,380 @@
+//! Signature and doc-comment extraction for graph nodes.
+//!
+//! Works on chunk text only (the builder never reads files), so documentation is looked up in
+//! three places, in order: comment lines right above the symbol inside its chunk (nested symbols
+//! such as trait methods), the chunker's `metadata.documentation`, and a comment-only tail of
+//! the chunk that ends right before the symbol. Python docstrings live in the body and win over
+//! `#` comments.
+
+use crate::builder::GraphLanguage;
+use context_code_chunker::CodeChunk;
+
+/// Signatures longer than this are assumed to be mis-detected (e.g. a missing body opener)
+const MAX_SIGNATURE_LINES: usize = 20;
+
+/// Maximum number of unchunked lines (attributes, blank lines) between a doc-only chunk and the
+/// symbol it documents
+const MAX_ADJACENT_GAP: usize = 3;
+
+/// Signature of the symbol starting at `line_offset` (0-based, relative to the chunk), without
+/// the body: everything up to the opening `{` (or the `:` ending a Python `def`/`class`).
+pub(crate) fn extract_signature(
+    content: &str,
+    line_offset: usize,
+    language: GraphLanguage,
+) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut start = line_offset;
+    while lines
+        .get(start)
+        .is_some_and(|line| is_attribute_line(line.trim_start(), language))
+    {
+        start += 1;
+    }
+
+    let mut depth = 0i32;
+    let mut out: Vec<String> = Vec::new();
+    for line in lines.iter().skip(start).take(MAX_SIGNATURE_LINES) {
+        let mut end = None;
+        for (idx, ch) in line.char_indices() {
+            match ch {
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth -= 1,
+                '{' if depth <= 0 && language != GraphLanguage::Python => {
+                    end = Some(idx);
+                    break;
+                }
+                ';' if depth <= 0 && language != GraphLanguage::Python => {
+                    end = Some(idx);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        if language == GraphLanguage::Python && depth <= 0 && line.trim_end().ends_with(':') {
+            let trimmed = line.trim_end();
+            end = Some(trimmed.len() - 1);
+        }
+        match end {
+            Some(idx) => {
+                out.push(line[..idx].trim_end().to_string());
+                let signature = out.join("\n").trim().to_string();
+                return (!signature.is_empty()).then_some(signature);
+            }
+            None => out.push(line.trim_end().to_string()),
+        }
+    }
+    None
+}
+
+/// Documentation for the symbol starting at `line_offset` inside `chunk`, cleaned of comment
+/// markers. `previous` is the chunk of the same file that ends closest before `chunk`.
+pub(crate) fn extract_documentation(
+    chunk: &CodeChunk,
+    line_offset: usize,
+    language: GraphLanguage,
+    previous: Option<&CodeChunk>,
+) -> Option<String> {
+    if language == GraphLanguage::Python {
+        if let Some(doc) = python_docstring(&chunk.content, line_offset) {
+            return Some(doc);
+        }
+    }
+
+    let lines: Vec<&str> = chunk.content.lines().collect();
+    if let Some(doc) = comment_block_before(&lines, line_offset, language) {
+        return Some(doc);
+    }
+
+    if line_offset == 0 {
+        if let Some(raw) = chunk.metadata.documentation.as_deref() {
+            let raw_lines: Vec<&str> = raw.lines().collect();
+            if let Some(doc) = comment_block_before(&raw_lines, raw_lines.len(), language) {
+                return Some(doc);
+            }
+        }
+
+        if let Some(prev) = previous {
+            let gap = chunk.start_line.saturating_sub(prev.end_line + 1);
+            if prev.file_path == chunk.file_path && gap <= MAX_ADJACENT_GAP {
+                let prev_lines: Vec<&str> = prev.content.lines().collect();
+                return comment_block_before(&prev_lines, prev_lines.len(), language);
+            }
+        }
+    }
+
+    None
+}
+
+/// Fenced code blocks (```` ``` ````) in cleaned documentation, e.g. Rust doc-tests.
+#[must_use]
+pub fn doc_examples(documentation: &str) -> Vec<String> {
+    let mut examples = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    for line in documentation.lines() {
+        let is_fence = line.trim_start().starts_with("```");
+        match (&mut current, is_fence) {
+            (None, true) => current = Some(Vec::new()),
+            (Some(body), true) => {
+                let body = std::mem::take(body);
+                current = None;
+                let example = body.join("\n");
+                if !example.trim().is_empty() {
+                    examples.push(example);
+                }
+            }
+            (Some(body), false) => body.push(line),
+            (None, false) => {}
+        }
+    }
+    examples
+}
+
+/// Comment lines directly above `end` (exclusive), skipping attributes/decorators and blank
+/// lines in between, returned without comment markers.
+fn comment_block_before(lines: &[&str], end: usize, language: GraphLanguage) -> Option<String> {
+    let mut idx = end.min(lines.len());
+    let mut collected: Vec<&str> = Vec::new();
+    let mut in_block = false;
+
+    while idx > 0 {
+        idx -= 1;
+        let line = lines[idx].trim();
+
+        if in_block {
+            collected.push(line);
+            if line.starts_with("/*") {
+                in_block = false;
+            }
+            continue;
+        }
+
+        if line.ends_with("*/") && language != GraphLanguage::Python {
+            collected.push(line);
+            in_block = !line.starts_with("/*");
+            continue;
+        }
+
+        let is_line_doc = match language {
+            GraphLanguage::Rust => line.starts_with("///") && !line.starts_with("////"),
+            GraphLanguage::Python => line.starts_with('#'),
+            GraphLanguage::JavaScript | GraphLanguage::TypeScript => line.starts_with("//"),
+        };
+        if is_line_doc {
+            collected.push(line);
+            continue;
+        }
+
+        if collected.is_empty() && (line.is_empty() || is_attribute_line(line, language)) {
+            continue;
+        }
+        break;
+    }
+
+    collected.reverse();
+    let doc = clean_comment_lines(&collected, language);
+    (!doc.is_empty()).then_some(doc)
+}
+
+fn python_docstring(content: &str, line_offset: usize) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let header_end = lines
+        .iter()
+        .enumerate()
+        .skip(line_offset)
+        .take(MAX_SIGNATURE_LINES)
+        .find(|(_, line)| line.trim_end().ends_with(':'))
+        .map(|(idx, _)| idx)?;
+    let (first_idx, first) = lines
+        .iter()
+        .enumerate()
+        .skip(header_end + 1)
+        .find(|(_, line)| !line.trim().is_empty())?;
+
+    let first = first.trim_start();
+    let first = first
+        .strip_prefix(['r', 'R', 'u', 'U'])
+        .filter(|rest| rest.starts_with("\"\"\"") || rest.starts_with("'''"))
+        .unwrap_or(first);
+    let quote = ["\"\"\"", "'''"]
+        .into_iter()
+        .find(|q| first.starts_with(q))?;
+    let rest = &first[quote.len()..];
+
+    let mut body: Vec<&str> = Vec::new();
+    if let Some(closed) = rest.find(quote) {
+        body.push(&rest[..closed]);
+    } else {
+        body.push(rest);
+        let mut closed = false;
+        for line in lines.iter().skip(first_idx + 1) {
+            if let Some(pos) = line.find(quote) {
+                body.push(&line[..pos]);
+                closed = true;
+                break;
+            }
+            body.push(line);
+        }
+        if !closed {
+            return None;
+        }
+    }
+
+    let doc = dedent(&body);
+    (!doc.is_empty()).then_some(doc)
+}
+
+fn is_attribute_line(line: &str, language: GraphLanguage) -> bool {
+    match language {
+        GraphLanguage::Rust => line.starts_with("#[") || line.starts_with("#!["),
+        GraphLanguage::Python | GraphLanguage::TypeScript | GraphLanguage::JavaScript => {
+            line.starts_with('@')
+        }
+    }
+}
+
+fn clean_comment_lines(lines: &[&str], language: GraphLanguage) -> String {
+    let stripped: Vec<&str> = lines
+        .iter()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_suffix("*/").unwrap_or(line).trim_end();
+            let body = match language {
+                GraphLanguage::Python => line.strip_prefix('#'),
+                GraphLanguage::Rust => line.strip_prefix("///"),
+                GraphLanguage::JavaScript | GraphLanguage::TypeScript => line.strip_prefix("//"),
+            }
+            .or_else(|| line.strip_prefix("/**"))
+            .or_else(|| line.strip_prefix("/*"))
+            .or_else(|| line.strip_prefix('*'))
+            .unwrap_or(line);
+            body.strip_prefix(' ').unwrap_or(body)
+        })
+        .collect();
+    trim_blank_edges(&stripped).join("\n")
+}
+
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let out: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx == 0 {
+                line.trim()
+            } else {
+                line.get(indent..)
+                    .unwrap_or_else(|| line.trim_start())
+                    .trim_end()
+            }
+        })
+        .collect();
+    trim_blank_edges(&out).join("\n")
+}
+
+fn trim_blank_edges<'a>(lines: &[&'a str]) -> Vec<&'a str> {
+    let start = lines.iter().position(|l| !l.trim().is_empty());
+    let end = lines.iter().rposition(|l| !l.trim().is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_code_chunker::ChunkMetadata;
+
+    fn chunk(path: &str, start: usize, content: &str, doc: Option<&str>) -> CodeChunk {
+        let end = start + content.lines().count().saturating_sub(1);
+        CodeChunk::new(
+            path.to_string(),
+            start,
+            end,
+            content.to_string(),
+            ChunkMetadata {
+                documentation: doc.map(str::to_string),
+                ..ChunkMetadata::default()
+            },
+        )
+    }
+
+    #[test]
+    fn multi_line_rust_signature_stops_at_body() {
+        let content = "pub fn render<W: Write>(\n    writer: &mut W,\n    items: &[Item],\n) -> io::Result<()>\nwhere\n    W: Send,\n{\n    Ok(())\n}";
+        assert_eq!(
+            extract_signature(content, 0, GraphLanguage::Rust).as_deref(),
+            Some(
+                "pub fn render<W: Write>(\n    writer: &mut W,\n    items: &[Item],\n) -> io::Result<()>\nwhere\n    W: Send,"
+            )
+        );
+        assert_eq!(
+            extract_signature("fn area(&self) -> f64;", 0, GraphLanguage::Rust).as_deref(),
+            Some("fn area(&self) -> f64")
+        );
+        assert_eq!(
+            extract_signature(
+                "def load(path,\n         strict=False):\n    pass",
+                0,
+                GraphLanguage::Python
+            )
+            .as_deref(),
+            Some("def load(path,\n         strict=False)")
+        );
+    }
+
+    #[test]
+    fn rust_doc_comments_come_from_chunker_metadata_and_keep_code_fences() {
+        let raw =
+            "/// Renders items.\n///\n/// ```\n/// let out = render(&mut buf, &[])?;\n/// ```";
+        let chunk = chunk("src/lib.rs", 10, "pub fn render() {}", Some(raw));
+        let doc = extract_documentation(&chunk, 0, GraphLanguage::Rust, None).unwrap();
+        assert_eq!(
+            doc,
+            "Renders items.\n\n```\nlet out = render(&mut buf, &[])?;\n```"
+        );
+        assert_eq!(
+            doc_examples(&doc),
+            vec!["let out = render(&mut buf, &[])?;"]
+        );
+    }
+
+    #[test]
+    fn nested_symbols_read_comments_inside_the_chunk() {
+        let content = "pub trait Shape {\n    /** Area in square\n     * units. */\n    #[must_use]\n    fn area(&self) -> f64;\n}";
+        let chunk = chunk("src/shape.rs", 1, content, None);
+        assert_eq!(
+            extract_documentation(&chunk, 4, GraphLanguage::Rust, None).as_deref(),
+            Some("Area in square\nunits.")
+        );
+    }
+
+    #[test]
+    fn doc_only_chunk_right_before_the_symbol_is_used() {
+        let prev = chunk("src/lib.rs", 1, "use std::io;\n\n/// Entry point.", None);
+        let item = chunk("src/lib.rs", 5, "fn main() {}", None);
+        assert_eq!(
+            extract_documentation(&item, 0, GraphLanguage::Rust, Some(&prev)).as_deref(),
+            Some("Entry point.")
+        );
+        let far = chunk("src/lib.rs", 40, "fn main() {}", None);
+        assert!(extract_documentation(&far, 0, GraphLanguage::Rust, Some(&prev)).is_none());
+    }
+
+    #[test]
+    fn python_docstrings_are_dedented() {
+        let content = "def load(path):\n    \"\"\"Load a file.\n\n    Returns bytes.\n    \"\"\"\n    return open(path).read()";
+        let chunk = chunk("a.py", 1, content, Some("# not this"));
+        assert_eq!(
+            extract_documentation(&chunk, 0, GraphLanguage::Python, None).as_deref(),
+            Some("Load a file.\n\nReturns bytes.")
+        );
+    }
+}
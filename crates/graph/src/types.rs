@@ -37,7 +37,7 @@ pub enum SymbolType {
 }
 
 /// Type of relationship between symbols
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, schemars::JsonSchema)]
 pub enum RelationshipType {
     /// A calls B (function call)
     Calls,
@@ -54,10 +54,64 @@ pub enum RelationshipType {
     /// A extends/implements B (inheritance)
     Extends,
 
+    /// A implements trait item B (`impl Trait for Type`, impl method -> trait method)
+    Implements,
+
     /// A is tested by B (test relationship)
     TestedBy,
 }
 
+impl RelationshipType {
+    /// Traversal cost used by weighted path search (trace tool).
+    /// Direct calls are the cheapest hop; imports and test links the most expensive,
+    /// so paths prefer real control flow over incidental coupling.
+    #[must_use]
+    pub const fn trace_cost(self) -> u32 {
+        match self {
+            Self::Calls => 1,
+            Self::Contains => 2,
+            Self::Extends => 2,
+            Self::Implements => 2,
+            Self::Uses => 3,
+            Self::Imports => 4,
+            Self::TestedBy => 5,
+        }
+    }
+
+    /// Importance order used to break relevance ties and to pick which related
+    /// chunks to drop first under a budget (lower = more important).
+    #[must_use]
+    pub const fn relevance_rank(self) -> u8 {
+        match self {
+            Self::Calls => 0,
+            Self::Implements => 1,
+            Self::Uses => 2,
+            Self::Contains => 3,
+            Self::Extends => 4,
+            Self::Imports => 5,
+            Self::TestedBy => 6,
+        }
+    }
+}
+
+impl std::str::FromStr for RelationshipType {
+    type Err = String;
+
+    /// Parse the `Debug` name used in tool output (e.g. "Calls")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Calls" => Ok(Self::Calls),
+            "Uses" => Ok(Self::Uses),
+            "Imports" => Ok(Self::Imports),
+            "Contains" => Ok(Self::Contains),
+            "Extends" => Ok(Self::Extends),
+            "Implements" => Ok(Self::Implements),
+            "TestedBy" => Ok(Self::TestedBy),
+            other => Err(format!("unknown relationship type '{other}'")),
+        }
+    }
+}
+
 /// Node in code graph
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphNode {
@@ -70,6 +124,14 @@ pub struct GraphNode {
     /// Chunk reference for quick access
     #[serde(skip)]
     pub chunk: Option<CodeChunk>,
+
+    /// Declaration without the body (may span several lines)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+
+    /// Doc comment / docstring with comment markers stripped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<String>,
 }
 
 /// Edge in code graph
@@ -109,11 +171,19 @@ impl CodeGraph {
     pub fn add_node(&mut self, node: GraphNode) -> NodeIndex {
         let chunk_id = node.chunk_id.clone();
         let symbol_name = node.symbol.name.clone();
+        let qualified_name = node
+            .symbol
+            .qualified_name
+            .clone()
+            .filter(|qualified| *qualified != symbol_name);
 
         let idx = self.graph.add_node(node);
 
-        // Update indices
+        // Update indices (qualified names disambiguate same-named methods, e.g. `Html::render`)
         self.symbol_index.insert(symbol_name, idx);
+        if let Some(qualified_name) = qualified_name {
+            self.symbol_index.insert(qualified_name, idx);
+        }
         self.chunk_index.entry(chunk_id).or_default().push(idx);
 
         idx
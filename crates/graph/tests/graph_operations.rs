@@ -1,6 +1,9 @@
 //! Tests for `CodeGraph` operations
 
-use context_graph::{CodeGraph, GraphEdge, GraphNode, RelationshipType, Symbol, SymbolType};
+use context_graph::{
+    module_for_path, CodeGraph, GraphEdge, GraphNode, ModuleGraphConfig, RelationshipType, Symbol,
+    SymbolType,
+};
 
 fn make_symbol(name: &str, file: &str, start: usize, end: usize, sym_type: SymbolType) -> Symbol {
     Symbol {
@@ -18,6 +21,8 @@ fn make_node(name: &str, chunk_id: &str, file: &str) -> GraphNode {
         symbol: make_symbol(name, file, 1, 10, SymbolType::Function),
         chunk_id: chunk_id.to_string(),
         chunk: None,
+        signature: None,
+        documentation: None,
     }
 }
 
@@ -278,3 +283,110 @@ fn test_relationship_types() {
         vec![n_dep]
     );
 }
+
+#[test]
+fn test_module_graph_collapses_edges_and_reports_cycles() {
+    let mut graph = CodeGraph::new();
+
+    let api = graph.add_node(make_node("handle", "c1", "src/api/routes.rs"));
+    let api_helper = graph.add_node(make_node("render", "c2", "src/api/view.rs"));
+    let db = graph.add_node(make_node("query", "c3", "src/db/pool.rs"));
+    let db_hook = graph.add_node(make_node("on_commit", "c4", "src/db/hooks.rs"));
+    let util = graph.add_node(make_node("fmt", "c5", "src/util/fmt.rs"));
+
+    graph.add_edge(api, db, make_edge(RelationshipType::Calls));
+    graph.add_edge(api_helper, db, make_edge(RelationshipType::Calls));
+    graph.add_edge(db_hook, api_helper, make_edge(RelationshipType::Calls));
+    graph.add_edge(api, util, make_edge(RelationshipType::Uses));
+    graph.add_edge(api, api_helper, make_edge(RelationshipType::Calls));
+
+    let modules = graph.module_graph(ModuleGraphConfig::default());
+
+    let names: Vec<&str> = modules.nodes.iter().map(|n| n.module.as_str()).collect();
+    assert_eq!(names, vec!["src/api", "src/db", "src/util"]);
+
+    let api_to_db = modules
+        .edges
+        .iter()
+        .find(|e| e.from == "src/api" && e.to == "src/db")
+        .expect("api -> db edge");
+    assert_eq!(api_to_db.count, 2);
+    assert!(!modules.edges.iter().any(|e| e.from == e.to));
+
+    assert_eq!(modules.cycles.len(), 1);
+    let cycle = &modules.cycles[0];
+    assert_eq!(cycle.modules, vec!["src/api", "src/db"]);
+    assert_eq!(cycle.edges.len(), 2);
+    assert_eq!(cycle.edges[1].from_symbol, "on_commit");
+    assert_eq!(cycle.edges[1].to_symbol, "render");
+}
+
+#[test]
+fn test_module_for_path_depth() {
+    assert_eq!(
+        module_for_path("crates/graph/src/lib.rs", 2),
+        "crates/graph"
+    );
+    assert_eq!(module_for_path("src/lib.rs", 2), "src");
+    assert_eq!(module_for_path("main.rs", 2), ".");
+}
+
+#[test]
+fn test_weighted_paths_prefer_calls_and_return_alternates() {
+    let mut graph = CodeGraph::new();
+
+    let start = graph.add_node(make_node("start", "c1", "src/a.rs"));
+    let util = graph.add_node(make_node("util", "c2", "src/util.rs"));
+    let service = graph.add_node(make_node("service", "c3", "src/service.rs"));
+    let repo = graph.add_node(make_node("repo", "c4", "src/repo.rs"));
+    let target = graph.add_node(make_node("target", "c5", "src/b.rs"));
+
+    // Short but expensive route through a utility module (imports + uses)
+    graph.add_edge(start, util, make_edge(RelationshipType::Imports));
+    graph.add_edge(util, target, make_edge(RelationshipType::Uses));
+    // Longer route made of direct calls
+    graph.add_edge(start, service, make_edge(RelationshipType::Calls));
+    graph.add_edge(service, repo, make_edge(RelationshipType::Calls));
+    graph.add_edge(repo, target, make_edge(RelationshipType::Calls));
+
+    let paths = graph.find_weighted_paths(start, target, 3, None);
+    assert_eq!(paths.len(), 2);
+
+    let best: Vec<_> = paths[0].steps.iter().map(|(n, _)| *n).collect();
+    assert_eq!(best, vec![start, service, repo, target]);
+    assert_eq!(paths[0].cost, 3);
+    assert_eq!(paths[0].steps[1].1, Some(RelationshipType::Calls));
+
+    let alternate: Vec<_> = paths[1].steps.iter().map(|(n, _)| *n).collect();
+    assert_eq!(alternate, vec![start, util, target]);
+    assert!(paths[1].cost > paths[0].cost);
+
+    let via_util = graph.find_weighted_paths(start, target, 3, Some(util));
+    assert_eq!(via_util.len(), 1);
+    assert!(via_util[0].steps.iter().any(|(n, _)| *n == util));
+}
+
+#[test]
+fn test_weighted_paths_via_search_avoids_revisiting_shared_hops() {
+    let mut graph = CodeGraph::new();
+
+    let start = graph.add_node(make_node("start", "c1", "src/a.rs"));
+    let shared = graph.add_node(make_node("shared", "c2", "src/shared.rs"));
+    let via = graph.add_node(make_node("via", "c3", "src/via.rs"));
+    let detour = graph.add_node(make_node("detour", "c4", "src/detour.rs"));
+    let target = graph.add_node(make_node("target", "c5", "src/b.rs"));
+
+    // Cheapest start -> via and via -> target both run through `shared`
+    graph.add_edge(start, shared, make_edge(RelationshipType::Calls));
+    graph.add_edge(shared, via, make_edge(RelationshipType::Calls));
+    graph.add_edge(via, shared, make_edge(RelationshipType::Calls));
+    graph.add_edge(shared, target, make_edge(RelationshipType::Calls));
+    // The only loop-free route through `via` takes the expensive detour in
+    graph.add_edge(start, detour, make_edge(RelationshipType::Imports));
+    graph.add_edge(detour, via, make_edge(RelationshipType::Imports));
+
+    let paths = graph.find_weighted_paths(start, target, 1, Some(via));
+    assert_eq!(paths.len(), 1);
+    let nodes: Vec<_> = paths[0].steps.iter().map(|(n, _)| *n).collect();
+    assert_eq!(nodes, vec![start, detour, via, shared, target]);
+}
@@ -1,3 +1,4 @@
+use context_protocol::ErrorCode;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, IndexerError>;
@@ -28,3 +29,22 @@ pub enum IndexerError {
     #[error("{0}")]
     Other(String),
 }
+
+impl IndexerError {
+    #[must_use]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::IoError(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                ErrorCode::FileNotFound
+            }
+            Self::IoError(_) => ErrorCode::FilesystemError,
+            Self::VectorStoreError(err) => err.code(),
+            Self::InvalidPath(_) => ErrorCode::ProjectNotFound,
+            Self::BudgetExceeded => ErrorCode::Timeout,
+            Self::ChunkerError(_)
+            | Self::SystemTimeError(_)
+            | Self::JsonError(_)
+            | Self::Other(_) => ErrorCode::Internal,
+        }
+    }
+}
@@ -1,15 +1,30 @@
 use crate::{IndexStats, Result};
 use context_vector_store::current_model_id;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::fs;
+use tokio::sync::Mutex;
 
 const MAX_FAILURES: usize = 5;
+/// Distinct failure reasons kept in `failure_reason_counts` unless
+/// `CONTEXT_FINDER_HEALTH_MAX_FAILURE_REASONS` overrides it.
+const DEFAULT_MAX_FAILURE_REASONS: usize = 50;
+/// Longer failure details are cut so one runaway error message cannot bloat `health.json`.
+const MAX_FAILURE_REASON_CHARS: usize = 500;
+const READ_RETRIES: usize = 5;
+const READ_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// Serializes read-modify-write cycles of `health.json` within one process.
+/// Cross-process writers are kept safe by the temp-file + rename in `persist_snapshot`.
+static HEALTH_WRITE_LOCK: Mutex<()> = Mutex::const_new(());
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 /// Snapshot persisted to `.context-finder/health.json` so other processes can
 /// report the last successful indexing run.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HealthSnapshot {
     pub last_success_unix_ms: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -37,6 +52,89 @@ pub struct HealthSnapshot {
     pub graph_cache_size_bytes: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub failure_count: Option<usize>,
+    /// Distinct failure reasons with how often they recurred, oldest last occurrence first.
+    /// Kept across successful runs and bounded to the most recent reasons.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failure_reason_counts: Vec<FailureReasonCount>,
+}
+
+/// One distinct failure reason in [`HealthSnapshot::failure_reason_counts`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct FailureReasonCount {
+    pub reason: String,
+    pub count: usize,
+    pub first_unix_ms: u64,
+    pub last_unix_ms: u64,
+}
+
+impl HealthSnapshot {
+    /// Drops failure history beyond the configured bounds and cuts oversized reasons; returns
+    /// whether anything was removed.
+    fn compact(&mut self) -> bool {
+        let mut changed = false;
+        if self.failure_reasons.len() > MAX_FAILURES {
+            let start = self.failure_reasons.len() - MAX_FAILURES;
+            self.failure_reasons.drain(..start);
+            changed = true;
+        }
+        for reason in &mut self.failure_reasons {
+            changed |= truncate_reason(reason);
+        }
+        for entry in &mut self.failure_reason_counts {
+            changed |= truncate_reason(&mut entry.reason);
+        }
+        let max = max_failure_reasons();
+        if self.failure_reason_counts.len() > max {
+            self.failure_reason_counts
+                .sort_by_key(|entry| entry.last_unix_ms);
+            let start = self.failure_reason_counts.len() - max;
+            self.failure_reason_counts.drain(..start);
+            changed = true;
+        }
+        changed
+    }
+
+    fn record_failure_reason(&mut self, reason: String, now_ms: u64) {
+        if let Some(pos) = self
+            .failure_reason_counts
+            .iter()
+            .position(|entry| entry.reason == reason)
+        {
+            let mut entry = self.failure_reason_counts.remove(pos);
+            entry.count += 1;
+            entry.last_unix_ms = now_ms;
+            self.failure_reason_counts.push(entry);
+        } else {
+            self.failure_reason_counts.push(FailureReasonCount {
+                reason,
+                count: 1,
+                first_unix_ms: now_ms,
+                last_unix_ms: now_ms,
+            });
+        }
+    }
+}
+
+/// Cuts `reason` to `MAX_FAILURE_REASON_CHARS` chars, ending in `…`; returns whether it was cut.
+fn truncate_reason(reason: &mut String) -> bool {
+    if reason.chars().nth(MAX_FAILURE_REASON_CHARS).is_none() {
+        return false;
+    }
+    let cut = reason
+        .char_indices()
+        .nth(MAX_FAILURE_REASON_CHARS - 1)
+        .map_or(reason.len(), |(idx, _)| idx);
+    reason.truncate(cut);
+    reason.push('…');
+    true
+}
+
+fn max_failure_reasons() -> usize {
+    std::env::var("CONTEXT_FINDER_HEALTH_MAX_FAILURE_REASONS")
+        .ok()
+        .and_then(|raw| raw.trim().parse::<usize>().ok())
+        .filter(|max| *max > 0)
+        .unwrap_or(DEFAULT_MAX_FAILURE_REASONS)
 }
 
 pub async fn write_health_snapshot(
@@ -53,8 +151,9 @@ pub async fn write_health_snapshot(
         .join(model_id_dir_name(&model_id))
         .join("index.json");
     let index_size_bytes = tokio::fs::metadata(index_path).await.ok().map(|m| m.len());
+    // Mirrors `context_graph::GRAPH_CACHE_FILE_NAME` (the indexer does not depend on the graph crate).
     let graph_cache_size_bytes =
-        tokio::fs::metadata(root.join(".context-finder").join("graph_cache.json"))
+        tokio::fs::metadata(root.join(".context-finder").join("graph_cache.bin"))
             .await
             .ok()
             .map(|m| m.len());
@@ -64,7 +163,16 @@ pub async fn write_health_snapshot(
     } else {
         None
     };
-    let snapshot = HealthSnapshot {
+    let _guard = HEALTH_WRITE_LOCK.lock().await;
+    // Failure history outlives successful runs, so a flaky embedder shows up as one counted
+    // reason instead of disappearing on every success.
+    let failure_reason_counts = read_snapshot(&health_file_path(root))
+        .await
+        .ok()
+        .flatten()
+        .map(|previous| previous.failure_reason_counts)
+        .unwrap_or_default();
+    let mut snapshot = HealthSnapshot {
         last_success_unix_ms: current_unix_ms(),
         last_duration_ms: Some(stats.time_ms),
         p95_duration_ms,
@@ -79,14 +187,11 @@ pub async fn write_health_snapshot(
         index_size_bytes,
         graph_cache_size_bytes,
         failure_count: Some(0),
+        failure_reason_counts,
     };
+    snapshot.compact();
 
-    let path = health_file_path(root);
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).await?;
-    }
-    let data = serde_json::to_vec_pretty(&snapshot)?;
-    fs::write(&path, data).await?;
+    persist_snapshot(root, &snapshot).await?;
     Ok(snapshot)
 }
 
@@ -106,7 +211,8 @@ pub async fn append_failure_reason(
     detail: &str,
     p95_duration_ms: Option<u64>,
 ) -> Result<()> {
-    let mut snapshot = read_health_snapshot(root)
+    let _guard = HEALTH_WRITE_LOCK.lock().await;
+    let mut snapshot = read_snapshot(&health_file_path(root))
         .await?
         .unwrap_or_else(|| HealthSnapshot {
             last_success_unix_ms: 0,
@@ -123,39 +229,89 @@ pub async fn append_failure_reason(
             failure_count: None,
             files_per_sec: None,
             pending_events: None,
+            failure_reason_counts: Vec::new(),
         });
 
-    snapshot.failure_reasons.push(format!("{reason}: {detail}"));
+    let now_ms = current_unix_ms();
+    let mut line = format!("{reason}: {detail}");
+    truncate_reason(&mut line);
+    snapshot.failure_reasons.push(line.clone());
+    snapshot.record_failure_reason(line, now_ms);
     snapshot.p95_duration_ms = snapshot.p95_duration_ms.or(p95_duration_ms);
-    snapshot.last_failure_unix_ms = Some(current_unix_ms());
-    snapshot.last_failure_reason = Some(detail.to_string());
-    if snapshot.failure_reasons.len() > MAX_FAILURES {
-        let start = snapshot.failure_reasons.len() - MAX_FAILURES;
-        snapshot.failure_reasons = snapshot.failure_reasons.split_off(start);
-    }
+    snapshot.last_failure_unix_ms = Some(now_ms);
+    let mut last_failure_reason = detail.to_string();
+    truncate_reason(&mut last_failure_reason);
+    snapshot.last_failure_reason = Some(last_failure_reason);
+    snapshot.compact();
     snapshot.failure_count = Some(snapshot.failure_reasons.len());
 
-    let path = health_file_path(root);
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).await?;
-    }
-    let data = serde_json::to_vec_pretty(&snapshot)?;
-    fs::write(&path, data).await?;
-    Ok(())
+    persist_snapshot(root, &snapshot).await
 }
 
+/// Reads `health.json`, retrying briefly on parse errors so a reader racing a
+/// writer on filesystems without atomic rename does not surface a transient failure.
+///
+/// A file written before failure history was bounded is truncated in place on first load so
+/// later reads stay cheap.
 pub async fn read_health_snapshot(root: &Path) -> Result<Option<HealthSnapshot>> {
     let path = health_file_path(root);
-    match fs::read(&path).await {
-        Ok(bytes) => {
-            let snapshot = serde_json::from_slice(&bytes)?;
-            Ok(Some(snapshot))
+    let Some(mut snapshot) = read_snapshot(&path).await? else {
+        return Ok(None);
+    };
+    if snapshot.compact() {
+        let _guard = HEALTH_WRITE_LOCK.lock().await;
+        // Re-read under the lock so a snapshot written meanwhile is not overwritten.
+        if let Some(mut current) = read_snapshot(&path).await? {
+            if current.compact() {
+                log::info!("Truncating oversized failure history in {}", path.display());
+                persist_snapshot(root, &current).await?;
+            }
+            snapshot = current;
+        }
+    }
+    Ok(Some(snapshot))
+}
+
+async fn read_snapshot(path: &Path) -> Result<Option<HealthSnapshot>> {
+    let mut attempt = 0;
+    loop {
+        let bytes = match fs::read(path).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        match serde_json::from_slice(&bytes) {
+            Ok(snapshot) => return Ok(Some(snapshot)),
+            Err(_) if attempt + 1 < READ_RETRIES => {
+                attempt += 1;
+                tokio::time::sleep(READ_RETRY_DELAY).await;
+            }
+            Err(err) => return Err(err.into()),
         }
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
-        Err(err) => Err(err.into()),
     }
 }
 
+/// Writes the snapshot to a unique temp file and renames it over `health.json`,
+/// so concurrent writers (watcher + manual CLI index) never interleave bytes.
+async fn persist_snapshot(root: &Path, snapshot: &HealthSnapshot) -> Result<()> {
+    let path = health_file_path(root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let data = serde_json::to_vec_pretty(snapshot)?;
+    let tmp = path.with_extension(format!(
+        "json.{}.{}.tmp",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&tmp, data).await?;
+    if let Err(err) = fs::rename(&tmp, &path).await {
+        let _ = fs::remove_file(&tmp).await;
+        return Err(err.into());
+    }
+    Ok(())
+}
+
 #[must_use]
 pub fn health_file_path(root: &Path) -> PathBuf {
     root.join(".context-finder").join("health.json")
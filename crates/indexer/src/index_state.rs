@@ -30,6 +30,12 @@ pub enum StaleReason {
     GitHeadMismatch,
     GitDirtyMismatch,
     FilesystemChanged,
+    /// Built by a different tool version, embedding templates or chunking config
+    ToolOrTemplateChanged,
+    /// Built for a different search profile or embedding templates than the active ones
+    ConfigChanged,
+    /// Built with a different embedding model (or dimension) than the active one
+    ModelMismatch,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
@@ -66,6 +72,87 @@ pub struct IndexSnapshot {
     pub built_at_unix_ms: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub watermark: Option<Watermark>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<IndexProvenance>,
+}
+
+/// What built an index: recorded next to the watermark at index time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct IndexProvenance {
+    /// Version of the tool that built the index
+    pub tool_version: String,
+    pub model_id: String,
+    /// Embedding dimension; only known for a built index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimension: Option<usize>,
+    pub doc_template_hash: u64,
+    pub graph_node_template_hash: u64,
+    /// Hash of the effective chunker configuration
+    pub chunker_fingerprint: u64,
+    /// Profile the index was built for; absent when the indexer was not told
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<IndexConfigMark>,
+}
+
+/// Search profile and embedding templates an index was built for.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct IndexConfigMark {
+    pub profile: String,
+    /// Hash of the profile's complete embedding templates
+    pub templates_hash: u64,
+}
+
+impl IndexProvenance {
+    /// Whether an index built with `self` would be built differently by `current`
+    /// (the model and `config` are checked separately by [`assess_staleness`]).
+    #[must_use]
+    pub fn differs_from(&self, current: &Self) -> bool {
+        self.tool_version != current.tool_version
+            || self.doc_template_hash != current.doc_template_hash
+            || self.graph_node_template_hash != current.graph_node_template_hash
+            || self.chunker_fingerprint != current.chunker_fingerprint
+    }
+
+    /// Whether `self` was embedded with another model than `current`; dimensions are
+    /// compared only when both are known (the active one usually is not).
+    #[must_use]
+    pub fn model_differs_from(&self, current: &Self) -> bool {
+        self.model_id != current.model_id
+            || matches!((self.dimension, current.dimension), (Some(a), Some(b)) if a != b)
+    }
+
+    /// One-line summary, e.g. `index built by v0.1.0 with model bge-small on 2026-10-16`.
+    #[must_use]
+    pub fn describe(&self, built_at_unix_ms: Option<u64>) -> String {
+        let mut out = format!(
+            "index built by v{} with model {}",
+            self.tool_version, self.model_id
+        );
+        if let Some(dimension) = self.dimension {
+            out.push_str(&format!(" ({dimension}d)"));
+        }
+        if let Some(built_at) = built_at_unix_ms {
+            out.push_str(" on ");
+            out.push_str(&utc_date(built_at));
+        }
+        out
+    }
+}
+
+/// `YYYY-MM-DD` (UTC) for a unix timestamp in milliseconds.
+fn utc_date(unix_ms: u64) -> String {
+    // Civil-from-days (Howard Hinnant), valid for any date after 1970.
+    let days = i64::try_from(unix_ms / 86_400_000).unwrap_or(i64::MAX / 2) + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
@@ -82,6 +169,9 @@ pub struct IndexState {
     pub stale_reasons: Vec<StaleReason>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reindex: Option<ReindexAttempt>,
+    /// Every attempt of a retried reindex, oldest first; `reindex` is the last one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reindex_attempts: Vec<ReindexAttempt>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, JsonSchema)]
@@ -90,10 +180,70 @@ pub struct StaleAssessment {
     pub reasons: Vec<StaleReason>,
 }
 
+/// Live state of the in-process streaming watcher for a project root.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema, Default)]
+pub struct WatcherState {
+    pub active: bool,
+    #[serde(default)]
+    pub indexing: bool,
+    #[serde(default)]
+    pub pending_events: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success_unix_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Process-lifetime call statistics for one MCP tool.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema, Default)]
+pub struct ToolCallStats {
+    pub tool: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    /// Text bytes returned to the client across all calls
+    pub bytes_returned: u64,
+}
+
+/// Index state of one member root in a multi-root (workspace) query.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct WorkspaceRootState {
+    /// Member name used to prefix result paths
+    pub name: String,
+    pub root: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_state: Option<IndexState>,
+    /// Why the member was left out of the results, if it was
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema, Default)]
 pub struct ToolMeta {
     #[serde(default)]
     pub index_state: Option<IndexState>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watcher: Option<WatcherState>,
+    /// Per-tool call statistics (only when `CONTEXT_FINDER_DEBUG_METRICS` is set)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_stats: Vec<ToolCallStats>,
+    /// Per-member index states when the query spanned several roots
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspace: Vec<WorkspaceRootState>,
+    /// This call ran an auto-reindex or waited for one already in flight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reindex_attempted: Option<bool>,
+    /// The auto-reindex budget ran out before the index was refreshed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reindex_deadline_hit: Option<bool>,
+    /// Language the code graph was built for (`rust`, `python`, `javascript`, `typescript`), or
+    /// the detected dominant language that has no graph builder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph_language: Option<String>,
+    /// `graph_language` came from the indexed chunks rather than the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected: Option<bool>,
 }
 
 #[must_use]
@@ -102,6 +252,8 @@ pub fn assess_staleness(
     index_exists: bool,
     index_corrupt: bool,
     index_watermark: Option<&Watermark>,
+    index_provenance: Option<&IndexProvenance>,
+    current_provenance: Option<&IndexProvenance>,
 ) -> StaleAssessment {
     let mut reasons = Vec::new();
 
@@ -160,6 +312,20 @@ pub fn assess_staleness(
         },
     }
 
+    if let (Some(index), Some(current)) = (index_provenance, current_provenance) {
+        if index.differs_from(current) {
+            reasons.push(StaleReason::ToolOrTemplateChanged);
+        }
+        if index.model_differs_from(current) {
+            reasons.push(StaleReason::ModelMismatch);
+        }
+        if let (Some(built_for), Some(active)) = (&index.config, &current.config) {
+            if built_for != active {
+                reasons.push(StaleReason::ConfigChanged);
+            }
+        }
+    }
+
     let stale = !reasons.is_empty();
     StaleAssessment { stale, reasons }
 }
@@ -188,49 +354,84 @@ mod tests {
 
     #[test]
     fn stale_when_index_missing() {
-        let out = assess_staleness(&git("abc", false), false, false, None);
+        let out = assess_staleness(&git("abc", false), false, false, None, None, None);
         assert_eq!(out.stale, true);
         assert_eq!(out.reasons, vec![StaleReason::IndexMissing]);
     }
 
     #[test]
     fn stale_when_index_corrupt() {
-        let out = assess_staleness(&git("abc", false), true, true, Some(&git("abc", false)));
+        let out = assess_staleness(
+            &git("abc", false),
+            true,
+            true,
+            Some(&git("abc", false)),
+            None,
+            None,
+        );
         assert_eq!(out.stale, true);
         assert_eq!(out.reasons, vec![StaleReason::IndexCorrupt]);
     }
 
     #[test]
     fn stale_when_watermark_missing() {
-        let out = assess_staleness(&git("abc", false), true, false, None);
+        let out = assess_staleness(&git("abc", false), true, false, None, None, None);
         assert_eq!(out.stale, true);
         assert_eq!(out.reasons, vec![StaleReason::WatermarkMissing]);
     }
 
     #[test]
     fn stale_when_git_head_mismatch() {
-        let out = assess_staleness(&git("bbb", false), true, false, Some(&git("aaa", false)));
+        let out = assess_staleness(
+            &git("bbb", false),
+            true,
+            false,
+            Some(&git("aaa", false)),
+            None,
+            None,
+        );
         assert_eq!(out.stale, true);
         assert_eq!(out.reasons, vec![StaleReason::GitHeadMismatch]);
     }
 
     #[test]
     fn stale_when_git_dirty_mismatch() {
-        let out = assess_staleness(&git("aaa", true), true, false, Some(&git("aaa", false)));
+        let out = assess_staleness(
+            &git("aaa", true),
+            true,
+            false,
+            Some(&git("aaa", false)),
+            None,
+            None,
+        );
         assert_eq!(out.stale, true);
         assert_eq!(out.reasons, vec![StaleReason::GitDirtyMismatch]);
     }
 
     #[test]
     fn stale_when_filesystem_changed() {
-        let out = assess_staleness(&fs(10, 123, 50), true, false, Some(&fs(10, 124, 50)));
+        let out = assess_staleness(
+            &fs(10, 123, 50),
+            true,
+            false,
+            Some(&fs(10, 124, 50)),
+            None,
+            None,
+        );
         assert_eq!(out.stale, true);
         assert_eq!(out.reasons, vec![StaleReason::FilesystemChanged]);
     }
 
     #[test]
     fn fresh_when_git_equal() {
-        let out = assess_staleness(&git("aaa", false), true, false, Some(&git("aaa", false)));
+        let out = assess_staleness(
+            &git("aaa", false),
+            true,
+            false,
+            Some(&git("aaa", false)),
+            None,
+            None,
+        );
         assert_eq!(out.stale, false);
         assert_eq!(out.reasons, Vec::<StaleReason>::new());
     }
@@ -238,8 +439,146 @@ mod tests {
     #[test]
     fn fresh_when_filesystem_equal() {
         let mark = fs(10, 123, 50);
-        let out = assess_staleness(&mark, true, false, Some(&mark));
+        let out = assess_staleness(&mark, true, false, Some(&mark), None, None);
         assert_eq!(out.stale, false);
         assert_eq!(out.reasons, Vec::<StaleReason>::new());
     }
+
+    fn provenance(tool_version: &str, doc_template_hash: u64) -> IndexProvenance {
+        IndexProvenance {
+            tool_version: tool_version.to_string(),
+            model_id: "bge-small".to_string(),
+            dimension: Some(384),
+            doc_template_hash,
+            graph_node_template_hash: 7,
+            chunker_fingerprint: 11,
+            config: None,
+        }
+    }
+
+    #[test]
+    fn stale_when_tool_or_template_changed() {
+        let mark = git("aaa", false);
+        let built = provenance("0.1.0", 1);
+        for current in [provenance("0.2.0", 1), provenance("0.1.0", 2)] {
+            let out = assess_staleness(
+                &mark,
+                true,
+                false,
+                Some(&mark),
+                Some(&built),
+                Some(&current),
+            );
+            assert_eq!(out.reasons, vec![StaleReason::ToolOrTemplateChanged]);
+        }
+
+        let current = IndexProvenance {
+            dimension: None,
+            ..built.clone()
+        };
+        let out = assess_staleness(
+            &mark,
+            true,
+            false,
+            Some(&mark),
+            Some(&built),
+            Some(&current),
+        );
+        assert_eq!(out.stale, false);
+        let out = assess_staleness(&mark, true, false, Some(&mark), None, Some(&current));
+        assert_eq!(out.stale, false);
+    }
+
+    #[test]
+    fn stale_when_profile_changed() {
+        let mark = git("aaa", false);
+        let with_profile = |profile: &str| IndexProvenance {
+            config: Some(IndexConfigMark {
+                profile: profile.to_string(),
+                templates_hash: 42,
+            }),
+            ..provenance("0.1.0", 1)
+        };
+        let built = with_profile("general");
+
+        let out = assess_staleness(
+            &mark,
+            true,
+            false,
+            Some(&mark),
+            Some(&built),
+            Some(&with_profile("quality")),
+        );
+        assert_eq!(out.stale, true);
+        assert_eq!(out.reasons, vec![StaleReason::ConfigChanged]);
+
+        let out = assess_staleness(
+            &mark,
+            true,
+            false,
+            Some(&mark),
+            Some(&built),
+            Some(&with_profile("general")),
+        );
+        assert_eq!(out.stale, false);
+
+        let unrecorded = provenance("0.1.0", 1);
+        let out = assess_staleness(
+            &mark,
+            true,
+            false,
+            Some(&mark),
+            Some(&unrecorded),
+            Some(&with_profile("quality")),
+        );
+        assert_eq!(out.stale, false);
+    }
+
+    #[test]
+    fn stale_when_model_changed() {
+        let mark = git("aaa", false);
+        let built = provenance("0.1.0", 1);
+        let current = IndexProvenance {
+            model_id: "embeddinggemma-300m".to_string(),
+            dimension: None,
+            ..built.clone()
+        };
+        let out = assess_staleness(
+            &mark,
+            true,
+            false,
+            Some(&mark),
+            Some(&built),
+            Some(&current),
+        );
+        assert_eq!(out.stale, true);
+        assert_eq!(out.reasons, vec![StaleReason::ModelMismatch]);
+
+        let resized = IndexProvenance {
+            dimension: Some(768),
+            ..built.clone()
+        };
+        let out = assess_staleness(
+            &mark,
+            true,
+            false,
+            Some(&mark),
+            Some(&built),
+            Some(&resized),
+        );
+        assert_eq!(out.reasons, vec![StaleReason::ModelMismatch]);
+    }
+
+    #[test]
+    fn provenance_describe_is_human_readable() {
+        let built = provenance("0.1.0", 1);
+        assert_eq!(
+            built.describe(Some(1_792_108_800_000)),
+            "index built by v0.1.0 with model bge-small (384d) on 2026-10-16"
+        );
+        assert_eq!(
+            built.describe(None),
+            "index built by v0.1.0 with model bge-small (384d)"
+        );
+    }
 }
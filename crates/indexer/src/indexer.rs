@@ -1,16 +1,21 @@
 use crate::error::{IndexerError, Result};
+use crate::progress::{IndexProgressCallback, ProgressTracker};
 use crate::scanner::FileScanner;
-use crate::stats::IndexStats;
-use context_code_chunker::{Chunker, ChunkerConfig};
+use crate::stats::{IndexPhase, IndexPlan, IndexStats, ModelStats, MultiModelIndexStats};
+use crate::{IndexConfigMark, IndexProvenance};
+use context_code_chunker::{ChunkSizeOverride, Chunker, ChunkerConfig};
 use context_vector_store::current_model_id;
 use context_vector_store::EmbeddingTemplates;
 use context_vector_store::VectorStore;
-use context_vector_store::{corpus_path_for_project_root, ChunkCorpus};
-use std::collections::{HashMap, HashSet};
+use context_vector_store::{corpus_path_for_project_root, ChunkCorpus, ChunkFreshness};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant, SystemTime};
 
-use crate::{compute_project_watermark, write_index_watermark};
+use crate::watermark_io::unix_now_ms;
+use crate::{
+    compute_project_watermark, read_index_watermark, write_index_watermark, PersistedIndexWatermark,
+};
 
 #[derive(Clone, Debug)]
 pub struct ModelIndexSpec {
@@ -34,6 +39,8 @@ pub struct ProjectIndexer {
     model_id: String,
     chunker: Chunker,
     templates: Option<EmbeddingTemplates>,
+    profile: Option<String>,
+    progress: Option<IndexProgressCallback>,
 }
 
 /// Multi-model project indexer that scans/chunks files once and embeds the resulting chunks into
@@ -41,6 +48,8 @@ pub struct ProjectIndexer {
 pub struct MultiModelProjectIndexer {
     root: PathBuf,
     chunker: Chunker,
+    profile: Option<String>,
+    progress: Option<IndexProgressCallback>,
 }
 
 impl ProjectIndexer {
@@ -99,7 +108,7 @@ impl ProjectIndexer {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        let chunker = Chunker::new(ChunkerConfig::for_embeddings());
+        let chunker = Chunker::new(load_chunker_config(&root).await);
 
         Ok(Self {
             root,
@@ -107,9 +116,36 @@ impl ProjectIndexer {
             model_id,
             chunker,
             templates,
+            profile: None,
+            progress: None,
         })
     }
 
+    /// Report progress (phase changes, files chunked, chunks embedded) while indexing.
+    #[must_use]
+    pub fn with_progress(mut self, callback: IndexProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    /// Record `profile` in the index watermark, so switching profiles marks the index stale.
+    #[must_use]
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Chunk with `config` instead of the project's configured chunking.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` fails [`ChunkerConfig::validate`].
+    #[must_use]
+    pub fn with_chunker_config(mut self, config: ChunkerConfig) -> Self {
+        self.chunker = Chunker::new(config);
+        self
+    }
+
     /// Index the project (with incremental support)
     pub async fn index(&self) -> Result<IndexStats> {
         self.index_with_mode(false, None).await
@@ -135,6 +171,54 @@ impl ProjectIndexer {
             .await
     }
 
+    /// Dry run: scan, filter changed files and chunk them without embedding or saving.
+    pub async fn plan(&self) -> Result<IndexPlan> {
+        self.plan_with_mode(false).await
+    }
+
+    /// Dry run of a full reindex (every scanned file is chunked).
+    pub async fn plan_full(&self) -> Result<IndexPlan> {
+        self.plan_with_mode(true).await
+    }
+
+    async fn plan_with_mode(&self, force_full: bool) -> Result<IndexPlan> {
+        let scanner = FileScanner::new(&self.root);
+        let files = scanner.scan();
+
+        let corpus_path = corpus_path_for_project_root(&self.root);
+        let incremental = !force_full && corpus_path.exists() && self.store_path.exists();
+        let files_to_process = if incremental {
+            let mtimes = self.load_mtimes().await.unwrap_or_default();
+            self.filter_changed_files(&files, &mtimes).await?
+        } else {
+            files.clone()
+        };
+
+        let mut plan = IndexPlan {
+            total_files: files.len(),
+            unchanged_files: files.len() - files_to_process.len(),
+            incremental,
+            ..IndexPlan::default()
+        };
+        let mut stats = IndexStats::new();
+        let mut tracker = ProgressTracker::new(None);
+        for result in self
+            .process_files_parallel(&files_to_process, None, &mut tracker)
+            .await?
+        {
+            match result {
+                Ok((_, chunks, language, lines)) => stats.add_file(&language, lines, chunks.len()),
+                Err(e) => stats.add_error(e),
+            }
+        }
+        plan.files = stats.files;
+        plan.estimated_chunks = stats.chunks;
+        plan.total_lines = stats.total_lines;
+        plan.languages = stats.languages;
+        plan.errors = stats.errors;
+        Ok(plan)
+    }
+
     /// Index with specified mode
     #[allow(clippy::cognitive_complexity)]
     #[allow(clippy::too_many_lines)]
@@ -145,13 +229,18 @@ impl ProjectIndexer {
     ) -> Result<IndexStats> {
         let start = Instant::now();
         let mut stats = IndexStats::new();
+        let mut tracker = ProgressTracker::new(self.progress.clone());
+        tracker.enter(IndexPhase::Scan, &mut stats);
 
         log::info!("Indexing project at {}", self.root.display());
         check_budget(deadline)?;
+        let force_full =
+            force_full || chunking_changed(&self.store_path, self.chunker.config()).await;
 
         // 1. Scan for files
         let scanner = FileScanner::new(&self.root);
         let files = scanner.scan();
+        tracker.update(|p| p.files_scanned = files.len());
         check_budget(deadline)?;
         let live_files: HashSet<String> = files.iter().map(|p| self.normalize_path(p)).collect();
 
@@ -238,6 +327,15 @@ impl ProjectIndexer {
             files.clone()
         };
 
+        // A full rebuild drops files that are gone without purging them, so the previous run's
+        // mtimes also count towards what this run deleted.
+        let mut deleted_files: BTreeSet<String> = match &existing_mtimes {
+            Some(mtimes) => deleted_since(mtimes.keys().cloned(), &live_files),
+            None => deleted_since(
+                self.load_mtimes().await.unwrap_or_default().into_keys(),
+                &live_files,
+            ),
+        };
         if existing_mtimes.is_some() {
             log::info!(
                 "Incremental: processing {} of {} files",
@@ -246,17 +344,20 @@ impl ProjectIndexer {
             );
 
             // Purge chunks that belong to files no longer present in the project (deleted/renamed).
+            deleted_files.extend(store.missing_files(&live_files));
             let removed = store.purge_missing_files(&live_files);
             if removed > 0 {
                 log::info!("Purged {removed} stale chunks from deleted files");
             }
 
+            deleted_files.extend(corpus.missing_files(&live_files));
             let removed = corpus.purge_missing_files(&live_files);
             if removed > 0 {
                 log::info!("Purged {removed} missing files from chunk corpus");
                 corpus_dirty = true;
             }
         }
+        stats.deleted_files = deleted_files.into_iter().collect();
 
         // 4. Process files (parallel for better performance)
         let mut current_mtimes = HashMap::new();
@@ -291,26 +392,52 @@ impl ProjectIndexer {
         };
 
         if !corpus_targets.is_empty() {
+            tracker.update(|p| p.files_total = corpus_targets.len());
+            tracker.enter(IndexPhase::Chunk, &mut stats);
             let results = self
-                .process_files_parallel(&corpus_targets, deadline)
+                .process_files_parallel(&corpus_targets, deadline, &mut tracker)
                 .await?;
 
+            let chunks_total = results
+                .iter()
+                .filter_map(|result| result.as_ref().ok())
+                .filter(|(relative_path, ..)| changed_rels.contains(relative_path))
+                .map(|(_, chunks, ..)| chunks.len())
+                .sum();
+            tracker.update(|p| p.chunks_total = chunks_total);
+            tracker.enter(IndexPhase::Embed, &mut stats);
+
             // Aggregate results
+            let indexed_at_ms = unix_now_ms();
             for result in results {
                 check_budget(deadline)?;
                 match result {
                     Ok((relative_path, chunks, language, lines)) => {
-                        stats.add_file(&language, lines);
-                        stats.add_chunks(chunks.len());
+                        stats.add_file(&language, lines, chunks.len());
 
                         corpus.set_file_chunks(relative_path.clone(), chunks.clone());
+                        if let Some(&file_mtime_ms) = current_mtimes.get(&relative_path) {
+                            corpus.set_file_freshness(
+                                relative_path.clone(),
+                                ChunkFreshness {
+                                    indexed_at_ms,
+                                    file_mtime_ms,
+                                },
+                            );
+                        }
                         corpus_dirty = true;
 
                         if changed_rels.contains(&relative_path) {
+                            stats.changed_files.push(relative_path.clone());
+                            let processed = chunks.len();
                             if existing_mtimes.is_some() {
-                                store.remove_chunks_for_file(&relative_path);
+                                let replaced =
+                                    store.replace_file_chunks(&relative_path, chunks).await?;
+                                stats.chunks_reused += replaced.reused;
+                            } else {
+                                store.add_chunks(chunks).await?;
                             }
-                            store.add_chunks(chunks).await?;
+                            tracker.update(|p| p.chunks_embedded += processed);
                         }
                     }
                     Err(e) => {
@@ -323,13 +450,25 @@ impl ProjectIndexer {
 
         // 5. Save store and mtimes
         check_budget(deadline)?;
+        tracker.enter(IndexPhase::Save, &mut stats);
+        stats.docs_token_truncated += store.docs_token_truncated();
         if corpus_dirty {
             corpus.save(&corpus_path).await?;
         }
         store.save().await?;
         self.save_mtimes(&current_mtimes).await?;
+        stats.changed_files.sort();
         let watermark = compute_project_watermark(&self.root).await?;
-        write_index_watermark(&self.store_path, watermark).await?;
+        let templates = self.templates.clone().unwrap_or_default();
+        let provenance = index_provenance(
+            &self.model_id,
+            Some(store.dimension()),
+            &templates,
+            self.chunker.config(),
+            self.profile.as_deref(),
+        );
+        write_index_watermark(&self.store_path, watermark, provenance).await?;
+        tracker.finish(&mut stats);
 
         #[allow(clippy::cast_possible_truncation)]
         {
@@ -414,6 +553,7 @@ impl ProjectIndexer {
         &self,
         files: &[PathBuf],
         deadline: Option<Instant>,
+        tracker: &mut ProgressTracker,
     ) -> Result<
         Vec<
             std::result::Result<
@@ -471,6 +611,7 @@ impl ProjectIndexer {
                     Ok(Err(e)) => aggregated.push(Err(e)),
                     Err(e) => aggregated.push(Err(format!("Task panicked: {e}"))),
                 }
+                tracker.update(|p| p.files_processed += 1);
             }
         }
 
@@ -513,8 +654,7 @@ impl ProjectIndexer {
 
         let language = chunks[0].metadata.language.as_deref().unwrap_or("unknown");
 
-        stats.add_file(language, lines);
-        stats.add_chunks(chunks.len());
+        stats.add_file(language, lines, chunks.len());
 
         // Add to vector store (batch embedding happens here)
         store.add_chunks(chunks).await?;
@@ -544,6 +684,158 @@ impl ProjectIndexer {
     }
 }
 
+/// Embedding chunker preset adjusted by the `chunking` section of `.context-finder/config.json`
+/// (see [`chunker_config_from_settings`]); a missing file keeps the preset, and an invalid section
+/// is ignored with a warning naming the offending key.
+pub async fn load_chunker_config(root: &Path) -> ChunkerConfig {
+    let preset = ChunkerConfig::for_embeddings();
+    let path = root.join(".context-finder").join("config.json");
+    let Ok(bytes) = tokio::fs::read(&path).await else {
+        return preset;
+    };
+    let value: serde_json::Value = match serde_json::from_slice(&bytes) {
+        Ok(value) => value,
+        Err(err) => {
+            log::debug!("Ignoring unparsable config {}: {err}", path.display());
+            return preset;
+        }
+    };
+    match chunker_config_from_settings(value.get("chunking")) {
+        Ok(config) => config,
+        Err(err) => {
+            log::warn!("Ignoring chunking settings in {}: {err}", path.display());
+            preset
+        }
+    }
+}
+
+/// Embedding chunker preset with the size limits of a `chunking` section applied:
+/// `max_chunk_chars`, `overlap_chars`, `min_chunk_chars`, `max_lines`, `overlap_lines` and
+/// `min_chunk_lines`, plus the same keys per language under `languages` (keyed by
+/// [`Language::as_str`](context_code_chunker::Language::as_str)). Errors name the full key path.
+fn chunker_config_from_settings(
+    section: Option<&serde_json::Value>,
+) -> std::result::Result<ChunkerConfig, String> {
+    let preset = ChunkerConfig::for_embeddings();
+    let Some(section) = section else {
+        return Ok(preset);
+    };
+    let base = chunk_size_settings(section, "chunking")?;
+    let mut config = ChunkerConfig {
+        max_chunk_chars: base.max_chunk_chars.filter(|chars| *chars > 0),
+        overlap_chars: base.overlap_chars.unwrap_or(0),
+        min_chunk_chars: base.min_chunk_chars.unwrap_or(0),
+        max_lines: base.max_lines.filter(|lines| *lines > 0),
+        overlap_lines: base.overlap_lines.unwrap_or(0),
+        min_chunk_lines: base.min_chunk_lines.unwrap_or(0),
+        ..preset
+    };
+    if let Some(languages) = section.get("languages") {
+        let languages = languages
+            .as_object()
+            .ok_or("chunking.languages must be an object")?;
+        for (language, limits) in languages {
+            let limits = chunk_size_settings(limits, &format!("chunking.languages.{language}"))?;
+            config.languages.insert(language.clone(), limits);
+        }
+    }
+    config.validate().map_err(|err| format!("chunking.{err}"))?;
+    Ok(config)
+}
+
+fn chunk_size_settings(
+    value: &serde_json::Value,
+    context: &str,
+) -> std::result::Result<ChunkSizeOverride, String> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| format!("{context} must be an object"))?;
+    let mut limits = ChunkSizeOverride::default();
+    for (key, value) in object {
+        let slot = match key.as_str() {
+            "max_chunk_chars" => &mut limits.max_chunk_chars,
+            "overlap_chars" => &mut limits.overlap_chars,
+            "min_chunk_chars" => &mut limits.min_chunk_chars,
+            "max_lines" => &mut limits.max_lines,
+            "overlap_lines" => &mut limits.overlap_lines,
+            "min_chunk_lines" => &mut limits.min_chunk_lines,
+            "languages" if context == "chunking" => continue,
+            _ => return Err(format!("{context}.{key} is not a chunking setting")),
+        };
+        let setting = value
+            .as_u64()
+            .and_then(|v| usize::try_from(v).ok())
+            .ok_or_else(|| format!("{context}.{key} must be a non-negative integer"))?;
+        *slot = Some(setting);
+    }
+    Ok(limits)
+}
+
+/// Provenance of the index this process would build for `root` with `model_id` and the
+/// `templates` of `profile`.
+pub async fn current_index_provenance(
+    root: &Path,
+    model_id: &str,
+    profile: &str,
+    templates: &EmbeddingTemplates,
+) -> IndexProvenance {
+    index_provenance(
+        model_id,
+        None,
+        templates,
+        &load_chunker_config(root).await,
+        Some(profile),
+    )
+}
+
+fn index_provenance(
+    model_id: &str,
+    dimension: Option<usize>,
+    templates: &EmbeddingTemplates,
+    chunker: &ChunkerConfig,
+    profile: Option<&str>,
+) -> IndexProvenance {
+    IndexProvenance {
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        model_id: model_id.to_string(),
+        dimension,
+        doc_template_hash: templates.doc_template_hash(),
+        graph_node_template_hash: templates.graph_node_template_hash(),
+        chunker_fingerprint: chunker_fingerprint(chunker),
+        config: profile.map(|profile| IndexConfigMark {
+            profile: profile.to_string(),
+            templates_hash: fnv1a64(&serde_json::to_vec(templates).unwrap_or_default()),
+        }),
+    }
+}
+
+fn chunker_fingerprint(config: &ChunkerConfig) -> u64 {
+    fnv1a64(&serde_json::to_vec(config).unwrap_or_default())
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 14_695_981_039_346_656_037;
+    const PRIME: u64 = 1_099_511_628_211;
+    let mut hash = OFFSET;
+    for b in bytes {
+        hash ^= u64::from(*b);
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash
+}
+
+/// Whether the index at `store_path` was chunked with a different configuration. Unchanged
+/// files keep their old chunks in an incremental run, so such an index needs a full rebuild.
+async fn chunking_changed(store_path: &Path, config: &ChunkerConfig) -> bool {
+    matches!(
+        read_index_watermark(store_path).await,
+        Ok(Some(PersistedIndexWatermark {
+            provenance: Some(provenance),
+            ..
+        })) if provenance.chunker_fingerprint != chunker_fingerprint(config)
+    )
+}
+
 fn model_id_dir_name(model_id: &str) -> String {
     model_id
         .chars()
@@ -554,6 +846,17 @@ fn model_id_dir_name(model_id: &str) -> String {
         .collect()
 }
 
+/// Files of a previous index (`previous`) that are no longer in `live_files`, in path order.
+fn deleted_since(
+    previous: impl IntoIterator<Item = String>,
+    live_files: &HashSet<String>,
+) -> BTreeSet<String> {
+    previous
+        .into_iter()
+        .filter(|file| !live_files.contains(file))
+        .collect()
+}
+
 const fn normalize_mtime_ms(value: u64) -> u64 {
     // Backward-compatible upgrade: older `mtimes.json` persisted seconds since UNIX epoch.
     // Milliseconds since epoch are ~1e12 in 2025; seconds are ~1e9.
@@ -565,7 +868,6 @@ const fn normalize_mtime_ms(value: u64) -> u64 {
 }
 
 impl MultiModelProjectIndexer {
-    #[allow(clippy::unused_async)]
     pub async fn new(root: impl AsRef<Path>) -> Result<Self> {
         let root = root.as_ref().to_path_buf();
 
@@ -576,12 +878,40 @@ impl MultiModelProjectIndexer {
             )));
         }
 
+        let chunker = Chunker::new(load_chunker_config(&root).await);
         Ok(Self {
             root,
-            chunker: Chunker::new(ChunkerConfig::for_embeddings()),
+            chunker,
+            profile: None,
+            progress: None,
         })
     }
 
+    /// Report progress (phase changes, files chunked, chunks embedded) while indexing.
+    #[must_use]
+    pub fn with_progress(mut self, callback: IndexProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    /// Record `profile` in the index watermark, so switching profiles marks the index stale.
+    #[must_use]
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Chunk with `config` instead of the project's configured chunking.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` fails [`ChunkerConfig::validate`].
+    #[must_use]
+    pub fn with_chunker_config(mut self, config: ChunkerConfig) -> Self {
+        self.chunker = Chunker::new(config);
+        self
+    }
+
     #[must_use]
     pub fn root(&self) -> &Path {
         &self.root
@@ -592,14 +922,20 @@ impl MultiModelProjectIndexer {
     /// Design goals:
     /// - Scan + chunk once (union of changed files across models),
     /// - Keep incremental correctness per model (per-model mtimes + purge),
-    /// - Avoid process-global env mutation (explicit `model_id` wiring).
+    /// - Avoid process-global env mutation (explicit `model_id` wiring),
+    /// - Embed each document once per backend: specs that alias the same model share vectors
+    ///   through the embedding cache (keyed by backend id + document hash),
+    /// - Isolate models: one model failing (unknown id, invalid templates, a failed save) is
+    ///   reported in [`MultiModelIndexStats::per_model`] without aborting the others.
+    ///
+    /// Errors only for failures shared by every model (scan, corpus save, watermark).
     #[allow(clippy::cognitive_complexity)]
     #[allow(clippy::too_many_lines)]
     pub async fn index_models(
         &self,
         models: &[ModelIndexSpec],
         force_full: bool,
-    ) -> Result<IndexStats> {
+    ) -> Result<MultiModelIndexStats> {
         struct ModelPlan {
             model_id: String,
             store_path: PathBuf,
@@ -621,10 +957,25 @@ impl MultiModelProjectIndexer {
             self.root.display(),
             models.len()
         );
+        let mut stats = IndexStats::new();
+        let mut tracker = ProgressTracker::new(self.progress.clone());
+        tracker.enter(IndexPhase::Scan, &mut stats);
+
+        let mut force_full = force_full;
+        for spec in models {
+            let store_path = self
+                .root
+                .join(".context-finder")
+                .join("indexes")
+                .join(model_id_dir_name(spec.model_id.trim()))
+                .join("index.json");
+            force_full |= chunking_changed(&store_path, self.chunker.config()).await;
+        }
 
         // 1. Scan for files once.
         let scanner = FileScanner::new(&self.root);
         let files = scanner.scan();
+        tracker.update(|p| p.files_scanned = files.len());
 
         let live_files: HashSet<String> = files.iter().map(|p| self.normalize_path(p)).collect();
 
@@ -670,71 +1021,93 @@ impl MultiModelProjectIndexer {
             abs_by_rel.insert(self.normalize_path(file_path), file_path.clone());
         }
 
+        let mut per_model: HashMap<String, std::result::Result<ModelStats, String>> =
+            HashMap::new();
+        let mut deleted_files: BTreeSet<String> = BTreeSet::new();
         for spec in models {
             let model_id = spec.model_id.trim().to_string();
-            if model_id.is_empty() {
-                return Err(IndexerError::Other(
-                    "model_id must not be empty".to_string(),
-                ));
-            }
-
-            let model_dir = model_id_dir_name(&model_id);
-            let store_path = self
-                .root
-                .join(".context-finder")
-                .join("indexes")
-                .join(model_dir)
-                .join("index.json");
-            if let Some(parent) = store_path.parent() {
-                tokio::fs::create_dir_all(parent).await?;
-            }
-            let mtimes_path = store_path
-                .parent()
-                .expect("index.json has a parent dir")
-                .join("mtimes.json");
-
-            let incremental = !force_full && !corpus_full_rebuild && store_path.exists();
-            let existing_mtimes = if incremental && mtimes_path.exists() {
-                let json = tokio::fs::read_to_string(&mtimes_path).await?;
-                let mut loaded = serde_json::from_str::<HashMap<String, u64>>(&json)?;
-                for value in loaded.values_mut() {
-                    *value = normalize_mtime_ms(*value);
+            let planned: Result<ModelPlan> = async {
+                if model_id.is_empty() {
+                    return Err(IndexerError::Other(
+                        "model_id must not be empty".to_string(),
+                    ));
                 }
-                loaded
-            } else {
-                HashMap::new()
-            };
 
-            let mut changed_files = HashSet::new();
-            if force_full || corpus_full_rebuild || !store_path.exists() {
-                // Fresh index: process everything.
-                for rel in current_mtimes.keys() {
-                    changed_files.insert(rel.clone());
+                let model_dir = model_id_dir_name(&model_id);
+                let store_path = self
+                    .root
+                    .join(".context-finder")
+                    .join("indexes")
+                    .join(model_dir)
+                    .join("index.json");
+                if let Some(parent) = store_path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
                 }
-            } else {
-                for (rel, mtime) in &current_mtimes {
-                    let is_changed = existing_mtimes
-                        .get(rel)
-                        .is_none_or(|old| *mtime > normalize_mtime_ms(*old));
-                    if is_changed {
+                let mtimes_path = store_path
+                    .parent()
+                    .expect("index.json has a parent dir")
+                    .join("mtimes.json");
+
+                let incremental = !force_full && !corpus_full_rebuild && store_path.exists();
+                let previous_mtimes = if mtimes_path.exists() {
+                    let json = tokio::fs::read_to_string(&mtimes_path).await?;
+                    let mut loaded = serde_json::from_str::<HashMap<String, u64>>(&json)?;
+                    for value in loaded.values_mut() {
+                        *value = normalize_mtime_ms(*value);
+                    }
+                    loaded
+                } else {
+                    HashMap::new()
+                };
+                // Files this model indexed last time are deleted now even on a full rebuild.
+                deleted_files.extend(deleted_since(previous_mtimes.keys().cloned(), &live_files));
+                let existing_mtimes = if incremental {
+                    previous_mtimes
+                } else {
+                    HashMap::new()
+                };
+
+                let mut changed_files = HashSet::new();
+                if force_full || corpus_full_rebuild || !store_path.exists() {
+                    // Fresh index: process everything.
+                    for rel in current_mtimes.keys() {
                         changed_files.insert(rel.clone());
                     }
+                } else {
+                    for (rel, mtime) in &current_mtimes {
+                        let is_changed = existing_mtimes
+                            .get(rel)
+                            .is_none_or(|old| *mtime > normalize_mtime_ms(*old));
+                        if is_changed {
+                            changed_files.insert(rel.clone());
+                        }
+                    }
                 }
-            }
 
-            union_changed.extend(changed_files.iter().cloned());
-            plans.push(ModelPlan {
-                model_id,
-                store_path,
-                mtimes_path,
-                templates: spec.templates.clone(),
-                incremental,
-                changed_files,
-            });
+                Ok(ModelPlan {
+                    model_id: model_id.clone(),
+                    store_path,
+                    mtimes_path,
+                    templates: spec.templates.clone(),
+                    incremental,
+                    changed_files,
+                })
+            }
+            .await;
+            match planned {
+                Ok(plan) => {
+                    union_changed.extend(plan.changed_files.iter().cloned());
+                    plans.push(plan);
+                }
+                Err(err) => {
+                    log::warn!("Skipping model {model_id:?}: {err}");
+                    stats.add_error(format!("model {model_id}: {err}"));
+                    per_model.insert(model_id, Err(err.to_string()));
+                }
+            }
         }
 
         // 4. Chunk the union set once.
-        let mut stats = IndexStats::new();
         let mut union_paths: Vec<PathBuf> = if corpus_full_rebuild {
             files.clone()
         } else {
@@ -745,10 +1118,13 @@ impl MultiModelProjectIndexer {
         };
         union_paths.sort();
 
+        tracker.update(|p| p.files_total = union_paths.len());
+        tracker.enter(IndexPhase::Chunk, &mut stats);
         let processed = if union_paths.is_empty() {
             Vec::new()
         } else {
-            self.process_files_parallel(&union_paths).await?
+            self.process_files_parallel(&union_paths, &mut tracker)
+                .await?
         };
 
         let mut processed_by_rel: HashMap<String, Vec<context_code_chunker::CodeChunk>> =
@@ -758,8 +1134,7 @@ impl MultiModelProjectIndexer {
         for result in processed {
             match result {
                 Ok((relative_path, chunks, language, lines)) => {
-                    stats.add_file(&language, lines);
-                    stats.add_chunks(chunks.len());
+                    stats.add_file(&language, lines, chunks.len());
                     processed_by_rel.insert(relative_path, chunks);
                 }
                 Err(err) => {
@@ -774,6 +1149,7 @@ impl MultiModelProjectIndexer {
         }
 
         if !corpus_full_rebuild {
+            deleted_files.extend(corpus.missing_files(&live_files));
             let removed = corpus.purge_missing_files(&live_files);
             if removed > 0 {
                 log::info!("Purged {removed} missing files from chunk corpus");
@@ -781,87 +1157,146 @@ impl MultiModelProjectIndexer {
             }
         }
 
+        let indexed_at_ms = unix_now_ms();
         for (relative_path, chunks) in &processed_by_rel {
             if processed_errs.contains_key(relative_path) {
                 continue;
             }
             corpus.set_file_chunks(relative_path.clone(), chunks.clone());
+            if let Some(&file_mtime_ms) = current_mtimes.get(relative_path) {
+                corpus.set_file_freshness(
+                    relative_path.clone(),
+                    ChunkFreshness {
+                        indexed_at_ms,
+                        file_mtime_ms,
+                    },
+                );
+            }
             corpus_dirty = true;
+            stats.changed_files.push(relative_path.clone());
         }
+        stats.changed_files.sort();
 
         if corpus_dirty {
+            tracker.enter(IndexPhase::Save, &mut stats);
             corpus.save(&corpus_path).await?;
         }
 
         // 5. Apply the chunk deltas per model (embed + update store).
+        let chunks_total = plans
+            .iter()
+            .flat_map(|plan| plan.changed_files.iter())
+            .filter(|rel| !processed_errs.contains_key(*rel))
+            .filter_map(|rel| processed_by_rel.get(rel))
+            .map(Vec::len)
+            .sum();
+        tracker.update(|p| p.chunks_total = chunks_total);
         for plan in &plans {
-            let mut store = if plan.incremental && plan.store_path.exists() {
-                let loaded = VectorStore::load_with_templates_for_model(
-                    &plan.store_path,
-                    plan.templates.clone(),
-                    &plan.model_id,
-                )
-                .await;
-                match loaded {
-                    Ok(store) => store,
-                    Err(e) => {
-                        log::warn!(
-                            "Failed to load existing index {}: {e}, starting fresh",
-                            plan.store_path.display()
-                        );
-                        VectorStore::new_with_templates_for_model(
-                            &plan.store_path,
-                            &plan.model_id,
-                            plan.templates.clone(),
-                        )?
+            let indexed: Result<ModelStats> = async {
+                tracker.enter(IndexPhase::Embed, &mut stats);
+                let mut model_stats = ModelStats::default();
+                let mut store = if plan.incremental && plan.store_path.exists() {
+                    let loaded = VectorStore::load_with_templates_for_model(
+                        &plan.store_path,
+                        plan.templates.clone(),
+                        &plan.model_id,
+                    )
+                    .await;
+                    match loaded {
+                        Ok(store) => store,
+                        Err(e) => {
+                            log::warn!(
+                                "Failed to load existing index {}: {e}, starting fresh",
+                                plan.store_path.display()
+                            );
+                            VectorStore::new_with_templates_for_model(
+                                &plan.store_path,
+                                &plan.model_id,
+                                plan.templates.clone(),
+                            )?
+                        }
                     }
-                }
-            } else {
-                VectorStore::new_with_templates_for_model(
-                    &plan.store_path,
-                    &plan.model_id,
-                    plan.templates.clone(),
-                )?
-            };
-
-            if plan.incremental {
-                let removed = store.purge_missing_files(&live_files);
-                if removed > 0 {
-                    log::info!("Purged {removed} stale chunks for model {}", plan.model_id);
-                }
-            }
-
-            for rel in &plan.changed_files {
-                if processed_errs.contains_key(rel) {
-                    continue;
-                }
-                let Some(chunks) = processed_by_rel.get(rel) else {
-                    continue;
+                } else {
+                    VectorStore::new_with_templates_for_model(
+                        &plan.store_path,
+                        &plan.model_id,
+                        plan.templates.clone(),
+                    )?
                 };
 
                 if plan.incremental {
-                    store.remove_chunks_for_file(rel);
+                    deleted_files.extend(store.missing_files(&live_files));
+                    let removed = store.purge_missing_files(&live_files);
+                    if removed > 0 {
+                        log::info!("Purged {removed} stale chunks for model {}", plan.model_id);
+                    }
                 }
 
-                store.add_chunks(chunks.clone()).await?;
-            }
+                for rel in &plan.changed_files {
+                    if processed_errs.contains_key(rel) {
+                        continue;
+                    }
+                    let Some(chunks) = processed_by_rel.get(rel) else {
+                        continue;
+                    };
 
-            store.save().await?;
+                    if plan.incremental {
+                        let replaced = store.replace_file_chunks(rel, chunks.clone()).await?;
+                        model_stats.chunks_reused += replaced.reused;
+                    } else {
+                        store.add_chunks(chunks.clone()).await?;
+                    }
+                    model_stats.chunks_embedded += chunks.len();
+                    tracker.update(|p| p.chunks_embedded += chunks.len());
+                }
 
-            // Persist mtimes for this model so incremental correctness is per-model (avoids
-            // cross-model skew if users index subsets of experts).
-            let json = serde_json::to_string_pretty(&current_mtimes)?;
-            let tmp = plan.mtimes_path.with_extension("json.tmp");
-            tokio::fs::write(&tmp, json).await?;
-            tokio::fs::rename(&tmp, &plan.mtimes_path).await?;
+                tracker.enter(IndexPhase::Save, &mut stats);
+                model_stats.docs_token_truncated = store.docs_token_truncated();
+                model_stats.docs_embedded = store.docs_embedded();
+                model_stats.dimension = store.dimension();
+                store.save().await?;
+
+                // Persist mtimes for this model so incremental correctness is per-model (avoids
+                // cross-model skew if users index subsets of experts).
+                let json = serde_json::to_string_pretty(&current_mtimes)?;
+                let tmp = plan.mtimes_path.with_extension("json.tmp");
+                tokio::fs::write(&tmp, json).await?;
+                tokio::fs::rename(&tmp, &plan.mtimes_path).await?;
+                Ok(model_stats)
+            }
+            .await;
+            match indexed {
+                Ok(model_stats) => {
+                    stats.chunks_reused += model_stats.chunks_reused;
+                    stats.docs_token_truncated += model_stats.docs_token_truncated;
+                    per_model.insert(plan.model_id.clone(), Ok(model_stats));
+                }
+                Err(err) => {
+                    log::warn!("Indexing model {} failed: {err}", plan.model_id);
+                    stats.add_error(format!("model {}: {err}", plan.model_id));
+                    per_model.insert(plan.model_id.clone(), Err(err.to_string()));
+                }
+            }
         }
+        stats.deleted_files = deleted_files.into_iter().collect();
 
         // Capture a project watermark at the end and persist it for each model store.
         // This is a lightweight "freshness contract" used by the read path to detect stale indices.
         let watermark = compute_project_watermark(&self.root).await?;
         for plan in &plans {
-            write_index_watermark(&plan.store_path, watermark.clone()).await?;
+            let Some(Ok(model_stats)) = per_model.get(&plan.model_id) else {
+                continue;
+            };
+            let provenance = index_provenance(
+                &plan.model_id,
+                Some(model_stats.dimension),
+                &plan.templates,
+                self.chunker.config(),
+                self.profile.as_deref(),
+            );
+            write_index_watermark(&plan.store_path, watermark.clone(), provenance).await?;
         }
+        tracker.finish(&mut stats);
 
         #[allow(clippy::cast_possible_truncation)]
         {
@@ -871,7 +1306,7 @@ impl MultiModelProjectIndexer {
             }
         }
 
-        Ok(stats)
+        Ok(MultiModelIndexStats { stats, per_model })
     }
 
     fn normalize_path(&self, path: &Path) -> String {
@@ -886,6 +1321,7 @@ impl MultiModelProjectIndexer {
     async fn process_files_parallel(
         &self,
         files: &[PathBuf],
+        tracker: &mut ProgressTracker,
     ) -> Result<
         Vec<
             std::result::Result<
@@ -942,6 +1378,7 @@ impl MultiModelProjectIndexer {
                     Ok(Err(e)) => aggregated.push(Err(e)),
                     Err(e) => aggregated.push(Err(format!("Task panicked: {e}"))),
                 }
+                tracker.update(|p| p.files_processed += 1);
             }
         }
 
@@ -963,6 +1400,95 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn chunking_settings_come_from_project_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let preset = load_chunker_config(temp_dir.path()).await;
+        assert_eq!(preset.max_chunk_chars, None);
+
+        let config_dir = temp_dir.path().join(".context-finder");
+        tokio::fs::create_dir_all(&config_dir).await.unwrap();
+        let config_path = config_dir.join("config.json");
+        tokio::fs::write(
+            &config_path,
+            r#"{"chunking": {"max_chunk_chars": 1200, "overlap_chars": 200, "min_chunk_chars": 80}}"#,
+        )
+        .await
+        .unwrap();
+        let config = load_chunker_config(temp_dir.path()).await;
+        assert_eq!(config.max_chunk_chars, Some(1200));
+        assert_eq!(config.overlap_chars, 200);
+        assert_eq!(config.min_chunk_chars, 80);
+        assert_eq!(config.max_chunk_tokens, preset.max_chunk_tokens);
+
+        // An overlap that would stall splitting falls back to the preset.
+        tokio::fs::write(
+            &config_path,
+            r#"{"chunking": {"max_chunk_chars": 100, "overlap_chars": 100}}"#,
+        )
+        .await
+        .unwrap();
+        let config = load_chunker_config(temp_dir.path()).await;
+        assert_eq!(config.max_chunk_chars, None);
+        assert_eq!(config.overlap_chars, 0);
+    }
+
+    #[test]
+    fn chunking_settings_accept_line_limits_per_language() {
+        let section = serde_json::json!({
+            "max_lines": 60,
+            "overlap_lines": 5,
+            "languages": { "sql": { "max_lines": 200, "min_chunk_lines": 4 } }
+        });
+        let config = chunker_config_from_settings(Some(&section)).unwrap();
+        assert_eq!(config.max_lines, Some(60));
+        assert_eq!(config.overlap_lines, 5);
+        let sql = config.for_language("sql");
+        assert_eq!(sql.max_lines, Some(200));
+        assert_eq!(sql.overlap_lines, 5);
+        assert_eq!(sql.min_chunk_lines, 4);
+        assert_ne!(
+            chunker_fingerprint(&config),
+            chunker_fingerprint(&ChunkerConfig::for_embeddings())
+        );
+        assert_eq!(
+            chunker_fingerprint(&chunker_config_from_settings(None).unwrap()),
+            chunker_fingerprint(&ChunkerConfig::for_embeddings())
+        );
+    }
+
+    #[test]
+    fn chunking_errors_name_the_offending_key() {
+        let error =
+            |section: serde_json::Value| chunker_config_from_settings(Some(&section)).unwrap_err();
+        assert_eq!(
+            error(serde_json::json!({ "max_lines": 10, "overlap_lines": 10 })),
+            "chunking.overlap_lines (10) must be smaller than max_lines (10)"
+        );
+        assert_eq!(
+            error(
+                serde_json::json!({ "languages": { "yaml": { "max_lines": 8, "min_chunk_lines": 9 } } })
+            ),
+            "chunking.languages.yaml: min_chunk_lines (9) cannot exceed max_lines (8)"
+        );
+        assert_eq!(
+            error(serde_json::json!({ "languages": { "sql": { "max_lines": -1 } } })),
+            "chunking.languages.sql.max_lines must be a non-negative integer"
+        );
+        assert_eq!(
+            error(serde_json::json!({ "languages": { "sql": { "languages": {} } } })),
+            "chunking.languages.sql.languages is not a chunking setting"
+        );
+        assert_eq!(
+            error(serde_json::json!({ "max_line": 10 })),
+            "chunking.max_line is not a chunking setting"
+        );
+        assert_eq!(
+            error(serde_json::json!({ "languages": { "sequel": {} } })),
+            "chunking.languages.sequel: unknown language"
+        );
+    }
+
     #[tokio::test]
     #[ignore = "Requires ONNX embedding model"]
     async fn test_indexing() {
@@ -36,6 +36,8 @@ mod error;
 mod health;
 mod index_state;
 mod indexer;
+mod progress;
+mod reindex_gate;
 mod scanner;
 mod stats;
 mod watcher;
@@ -43,17 +45,28 @@ mod watermark_io;
 
 pub use error::{IndexerError, Result};
 pub use health::append_failure_reason;
-pub use health::{health_file_path, read_health_snapshot, write_health_snapshot, HealthSnapshot};
+pub use health::{
+    health_file_path, read_health_snapshot, write_health_snapshot, FailureReasonCount,
+    HealthSnapshot,
+};
 pub use index_state::{
-    assess_staleness, IndexSnapshot, IndexState, ReindexAttempt, ReindexResult, StaleAssessment,
-    StaleReason, ToolMeta, Watermark, INDEX_STATE_SCHEMA_VERSION,
+    assess_staleness, IndexConfigMark, IndexProvenance, IndexSnapshot, IndexState, ReindexAttempt,
+    ReindexResult, StaleAssessment, StaleReason, ToolCallStats, ToolMeta, WatcherState, Watermark,
+    WorkspaceRootState, INDEX_STATE_SCHEMA_VERSION,
+};
+pub use indexer::{
+    current_index_provenance, load_chunker_config, ModelIndexSpec, MultiModelProjectIndexer,
+    ProjectIndexer,
+};
+pub use progress::{IndexProgress, IndexProgressCallback};
+pub use reindex_gate::{reindex_with_policy, shared_reindex, RetryPolicy, SharedReindex};
+pub use scanner::{FileScanner, ScanStats};
+pub use stats::{
+    IndexPhase, IndexPlan, IndexStats, LanguageStat, ModelStats, MultiModelIndexStats, PhaseTiming,
 };
-pub use indexer::{ModelIndexSpec, MultiModelProjectIndexer, ProjectIndexer};
-pub use scanner::FileScanner;
-pub use stats::IndexStats;
 pub use watcher::{
-    IndexUpdate, IndexerHealth, MultiModelStreamingIndexer, StreamingIndexer,
-    StreamingIndexerConfig,
+    AlertHook, AlertRecord, IndexUpdate, IndexerHealth, MultiModelStreamingIndexer,
+    StreamingIndexer, StreamingIndexerConfig,
 };
 pub use watermark_io::{
     compute_project_watermark, index_watermark_path_for_store, read_index_watermark,
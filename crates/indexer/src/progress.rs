@@ -0,0 +1,169 @@
+use crate::stats::{IndexPhase, IndexStats};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Minimum gap between two progress callbacks within the same phase.
+const MIN_EMIT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Snapshot of a running index, passed to an [`IndexProgressCallback`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexProgress {
+    pub phase: IndexPhase,
+    /// Files found by the scanner
+    pub files_scanned: usize,
+    /// Files that will be chunked in this run
+    pub files_total: usize,
+    pub files_processed: usize,
+    /// Chunk embeddings this run will compute (known once chunking is done)
+    pub chunks_total: usize,
+    pub chunks_embedded: usize,
+    pub elapsed_ms: u64,
+    /// Estimated time left in the current phase, extrapolated from its rate so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_ms: Option<u64>,
+}
+
+impl IndexProgress {
+    const fn new() -> Self {
+        Self {
+            phase: IndexPhase::Scan,
+            files_scanned: 0,
+            files_total: 0,
+            files_processed: 0,
+            chunks_total: 0,
+            chunks_embedded: 0,
+            elapsed_ms: 0,
+            eta_ms: None,
+        }
+    }
+}
+
+/// Called from the indexing task on phase changes and (throttled) as work completes.
+/// Keep it cheap: hand the snapshot off to a channel rather than doing I/O inline.
+pub type IndexProgressCallback = Arc<dyn Fn(&IndexProgress) + Send + Sync>;
+
+/// Tracks phase timings for [`IndexStats`] and forwards progress to an optional callback.
+pub(crate) struct ProgressTracker {
+    callback: Option<IndexProgressCallback>,
+    started: Instant,
+    phase_started: Instant,
+    current: Option<IndexPhase>,
+    last_emit: Option<Instant>,
+    progress: IndexProgress,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(callback: Option<IndexProgressCallback>) -> Self {
+        let now = Instant::now();
+        Self {
+            callback,
+            started: now,
+            phase_started: now,
+            current: None,
+            last_emit: None,
+            progress: IndexProgress::new(),
+        }
+    }
+
+    /// Close the current phase (adding its duration to `stats`) and start `phase`.
+    pub(crate) fn enter(&mut self, phase: IndexPhase, stats: &mut IndexStats) {
+        self.close_phase(stats);
+        self.current = Some(phase);
+        self.phase_started = Instant::now();
+        self.progress.phase = phase;
+        self.emit(true);
+    }
+
+    /// Close the last phase; call once the run has succeeded.
+    pub(crate) fn finish(&mut self, stats: &mut IndexStats) {
+        self.close_phase(stats);
+        self.current = None;
+    }
+
+    pub(crate) fn update(&mut self, apply: impl FnOnce(&mut IndexProgress)) {
+        apply(&mut self.progress);
+        self.emit(false);
+    }
+
+    fn close_phase(&self, stats: &mut IndexStats) {
+        if let Some(phase) = self.current {
+            stats.add_phase_time(phase, millis(self.phase_started.elapsed()));
+        }
+    }
+
+    fn emit(&mut self, force: bool) {
+        let Some(callback) = self.callback.as_ref() else {
+            return;
+        };
+        let now = Instant::now();
+        if !force
+            && self
+                .last_emit
+                .is_some_and(|last| now.duration_since(last) < MIN_EMIT_INTERVAL)
+        {
+            return;
+        }
+        self.last_emit = Some(now);
+
+        let (done, total) = match self.progress.phase {
+            IndexPhase::Chunk => (self.progress.files_processed, self.progress.files_total),
+            IndexPhase::Embed => (self.progress.chunks_embedded, self.progress.chunks_total),
+            IndexPhase::Scan | IndexPhase::Save => (0, 0),
+        };
+        self.progress.elapsed_ms = millis(self.started.elapsed());
+        self.progress.eta_ms = (done > 0 && total >= done).then(|| {
+            let phase_ms = u128::from(millis(self.phase_started.elapsed()));
+            let eta = phase_ms * (total - done) as u128 / done as u128;
+            u64::try_from(eta).unwrap_or(u64::MAX)
+        });
+        callback(&self.progress);
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn tracker_records_phases_and_throttles_updates() {
+        let seen: Arc<Mutex<Vec<IndexProgress>>> = Arc::default();
+        let sink = Arc::clone(&seen);
+        let callback: IndexProgressCallback =
+            Arc::new(move |progress| sink.lock().unwrap().push(progress.clone()));
+
+        let mut stats = IndexStats::new();
+        let mut tracker = ProgressTracker::new(Some(callback));
+        tracker.enter(IndexPhase::Scan, &mut stats);
+        tracker.enter(IndexPhase::Chunk, &mut stats);
+        tracker.update(|p| p.files_processed = 1);
+        tracker.update(|p| p.files_processed = 2);
+        tracker.enter(IndexPhase::Embed, &mut stats);
+        tracker.enter(IndexPhase::Chunk, &mut stats);
+        tracker.finish(&mut stats);
+
+        let phases: Vec<IndexPhase> = stats.phases.iter().map(|t| t.phase).collect();
+        assert_eq!(
+            phases,
+            vec![IndexPhase::Scan, IndexPhase::Chunk, IndexPhase::Embed]
+        );
+
+        let seen = seen.lock().unwrap();
+        let emitted: Vec<IndexPhase> = seen.iter().map(|p| p.phase).collect();
+        // Phase changes always emit; back-to-back updates inside a phase are throttled.
+        assert_eq!(
+            emitted,
+            vec![
+                IndexPhase::Scan,
+                IndexPhase::Chunk,
+                IndexPhase::Embed,
+                IndexPhase::Chunk
+            ]
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! One budgeted auto-reindex per project root at a time.
+//!
+//! Tools reindex a stale project on demand within a time budget. Without coordination, N
+//! concurrent calls (or the items of one batch) for the same root each start their own
+//! reindex and each spend the full budget. [`shared_reindex`] lets the first caller run the
+//! reindex while the others wait for it, bounded by their own budget. A caller arriving just
+//! after a successful reindex finished (within [`REUSE_WINDOW`]) reuses that attempt instead of
+//! starting another one; a root that goes stale again inside the window is only refreshed by the
+//! next call after it.
+//!
+//! [`reindex_with_policy`] retries a reindex that failed (e.g. a transient I/O or lock
+//! error) with exponential backoff, recording every attempt into the index state.
+
+use crate::index_state::{IndexState, ReindexAttempt, ReindexResult};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+/// How long a successful reindex is handed to sequential callers instead of being rerun
+pub const REUSE_WINDOW: Duration = Duration::from_millis(500);
+
+/// Held while a reindex of the root runs; keeps the last finished attempt (and when it
+/// finished) for waiters and for callers inside [`REUSE_WINDOW`].
+type RootSlot = Arc<tokio::sync::Mutex<Option<(ReindexAttempt, Instant)>>>;
+
+fn root_slot(root: &Path) -> RootSlot {
+    static SLOTS: OnceLock<Mutex<HashMap<PathBuf, RootSlot>>> = OnceLock::new();
+    let mut slots = SLOTS
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    slots.entry(root.to_path_buf()).or_default().clone()
+}
+
+/// What [`shared_reindex`] did for one caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedReindex {
+    /// The attempt this caller ran, or the one it waited for
+    pub attempt: ReindexAttempt,
+    /// Another caller was already reindexing the root; this one only waited
+    pub joined: bool,
+    /// The budget ran out before the index was refreshed
+    pub deadline_hit: bool,
+}
+
+/// Run `reindex` for `root`, unless a reindex of that root is already in flight. In that
+/// case wait for it for at most `budget` and report its attempt; when the budget runs out
+/// first, give up with `deadline_hit` so the caller can proceed with the stale index.
+/// A successful reindex that finished less than [`REUSE_WINDOW`] ago is reported as joined
+/// without running `reindex` again.
+pub async fn shared_reindex<F, Fut>(root: &Path, budget: Duration, reindex: F) -> SharedReindex
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = ReindexAttempt>,
+{
+    let slot = root_slot(root);
+    if let Ok(mut last) = slot.try_lock() {
+        if let Some((attempt, _)) = last.as_ref().filter(|(attempt, finished)| {
+            attempt.result == Some(ReindexResult::Ok) && finished.elapsed() < REUSE_WINDOW
+        }) {
+            return SharedReindex {
+                attempt: attempt.clone(),
+                joined: true,
+                deadline_hit: false,
+            };
+        }
+        let attempt = reindex().await;
+        *last = Some((attempt.clone(), Instant::now()));
+        return SharedReindex {
+            deadline_hit: attempt.result == Some(ReindexResult::BudgetExceeded),
+            attempt,
+            joined: false,
+        };
+    }
+
+    let started = Instant::now();
+    let budget_ms = u64::try_from(budget.as_millis()).unwrap_or(u64::MAX);
+    let waited = tokio::time::timeout(budget, slot.lock()).await;
+    let duration_ms = Some(u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX));
+    match waited {
+        Ok(last) => {
+            let attempt = last
+                .clone()
+                .map(|(attempt, _)| attempt)
+                .unwrap_or(ReindexAttempt {
+                    attempted: true,
+                    performed: false,
+                    budget_ms: Some(budget_ms),
+                    duration_ms,
+                    result: Some(ReindexResult::Skipped),
+                    error: None,
+                });
+            SharedReindex {
+                deadline_hit: attempt.result == Some(ReindexResult::BudgetExceeded),
+                attempt,
+                joined: true,
+            }
+        }
+        Err(_) => SharedReindex {
+            attempt: ReindexAttempt {
+                attempted: true,
+                performed: false,
+                budget_ms: Some(budget_ms),
+                duration_ms,
+                result: Some(ReindexResult::BudgetExceeded),
+                error: None,
+            },
+            joined: true,
+            deadline_hit: true,
+        },
+    }
+}
+
+/// How often and how patiently [`reindex_with_policy`] retries a failed reindex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first; `0` is treated as `1`
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled before each further one
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+        }
+    }
+}
+
+/// Run `reindex` for `root` until it stops failing or `policy.max_attempts` is used up.
+/// Only [`ReindexResult::Failed`] is retried: an exhausted budget or a skipped reindex
+/// would not change on a second try. Each attempt is appended to `state.reindex_attempts`
+/// and the last one becomes `state.reindex`; its result is returned.
+pub async fn reindex_with_policy<F, Fut>(
+    root: &Path,
+    policy: RetryPolicy,
+    state: &mut IndexState,
+    mut reindex: F,
+) -> ReindexResult
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ReindexAttempt>,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut delay = policy.base_delay;
+    let mut attempt_no = 1;
+    loop {
+        let attempt = reindex().await;
+        let result = attempt.result.clone().unwrap_or(ReindexResult::Skipped);
+        if result == ReindexResult::Failed && attempt_no < max_attempts {
+            log::warn!(
+                "Reindex of {} failed (attempt {attempt_no}/{max_attempts}): {}; retrying in {}ms",
+                root.display(),
+                attempt.error.as_deref().unwrap_or("unknown error"),
+                delay.as_millis()
+            );
+            state.reindex_attempts.push(attempt);
+            tokio::time::sleep(delay).await;
+            delay = delay.saturating_mul(2);
+            attempt_no += 1;
+            continue;
+        }
+        state.reindex_attempts.push(attempt.clone());
+        state.reindex = Some(attempt);
+        return result;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn finished(result: ReindexResult) -> ReindexAttempt {
+        ReindexAttempt {
+            attempted: true,
+            performed: result == ReindexResult::Ok,
+            budget_ms: Some(1_000),
+            duration_ms: Some(200),
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_callers_share_one_reindex() {
+        let root = Path::new("/tmp/context-finder-reindex-gate-shared");
+        let counter = AtomicUsize::new(0);
+        let runs = &counter;
+        let run = move || async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            finished(ReindexResult::Ok)
+        };
+
+        let (leader, patient, impatient) = tokio::join!(
+            shared_reindex(root, Duration::from_secs(5), run),
+            async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                shared_reindex(root, Duration::from_secs(5), run).await
+            },
+            async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                shared_reindex(root, Duration::from_millis(50), run).await
+            },
+        );
+
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(!leader.joined && !leader.deadline_hit);
+        assert!(leader.attempt.performed);
+
+        assert!(patient.joined && !patient.deadline_hit);
+        assert_eq!(patient.attempt, leader.attempt);
+
+        assert!(impatient.joined && impatient.deadline_hit);
+        assert!(!impatient.attempt.performed);
+        assert_eq!(
+            impatient.attempt.result,
+            Some(ReindexResult::BudgetExceeded)
+        );
+    }
+
+    #[tokio::test]
+    async fn sequential_caller_reuses_a_just_finished_reindex() {
+        let root = Path::new("/tmp/context-finder-reindex-gate-sequential");
+        let counter = AtomicUsize::new(0);
+        let runs = &counter;
+        let run = move || async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            finished(ReindexResult::Ok)
+        };
+
+        let first = shared_reindex(root, Duration::from_secs(5), run).await;
+        let second = shared_reindex(root, Duration::from_secs(5), run).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(!first.joined);
+        assert!(second.joined && !second.deadline_hit);
+        assert_eq!(second.attempt, first.attempt);
+
+        tokio::time::sleep(REUSE_WINDOW).await;
+        let third = shared_reindex(root, Duration::from_secs(5), run).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+        assert!(!third.joined);
+    }
+
+    fn empty_state() -> IndexState {
+        IndexState {
+            schema_version: crate::INDEX_STATE_SCHEMA_VERSION,
+            project_root: None,
+            model_id: "stub".to_string(),
+            profile: "quality".to_string(),
+            project_watermark: crate::Watermark::Filesystem {
+                computed_at_unix_ms: None,
+                file_count: 0,
+                max_mtime_ms: 0,
+                total_bytes: 0,
+            },
+            index: crate::IndexSnapshot {
+                exists: true,
+                path: None,
+                mtime_ms: None,
+                built_at_unix_ms: None,
+                watermark: None,
+                provenance: None,
+            },
+            stale: true,
+            stale_reasons: Vec::new(),
+            reindex: None,
+            reindex_attempts: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn flaky_reindex_is_retried_and_every_attempt_recorded() {
+        let root = Path::new("/tmp/context-finder-reindex-retry");
+        let calls = AtomicUsize::new(0);
+        let mut state = empty_state();
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(5),
+        };
+
+        let result = reindex_with_policy(root, policy, &mut state, || {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if call == 0 {
+                    ReindexAttempt {
+                        error: Some("index lock busy".to_string()),
+                        ..finished(ReindexResult::Failed)
+                    }
+                } else {
+                    finished(ReindexResult::Ok)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result, ReindexResult::Ok);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        let results: Vec<_> = state
+            .reindex_attempts
+            .iter()
+            .map(|attempt| attempt.result.clone())
+            .collect();
+        assert_eq!(
+            results,
+            vec![Some(ReindexResult::Failed), Some(ReindexResult::Ok)]
+        );
+        assert_eq!(state.reindex.as_ref(), state.reindex_attempts.last());
+    }
+
+    #[tokio::test]
+    async fn leader_over_budget_reports_deadline_hit() {
+        let root = Path::new("/tmp/context-finder-reindex-gate-budget");
+        let outcome = shared_reindex(root, Duration::from_millis(10), || async {
+            finished(ReindexResult::BudgetExceeded)
+        })
+        .await;
+        assert!(!outcome.joined);
+        assert!(outcome.deadline_hit);
+    }
+}
@@ -1,31 +1,114 @@
-use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Entries a scan skipped, next to the files it returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanStats {
+    /// Entries excluded by `.gitignore`/`.git/info/exclude`/global excludes, hidden entries and
+    /// built-in noise directories; an excluded directory counts once and is not walked
+    pub ignored_files: usize,
+    /// Entries the walk could not read (permissions, broken links)
+    pub unreadable_files: usize,
+}
 
 /// Scanner for finding source files in a project
 pub struct FileScanner {
     root: PathBuf,
+    follow_symlinks: bool,
 }
 
 impl FileScanner {
     pub fn new(root: impl AsRef<Path>) -> Self {
         Self {
             root: root.as_ref().to_path_buf(),
+            follow_symlinks: false,
         }
     }
 
-    /// Scan directory for source files (.gitignore aware)
+    /// Descend into symlinked directories and include symlinked files (off by default).
+    ///
+    /// Each directory is walked at most once by its canonical path, so link cycles and links
+    /// back into the tree do not loop or duplicate files.
+    #[must_use]
+    pub const fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Scan directory for source files (.gitignore aware).
+    ///
+    /// Paths are sorted by their normalized (`/`-separated) path relative to the root, so
+    /// repeated scans of the same tree return identical vectors regardless of walk order.
     #[must_use]
     pub fn scan(&self) -> Vec<PathBuf> {
+        self.scan_with_stats().0
+    }
+
+    /// [`Self::scan`], plus how many entries were ignored or unreadable.
+    ///
+    /// Ignore rules follow git: the deepest `.gitignore` with a matching pattern decides (so a
+    /// nested `!pattern` re-includes what a parent ignored), then `.git/info/exclude`, then the
+    /// global excludes file. A `.ignore` file (as read by ripgrep) is applied alongside the
+    /// `.gitignore` of its directory and wins over it. Files under an ignored directory stay ignored, as in git. A path
+    /// re-included by a negation is kept even when it is hidden or in a built-in noise scope.
+    #[must_use]
+    pub fn scan_with_stats(&self) -> (Vec<PathBuf>, ScanStats) {
         let mut files = Vec::new();
+        let mut unreadable_files = 0usize;
 
-        let root = self.root.clone();
         let mut builder = WalkBuilder::new(&self.root);
         builder
-            .hidden(true) // do not index hidden files by default
-            .git_ignore(true)
-            .git_global(true)
-            .git_exclude(true);
-        builder.filter_entry(move |entry| !Self::is_ignored_scope(entry.path(), &root));
+            // Ignore rules are applied in `filter_entry` so that skipped entries can be counted.
+            .standard_filters(false)
+            .follow_links(self.follow_symlinks);
+        let visited_dirs = Arc::new(Mutex::new(
+            self.root.canonicalize().into_iter().collect::<HashSet<_>>(),
+        ));
+        let rules = Arc::new(Mutex::new(IgnoreRules::new(&self.root)));
+        let ignored = Arc::new(AtomicUsize::new(0));
+        let ignored_files = Arc::clone(&ignored);
+        let follow_symlinks = self.follow_symlinks;
+        builder.filter_entry(move |entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            let path = entry.path();
+            if path.file_name().is_some_and(|name| name == ".git") {
+                return false;
+            }
+            let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+            let decision = rules
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .matched(path, is_dir);
+            let skip = match decision {
+                Match::Ignore(()) => true,
+                Match::Whitelist(()) => false,
+                Match::None => Self::is_hidden(path) || Self::is_ignored_scope(path),
+            };
+            if skip {
+                ignored_files.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+            if follow_symlinks && is_dir {
+                let Ok(canonical) = entry.path().canonicalize() else {
+                    return false;
+                };
+                let mut visited = visited_dirs.lock().unwrap_or_else(|e| e.into_inner());
+                if !visited.insert(canonical) {
+                    log::debug!(
+                        "Skipping already visited directory {}",
+                        entry.path().display()
+                    );
+                    return false;
+                }
+            }
+            true
+        });
 
         for result in builder.build() {
             match result {
@@ -38,8 +121,8 @@ impl FileScanner {
                     }
 
                     let path = entry.path();
-                    if let Ok(meta) = entry.metadata() {
-                        if meta.len() > MAX_FILE_SIZE_BYTES {
+                    match entry.metadata() {
+                        Ok(meta) if meta.len() > MAX_FILE_SIZE_BYTES => {
                             log::debug!(
                                 "Skipping large file {} ({} bytes > {})",
                                 path.display(),
@@ -48,6 +131,12 @@ impl FileScanner {
                             );
                             continue;
                         }
+                        Ok(_) => {}
+                        Err(e) => {
+                            log::debug!("Skipping unreadable file {}: {e}", path.display());
+                            unreadable_files += 1;
+                            continue;
+                        }
                     }
 
                     if Self::is_noise_file(path) {
@@ -61,12 +150,34 @@ impl FileScanner {
 
                     files.push(path.to_path_buf());
                 }
-                Err(e) => log::warn!("Failed to read entry: {e}"),
+                Err(e) => {
+                    log::warn!("Failed to read entry: {e}");
+                    unreadable_files += 1;
+                }
             }
         }
 
-        log::info!("Found {} source files", files.len());
-        files
+        files.sort_by_cached_key(|path| Self::sort_key(path, &self.root));
+        let stats = ScanStats {
+            ignored_files: ignored.load(Ordering::Relaxed),
+            unreadable_files,
+        };
+        log::info!(
+            "Found {} source files ({} ignored, {} unreadable entries)",
+            files.len(),
+            stats.ignored_files,
+            stats.unreadable_files
+        );
+        (files, stats)
+    }
+
+    fn sort_key(path: &Path, root: &Path) -> String {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let mut normalized = relative.to_string_lossy().to_string();
+        if normalized.contains('\\') {
+            normalized = normalized.replace('\\', "/");
+        }
+        normalized
     }
 
     /// Check if file is a source code file
@@ -123,18 +234,19 @@ impl FileScanner {
             .is_some_and(|name| name.eq_ignore_ascii_case(target))
     }
 
-    fn is_ignored_scope(path: &Path, root: &Path) -> bool {
-        if let Ok(relative) = path.strip_prefix(root) {
-            for component in relative.components() {
-                if let std::path::Component::Normal(name) = component {
-                    let lowered = name.to_string_lossy().to_lowercase();
-                    if IGNORED_SCOPES.iter().any(|ignored| ignored == &lowered) {
-                        return true;
-                    }
-                }
-            }
-        }
-        false
+    fn is_hidden(path: &Path) -> bool {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with('.'))
+    }
+
+    /// Only the entry's own name is checked: the walk never descends into an ignored scope
+    /// unless a `.gitignore` negation re-included it.
+    fn is_ignored_scope(path: &Path) -> bool {
+        path.file_name().is_some_and(|name| {
+            let lowered = name.to_string_lossy().to_lowercase();
+            IGNORED_SCOPES.iter().any(|ignored| ignored == &lowered)
+        })
     }
 
     fn is_noise_file(path: &Path) -> bool {
@@ -155,6 +267,125 @@ impl FileScanner {
     }
 }
 
+/// `.gitignore` (plus `.ignore`) rules with git's precedence. Matchers of directories under the
+/// scan root are built on first use; those of directories above it (up to the enclosing
+/// repository root), `.git/info/exclude` and the global excludes file are built once.
+struct IgnoreRules {
+    root: PathBuf,
+    /// Canonical scan root, to match the rules rooted above it
+    canonical_root: PathBuf,
+    scopes: HashMap<PathBuf, Option<Gitignore>>,
+    /// Ignore files of the directories above the root, deepest first
+    above: Vec<Gitignore>,
+    /// `.git/info/exclude`, then the global excludes file
+    fallback: Vec<Gitignore>,
+}
+
+impl IgnoreRules {
+    fn new(root: &Path) -> Self {
+        let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let repo_root = canonical_root
+            .ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .map(Path::to_path_buf);
+
+        let mut above = Vec::new();
+        if let Some(repo_root) = &repo_root {
+            for dir in canonical_root.ancestors().skip(1) {
+                if !dir.starts_with(repo_root) {
+                    break;
+                }
+                above.extend(Self::load_dir(dir));
+            }
+        }
+
+        let mut fallback = Vec::new();
+        if let Some(repo_root) = &repo_root {
+            fallback.extend(Self::load(
+                repo_root,
+                &[repo_root.join(".git").join("info").join("exclude")],
+            ));
+        }
+        let (global, err) = Gitignore::global();
+        if let Some(err) = err {
+            log::debug!("Failed to read the global git excludes file: {err}");
+        }
+        if !global.is_empty() {
+            fallback.push(global);
+        }
+
+        Self {
+            root: root.to_path_buf(),
+            canonical_root,
+            scopes: HashMap::new(),
+            above,
+            fallback,
+        }
+    }
+
+    /// Rules of one directory: its `.gitignore`, then its `.ignore` (later rules win).
+    fn load_dir(dir: &Path) -> Option<Gitignore> {
+        Self::load(dir, &[dir.join(".gitignore"), dir.join(".ignore")])
+    }
+
+    fn load(dir: &Path, files: &[PathBuf]) -> Option<Gitignore> {
+        let files: Vec<&PathBuf> = files.iter().filter(|file| file.is_file()).collect();
+        let first = files.first()?;
+        let mut builder = GitignoreBuilder::new(dir);
+        for file in &files {
+            if let Some(err) = builder.add(file) {
+                log::warn!("Failed to parse {}: {err}", file.display());
+            }
+        }
+        match builder.build() {
+            Ok(gitignore) if !gitignore.is_empty() => Some(gitignore),
+            Ok(_) => None,
+            Err(err) => {
+                log::warn!(
+                    "Failed to build ignore rules from {}: {err}",
+                    first.display()
+                );
+                None
+            }
+        }
+    }
+
+    fn matched(&mut self, path: &Path, is_dir: bool) -> Match<()> {
+        for dir in path.ancestors().skip(1) {
+            if !dir.starts_with(&self.root) {
+                break;
+            }
+            let scope = self
+                .scopes
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| Self::load_dir(dir));
+            if let Some(gitignore) = scope {
+                let decision = Self::decide(gitignore, path, is_dir);
+                if !decision.is_none() {
+                    return decision;
+                }
+            }
+        }
+
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let absolute = self.canonical_root.join(relative);
+        self.above
+            .iter()
+            .chain(&self.fallback)
+            .map(|gitignore| Self::decide(gitignore, &absolute, is_dir))
+            .find(|decision| !decision.is_none())
+            .unwrap_or(Match::None)
+    }
+
+    fn decide(gitignore: &Gitignore, path: &Path, is_dir: bool) -> Match<()> {
+        match gitignore.matched(path, is_dir) {
+            Match::None => Match::None,
+            Match::Ignore(_) => Match::Ignore(()),
+            Match::Whitelist(_) => Match::Whitelist(()),
+        }
+    }
+}
+
 const IGNORED_SCOPES: &[&str] = &[
     // VCS / tooling
     ".git",
@@ -336,4 +567,77 @@ mod tests {
         assert!(files.iter().any(|p| p.ends_with("src.rs")));
         assert!(files.iter().all(|p| !p.ends_with(".gitignore")));
     }
+
+    #[test]
+    fn scan_order_is_deterministic() {
+        let temp = tempdir().unwrap();
+        for dir in ["zeta", "alpha/nested", "mid"] {
+            fs::create_dir_all(temp.path().join(dir)).unwrap();
+        }
+        for file in [
+            "zeta/z.rs",
+            "alpha/nested/b.rs",
+            "alpha/a.rs",
+            "mid/m.py",
+            "root.md",
+        ] {
+            fs::write(temp.path().join(file), b"// content").unwrap();
+        }
+
+        let scanner = FileScanner::new(temp.path());
+        let first = scanner.scan();
+        let second = scanner.scan();
+        assert_eq!(first, second);
+
+        let relative: Vec<_> = first
+            .iter()
+            .map(|p| {
+                p.strip_prefix(temp.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        assert_eq!(
+            relative,
+            vec![
+                "alpha/a.rs",
+                "alpha/nested/b.rs",
+                "mid/m.py",
+                "root.md",
+                "zeta/z.rs"
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follows_symlinked_dirs_only_when_enabled() {
+        let temp = tempdir().unwrap();
+        let outside = temp.path().join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("shared.rs"), b"pub fn shared() {}").unwrap();
+        std::os::unix::fs::symlink(temp.path(), outside.join("loop")).unwrap();
+
+        let root = temp.path().join("project");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/main.rs"), b"fn main() {}").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("linked")).unwrap();
+        std::os::unix::fs::symlink(root.join("src"), root.join("src_alias")).unwrap();
+
+        let files = FileScanner::new(&root).scan();
+        assert_eq!(files, vec![root.join("src/main.rs")]);
+
+        let files = FileScanner::new(&root).follow_symlinks(true).scan();
+        let relative: Vec<_> = files
+            .iter()
+            .map(|p| p.strip_prefix(&root).unwrap().to_path_buf())
+            .collect();
+        assert!(relative.iter().any(|p| p.ends_with("linked/shared.rs")));
+        assert_eq!(
+            relative.iter().filter(|p| p.ends_with("main.rs")).count(),
+            1,
+            "{relative:?}"
+        );
+    }
 }
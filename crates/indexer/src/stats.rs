@@ -1,7 +1,50 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Per-language totals for one indexing run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LanguageStat {
+    pub files: usize,
+    pub lines: usize,
+    pub chunks: usize,
+}
+
+/// Phase of an index run, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexPhase {
+    /// Walk the project and load existing corpus/index state
+    Scan,
+    /// Read and chunk changed files
+    Chunk,
+    /// Embed chunks into the vector store(s)
+    Embed,
+    /// Persist corpus, indexes, mtimes and watermarks
+    Save,
+}
+
+impl IndexPhase {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Scan => "scan",
+            Self::Chunk => "chunk",
+            Self::Embed => "embed",
+            Self::Save => "save",
+        }
+    }
+}
+
+/// Wall-clock time spent in one phase (summed if the phase is entered more than once)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PhaseTiming {
+    pub phase: IndexPhase,
+    pub time_ms: u64,
+}
 
 /// Statistics about indexing operation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct IndexStats {
     /// Number of files processed
     pub files: usize,
@@ -15,11 +58,31 @@ pub struct IndexStats {
     /// Time taken in milliseconds
     pub time_ms: u64,
 
-    /// Languages found
-    pub languages: std::collections::HashMap<String, usize>,
+    /// Per-language histogram (files, lines, chunks)
+    pub languages: HashMap<String, LanguageStat>,
 
     /// Errors encountered
     pub errors: Vec<String>,
+
+    /// Time per phase, in the order phases were first entered
+    #[serde(default)]
+    pub phases: Vec<PhaseTiming>,
+
+    /// Chunks of changed files that kept their stored vector because their content is unchanged
+    #[serde(default)]
+    pub chunks_reused: usize,
+
+    /// Embedded documents cut to `embedding.max_tokens` (summed over models)
+    #[serde(default)]
+    pub docs_token_truncated: usize,
+
+    /// Files (re)chunked by this run, relative to the project root, sorted
+    #[serde(skip)]
+    pub changed_files: Vec<String>,
+
+    /// Files purged from the index because they no longer exist, sorted
+    #[serde(skip)]
+    pub deleted_files: Vec<String>,
 }
 
 impl IndexStats {
@@ -30,15 +93,31 @@ impl IndexStats {
             chunks: 0,
             total_lines: 0,
             time_ms: 0,
-            languages: std::collections::HashMap::new(),
+            languages: HashMap::new(),
             errors: Vec::new(),
+            phases: Vec::new(),
+            chunks_reused: 0,
+            docs_token_truncated: 0,
+            changed_files: Vec::new(),
+            deleted_files: Vec::new(),
         }
     }
 
-    pub fn add_file(&mut self, language: &str, lines: usize) {
+    pub fn add_phase_time(&mut self, phase: IndexPhase, time_ms: u64) {
+        match self.phases.iter_mut().find(|timing| timing.phase == phase) {
+            Some(timing) => timing.time_ms += time_ms,
+            None => self.phases.push(PhaseTiming { phase, time_ms }),
+        }
+    }
+
+    pub fn add_file(&mut self, language: &str, lines: usize, chunks: usize) {
         self.files += 1;
         self.total_lines += lines;
-        *self.languages.entry(language.to_string()).or_insert(0) += 1;
+        self.chunks += chunks;
+        let stat = self.languages.entry(language.to_string()).or_default();
+        stat.files += 1;
+        stat.lines += lines;
+        stat.chunks += chunks;
     }
 
     pub const fn add_chunks(&mut self, count: usize) {
@@ -50,8 +129,93 @@ impl IndexStats {
     }
 }
 
+/// Work an index run would do, computed without embedding or saving (dry run)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct IndexPlan {
+    /// Files found by the scanner
+    pub total_files: usize,
+
+    /// Files that would be chunked and embedded
+    pub files: usize,
+
+    /// Files skipped because they are unchanged since the last index
+    pub unchanged_files: usize,
+
+    /// Whether an existing index would be updated incrementally
+    pub incremental: bool,
+
+    /// Chunks that would be embedded
+    pub estimated_chunks: usize,
+
+    /// Total lines in the files that would be processed
+    pub total_lines: usize,
+
+    /// Per-language histogram of the files that would be processed
+    pub languages: HashMap<String, LanguageStat>,
+
+    /// Files that could not be read or chunked
+    pub errors: Vec<String>,
+}
+
 impl Default for IndexStats {
     fn default() -> Self {
         Self::new()
     }
 }
+
+/// What one model's store got out of a multi-model index run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ModelStats {
+    /// Chunks embedded into (or carried over by) this model's store
+    pub chunks_embedded: usize,
+
+    /// Chunks of changed files that kept their stored vector
+    pub chunks_reused: usize,
+
+    /// Embedded documents cut to `embedding.max_tokens`
+    pub docs_token_truncated: usize,
+
+    /// Documents this model actually embedded; the rest came from the embedding cache, which
+    /// specs sharing a backend (model aliases) fill for each other
+    pub docs_embedded: usize,
+
+    /// Embedding dimension of the store
+    pub dimension: usize,
+}
+
+/// Result of [`crate::MultiModelProjectIndexer::index_models`]: the shared scan/chunk stats
+/// plus one entry per model, so a model that fails (unknown id, invalid templates, a failed
+/// save) does not discard the work done for the others.
+#[derive(Debug, Clone, Default)]
+pub struct MultiModelIndexStats {
+    /// Totals over the whole run; failed models are listed in `errors`
+    pub stats: IndexStats,
+
+    /// Outcome per (trimmed) model id
+    pub per_model: HashMap<String, Result<ModelStats, String>>,
+}
+
+impl MultiModelIndexStats {
+    /// Failed models with their error, sorted by model id.
+    #[must_use]
+    pub fn failures(&self) -> Vec<(&str, &str)> {
+        let mut failures: Vec<(&str, &str)> = self
+            .per_model
+            .iter()
+            .filter_map(|(model_id, result)| {
+                result
+                    .as_ref()
+                    .err()
+                    .map(|err| (model_id.as_str(), err.as_str()))
+            })
+            .collect();
+        failures.sort_unstable();
+        failures
+    }
+
+    /// No model was indexed.
+    #[must_use]
+    pub fn all_failed(&self) -> bool {
+        self.per_model.values().all(Result::is_err)
+    }
+}
@@ -2,14 +2,18 @@ use crate::{
     health::write_health_snapshot, IndexStats, IndexerError, ModelIndexSpec,
     MultiModelProjectIndexer, ProjectIndexer, Result,
 };
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 use notify::{Config as NotifyConfig, Event, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::Serialize;
 use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
 use std::path::Path;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::{broadcast, mpsc, watch, Mutex as TokioMutex};
+use tokio::task::JoinHandle;
 use tokio::time;
 
 const DEFAULT_ALERT_REASON: &str = "fs_event";
@@ -22,6 +26,57 @@ pub struct IndexUpdate {
     pub success: bool,
     pub reason: String,
     pub store_size_bytes: Option<u64>,
+    /// Files (re)indexed by the cycle, relative to the project root; the union across models
+    /// for a multi-model watcher. Empty for a failed cycle.
+    pub changed_files: Vec<String>,
+    /// Files the cycle dropped from the index because they no longer exist, including those a
+    /// full rebuild left out.
+    pub deleted_files: Vec<String>,
+    /// Set when either list was cut to [`StreamingIndexerConfig::max_changed_files`];
+    /// subscribers should then treat the whole project as changed.
+    pub changed_truncated: bool,
+}
+
+impl IndexUpdate {
+    fn succeeded(
+        stats: &IndexStats,
+        duration_ms: u64,
+        reason: String,
+        store_size_bytes: Option<u64>,
+        max_changed_files: usize,
+    ) -> Self {
+        let (changed_files, changed_cut) = capped_files(&stats.changed_files, max_changed_files);
+        let (deleted_files, deleted_cut) = capped_files(&stats.deleted_files, max_changed_files);
+        Self {
+            completed_at: SystemTime::now(),
+            duration_ms,
+            stats: Some(stats.clone()),
+            success: true,
+            reason,
+            store_size_bytes,
+            changed_files,
+            deleted_files,
+            changed_truncated: changed_cut || deleted_cut,
+        }
+    }
+
+    fn failed(duration_ms: u64, reason: String) -> Self {
+        Self {
+            completed_at: SystemTime::now(),
+            duration_ms,
+            stats: None,
+            success: false,
+            reason,
+            store_size_bytes: None,
+            changed_files: Vec::new(),
+            deleted_files: Vec::new(),
+            changed_truncated: false,
+        }
+    }
+}
+
+fn capped_files(files: &[String], cap: usize) -> (Vec<String>, bool) {
+    (files.iter().take(cap).cloned().collect(), files.len() > cap)
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -31,6 +86,9 @@ pub struct IndexerHealth {
     pub consecutive_failures: u32,
     pub last_duration_ms: Option<u64>,
     pub pending_events: usize,
+    /// Quiet period the watcher currently waits for; above the configured debounce while a
+    /// burst of events (build, install, checkout) is in progress
+    pub adaptive_debounce_ms: u64,
     pub indexing: bool,
     pub last_throughput_files_per_sec: Option<f32>,
     pub p95_duration_ms: Option<u64>,
@@ -40,13 +98,14 @@ pub struct IndexerHealth {
 }
 
 impl IndexerHealth {
-    fn initial() -> Self {
+    fn initial(config: &StreamingIndexerConfig) -> Self {
         Self {
             last_success: None,
             last_error: None,
             consecutive_failures: 0,
             last_duration_ms: None,
             pending_events: 0,
+            adaptive_debounce_ms: duration_ms(config.debounce),
             indexing: false,
             last_throughput_files_per_sec: None,
             p95_duration_ms: None,
@@ -57,11 +116,40 @@ impl IndexerHealth {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Alert pushed to the watcher's alert log (and to [`StreamingIndexerConfig::on_alert`]).
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertRecord {
+    pub timestamp_unix_ms: u64,
+    pub level: String,
+    pub reason: String,
+    pub detail: String,
+    /// Failed index cycles in a row, including the one that raised this alert
+    pub consecutive_failures: u32,
+}
+
+/// Async hook for ops integrations (post to a chat webhook, run a command). An `Err` is only
+/// logged; it never affects the index loop.
+pub type AlertHook = Arc<
+    dyn Fn(AlertRecord) -> Pin<Box<dyn Future<Output = std::result::Result<(), String>> + Send>>
+        + Send
+        + Sync,
+>;
+
+#[derive(Clone)]
 pub struct StreamingIndexerConfig {
     pub debounce: Duration,
     pub max_batch_wait: Duration,
     pub notify_poll_interval: Duration,
+    /// Cap for the adaptive debounce. While more than `burst_events` relevant events arrive
+    /// within one `debounce`, the quiet period doubles up to this cap and `max_batch_wait` is
+    /// extended by it, so a burst is indexed once it quiesces instead of every batch.
+    pub max_debounce: Duration,
+    pub burst_events: usize,
+    /// Called with each alert once `consecutive_failures` reaches `alert_after_failures`
+    pub on_alert: Option<AlertHook>,
+    pub alert_after_failures: u32,
+    /// Longest `changed_files`/`deleted_files` list an [`IndexUpdate`] carries
+    pub max_changed_files: usize,
 }
 
 impl Default for StreamingIndexerConfig {
@@ -70,10 +158,30 @@ impl Default for StreamingIndexerConfig {
             debounce: Duration::from_millis(750),
             max_batch_wait: Duration::from_secs(3),
             notify_poll_interval: Duration::from_secs(2),
+            max_debounce: Duration::from_secs(6),
+            burst_events: 100,
+            on_alert: None,
+            alert_after_failures: 1,
+            max_changed_files: 1000,
         }
     }
 }
 
+impl fmt::Debug for StreamingIndexerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamingIndexerConfig")
+            .field("debounce", &self.debounce)
+            .field("max_batch_wait", &self.max_batch_wait)
+            .field("notify_poll_interval", &self.notify_poll_interval)
+            .field("max_debounce", &self.max_debounce)
+            .field("burst_events", &self.burst_events)
+            .field("on_alert", &self.on_alert.is_some())
+            .field("alert_after_failures", &self.alert_after_failures)
+            .field("max_changed_files", &self.max_changed_files)
+            .finish()
+    }
+}
+
 #[derive(Clone)]
 pub struct StreamingIndexer {
     inner: Arc<StreamingIndexerInner>,
@@ -85,6 +193,7 @@ struct StreamingIndexerInner {
     health_tx: watch::Sender<IndexerHealth>,
     _watcher: Arc<std::sync::Mutex<Option<RecommendedWatcher>>>,
     _health_guard: TokioMutex<watch::Receiver<IndexerHealth>>,
+    index_loop: TokioMutex<Option<JoinHandle<()>>>,
 }
 
 enum WatcherCommand {
@@ -96,13 +205,13 @@ impl StreamingIndexer {
     pub fn start(indexer: Arc<ProjectIndexer>, config: StreamingIndexerConfig) -> Result<Self> {
         let (event_tx, event_rx) = mpsc::channel(1024);
         let (command_tx, command_rx) = mpsc::channel(16);
-        let (health_tx, health_rx) = watch::channel(IndexerHealth::initial());
+        let (health_tx, health_rx) = watch::channel(IndexerHealth::initial(&config));
         let (update_tx, _) = broadcast::channel(32);
 
         let watcher = create_fs_watcher(indexer.root(), event_tx, config.notify_poll_interval)?;
         let watcher = Arc::new(std::sync::Mutex::new(Some(watcher)));
 
-        spawn_index_loop(
+        let index_loop = spawn_index_loop(
             indexer,
             config,
             event_rx,
@@ -118,6 +227,7 @@ impl StreamingIndexer {
                 health_tx,
                 _watcher: watcher,
                 _health_guard: TokioMutex::new(health_rx),
+                index_loop: TokioMutex::new(Some(index_loop)),
             }),
         })
     }
@@ -147,6 +257,13 @@ impl StreamingIndexer {
     pub fn health_stream(&self) -> watch::Receiver<IndexerHealth> {
         self.inner.health_tx.subscribe()
     }
+
+    /// Stop watching once the in-flight index cycle (if any) has finished, waiting at most
+    /// `timeout`. Returns `false` when the cycle was still running at the deadline; queued
+    /// events that have not started a cycle are dropped.
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        drain_index_loop(&self.inner.command_tx, &self.inner.index_loop, timeout).await
+    }
 }
 
 impl Drop for StreamingIndexer {
@@ -168,6 +285,7 @@ struct MultiModelStreamingIndexerInner {
     health_tx: watch::Sender<IndexerHealth>,
     _watcher: Arc<std::sync::Mutex<Option<RecommendedWatcher>>>,
     _health_guard: TokioMutex<watch::Receiver<IndexerHealth>>,
+    index_loop: TokioMutex<Option<JoinHandle<()>>>,
     models: Arc<TokioMutex<Vec<ModelIndexSpec>>>,
 }
 
@@ -185,7 +303,7 @@ impl MultiModelStreamingIndexer {
 
         let (event_tx, event_rx) = mpsc::channel(1024);
         let (command_tx, command_rx) = mpsc::channel(16);
-        let (health_tx, health_rx) = watch::channel(IndexerHealth::initial());
+        let (health_tx, health_rx) = watch::channel(IndexerHealth::initial(&config));
         let (update_tx, _) = broadcast::channel(32);
 
         let watcher = create_fs_watcher(indexer.root(), event_tx, config.notify_poll_interval)?;
@@ -193,7 +311,7 @@ impl MultiModelStreamingIndexer {
 
         let models = Arc::new(TokioMutex::new(models));
 
-        spawn_multi_model_index_loop(
+        let index_loop = spawn_multi_model_index_loop(
             indexer,
             config,
             event_rx,
@@ -210,6 +328,7 @@ impl MultiModelStreamingIndexer {
                 health_tx,
                 _watcher: watcher,
                 _health_guard: TokioMutex::new(health_rx),
+                index_loop: TokioMutex::new(Some(index_loop)),
                 models,
             }),
         })
@@ -253,6 +372,13 @@ impl MultiModelStreamingIndexer {
     pub fn health_stream(&self) -> watch::Receiver<IndexerHealth> {
         self.inner.health_tx.subscribe()
     }
+
+    /// Stop watching once the in-flight index cycle (if any) has finished, waiting at most
+    /// `timeout`. Returns `false` when the cycle was still running at the deadline; queued
+    /// events that have not started a cycle are dropped.
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        drain_index_loop(&self.inner.command_tx, &self.inner.index_loop, timeout).await
+    }
 }
 
 impl Drop for MultiModelStreamingIndexer {
@@ -263,6 +389,25 @@ impl Drop for MultiModelStreamingIndexer {
     }
 }
 
+async fn drain_index_loop(
+    command_tx: &mpsc::Sender<WatcherCommand>,
+    index_loop: &TokioMutex<Option<JoinHandle<()>>>,
+    timeout: Duration,
+) -> bool {
+    let deadline = time::Instant::now() + timeout;
+    let Some(handle) = index_loop.lock().await.take() else {
+        return true;
+    };
+    // The loop only reads commands between cycles, so this waits behind a running index.
+    if time::timeout_at(deadline, command_tx.send(WatcherCommand::Shutdown))
+        .await
+        .is_err()
+    {
+        return false;
+    }
+    time::timeout_at(deadline, handle).await.is_ok()
+}
+
 fn create_fs_watcher(
     root: &Path,
     sender: mpsc::Sender<notify::Result<Event>>,
@@ -290,10 +435,10 @@ fn spawn_index_loop(
     mut command_rx: mpsc::Receiver<WatcherCommand>,
     update_tx: broadcast::Sender<IndexUpdate>,
     health_tx: watch::Sender<IndexerHealth>,
-) {
+) -> JoinHandle<()> {
     tokio::spawn(async move {
-        let mut state = DebounceState::new(config.debounce, config.max_batch_wait);
-        let mut health = IndexerHealth::initial();
+        let mut state = DebounceState::new(&config);
+        let mut health = IndexerHealth::initial(&config);
         let mut duration_history: VecDeque<u64> = VecDeque::new();
         let mut alert_log: VecDeque<AlertRecord> = VecDeque::new();
 
@@ -304,6 +449,7 @@ fn spawn_index_loop(
                 Some(event) = event_rx.recv() => {
                     if handle_event(indexer.root(), event, &mut state) {
                         health.pending_events = state.pending();
+                        health.adaptive_debounce_ms = duration_ms(state.effective_debounce());
                         let _ = health_tx.send(health.clone());
                     }
                 }
@@ -356,14 +502,13 @@ fn spawn_index_loop(
                                 warn!("Failed to persist health snapshot after watcher index: {err}");
                             }
                             let _ = health_tx.send(health.clone());
-                            let _ = update_tx.send(IndexUpdate {
-                                completed_at: SystemTime::now(),
-                                duration_ms: duration,
-                                stats: Some(cycle_stats.clone()),
-                                success: true,
+                            let _ = update_tx.send(IndexUpdate::succeeded(
+                                &cycle_stats,
+                                duration,
                                 reason,
-                                store_size_bytes: store_size,
-                            });
+                                store_size,
+                                config.max_changed_files,
+                            ));
                         }
                         Err((err, duration, reason)) => {
                             error!("Streaming index failure: {err}");
@@ -382,18 +527,18 @@ fn spawn_index_loop(
                             {
                                 warn!("Failed to persist failure reason: {e}");
                             }
-                            push_alert(&mut alert_log, "error", &reason, &err);
+                            raise_alert(
+                                &mut alert_log,
+                                &config,
+                                "error",
+                                &reason,
+                                &err,
+                                health.consecutive_failures,
+                            );
                             health.alert_log_json = serialize_alerts(&alert_log);
                             health.alert_log_len = alert_log.len();
                             let _ = health_tx.send(health.clone());
-                            let _ = update_tx.send(IndexUpdate {
-                                completed_at: SystemTime::now(),
-                                duration_ms: duration,
-                                stats: None,
-                                success: false,
-                                reason,
-                                store_size_bytes: None,
-                            });
+                            let _ = update_tx.send(IndexUpdate::failed(duration, reason));
                         }
                     }
 
@@ -401,7 +546,7 @@ fn spawn_index_loop(
                 }
             }
         }
-    });
+    })
 }
 
 #[allow(clippy::too_many_lines)]
@@ -413,10 +558,10 @@ fn spawn_multi_model_index_loop(
     update_tx: broadcast::Sender<IndexUpdate>,
     health_tx: watch::Sender<IndexerHealth>,
     models: Arc<TokioMutex<Vec<ModelIndexSpec>>>,
-) {
+) -> JoinHandle<()> {
     tokio::spawn(async move {
-        let mut state = DebounceState::new(config.debounce, config.max_batch_wait);
-        let mut health = IndexerHealth::initial();
+        let mut state = DebounceState::new(&config);
+        let mut health = IndexerHealth::initial(&config);
         let mut duration_history: VecDeque<u64> = VecDeque::new();
         let mut alert_log: VecDeque<AlertRecord> = VecDeque::new();
 
@@ -427,6 +572,7 @@ fn spawn_multi_model_index_loop(
                 Some(event) = event_rx.recv() => {
                     if handle_event(indexer.root(), event, &mut state) {
                         health.pending_events = state.pending();
+                        health.adaptive_debounce_ms = duration_ms(state.effective_debounce());
                         let _ = health_tx.send(health.clone());
                     }
                 }
@@ -497,14 +643,13 @@ fn spawn_multi_model_index_loop(
                                 warn!("Failed to persist health snapshot after watcher index: {err}");
                             }
                             let _ = health_tx.send(health.clone());
-                            let _ = update_tx.send(IndexUpdate {
-                                completed_at: SystemTime::now(),
-                                duration_ms: duration,
-                                stats: Some(cycle_stats.clone()),
-                                success: true,
+                            let _ = update_tx.send(IndexUpdate::succeeded(
+                                &cycle_stats,
+                                duration,
                                 reason,
-                                store_size_bytes: store_size,
-                            });
+                                store_size,
+                                config.max_changed_files,
+                            ));
                         }
                         Err((err, duration, reason)) => {
                             error!("Streaming index failure: {err}");
@@ -523,18 +668,18 @@ fn spawn_multi_model_index_loop(
                             {
                                 warn!("Failed to persist failure reason: {e}");
                             }
-                            push_alert(&mut alert_log, "error", &reason, &err);
+                            raise_alert(
+                                &mut alert_log,
+                                &config,
+                                "error",
+                                &reason,
+                                &err,
+                                health.consecutive_failures,
+                            );
                             health.alert_log_json = serialize_alerts(&alert_log);
                             health.alert_log_len = alert_log.len();
                             let _ = health_tx.send(health.clone());
-                            let _ = update_tx.send(IndexUpdate {
-                                completed_at: SystemTime::now(),
-                                duration_ms: duration,
-                                stats: None,
-                                success: false,
-                                reason,
-                                store_size_bytes: None,
-                            });
+                            let _ = update_tx.send(IndexUpdate::failed(duration, reason));
                         }
                     }
 
@@ -542,7 +687,7 @@ fn spawn_multi_model_index_loop(
                 }
             }
         }
-    });
+    })
 }
 
 async fn run_index_cycle(
@@ -576,12 +721,23 @@ async fn run_multi_model_index_cycle(
 ) -> std::result::Result<(IndexStats, u64, String, Option<u64>), (String, u64, String)> {
     let started = Instant::now();
     match indexer.index_models(&models, false).await {
-        Ok(stats) => {
+        Ok(outcome) if outcome.all_failed() => {
+            #[allow(clippy::cast_possible_truncation)]
+            let duration = started.elapsed().as_millis() as u64;
+            let message = outcome
+                .failures()
+                .into_iter()
+                .map(|(model_id, err)| format!("{model_id}: {err}"))
+                .collect::<Vec<_>>()
+                .join("; ");
+            Err((message, duration, reason))
+        }
+        Ok(outcome) => {
             #[allow(clippy::cast_possible_truncation)]
             let duration = started.elapsed().as_millis() as u64;
             info!("Incremental multi-model index finished in {duration}ms");
             let store_size = sum_model_store_sizes(indexer.root(), &models).await;
-            Ok((stats, duration, reason, store_size))
+            Ok((outcome.stats, duration, reason, store_size))
         }
         Err(e) => {
             #[allow(clippy::cast_possible_truncation)]
@@ -646,6 +802,9 @@ fn handle_event(root: &Path, event: notify::Result<Event>, state: &mut DebounceS
 }
 
 fn is_relevant_path(root: &Path, path: &Path) -> bool {
+    /// Build output and VCS directories, ignored at any depth (`crates/x/target`,
+    /// `web/node_modules`): a build or install floods them with events.
+    const IGNORED_ANYWHERE: &[&str] = &[".git", ".context-finder", "target", "node_modules"];
     const IGNORED: &[&str] = &[
         ".git",
         ".hg",
@@ -677,6 +836,16 @@ fn is_relevant_path(root: &Path, path: &Path) -> bool {
             }
         }
 
+        let mut dirs = relative.components();
+        dirs.next_back();
+        if dirs.any(|dir| {
+            IGNORED_ANYWHERE
+                .iter()
+                .any(|ignored| dir.as_os_str() == *ignored)
+        }) {
+            return false;
+        }
+
         // ignore .gitignore anywhere
         if relative
             .file_name()
@@ -689,17 +858,18 @@ fn is_relevant_path(root: &Path, path: &Path) -> bool {
     true
 }
 
-#[derive(Debug, Serialize)]
-struct AlertRecord {
-    timestamp_unix_ms: u64,
-    level: String,
-    reason: String,
-    detail: String,
-}
-
 struct DebounceState {
     debounce: Duration,
     max_batch: Duration,
+    max_debounce: Duration,
+    burst_events: usize,
+    /// Quiet period currently required before a run; grows while events arrive in bursts
+    effective_debounce: Duration,
+    /// Relevant events since `window_start`, to measure the event rate per `debounce`
+    window_events: usize,
+    window_start: Option<Instant>,
+    /// Last relevant event, kept across runs so a burst spanning a run stays a burst
+    last_seen: Option<Instant>,
     dirty: bool,
     pending: usize,
     last_event: Option<Instant>,
@@ -711,10 +881,16 @@ struct DebounceState {
 }
 
 impl DebounceState {
-    const fn new(debounce: Duration, max_batch: Duration) -> Self {
+    fn new(config: &StreamingIndexerConfig) -> Self {
         Self {
-            debounce,
-            max_batch,
+            debounce: config.debounce,
+            max_batch: config.max_batch_wait,
+            max_debounce: config.max_debounce.max(config.debounce),
+            burst_events: config.burst_events.max(1),
+            effective_debounce: config.debounce,
+            window_events: 0,
+            window_start: None,
+            last_seen: None,
             dirty: false,
             pending: 0,
             last_event: None,
@@ -727,13 +903,57 @@ impl DebounceState {
     }
 
     fn record_event(&mut self, count: usize, reason: &str) {
+        self.record_event_at(count, reason, Instant::now());
+    }
+
+    fn record_event_at(&mut self, count: usize, reason: &str, now: Instant) {
+        self.adapt(count.max(1), now);
         self.pending += count.max(1);
         self.reason = Some(reason.to_string());
-        self.last_event = Some(Instant::now());
-        self.first_event.get_or_insert_with(Instant::now);
+        self.last_event = Some(now);
+        self.first_event.get_or_insert(now);
         self.dirty = true;
     }
 
+    /// Doubles the effective debounce (up to `max_debounce`) each time `burst_events` events
+    /// land within one `debounce`; a quiet period as long as the effective debounce resets it.
+    fn adapt(&mut self, count: usize, now: Instant) {
+        if self
+            .last_seen
+            .is_none_or(|last| now.duration_since(last) >= self.effective_debounce)
+        {
+            self.effective_debounce = self.debounce;
+            self.window_start = None;
+        }
+        self.last_seen = Some(now);
+
+        let window_start = *self.window_start.get_or_insert(now);
+        if now.duration_since(window_start) >= self.debounce {
+            self.window_start = Some(now);
+            self.window_events = 0;
+        }
+        self.window_events += count;
+        if self.window_events >= self.burst_events {
+            let grown = self
+                .effective_debounce
+                .saturating_mul(2)
+                .min(self.max_debounce);
+            if grown > self.effective_debounce {
+                debug!(
+                    "Watcher event burst: debounce {:?} -> {grown:?}",
+                    self.effective_debounce
+                );
+            }
+            self.effective_debounce = grown;
+            self.window_start = Some(now);
+            self.window_events = 0;
+        }
+    }
+
+    const fn effective_debounce(&self) -> Duration {
+        self.effective_debounce
+    }
+
     fn force_run(&mut self, reason: String) {
         self.pending += 1;
         self.reason = Some(reason);
@@ -750,18 +970,26 @@ impl DebounceState {
     }
 
     fn next_deadline(&self) -> Option<time::Instant> {
-        if !self.dirty {
-            return None;
+        if self.force_immediate && self.dirty {
+            return Some(time::Instant::now());
         }
+        self.deadline().map(time::Instant::from_std)
+    }
 
-        if self.force_immediate {
-            return Some(time::Instant::now());
+    /// When the pending batch runs: after `effective_debounce` without events, or at the
+    /// latest `max_batch` after its first event (plus `max_debounce` during a burst).
+    fn deadline(&self) -> Option<Instant> {
+        if !self.dirty {
+            return None;
         }
 
-        let mut deadline = self.last_event.map(|last| last + self.debounce);
+        let mut deadline = self.last_event.map(|last| last + self.effective_debounce);
 
         if let Some(first) = self.first_event {
-            let forced = first + self.max_batch;
+            let mut forced = first + self.max_batch;
+            if self.effective_debounce > self.debounce {
+                forced += self.max_debounce;
+            }
             deadline = Some(match deadline {
                 Some(current) if forced < current => forced,
                 Some(current) => current,
@@ -769,7 +997,7 @@ impl DebounceState {
             });
         }
 
-        deadline.map(time::Instant::from_std)
+        deadline
     }
 
     #[allow(clippy::missing_const_for_fn)]
@@ -825,14 +1053,35 @@ fn compute_p95(history: &VecDeque<u64>) -> Option<u64> {
     sorted.get(idx).copied()
 }
 
-fn push_alert(log: &mut VecDeque<AlertRecord>, level: &str, reason: &str, detail: &str) {
+/// Append an alert to `log` and hand it to `config.on_alert` once the failure streak reaches
+/// `alert_after_failures`. The hook runs on its own task, so a slow, failing or panicking hook
+/// never stalls the index loop.
+fn raise_alert(
+    log: &mut VecDeque<AlertRecord>,
+    config: &StreamingIndexerConfig,
+    level: &str,
+    reason: &str,
+    detail: &str,
+    consecutive_failures: u32,
+) {
     const MAX_ALERTS: usize = 20;
     let record = AlertRecord {
         timestamp_unix_ms: current_unix_ms(),
         level: level.to_string(),
         reason: reason.to_string(),
         detail: detail.to_string(),
+        consecutive_failures,
     };
+    if let Some(hook) = config.on_alert.clone() {
+        if consecutive_failures >= config.alert_after_failures {
+            let record = record.clone();
+            tokio::spawn(async move {
+                if let Err(err) = hook(record).await {
+                    warn!("Watcher alert hook failed: {err}");
+                }
+            });
+        }
+    }
     log.push_back(record);
     if log.len() > MAX_ALERTS {
         log.pop_front();
@@ -843,6 +1092,10 @@ fn serialize_alerts(log: &VecDeque<AlertRecord>) -> String {
     serde_json::to_string(log).unwrap_or_else(|_| "[]".to_string())
 }
 
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
 fn current_unix_ms() -> u64 {
     SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -853,12 +1106,50 @@ fn current_unix_ms() -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use super::DebounceState;
-    use std::time::Duration;
+    use super::{is_relevant_path, DebounceState, StreamingIndexerConfig};
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+
+    fn config(debounce: Duration, max_batch_wait: Duration) -> StreamingIndexerConfig {
+        StreamingIndexerConfig {
+            debounce,
+            max_batch_wait,
+            ..StreamingIndexerConfig::default()
+        }
+    }
+
+    /// Feeds `events` (offsets from a common start) to the debouncer the way the index loop
+    /// does and returns the offsets at which index cycles would start.
+    fn simulate_cycles(
+        state: &mut DebounceState,
+        events: &[Duration],
+        until: Duration,
+    ) -> Vec<Duration> {
+        let start = Instant::now();
+        let step = Duration::from_millis(5);
+        let mut cycles = Vec::new();
+        let mut events = events.iter().peekable();
+        let mut now = Duration::ZERO;
+        while now <= until {
+            if state
+                .deadline()
+                .is_some_and(|deadline| deadline <= start + now)
+            {
+                cycles.push(now);
+                state.reset();
+            }
+            while events.next_if(|offset| **offset <= now).is_some() {
+                state.record_event_at(1, "fs_event", start + now);
+            }
+            now += step;
+        }
+        cycles
+    }
 
     #[test]
     fn debounce_generates_deadline() {
-        let mut state = DebounceState::new(Duration::from_millis(100), Duration::from_secs(1));
+        let mut state =
+            DebounceState::new(&config(Duration::from_millis(100), Duration::from_secs(1)));
         state.record_event(1, "fs_event");
         assert!(state.should_run());
         assert!(state.next_deadline().is_some());
@@ -866,10 +1157,50 @@ mod tests {
 
     #[test]
     fn force_run_sets_immediate_deadline() {
-        let mut state = DebounceState::new(Duration::from_secs(5), Duration::from_secs(10));
+        let mut state =
+            DebounceState::new(&config(Duration::from_secs(5), Duration::from_secs(10)));
         state.force_run("manual".to_string());
         assert!(state.should_run());
         assert!(state.force_flag());
         assert!(state.next_deadline().is_some());
     }
+
+    #[test]
+    fn event_storm_results_in_a_single_index_cycle() {
+        // Five seconds of events every 5 ms, longer than `max_batch_wait`.
+        let storm: Vec<Duration> = (0..1_000).map(|i| Duration::from_millis(i * 5)).collect();
+        let config = StreamingIndexerConfig::default();
+        let mut state = DebounceState::new(&config);
+
+        let cycles = simulate_cycles(&mut state, &storm, Duration::from_secs(20));
+        assert_eq!(cycles.len(), 1, "{cycles:?}");
+        assert!(cycles[0] >= Duration::from_secs(5), "{cycles:?}");
+        assert!(state.effective_debounce() > config.debounce);
+    }
+
+    #[test]
+    fn sparse_events_keep_the_configured_debounce() {
+        let config = StreamingIndexerConfig::default();
+        let mut state = DebounceState::new(&config);
+        let edits: Vec<Duration> = (0..5).map(|i| Duration::from_secs(i * 2)).collect();
+
+        let cycles = simulate_cycles(&mut state, &edits, Duration::from_secs(10));
+        assert_eq!(cycles.len(), 5, "{cycles:?}");
+        assert_eq!(state.effective_debounce(), config.debounce);
+    }
+
+    #[test]
+    fn nested_build_output_is_not_relevant() {
+        let root = Path::new("/repo");
+        assert!(!is_relevant_path(
+            root,
+            Path::new("/repo/crates/a/target/debug/x.o")
+        ));
+        assert!(!is_relevant_path(
+            root,
+            Path::new("/repo/web/node_modules/x/index.js")
+        ));
+        assert!(is_relevant_path(root, Path::new("/repo/src/target.rs")));
+        assert!(is_relevant_path(root, Path::new("/repo/src/build/mod.rs")));
+    }
 }
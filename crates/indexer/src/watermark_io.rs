@@ -1,5 +1,5 @@
 use crate::scanner::FileScanner;
-use crate::{IndexerError, Result, Watermark};
+use crate::{IndexProvenance, IndexerError, Result, Watermark};
 use serde::{Deserialize, Serialize};
 use std::cmp::max;
 use std::path::{Path, PathBuf};
@@ -11,6 +11,9 @@ const INDEX_WATERMARK_FILE_NAME: &str = "watermark.json";
 pub struct PersistedIndexWatermark {
     pub built_at_unix_ms: u64,
     pub watermark: Watermark,
+    /// Absent for indexes written before provenance was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<IndexProvenance>,
 }
 
 pub fn index_watermark_path_for_store(store_path: &Path) -> Result<PathBuf> {
@@ -20,7 +23,11 @@ pub fn index_watermark_path_for_store(store_path: &Path) -> Result<PathBuf> {
     Ok(dir.join(INDEX_WATERMARK_FILE_NAME))
 }
 
-pub async fn write_index_watermark(store_path: &Path, watermark: Watermark) -> Result<()> {
+pub async fn write_index_watermark(
+    store_path: &Path,
+    watermark: Watermark,
+    provenance: IndexProvenance,
+) -> Result<()> {
     let path = index_watermark_path_for_store(store_path)?;
     if let Some(parent) = path.parent() {
         tokio::fs::create_dir_all(parent).await?;
@@ -30,6 +37,7 @@ pub async fn write_index_watermark(store_path: &Path, watermark: Watermark) -> R
     let persisted = PersistedIndexWatermark {
         built_at_unix_ms,
         watermark,
+        provenance: Some(provenance),
     };
 
     let bytes = serde_json::to_vec_pretty(&persisted)?;
@@ -126,7 +134,7 @@ async fn compute_filesystem_watermark(project_root: &Path) -> Result<Watermark>
     .map_err(|e| IndexerError::Other(format!("failed to compute filesystem watermark: {e}")))?
 }
 
-fn unix_now_ms() -> u64 {
+pub(crate) fn unix_now_ms() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
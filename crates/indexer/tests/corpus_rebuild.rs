@@ -43,6 +43,9 @@ pub fn world() {
     )
     .await
     .expect("write file");
+    tokio::fs::write(src_dir.join("old.rs"), "pub fn old() {}\n")
+        .await
+        .expect("write old file");
 
     let indexer = ProjectIndexer::new(temp.path()).await.expect("indexer");
     indexer.index_full().await.expect("initial index");
@@ -57,8 +60,12 @@ pub fn world() {
     tokio::fs::remove_file(corpus_path(temp.path()))
         .await
         .expect("delete corpus");
+    tokio::fs::remove_file(src_dir.join("old.rs"))
+        .await
+        .expect("delete old file");
 
-    indexer.index().await.expect("incremental rebuild");
+    let stats = indexer.index().await.expect("incremental rebuild");
+    assert_eq!(stats.deleted_files, vec!["src/old.rs".to_string()]);
 
     let index = VectorIndex::load(&path).await.expect("load rebuilt index");
     assert!(
@@ -90,6 +97,9 @@ pub fn world() {
     )
     .await
     .expect("write file");
+    tokio::fs::write(src_dir.join("old.rs"), "pub fn old() {}\n")
+        .await
+        .expect("write old file");
 
     let indexer = MultiModelProjectIndexer::new(temp.path())
         .await
@@ -109,11 +119,15 @@ pub fn world() {
     tokio::fs::remove_file(corpus_path(temp.path()))
         .await
         .expect("delete corpus");
+    tokio::fs::remove_file(src_dir.join("old.rs"))
+        .await
+        .expect("delete old file");
 
-    indexer
+    let stats = indexer
         .index_models(&models, false)
         .await
         .expect("incremental rebuild");
+    assert_eq!(stats.stats.deleted_files, vec!["src/old.rs".to_string()]);
 
     for model_id in ["bge-small", "multilingual-e5-small"] {
         let path = index_path(temp.path(), model_id);
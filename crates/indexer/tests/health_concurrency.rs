@@ -0,0 +1,63 @@
+use context_indexer::{
+    append_failure_reason, health_file_path, read_health_snapshot, write_health_snapshot,
+    HealthSnapshot, IndexStats,
+};
+use tempfile::TempDir;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_health_writers_never_corrupt_snapshot() {
+    std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+
+    let temp = TempDir::new().expect("tempdir");
+    let root = temp.path().to_path_buf();
+
+    let success_root = root.clone();
+    let success_writer = tokio::spawn(async move {
+        let mut stats = IndexStats::new();
+        stats.add_file("rust", 10, 2);
+        for _ in 0..50 {
+            write_health_snapshot(&success_root, &stats, "watcher", Some(5), Some(0))
+                .await
+                .expect("write snapshot");
+        }
+    });
+
+    let failure_root = root.clone();
+    let failure_writer = tokio::spawn(async move {
+        for idx in 0..50 {
+            append_failure_reason(&failure_root, "cli", &format!("attempt {idx}"), None)
+                .await
+                .expect("append failure");
+        }
+    });
+
+    let reader_root = root.clone();
+    let reader = tokio::spawn(async move {
+        let path = health_file_path(&reader_root);
+        for _ in 0..200 {
+            if let Ok(bytes) = tokio::fs::read(&path).await {
+                serde_json::from_slice::<HealthSnapshot>(&bytes)
+                    .expect("health.json must always be valid JSON");
+            }
+            tokio::task::yield_now().await;
+        }
+    });
+
+    success_writer.await.expect("success writer");
+    failure_writer.await.expect("failure writer");
+    reader.await.expect("reader");
+
+    let snapshot = read_health_snapshot(&root)
+        .await
+        .expect("read snapshot")
+        .expect("snapshot exists");
+    assert!(snapshot.failure_reasons.len() <= 5);
+
+    let mut leftovers = tokio::fs::read_dir(root.join(".context-finder"))
+        .await
+        .expect("read dir");
+    while let Some(entry) = leftovers.next_entry().await.expect("dir entry") {
+        let name = entry.file_name().to_string_lossy().to_string();
+        assert!(!name.ends_with(".tmp"), "temp file left behind: {name}");
+    }
+}
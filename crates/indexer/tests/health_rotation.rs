@@ -0,0 +1,98 @@
+use context_indexer::{
+    append_failure_reason, health_file_path, read_health_snapshot, write_health_snapshot,
+    IndexStats,
+};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn repeated_failures_are_counted_and_survive_successes() {
+    std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+    let temp = TempDir::new().expect("tempdir");
+    let root = temp.path();
+
+    for _ in 0..37 {
+        append_failure_reason(root, "watcher", "embed timeout", None)
+            .await
+            .expect("append failure");
+    }
+    append_failure_reason(root, "cli", "model missing", None)
+        .await
+        .expect("append failure");
+
+    let mut stats = IndexStats::new();
+    stats.add_file("rust", 10, 2);
+    write_health_snapshot(root, &stats, "watcher", None, Some(0))
+        .await
+        .expect("write snapshot");
+    append_failure_reason(root, "watcher", "embed timeout", None)
+        .await
+        .expect("append failure");
+
+    let snapshot = read_health_snapshot(root)
+        .await
+        .expect("read snapshot")
+        .expect("snapshot exists");
+    assert_eq!(snapshot.failure_reasons, vec!["watcher: embed timeout"]);
+    let counts: Vec<(&str, usize)> = snapshot
+        .failure_reason_counts
+        .iter()
+        .map(|entry| (entry.reason.as_str(), entry.count))
+        .collect();
+    assert_eq!(
+        counts,
+        vec![("cli: model missing", 1), ("watcher: embed timeout", 38)]
+    );
+    let timeout = &snapshot.failure_reason_counts[1];
+    assert!(timeout.first_unix_ms <= timeout.last_unix_ms);
+}
+
+#[tokio::test]
+async fn distinct_failure_reasons_are_bounded() {
+    let temp = TempDir::new().expect("tempdir");
+    let root = temp.path();
+
+    for idx in 0..60 {
+        append_failure_reason(root, "cli", &format!("attempt {idx}"), None)
+            .await
+            .expect("append failure");
+    }
+
+    let snapshot = read_health_snapshot(root)
+        .await
+        .expect("read snapshot")
+        .expect("snapshot exists");
+    assert_eq!(snapshot.failure_reason_counts.len(), 50);
+    assert_eq!(snapshot.failure_reason_counts[0].reason, "cli: attempt 10");
+    assert_eq!(snapshot.failure_reason_counts[49].reason, "cli: attempt 59");
+    assert_eq!(snapshot.failure_reasons.len(), 5);
+}
+
+#[tokio::test]
+async fn oversized_legacy_snapshot_is_truncated_on_load() {
+    let temp = TempDir::new().expect("tempdir");
+    let root = temp.path();
+    let path = health_file_path(root);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let huge = "x".repeat(10_000);
+    let legacy = serde_json::json!({
+        "last_success_unix_ms": 1,
+        "reason": "watcher",
+        "failure_reasons": vec![format!("watcher: {huge}"); 500],
+        "failure_count": 500,
+    });
+    std::fs::write(&path, serde_json::to_vec(&legacy).unwrap()).unwrap();
+    let before = std::fs::metadata(&path).unwrap().len();
+
+    let snapshot = read_health_snapshot(root)
+        .await
+        .expect("read snapshot")
+        .expect("snapshot exists");
+    assert_eq!(snapshot.failure_reasons.len(), 5);
+    assert!(snapshot
+        .failure_reasons
+        .iter()
+        .all(|reason| reason.chars().count() <= 500));
+
+    let after = std::fs::metadata(&path).unwrap().len();
+    assert!(after < before / 100, "{before} -> {after}");
+}
@@ -0,0 +1,99 @@
+use context_indexer::ProjectIndexer;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn index_stats_language_histogram_sums_to_totals() {
+    std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+
+    let temp = TempDir::new().expect("tempdir");
+    let src = temp.path().join("src");
+    tokio::fs::create_dir_all(&src).await.expect("create src");
+    tokio::fs::write(
+        src.join("lib.rs"),
+        "pub fn alpha(values: &[u32]) -> u32 {\n    let total: u32 = values.iter().sum();\n    total + 1\n}\n",
+    )
+    .await
+    .expect("write rust");
+    tokio::fs::write(
+        src.join("util.rs"),
+        "pub fn beta(name: &str) -> String {\n    let greeting = format!(\"hello {name}\");\n    greeting.to_uppercase()\n}\n",
+    )
+    .await
+    .expect("write rust");
+    tokio::fs::write(
+        src.join("tool.py"),
+        "def gamma(values):\n    total = sum(values)\n    return total * 3\n",
+    )
+    .await
+    .expect("write python");
+    tokio::fs::write(
+        src.join("app.ts"),
+        "export function delta(): number {\n  return 4;\n}\n",
+    )
+    .await
+    .expect("write typescript");
+
+    let indexer = ProjectIndexer::new(temp.path()).await.expect("indexer");
+    let stats = indexer.index_full().await.expect("index");
+
+    assert!(stats.languages.len() >= 3, "{:?}", stats.languages);
+    assert_eq!(stats.languages.get("rust").map(|s| s.files), Some(2));
+    assert_eq!(
+        stats.languages.values().map(|s| s.files).sum::<usize>(),
+        stats.files
+    );
+    assert_eq!(
+        stats.languages.values().map(|s| s.lines).sum::<usize>(),
+        stats.total_lines
+    );
+    assert_eq!(
+        stats.languages.values().map(|s| s.chunks).sum::<usize>(),
+        stats.chunks
+    );
+}
+
+#[tokio::test]
+async fn plan_reports_index_work_without_writing_index() {
+    std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+
+    let temp = TempDir::new().expect("tempdir");
+    let src = temp.path().join("src");
+    tokio::fs::create_dir_all(&src).await.expect("create src");
+    tokio::fs::write(
+        src.join("lib.rs"),
+        "pub fn alpha(values: &[u32]) -> u32 {\n    let total: u32 = values.iter().sum();\n    total + 1\n}\n",
+    )
+    .await
+    .expect("write rust");
+    tokio::fs::write(
+        src.join("tool.py"),
+        "def gamma(values):\n    total = sum(values)\n    return total * 3\n",
+    )
+    .await
+    .expect("write python");
+
+    let indexer = ProjectIndexer::new(temp.path()).await.expect("indexer");
+    let plan = indexer.plan().await.expect("plan");
+    let index_json = temp
+        .path()
+        .join(".context-finder")
+        .join("indexes")
+        .join("bge-small")
+        .join("index.json");
+    assert!(!index_json.exists(), "dry run must not write index.json");
+    assert!(!plan.incremental);
+
+    let stats = indexer.index().await.expect("index");
+    assert!(index_json.exists());
+    assert_eq!(plan.files, stats.files);
+    assert_eq!(plan.estimated_chunks, stats.chunks);
+    assert_eq!(
+        plan.languages.values().map(|s| s.files).sum::<usize>(),
+        plan.files
+    );
+
+    let replan = indexer.plan().await.expect("replan");
+    assert!(replan.incremental);
+    assert_eq!(replan.files, 0);
+    assert_eq!(replan.unchanged_files, replan.total_files);
+}
@@ -0,0 +1,61 @@
+use context_indexer::{ModelIndexSpec, MultiModelProjectIndexer};
+use context_vector_store::{EmbeddingTemplates, VectorIndex};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn invalid_model_does_not_abort_the_others() {
+    std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+
+    let temp = TempDir::new().expect("tempdir");
+    let src_dir = temp.path().join("src");
+    tokio::fs::create_dir_all(&src_dir)
+        .await
+        .expect("create src");
+    tokio::fs::write(
+        src_dir.join("lib.rs"),
+        "/// Greets the caller by name.\npub fn hello(name: &str) -> String {\n    let greeting = format!(\"hello {name}\");\n    greeting\n}\n",
+    )
+    .await
+    .expect("write file");
+
+    let indexer = MultiModelProjectIndexer::new(temp.path())
+        .await
+        .expect("multimodel indexer");
+    let templates = EmbeddingTemplates::default();
+    let models = vec![
+        ModelIndexSpec::new("no-such-model", templates.clone()),
+        ModelIndexSpec::new("bge-small", templates),
+    ];
+
+    let outcome = indexer
+        .index_models(&models, true)
+        .await
+        .expect("shared indexing work succeeds");
+
+    assert!(!outcome.all_failed());
+    let failures = outcome.failures();
+    assert_eq!(failures.len(), 1, "{failures:?}");
+    assert_eq!(failures[0].0, "no-such-model");
+    assert!(outcome
+        .stats
+        .errors
+        .iter()
+        .any(|err| err.starts_with("model no-such-model:")));
+
+    let valid = outcome.per_model["bge-small"]
+        .as_ref()
+        .expect("valid model indexed");
+    assert!(valid.chunks_embedded > 0);
+
+    let path = temp
+        .path()
+        .join(".context-finder")
+        .join("indexes")
+        .join("bge-small")
+        .join("index.json");
+    let index = VectorIndex::load(&path).await.expect("load index");
+    assert!(index
+        .chunk_ids()
+        .iter()
+        .any(|chunk_id| chunk_id.starts_with("src/lib.rs:")));
+}
@@ -0,0 +1,54 @@
+use context_indexer::{ModelIndexSpec, MultiModelProjectIndexer};
+use context_vector_store::EmbeddingTemplates;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn aliased_models_embed_each_chunk_once() {
+    std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+
+    let temp = TempDir::new().expect("tempdir");
+    let src_dir = temp.path().join("src");
+    tokio::fs::create_dir_all(&src_dir)
+        .await
+        .expect("create src");
+    tokio::fs::write(
+        src_dir.join("lib.rs"),
+        "/// Greets the caller by name.\npub fn hello(name: &str) -> String {\n    let greeting = format!(\"hello {name}\");\n    greeting\n}\n\n/// Answers the question.\npub fn world() -> u32 {\n    let answer = 40;\n    answer + 2\n}\n",
+    )
+    .await
+    .expect("write file");
+    tokio::fs::write(
+        src_dir.join("util.rs"),
+        "/// Shared helpers.\npub struct Util {\n    pub name: String,\n    pub retries: u32,\n}\n",
+    )
+    .await
+    .expect("write file");
+
+    let indexer = MultiModelProjectIndexer::new(temp.path())
+        .await
+        .expect("multimodel indexer");
+    let templates = EmbeddingTemplates::default();
+    let models = vec![
+        ModelIndexSpec::new("bge-small", templates.clone()),
+        ModelIndexSpec::new("bge-small-en-v1.5", templates),
+    ];
+
+    let outcome = indexer
+        .index_models(&models, true)
+        .await
+        .expect("index models");
+    assert!(outcome.failures().is_empty(), "{:?}", outcome.failures());
+
+    let primary = outcome.per_model["bge-small"].as_ref().expect("bge-small");
+    let alias = outcome.per_model["bge-small-en-v1.5"]
+        .as_ref()
+        .expect("alias");
+    let unique_chunks = primary.chunks_embedded;
+    assert!(unique_chunks > 0);
+    assert_eq!(alias.chunks_embedded, unique_chunks);
+    assert_eq!(
+        primary.docs_embedded + alias.docs_embedded,
+        unique_chunks,
+        "the alias should reuse every vector the first spec computed"
+    );
+}
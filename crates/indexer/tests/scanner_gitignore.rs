@@ -0,0 +1,161 @@
+use context_indexer::FileScanner;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .expect("spawn git");
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn write(root: &Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn scanned(root: &Path, scanner: &FileScanner) -> Vec<String> {
+    scanner
+        .scan()
+        .iter()
+        .map(|p| {
+            p.strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect()
+}
+
+/// Files git would track (`git add -A`), minus the `.gitignore` files the scanner drops as noise.
+fn git_files(root: &Path) -> Vec<String> {
+    let mut files: Vec<String> = git(
+        root,
+        &["ls-files", "--cached", "--others", "--exclude-standard"],
+    )
+    .lines()
+    .filter(|line| !line.ends_with(".gitignore"))
+    .map(str::to_string)
+    .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn scan_matches_git_for_nested_negations() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    git(root, &["init", "-q"]);
+
+    write(
+        root,
+        ".gitignore",
+        "*.txt\n!keep.txt\nout/\ngen/*\n!gen/manual.rs\n/docs/*.md\n!/docs/README.md\n",
+    );
+    write(root, "nested/.gitignore", "*.rs\n!keep.rs\nprivate/\n");
+    write(root, "nested/deep/.gitignore", "!*.rs\n");
+    write(root, "nested/private/.gitignore", "!*.rs\n");
+    for file in [
+        "src/main.rs",
+        "notes.txt",
+        "keep.txt",
+        "sub/keep.txt",
+        "sub/other.txt",
+        "out/a.rs",
+        "gen/auto.rs",
+        "gen/manual.rs",
+        "docs/guide.md",
+        "docs/README.md",
+        "docs/sub/x.md",
+        "nested/a.rs",
+        "nested/keep.rs",
+        "nested/y.py",
+        "nested/deep/b.rs",
+        "nested/private/c.rs",
+    ] {
+        write(root, file, "// content\n");
+    }
+
+    let scanner = FileScanner::new(root);
+    let files = scanned(root, &scanner);
+    assert_eq!(files, git_files(root));
+    assert_eq!(
+        files,
+        vec![
+            "docs/README.md",
+            "docs/sub/x.md",
+            "gen/manual.rs",
+            "keep.txt",
+            "nested/deep/b.rs",
+            "nested/keep.rs",
+            "nested/y.py",
+            "src/main.rs",
+            "sub/keep.txt",
+        ]
+    );
+
+    // notes.txt, sub/other.txt, out/, gen/auto.rs, docs/guide.md, nested/a.rs, nested/private/
+    // plus the three reachable (hidden) .gitignore files.
+    let (_, stats) = scanner.scan_with_stats();
+    assert_eq!(stats.ignored_files, 10);
+    assert_eq!(stats.unreadable_files, 0);
+}
+
+#[test]
+fn scan_applies_repository_rules_above_the_scan_root() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    git(repo, &["init", "-q"]);
+    write(repo, ".gitignore", "*.gen.rs\n");
+    write(repo, ".git/info/exclude", "scratch.rs\n");
+    write(repo, "crate/.gitignore", "!keep.gen.rs\n");
+    for file in [
+        "crate/lib.rs",
+        "crate/skip.gen.rs",
+        "crate/keep.gen.rs",
+        "crate/scratch.rs",
+    ] {
+        write(repo, file, "// content\n");
+    }
+
+    let root = repo.join("crate");
+    let files = scanned(&root, &FileScanner::new(&root));
+    assert_eq!(files, vec!["keep.gen.rs", "lib.rs"]);
+
+    let expected: Vec<String> = git_files(repo)
+        .into_iter()
+        .filter_map(|file| file.strip_prefix("crate/").map(str::to_string))
+        .collect();
+    assert_eq!(files, expected);
+}
+
+#[test]
+fn scan_honors_dot_ignore_files_over_gitignore() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+    git(root, &["init", "-q"]);
+
+    write(root, ".gitignore", "*.gen.rs\n");
+    write(root, ".ignore", "fixtures/\n!keep.gen.rs\n");
+    write(root, "nested/.ignore", "local.rs\n");
+    for file in [
+        "src/lib.rs",
+        "skip.gen.rs",
+        "keep.gen.rs",
+        "fixtures/big.rs",
+        "nested/local.rs",
+        "nested/shared.rs",
+    ] {
+        write(root, file, "// content\n");
+    }
+
+    let files = scanned(root, &FileScanner::new(root));
+    assert_eq!(files, vec!["keep.gen.rs", "nested/shared.rs", "src/lib.rs"]);
+}
@@ -1,4 +1,4 @@
-use context_indexer::IndexUpdate;
+use context_indexer::{AlertHook, AlertRecord, IndexUpdate};
 use context_indexer::{ProjectIndexer, StreamingIndexer, StreamingIndexerConfig};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
@@ -40,6 +40,7 @@ async fn streaming_indexer_latency_under_two_seconds() {
         debounce: Duration::from_millis(200),
         max_batch_wait: Duration::from_secs(1),
         notify_poll_interval: Duration::from_millis(100),
+        ..StreamingIndexerConfig::default()
     };
     let streamer = match StreamingIndexer::start(indexer.clone(), cfg) {
         Ok(s) => s,
@@ -111,6 +112,7 @@ async fn streaming_indexer_soak_keeps_alert_log_empty() {
         debounce: Duration::from_millis(100),
         max_batch_wait: Duration::from_millis(400),
         notify_poll_interval: Duration::from_millis(50),
+        ..StreamingIndexerConfig::default()
     };
     let streamer = match StreamingIndexer::start(indexer.clone(), cfg) {
         Ok(s) => s,
@@ -201,6 +203,7 @@ async fn streaming_indexer_health_records_last_success() {
         debounce: Duration::from_millis(200),
         max_batch_wait: Duration::from_secs(1),
         notify_poll_interval: Duration::from_millis(100),
+        ..StreamingIndexerConfig::default()
     };
     let streamer = match StreamingIndexer::start(indexer.clone(), cfg) {
         Ok(s) => s,
@@ -238,6 +241,228 @@ async fn streaming_indexer_health_records_last_success() {
     );
 }
 
+#[cfg_attr(
+    not(target_os = "linux"),
+    ignore = "watcher latency test is only reliable on Linux"
+)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn streaming_indexer_alert_hook_fires_on_failure() {
+    if std::env::var("SKIP_WATCH_FLOW").is_ok() {
+        eprintln!("skipping watch_flow due to SKIP_WATCH_FLOW");
+        return;
+    }
+    if low_fd_limit() {
+        warn_skip_fd();
+        return;
+    }
+    ensure_ulimit();
+    std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+
+    let temp = TempDir::new().expect("tempdir");
+    let src_dir = temp.path().join("src");
+    tokio::fs::create_dir_all(&src_dir)
+        .await
+        .expect("create src");
+    tokio::fs::write(src_dir.join("lib.rs"), "fn noop() {}\n")
+        .await
+        .expect("write initial file");
+
+    let indexer = Arc::new(ProjectIndexer::new(temp.path()).await.expect("indexer"));
+    // A plain file where the index directory belongs makes every index cycle fail.
+    let index_dir = temp.path().join(".context-finder");
+    if index_dir.exists() {
+        tokio::fs::remove_dir_all(&index_dir)
+            .await
+            .expect("remove index dir");
+    }
+    tokio::fs::write(&index_dir, "not a directory")
+        .await
+        .expect("block index dir");
+    let (alert_tx, mut alert_rx) = tokio::sync::mpsc::unbounded_channel::<AlertRecord>();
+    let hook: AlertHook = Arc::new(move |record| {
+        let alert_tx = alert_tx.clone();
+        Box::pin(async move {
+            alert_tx.send(record).map_err(|err| err.to_string())?;
+            // A failing hook must not take the watcher down.
+            Err("webhook unreachable".to_string())
+        })
+    });
+    let cfg = StreamingIndexerConfig {
+        notify_poll_interval: Duration::from_millis(100),
+        on_alert: Some(hook),
+        ..StreamingIndexerConfig::default()
+    };
+    let streamer = match StreamingIndexer::start(indexer, cfg) {
+        Ok(s) => s,
+        Err(e) if e.to_string().contains("Too many open files") => {
+            warn_skip_fd();
+            return;
+        }
+        Err(e) => panic!("start streamer: {e}"),
+    };
+
+    for attempt in 1..=2 {
+        streamer.trigger("manual").await.expect("trigger");
+        let record = tokio::time::timeout(Duration::from_secs(4), alert_rx.recv())
+            .await
+            .expect("alert hook should fire")
+            .expect("alert record");
+        assert_eq!(record.level, "error");
+        assert_eq!(record.reason, "manual");
+        assert_eq!(record.consecutive_failures, attempt);
+    }
+
+    // The hook runs on its own task, so the health update may land just after it.
+    let mut health = streamer.health_stream();
+    let snapshot = tokio::time::timeout(
+        Duration::from_secs(2),
+        health.wait_for(|health| health.alert_log_len == 2),
+    )
+    .await
+    .expect("health update")
+    .expect("watcher running")
+    .clone();
+    assert_eq!(snapshot.consecutive_failures, 2);
+}
+
+#[cfg_attr(
+    not(target_os = "linux"),
+    ignore = "watcher latency test is only reliable on Linux"
+)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn streaming_indexer_updates_list_changed_and_deleted_files() {
+    if std::env::var("SKIP_WATCH_FLOW").is_ok() {
+        eprintln!("skipping watch_flow due to SKIP_WATCH_FLOW");
+        return;
+    }
+    if low_fd_limit() {
+        warn_skip_fd();
+        return;
+    }
+    ensure_ulimit();
+    std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+
+    let temp = TempDir::new().expect("tempdir");
+    let src_dir = temp.path().join("src");
+    tokio::fs::create_dir_all(&src_dir)
+        .await
+        .expect("create src");
+    for name in ["alpha.rs", "beta.rs", "gamma.rs"] {
+        tokio::fs::write(
+            src_dir.join(name),
+            format!("fn {}() {{}}\n", &name[..name.len() - 3]),
+        )
+        .await
+        .expect("write initial file");
+    }
+
+    let indexer = Arc::new(ProjectIndexer::new(temp.path()).await.expect("indexer"));
+    indexer.index_full().await.expect("initial index");
+
+    // Touch the tree before the watcher starts so a single manual cycle picks up every change.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    tokio::fs::write(src_dir.join("alpha.rs"), "fn alpha_updated() {}\n")
+        .await
+        .expect("update file");
+    tokio::fs::remove_file(src_dir.join("gamma.rs"))
+        .await
+        .expect("delete file");
+
+    let streamer = match StreamingIndexer::start(indexer, StreamingIndexerConfig::default()) {
+        Ok(s) => s,
+        Err(e) if e.to_string().contains("Too many open files") => {
+            warn_skip_fd();
+            return;
+        }
+        Err(e) => panic!("start streamer: {e}"),
+    };
+
+    // A cache that invalidates per file, the way a graph cache would subscribe.
+    let mut updates = streamer.subscribe_updates();
+    let subscriber = tokio::spawn(async move {
+        let update = wait_for_success(&mut updates, Duration::from_secs(4)).await?;
+        Some((
+            update.changed_files,
+            update.deleted_files,
+            update.changed_truncated,
+        ))
+    });
+    streamer.trigger("manual").await.expect("trigger");
+
+    let (changed, deleted, truncated) = subscriber
+        .await
+        .expect("subscriber task")
+        .expect("timeout waiting for update");
+    assert_eq!(changed, vec!["src/alpha.rs".to_string()]);
+    assert_eq!(deleted, vec!["src/gamma.rs".to_string()]);
+    assert!(!truncated);
+}
+
+#[cfg_attr(
+    not(target_os = "linux"),
+    ignore = "watcher latency test is only reliable on Linux"
+)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn streaming_indexer_caps_changed_files() {
+    if std::env::var("SKIP_WATCH_FLOW").is_ok() {
+        eprintln!("skipping watch_flow due to SKIP_WATCH_FLOW");
+        return;
+    }
+    if low_fd_limit() {
+        warn_skip_fd();
+        return;
+    }
+    ensure_ulimit();
+    std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+
+    let temp = TempDir::new().expect("tempdir");
+    let src_dir = temp.path().join("src");
+    tokio::fs::create_dir_all(&src_dir)
+        .await
+        .expect("create src");
+    for idx in 0..3 {
+        tokio::fs::write(src_dir.join(format!("mod_{idx}.rs")), "fn noop() {}\n")
+            .await
+            .expect("write initial file");
+    }
+
+    let indexer = Arc::new(ProjectIndexer::new(temp.path()).await.expect("indexer"));
+    indexer.index_full().await.expect("initial index");
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    for idx in 0..3 {
+        tokio::fs::write(
+            src_dir.join(format!("mod_{idx}.rs")),
+            format!("fn updated_{idx}() {{}}\n"),
+        )
+        .await
+        .expect("update file");
+    }
+
+    let cfg = StreamingIndexerConfig {
+        max_changed_files: 2,
+        ..StreamingIndexerConfig::default()
+    };
+    let streamer = match StreamingIndexer::start(indexer, cfg) {
+        Ok(s) => s,
+        Err(e) if e.to_string().contains("Too many open files") => {
+            warn_skip_fd();
+            return;
+        }
+        Err(e) => panic!("start streamer: {e}"),
+    };
+    let mut updates = streamer.subscribe_updates();
+    streamer.trigger("manual").await.expect("trigger");
+
+    let update = wait_for_success(&mut updates, Duration::from_secs(4))
+        .await
+        .expect("timeout waiting for update");
+    assert_eq!(update.changed_files, vec!["src/mod_0.rs", "src/mod_1.rs"]);
+    assert!(update.deleted_files.is_empty());
+    assert!(update.changed_truncated);
+    assert_eq!(update.stats.expect("stats").changed_files.len(), 3);
+}
+
 fn low_fd_limit() -> bool {
     rlimit::Resource::NOFILE
         .get()
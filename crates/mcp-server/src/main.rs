@@ -20,6 +20,7 @@
 //! - `overview` - Architecture snapshot (layers, entry points)
 //! - `map` - Project structure overview (directories, files, top symbols)
 //! - `index` - Index a project directory for semantic search
+//! - `warm` - Prewarm a project's engine, graph and embedder
 //! - `doctor` - Diagnose model/GPU/index configuration
 //!
 //! ## Usage
@@ -110,6 +111,7 @@ async fn main() -> Result<()> {
 
     // Create and start the MCP server
     let service = ContextFinderService::new();
+    service.spawn_startup_prewarm();
     let server = service.serve(stdio_hybrid_server()).await?;
 
     // Wait for shutdown
@@ -1,5 +1,7 @@
 use anyhow::Result;
-use context_protocol::{enforce_max_chars, finalize_used_chars, BudgetTruncation, ErrorEnvelope};
+use context_protocol::{
+    enforce_max_chars, finalize_used_chars, BudgetTruncation, ErrorCode, ErrorEnvelope,
+};
 use rmcp::model::CallToolResult;
 
 use super::schemas::batch::{
@@ -13,6 +15,13 @@ pub(super) fn resolve_batch_refs(
     context_batch_ref::resolve_batch_refs(input, ctx)
 }
 
+pub(super) fn evaluate_when(
+    when: &serde_json::Value,
+    ctx: &serde_json::Value,
+) -> Result<bool, String> {
+    context_batch_ref::evaluate_when(when, ctx)
+}
+
 pub(super) fn extract_path_from_input(input: &serde_json::Value) -> Option<String> {
     let serde_json::Value::Object(map) = input else {
         return None;
@@ -47,6 +56,7 @@ pub(super) fn prepare_item_input(
             | BatchToolName::FileSlice
             | BatchToolName::ListFiles
             | BatchToolName::GrepContext
+            | BatchToolName::Doctor
     ) {
         if let serde_json::Value::Object(ref mut map) = input {
             if !map.contains_key("max_chars") {
@@ -142,7 +152,7 @@ pub(super) fn push_item_or_truncate(
                 status: BatchItemStatus::Error,
                 message: Some(format!("Failed to compute batch budget: {err:#}")),
                 error: Some(ErrorEnvelope {
-                    code: "internal".to_string(),
+                    code: ErrorCode::Internal.to_string(),
                     message: format!("Failed to compute batch budget: {err:#}"),
                     details: None,
                     hint: None,
@@ -171,7 +181,7 @@ pub(super) fn push_item_or_truncate(
                 status: BatchItemStatus::Error,
                 message: Some(message.clone()),
                 error: Some(ErrorEnvelope {
-                    code: "invalid_request".to_string(),
+                    code: ErrorCode::InvalidRequest.to_string(),
                     message,
                     details: None,
                     hint: None,
@@ -75,6 +75,14 @@ pub(crate) const TOOL_CATALOG: &[ToolDescriptor] = &[
         name: "index",
         summary: "Build or refresh the semantic index.",
     },
+    ToolDescriptor {
+        name: "watch",
+        summary: "Streaming watcher: start/stop/status/trigger incremental reindex.",
+    },
+    ToolDescriptor {
+        name: "warm",
+        summary: "Prewarm engine/graph/embedder; reports per-stage timings.",
+    },
     ToolDescriptor {
         name: "doctor",
         summary: "Diagnostics for model/GPU/index state.",
@@ -0,0 +1,329 @@
+use context_code_chunker::CodeChunk;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Longest hunk snippet (in lines) per unit; larger uncovered hunks are split.
+const MAX_HUNK_SNIPPET_LINES: usize = 200;
+
+/// Files touched since a git ref, with new-side line ranges (1-based, inclusive).
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct GitChanges {
+    pub(super) files: BTreeMap<String, Vec<(usize, usize)>>,
+    pub(super) deleted: Vec<String>,
+}
+
+#[derive(Debug)]
+pub(super) enum GitDiffError {
+    /// `git` could not be spawned at all.
+    GitUnavailable(String),
+    /// The root is not inside a git work tree.
+    NotGitRepo,
+    /// `git diff` ran but failed (bad ref, corrupt repo, ...).
+    Failed(String),
+}
+
+/// One packable unit of a diff: a corpus chunk overlapping a hunk, or a raw hunk for files
+/// the corpus does not cover (new, unindexed, or non-code files).
+#[derive(Debug)]
+pub(super) enum DiffUnit {
+    Chunk {
+        chunk: Box<CodeChunk>,
+        ranges: Vec<(usize, usize)>,
+    },
+    Hunk {
+        file: String,
+        start_line: usize,
+        end_line: usize,
+    },
+}
+
+impl DiffUnit {
+    pub(super) fn file(&self) -> &str {
+        match self {
+            Self::Chunk { chunk, .. } => &chunk.file_path,
+            Self::Hunk { file, .. } => file,
+        }
+    }
+
+    pub(super) const fn start_line(&self) -> usize {
+        match self {
+            Self::Chunk { chunk, .. } => chunk.start_line,
+            Self::Hunk { start_line, .. } => *start_line,
+        }
+    }
+}
+
+/// Reject refs that could be parsed as git options or contain shell/pathspec oddities.
+pub(super) fn validate_since(since: &str) -> Result<(), String> {
+    if since.is_empty() {
+        return Err("since must not be empty".to_string());
+    }
+    if since.starts_with('-') {
+        return Err(format!(
+            "Invalid since ref '{since}': must not start with '-'"
+        ));
+    }
+    let valid = since.chars().all(|c| {
+        c.is_ascii_alphanumeric()
+            || matches!(c, '_' | '-' | '.' | '/' | '~' | '^' | '@' | '{' | '}')
+    });
+    if !valid {
+        return Err(format!(
+            "Invalid since ref '{since}': use a branch, tag, commit or relative ref like 'HEAD~5'"
+        ));
+    }
+    Ok(())
+}
+
+pub(super) async fn git_changes(root: &Path, since: &str) -> Result<GitChanges, GitDiffError> {
+    let probe = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+        .await
+        .map_err(|err| GitDiffError::GitUnavailable(err.to_string()))?;
+    if !probe.status.success() || String::from_utf8_lossy(&probe.stdout).trim() != "true" {
+        return Err(GitDiffError::NotGitRepo);
+    }
+
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "--relative",
+            "--unified=0",
+            "--no-color",
+            "--no-ext-diff",
+            since,
+            "--",
+        ])
+        .output()
+        .await
+        .map_err(|err| GitDiffError::GitUnavailable(err.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitDiffError::Failed(stderr.trim().to_string()));
+    }
+
+    Ok(parse_unified_zero(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git diff --unified=0` output into new-side line ranges per file.
+///
+/// Pure deletions (`+c,0`) are anchored on the line after which content was removed, so the
+/// enclosing chunk still counts as changed.
+fn parse_unified_zero(diff: &str) -> GitChanges {
+    let mut changes = GitChanges::default();
+    let mut old_path: Option<String> = None;
+    let mut current: Option<String> = None;
+
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("--- ") {
+            old_path = rest.strip_prefix("a/").map(str::to_string);
+            current = None;
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("+++ ") {
+            if let Some(path) = rest.strip_prefix("b/") {
+                changes.files.entry(path.to_string()).or_default();
+                current = Some(path.to_string());
+            } else if let Some(path) = old_path.take() {
+                changes.deleted.push(path);
+                current = None;
+            }
+            continue;
+        }
+        let Some(file) = current.as_ref() else {
+            continue;
+        };
+        let Some(header) = line.strip_prefix("@@ ") else {
+            continue;
+        };
+        let Some(new_side) = header.split_whitespace().find(|part| part.starts_with('+')) else {
+            continue;
+        };
+        let new_side = &new_side[1..];
+        let (start, count) = match new_side.split_once(',') {
+            Some((start, count)) => (start.parse::<usize>(), count.parse::<usize>()),
+            None => (new_side.parse::<usize>(), Ok(1)),
+        };
+        let (Ok(start), Ok(count)) = (start, count) else {
+            continue;
+        };
+        let range = if count == 0 {
+            (start.max(1), start.max(1))
+        } else {
+            (start.max(1), start.max(1) + count - 1)
+        };
+        if let Some(ranges) = changes.files.get_mut(file) {
+            ranges.push(range);
+        }
+    }
+
+    changes.files.retain(|_, ranges| !ranges.is_empty());
+    changes
+}
+
+/// Map changed line ranges onto corpus chunks; hunks no chunk covers become raw hunk units.
+/// Units are ordered by file, then start line.
+pub(super) fn diff_units(changes: &GitChanges, chunks: &[CodeChunk]) -> Vec<DiffUnit> {
+    let mut units: Vec<DiffUnit> = Vec::new();
+    let mut covered: BTreeMap<&str, Vec<bool>> = changes
+        .files
+        .iter()
+        .map(|(file, ranges)| (file.as_str(), vec![false; ranges.len()]))
+        .collect();
+
+    for chunk in chunks {
+        let Some(ranges) = changes.files.get(&chunk.file_path) else {
+            continue;
+        };
+        let mut hit: Vec<(usize, usize)> = Vec::new();
+        for (idx, &(start, end)) in ranges.iter().enumerate() {
+            if chunk.start_line <= end && chunk.end_line >= start {
+                hit.push((start, end));
+                if let Some(flags) = covered.get_mut(chunk.file_path.as_str()) {
+                    flags[idx] = true;
+                }
+            }
+        }
+        if !hit.is_empty() {
+            units.push(DiffUnit::Chunk {
+                chunk: Box::new(chunk.clone()),
+                ranges: hit,
+            });
+        }
+    }
+
+    for (file, ranges) in &changes.files {
+        let flags = covered.get(file.as_str());
+        for (idx, &(start, end)) in ranges.iter().enumerate() {
+            if flags.is_some_and(|flags| flags[idx]) {
+                continue;
+            }
+            for piece_start in (start..=end).step_by(MAX_HUNK_SNIPPET_LINES) {
+                units.push(DiffUnit::Hunk {
+                    file: file.clone(),
+                    start_line: piece_start,
+                    end_line: end.min(piece_start + MAX_HUNK_SNIPPET_LINES - 1),
+                });
+            }
+        }
+    }
+
+    units.sort_by(|a, b| {
+        a.file()
+            .cmp(b.file())
+            .then_with(|| a.start_line().cmp(&b.start_line()))
+    });
+    units
+}
+
+/// Read `start_line..=end_line` of a file under `root` (best-effort; missing lines are empty).
+pub(super) async fn read_hunk_lines(
+    root: &Path,
+    file: &str,
+    start_line: usize,
+    end_line: usize,
+) -> String {
+    let Ok(content) = tokio::fs::read_to_string(root.join(file)).await else {
+        return String::new();
+    };
+    content
+        .lines()
+        .skip(start_line.saturating_sub(1))
+        .take(end_line.saturating_sub(start_line) + 1)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_code_chunker::ChunkMetadata;
+
+    #[test]
+    fn parse_unified_zero_tracks_new_side_ranges_and_deletions() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3 +3 @@ fn a() {
+-    old();
++    new();
+@@ -10,2 +11,0 @@ fn b() {
+-    gone();
+-    gone();
+@@ -20,0 +20,3 @@
++fn c() {}
++
++fn d() {}
+diff --git a/old.rs b/old.rs
+deleted file mode 100644
+--- a/old.rs
++++ /dev/null
+@@ -1,2 +0,0 @@
+-fn old() {}
+-
+";
+        let changes = parse_unified_zero(diff);
+        assert_eq!(
+            changes.files.get("src/lib.rs"),
+            Some(&vec![(3, 3), (11, 11), (20, 22)])
+        );
+        assert_eq!(changes.deleted, vec!["old.rs".to_string()]);
+        assert_eq!(changes.files.len(), 1);
+    }
+
+    #[test]
+    fn diff_units_prefers_chunks_and_keeps_uncovered_hunks() {
+        let chunk = CodeChunk::new(
+            "src/lib.rs".to_string(),
+            1,
+            5,
+            "fn a() {\n    new();\n}".to_string(),
+            ChunkMetadata::default(),
+        );
+        let mut changes = GitChanges::default();
+        changes
+            .files
+            .insert("src/lib.rs".to_string(), vec![(3, 3), (40, 41)]);
+        changes
+            .files
+            .insert("notes.txt".to_string(), vec![(1, 250)]);
+
+        let units = diff_units(&changes, &[chunk]);
+        let summary: Vec<(String, usize, bool)> = units
+            .iter()
+            .map(|u| {
+                (
+                    u.file().to_string(),
+                    u.start_line(),
+                    matches!(u, DiffUnit::Chunk { .. }),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("notes.txt".to_string(), 1, false),
+                ("notes.txt".to_string(), 201, false),
+                ("src/lib.rs".to_string(), 1, true),
+                ("src/lib.rs".to_string(), 40, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_since_rejects_option_like_refs() {
+        assert!(validate_since("HEAD~5").is_ok());
+        assert!(validate_since("origin/main").is_ok());
+        assert!(validate_since("--output=x").is_err());
+        assert!(validate_since("HEAD; rm").is_err());
+    }
+}
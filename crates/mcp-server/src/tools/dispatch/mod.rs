@@ -3,18 +3,22 @@
 //! Provides semantic code search capabilities to AI agents via MCP protocol.
 
 use super::batch::{
-    compute_used_chars, extract_path_from_input, parse_tool_result_as_json, prepare_item_input,
-    push_item_or_truncate, resolve_batch_refs, trim_output_to_budget,
+    compute_used_chars, evaluate_when, extract_path_from_input, parse_tool_result_as_json,
+    prepare_item_input, push_item_or_truncate, resolve_batch_refs, trim_output_to_budget,
 };
 use super::catalog;
 use super::cursor::{decode_cursor, encode_cursor, CURSOR_VERSION};
+use super::diff_pack::{
+    diff_units, git_changes, read_hunk_lines, validate_since, DiffUnit, GitDiffError,
+};
 use super::file_slice::compute_file_slice_result;
 pub(super) use super::grep_context::finalize_grep_context_budget;
-use super::grep_context::{compute_grep_context_result, GrepContextComputeOptions};
+use super::grep_context::{compute_grep_context_result, GrepContextComputeOptions, GrepMatcher};
 pub(super) use super::list_files::finalize_list_files_budget;
-use super::list_files::{compute_list_files_result, decode_list_files_cursor};
+use super::list_files::{compute_list_files_result, decode_list_files_cursor, ListFilesView};
 use super::map::{compute_map_result, decode_map_cursor};
-use super::paths::normalize_relative_path;
+use super::metrics::{debug_metrics_enabled, ToolMetrics};
+use super::paths::{normalize_relative_path, AllowedRoots};
 use super::repo_onboarding_pack::compute_repo_onboarding_pack_result;
 use super::schemas::batch::{
     BatchBudget, BatchItemResult, BatchItemStatus, BatchRequest, BatchResult, BatchToolName,
@@ -23,61 +27,77 @@ use super::schemas::capabilities::CapabilitiesRequest;
 use super::schemas::context::{ContextHit, ContextRequest, ContextResult, RelatedCode};
 use super::schemas::context_pack::ContextPackRequest;
 use super::schemas::doctor::{
-    DoctorEnvResult, DoctorIndexDrift, DoctorModelStatus, DoctorProjectResult, DoctorRequest,
-    DoctorResult,
+    DoctorBudget, DoctorDiskBudget, DoctorEnvResult, DoctorGraphCacheStatus, DoctorIndexDrift,
+    DoctorIndexInfo, DoctorModelStatus, DoctorProjectResult, DoctorRequest, DoctorResult,
+    DoctorSection,
 };
 use super::schemas::explain::{ExplainRequest, ExplainResult};
 use super::schemas::file_slice::{FileSliceCursorV1, FileSliceRequest};
 use super::schemas::grep_context::{GrepContextCursorV1, GrepContextRequest};
-use super::schemas::impact::{ImpactRequest, ImpactResult, SymbolLocation, UsageInfo};
-use super::schemas::index::{IndexRequest, IndexResult};
-use super::schemas::list_files::ListFilesRequest;
+use super::schemas::impact::{ImpactRequest, ImpactResult};
+use super::schemas::index::{IndexLanguageStat, IndexPhaseStat, IndexRequest, IndexResult};
 #[cfg(test)]
-use super::schemas::list_files::ListFilesTruncation;
+use super::schemas::list_files::{ListFilesItems, ListFilesTruncation};
+use super::schemas::list_files::{ListFilesOrder, ListFilesRequest, ListFilesSort};
 use super::schemas::map::MapRequest;
-use super::schemas::overview::{
-    GraphStats, KeyTypeInfo, LayerInfo, OverviewRequest, OverviewResult, ProjectInfo,
-};
+use super::schemas::overview::{OverviewRequest, OverviewResult};
 use super::schemas::read_pack::{
-    ReadPackBudget, ReadPackIntent, ReadPackNextAction, ReadPackRequest, ReadPackResult,
-    ReadPackSection, ReadPackTruncation,
+    ReadPackBudget, ReadPackDiffCursorV1, ReadPackDiffItem, ReadPackDiffResult, ReadPackIntent,
+    ReadPackNextAction, ReadPackRequest, ReadPackResult, ReadPackSection, ReadPackSymbol,
+    ReadPackSymbolsCursorV1, ReadPackSymbolsResult, ReadPackTruncation,
 };
 use super::schemas::repo_onboarding_pack::RepoOnboardingPackRequest;
-pub(super) use super::schemas::search::{SearchRequest, SearchResponse, SearchResult};
+pub(super) use super::schemas::search::{
+    SearchCursorV1, SearchRequest, SearchResponse, SearchResult,
+};
 use super::schemas::text_search::{
     TextSearchCursorModeV1, TextSearchCursorV1, TextSearchMatch, TextSearchRequest,
     TextSearchResult,
 };
-use super::schemas::trace::{TraceRequest, TraceResult, TraceStep};
-use super::util::{path_has_extension_ignore_ascii_case, unix_ms};
+use super::schemas::trace::{TraceRequest, TraceResult};
+use super::schemas::warm::{
+    PrewarmState, PrewarmStatus, WarmRequest, WarmResult, WarmStage, WarmStageKind,
+};
+use super::schemas::watch::{WatchAction, WatchConfig, WatchHealth, WatchRequest, WatchResult};
+use super::symbols::{is_outline_glob, outline_file_symbols, symbol_outline_candidates};
+use super::util::unix_ms;
+use super::workspace::{load_workspace_members, select_workspace_members, WorkspaceMember};
 use crate::runtime_env;
 use anyhow::{Context as AnyhowContext, Result};
+use context_graph::analysis::{detect_graph_language, DetectedLanguage};
 use context_graph::{
-    build_graph_docs, CodeGraph, ContextAssembler, GraphDocConfig, GraphEdge, GraphLanguage,
-    GraphNode, RelationshipType, Symbol, GRAPH_DOC_VERSION,
+    build_graph_docs, ContextAssembler, GraphCacheFile, GraphDocConfig, GraphLanguage,
+    GRAPH_CACHE_FILE_NAME, GRAPH_DOC_VERSION,
 };
 use context_indexer::{
-    assess_staleness, compute_project_watermark, read_index_watermark, FileScanner, IndexSnapshot,
-    IndexState, IndexerError, PersistedIndexWatermark, ReindexAttempt, ReindexResult, ToolMeta,
+    assess_staleness, compute_project_watermark, current_index_provenance, read_index_watermark,
+    shared_reindex, FileScanner, IndexSnapshot, IndexState, IndexerError, PersistedIndexWatermark,
+    ReindexAttempt, ReindexResult, SharedReindex, ToolCallStats, ToolMeta, WatcherState,
     INDEX_STATE_SCHEMA_VERSION,
 };
-use context_protocol::{finalize_used_chars, BudgetTruncation};
+use context_protocol::{finalize_used_chars, BudgetTruncation, CodedError, ErrorCode};
 use context_search::{
-    ContextPackBudget, ContextPackItem, ContextPackOutput, MultiModelContextSearch,
-    MultiModelHybridSearch, QueryClassifier, QueryType, SearchProfile, CONTEXT_PACK_VERSION,
+    find_overlapping_item, merge_overlapping_span, pack_order, ContextPackBudget, ContextPackItem,
+    ContextPackOmitted, ContextPackOutput, MultiModelContextSearch, MultiModelHybridSearch,
+    OmittedItems, PackBudget, QueryClassifier, QueryType, SearchProfile, CONTEXT_PACK_VERSION,
 };
 use context_vector_store::{
     classify_path_kind, corpus_path_for_project_root, current_model_id, ChunkCorpus, DocumentKind,
     GraphNodeDoc, GraphNodeStore, GraphNodeStoreMeta, QueryKind, VectorIndex,
 };
-use rmcp::handler::server::tool::ToolRouter;
+use rmcp::handler::server::tool::{ToolCallContext, ToolRouter};
 use rmcp::handler::server::wrapper::Parameters;
-use rmcp::model::{CallToolResult, Content, Implementation, ServerCapabilities, ServerInfo};
-use rmcp::{tool, tool_handler, tool_router, ErrorData as McpError, ServerHandler};
-use serde::{Deserialize, Serialize};
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, Content, Implementation, ListToolsResult, Meta,
+    PaginatedRequestParam, ServerCapabilities, ServerInfo,
+};
+use rmcp::service::RequestContext;
+use rmcp::{tool, tool_router, ErrorData as McpError, Peer, RoleServer, ServerHandler};
+use serde::Deserialize;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::env;
 use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::Mutex;
@@ -110,16 +130,40 @@ impl ContextFinderService {
         Self::touch_daemon_best_effort(&root);
         Ok((root, root_display))
     }
+
+    /// Prewarm the `warm.on_start` projects from the launch root's config in the background.
+    pub fn spawn_startup_prewarm(&self) {
+        tokio::spawn(router::warm::run_startup_prewarm(self.clone()));
+    }
 }
 
 const DEFAULT_AUTO_INDEX_BUDGET_MS: u64 = 3_000;
 const MIN_AUTO_INDEX_BUDGET_MS: u64 = 100;
 const MAX_AUTO_INDEX_BUDGET_MS: u64 = 120_000;
 
+tokio::task_local! {
+    /// When the current request (e.g. a batch item with a time budget) must be done by
+    static REQUEST_DEADLINE: Instant;
+}
+
+/// Run `fut` with `deadline` as its request deadline, so auto-reindexing inside it never
+/// outlives the caller's budget.
+pub(in crate::tools::dispatch) async fn with_request_deadline<F: std::future::Future>(
+    deadline: Option<Instant>,
+    fut: F,
+) -> F::Output {
+    match deadline {
+        Some(deadline) => REQUEST_DEADLINE.scope(deadline, fut).await,
+        None => fut.await,
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub(in crate::tools::dispatch) struct AutoIndexPolicy {
     enabled: bool,
-    budget_ms: u64,
+    /// The auto-index budget counts from when the request arrived, capped by any enclosing
+    /// request deadline.
+    deadline: Instant,
 }
 
 impl AutoIndexPolicy {
@@ -131,7 +175,17 @@ impl AutoIndexPolicy {
         let budget_ms = auto_index_budget_ms
             .unwrap_or(DEFAULT_AUTO_INDEX_BUDGET_MS)
             .clamp(MIN_AUTO_INDEX_BUDGET_MS, MAX_AUTO_INDEX_BUDGET_MS);
-        Self { enabled, budget_ms }
+        let own = Instant::now() + Duration::from_millis(budget_ms);
+        let deadline = REQUEST_DEADLINE
+            .try_with(|request| own.min(*request))
+            .unwrap_or(own);
+        Self { enabled, deadline }
+    }
+
+    /// What is left of the request's budget for a reindex
+    fn remaining_ms(&self) -> u64 {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        u64::try_from(remaining.as_millis()).unwrap_or(u64::MAX)
     }
 }
 
@@ -149,7 +203,12 @@ fn load_profile_from_env() -> SearchProfile {
     let candidate_path = PathBuf::from(&profile_name);
     if candidate_path.exists() {
         match SearchProfile::from_file(&profile_name, &candidate_path) {
-            Ok(profile) => return profile,
+            Ok(profile) => {
+                for overlap in profile.path_weight_overlaps() {
+                    log::warn!("Profile {}: {overlap}", candidate_path.display());
+                }
+                return profile;
+            }
             Err(err) => {
                 log::warn!(
                     "Failed to load profile from {}: {err:#}; falling back to builtin 'quality'",
@@ -164,7 +223,6 @@ fn load_profile_from_env() -> SearchProfile {
     SearchProfile::builtin("quality").unwrap_or_else(SearchProfile::general)
 }
 
-#[tool_handler]
 impl ServerHandler for ContextFinderService {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -174,6 +232,31 @@ impl ServerHandler for ContextFinderService {
             ..Default::default()
         }
     }
+
+    // Hand-written instead of `#[tool_handler]` so every call feeds the metrics registry.
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let tool = request.name.to_string();
+        let started = Instant::now();
+        let _active = ActiveCall::new(&self.state.active_calls);
+        let result = self
+            .tool_router
+            .call(ToolCallContext::new(self, request, context))
+            .await;
+        self.state.metrics.record(&tool, started.elapsed(), &result);
+        result
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult::with_all_items(self.tool_router.list_all()))
+    }
 }
 
 impl ContextFinderService {
@@ -187,37 +270,105 @@ impl ContextFinderService {
         })?))
     }
 
+    /// Like [`Self::load_chunk_corpus`], but reads only the shards holding files `keep`
+    /// accepts.
+    pub(super) async fn load_chunk_corpus_matching(
+        root: &Path,
+        keep: impl Fn(&str) -> bool,
+    ) -> Result<Option<ChunkCorpus>> {
+        let path = corpus_path_for_project_root(root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(
+            ChunkCorpus::load_matching(&path, keep)
+                .await
+                .with_context(|| format!("Failed to load chunk corpus {}", path.display()))?,
+        ))
+    }
+
+    /// Sorted files of the chunk corpus, read from its manifest without any chunk text.
+    pub(super) async fn load_corpus_files(root: &Path) -> Result<Option<Vec<String>>> {
+        let path = corpus_path_for_project_root(root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(ChunkCorpus::list_files(&path).await.with_context(
+            || format!("Failed to load chunk corpus {}", path.display()),
+        )?))
+    }
+
     async fn tool_meta(&self, root: &Path) -> ToolMeta {
+        let watcher = Some(self.watcher_state(root).await);
+        let tool_stats = self.debug_tool_stats();
         match gather_index_state(root, &self.profile).await {
             Ok(index_state) => ToolMeta {
                 index_state: Some(index_state),
+                watcher,
+                tool_stats,
+                ..ToolMeta::default()
             },
             Err(err) => {
                 log::debug!("index_state unavailable for {}: {err:#}", root.display());
-                ToolMeta { index_state: None }
+                ToolMeta {
+                    watcher,
+                    tool_stats,
+                    ..ToolMeta::default()
+                }
             }
         }
     }
 
+    /// Per-tool stats for `meta`, only when `CONTEXT_FINDER_DEBUG_METRICS` is set.
+    fn debug_tool_stats(&self) -> Vec<ToolCallStats> {
+        if debug_metrics_enabled() {
+            self.state.metrics.snapshot()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// State of the `watch` tool's streaming watcher for `root` (inactive when none is running).
+    async fn watcher_state(&self, root: &Path) -> WatcherState {
+        self.state
+            .watchers
+            .lock()
+            .await
+            .get(root)
+            .map_or_else(WatcherState::default, router::watch::ActiveWatcher::state)
+    }
+
     async fn tool_meta_with_auto_index(&self, root: &Path, policy: AutoIndexPolicy) -> ToolMeta {
+        let watcher = Some(self.watcher_state(root).await);
         let mut index_state = match gather_index_state(root, &self.profile).await {
             Ok(state) => state,
             Err(err) => {
                 log::debug!("index_state unavailable for {}: {err:#}", root.display());
-                return ToolMeta { index_state: None };
+                return ToolMeta {
+                    watcher,
+                    tool_stats: self.debug_tool_stats(),
+                    ..ToolMeta::default()
+                };
             }
         };
 
+        let mut deadline_hit = None;
         if policy.enabled && (index_state.stale || !index_state.index.exists) {
-            let reindex = self.attempt_reindex(root, policy.budget_ms).await;
+            let reindex = self.shared_auto_reindex(root, policy.remaining_ms()).await;
             if let Ok(refreshed) = gather_index_state(root, &self.profile).await {
                 index_state = refreshed;
             }
-            index_state.reindex = Some(reindex);
+            index_state.reindex = Some(reindex.attempt);
+            deadline_hit = Some(reindex.deadline_hit);
         }
 
         ToolMeta {
             index_state: Some(index_state),
+            watcher,
+            tool_stats: self.debug_tool_stats(),
+            reindex_attempted: deadline_hit.map(|_| true),
+            reindex_deadline_hit: deadline_hit,
+            ..ToolMeta::default()
         }
     }
 
@@ -228,26 +379,34 @@ impl ContextFinderService {
     ) -> Result<(EngineLock, ToolMeta)> {
         let mut index_state = gather_index_state(root, &self.profile).await?;
         let mut attempt: Option<ReindexAttempt> = None;
+        let mut deadline_hit = None;
 
         if policy.enabled && (index_state.stale || !index_state.index.exists) {
-            let reindex = self.attempt_reindex(root, policy.budget_ms).await;
-            attempt = Some(reindex.clone());
+            let reindex = self.shared_auto_reindex(root, policy.remaining_ms()).await;
+            attempt = Some(reindex.attempt.clone());
+            deadline_hit = Some(reindex.deadline_hit);
             if let Ok(refreshed) = gather_index_state(root, &self.profile).await {
                 index_state = refreshed;
             }
-            index_state.reindex = Some(reindex);
+            index_state.reindex = Some(reindex.attempt);
         }
 
         if !index_state.index.exists {
-            return Err(anyhow::anyhow!(missing_index_message(
-                &index_state,
-                attempt.as_ref()
-            )));
+            return Err(CodedError::new(
+                ErrorCode::IndexMissing,
+                missing_index_message(&index_state, attempt.as_ref()),
+            )
+            .into());
         }
 
         let engine = self.lock_engine(root).await?;
         let meta = ToolMeta {
             index_state: Some(index_state),
+            watcher: Some(self.watcher_state(root).await),
+            tool_stats: self.debug_tool_stats(),
+            reindex_attempted: deadline_hit.map(|_| true),
+            reindex_deadline_hit: deadline_hit,
+            ..ToolMeta::default()
         };
         Ok((engine, meta))
     }
@@ -271,6 +430,16 @@ impl ContextFinderService {
         Ok(EngineLock { slot })
     }
 
+    /// Drop the on-disk graph cache and any graph held by the warm engine for `root`.
+    async fn clear_graph_cache(&self, root: &Path) -> Result<bool> {
+        let handle = self.state.engine_handle(root).await;
+        let mut slot = handle.lock().await;
+        if let Some(engine) = slot.engine.as_mut() {
+            engine.graph_language = None;
+        }
+        GraphCache::new(root).clear().await
+    }
+
     fn touch_daemon_best_effort(root: &Path) {
         let disable = std::env::var("CONTEXT_FINDER_DISABLE_DAEMON")
             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
@@ -291,6 +460,15 @@ impl ContextFinderService {
         });
     }
 
+    /// Auto-reindex `root` within `budget_ms`. Concurrent calls (including parallel batch
+    /// items) for the same root share one in-flight reindex instead of each starting their own.
+    async fn shared_auto_reindex(&self, root: &Path, budget_ms: u64) -> SharedReindex {
+        shared_reindex(root, Duration::from_millis(budget_ms), || {
+            self.attempt_reindex(root, budget_ms)
+        })
+        .await
+    }
+
     async fn attempt_reindex(&self, root: &Path, budget_ms: u64) -> ReindexAttempt {
         let start = Instant::now();
         let mut attempt = ReindexAttempt {
@@ -307,7 +485,7 @@ impl ContextFinderService {
             match context_indexer::ProjectIndexer::new_with_embedding_templates(root, templates)
                 .await
             {
-                Ok(indexer) => indexer,
+                Ok(indexer) => indexer.with_profile(self.profile.name()),
                 Err(err) => {
                     attempt.duration_ms = Some(start.elapsed().as_millis() as u64);
                     attempt.result = Some(ReindexResult::Failed);
@@ -373,10 +551,20 @@ async fn load_store_mtime(path: &Path) -> Result<SystemTime> {
 }
 
 async fn gather_index_state(root: &Path, profile: &SearchProfile) -> Result<IndexState> {
-    let project_watermark = compute_project_watermark(root).await?;
     let model_id = current_model_id().unwrap_or_else(|_| "bge-small".to_string());
+    gather_model_index_state(root, profile, model_id).await
+}
+
+async fn gather_model_index_state(
+    root: &Path,
+    profile: &SearchProfile,
+    model_id: String,
+) -> Result<IndexState> {
+    let project_watermark = compute_project_watermark(root).await?;
     let store_path = index_path_for_model(root, &model_id);
     let index_exists = store_path.exists();
+    let current_provenance =
+        current_index_provenance(root, &model_id, profile.name(), profile.embedding()).await;
 
     let mut index_corrupt = false;
     let mut index_mtime_ms = None;
@@ -393,13 +581,16 @@ async fn gather_index_state(root: &Path, profile: &SearchProfile) -> Result<Inde
 
     let mut watermark = None;
     let mut built_at_unix_ms = None;
+    let mut provenance = None;
     match read_index_watermark(&store_path).await {
         Ok(Some(PersistedIndexWatermark {
             built_at_unix_ms: built_at,
             watermark: mark,
+            provenance: built_by,
         })) => {
             built_at_unix_ms = Some(built_at);
             watermark = Some(mark);
+            provenance = built_by;
         }
         Ok(None) => {}
         Err(_) => {
@@ -412,6 +603,8 @@ async fn gather_index_state(root: &Path, profile: &SearchProfile) -> Result<Inde
         index_exists,
         index_corrupt,
         watermark.as_ref(),
+        provenance.as_ref(),
+        Some(&current_provenance),
     );
 
     let snapshot = IndexSnapshot {
@@ -420,6 +613,7 @@ async fn gather_index_state(root: &Path, profile: &SearchProfile) -> Result<Inde
         mtime_ms: index_mtime_ms,
         built_at_unix_ms,
         watermark,
+        provenance,
     };
 
     Ok(IndexState {
@@ -432,6 +626,7 @@ async fn gather_index_state(root: &Path, profile: &SearchProfile) -> Result<Inde
         stale: assessment.stale,
         stale_reasons: assessment.reasons,
         reindex: None,
+        reindex_attempts: Vec::new(),
     })
 }
 
@@ -520,7 +715,11 @@ async fn load_semantic_indexes(
     }
 
     if sources.is_empty() {
-        anyhow::bail!("No semantic indices available (run 'context-finder index' first)");
+        return Err(CodedError::new(
+            ErrorCode::IndexMissing,
+            "No semantic indices available (run 'context-finder index' first)",
+        )
+        .into());
     }
 
     Ok(sources)
@@ -551,6 +750,14 @@ type EngineHandle = Arc<Mutex<EngineSlot>>;
 struct ServiceState {
     engines: Mutex<EngineCache>,
     session: Mutex<SessionDefaults>,
+    /// Streaming watchers started via the `watch` tool, keyed by canonical project root.
+    watchers: Mutex<HashMap<PathBuf, router::watch::ActiveWatcher>>,
+    /// Per-tool call counters for the process lifetime.
+    metrics: ToolMetrics,
+    /// Tool calls in flight; background prewarm waits for this to reach zero.
+    active_calls: AtomicUsize,
+    /// Progress of the `warm.on_start` background prewarm, in config order.
+    prewarm: Mutex<Vec<PrewarmStatus>>,
 }
 
 impl ServiceState {
@@ -558,6 +765,10 @@ impl ServiceState {
         Self {
             engines: Mutex::new(EngineCache::new(ENGINE_CACHE_CAPACITY)),
             session: Mutex::new(SessionDefaults::default()),
+            watchers: Mutex::new(HashMap::new()),
+            metrics: ToolMetrics::default(),
+            active_calls: AtomicUsize::new(0),
+            prewarm: Mutex::new(Vec::new()),
         }
     }
 
@@ -569,6 +780,7 @@ impl ServiceState {
     async fn resolve_root(&self, raw_path: Option<&str>) -> Result<(PathBuf, String), String> {
         if let Some(raw) = trimmed_non_empty(raw_path) {
             let root = canonicalize_root(raw).map_err(|err| format!("Invalid path: {err}"))?;
+            ensure_root_allowed(&root)?;
             let root_display = root.to_string_lossy().to_string();
             let mut session = self.session.lock().await;
             session.root = Some(root.clone());
@@ -583,6 +795,7 @@ impl ServiceState {
         if let Some((var, value)) = env_root_override() {
             let root = canonicalize_root(&value)
                 .map_err(|err| format!("Invalid path from {var}: {err}"))?;
+            ensure_root_allowed(&root)?;
             let root_display = root.to_string_lossy().to_string();
             let mut session = self.session.lock().await;
             session.root = Some(root.clone());
@@ -595,6 +808,7 @@ impl ServiceState {
         let candidate = find_git_root(&cwd).unwrap_or(cwd);
         let root =
             canonicalize_root_path(&candidate).map_err(|err| format!("Invalid path: {err}"))?;
+        ensure_root_allowed(&root)?;
         let root_display = root.to_string_lossy().to_string();
         let mut session = self.session.lock().await;
         session.root = Some(root.clone());
@@ -603,6 +817,22 @@ impl ServiceState {
     }
 }
 
+/// Counts one tool call in `ServiceState::active_calls` until dropped.
+struct ActiveCall<'a>(&'a AtomicUsize);
+
+impl<'a> ActiveCall<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for ActiveCall<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 #[derive(Default)]
 struct SessionDefaults {
     root: Option<PathBuf>,
@@ -631,6 +861,11 @@ fn env_root_override() -> Option<(String, String)> {
     None
 }
 
+/// Reject canonical roots outside `CONTEXT_FINDER_ALLOWED_ROOTS` (no-op when unset).
+fn ensure_root_allowed(root: &Path) -> Result<(), String> {
+    AllowedRoots::from_env().map_or(Ok(()), |allowed| allowed.check(root))
+}
+
 fn canonicalize_root(raw: &str) -> Result<PathBuf, String> {
     canonicalize_root_path(Path::new(raw))
 }
@@ -716,11 +951,53 @@ struct ProjectEngine {
     context_search: MultiModelContextSearch,
     chunk_lookup: HashMap<String, usize>,
     available_models: Vec<String>,
+    /// Index mtimes of models loaded on request (not part of the engine signature)
+    extra_index_mtimes_ms: HashMap<String, Option<u64>>,
     canonical_index_mtime: SystemTime,
     graph_language: Option<GraphLanguage>,
 }
 
 impl ProjectEngine {
+    /// Make the on-disk index of `model_id` searchable, loading it when the engine was built
+    /// without it and reloading it when it changed since it was loaded.
+    async fn ensure_model(&mut self, model_id: &str) -> Result<()> {
+        let path = index_path_for_model(&self.root, model_id);
+        let mtime_ms = tokio::fs::metadata(&path)
+            .await
+            .and_then(|m| m.modified())
+            .ok()
+            .map(unix_ms);
+        let loaded = self.available_models.iter().any(|id| id == model_id);
+        if loaded
+            && self
+                .extra_index_mtimes_ms
+                .get(model_id)
+                .is_none_or(|seen| *seen == mtime_ms)
+        {
+            return Ok(());
+        }
+
+        let index = VectorIndex::load(&path)
+            .await
+            .with_context(|| format!("Failed to load index {}", path.display()))?;
+        self.context_search
+            .hybrid_mut()
+            .add_source(model_id.to_string(), index);
+        if !loaded {
+            self.available_models.push(model_id.to_string());
+            self.available_models.sort();
+        }
+        self.extra_index_mtimes_ms
+            .insert(model_id.to_string(), mtime_ms);
+        Ok(())
+    }
+
+    /// Answer without a code graph: drop the assembler built for an earlier call.
+    fn drop_graph(&mut self) {
+        self.context_search.clear_assembler();
+        self.graph_language = None;
+    }
+
     async fn ensure_graph(&mut self, language: GraphLanguage) -> Result<()> {
         if self.graph_language == Some(language) && self.context_search.assembler().is_some() {
             return Ok(());
@@ -771,7 +1048,17 @@ impl GraphCache {
         Self {
             path: project_root
                 .join(".context-finder")
-                .join("graph_cache.json"),
+                .join(GRAPH_CACHE_FILE_NAME),
+        }
+    }
+
+    /// Remove the cache file; returns whether there was one to remove.
+    async fn clear(&self) -> Result<bool> {
+        match tokio::fs::remove_file(&self.path).await {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err)
+                .with_context(|| format!("Failed to remove graph cache {}", self.path.display())),
         }
     }
 
@@ -794,60 +1081,28 @@ impl GraphCache {
             }
         };
 
-        let cached: CachedGraph = match serde_json::from_slice(&data) {
+        let cached = match GraphCacheFile::decode(&data) {
             Ok(cached) => cached,
             Err(err) => {
-                log::warn!("Graph cache corrupted ({}): {err}", self.path.display());
+                log::warn!(
+                    "Ignoring graph cache {} ({err}); rebuilding",
+                    self.path.display()
+                );
                 return Ok(None);
             }
         };
 
-        if cached.language != language {
-            return Ok(None);
-        }
-
-        if cached.index_mtime_ms != unix_ms(store_mtime) {
+        if !cached.is_fresh_for(language, unix_ms(store_mtime)) {
             return Ok(None);
         }
 
-        let mut graph = CodeGraph::new();
-        let mut node_indices = Vec::new();
-
-        for node in cached.nodes {
-            let Some(&idx) = chunk_index.get(&node.chunk_id) else {
-                return Ok(None);
-            };
-            let Some(chunk) = chunks.get(idx) else {
-                return Ok(None);
-            };
-
-            let graph_node = GraphNode {
-                symbol: node.symbol,
-                chunk_id: node.chunk_id,
-                chunk: Some(chunk.clone()),
-            };
-            let idx = graph.add_node(graph_node);
-            node_indices.push(idx);
-        }
-
-        for edge in cached.edges {
-            let Some(&from_idx) = node_indices.get(edge.from) else {
-                return Ok(None);
-            };
-            let Some(&to_idx) = node_indices.get(edge.to) else {
-                return Ok(None);
-            };
-            graph.add_edge(
-                from_idx,
-                to_idx,
-                GraphEdge {
-                    relationship: edge.relationship,
-                    weight: edge.weight,
-                },
-            );
-        }
-
-        Ok(Some(ContextAssembler::new(graph)))
+        let graph = cached.into_graph(|chunk_id| {
+            chunk_index
+                .get(chunk_id)
+                .and_then(|&idx| chunks.get(idx))
+                .cloned()
+        });
+        Ok(graph.map(ContextAssembler::new))
     }
 
     async fn save(
@@ -860,81 +1115,17 @@ impl GraphCache {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        let cached = CachedGraph::from_assembler(store_mtime, language, assembler);
-        let data = serde_json::to_vec_pretty(&cached)?;
-        tokio::fs::write(&self.path, data)
+        let cached = GraphCacheFile::from_graph(assembler.graph(), language, unix_ms(store_mtime));
+        let tmp = self.path.with_extension("bin.tmp");
+        tokio::fs::write(&tmp, cached.encode())
             .await
-            .with_context(|| format!("Failed to write graph cache {}", self.path.display()))
-    }
-}
-
-#[derive(Serialize, Deserialize)]
-struct CachedGraph {
-    index_mtime_ms: u64,
-    language: GraphLanguage,
-    nodes: Vec<CachedNode>,
-    edges: Vec<CachedEdge>,
-}
-
-#[derive(Serialize, Deserialize)]
-struct CachedNode {
-    symbol: Symbol,
-    chunk_id: String,
-}
-
-#[derive(Serialize, Deserialize)]
-struct CachedEdge {
-    from: usize,
-    to: usize,
-    relationship: RelationshipType,
-    weight: f32,
-}
-
-impl CachedGraph {
-    fn from_assembler(
-        store_mtime: SystemTime,
-        language: GraphLanguage,
-        assembler: &ContextAssembler,
-    ) -> Self {
-        let graph = assembler.graph();
-        let mut node_map = HashMap::new();
-        let mut nodes = Vec::new();
-
-        for (idx, node) in graph.graph.node_indices().enumerate() {
-            if let Some(data) = graph.graph.node_weight(node) {
-                node_map.insert(node, idx);
-                nodes.push(CachedNode {
-                    symbol: data.symbol.clone(),
-                    chunk_id: data.chunk_id.clone(),
-                });
-            }
-        }
-
-        let mut edges = Vec::new();
-        for edge_id in graph.graph.edge_indices() {
-            let Some((source, target)) = graph.graph.edge_endpoints(edge_id) else {
-                continue;
-            };
-            let Some(weight) = graph.graph.edge_weight(edge_id) else {
-                continue;
-            };
-            let (Some(&from), Some(&to)) = (node_map.get(&source), node_map.get(&target)) else {
-                continue;
-            };
-            edges.push(CachedEdge {
-                from,
-                to,
-                relationship: weight.relationship,
-                weight: weight.weight,
-            });
-        }
-
-        Self {
-            index_mtime_ms: unix_ms(store_mtime),
-            language,
-            nodes,
-            edges,
+            .with_context(|| format!("Failed to write graph cache {}", tmp.display()))?;
+        if let Err(err) = tokio::fs::rename(&tmp, &self.path).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(err)
+                .with_context(|| format!("Failed to write graph cache {}", self.path.display()));
         }
+        Ok(())
     }
 }
 
@@ -982,7 +1173,7 @@ async fn build_project_engine(
     let canonical_model_id = available_models
         .first()
         .cloned()
-        .ok_or_else(|| anyhow::anyhow!("No semantic indices available"))?;
+        .ok_or_else(|| CodedError::new(ErrorCode::IndexMissing, "No semantic indices available"))?;
     let canonical_index_path = index_path_for_model(root, &canonical_model_id);
     let canonical_index_mtime = tokio::fs::metadata(&canonical_index_path)
         .await
@@ -1007,6 +1198,7 @@ async fn build_project_engine(
         context_search,
         chunk_lookup,
         available_models,
+        extra_index_mtimes_ms: HashMap::new(),
         canonical_index_mtime,
         graph_language: None,
     })
@@ -1024,6 +1216,8 @@ struct ModelManifestFile {
 #[derive(Debug, Deserialize)]
 struct ModelManifestModel {
     id: String,
+    #[serde(default)]
+    dimension: Option<usize>,
     assets: Vec<ModelManifestAsset>,
 }
 
@@ -1085,11 +1279,23 @@ mod model_asset_path_tests {
 }
 
 #[derive(Debug, Deserialize)]
-struct IndexIdMapOnly {
+struct IndexHeaderOnly {
     #[serde(default)]
     schema_version: Option<u32>,
     #[serde(default)]
-    id_map: HashMap<usize, String>,
+    dimension: Option<usize>,
+}
+
+/// What doctor reads from an `index.json` header before reconciling it with the corpus.
+struct IndexSummary {
+    schema_version: Option<u32>,
+    dimension: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CorpusHeaderOnly {
+    #[serde(default)]
+    schema_version: Option<u32>,
 }
 
 async fn load_model_statuses(model_dir: &Path) -> Result<(bool, Vec<DoctorModelStatus>)> {
@@ -1106,6 +1312,7 @@ async fn load_model_statuses(model_dir: &Path) -> Result<(bool, Vec<DoctorModelS
 
     let mut statuses = Vec::new();
     for model in parsed.models {
+        let mut present = Vec::new();
         let mut missing = Vec::new();
         for asset in model.assets {
             let full = match safe_join_model_asset_path(model_dir, &asset.path) {
@@ -1115,7 +1322,9 @@ async fn load_model_statuses(model_dir: &Path) -> Result<(bool, Vec<DoctorModelS
                     continue;
                 }
             };
-            if !full.exists() {
+            if full.exists() {
+                present.push(asset.path);
+            } else {
                 missing.push(asset.path);
             }
         }
@@ -1123,62 +1332,34 @@ async fn load_model_statuses(model_dir: &Path) -> Result<(bool, Vec<DoctorModelS
         statuses.push(DoctorModelStatus {
             id: model.id,
             installed,
+            dimension: model.dimension,
+            present_assets: present,
             missing_assets: missing,
         });
     }
     Ok((true, statuses))
 }
 
-async fn load_corpus_chunk_ids(corpus_path: &Path) -> Result<HashSet<String>> {
-    let corpus = ChunkCorpus::load(corpus_path).await?;
-    let mut ids = HashSet::new();
-    for chunks in corpus.files().values() {
-        for chunk in chunks {
-            ids.insert(format!(
-                "{}:{}:{}",
-                chunk.file_path, chunk.start_line, chunk.end_line
-            ));
-        }
-    }
-    Ok(ids)
-}
-
-async fn load_index_chunk_ids(index_path: &Path) -> Result<HashSet<String>> {
+async fn load_index_summary(index_path: &Path) -> Result<IndexSummary> {
     let bytes = tokio::fs::read(index_path)
         .await
         .with_context(|| format!("Failed to read index {}", index_path.display()))?;
-    let parsed: IndexIdMapOnly = serde_json::from_slice(&bytes)
+    let parsed: IndexHeaderOnly = serde_json::from_slice(&bytes)
         .with_context(|| format!("Failed to parse index {}", index_path.display()))?;
-    // schema_version is tracked for diagnostics, but chunk id extraction relies on id_map values.
-    let _ = parsed.schema_version.unwrap_or(1);
-    Ok(parsed.id_map.into_values().collect())
-}
-
-fn chunk_id_file_path(chunk_id: &str) -> Option<String> {
-    let mut parts = chunk_id.rsplitn(3, ':');
-    let _end = parts.next()?;
-    let _start = parts.next()?;
-    Some(parts.next()?.to_string())
+    Ok(IndexSummary {
+        schema_version: parsed.schema_version,
+        dimension: parsed.dimension,
+    })
 }
 
-fn sample_file_paths<'a, I>(chunk_ids: I, limit: usize) -> Vec<String>
-where
-    I: Iterator<Item = &'a String>,
-{
-    let mut out = Vec::new();
-    let mut seen = HashSet::new();
-    for id in chunk_ids {
-        if out.len() >= limit {
-            break;
-        }
-        let Some(file) = chunk_id_file_path(id) else {
-            continue;
-        };
-        if seen.insert(file.clone()) {
-            out.push(file);
-        }
-    }
-    out
+/// Read only `schema_version` from `corpus.json` (the full corpus is not materialized).
+async fn load_corpus_schema_version(corpus_path: &Path) -> Result<Option<u32>> {
+    let bytes = tokio::fs::read(corpus_path)
+        .await
+        .with_context(|| format!("Failed to read corpus {}", corpus_path.display()))?;
+    let parsed: CorpusHeaderOnly = serde_json::from_slice(&bytes)
+        .with_context(|| format!("Failed to parse corpus {}", corpus_path.display()))?;
+    Ok(parsed.schema_version)
 }
 
 // ============================================================================
@@ -1299,6 +1480,28 @@ impl ContextFinderService {
         router::doctor::doctor(self, request).await
     }
 
+    /// Keep the index fresh while files change
+    #[tool(
+        description = "Manage an in-process streaming watcher that reindexes incrementally on file changes. Actions: start (guarded: one watcher per root), stop, status (live health plus persisted health.json), trigger (queue a reindex with a reason). Timings come from `.context-finder/config.json` `watch`."
+    )]
+    pub async fn watch(
+        &self,
+        Parameters(request): Parameters<WatchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        router::watch::watch(self, request).await
+    }
+
+    /// Warm a project before the first query
+    #[tool(
+        description = "Load a project's semantic engine ahead of the first query, optionally also the code graph for a language and the embedding session. Returns the time spent in each stage. Projects listed in `.context-finder/config.json` `warm.on_start` are prewarmed in the background at startup; `doctor` shows their progress."
+    )]
+    pub async fn warm(
+        &self,
+        Parameters(request): Parameters<WarmRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        router::warm::warm(self, request).await
+    }
+
     /// Semantic code search
     #[tool(
         description = "Search for code using natural language. Returns relevant code snippets with file locations and symbols."
@@ -1334,18 +1537,23 @@ impl ContextFinderService {
 
     /// Index a project
     #[tool(
-        description = "Index a project directory for semantic search. Required before using search/context tools on a new project."
+        description = "Index a project directory for semantic search. Required before using search/context tools on a new project. Sends notifications/progress (phase, files, chunks, ETA) when the call carries a progressToken."
     )]
     pub async fn index(
         &self,
         Parameters(request): Parameters<IndexRequest>,
+        meta: Meta,
+        peer: Peer<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        router::index::index(self, request).await
+        let progress = meta
+            .get_progress_token()
+            .map(|token| router::index::IndexProgressSink::new(peer, token));
+        router::index::index(self, request, progress).await
     }
 
     /// Find all usages of a symbol (impact analysis)
     #[tool(
-        description = "Find all places where a symbol is used. Essential for refactoring - shows direct usages, transitive dependencies, and related tests."
+        description = "Find all places where a symbol is used. Essential for refactoring - shows direct usages (including trait implementations), transitive dependencies, and related tests."
     )]
     pub async fn impact(
         &self,
@@ -1356,7 +1564,7 @@ impl ContextFinderService {
 
     /// Trace call path between two symbols
     #[tool(
-        description = "Show call chain from one symbol to another. Essential for understanding code flow and debugging."
+        description = "Show call chain from one symbol to another (cheapest path by relationship weight; calls are preferred over uses/imports). Supports `max_paths` for alternate routes and `via` to force a waypoint. Essential for understanding code flow and debugging."
     )]
     pub async fn trace(
         &self,
@@ -1378,7 +1586,7 @@ impl ContextFinderService {
 
     /// Project architecture overview
     #[tool(
-        description = "Get project architecture snapshot: layers, entry points, key types, and graph statistics. Use this first to understand a new codebase."
+        description = "Get project architecture snapshot: layers, entry points, key types, module dependency graph (with cycles), and graph statistics. Use this first to understand a new codebase."
     )]
     pub async fn overview(
         &self,
@@ -1396,115 +1604,6 @@ fn finalize_read_pack_budget(result: &mut ReadPackResult) -> anyhow::Result<()>
 // ============================================================================
 
 impl ContextFinderService {
-    fn find_text_usages(
-        chunks: &[context_code_chunker::CodeChunk],
-        symbol: &str,
-        exclude_chunk_id: Option<&str>,
-        max_results: usize,
-    ) -> Vec<UsageInfo> {
-        if symbol.is_empty() || max_results == 0 {
-            return Vec::new();
-        }
-
-        let mut out = Vec::new();
-        let mut seen: HashSet<(String, usize)> = HashSet::new();
-
-        for chunk in chunks {
-            if out.len() >= max_results {
-                break;
-            }
-
-            if path_has_extension_ignore_ascii_case(&chunk.file_path, "md") {
-                continue;
-            }
-
-            let chunk_id = format!(
-                "{}:{}:{}",
-                chunk.file_path, chunk.start_line, chunk.end_line
-            );
-            if let Some(exclude) = exclude_chunk_id {
-                if chunk_id == exclude {
-                    continue;
-                }
-            }
-
-            let Some(hit_byte) = Self::find_word_boundary(&chunk.content, symbol) else {
-                continue;
-            };
-
-            let line_offset = chunk.content[..hit_byte]
-                .bytes()
-                .filter(|b| *b == b'\n')
-                .count();
-            let line = chunk.start_line + line_offset;
-            if !seen.insert((chunk.file_path.clone(), line)) {
-                continue;
-            }
-
-            out.push(UsageInfo {
-                file: chunk.file_path.clone(),
-                line,
-                symbol: chunk
-                    .metadata
-                    .symbol_name
-                    .clone()
-                    .unwrap_or_else(|| "unknown".to_string()),
-                relationship: "TextMatch".to_string(),
-            });
-        }
-
-        out
-    }
-
-    fn find_word_boundary(haystack: &str, needle: &str) -> Option<usize> {
-        if needle.is_empty() {
-            return None;
-        }
-
-        let needle_is_ident = needle.bytes().all(Self::is_ident_byte);
-        if !needle_is_ident {
-            return haystack.find(needle);
-        }
-
-        let bytes = haystack.as_bytes();
-        for (idx, _) in haystack.match_indices(needle) {
-            let left_ok = idx == 0 || !Self::is_ident_byte(bytes[idx - 1]);
-            let right_idx = idx + needle.len();
-            let right_ok = right_idx >= bytes.len() || !Self::is_ident_byte(bytes[right_idx]);
-            if left_ok && right_ok {
-                return Some(idx);
-            }
-        }
-        None
-    }
-
-    const fn is_ident_byte(b: u8) -> bool {
-        matches!(b, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_')
-    }
-
-    fn match_in_line(
-        line: &str,
-        pattern: &str,
-        case_sensitive: bool,
-        whole_word: bool,
-    ) -> Option<usize> {
-        if case_sensitive {
-            if whole_word {
-                Self::find_word_boundary(line, pattern)
-            } else {
-                line.find(pattern)
-            }
-        } else {
-            let line_lower = line.to_ascii_lowercase();
-            let pat_lower = pattern.to_ascii_lowercase();
-            if whole_word {
-                Self::find_word_boundary(&line_lower, &pat_lower)
-            } else {
-                line_lower.find(&pat_lower)
-            }
-        }
-    }
-
     pub(super) fn matches_file_pattern(path: &str, pattern: Option<&str>) -> bool {
         let Some(pattern) = pattern else {
             return true;
@@ -1568,120 +1667,33 @@ impl ContextFinderService {
             _ => GraphLanguage::Rust,
         }
     }
-
-    /// Auto-detect primary language from file extensions in chunks
-    fn detect_language(chunks: &[context_code_chunker::CodeChunk]) -> GraphLanguage {
-        let mut rust_count = 0;
-        let mut python_count = 0;
-        let mut js_count = 0;
-        let mut ts_count = 0;
-
-        for chunk in chunks {
-            if path_has_extension_ignore_ascii_case(&chunk.file_path, "rs") {
-                rust_count += 1;
-            } else if path_has_extension_ignore_ascii_case(&chunk.file_path, "py") {
-                python_count += 1;
-            } else if path_has_extension_ignore_ascii_case(&chunk.file_path, "ts")
-                || path_has_extension_ignore_ascii_case(&chunk.file_path, "tsx")
-            {
-                ts_count += 1;
-            } else if path_has_extension_ignore_ascii_case(&chunk.file_path, "js")
-                || path_has_extension_ignore_ascii_case(&chunk.file_path, "jsx")
-            {
-                js_count += 1;
-            }
-        }
-
-        let max = rust_count.max(python_count).max(js_count).max(ts_count);
-        if max == 0 {
-            return GraphLanguage::Rust; // default
-        }
-        if max == rust_count {
-            GraphLanguage::Rust
-        } else if max == python_count {
-            GraphLanguage::Python
-        } else if max == ts_count {
-            GraphLanguage::TypeScript
-        } else {
-            GraphLanguage::JavaScript
-        }
-    }
-
-    fn guess_layer_role(name: &str) -> String {
-        match name.to_lowercase().as_str() {
-            "cli" | "cmd" | "bin" => "Command-line interface".to_string(),
-            "api" | "server" | "web" => "API/Server layer".to_string(),
-            "core" | "lib" | "src" => "Core library".to_string(),
-            "test" | "tests" => "Test suite".to_string(),
-            "crates" => "Workspace crates".to_string(),
-            "docs" | "doc" => "Documentation".to_string(),
-            _ => "Module".to_string(),
-        }
-    }
-
-    fn generate_impact_mermaid(
-        symbol: &str,
-        direct: &[UsageInfo],
-        transitive: &[UsageInfo],
-    ) -> String {
-        let mut lines = vec!["graph LR".to_string()];
-
-        // Add direct edges
-        for usage in direct.iter().take(10) {
-            lines.push(format!(
-                "    {}-->|{}|{}",
-                Self::mermaid_safe(&usage.symbol),
-                usage.relationship,
-                Self::mermaid_safe(symbol)
-            ));
-        }
-
-        // Add transitive edges (simplified)
-        for usage in transitive.iter().take(5) {
-            lines.push(format!(
-                "    {}-.->|transitive|{}",
-                Self::mermaid_safe(&usage.symbol),
-                Self::mermaid_safe(symbol)
-            ));
-        }
-
-        lines.join("\n")
-    }
-
-    fn generate_trace_mermaid(steps: &[TraceStep]) -> String {
-        if steps.is_empty() {
-            return "sequenceDiagram\n    Note over A: No path found".to_string();
-        }
-
-        let mut lines = vec!["sequenceDiagram".to_string()];
-
-        for window in steps.windows(2) {
-            let from = &window[0];
-            let to = &window[1];
-            let rel = to.relationship.as_deref().unwrap_or("calls");
-            lines.push(format!(
-                "    {}->>{}+: {}",
-                Self::mermaid_safe(&from.symbol),
-                Self::mermaid_safe(&to.symbol),
-                rel
-            ));
-        }
-
-        lines.join("\n")
-    }
-
-    fn mermaid_safe(s: &str) -> String {
-        s.replace("::", "_").replace(['<', '>', ' '], "_")
-    }
 }
 
 const fn graph_language_key(language: GraphLanguage) -> &'static str {
-    match language {
-        GraphLanguage::Rust => "rust",
-        GraphLanguage::Python => "python",
-        GraphLanguage::JavaScript => "javascript",
-        GraphLanguage::TypeScript => "typescript",
-    }
+    language.as_str()
+}
+
+/// Graph language for `context`/`impact`/`trace`: the requested one, else the dominant code
+/// language of the indexed chunks. The choice is recorded in `meta`; `Err` carries a dominant
+/// language that has no graph builder (also recorded, so graph-less answers still report it).
+fn resolve_graph_language(
+    engine: &mut EngineLock,
+    requested: Option<&str>,
+    meta: &mut ToolMeta,
+) -> Result<GraphLanguage, String> {
+    let detected = match requested {
+        Some(lang) => Ok(ContextFinderService::parse_language(Some(lang))),
+        None => match detect_graph_language(engine.engine_mut().context_search.hybrid().chunks()) {
+            DetectedLanguage::Supported(language) => Ok(language),
+            DetectedLanguage::Unsupported(name) => Err(name),
+        },
+    };
+    meta.graph_language = Some(match &detected {
+        Ok(language) => language.as_str().to_string(),
+        Err(name) => name.clone(),
+    });
+    meta.detected = Some(requested.is_none());
+    detected
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -1822,6 +1834,7 @@ fn prepare_related_contexts(
             .then_with(|| a.distance.cmp(&b.distance))
             .then_with(|| a.chunk.file_path.cmp(&b.chunk.file_path))
             .then_with(|| a.chunk.start_line.cmp(&b.chunk.start_line))
+            .then_with(|| a.chunk.end_line.cmp(&b.chunk.end_line))
     };
 
     match related_mode {
@@ -1867,6 +1880,7 @@ fn prepare_related_contexts(
                     .then_with(|| a.1.distance.cmp(&b.1.distance))
                     .then_with(|| a.1.chunk.file_path.cmp(&b.1.chunk.file_path))
                     .then_with(|| a.1.chunk.start_line.cmp(&b.1.chunk.start_line))
+                    .then_with(|| a.1.chunk.end_line.cmp(&b.1.chunk.end_line))
             });
 
             combined.into_iter().map(|(_, rc)| rc).collect()
@@ -1889,9 +1903,7 @@ fn prepare_context_pack_enriched(
         let b_kind = classify_path_kind(&b.primary.chunk.file_path);
         document_kind_rank(a_kind, prefer_code)
             .cmp(&document_kind_rank(b_kind, prefer_code))
-            .then_with(|| b.primary.score.total_cmp(&a.primary.score))
-            .then_with(|| a.primary.chunk.file_path.cmp(&b.primary.chunk.file_path))
-            .then_with(|| a.primary.chunk.start_line.cmp(&b.primary.chunk.start_line))
+            .then_with(|| pack_order(&a.primary, &b.primary))
     });
 
     if !include_docs {
@@ -1928,14 +1940,18 @@ const fn document_kind_rank(kind: DocumentKind, prefer_code: bool) -> u8 {
 fn pack_enriched_results(
     profile: &SearchProfile,
     enriched: Vec<context_search::EnrichedResult>,
-    max_chars: usize,
+    mut budget: PackBudget,
     max_related_per_primary: usize,
     related_mode: RelatedMode,
     query_tokens: &[String],
-) -> (Vec<ContextPackItem>, ContextPackBudget) {
-    let mut used_chars = 0usize;
-    let mut truncated = false;
+) -> (
+    Vec<ContextPackItem>,
+    ContextPackBudget,
+    Vec<ContextPackOmitted>,
+) {
+    let mut truncation: Option<BudgetTruncation> = None;
     let mut dropped_items = 0usize;
+    let mut omitted = OmittedItems::default();
 
     let mut items: Vec<ContextPackItem> = Vec::new();
     let mut seen: HashSet<String> = HashSet::new();
@@ -1947,14 +1963,22 @@ fn pack_enriched_results(
         }
 
         let primary_item = build_primary_item(primary);
-        let cost = estimate_item_chars(&primary_item);
-        if used_chars.saturating_add(cost) > max_chars {
-            truncated = true;
-            dropped_items += 1;
-            break;
+        if truncation.is_some() {
+            // Past the budget: only record which primaries were left out.
+            if find_overlapping_item(&items, &primary_item).is_none() {
+                omitted.push(&primary_item);
+            }
+            continue;
+        }
+        if let Some(primary_item) = merge_overlapping_span(&mut items, primary_item, &mut budget) {
+            if let Err(limit) = budget.try_add(&primary_item) {
+                truncation = Some(limit);
+                dropped_items += 1;
+                omitted.push(&primary_item);
+                continue;
+            }
+            items.push(primary_item);
         }
-        used_chars += cost;
-        items.push(primary_item);
 
         let mut related = er.related;
         related.retain(|rc| !profile.is_rejected(&rc.chunk.file_path));
@@ -1984,33 +2008,27 @@ fn pack_enriched_results(
             }
 
             let item = build_related_item(id, rc);
+            let Some(item) = merge_overlapping_span(&mut items, item, &mut budget) else {
+                continue;
+            };
 
-            let cost = estimate_item_chars(&item);
-            if used_chars.saturating_add(cost) > max_chars {
-                truncated = true;
+            if let Err(limit) = budget.try_add(&item) {
+                truncation = Some(limit);
                 dropped_items += 1;
+                omitted.push(&item);
                 break;
             }
-            used_chars += cost;
             items.push(item);
             *per_relationship.entry(kind).or_insert(0) += 1;
             selected_related += 1;
         }
-
-        if truncated {
-            break;
-        }
     }
 
+    let omitted_items = omitted.count();
     (
         items,
-        ContextPackBudget {
-            max_chars,
-            used_chars,
-            truncated,
-            dropped_items,
-            truncation: truncated.then_some(BudgetTruncation::MaxChars),
-        },
+        budget.finish(truncation, dropped_items, omitted_items),
+        omitted.into_stubs(),
     )
 }
 
@@ -2028,7 +2046,12 @@ fn chunk_id(file: &str, start_line: usize, end_line: usize) -> String {
 }
 
 fn build_primary_item(primary: context_search::SearchResult) -> ContextPackItem {
-    let context_search::SearchResult { chunk, score, id } = primary;
+    let context_search::SearchResult {
+        chunk,
+        score,
+        id,
+        freshness,
+    } = primary;
     ContextPackItem {
         id,
         role: "primary".to_string(),
@@ -2042,6 +2065,9 @@ fn build_primary_item(primary: context_search::SearchResult) -> ContextPackItem
         content: chunk.content,
         relationship: None,
         distance: None,
+        bucket: None,
+        indexed_at_ms: freshness.map(|f| f.indexed_at_ms),
+        file_mtime_ms: freshness.map(|f| f.file_mtime_ms),
     }
 }
 
@@ -2063,65 +2089,30 @@ fn build_related_item(id: String, rc: context_search::RelatedContext) -> Context
         content: rc.chunk.content,
         relationship: Some(rc.relationship_path),
         distance: Some(rc.distance),
+        bucket: None,
+        indexed_at_ms: None,
+        file_mtime_ms: None,
     }
 }
 
-fn estimate_item_chars(item: &ContextPackItem) -> usize {
-    let imports: usize = item.imports.iter().map(|s| s.len() + 1).sum();
-    item.content.len() + imports + 128
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use context_code_chunker::ChunkMetadata;
     use context_search::{EnrichedResult, RelatedContext};
-    use context_vector_store::SearchResult;
-
-    #[test]
-    fn word_boundary_match_hits_only_whole_identifier() {
-        assert!(ContextFinderService::find_word_boundary("fn new() {}", "new").is_some());
-        assert!(ContextFinderService::find_word_boundary("renew", "new").is_none());
-        assert!(ContextFinderService::find_word_boundary("news", "new").is_none());
-        assert!(ContextFinderService::find_word_boundary("new_", "new").is_none());
-        assert!(ContextFinderService::find_word_boundary(" new ", "new").is_some());
-    }
+    use context_vector_store::{reconcile_index_file, SearchResult};
 
-    #[test]
-    fn text_usages_compute_line_and_respect_exclusion() {
-        let chunk = context_code_chunker::CodeChunk::new(
-            "a.rs".to_string(),
-            10,
-            20,
-            "fn caller() {\n  touch_daemon_best_effort();\n}\n".to_string(),
-            ChunkMetadata::default()
-                .symbol_name("caller")
-                .chunk_type(context_code_chunker::ChunkType::Function),
-        );
+    #[tokio::test]
+    async fn auto_index_budget_is_capped_by_the_request_deadline() {
+        let policy = AutoIndexPolicy::from_request(None, Some(60_000));
+        assert!(policy.remaining_ms() > 30_000);
 
-        let usages = ContextFinderService::find_text_usages(
-            std::slice::from_ref(&chunk),
-            "touch_daemon_best_effort",
-            None,
-            10,
-        );
-        assert_eq!(usages.len(), 1);
-        assert_eq!(usages[0].file, "a.rs");
-        assert_eq!(usages[0].line, 11);
-        assert_eq!(usages[0].symbol, "caller");
-        assert_eq!(usages[0].relationship, "TextMatch");
-
-        let exclude = format!(
-            "{}:{}:{}",
-            chunk.file_path, chunk.start_line, chunk.end_line
-        );
-        let excluded = ContextFinderService::find_text_usages(
-            &[chunk],
-            "touch_daemon_best_effort",
-            Some(&exclude),
-            10,
-        );
-        assert!(excluded.is_empty());
+        let deadline = Instant::now() + Duration::from_millis(200);
+        let capped = with_request_deadline(Some(deadline), async {
+            AutoIndexPolicy::from_request(None, Some(60_000))
+        })
+        .await;
+        assert!(capped.remaining_ms() <= 200);
     }
 
     #[tokio::test]
@@ -2172,43 +2163,81 @@ mod tests {
 
         assert!(!root.join(".context-finder").exists());
 
-        let result = compute_list_files_result(root, &root_display, None, 50, 20_000, None)
-            .await
-            .unwrap();
+        let result = compute_list_files_result(
+            root,
+            &root_display,
+            None,
+            50,
+            20_000,
+            None,
+            ListFilesView::default(),
+        )
+        .await
+        .unwrap();
         assert_eq!(result.source, "filesystem");
-        assert!(result.files.contains(&"src/main.rs".to_string()));
-        assert!(result.files.contains(&"docs/README.md".to_string()));
-        assert!(result.files.contains(&"README.md".to_string()));
+        assert!(result.files.paths().contains(&"src/main.rs"));
+        assert!(result.files.paths().contains(&"docs/README.md"));
+        assert!(result.files.paths().contains(&"README.md"));
         assert!(!result.truncated);
         assert!(result.next_cursor.is_none());
 
-        let filtered =
-            compute_list_files_result(root, &root_display, Some("docs"), 50, 20_000, None)
-                .await
-                .unwrap();
-        assert_eq!(filtered.files, vec!["docs/README.md".to_string()]);
+        let filtered = compute_list_files_result(
+            root,
+            &root_display,
+            Some("docs"),
+            50,
+            20_000,
+            None,
+            ListFilesView::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(filtered.files.paths(), vec!["docs/README.md"]);
         assert!(!filtered.truncated);
         assert!(filtered.next_cursor.is_none());
 
-        let globbed =
-            compute_list_files_result(root, &root_display, Some("src/*"), 50, 20_000, None)
-                .await
-                .unwrap();
-        assert_eq!(globbed.files, vec!["src/main.rs".to_string()]);
+        let globbed = compute_list_files_result(
+            root,
+            &root_display,
+            Some("src/*"),
+            50,
+            20_000,
+            None,
+            ListFilesView::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(globbed.files.paths(), vec!["src/main.rs"]);
         assert!(!globbed.truncated);
         assert!(globbed.next_cursor.is_none());
 
-        let limited = compute_list_files_result(root, &root_display, None, 1, 20_000, None)
-            .await
-            .unwrap();
+        let limited = compute_list_files_result(
+            root,
+            &root_display,
+            None,
+            1,
+            20_000,
+            None,
+            ListFilesView::default(),
+        )
+        .await
+        .unwrap();
         assert!(limited.truncated);
         assert_eq!(limited.truncation, Some(ListFilesTruncation::MaxItems));
         assert_eq!(limited.files.len(), 1);
         assert!(limited.next_cursor.is_some());
 
-        let tiny = compute_list_files_result(root, &root_display, None, 50, 3, None)
-            .await
-            .unwrap();
+        let tiny = compute_list_files_result(
+            root,
+            &root_display,
+            None,
+            50,
+            3,
+            None,
+            ListFilesView::default(),
+        )
+        .await
+        .unwrap();
         assert!(tiny.truncated);
         assert_eq!(tiny.truncation, Some(ListFilesTruncation::MaxChars));
         assert!(tiny.next_cursor.is_none());
@@ -2216,6 +2245,81 @@ mod tests {
         assert!(!root.join(".context-finder").exists());
     }
 
+    #[tokio::test]
+    async fn list_files_sorts_with_meta_and_paginates_in_sort_order() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let root = tmp.path();
+        let root_display = root.to_string_lossy().to_string();
+
+        std::fs::write(root.join("small.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(root.join("big.py"), "x = 1\n".repeat(50)).unwrap();
+        std::fs::write(root.join("mid.md"), "# Title\n".repeat(5)).unwrap();
+        std::fs::write(root.join("tie.md"), "# Title\n".repeat(5)).unwrap();
+
+        let view = ListFilesView {
+            include_meta: true,
+            sort: ListFilesSort::Size,
+            order: ListFilesOrder::Desc,
+        };
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = compute_list_files_result(
+                root,
+                &root_display,
+                None,
+                1,
+                20_000,
+                cursor.as_ref(),
+                view,
+            )
+            .await
+            .unwrap();
+            let ListFilesItems::Entries(entries) = &page.files else {
+                panic!("expected metadata entries, got {:?}", page.files);
+            };
+            seen.extend(entries.iter().cloned());
+            let Some(next) = page.next_cursor else {
+                break;
+            };
+            let decoded = decode_list_files_cursor(&next).unwrap();
+            assert_eq!(decoded.sort, Some(ListFilesSort::Size));
+            assert_eq!(decoded.order, Some(ListFilesOrder::Desc));
+            cursor = Some(decoded);
+        }
+
+        let paths: Vec<&str> = seen.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(paths, vec!["big.py", "mid.md", "tie.md", "small.rs"]);
+        assert_eq!(seen[0].size_bytes, Some(300));
+        assert_eq!(seen[0].language.as_deref(), Some("python"));
+        assert!(seen[0].mtime_ms.is_some());
+        assert_eq!(seen[0].chunk_count, None);
+
+        let plain = compute_list_files_result(
+            root,
+            &root_display,
+            None,
+            50,
+            20_000,
+            None,
+            ListFilesView {
+                sort: ListFilesSort::Size,
+                order: ListFilesOrder::Asc,
+                ..ListFilesView::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            plain.files,
+            ListFilesItems::Paths(
+                ["small.rs", "mid.md", "tie.md", "big.py"]
+                    .map(str::to_string)
+                    .to_vec()
+            )
+        );
+    }
+
     #[test]
     fn batch_prepare_item_input_injects_max_chars_for_list_files() {
         let input = serde_json::json!({});
@@ -2250,9 +2354,11 @@ mod tests {
 
         std::fs::write(
             model_dir.join("manifest.json"),
-            r#"{"schema_version":1,"models":[{"id":"m1","assets":[{"path":"m1/model.onnx"}]}]}"#,
+            r#"{"schema_version":1,"models":[{"id":"m1","dimension":384,"assets":[{"path":"m1/model.onnx"},{"path":"m1/tokenizer.json"}]}]}"#,
         )
         .unwrap();
+        std::fs::create_dir_all(model_dir.join("m1")).unwrap();
+        std::fs::write(model_dir.join("m1/tokenizer.json"), "{}").unwrap();
 
         let (exists, models) = load_model_statuses(&model_dir).await.unwrap();
         assert!(exists);
@@ -2260,6 +2366,8 @@ mod tests {
         assert_eq!(models[0].id, "m1");
         assert!(!models[0].installed);
         assert_eq!(models[0].missing_assets, vec!["m1/model.onnx"]);
+        assert_eq!(models[0].present_assets, vec!["m1/tokenizer.json"]);
+        assert_eq!(models[0].dimension, Some(384));
     }
 
     #[tokio::test]
@@ -2295,17 +2403,212 @@ mod tests {
         // while missing c.rs:10:12.
         std::fs::write(
             &index_path,
-            r#"{"schema_version":3,"dimension":384,"next_id":2,"id_map":{"0":"a.rs:1:2","1":"b.rs:1:1"},"vectors":{}}"#,
+            r#"{"schema_version":3,"dimension":2,"next_id":2,"id_map":{"0":"a.rs:1:2","1":"b.rs:1:1"},
+                "vectors":{"a.rs:1:2":{"vector":[0.0,1.0]},"b.rs:1:1":{"vector":[1.0,0.0]}}}"#,
         )
         .unwrap();
 
-        let corpus_ids = load_corpus_chunk_ids(&corpus_path).await.unwrap();
-        let index_ids = load_index_chunk_ids(&index_path).await.unwrap();
+        let index = load_index_summary(&index_path).await.unwrap();
+        assert_eq!(index.schema_version, Some(3));
+        assert_eq!(index.dimension, Some(2));
 
-        assert_eq!(corpus_ids.len(), 2);
-        assert_eq!(index_ids.len(), 2);
-        assert_eq!(corpus_ids.difference(&index_ids).count(), 1);
-        assert_eq!(index_ids.difference(&corpus_ids).count(), 1);
+        let corpus = ChunkCorpus::load(&corpus_path).await.unwrap();
+        let report = reconcile_index_file(&corpus, &index_path).await.unwrap();
+        assert_eq!(report.corpus_chunks, 2);
+        assert_eq!(report.index_chunks, 2);
+        assert_eq!(report.totals.unembedded, 1);
+        assert_eq!(report.totals.orphaned, 1);
+        assert_eq!(report.files[0].file, "b.rs");
+        assert_eq!(report.files[1].unembedded, vec!["c.rs:10:12"]);
+    }
+
+    #[test]
+    fn doctor_suggests_models_fetch_for_models_missing_assets() {
+        let status = |id: &str, installed: bool| DoctorModelStatus {
+            id: id.to_string(),
+            installed,
+            dimension: Some(384),
+            present_assets: Vec::new(),
+            missing_assets: if installed {
+                Vec::new()
+            } else {
+                vec![format!("{id}/model.onnx")]
+            },
+        };
+
+        let mut findings = router::doctor::DoctorFindings::default();
+        router::doctor::suggest_model_fetch(&[status("a", true)], &mut findings);
+        assert!(findings.next_actions.is_empty() && findings.hints.is_empty());
+
+        router::doctor::suggest_model_fetch(
+            &[status("a", true), status("b", false), status("c", false)],
+            &mut findings,
+        );
+        assert_eq!(findings.next_actions.len(), 1);
+        assert_eq!(findings.next_actions[0].tool, "models_fetch");
+        assert_eq!(
+            findings.next_actions[0].args,
+            serde_json::json!({ "models": ["b", "c"] })
+        );
+    }
+
+    #[test]
+    fn doctor_disk_budget_warns_only_below_twice_the_index_size() {
+        let dir = std::path::Path::new("/repo/.context-finder");
+
+        let mut findings = router::doctor::DoctorFindings::default();
+        let budget = router::doctor::check_disk_budget(dir, 1_000, Some(1_999), &mut findings)
+            .expect("budget");
+        assert!(budget.low);
+        assert_eq!(budget.required_free_bytes, 2_000);
+        assert_eq!(findings.issues.len(), 1);
+        assert!(findings.issues[0].contains("1999 bytes free"));
+        assert_eq!(findings.hints.len(), 1);
+
+        let mut findings = router::doctor::DoctorFindings::default();
+        let budget = router::doctor::check_disk_budget(dir, 1_000, Some(2_000), &mut findings)
+            .expect("budget");
+        assert!(!budget.low);
+        assert!(findings.issues.is_empty() && findings.hints.is_empty());
+
+        let mut findings = router::doctor::DoctorFindings::default();
+        assert!(router::doctor::check_disk_budget(dir, 0, Some(0), &mut findings).is_none());
+        assert!(router::doctor::check_disk_budget(dir, 1_000, None, &mut findings).is_none());
+        assert!(findings.issues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn doctor_reports_incompatible_orphaned_and_stale_artifacts_with_repairs() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let root = tmp.path();
+        let indexes = root.join(".context-finder").join("indexes");
+        std::fs::create_dir_all(indexes.join("m1")).unwrap();
+        std::fs::create_dir_all(indexes.join("gone")).unwrap();
+
+        let mut corpus = ChunkCorpus::new();
+        corpus.set_file_chunks(
+            "a.rs".to_string(),
+            vec![context_code_chunker::CodeChunk::new(
+                "a.rs".to_string(),
+                1,
+                2,
+                "alpha".to_string(),
+                ChunkMetadata::default(),
+            )],
+        );
+        corpus
+            .save(&corpus_path_for_project_root(root))
+            .await
+            .unwrap();
+        std::fs::write(
+            indexes.join("m1").join("index.json"),
+            r#"{"schema_version":9,"dimension":384,"id_map":{"0":"a.rs:1:2"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            indexes.join("gone").join("index.json"),
+            r#"{"schema_version":3,"dimension":2,"next_id":1,"id_map":{"0":"a.rs:1:2"},
+                "vectors":{"a.rs:1:2":{"vector":[0.6,0.8]}}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join(".context-finder").join(GRAPH_CACHE_FILE_NAME),
+            b"garbage",
+        )
+        .unwrap();
+
+        let manifest = vec![DoctorModelStatus {
+            id: "m1".to_string(),
+            installed: true,
+            dimension: Some(384),
+            present_assets: vec!["m1/model.onnx".to_string()],
+            missing_assets: Vec::new(),
+        }];
+        let mut findings = router::doctor::DoctorFindings::default();
+        let project =
+            router::doctor::diagnose_project(root, "/repo", Some(&manifest), &mut findings)
+                .await
+                .expect("project");
+
+        assert_eq!(project.corpus_schema_version, Some(2));
+        assert_eq!(project.indexes.len(), 2);
+        let gone = &project.indexes[0];
+        assert_eq!(gone.model, "gone");
+        assert!(gone.orphaned && gone.schema_compatible);
+        assert!(gone.size_bytes > 0 && gone.age_secs.is_some());
+        let m1 = &project.indexes[1];
+        assert!(!m1.orphaned && !m1.schema_compatible);
+        assert_eq!(m1.schema_version, Some(9));
+        assert_eq!(project.drift.len(), 1, "incompatible index skips drift");
+
+        let graph_cache = project.graph_cache.as_ref().expect("graph cache status");
+        assert!(!graph_cache.fresh && graph_cache.error.is_some());
+        if let Some(disk) = project.disk.as_ref() {
+            assert!(disk.index_size_bytes >= gone.size_bytes + m1.size_bytes);
+        }
+
+        assert!(findings
+            .issues
+            .iter()
+            .any(|issue| issue.contains("'m1'") && issue.contains("schema_version 9")));
+        assert!(findings.hints.iter().any(|hint| hint.contains("'gone'")));
+        let actions: Vec<&serde_json::Value> = findings
+            .next_actions
+            .iter()
+            .filter(|action| action.tool == "index")
+            .map(|action| &action.args)
+            .collect();
+        assert!(actions.contains(&&serde_json::json!({
+            "path": "/repo",
+            "full": true,
+            "models": ["m1"]
+        })));
+        assert!(actions.contains(&&serde_json::json!({
+            "path": "/repo",
+            "clear_graph_cache": true
+        })));
+
+        let mut result = DoctorResult {
+            env: DoctorEnvResult {
+                profile: "quality".to_string(),
+                model_dir: "/models".to_string(),
+                model_manifest_exists: true,
+                models: manifest,
+                gpu: runtime_env::GpuEnvReport {
+                    ort_lib_location: None,
+                    ld_library_path: None,
+                    provider_present: false,
+                    cublas_present: false,
+                    nvrtc_present: false,
+                    provider_dir: None,
+                    cublas_dir: None,
+                    nvrtc_dir: None,
+                    searched_paths: Vec::new(),
+                },
+                embedding_probe: None,
+                cuda_disabled: true,
+                allow_cpu_fallback: false,
+                allowed_roots: None,
+                prewarm: Vec::new(),
+            },
+            project: Some(project),
+            issues: findings.issues,
+            hints: findings.hints,
+            next_actions: findings.next_actions,
+            stats: Vec::new(),
+            truncated_sections: Vec::new(),
+            budget: DoctorBudget {
+                max_chars: 1_500,
+                used_chars: 0,
+                truncated: false,
+            },
+            meta: ToolMeta::default(),
+        };
+        router::doctor::trim_to_budget(&mut result).expect("fits");
+        assert!(result.budget.truncated);
+        assert!(result.budget.used_chars <= 1_500);
+        assert!(!result.issues.is_empty() && !result.next_actions.is_empty());
+        assert!(result.truncated_sections.contains(&DoctorSection::Drift));
     }
 
     fn mk_chunk(
@@ -2328,22 +2631,26 @@ mod tests {
             id: "src/main.rs:1:1".to_string(),
             chunk: mk_chunk("src/main.rs", 1, "fn main() {}"),
             score: 0.9,
+            freshness: None,
         };
         let primary_docs = SearchResult {
             id: "docs/readme.md:1:1".to_string(),
             chunk: mk_chunk("docs/readme.md", 1, "# docs"),
             score: 1.0,
+            freshness: None,
         };
 
         let related_docs = RelatedContext {
             chunk: mk_chunk("docs/guide.md", 1, "# guide"),
             relationship_path: vec!["Calls".to_string()],
+            symbol_path: Vec::new(),
             distance: 1,
             relevance_score: 0.5,
         };
         let related_code = RelatedContext {
             chunk: mk_chunk("src/lib.rs", 10, "pub fn f() {}"),
             relationship_path: vec!["Calls".to_string()],
+            symbol_path: Vec::new(),
             distance: 1,
             relevance_score: 0.6,
         };
@@ -2384,11 +2691,13 @@ mod tests {
             id: "src/main.rs:1:1".to_string(),
             chunk: mk_chunk("src/main.rs", 1, "fn main() {}"),
             score: 0.9,
+            freshness: None,
         };
         let primary_docs = SearchResult {
             id: "docs/readme.md:1:1".to_string(),
             chunk: mk_chunk("docs/readme.md", 1, "# docs"),
             score: 1.0,
+            freshness: None,
         };
 
         let enriched = vec![
@@ -2419,12 +2728,14 @@ mod tests {
         let related_miss = RelatedContext {
             chunk: mk_chunk("src/miss.rs", 1, "fn unrelated() {}"),
             relationship_path: vec!["Calls".to_string()],
+            symbol_path: Vec::new(),
             distance: 1,
             relevance_score: 100.0,
         };
         let related_hit = RelatedContext {
             chunk: mk_chunk("src/hit.rs", 1, "fn target() {}"),
             relationship_path: vec!["Calls".to_string()],
+            symbol_path: Vec::new(),
             distance: 1,
             relevance_score: 0.1,
         };
@@ -2437,4 +2748,31 @@ mod tests {
         );
         assert_eq!(prepared[0].chunk.file_path, "src/hit.rs");
     }
+
+    #[test]
+    fn prewarm_config_resolves_on_start_paths_against_the_launch_root() {
+        let root = Path::new("/repo");
+        let config = router::warm::parse_prewarm_config(
+            root,
+            &serde_json::json!({
+                "warm": {
+                    "on_start": ["crates/app", "/abs/project", "  ", 7],
+                    "language": "python",
+                    "embedder": true
+                }
+            }),
+        );
+        assert_eq!(
+            config.on_start,
+            vec![
+                PathBuf::from("/repo/crates/app"),
+                PathBuf::from("/abs/project")
+            ]
+        );
+        assert_eq!(config.language, Some(GraphLanguage::Python));
+        assert!(config.embedder);
+
+        let empty = router::warm::parse_prewarm_config(root, &serde_json::json!({ "watch": {} }));
+        assert_eq!(empty, router::warm::PrewarmConfig::default());
+    }
 }
@@ -1,16 +1,21 @@
 use super::super::{
-    compute_used_chars, extract_path_from_input, parse_tool_result_as_json, prepare_item_input,
-    push_item_or_truncate, resolve_batch_refs, trim_output_to_budget, BatchBudget, BatchItemResult,
-    BatchItemStatus, BatchRequest, BatchResult, BatchToolName, CallToolResult, CapabilitiesRequest,
-    Content, ContextFinderService, ContextPackRequest, ContextRequest, DoctorRequest,
-    ExplainRequest, FileSliceRequest, GrepContextRequest, ImpactRequest, IndexRequest,
-    ListFilesRequest, MapRequest, McpError, OverviewRequest, Parameters, SearchRequest,
-    TextSearchRequest, TraceRequest,
+    compute_used_chars, ensure_root_allowed, evaluate_when, extract_path_from_input,
+    parse_tool_result_as_json, prepare_item_input, push_item_or_truncate, resolve_batch_refs,
+    trim_output_to_budget, with_request_deadline, BatchBudget, BatchItemResult, BatchItemStatus,
+    BatchRequest, BatchResult, BatchToolName, CallToolResult, CapabilitiesRequest, Content,
+    ContextFinderService, ContextPackRequest, ContextRequest, DoctorRequest, ExplainRequest,
+    FileSliceRequest, GrepContextRequest, ImpactRequest, IndexRequest, ListFilesRequest,
+    MapRequest, McpError, OverviewRequest, Parameters, SearchRequest, TextSearchRequest,
+    TraceRequest,
 };
 use crate::tools::schemas::batch::BatchItem;
-use context_protocol::ErrorEnvelope;
+use context_batch_ref::referenced_items;
+use context_protocol::{ErrorCode, ErrorEnvelope};
+use futures::stream::{self, StreamExt};
 use std::collections::HashSet;
+use std::future::Future;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use super::error::{
     attach_meta, invalid_request, invalid_request_with, invalid_request_with_meta, meta_for_request,
@@ -20,6 +25,8 @@ const MAX_MAX_CHARS: usize = 500_000;
 const MIN_SUPPORTED_VERSION: u32 = 1;
 const LATEST_VERSION: u32 = 2;
 const DEFAULT_VERSION: u32 = LATEST_VERSION;
+/// Upper bound on items of one wave dispatched at the same time.
+const MAX_PARALLEL_ITEMS: usize = 8;
 
 type ToolResult<T> = std::result::Result<T, CallToolResult>;
 
@@ -41,11 +48,11 @@ fn retry_action(
     if let Some(path) = path {
         args["path"] = serde_json::Value::String(path.to_string());
     }
-    context_protocol::ToolNextAction {
-        tool: "batch".to_string(),
+    context_protocol::ToolNextAction::new(
+        "batch",
         args,
-        reason: "Increase max_chars to fit the batch response envelope.".to_string(),
-    }
+        "Increase max_chars to fit the batch response envelope.",
+    )
 }
 
 fn budget_error(
@@ -102,7 +109,11 @@ async fn dispatch_tool(
         BatchToolName::Search => typed_call!(SearchRequest, search, "search"),
         BatchToolName::Context => typed_call!(ContextRequest, context, "context"),
         BatchToolName::ContextPack => typed_call!(ContextPackRequest, context_pack, "context_pack"),
-        BatchToolName::Index => typed_call!(IndexRequest, index, "index"),
+        BatchToolName::Index => match serde_json::from_value::<IndexRequest>(input) {
+            // Batch items have no progress token of their own.
+            Ok(req) => super::index::index(service, req, None).await,
+            Err(err) => Ok(invalid_request(format!("Invalid input for index: {err}"))),
+        },
         BatchToolName::Impact => typed_call!(ImpactRequest, impact, "impact"),
         BatchToolName::Trace => typed_call!(TraceRequest, trace, "trace"),
         BatchToolName::Explain => typed_call!(ExplainRequest, explain, "explain"),
@@ -110,12 +121,108 @@ async fn dispatch_tool(
     }
 }
 
+/// Split items into consecutive waves whose members never read each other (via `$ref`,
+/// `$refList` or `when`), so each wave can run concurrently while results keep request order.
+/// `index` mutates shared state, so it always runs in a wave of its own.
+fn plan_waves(items: Vec<BatchItem>) -> Vec<Vec<BatchItem>> {
+    let mut waves: Vec<Vec<BatchItem>> = Vec::new();
+    let mut current: Vec<BatchItem> = Vec::new();
+    for item in items {
+        let refs = referenced_items(&item.input, item.when.as_ref());
+        let depends_on_current = item.tool == BatchToolName::Index
+            || current
+                .iter()
+                .any(|prev| prev.tool == BatchToolName::Index || refs.depends_on(prev.id.trim()));
+        if depends_on_current && !current.is_empty() {
+            waves.push(std::mem::take(&mut current));
+        }
+        current.push(item);
+    }
+    if !current.is_empty() {
+        waves.push(current);
+    }
+    waves
+}
+
+/// An item that passed validation and ref resolution and is ready to dispatch.
+struct PendingItem {
+    id: String,
+    tool: BatchToolName,
+    input: serde_json::Value,
+    time_budget: Option<Duration>,
+}
+
+/// An item settled while preparing its wave, without being dispatched.
+enum SettledItem {
+    /// Rejected before running (invalid id, bad `when`, ref or path errors).
+    Rejected {
+        id: String,
+        tool: BatchToolName,
+        message: String,
+    },
+    /// Not run (`when` false, batch deadline passed).
+    Skipped(Box<BatchItemResult>),
+}
+
+async fn run_pending_item<F, Fut>(pending: PendingItem, dispatch: &F) -> BatchItemResult
+where
+    F: Fn(BatchToolName, serde_json::Value) -> Fut,
+    Fut: Future<Output = std::result::Result<CallToolResult, McpError>>,
+{
+    let PendingItem {
+        id,
+        tool,
+        input,
+        time_budget,
+    } = pending;
+    let started = Instant::now();
+    let run = with_request_deadline(
+        time_budget.map(|limit| started + limit),
+        dispatch(tool, input),
+    );
+    let tool_result = match time_budget {
+        // Cancelling `index` midway could leave a half-written index; let it finish instead.
+        Some(limit) if tool != BatchToolName::Index => {
+            match tokio::time::timeout(limit, run).await {
+                Ok(result) => result,
+                Err(_) => return timeout_item(id, tool, limit, serde_json::Value::Null),
+            }
+        }
+        _ => run.await,
+    };
+    let outcome = materialize_item_result(id, tool, tool_result);
+    match time_budget {
+        Some(limit) if outcome.status == BatchItemStatus::Ok && started.elapsed() > limit => {
+            timeout_item(outcome.id, outcome.tool, limit, outcome.data)
+        }
+        _ => outcome,
+    }
+}
+
+/// Run the pending items of one wave concurrently; results come back in input order.
+async fn run_wave_items<F, Fut>(pending: Vec<PendingItem>, dispatch: F) -> Vec<BatchItemResult>
+where
+    F: Fn(BatchToolName, serde_json::Value) -> Fut,
+    Fut: Future<Output = std::result::Result<CallToolResult, McpError>>,
+{
+    let dispatch = &dispatch;
+    let mut results: Vec<(usize, BatchItemResult)> = stream::iter(pending.into_iter().enumerate())
+        .map(|(idx, item)| async move { (idx, run_pending_item(item, dispatch).await) })
+        .buffer_unordered(MAX_PARALLEL_ITEMS)
+        .collect()
+        .await;
+    results.sort_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
 struct BatchRunner<'a> {
     service: &'a ContextFinderService,
     stop_on_error: bool,
     inferred_path: Option<String>,
     seen_ids: HashSet<String>,
     ref_context: Option<serde_json::Value>,
+    deadline: Option<(Instant, u64)>,
+    item_timeout_ms: Option<u64>,
     output: BatchResult,
 }
 
@@ -136,7 +243,7 @@ impl<'a> BatchRunner<'a> {
                 truncation: None,
             },
             next_actions: Vec::new(),
-            meta: context_indexer::ToolMeta { index_state: None },
+            meta: context_indexer::ToolMeta::default(),
         };
         let ref_context = (version >= 2).then(|| {
             serde_json::json!({
@@ -152,6 +259,8 @@ impl<'a> BatchRunner<'a> {
             inferred_path,
             seen_ids: HashSet::new(),
             ref_context,
+            deadline: None,
+            item_timeout_ms: None,
             output,
         }
     }
@@ -161,6 +270,29 @@ impl<'a> BatchRunner<'a> {
         self
     }
 
+    fn with_deadline_ms(mut self, deadline_ms: Option<u64>) -> Self {
+        self.deadline = deadline_ms.map(|ms| (Instant::now() + Duration::from_millis(ms), ms));
+        self
+    }
+
+    const fn with_item_timeout_ms(mut self, item_timeout_ms: Option<u64>) -> Self {
+        self.item_timeout_ms = item_timeout_ms;
+        self
+    }
+
+    /// Time an item may run: its own `timeout_ms` (or the batch default), capped by what is left
+    /// of the batch deadline.
+    fn item_time_budget(&self, timeout_ms: Option<u64>) -> Option<Duration> {
+        let remaining = self
+            .deadline
+            .map(|(deadline, _)| deadline.saturating_duration_since(Instant::now()));
+        let timeout_ms = timeout_ms.or(self.item_timeout_ms);
+        match (timeout_ms.map(Duration::from_millis), remaining) {
+            (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+            (timeout, remaining) => timeout.or(remaining),
+        }
+    }
+
     const fn remaining_chars(&self) -> usize {
         self.output
             .budget
@@ -214,7 +346,7 @@ impl<'a> BatchRunner<'a> {
         tool: BatchToolName,
         message: String,
     ) -> ToolResult<bool> {
-        let rejected = batch_error_item(id, tool, "invalid_request", message);
+        let rejected = batch_error_item(id, tool, ErrorCode::InvalidRequest, message);
 
         let pushed = push_item_or_truncate(&mut self.output, rejected).map_err(|err| {
             budget_error(
@@ -253,31 +385,63 @@ impl<'a> BatchRunner<'a> {
                 .is_some_and(|v| v.status == BatchItemStatus::Error)))
     }
 
-    async fn run_item(&mut self, item: BatchItem) -> ToolResult<bool> {
+    fn prepare_item(
+        &mut self,
+        item: BatchItem,
+        max_chars_share: usize,
+    ) -> Result<PendingItem, SettledItem> {
+        let reject = |id: String, message: String| SettledItem::Rejected {
+            id,
+            tool: item.tool,
+            message,
+        };
         let trimmed_id = item.id.trim().to_string();
         if trimmed_id.is_empty() {
-            return self.push_rejected(
+            return Err(reject(
                 item.id,
-                item.tool,
                 "Batch item id must not be empty".to_string(),
-            );
+            ));
         }
 
         if !self.seen_ids.insert(trimmed_id.clone()) {
             let message = format!("Duplicate batch item id is not supported: '{trimmed_id}'");
-            return self.push_rejected(trimmed_id, item.tool, message);
+            return Err(reject(trimmed_id, message));
+        }
+
+        if let Some((deadline, deadline_ms)) = self.deadline {
+            if Instant::now() >= deadline {
+                let mut skipped = skipped_item(trimmed_id, item.tool);
+                skipped.message = Some(format!(
+                    "Batch deadline (deadline_ms={deadline_ms}) passed before this item started; rerun it in a new batch or raise deadline_ms."
+                ));
+                return Err(SettledItem::Skipped(Box::new(skipped)));
+            }
+        }
+
+        if let Some(when) = item.when.as_ref() {
+            let Some(ctx) = self.ref_context.as_ref() else {
+                return Err(reject(
+                    trimmed_id,
+                    "`when` requires batch version 2".to_string(),
+                ));
+            };
+            match evaluate_when(when, ctx) {
+                Ok(true) => {}
+                Ok(false) => {
+                    return Err(SettledItem::Skipped(Box::new(skipped_item(
+                        trimmed_id, item.tool,
+                    ))))
+                }
+                Err(err) => {
+                    return Err(reject(trimmed_id, format!("Invalid when predicate: {err}")))
+                }
+            }
         }
 
         let resolved_input = if let Some(ctx) = self.ref_context.as_ref() {
             match resolve_batch_refs(item.input, ctx) {
                 Ok(value) => value,
-                Err(err) => {
-                    return self.push_rejected(
-                        trimmed_id,
-                        item.tool,
-                        format!("Ref resolution error: {err}"),
-                    );
-                }
+                Err(err) => return Err(reject(trimmed_id, format!("Ref resolution error: {err}"))),
             }
         } else {
             item.input
@@ -286,13 +450,10 @@ impl<'a> BatchRunner<'a> {
         if let Some(item_path) = extract_path_from_input(&resolved_input) {
             if let Some(batch_path) = self.inferred_path.as_deref() {
                 if batch_path != item_path {
-                    return self.push_rejected(
-                        trimmed_id,
-                        item.tool,
-                        format!(
-                            "Batch path mismatch: batch uses '{batch_path}', item uses '{item_path}'"
-                        ),
+                    let message = format!(
+                        "Batch path mismatch: batch uses '{batch_path}', item uses '{item_path}'"
                     );
+                    return Err(reject(trimmed_id, message));
                 }
             } else {
                 self.inferred_path = Some(item_path);
@@ -304,12 +465,62 @@ impl<'a> BatchRunner<'a> {
             resolved_input,
             self.inferred_path.as_deref(),
             item.tool,
-            self.remaining_chars(),
+            max_chars_share,
         );
-        let tool_result = dispatch_tool(self.service, item.tool, input).await;
-        let outcome = materialize_item_result(trimmed_id, item.tool, tool_result);
+        Ok(PendingItem {
+            id: trimmed_id,
+            tool: item.tool,
+            input,
+            time_budget: self.item_time_budget(item.timeout_ms),
+        })
+    }
 
-        self.push_processed(outcome)
+    /// Prepare, dispatch concurrently and record one wave. Items of a wave split the remaining
+    /// char budget evenly; results are pushed in request order, so budget truncation and
+    /// `stop_on_error` cut the output exactly where a sequential run would.
+    async fn run_wave(&mut self, wave: Vec<BatchItem>) -> ToolResult<bool> {
+        let max_chars_share = self.remaining_chars() / wave.len().max(1);
+        // `None` marks an item handed to `pending`; its outcome is taken in the same order.
+        let mut settled: Vec<Option<SettledItem>> = Vec::with_capacity(wave.len());
+        let mut pending: Vec<PendingItem> = Vec::new();
+        for item in wave {
+            match self.prepare_item(item, max_chars_share) {
+                Ok(item) => {
+                    pending.push(item);
+                    settled.push(None);
+                }
+                Err(item) => {
+                    let stops = self.stop_on_error && matches!(item, SettledItem::Rejected { .. });
+                    settled.push(Some(item));
+                    if stops {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let service = self.service;
+        let mut outcomes =
+            run_wave_items(pending, |tool, input| dispatch_tool(service, tool, input))
+                .await
+                .into_iter();
+
+        for item in settled {
+            let keep_going = match item {
+                Some(SettledItem::Rejected { id, tool, message }) => {
+                    self.push_rejected(id, tool, message)?
+                }
+                Some(SettledItem::Skipped(result)) => self.push_processed(*result)?,
+                None => match outcomes.next() {
+                    Some(result) => self.push_processed(result)?,
+                    None => true,
+                },
+            };
+            if !keep_going {
+                return Ok(false);
+            }
+        }
+        Ok(true)
     }
 
     fn finish(self) -> CallToolResult {
@@ -325,6 +536,9 @@ impl<'a> BatchRunner<'a> {
         let Ok(root) = PathBuf::from(raw_path).canonicalize() else {
             return Ok(());
         };
+        if ensure_root_allowed(&root).is_err() {
+            return Ok(());
+        }
         self.output.meta = self.service.tool_meta(&root).await;
         trim_output_to_budget(&mut self.output).map_err(|err| {
             budget_error(
@@ -393,7 +607,7 @@ fn materialize_item_result(
         },
         Err(err) => {
             let error = ErrorEnvelope {
-                code: "tool_error".to_string(),
+                code: ErrorCode::ToolError.to_string(),
                 message: err.to_string(),
                 details: None,
                 hint: None,
@@ -411,10 +625,54 @@ fn materialize_item_result(
     }
 }
 
+/// Placeholder for an item whose `when` predicate was false: keeps the id addressable.
+const fn skipped_item(id: String, tool: BatchToolName) -> BatchItemResult {
+    BatchItemResult {
+        id,
+        tool,
+        status: BatchItemStatus::Skipped,
+        message: None,
+        error: None,
+        data: serde_json::Value::Null,
+    }
+}
+
+/// Error item for an item that ran past its time budget. `data` is kept for items that were
+/// allowed to finish (`index`), so callers can still see what was done.
+fn timeout_item(
+    id: String,
+    tool: BatchToolName,
+    limit: Duration,
+    data: serde_json::Value,
+) -> BatchItemResult {
+    let limit_ms = limit.as_millis();
+    let message = if data.is_null() {
+        format!("Item exceeded its time budget ({limit_ms} ms) and was cancelled")
+    } else {
+        format!("Item exceeded its time budget ({limit_ms} ms); it ran to completion to keep the index consistent")
+    };
+    BatchItemResult {
+        id,
+        tool,
+        status: BatchItemStatus::Error,
+        message: Some(message.clone()),
+        error: Some(ErrorEnvelope {
+            code: ErrorCode::Timeout.to_string(),
+            message,
+            details: None,
+            hint: Some(
+                "Raise timeout_ms/deadline_ms or run the item in its own batch.".to_string(),
+            ),
+            next_actions: Vec::new(),
+        }),
+        data,
+    }
+}
+
 fn batch_error_item(
     id: String,
     tool: BatchToolName,
-    code: &str,
+    code: ErrorCode,
     message: String,
 ) -> BatchItemResult {
     BatchItemResult {
@@ -474,7 +732,7 @@ pub(in crate::tools::dispatch) async fn batch(
             truncation: None,
         },
         next_actions: Vec::new(),
-        meta: context_indexer::ToolMeta { index_state: None },
+        meta: context_indexer::ToolMeta::default(),
     };
     if let Ok(min_chars) = compute_used_chars(&min_payload) {
         if min_chars > max_chars {
@@ -498,11 +756,13 @@ pub(in crate::tools::dispatch) async fn batch(
         }
     };
     let mut runner = BatchRunner::new(service, version, max_chars, inferred_path)
-        .with_stop_on_error(request.stop_on_error);
+        .with_stop_on_error(request.stop_on_error)
+        .with_deadline_ms(request.deadline_ms)
+        .with_item_timeout_ms(request.item_timeout_ms);
     runner.update_ref_context_path();
 
-    for item in request.items {
-        match runner.run_item(item).await {
+    for wave in plan_waves(request.items) {
+        match runner.run_wave(wave).await {
             Ok(true) => {}
             Ok(false) => break,
             Err(result) => return Ok(attach_meta(result, meta.clone())),
@@ -533,4 +793,134 @@ mod tests {
         assert!(validate_batch_version(0).is_some());
         assert!(validate_batch_version(3).is_some());
     }
+
+    fn item(id: &str, tool: BatchToolName, input: serde_json::Value) -> BatchItem {
+        BatchItem {
+            id: id.to_string(),
+            tool,
+            input,
+            when: None,
+            timeout_ms: None,
+        }
+    }
+
+    fn wave_ids(waves: &[Vec<BatchItem>]) -> Vec<Vec<&str>> {
+        waves
+            .iter()
+            .map(|wave| wave.iter().map(|item| item.id.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn plan_waves_splits_on_refs_and_index() {
+        let mut gated = item("gated", BatchToolName::Map, serde_json::json!({}));
+        gated.when = Some(serde_json::json!({ "exists": "#/items/slice/data" }));
+        let items = vec![
+            item(
+                "hits",
+                BatchToolName::TextSearch,
+                serde_json::json!({ "pattern": "a" }),
+            ),
+            item("files", BatchToolName::ListFiles, serde_json::json!({})),
+            item(
+                "slice",
+                BatchToolName::FileSlice,
+                serde_json::json!({ "file": { "$ref": "#/items/hits/data/matches/0/file" } }),
+            ),
+            item("map", BatchToolName::Map, serde_json::json!({})),
+            gated,
+            item("index", BatchToolName::Index, serde_json::json!({})),
+            item(
+                "search",
+                BatchToolName::Search,
+                serde_json::json!({ "query": "a" }),
+            ),
+        ];
+
+        let waves = plan_waves(items);
+        assert_eq!(
+            wave_ids(&waves),
+            vec![
+                vec!["hits", "files"],
+                vec!["slice", "map"],
+                vec!["gated"],
+                vec!["index"],
+                vec!["search"],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn independent_items_run_concurrently_in_stable_order() {
+        let delays_ms = [150u64, 100, 50];
+        let pending: Vec<PendingItem> = delays_ms
+            .iter()
+            .enumerate()
+            .map(|(idx, delay_ms)| PendingItem {
+                id: format!("item{idx}"),
+                tool: BatchToolName::Map,
+                input: serde_json::json!({ "delay_ms": delay_ms }),
+                time_budget: None,
+            })
+            .collect();
+
+        let started = Instant::now();
+        let results = run_wave_items(pending, |_tool, input| async move {
+            let delay_ms = input["delay_ms"].as_u64().unwrap_or(0);
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            Ok(CallToolResult::success(vec![Content::text(
+                input.to_string(),
+            )]))
+        })
+        .await;
+        let elapsed = started.elapsed();
+
+        assert!(
+            elapsed < Duration::from_millis(250),
+            "expected roughly one item's time (150ms), took {elapsed:?}"
+        );
+        let ids: Vec<&str> = results.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, vec!["item0", "item1", "item2"]);
+        assert!(results
+            .iter()
+            .zip(delays_ms)
+            .all(|(item, delay_ms)| item.status == BatchItemStatus::Ok
+                && item.data["delay_ms"] == delay_ms));
+    }
+
+    #[tokio::test]
+    async fn slow_item_times_out_without_failing_siblings() {
+        let pending = vec![
+            PendingItem {
+                id: "slow".to_string(),
+                tool: BatchToolName::Map,
+                input: serde_json::json!({ "delay_ms": 500 }),
+                time_budget: Some(Duration::from_millis(50)),
+            },
+            PendingItem {
+                id: "fast".to_string(),
+                tool: BatchToolName::ListFiles,
+                input: serde_json::json!({ "delay_ms": 0 }),
+                time_budget: Some(Duration::from_millis(50)),
+            },
+        ];
+
+        let results = run_wave_items(pending, |_tool, input| async move {
+            let delay_ms = input["delay_ms"].as_u64().unwrap_or(0);
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            Ok(CallToolResult::success(vec![Content::text(
+                input.to_string(),
+            )]))
+        })
+        .await;
+
+        assert_eq!(results[0].id, "slow");
+        assert_eq!(results[0].status, BatchItemStatus::Error);
+        assert_eq!(
+            results[0].error.as_ref().map(|err| err.code.as_str()),
+            Some("timeout")
+        );
+        assert_eq!(results[1].id, "fast");
+        assert_eq!(results[1].status, BatchItemStatus::Ok);
+    }
 }
@@ -23,14 +23,14 @@ pub(in crate::tools::dispatch) async fn capabilities(
     };
 
     let budgets = DefaultBudgets::default();
-    let start_route = ToolNextAction {
-        tool: "repo_onboarding_pack".to_string(),
-        args: json!({
+    let start_route = ToolNextAction::new(
+        "repo_onboarding_pack",
+        json!({
             "path": root_display,
             "max_chars": budgets.repo_onboarding_pack_max_chars
         }),
-        reason: "Start with a compact repo map + key docs (onboarding pack).".to_string(),
-    };
+        "Start with a compact repo map + key docs (onboarding pack).",
+    );
 
     let output = Capabilities {
         schema_version: CAPABILITIES_SCHEMA_VERSION,
@@ -1,24 +1,146 @@
 use super::super::{
-    AutoIndexPolicy, CallToolResult, Content, ContextFinderService, ContextHit, ContextRequest,
-    ContextResult, McpError, RelatedCode,
+    resolve_graph_language, AutoIndexPolicy, CallToolResult, Content, ContextFinderService,
+    ContextHit, ContextRequest, ContextResult, McpError, RelatedCode, WorkspaceMember,
 };
 
 use super::error::{
-    index_recovery_actions, internal_error_with_meta, invalid_request_with_meta, meta_for_request,
-    tool_error_envelope_with_meta,
+    index_recovery_actions, internal_error_with_meta, invalid_cursor_with_meta,
+    invalid_request_with_meta, meta_for_request, tool_error_envelope_with_meta,
 };
-use context_protocol::ErrorEnvelope;
+use super::search::{take_page, SearchPaging, MAX_PAGED_HITS};
+use super::workspace::{merge_member_hits, resolve_workspace_scope, WorkspaceRun};
+use context_graph::analysis::unsupported_language_hint;
+use context_indexer::ToolMeta;
+use context_protocol::{ErrorCode, ErrorEnvelope, ToolNextAction};
+
+fn parse_strategy(raw: Option<&str>) -> context_graph::AssemblyStrategy {
+    match raw {
+        Some("direct") => context_graph::AssemblyStrategy::Direct,
+        Some("deep") => context_graph::AssemblyStrategy::Deep,
+        _ => context_graph::AssemblyStrategy::Extended,
+    }
+}
+
+/// One primary hit with up to five related chunks; paths gain the member prefix when the hit
+/// comes from a workspace member.
+fn context_hit(er: context_search::EnrichedResult, member: Option<&WorkspaceMember>) -> ContextHit {
+    let display = |file: &str| member.map_or_else(|| file.to_string(), |m| m.prefixed(file));
+    let related: Vec<RelatedCode> = er
+        .related
+        .iter()
+        .take(5)
+        .map(|rc| RelatedCode {
+            file: display(&rc.chunk.file_path),
+            lines: format!("{}-{}", rc.chunk.start_line, rc.chunk.end_line),
+            symbol: rc.chunk.metadata.symbol_name.clone(),
+            relationship: rc.relationship_path.join(" -> "),
+        })
+        .collect();
+
+    let chunk = er.primary.chunk;
+    ContextHit {
+        file: display(&chunk.file_path),
+        start_line: chunk.start_line,
+        end_line: chunk.end_line,
+        symbol: chunk.metadata.symbol_name,
+        score: er.primary.score,
+        content: chunk.content,
+        related,
+    }
+}
+
+/// `context` over several roots; the graph walk stays inside each member, and the merged
+/// ranking has no cursor.
+async fn context_workspace(
+    service: &ContextFinderService,
+    request: &ContextRequest,
+    members: &[WorkspaceMember],
+    limit: usize,
+) -> CallToolResult {
+    let policy = AutoIndexPolicy::from_request(request.auto_index, request.auto_index_budget_ms);
+    let strategy = parse_strategy(request.strategy.as_deref());
+    let mut run = WorkspaceRun::default();
+    if request
+        .cursor
+        .as_deref()
+        .is_some_and(|c| !c.trim().is_empty())
+    {
+        return invalid_request_with_meta(
+            "Error: cursor is not supported for multi-root context",
+            run.meta(service),
+            Some("Rerun without cursor and raise limit instead.".to_string()),
+            Vec::new(),
+        );
+    }
+
+    let mut per_member: Vec<Vec<ContextHit>> = Vec::new();
+    let mut hints: Vec<String> = Vec::new();
+    for member in members {
+        let Some(mut engine) = run.lock_member(service, member, policy).await else {
+            continue;
+        };
+        match resolve_graph_language(
+            &mut engine,
+            request.language.as_deref(),
+            &mut ToolMeta::default(),
+        ) {
+            Ok(language) => {
+                if let Err(err) = engine.engine_mut().ensure_graph(language).await {
+                    run.fail_last(format!("Graph build error: {err}"));
+                    continue;
+                }
+            }
+            Err(language) => {
+                engine.engine_mut().drop_graph();
+                hints.push(format!(
+                    "{}: {}",
+                    member.name,
+                    unsupported_language_hint(&language)
+                ));
+            }
+        }
+        let enriched = engine
+            .engine_mut()
+            .context_search
+            .search_with_context(&request.query, limit, strategy)
+            .await;
+        drop(engine);
+        match enriched {
+            Ok(enriched) => per_member.push(
+                enriched
+                    .into_iter()
+                    .map(|er| context_hit(er, Some(member)))
+                    .collect(),
+            ),
+            Err(err) => run.fail_last(format!("Search error: {err}")),
+        }
+    }
+    if let Some(error) = run.unusable_error(service) {
+        return error;
+    }
+
+    let mut results = merge_member_hits(per_member, |hit| &mut hit.score);
+    results.truncate(limit);
+    let result = ContextResult {
+        related_count: results.iter().map(|hit| hit.related.len()).sum(),
+        results,
+        next_cursor: None,
+        next_actions: Vec::new(),
+        hint: (!hints.is_empty()).then(|| hints.join(" ")),
+        meta: run.meta(service),
+    };
+    CallToolResult::success(vec![Content::text(
+        context_protocol::serialize_json(&result).unwrap_or_default(),
+    )])
+}
+
 /// Search with graph context
 pub(in crate::tools::dispatch) async fn context(
     service: &ContextFinderService,
     request: ContextRequest,
 ) -> Result<CallToolResult, McpError> {
     let limit = request.limit.unwrap_or(5).clamp(1, 20);
-    let strategy = match request.strategy.as_deref() {
-        Some("direct") => context_graph::AssemblyStrategy::Direct,
-        Some("deep") => context_graph::AssemblyStrategy::Deep,
-        _ => context_graph::AssemblyStrategy::Extended,
-    };
+    let strategy = parse_strategy(request.strategy.as_deref());
 
     if request.query.trim().is_empty() {
         let meta = meta_for_request(service, request.path.as_deref()).await;
@@ -30,6 +152,24 @@ pub(in crate::tools::dispatch) async fn context(
         ));
     }
 
+    match resolve_workspace_scope(
+        service,
+        request.path.as_deref(),
+        request.workspace,
+        request.roots.as_deref(),
+    )
+    .await
+    {
+        Ok(Some(members)) => {
+            return Ok(context_workspace(service, &request, &members, limit).await);
+        }
+        Ok(None) => {}
+        Err(message) => {
+            let meta = meta_for_request(service, request.path.as_deref()).await;
+            return Ok(invalid_request_with_meta(message, meta, None, Vec::new()));
+        }
+    }
+
     let (root, root_display) = match service.resolve_root(request.path.as_deref()).await {
         Ok(value) => value,
         Err(message) => {
@@ -39,17 +179,15 @@ pub(in crate::tools::dispatch) async fn context(
     };
 
     let policy = AutoIndexPolicy::from_request(request.auto_index, request.auto_index_budget_ms);
-    let (mut engine, meta) = match service.prepare_semantic_engine(&root, policy).await {
+    let (mut engine, mut meta) = match service.prepare_semantic_engine(&root, policy).await {
         Ok(engine) => engine,
         Err(e) => {
             let message = format!("Error: {e}");
             let meta = service.tool_meta(&root).await;
-            if message.contains("Index not found")
-                || message.contains("No semantic indices available")
-            {
+            if ErrorCode::from_chain(&e) == Some(ErrorCode::IndexMissing) {
                 return Ok(tool_error_envelope_with_meta(
                     ErrorEnvelope {
-                        code: "index_missing".to_string(),
+                        code: ErrorCode::IndexMissing.to_string(),
                         message,
                         details: None,
                         hint: Some("Index missing — run index (see next_actions).".to_string()),
@@ -62,27 +200,42 @@ pub(in crate::tools::dispatch) async fn context(
         }
     };
 
-    let enriched = {
-        let language = request.language.as_deref().map_or_else(
-            || {
-                ContextFinderService::detect_language(
-                    engine.engine_mut().context_search.hybrid().chunks(),
-                )
-            },
-            |lang| ContextFinderService::parse_language(Some(lang)),
-        );
+    let mut paging = SearchPaging::new(
+        service,
+        "context",
+        &root_display,
+        &request.query,
+        engine.engine_mut().canonical_index_mtime,
+    );
+    paging.strategy = request.strategy.as_deref();
+    paging.language = request.language.as_deref();
+    let offset = match paging.start_offset(request.cursor.as_deref()) {
+        Ok(offset) => offset,
+        Err(message) => return Ok(invalid_cursor_with_meta(message, meta)),
+    };
+    let fetch = (offset + limit).min(MAX_PAGED_HITS) + 1;
 
-        if let Err(e) = engine.engine_mut().ensure_graph(language).await {
-            return Ok(internal_error_with_meta(
-                format!("Graph build error: {e}"),
-                meta.clone(),
-            ));
+    let mut hint = None;
+    let enriched = {
+        match resolve_graph_language(&mut engine, request.language.as_deref(), &mut meta) {
+            Ok(language) => {
+                if let Err(e) = engine.engine_mut().ensure_graph(language).await {
+                    return Ok(internal_error_with_meta(
+                        format!("Graph build error: {e}"),
+                        meta.clone(),
+                    ));
+                }
+            }
+            Err(language) => {
+                engine.engine_mut().drop_graph();
+                hint = Some(unsupported_language_hint(&language));
+            }
         }
 
         match engine
             .engine_mut()
             .context_search
-            .search_with_context(&request.query, limit, strategy)
+            .search_with_context(&request.query, fetch, strategy)
             .await
         {
             Ok(r) => r,
@@ -97,41 +250,41 @@ pub(in crate::tools::dispatch) async fn context(
 
     drop(engine);
 
-    let mut related_count = 0;
+    let (enriched, next_offset) = take_page(enriched, offset, limit);
+    let next_cursor = match next_offset.map(|next| paging.encode(next)).transpose() {
+        Ok(cursor) => cursor,
+        Err(err) => return Ok(internal_error_with_meta(format!("Error: {err:#}"), meta)),
+    };
+    let next_actions = next_cursor
+        .iter()
+        .map(|cursor| {
+            ToolNextAction::new(
+                "context",
+                serde_json::json!({
+                    "path": root_display,
+                    "query": request.query,
+                    "limit": limit,
+                    "strategy": request.strategy,
+                    "language": request.language,
+                    "cursor": cursor,
+                }),
+                "Continue context pagination with the next cursor.",
+            )
+        })
+        .collect();
+
     let results: Vec<ContextHit> = enriched
         .into_iter()
-        .map(|er| {
-            let related: Vec<RelatedCode> = er
-                .related
-                .iter()
-                .take(5)
-                .map(|rc| {
-                    related_count += 1;
-                    RelatedCode {
-                        file: rc.chunk.file_path.clone(),
-                        lines: format!("{}-{}", rc.chunk.start_line, rc.chunk.end_line),
-                        symbol: rc.chunk.metadata.symbol_name.clone(),
-                        relationship: rc.relationship_path.join(" -> "),
-                    }
-                })
-                .collect();
-
-            let symbol = er.primary.chunk.metadata.symbol_name;
-            ContextHit {
-                file: er.primary.chunk.file_path,
-                start_line: er.primary.chunk.start_line,
-                end_line: er.primary.chunk.end_line,
-                symbol,
-                score: er.primary.score,
-                content: er.primary.chunk.content,
-                related,
-            }
-        })
+        .map(|er| context_hit(er, None))
         .collect();
+    let related_count = results.iter().map(|hit| hit.related.len()).sum();
 
     let result = ContextResult {
         results,
         related_count,
+        next_cursor,
+        next_actions,
+        hint,
         meta,
     };
 
@@ -3,9 +3,15 @@ use super::super::{
     pack_enriched_results, prepare_context_pack_enriched, tokenize_focus_query, unix_ms,
     AutoIndexPolicy, CallToolResult, Content, ContextFinderService, ContextPackOutput,
     ContextPackRequest, GraphDocConfig, GraphNodeDoc, GraphNodeStore, GraphNodeStoreMeta, McpError,
-    QueryClassifier, QueryKind, QueryType, RelatedMode, CONTEXT_PACK_VERSION, GRAPH_DOC_VERSION,
+    PackBudget, QueryClassifier, QueryKind, QueryType, RelatedMode, ToolMeta, WorkspaceMember,
+    CONTEXT_PACK_VERSION, GRAPH_DOC_VERSION,
 };
-use context_protocol::{enforce_max_chars, BudgetTruncation, ErrorEnvelope, ToolNextAction};
+use context_graph::analysis::detect_language;
+use context_protocol::{
+    enforce_max_chars, enforce_max_tokens, finalize_used_chars, BudgetTruncation, ErrorCode,
+    ErrorEnvelope, ToolNextAction,
+};
+use context_search::{oldest_indexed_at_ms, pack_order, parse_query};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
@@ -15,6 +21,8 @@ use super::error::{
     attach_meta, index_recovery_actions, internal_error, internal_error_with_meta, invalid_request,
     invalid_request_with_meta, meta_for_request, tool_error_envelope_with_meta,
 };
+use super::search::select_models;
+use super::workspace::{merge_member_hits, resolve_workspace_scope, WorkspaceRun};
 
 #[derive(Clone, Copy, Debug)]
 struct ContextPackFlags(u8);
@@ -47,6 +55,7 @@ struct ContextPackInputs {
     path: Option<String>,
     limit: usize,
     max_chars: usize,
+    max_tokens: Option<usize>,
     max_related_per_primary: usize,
     flags: ContextPackFlags,
     query_type: QueryType,
@@ -94,33 +103,56 @@ fn parse_related_mode(
     }
 }
 
+/// Drop the tail item (leaving a stub for it), then the stubs themselves once no item is left.
+fn drop_last_item(output: &mut ContextPackOutput) -> bool {
+    if let Some(last) = output.items.len().checked_sub(1) {
+        output.omit_item(last);
+        return true;
+    }
+    output.omitted.pop().is_some()
+}
+
+fn mark_truncated(output: &mut ContextPackOutput, reason: BudgetTruncation) {
+    output.budget.truncated = true;
+    if output.budget.truncation.is_none() {
+        output.budget.truncation = Some(reason);
+    }
+}
+
 fn enforce_context_pack_budget(output: &mut ContextPackOutput) -> ToolResult<()> {
     let max_chars = output.budget.max_chars;
     enforce_max_chars(
         output,
         max_chars,
         |inner, used| inner.budget.used_chars = used,
-        |inner| {
-            inner.budget.truncated = true;
-            if inner.budget.truncation.is_none() {
-                inner.budget.truncation = Some(BudgetTruncation::MaxChars);
-            }
-        },
-        |inner| {
-            if !inner.items.is_empty() {
-                inner.items.pop();
-                inner.budget.dropped_items += 1;
-                return true;
-            }
-            false
-        },
+        |inner| mark_truncated(inner, BudgetTruncation::MaxChars),
+        drop_last_item,
     )
-    .map(|_| ())
     .map_err(|err| {
         invalid_request(format!(
             "Error: max_chars too small for response envelope ({err:#})"
         ))
-    })
+    })?;
+
+    let Some(max_tokens) = output.budget.max_tokens else {
+        return Ok(());
+    };
+    enforce_max_tokens(
+        output,
+        max_tokens,
+        |inner, used| inner.budget.used_tokens = Some(used),
+        |inner| mark_truncated(inner, BudgetTruncation::MaxTokens),
+        drop_last_item,
+    )
+    .map_err(|err| {
+        invalid_request(format!(
+            "Error: max_tokens too small for response envelope ({err:#})"
+        ))
+    })?;
+    // Items dropped for tokens only shrink the output, so the char count just needs a refresh.
+    finalize_used_chars(output, |inner, used| inner.budget.used_chars = used)
+        .map(|_| ())
+        .map_err(|err| internal_error(format!("Error: {err:#}")))
 }
 
 fn parse_inputs(request: &ContextPackRequest) -> ToolResult<ContextPackInputs> {
@@ -130,12 +162,15 @@ fn parse_inputs(request: &ContextPackRequest) -> ToolResult<ContextPackInputs> {
 
     let limit = request.limit.unwrap_or(10).clamp(1, 50);
     let max_chars = request.max_chars.unwrap_or(20_000).max(1_000);
+    let max_tokens = request.max_tokens.map(|tokens| tokens.max(250));
     let max_related_per_primary = request.max_related_per_primary.unwrap_or(3).clamp(0, 12);
     let trace = request.trace.unwrap_or(false);
     let auto_index = request.auto_index.unwrap_or(true);
 
-    let query_type = QueryClassifier::classify(&request.query);
-    let docs_intent = QueryClassifier::is_docs_intent(&request.query);
+    // `-term` exclusions and quote marks say nothing about what kind of query this is.
+    let query_text = parse_query(&request.query).text;
+    let query_type = QueryClassifier::classify(&query_text);
+    let docs_intent = QueryClassifier::is_docs_intent(&query_text);
     let strategy = parse_strategy(request.strategy.as_deref(), docs_intent, query_type);
 
     let include_docs = request.include_docs.unwrap_or(true);
@@ -148,7 +183,7 @@ fn parse_inputs(request: &ContextPackRequest) -> ToolResult<ContextPackInputs> {
     } else {
         limit.saturating_add(50).min(200)
     };
-    let query_tokens = tokenize_focus_query(&request.query);
+    let query_tokens = tokenize_focus_query(&query_text);
     let flags = {
         let mut bits = 0u8;
         if trace {
@@ -170,6 +205,7 @@ fn parse_inputs(request: &ContextPackRequest) -> ToolResult<ContextPackInputs> {
         path: request.path.clone(),
         limit,
         max_chars,
+        max_tokens,
         max_related_per_primary,
         flags,
         query_type,
@@ -187,7 +223,7 @@ fn select_language(
     raw.map_or_else(
         || {
             let chunks = engine.engine_mut().context_search.hybrid().chunks();
-            ContextFinderService::detect_language(chunks)
+            detect_language(chunks)
         },
         |lang| ContextFinderService::parse_language(Some(lang)),
     )
@@ -289,7 +325,7 @@ fn merge_graph_node_rrf_scores(
 fn append_graph_node_hits(
     service: &ContextFinderService,
     assembler: &context_graph::ContextAssembler,
-    chunks: &[context_code_chunker::CodeChunk],
+    hybrid: &context_search::MultiModelHybridSearch,
     chunk_lookup: &HashMap<String, usize>,
     hits: &[context_vector_store::GraphNodeHit],
     strategy: context_graph::AssemblyStrategy,
@@ -305,7 +341,7 @@ fn append_graph_node_hits(
         let Some(&chunk_idx) = chunk_lookup.get(&hit.chunk_id) else {
             continue;
         };
-        let Some(chunk) = chunks.get(chunk_idx).cloned() else {
+        let Some(chunk) = hybrid.chunks().get(chunk_idx).cloned() else {
             continue;
         };
         if service.profile.is_rejected(&chunk.file_path) {
@@ -319,12 +355,7 @@ fn append_graph_node_hits(
             related = assembled
                 .related_chunks
                 .into_iter()
-                .map(|rc| context_search::RelatedContext {
-                    chunk: rc.chunk,
-                    relationship_path: rc.relationship.iter().map(|r| format!("{r:?}")).collect(),
-                    distance: rc.distance,
-                    relevance_score: rc.relevance_score,
-                })
+                .map(context_search::RelatedContext::from)
                 .collect();
         }
 
@@ -333,6 +364,7 @@ fn append_graph_node_hits(
                 chunk,
                 score: 0.0,
                 id: hit.chunk_id.clone(),
+                freshness: hybrid.chunk_freshness(chunk_idx),
             },
             related,
             total_lines,
@@ -398,7 +430,7 @@ async fn maybe_apply_graph_nodes(
         return Ok(());
     };
 
-    let chunks = engine_ref.context_search.hybrid().chunks();
+    let hybrid = engine_ref.context_search.hybrid();
     let chunk_lookup = &engine_ref.chunk_lookup;
 
     let store = load_or_build_graph_nodes_store(
@@ -428,7 +460,7 @@ async fn maybe_apply_graph_nodes(
     append_graph_node_hits(
         service,
         assembler,
-        chunks,
+        hybrid,
         chunk_lookup,
         &hits,
         ctx.strategy,
@@ -436,12 +468,7 @@ async fn maybe_apply_graph_nodes(
     );
     apply_fused_scores(enriched, &fused);
 
-    enriched.sort_by(|a, b| {
-        b.primary
-            .score
-            .total_cmp(&a.primary.score)
-            .then_with(|| a.primary.id.cmp(&b.primary.id))
-    });
+    enriched.sort_by(|a, b| pack_order(&a.primary, &b.primary));
     enriched.truncate(ctx.candidate_limit);
     Ok(())
 }
@@ -488,6 +515,141 @@ fn append_trace_debug(
     ));
 }
 
+/// Rank, pack and budget the enriched hits. `retry_args` are the arguments a larger-budget
+/// retry repeats; its `max_chars` is filled in here.
+fn pack_output(
+    service: &ContextFinderService,
+    query: &str,
+    inputs: &ContextPackInputs,
+    enriched: Vec<context_search::EnrichedResult>,
+    meta: ToolMeta,
+    mut retry_args: serde_json::Value,
+) -> ToolResult<ContextPackOutput> {
+    let enriched = prepare_context_pack_enriched(
+        enriched,
+        inputs.limit,
+        inputs.flags.prefer_code(),
+        inputs.flags.include_docs(),
+    );
+
+    let (items, budget, omitted) = pack_enriched_results(
+        &service.profile,
+        enriched,
+        PackBudget::new(inputs.max_chars, inputs.max_tokens),
+        inputs.max_related_per_primary,
+        inputs.related_mode,
+        &inputs.query_tokens,
+    );
+    let model_id = current_model_id().unwrap_or_else(|_| "bge-small".to_string());
+    let mut output = ContextPackOutput {
+        version: CONTEXT_PACK_VERSION,
+        query: query.to_string(),
+        model_id,
+        profile: service.profile.name().to_string(),
+        oldest_result_ms: oldest_indexed_at_ms(&items),
+        items,
+        budget,
+        omitted,
+        next_actions: Vec::new(),
+        meta,
+    };
+    let next_max_chars = output.budget.max_chars.saturating_mul(2).min(500_000);
+    retry_args["max_chars"] = serde_json::json!(next_max_chars);
+    let reason = match output.budget.max_tokens {
+        Some(max_tokens) => {
+            retry_args["max_tokens"] = serde_json::json!(max_tokens.saturating_mul(2));
+            "Retry context_pack with larger max_chars/max_tokens budgets."
+        }
+        None => "Retry context_pack with a larger max_chars budget.",
+    };
+    let retry_action = ToolNextAction::new("context_pack", retry_args, reason);
+    if output.budget.truncated {
+        output.next_actions.push(retry_action.clone());
+    }
+    enforce_context_pack_budget(&mut output)?;
+    if output.budget.truncated && output.next_actions.is_empty() {
+        output.next_actions.push(retry_action);
+        enforce_context_pack_budget(&mut output)?;
+    }
+    Ok(output)
+}
+
+/// `context_pack` over several roots: candidates from every member are merged on normalized
+/// scores and packed under one budget. Graph walks stay inside each member and graph-node
+/// fusion is skipped.
+async fn context_pack_workspace(
+    service: &ContextFinderService,
+    request: &ContextPackRequest,
+    inputs: &ContextPackInputs,
+    members: &[WorkspaceMember],
+) -> CallToolResult {
+    let policy = AutoIndexPolicy::from_request(
+        Some(inputs.flags.auto_index()),
+        request.auto_index_budget_ms,
+    );
+    let mut run = WorkspaceRun::default();
+    let mut per_member: Vec<Vec<context_search::EnrichedResult>> = Vec::new();
+    for member in members {
+        let Some(mut engine) = run.lock_member(service, member, policy).await else {
+            continue;
+        };
+        let language = select_language(request.language.as_deref(), &mut engine);
+        if let Err(err) = engine.engine_mut().ensure_graph(language).await {
+            run.fail_last(format!("Graph build error: {err}"));
+            continue;
+        }
+        let enriched = engine
+            .engine_mut()
+            .context_search
+            .search_with_context(&request.query, inputs.candidate_limit, inputs.strategy)
+            .await;
+        drop(engine);
+        match enriched {
+            Ok(mut enriched) => {
+                for er in &mut enriched {
+                    prefix_enriched(er, member);
+                }
+                per_member.push(enriched);
+            }
+            Err(err) => run.fail_last(format!("Search error: {err}")),
+        }
+    }
+    if let Some(error) = run.unusable_error(service) {
+        return error;
+    }
+
+    let mut enriched = merge_member_hits(per_member, |er| &mut er.primary.score);
+    enriched.truncate(inputs.candidate_limit);
+    let retry_args = serde_json::json!({
+        "path": request.path,
+        "query": request.query,
+        "workspace": request.workspace,
+        "roots": request.roots,
+    });
+    match pack_output(
+        service,
+        &request.query,
+        inputs,
+        enriched,
+        run.meta(service),
+        retry_args,
+    ) {
+        Ok(output) => CallToolResult::success(vec![Content::text(
+            context_protocol::serialize_json(&output).unwrap_or_default(),
+        )]),
+        Err(result) => result,
+    }
+}
+
+/// Rewrites a member's hit so its ids and paths are unique across the workspace.
+fn prefix_enriched(er: &mut context_search::EnrichedResult, member: &WorkspaceMember) {
+    er.primary.id = member.prefixed(&er.primary.id);
+    er.primary.chunk.file_path = member.prefixed(&er.primary.chunk.file_path);
+    for rc in &mut er.related {
+        rc.chunk.file_path = member.prefixed(&rc.chunk.file_path);
+    }
+}
+
 /// Build a bounded context pack for agents (single-call context).
 pub(in crate::tools::dispatch) async fn context_pack(
     service: &ContextFinderService,
@@ -501,6 +663,24 @@ pub(in crate::tools::dispatch) async fn context_pack(
         }
     };
 
+    match resolve_workspace_scope(
+        service,
+        inputs.path.as_deref(),
+        request.workspace,
+        request.roots.as_deref(),
+    )
+    .await
+    {
+        Ok(Some(members)) => {
+            return Ok(context_pack_workspace(service, &request, &inputs, &members).await);
+        }
+        Ok(None) => {}
+        Err(message) => {
+            let meta = meta_for_request(service, inputs.path.as_deref()).await;
+            return Ok(invalid_request_with_meta(message, meta, None, Vec::new()));
+        }
+    }
+
     let (root, root_display) = match service.resolve_root(inputs.path.as_deref()).await {
         Ok(value) => value,
         Err(message) => {
@@ -513,17 +693,15 @@ pub(in crate::tools::dispatch) async fn context_pack(
         Some(inputs.flags.auto_index()),
         request.auto_index_budget_ms,
     );
-    let (mut engine, meta) = match service.prepare_semantic_engine(&root, policy).await {
+    let (mut engine, mut meta) = match service.prepare_semantic_engine(&root, policy).await {
         Ok(engine) => engine,
         Err(err) => {
             let message = format!("Error: {err}");
             let meta = service.tool_meta(&root).await;
-            if message.contains("Index not found")
-                || message.contains("No semantic indices available")
-            {
+            if ErrorCode::from_chain(&err) == Some(ErrorCode::IndexMissing) {
                 return Ok(tool_error_envelope_with_meta(
                     ErrorEnvelope {
-                        code: "index_missing".to_string(),
+                        code: ErrorCode::IndexMissing.to_string(),
                         message,
                         details: None,
                         hint: Some("Index missing — run index (see next_actions).".to_string()),
@@ -536,6 +714,21 @@ pub(in crate::tools::dispatch) async fn context_pack(
         }
     };
 
+    let models = match select_models(
+        service,
+        &mut engine,
+        &root,
+        &root_display,
+        request.models.as_deref(),
+        request.stale_policy.as_deref(),
+        &mut meta,
+    )
+    .await
+    {
+        Ok(models) => models,
+        Err(result) => return Ok(result),
+    };
+
     let language = select_language(request.language.as_deref(), &mut engine);
     if let Err(err) = engine.engine_mut().ensure_graph(language).await {
         return Ok(internal_error_with_meta(
@@ -547,12 +740,13 @@ pub(in crate::tools::dispatch) async fn context_pack(
     let available_models = engine.engine_mut().available_models.clone();
     let source_index_mtime_ms = unix_ms(engine.engine_mut().canonical_index_mtime);
 
-    let mut enriched = match engine
-        .engine_mut()
-        .context_search
+    let context_search = &mut engine.engine_mut().context_search;
+    context_search.hybrid_mut().set_models(models);
+    let outcome = context_search
         .search_with_context(&request.query, inputs.candidate_limit, inputs.strategy)
-        .await
-    {
+        .await;
+    context_search.hybrid_mut().set_models(None);
+    let mut enriched = match outcome {
         Ok(r) => r,
         Err(e) => {
             return Ok(internal_error_with_meta(
@@ -579,55 +773,14 @@ pub(in crate::tools::dispatch) async fn context_pack(
 
     drop(engine);
 
-    let enriched = prepare_context_pack_enriched(
-        enriched,
-        inputs.limit,
-        inputs.flags.prefer_code(),
-        inputs.flags.include_docs(),
-    );
-
-    let (items, budget) = pack_enriched_results(
-        &service.profile,
-        enriched,
-        inputs.max_chars,
-        inputs.max_related_per_primary,
-        inputs.related_mode,
-        &inputs.query_tokens,
-    );
-    let model_id = current_model_id().unwrap_or_else(|_| "bge-small".to_string());
-    let query = request.query.clone();
-    let mut output = ContextPackOutput {
-        version: CONTEXT_PACK_VERSION,
-        query: query.clone(),
-        model_id,
-        profile: service.profile.name().to_string(),
-        items,
-        budget,
-        next_actions: Vec::new(),
-        meta,
-    };
-    let next_max_chars = output.budget.max_chars.saturating_mul(2).min(500_000);
-    let retry_action = ToolNextAction {
-        tool: "context_pack".to_string(),
-        args: serde_json::json!({
-            "path": root_display,
-            "query": query,
-            "max_chars": next_max_chars
-        }),
-        reason: "Retry context_pack with a larger max_chars budget.".to_string(),
+    let retry_args = serde_json::json!({
+        "path": root_display,
+        "query": request.query,
+    });
+    let output = match pack_output(service, &request.query, &inputs, enriched, meta, retry_args) {
+        Ok(output) => output,
+        Err(result) => return Ok(result),
     };
-    if output.budget.truncated {
-        output.next_actions.push(retry_action.clone());
-    }
-    if let Err(result) = enforce_context_pack_budget(&mut output) {
-        return Ok(result);
-    }
-    if output.budget.truncated && output.next_actions.is_empty() {
-        output.next_actions.push(retry_action);
-        if let Err(result) = enforce_context_pack_budget(&mut output) {
-            return Ok(result);
-        }
-    }
 
     let mut contents = Vec::new();
     contents.push(Content::text(
@@ -650,8 +803,11 @@ mod tests {
         let request = ContextPackRequest {
             query: "README".to_string(),
             path: None,
+            workspace: None,
+            roots: None,
             limit: Some(5),
             max_chars: None,
+            max_tokens: None,
             max_related_per_primary: None,
             prefer_code: Some(false),
             include_docs: Some(true),
@@ -661,6 +817,8 @@ mod tests {
             auto_index: None,
             auto_index_budget_ms: None,
             trace: None,
+            models: None,
+            stale_policy: None,
         };
         let inputs = parse_inputs(&request)
             .unwrap_or_else(|_| panic!("parse_inputs should succeed for docs-first request"));
@@ -672,8 +830,11 @@ mod tests {
         let request = ContextPackRequest {
             query: "EmbeddingCache".to_string(),
             path: None,
+            workspace: None,
+            roots: None,
             limit: Some(10),
             max_chars: None,
+            max_tokens: None,
             max_related_per_primary: None,
             prefer_code: Some(true),
             include_docs: Some(true),
@@ -683,6 +844,8 @@ mod tests {
             auto_index: None,
             auto_index_budget_ms: None,
             trace: None,
+            models: None,
+            stale_policy: None,
         };
         let inputs = parse_inputs(&request)
             .unwrap_or_else(|_| panic!("parse_inputs should succeed for code-first request"));
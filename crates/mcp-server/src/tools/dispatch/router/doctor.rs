@@ -1,21 +1,216 @@
 use super::super::{
-    load_corpus_chunk_ids, load_index_chunk_ids, load_model_statuses, runtime_env,
-    sample_file_paths, CallToolResult, Content, ContextFinderService, DoctorEnvResult,
-    DoctorIndexDrift, DoctorProjectResult, DoctorRequest, DoctorResult, McpError,
+    load_corpus_schema_version, load_index_summary, load_model_statuses, model_id_dir_name,
+    runtime_env, unix_ms, CallToolResult, Content, ContextFinderService, DoctorBudget,
+    DoctorDiskBudget, DoctorEnvResult, DoctorGraphCacheStatus, DoctorIndexDrift, DoctorIndexInfo,
+    DoctorModelStatus, DoctorProjectResult, DoctorRequest, DoctorResult, DoctorSection,
+    GraphCacheFile, McpError, GRAPH_CACHE_FILE_NAME,
+};
+use crate::tools::paths::{AllowedRoots, ALLOWED_ROOTS_ENV};
+use context_indexer::{read_health_snapshot, read_index_watermark};
+use context_protocol::{enforce_max_chars, DefaultBudgets, ToolNextAction};
+use context_vector_store::{
+    corpus_path_for_project_root, corpus_size_bytes, is_supported_corpus_schema_version,
+    is_supported_index_schema_version, reconcile_index_file, ChunkCorpus, FileDrift,
+    CHUNK_CORPUS_SCHEMA_VERSION, VECTOR_STORE_SCHEMA_VERSION,
 };
-use context_protocol::{DefaultBudgets, ToolNextAction};
-use context_vector_store::corpus_path_for_project_root;
 use serde_json::json;
+use std::collections::BTreeSet;
 use std::path::Path;
+use std::time::SystemTime;
 
 use super::error::{internal_error_with_meta, invalid_request_with_meta, meta_for_request};
-async fn diagnose_project(
+
+/// Issues, hints and repair actions accumulated across checks.
+#[derive(Default)]
+pub(in crate::tools::dispatch) struct DoctorFindings {
+    pub(in crate::tools::dispatch) issues: Vec<String>,
+    pub(in crate::tools::dispatch) hints: Vec<String>,
+    pub(in crate::tools::dispatch) next_actions: Vec<ToolNextAction>,
+}
+
+impl DoctorFindings {
+    fn action(&mut self, tool: &str, args: serde_json::Value, reason: impl Into<String>) {
+        if self
+            .next_actions
+            .iter()
+            .any(|action| action.tool == tool && action.args == args)
+        {
+            return;
+        }
+        self.next_actions
+            .push(ToolNextAction::new(tool, args, reason));
+    }
+}
+
+fn dir_size_bytes(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size_bytes(&entry.path()),
+            Ok(_) => entry.metadata().map_or(0, |meta| meta.len()),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// A reindex writes new files next to the old ones before replacing them, so it can need about
+/// this many times the current index size in free space.
+const REINDEX_FREE_SPACE_FACTOR: u64 = 2;
+
+/// Bytes available to unprivileged writers on the filesystem holding `path`.
+#[cfg(unix)]
+fn available_space_bytes(path: &Path) -> Option<u64> {
+    let stat = rustix::fs::statvfs(path).ok()?;
+    Some(stat.f_bavail.saturating_mul(stat.f_frsize))
+}
+
+#[cfg(not(unix))]
+fn available_space_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Compare free space under `dir` with what a reindex may need. An empty index needs nothing,
+/// and an unknown free-space value is not reported.
+pub(in crate::tools::dispatch) fn check_disk_budget(
+    dir: &Path,
+    index_size_bytes: u64,
+    free_bytes: Option<u64>,
+    findings: &mut DoctorFindings,
+) -> Option<DoctorDiskBudget> {
+    let free_bytes = free_bytes?;
+    if index_size_bytes == 0 {
+        return None;
+    }
+    let required_free_bytes = index_size_bytes.saturating_mul(REINDEX_FREE_SPACE_FACTOR);
+    let low = free_bytes < required_free_bytes;
+    if low {
+        findings.issues.push(format!(
+            "Only {free_bytes} bytes free under {}, but a reindex may need {required_free_bytes} ({REINDEX_FREE_SPACE_FACTOR}x the {index_size_bytes}-byte index).",
+            dir.display()
+        ));
+        findings.hints.push(
+            "Free disk space (or delete orphaned index directories) before reindexing so the write does not fail midway."
+                .into(),
+        );
+    }
+    Some(DoctorDiskBudget {
+        dir: dir.to_string_lossy().into_owned(),
+        index_size_bytes,
+        free_bytes,
+        required_free_bytes,
+        low,
+    })
+}
+
+/// Check the on-disk graph cache against the current index files. A stale or unreadable cache
+/// is rebuilt lazily by graph tools, so this only produces hints, never issues.
+async fn diagnose_graph_cache(
     root: &Path,
-    issues: &mut Vec<String>,
-    hints: &mut Vec<String>,
+    root_display: &str,
+    index_mtimes_ms: &[u64],
+    findings: &mut DoctorFindings,
+) -> Option<DoctorGraphCacheStatus> {
+    let path = root.join(".context-finder").join(GRAPH_CACHE_FILE_NAME);
+    let bytes = tokio::fs::read(&path).await.ok()?;
+
+    let mut status = DoctorGraphCacheStatus {
+        path: path.to_string_lossy().into_owned(),
+        size_bytes: bytes.len() as u64,
+        schema_version: None,
+        store_mtime_ms: None,
+        fresh: false,
+        error: None,
+    };
+    match GraphCacheFile::decode(&bytes) {
+        Ok(cached) => {
+            status.schema_version = Some(cached.schema_version);
+            status.store_mtime_ms = Some(cached.store_mtime_ms);
+            status.fresh = index_mtimes_ms.contains(&cached.store_mtime_ms);
+        }
+        Err(err) => status.error = Some(err.to_string()),
+    }
+
+    if !status.fresh {
+        let why = status.error.as_deref().map_or_else(
+            || "was built for an older index".to_string(),
+            str::to_string,
+        );
+        findings.hints.push(format!(
+            "Graph cache {} {why}; graph tools will rebuild it on next use.",
+            status.path
+        ));
+        findings.action(
+            "index",
+            json!({ "path": root_display, "clear_graph_cache": true }),
+            "Drop the stale graph cache so the next graph tool rebuilds it from the current index.",
+        );
+    }
+    Some(status)
+}
+
+/// Failure reasons recorded in `health.json`, most recent first, e.g. "embed timeout ×37".
+async fn diagnose_health_failures(root: &Path, findings: &mut DoctorFindings) {
+    const MAX_LISTED: usize = 5;
+    let snapshot = match read_health_snapshot(root).await {
+        Ok(Some(snapshot)) => snapshot,
+        Ok(None) => return,
+        Err(err) => {
+            findings
+                .issues
+                .push(format!("Failed to read the health snapshot: {err}"));
+            return;
+        }
+    };
+    if snapshot.failure_reason_counts.is_empty() {
+        return;
+    }
+    let listed = snapshot
+        .failure_reason_counts
+        .iter()
+        .rev()
+        .take(MAX_LISTED)
+        .map(|entry| format!("{} ×{}", entry.reason, entry.count))
+        .collect::<Vec<_>>()
+        .join("; ");
+    findings.hints.push(format!(
+        "Recorded indexing failures (health.json): {listed}"
+    ));
+}
+
+pub(in crate::tools::dispatch) async fn diagnose_project(
+    root: &Path,
+    root_display: &str,
+    manifest_models: Option<&[DoctorModelStatus]>,
+    findings: &mut DoctorFindings,
 ) -> Option<DoctorProjectResult> {
     let corpus_path = corpus_path_for_project_root(root);
     let has_corpus = corpus_path.exists();
+    let mut needs_full_reindex = false;
+    let mut reindex_models: BTreeSet<String> = BTreeSet::new();
+
+    let mut corpus_schema_version = None;
+    if has_corpus {
+        match load_corpus_schema_version(&corpus_path).await {
+            Ok(version) => {
+                corpus_schema_version = version;
+                if !version.is_some_and(is_supported_corpus_schema_version) {
+                    findings.issues.push(format!(
+                        "Corpus {} has unsupported schema_version {} (expected {CHUNK_CORPUS_SCHEMA_VERSION})",
+                        corpus_path.display(),
+                        version.map_or_else(|| "<missing>".to_string(), |v| v.to_string())
+                    ));
+                    needs_full_reindex = true;
+                }
+            }
+            Err(err) => {
+                findings.issues.push(format!("{err:#}"));
+                needs_full_reindex = true;
+            }
+        }
+    }
 
     let indexes_dir = root.join(".context-finder").join("indexes");
     let mut indexed_models: Vec<String> = Vec::new();
@@ -34,87 +229,356 @@ async fn diagnose_project(
     indexed_models.sort();
 
     if indexed_models.is_empty() {
-        hints
+        findings
+            .hints
             .push("No semantic indexes found for this project. Run the `index` tool first.".into());
     }
 
+    let corpus = if has_corpus && !indexed_models.is_empty() && !needs_full_reindex {
+        match ChunkCorpus::load(&corpus_path).await {
+            Ok(corpus) => Some(corpus),
+            Err(err) => {
+                findings.issues.push(format!(
+                    "Failed to load corpus {}: {err:#}",
+                    corpus_path.display()
+                ));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let now = SystemTime::now();
+    let mut indexes: Vec<DoctorIndexInfo> = Vec::new();
+    let mut index_mtimes_ms: Vec<u64> = Vec::new();
     let mut drift: Vec<DoctorIndexDrift> = Vec::new();
-    if has_corpus && !indexed_models.is_empty() {
-        match load_corpus_chunk_ids(&corpus_path).await {
-            Ok(corpus_ids) => {
-                let corpus_chunks = corpus_ids.len();
-                let mut drifted_models = Vec::new();
-
-                for model_id in &indexed_models {
-                    let index_path = indexes_dir.join(model_id).join("index.json");
-                    let index_ids = match load_index_chunk_ids(&index_path).await {
-                        Ok(ids) => ids,
-                        Err(err) => {
-                            issues.push(format!(
-                                "Failed to read index for model '{model_id}': {err:#}"
-                            ));
-                            continue;
-                        }
-                    };
-
-                    let missing_chunks = corpus_ids.difference(&index_ids).count();
-                    let extra_chunks = index_ids.difference(&corpus_ids).count();
-
-                    if missing_chunks > 0 || extra_chunks > 0 {
-                        drifted_models.push(model_id.clone());
-                    }
-
-                    let missing_file_samples =
-                        sample_file_paths(corpus_ids.difference(&index_ids), 8);
-                    let extra_file_samples =
-                        sample_file_paths(index_ids.difference(&corpus_ids), 8);
-
-                    drift.push(DoctorIndexDrift {
-                        model: model_id.clone(),
-                        index_path: index_path.to_string_lossy().into_owned(),
-                        index_chunks: index_ids.len(),
-                        corpus_chunks,
-                        missing_chunks,
-                        extra_chunks,
-                        missing_file_samples,
-                        extra_file_samples,
-                    });
-                }
+    let mut drifted_models = Vec::new();
 
-                if !drifted_models.is_empty() {
-                    issues.push(format!(
-                        "Index drift detected vs corpus for models: {}",
-                        drifted_models.join(", ")
-                    ));
-                    hints.push("Run `context-finder index --force --experts` (or the MCP `index` tool) to rebuild semantic indexes to match the current corpus. If you recently changed profiles/models, consider reindexing all models in your roster.".into());
-                }
+    for model_id in &indexed_models {
+        let dir = indexes_dir.join(model_id);
+        let index_path = dir.join("index.json");
+        let modified = std::fs::metadata(&index_path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        index_mtimes_ms.extend(modified.map(unix_ms));
+        let manifest_model = manifest_models.and_then(|models| {
+            models
+                .iter()
+                .find(|model| model_id_dir_name(&model.id) == *model_id)
+        });
+        let orphaned = manifest_models.is_some() && manifest_model.is_none();
+        let size_bytes = dir_size_bytes(&dir);
+
+        let summary = match load_index_summary(&index_path).await {
+            Ok(summary) => Some(summary),
+            Err(err) => {
+                findings.issues.push(format!(
+                    "Failed to read index for model '{model_id}': {err:#}"
+                ));
+                reindex_models.insert(model_id.clone());
+                None
+            }
+        };
+        let schema_version = summary.as_ref().and_then(|summary| summary.schema_version);
+        let dimension = summary.as_ref().and_then(|summary| summary.dimension);
+        let schema_compatible =
+            summary.is_some() && is_supported_index_schema_version(schema_version.unwrap_or(1));
+
+        if summary.is_some() && !schema_compatible {
+            findings.issues.push(format!(
+                "Index for model '{model_id}' has unsupported schema_version {} (expected {VECTOR_STORE_SCHEMA_VERSION})",
+                schema_version.unwrap_or(1)
+            ));
+            reindex_models.insert(model_id.clone());
+        }
+        if let (Some(expected), Some(actual)) =
+            (manifest_model.and_then(|model| model.dimension), dimension)
+        {
+            if expected != actual {
+                findings.issues.push(format!(
+                    "Index for model '{model_id}' has dimension {actual}, but the model manifest declares {expected}"
+                ));
+                reindex_models.insert(model_id.clone());
             }
+        }
+        let built = match read_index_watermark(&index_path).await {
+            Ok(built) => built,
             Err(err) => {
-                issues.push(format!(
-                    "Failed to load corpus {}: {err:#}",
-                    corpus_path.display()
+                findings.issues.push(format!(
+                    "Failed to read watermark for model '{model_id}': {err:#}"
+                ));
+                None
+            }
+        };
+        let provenance = built.as_ref().and_then(|built| built.provenance.clone());
+        let built_by = built.as_ref().and_then(|built| {
+            built
+                .provenance
+                .as_ref()
+                .map(|provenance| provenance.describe(Some(built.built_at_unix_ms)))
+        });
+        if let Some(provenance) = &provenance {
+            if provenance.tool_version != env!("CARGO_PKG_VERSION") {
+                findings.hints.push(format!(
+                    "Index for model '{model_id}' was built by v{} (this server is v{}); reindex if results look off.",
+                    provenance.tool_version,
+                    env!("CARGO_PKG_VERSION")
                 ));
             }
         }
-    } else if !has_corpus && !indexed_models.is_empty() {
-        hints.push("Corpus not found for this project; drift detection is unavailable. Run `context-finder index` once to generate corpus + indexes.".into());
+        if orphaned {
+            findings.hints.push(format!(
+                "Index directory {} ({size_bytes} bytes) belongs to model '{model_id}', which is not in the model manifest; delete it to reclaim disk space.",
+                dir.display()
+            ));
+        }
+
+        indexes.push(DoctorIndexInfo {
+            model: model_id.clone(),
+            dir: dir.to_string_lossy().into_owned(),
+            size_bytes,
+            modified_unix_ms: modified.map(unix_ms),
+            age_secs: modified
+                .and_then(|modified| now.duration_since(modified).ok())
+                .map(|age| age.as_secs()),
+            schema_version,
+            schema_compatible,
+            dimension,
+            orphaned,
+            built_by,
+            provenance,
+        });
+
+        let Some(corpus) = corpus.as_ref() else {
+            continue;
+        };
+        if !schema_compatible {
+            continue;
+        }
+        let report = match reconcile_index_file(corpus, &index_path).await {
+            Ok(report) => report,
+            Err(err) => {
+                findings.issues.push(format!(
+                    "Failed to reconcile index for model '{model_id}' with the corpus: {err:#}"
+                ));
+                reindex_models.insert(model_id.clone());
+                continue;
+            }
+        };
+
+        if !report.is_clean() {
+            drifted_models.push(model_id.clone());
+            reindex_models.insert(model_id.clone());
+        }
+
+        let sample_files = |drifted: fn(&FileDrift) -> bool| -> Vec<String> {
+            report
+                .files
+                .iter()
+                .filter(|file| drifted(file))
+                .take(8)
+                .map(|file| file.file.clone())
+                .collect()
+        };
+        drift.push(DoctorIndexDrift {
+            model: model_id.clone(),
+            index_path: index_path.to_string_lossy().into_owned(),
+            index_chunks: report.index_chunks,
+            corpus_chunks: report.corpus_chunks,
+            missing_chunks: report.totals.unembedded,
+            extra_chunks: report.totals.orphaned,
+            stale_chunks: report.totals.stale,
+            dimension_mismatch_chunks: report.totals.dimension_mismatch,
+            missing_file_samples: sample_files(|file| !file.unembedded.is_empty()),
+            extra_file_samples: sample_files(|file| !file.orphaned.is_empty()),
+            stale_file_samples: sample_files(|file| {
+                !file.stale.is_empty() || !file.dimension_mismatch.is_empty()
+            }),
+        });
+    }
+
+    if !drifted_models.is_empty() {
+        findings.issues.push(format!(
+            "Index drift detected vs corpus for models: {}",
+            drifted_models.join(", ")
+        ));
+        findings.hints.push("Run `context-finder index --force --experts` (or the MCP `index` tool) to rebuild semantic indexes to match the current corpus, or the CLI `index_verify` action with `repair: true` to re-embed only the drifted chunks. If you recently changed profiles/models, consider reindexing all models in your roster.".into());
+    }
+    if !has_corpus && !indexed_models.is_empty() {
+        findings.hints.push("Corpus not found for this project; drift detection is unavailable. Run `context-finder index` once to generate corpus + indexes.".into());
     }
 
+    if needs_full_reindex || !reindex_models.is_empty() {
+        let mut args = json!({ "path": root_display, "full": true });
+        if !reindex_models.is_empty() {
+            args["models"] = json!(reindex_models);
+        }
+        let reason = if reindex_models.is_empty() {
+            "Rebuild the corpus and indexes with the current schema.".to_string()
+        } else {
+            format!(
+                "Rebuild drifted, unreadable or incompatible indexes: {}.",
+                reindex_models
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        findings.action("index", args, reason);
+    }
+
+    let graph_cache = diagnose_graph_cache(root, root_display, &index_mtimes_ms, findings).await;
+    diagnose_health_failures(root, findings).await;
+
+    let context_dir = root.join(".context-finder");
+    let index_size_bytes = indexes.iter().map(|index| index.size_bytes).sum::<u64>()
+        + corpus_size_bytes(&corpus_path)
+        + graph_cache.as_ref().map_or(0, |cache| cache.size_bytes);
+    let disk = check_disk_budget(
+        &context_dir,
+        index_size_bytes,
+        available_space_bytes(&context_dir),
+        findings,
+    );
+
     Some(DoctorProjectResult {
         root: root.to_string_lossy().into_owned(),
         corpus_path: corpus_path.to_string_lossy().into_owned(),
         has_corpus,
+        corpus_schema_version,
         indexed_models,
+        indexes,
         drift,
+        graph_cache,
+        disk,
     })
 }
 
+fn mark_truncated(result: &mut DoctorResult, section: DoctorSection) {
+    if !result.truncated_sections.contains(&section) {
+        result.truncated_sections.push(section);
+        result.truncated_sections.sort();
+    }
+}
+
+/// Drop whole entries (least actionable first) until the report fits `budget.max_chars`.
+/// At least one issue and one next action always survive.
+pub(in crate::tools::dispatch) fn trim_to_budget(result: &mut DoctorResult) -> anyhow::Result<()> {
+    let max_chars = result.budget.max_chars;
+    let used = enforce_max_chars(
+        result,
+        max_chars,
+        |inner, used| inner.budget.used_chars = used,
+        |inner| inner.budget.truncated = true,
+        |inner| {
+            let section = shrink_once(inner);
+            if let Some(section) = section {
+                mark_truncated(inner, section);
+            }
+            section.is_some()
+        },
+    )?;
+    result.budget.used_chars = used;
+    Ok(())
+}
+
+fn shrink_once(result: &mut DoctorResult) -> Option<DoctorSection> {
+    if let Some(project) = result.project.as_mut() {
+        if let Some(drift) = project.drift.iter_mut().find(|drift| {
+            !drift.missing_file_samples.is_empty()
+                || !drift.extra_file_samples.is_empty()
+                || !drift.stale_file_samples.is_empty()
+        }) {
+            drift.missing_file_samples.clear();
+            drift.extra_file_samples.clear();
+            drift.stale_file_samples.clear();
+            return Some(DoctorSection::Drift);
+        }
+        if project.drift.pop().is_some() {
+            return Some(DoctorSection::Drift);
+        }
+    }
+    if result.stats.pop().is_some() {
+        return Some(DoctorSection::Stats);
+    }
+    if result.env.models.pop().is_some() {
+        return Some(DoctorSection::Models);
+    }
+    if let Some(project) = result.project.as_mut() {
+        if project.indexes.pop().is_some() {
+            return Some(DoctorSection::Indexes);
+        }
+    }
+    if result.hints.pop().is_some() {
+        return Some(DoctorSection::Hints);
+    }
+    if result.next_actions.len() > 1 {
+        result.next_actions.pop();
+        return Some(DoctorSection::NextActions);
+    }
+    if result.issues.len() > 1 {
+        result.issues.pop();
+        return Some(DoctorSection::Issues);
+    }
+    None
+}
+
+/// Point at the CLI `models_fetch` command action for models whose assets are missing.
+pub(in crate::tools::dispatch) fn suggest_model_fetch(
+    models: &[DoctorModelStatus],
+    findings: &mut DoctorFindings,
+) {
+    let missing: Vec<&str> = models
+        .iter()
+        .filter(|model| !model.installed)
+        .map(|model| model.id.as_str())
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+    findings.hints.push("Some models are missing assets. Run `context-finder install-models` (or the `models_fetch` command action) to download them into the model directory, then `models_verify` to check they load.".into());
+    findings.action(
+        "models_fetch",
+        json!({ "models": missing }),
+        "Download the missing model assets with sha256 checks (`context-finder command` action).",
+    );
+}
+
+/// Embed one text with the active model to learn which device really runs it. Skipped when the
+/// model's assets are not installed, since loading would only repeat the missing-asset issue.
+async fn probe_active_model(
+    model_manifest_exists: bool,
+    models: &[DoctorModelStatus],
+    findings: &mut DoctorFindings,
+) -> Option<context_vector_store::EmbeddingProbe> {
+    let model_id = context_vector_store::current_model_id().ok()?;
+    let installed = models
+        .iter()
+        .any(|model| model.id == model_id && model.installed);
+    if !model_manifest_exists || !installed {
+        return None;
+    }
+    match context_vector_store::probe_embedding(&model_id).await {
+        Ok(probe) => Some(probe),
+        Err(err) => {
+            findings.issues.push(format!(
+                "Probe embedding with model '{model_id}' failed: {err}"
+            ));
+            None
+        }
+    }
+}
+
 /// Diagnose model/GPU/index configuration
 pub(in crate::tools::dispatch) async fn doctor(
     service: &ContextFinderService,
     request: DoctorRequest,
 ) -> Result<CallToolResult, McpError> {
-    let DoctorRequest { path } = request;
+    let DoctorRequest { path, max_chars } = request;
+    let budgets = DefaultBudgets::default();
+    let max_chars = max_chars.unwrap_or(budgets.max_chars).max(1);
     let model_dir = context_vector_store::model_dir();
     let manifest_path = model_dir.join("manifest.json");
 
@@ -138,22 +602,53 @@ pub(in crate::tools::dispatch) async fn doctor(
         .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
         .unwrap_or(false);
 
-    let mut issues: Vec<String> = Vec::new();
-    let mut hints: Vec<String> = Vec::new();
+    let mut findings = DoctorFindings::default();
+
+    let allowed_roots = AllowedRoots::from_env();
+    if let Some(allowed) = allowed_roots.as_ref() {
+        if !allowed.invalid.is_empty() {
+            findings.issues.push(format!(
+                "{ALLOWED_ROOTS_ENV} entries ignored (not absolute or not found): {}",
+                allowed.invalid.join(", ")
+            ));
+        }
+        if allowed.roots.is_empty() {
+            findings.issues.push(format!(
+                "{ALLOWED_ROOTS_ENV} has no usable entries, so every project path is rejected."
+            ));
+            findings.hints.push(format!(
+                "Set {ALLOWED_ROOTS_ENV} to colon-separated absolute paths of existing directories, or unset it to allow any path."
+            ));
+        }
+    }
 
     if !cuda_disabled && (!gpu.provider_present || !gpu.cublas_present) {
-        issues.push("CUDA libraries are not fully configured (provider/cublas missing).".into());
-        hints.push("Run `bash scripts/setup_cuda_deps.sh` in the Context Finder repo, or set ORT_LIB_LOCATION/LD_LIBRARY_PATH to directories containing libonnxruntime_providers_cuda.so and libcublasLt.so.*. If you want CPU fallback, set CONTEXT_FINDER_ALLOW_CPU=1.".into());
+        findings
+            .issues
+            .push("CUDA libraries are not fully configured (provider/cublas missing).".into());
+        findings.hints.push("Run `bash scripts/setup_cuda_deps.sh` in the Context Finder repo, or set ORT_LIB_LOCATION/LD_LIBRARY_PATH to directories containing libonnxruntime_providers_cuda.so and libcublasLt.so.*. If you want CPU fallback, set CONTEXT_FINDER_ALLOW_CPU=1.".into());
+    }
+
+    let embedding_probe = probe_active_model(model_manifest_exists, &models, &mut findings).await;
+    if !cuda_disabled
+        && embedding_probe
+            .as_ref()
+            .is_some_and(|probe| probe.device == context_vector_store::EmbeddingDevice::Cpu)
+    {
+        findings.hints.push(
+            "Embeddings fell back to CPU (CONTEXT_FINDER_ALLOW_CPU); fix the CUDA setup for GPU speed."
+                .into(),
+        );
     }
 
     if !model_manifest_exists {
-        issues.push(format!(
+        findings.issues.push(format!(
             "Model manifest not found at {}",
             manifest_path.display()
         ));
-        hints.push("Run `context-finder install-models` (or set CONTEXT_FINDER_MODEL_DIR to a directory containing models/manifest.json).".into());
-    } else if models.iter().any(|m| !m.installed) {
-        hints.push("Some models are missing assets. Run `context-finder install-models` to download them into the model directory.".into());
+        findings.hints.push("Run `context-finder install-models` (or set CONTEXT_FINDER_MODEL_DIR to a directory containing models/manifest.json).".into());
+    } else {
+        suggest_model_fetch(&models, &mut findings);
     }
 
     let (root, root_display) = match service.resolve_root(path.as_deref()).await {
@@ -164,8 +659,70 @@ pub(in crate::tools::dispatch) async fn doctor(
         }
     };
     let meta = service.tool_meta(&root).await;
-    let project = diagnose_project(&root, &mut issues, &mut hints).await;
+    let manifest_models = model_manifest_exists.then_some(models.as_slice());
+    let project = diagnose_project(&root, &root_display, manifest_models, &mut findings).await;
+
+    if let Some(project) = project.as_ref() {
+        if !project.has_corpus || project.indexed_models.is_empty() {
+            findings.action(
+                "index",
+                json!({ "path": root_display.clone() }),
+                "Build the semantic index for this project.",
+            );
+        }
+        findings.action(
+            "repo_onboarding_pack",
+            json!({
+                "path": root_display.clone(),
+                "max_chars": budgets.repo_onboarding_pack_max_chars
+            }),
+            "Get a compact repo map + key docs for fast onboarding.",
+        );
+        if project.has_corpus {
+            findings.action(
+                "context_pack",
+                json!({
+                    "path": root_display.clone(),
+                    "query": "project overview",
+                    "max_chars": budgets.context_pack_max_chars
+                }),
+                "Build a bounded semantic overview after diagnostics.",
+            );
+        }
+    }
+
+    let prewarm = service.state.prewarm.lock().await.clone();
+    for status in &prewarm {
+        if let Some(error) = status.error.as_deref() {
+            findings.issues.push(format!(
+                "Startup prewarm of {} failed: {error}",
+                status.root
+            ));
+        }
+    }
 
+    let index_stale = meta
+        .index_state
+        .as_ref()
+        .is_some_and(|state| state.index.exists && state.stale);
+    let watcher_active = meta.watcher.as_ref().is_some_and(|watcher| watcher.active);
+    if index_stale && !watcher_active {
+        findings.hints.push(
+            "The index is stale and no watcher is running; start one to keep it fresh while you edit."
+                .into(),
+        );
+        findings.action(
+            "watch",
+            json!({ "path": root_display.clone(), "action": "start" }),
+            "Reindex incrementally on file changes.",
+        );
+    }
+
+    let DoctorFindings {
+        issues,
+        hints,
+        next_actions,
+    } = findings;
     let mut result = DoctorResult {
         env: DoctorEnvResult {
             profile: service.profile.name().to_string(),
@@ -173,43 +730,41 @@ pub(in crate::tools::dispatch) async fn doctor(
             model_manifest_exists,
             models,
             gpu,
+            embedding_probe,
             cuda_disabled,
             allow_cpu_fallback,
+            allowed_roots: allowed_roots.map(|allowed| {
+                allowed
+                    .roots
+                    .iter()
+                    .map(|root| root.to_string_lossy().into_owned())
+                    .collect()
+            }),
+            prewarm,
         },
         project,
         issues,
         hints,
-        next_actions: Vec::new(),
+        next_actions,
+        stats: service.state.metrics.snapshot(),
+        truncated_sections: Vec::new(),
+        budget: DoctorBudget {
+            max_chars,
+            used_chars: 0,
+            truncated: false,
+        },
         meta,
     };
-    if let Some(project) = result.project.as_ref() {
-        let budgets = DefaultBudgets::default();
-        if !project.has_corpus || project.indexed_models.is_empty() {
-            result.next_actions.push(ToolNextAction {
-                tool: "index".to_string(),
-                args: json!({ "path": root_display.clone() }),
-                reason: "Build the semantic index for this project.".to_string(),
-            });
-        }
-        result.next_actions.push(ToolNextAction {
-            tool: "repo_onboarding_pack".to_string(),
-            args: json!({
-                "path": root_display.clone(),
-                "max_chars": budgets.repo_onboarding_pack_max_chars
-            }),
-            reason: "Get a compact repo map + key docs for fast onboarding.".to_string(),
-        });
-        if project.has_corpus {
-            result.next_actions.push(ToolNextAction {
-                tool: "context_pack".to_string(),
-                args: json!({
-                    "path": root_display.clone(),
-                    "query": "project overview",
-                    "max_chars": budgets.context_pack_max_chars
-                }),
-                reason: "Build a bounded semantic overview after diagnostics.".to_string(),
-            });
-        }
+    if let Err(err) = trim_to_budget(&mut result) {
+        return Ok(invalid_request_with_meta(
+            format!("doctor report does not fit: {err:#}"),
+            result.meta,
+            Some(
+                "Increase max_chars (the report keeps at least one issue and one next action)."
+                    .to_string(),
+            ),
+            Vec::new(),
+        ));
     }
 
     Ok(CallToolResult::success(vec![Content::text(
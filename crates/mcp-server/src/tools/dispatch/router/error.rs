@@ -1,10 +1,11 @@
 use super::super::{CallToolResult, Content, ContextFinderService};
+use context_graph::{analysis, GraphLanguage};
 use context_indexer::ToolMeta;
-use context_protocol::{DefaultBudgets, ErrorEnvelope, ToolNextAction};
+use context_protocol::{DefaultBudgets, ErrorCode, ErrorEnvelope, ToolNextAction};
 use serde_json::json;
 
 pub(super) fn tool_error_envelope(error: ErrorEnvelope) -> CallToolResult {
-    tool_error_envelope_with_meta(error, ToolMeta { index_state: None })
+    tool_error_envelope_with_meta(error, ToolMeta::default())
 }
 
 pub(super) fn tool_error_envelope_with_meta(
@@ -16,7 +17,7 @@ pub(super) fn tool_error_envelope_with_meta(
     result
 }
 
-pub(super) fn tool_error(code: &'static str, message: impl Into<String>) -> CallToolResult {
+pub(super) fn tool_error(code: ErrorCode, message: impl Into<String>) -> CallToolResult {
     tool_error_envelope(ErrorEnvelope {
         code: code.to_string(),
         message: message.into(),
@@ -27,15 +28,15 @@ pub(super) fn tool_error(code: &'static str, message: impl Into<String>) -> Call
 }
 
 pub(super) fn invalid_request(message: impl Into<String>) -> CallToolResult {
-    tool_error("invalid_request", message)
+    tool_error(ErrorCode::InvalidRequest, message)
 }
 
 pub(super) fn invalid_cursor(message: impl Into<String>) -> CallToolResult {
-    tool_error("invalid_cursor", message)
+    tool_error(ErrorCode::InvalidCursor, message)
 }
 
 pub(super) fn internal_error(message: impl Into<String>) -> CallToolResult {
-    tool_error("internal", message)
+    tool_error(ErrorCode::Internal, message)
 }
 
 pub(super) fn invalid_cursor_with_meta(
@@ -44,7 +45,7 @@ pub(super) fn invalid_cursor_with_meta(
 ) -> CallToolResult {
     tool_error_envelope_with_meta(
         ErrorEnvelope {
-            code: "invalid_cursor".to_string(),
+            code: ErrorCode::InvalidCursor.to_string(),
             message: message.into(),
             details: None,
             hint: None,
@@ -62,7 +63,7 @@ pub(super) fn invalid_request_with_meta(
 ) -> CallToolResult {
     tool_error_envelope_with_meta(
         ErrorEnvelope {
-            code: "invalid_request".to_string(),
+            code: ErrorCode::InvalidRequest.to_string(),
             message: message.into(),
             details: None,
             hint,
@@ -78,7 +79,7 @@ pub(super) fn internal_error_with_meta(
 ) -> CallToolResult {
     tool_error_envelope_with_meta(
         ErrorEnvelope {
-            code: "internal".to_string(),
+            code: ErrorCode::Internal.to_string(),
             message: message.into(),
             details: None,
             hint: None,
@@ -88,13 +89,48 @@ pub(super) fn internal_error_with_meta(
     )
 }
 
+/// `graph_language_missing` for a root whose dominant language has no graph builder, instead of
+/// answering from an empty graph. `retry_args` are the call's own arguments; the next action
+/// repeats it with a `{{language}}` placeholder.
+pub(super) fn unsupported_graph_language_with_meta(
+    language: &str,
+    tool: &str,
+    mut retry_args: serde_json::Value,
+    meta: ToolMeta,
+) -> CallToolResult {
+    let supported: Vec<&str> = GraphLanguage::ALL
+        .iter()
+        .map(|language| language.as_str())
+        .collect();
+    if let Some(args) = retry_args.as_object_mut() {
+        args.insert("language".to_string(), json!("{{language}}"));
+    }
+    tool_error_envelope_with_meta(
+        ErrorEnvelope {
+            code: ErrorCode::GraphLanguageMissing.to_string(),
+            message: analysis::unsupported_language_message(language),
+            details: Some(json!({ "detected": language, "supported": supported })),
+            hint: Some(format!(
+                "Pass `language` ({}) to build the graph for part of the project.",
+                supported.join(" | ")
+            )),
+            next_actions: vec![ToolNextAction::templated(
+                tool,
+                retry_args,
+                "Retry with one of the supported graph languages.",
+            )],
+        },
+        meta,
+    )
+}
+
 pub(super) fn invalid_request_with(
     message: impl Into<String>,
     hint: Option<String>,
     next_actions: Vec<ToolNextAction>,
 ) -> CallToolResult {
     tool_error_envelope(ErrorEnvelope {
-        code: "invalid_request".to_string(),
+        code: ErrorCode::InvalidRequest.to_string(),
         message: message.into(),
         details: None,
         hint,
@@ -105,19 +141,19 @@ pub(super) fn invalid_request_with(
 pub(super) fn index_recovery_actions(root_display: &str) -> Vec<ToolNextAction> {
     let budgets = DefaultBudgets::default();
     vec![
-        ToolNextAction {
-            tool: "index".to_string(),
-            args: json!({ "path": root_display }),
-            reason: format!(
+        ToolNextAction::new(
+            "index",
+            json!({ "path": root_display }),
+            format!(
                 "Build the semantic index (recommended auto_index_budget_ms={}).",
                 budgets.auto_index_budget_ms
             ),
-        },
-        ToolNextAction {
-            tool: "doctor".to_string(),
-            args: json!({ "path": root_display }),
-            reason: "Check environment + index state before retrying.".to_string(),
-        },
+        ),
+        ToolNextAction::new(
+            "doctor",
+            json!({ "path": root_display }),
+            "Check environment + index state before retrying.",
+        ),
     ]
 }
 
@@ -135,7 +171,7 @@ pub(super) async fn meta_for_request(
 ) -> ToolMeta {
     match resolve_root_for_meta(service, path).await {
         Some(root) => service.tool_meta(&root).await,
-        None => ToolMeta { index_state: None },
+        None => ToolMeta::default(),
     }
 }
 
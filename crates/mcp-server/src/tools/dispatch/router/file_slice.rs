@@ -33,17 +33,17 @@ pub(in crate::tools::dispatch) async fn file_slice(
     };
     result.meta = meta;
     if let Some(cursor) = result.next_cursor.clone() {
-        result.next_actions = Some(vec![ToolNextAction {
-            tool: "file_slice".to_string(),
-            args: json!({
+        result.next_actions = Some(vec![ToolNextAction::new(
+            "file_slice",
+            json!({
                 "path": root_display,
                 "file": result.file.clone(),
                 "max_lines": result.max_lines,
                 "max_chars": result.max_chars,
                 "cursor": cursor,
             }),
-            reason: "Continue file_slice pagination with the next cursor.".to_string(),
-        }]);
+            "Continue file_slice pagination with the next cursor.",
+        )]);
     }
 
     Ok(CallToolResult::success(vec![Content::text(
@@ -1,26 +1,32 @@
 use super::super::{
     compute_grep_context_result, decode_cursor, finalize_grep_context_budget, CallToolResult,
     Content, ContextFinderService, GrepContextComputeOptions, GrepContextCursorV1,
-    GrepContextRequest, McpError, CURSOR_VERSION,
+    GrepContextRequest, GrepMatcher, McpError, CURSOR_VERSION,
 };
 use crate::tools::schemas::ToolNextAction;
-use regex::RegexBuilder;
 use serde_json::json;
 
 use super::error::{
     internal_error_with_meta, invalid_cursor_with_meta, invalid_request_with_meta, meta_for_request,
 };
 
-fn build_regex(pattern: &str, case_sensitive: bool) -> Result<regex::Regex, String> {
-    RegexBuilder::new(pattern)
-        .case_insensitive(!case_sensitive)
-        .build()
-        .map_err(|err| format!("Invalid regex: {err}"))
+/// Effective OR-list of patterns: `pattern` first, then `patterns`, trimmed and deduplicated.
+fn collect_patterns(request: &GrepContextRequest) -> Vec<String> {
+    let mut patterns: Vec<String> = Vec::new();
+    for pattern in std::iter::once(&request.pattern).chain(&request.patterns) {
+        let pattern = pattern.trim();
+        if !pattern.is_empty() && !patterns.iter().any(|p| p == pattern) {
+            patterns.push(pattern.to_string());
+        }
+    }
+    patterns
 }
 
 struct CursorValidation<'a> {
     root_display: &'a str,
     pattern: &'a str,
+    patterns: &'a [String],
+    exclude_pattern: Option<&'a str>,
     case_sensitive: bool,
     before: usize,
     after: usize,
@@ -48,9 +54,12 @@ fn decode_resume_cursor(
     if decoded.root != validation.root_display {
         return Err("Invalid cursor: different root".to_string());
     }
-    if decoded.pattern != validation.pattern {
+    if decoded.pattern != validation.pattern || decoded.patterns != validation.patterns {
         return Err("Invalid cursor: different pattern".to_string());
     }
+    if decoded.exclude_pattern.as_deref() != validation.exclude_pattern {
+        return Err("Invalid cursor: different exclude_pattern".to_string());
+    }
     if decoded.file.as_deref() != validation.normalized_file {
         return Err("Invalid cursor: different file".to_string());
     }
@@ -89,19 +98,30 @@ pub(in crate::tools::dispatch) async fn grep_context(
     };
     let meta = service.tool_meta(&root).await;
 
-    request.pattern = request.pattern.trim().to_string();
-    if request.pattern.is_empty() {
+    let patterns = collect_patterns(&request);
+    let Some(first_pattern) = patterns.first().cloned() else {
         return Ok(invalid_request_with_meta(
-            "Pattern must not be empty",
+            "Pattern must not be empty (provide pattern or patterns)",
             meta.clone(),
             None,
             Vec::new(),
         ));
-    }
+    };
+    request.pattern = first_pattern;
+    request.exclude_pattern = request
+        .exclude_pattern
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
 
     let case_sensitive = request.case_sensitive.unwrap_or(true);
-    let regex = match build_regex(&request.pattern, case_sensitive) {
-        Ok(re) => re,
+    let matcher = match GrepMatcher::new(
+        &patterns,
+        request.exclude_pattern.as_deref(),
+        case_sensitive,
+    ) {
+        Ok(matcher) => matcher,
         Err(msg) => {
             return Ok(invalid_request_with_meta(
                 msg,
@@ -111,6 +131,12 @@ pub(in crate::tools::dispatch) async fn grep_context(
             ))
         }
     };
+    // A single pattern keeps the legacy shape; `patterns` is only echoed for OR searches.
+    request.patterns = if patterns.len() > 1 {
+        patterns
+    } else {
+        Vec::new()
+    };
 
     let before = request
         .before
@@ -154,6 +180,8 @@ pub(in crate::tools::dispatch) async fn grep_context(
         &CursorValidation {
             root_display: &root_display,
             pattern: &request.pattern,
+            patterns: &request.patterns,
+            exclude_pattern: request.exclude_pattern.as_deref(),
             case_sensitive,
             before,
             after,
@@ -169,7 +197,7 @@ pub(in crate::tools::dispatch) async fn grep_context(
         &root,
         &root_display,
         &request,
-        &regex,
+        &matcher,
         GrepContextComputeOptions {
             case_sensitive,
             before,
@@ -193,11 +221,13 @@ pub(in crate::tools::dispatch) async fn grep_context(
     };
     result.meta = meta.clone();
     if let Some(cursor) = result.next_cursor.clone() {
-        result.next_actions = Some(vec![ToolNextAction {
-            tool: "grep_context".to_string(),
-            args: json!({
+        result.next_actions = Some(vec![ToolNextAction::new(
+            "grep_context",
+            json!({
                 "path": root_display,
                 "pattern": request.pattern,
+                "patterns": request.patterns,
+                "exclude_pattern": request.exclude_pattern,
                 "file": normalized_file,
                 "file_pattern": normalized_file_pattern,
                 "before": before,
@@ -208,8 +238,8 @@ pub(in crate::tools::dispatch) async fn grep_context(
                 "max_chars": max_chars,
                 "cursor": cursor,
             }),
-            reason: "Continue grep_context pagination with the next cursor.".to_string(),
-        }]);
+            "Continue grep_context pagination with the next cursor.",
+        )]);
     }
     if let Err(err) = finalize_grep_context_budget(&mut result) {
         return Ok(invalid_request_with_meta(
@@ -1,17 +1,15 @@
 use super::super::{
-    AutoIndexPolicy, CallToolResult, Content, ContextFinderService, ImpactRequest, ImpactResult,
-    McpError, SymbolLocation, UsageInfo,
+    resolve_graph_language, AutoIndexPolicy, CallToolResult, Content, ContextAssembler,
+    ContextFinderService, EngineLock, ImpactRequest, ImpactResult, McpError, WorkspaceMember,
+};
+use context_graph::analysis::{
+    self, count_files_affected, impact_mermaid, unsupported_language_hint, ImpactReport,
+    SymbolLocation, UsageInfo, MAX_DIRECT_USAGES, MAX_TRANSITIVE_USAGES,
 };
-use crate::tools::util::path_has_extension_ignore_ascii_case;
-use context_code_chunker::CodeChunk;
-use context_graph::CodeGraph;
 use context_indexer::ToolMeta;
-use petgraph::graph::NodeIndex;
-use std::collections::HashSet;
 
 use super::error::{internal_error_with_meta, invalid_request_with_meta, meta_for_request};
-const MAX_DIRECT: usize = 200;
-const MAX_TRANSITIVE: usize = 200;
+use super::workspace::{resolve_workspace_scope, WorkspaceRun};
 
 fn success_payload(result: &ImpactResult) -> CallToolResult {
     CallToolResult::success(vec![Content::text(
@@ -19,141 +17,109 @@ fn success_payload(result: &ImpactResult) -> CallToolResult {
     )])
 }
 
-fn best_effort_text_only(symbol: String, chunks: &[CodeChunk]) -> ImpactResult {
-    let direct = ContextFinderService::find_text_usages(chunks, &symbol, None, MAX_DIRECT);
-    let mermaid = ContextFinderService::generate_impact_mermaid(&symbol, &direct, &[]);
-    let files_affected: HashSet<&str> = direct.iter().map(|u| u.file.as_str()).collect();
+/// Impact of `symbol` inside one root's index: graph usages when the symbol resolves, plain
+/// text hits otherwise. A dominant language without graph support is answered from text hits
+/// alone, with a hint naming it.
+async fn analyze_impact(
+    engine: &mut EngineLock,
+    symbol: &str,
+    language: Option<&str>,
+    depth: usize,
+    meta: &mut ToolMeta,
+) -> (ImpactReport, Option<String>) {
+    let (graph_ready, hint) = match resolve_graph_language(engine, language, meta) {
+        Ok(language) => (
+            engine.engine_mut().ensure_graph(language).await.is_ok(),
+            None,
+        ),
+        Err(language) => (false, Some(unsupported_language_hint(&language))),
+    };
+
+    let engine_ref = engine.engine_mut();
+    let chunks = engine_ref.context_search.hybrid().chunks();
+    let graph = engine_ref
+        .context_search
+        .assembler()
+        .filter(|_| graph_ready)
+        .map(ContextAssembler::graph);
+    (analysis::impact(graph, chunks, symbol, depth), hint)
+}
 
-    ImpactResult {
-        symbol,
+/// `impact` over several roots: each member is analyzed on its own graph and the usages are
+/// concatenated with member-prefixed paths. The definition is the first member's that has one.
+async fn impact_workspace(
+    service: &ContextFinderService,
+    request: &ImpactRequest,
+    members: &[WorkspaceMember],
+    depth: usize,
+) -> CallToolResult {
+    let policy = AutoIndexPolicy::from_request(request.auto_index, request.auto_index_budget_ms);
+    let mut run = WorkspaceRun::default();
+    let mut merged = ImpactReport {
+        symbol: request.symbol.clone(),
         definition: None,
-        total_usages: direct.len(),
-        files_affected: files_affected.len(),
-        direct,
+        total_usages: 0,
+        files_affected: 0,
+        direct: Vec::new(),
         transitive: Vec::new(),
         tests: Vec::new(),
         public_api: false,
-        mermaid,
-        meta: ToolMeta { index_state: None },
-    }
-}
-
-fn should_skip_graph_symbol(symbol_name: &str, file_path: &str) -> bool {
-    symbol_name == "unknown" || path_has_extension_ignore_ascii_case(file_path, "md")
-}
-
-fn collect_direct_usages(
-    graph: &CodeGraph,
-    node: NodeIndex,
-) -> (Vec<UsageInfo>, HashSet<(String, usize)>) {
-    let direct_usages = graph.get_all_usages(node);
-    let mut seen: HashSet<(String, usize)> = HashSet::new();
-    let mut direct: Vec<UsageInfo> = direct_usages
-        .iter()
-        .filter_map(|(n, rel)| {
-            graph.get_node(*n).and_then(|nd| {
-                if should_skip_graph_symbol(&nd.symbol.name, &nd.symbol.file_path) {
-                    return None;
-                }
-                let key = (nd.symbol.file_path.clone(), nd.symbol.start_line);
-                if !seen.insert(key) {
-                    return None;
-                }
-                Some(UsageInfo {
-                    file: nd.symbol.file_path.clone(),
-                    line: nd.symbol.start_line,
-                    symbol: nd.symbol.name.clone(),
-                    relationship: format!("{rel:?}"),
-                })
-            })
-        })
-        .collect();
-
-    direct.truncate(MAX_DIRECT);
-    (direct, seen)
-}
-
-fn collect_transitive_usages(graph: &CodeGraph, node: NodeIndex, depth: usize) -> Vec<UsageInfo> {
-    let transitive_usages = graph.get_transitive_usages(node, depth);
-    let mut seen: HashSet<(String, usize)> = HashSet::new();
-    let mut transitive: Vec<UsageInfo> = transitive_usages
-        .iter()
-        .filter(|(_, d, _)| *d > 1)
-        .filter_map(|(n, _, path)| {
-            graph.get_node(*n).and_then(|nd| {
-                if should_skip_graph_symbol(&nd.symbol.name, &nd.symbol.file_path) {
-                    return None;
-                }
-                let key = (nd.symbol.file_path.clone(), nd.symbol.start_line);
-                if !seen.insert(key) {
-                    return None;
-                }
-                Some(UsageInfo {
-                    file: nd.symbol.file_path.clone(),
-                    line: nd.symbol.start_line,
-                    symbol: nd.symbol.name.clone(),
-                    relationship: path
-                        .iter()
-                        .map(|r| format!("{r:?}"))
-                        .collect::<Vec<_>>()
-                        .join(" -> "),
-                })
-            })
-        })
-        .collect();
-
-    transitive.truncate(MAX_TRANSITIVE);
-    transitive
-}
-
-fn add_text_hits_to_direct(
-    direct: &mut Vec<UsageInfo>,
-    seen_direct: &mut HashSet<(String, usize)>,
-    chunks: &[CodeChunk],
-    symbol: &str,
-    exclude_chunk_id: Option<&str>,
-) {
-    let remaining = MAX_DIRECT.saturating_sub(direct.len());
-    if remaining == 0 {
-        return;
-    }
+        mermaid: String::new(),
+    };
+    let mut hints: Vec<String> = Vec::new();
 
-    for usage in ContextFinderService::find_text_usages(chunks, symbol, exclude_chunk_id, remaining)
-    {
-        let key = (usage.file.clone(), usage.line);
-        if !seen_direct.insert(key) {
+    for member in members {
+        let Some(mut engine) = run.lock_member(service, member, policy).await else {
             continue;
+        };
+        let (report, hint) = analyze_impact(
+            &mut engine,
+            &request.symbol,
+            request.language.as_deref(),
+            depth,
+            &mut ToolMeta::default(),
+        )
+        .await;
+        drop(engine);
+        if let Some(hint) = hint {
+            hints.push(format!("{}: {hint}", member.name));
         }
-        direct.push(usage);
-        if direct.len() >= MAX_DIRECT {
-            break;
+
+        if merged.definition.is_none() {
+            merged.definition = report.definition.map(|def| SymbolLocation {
+                file: member.prefixed(&def.file),
+                line: def.line,
+            });
         }
+        let prefix_usage = |mut usage: UsageInfo| {
+            usage.file = member.prefixed(&usage.file);
+            usage
+        };
+        merged
+            .direct
+            .extend(report.direct.into_iter().map(prefix_usage));
+        merged
+            .transitive
+            .extend(report.transitive.into_iter().map(prefix_usage));
+        merged
+            .tests
+            .extend(report.tests.iter().map(|test| member.prefixed(test)));
+        merged.public_api |= report.public_api;
+    }
+    if let Some(error) = run.unusable_error(service) {
+        return error;
     }
-}
-
-fn collect_related_tests(graph: &CodeGraph, node: NodeIndex) -> Vec<String> {
-    let test_nodes = graph.find_related_tests(node);
-    let mut tests: Vec<String> = test_nodes
-        .iter()
-        .filter_map(|n| {
-            graph
-                .get_node(*n)
-                .map(|nd| format!("{}:{}", nd.symbol.file_path, nd.symbol.start_line))
-        })
-        .collect();
-
-    tests.sort();
-    tests.dedup();
-    tests
-}
 
-fn count_files_affected(direct: &[UsageInfo], transitive: &[UsageInfo]) -> usize {
-    direct
-        .iter()
-        .chain(transitive.iter())
-        .map(|u| u.file.as_str())
-        .collect::<HashSet<_>>()
-        .len()
+    merged.direct.truncate(MAX_DIRECT_USAGES);
+    merged.transitive.truncate(MAX_TRANSITIVE_USAGES);
+    merged.total_usages = merged.direct.len() + merged.transitive.len();
+    merged.files_affected = count_files_affected(&merged.direct, &merged.transitive);
+    merged.mermaid = impact_mermaid(&merged.symbol, &merged.direct, &merged.transitive);
+    success_payload(&ImpactResult {
+        report: merged,
+        hint: (!hints.is_empty()).then(|| hints.join(" ")),
+        meta: run.meta(service),
+    })
 }
 
 /// Find all usages of a symbol (impact analysis)
@@ -162,6 +128,24 @@ pub(in crate::tools::dispatch) async fn impact(
     request: ImpactRequest,
 ) -> Result<CallToolResult, McpError> {
     let depth = request.depth.unwrap_or(2).clamp(1, 3);
+    match resolve_workspace_scope(
+        service,
+        request.path.as_deref(),
+        request.workspace,
+        request.roots.as_deref(),
+    )
+    .await
+    {
+        Ok(Some(members)) => {
+            return Ok(impact_workspace(service, &request, &members, depth).await);
+        }
+        Ok(None) => {}
+        Err(message) => {
+            let meta = meta_for_request(service, request.path.as_deref()).await;
+            return Ok(invalid_request_with_meta(message, meta, None, Vec::new()));
+        }
+    }
+
     let root = match service.resolve_root(request.path.as_deref()).await {
         Ok((root, _)) => root,
         Err(message) => {
@@ -171,7 +155,7 @@ pub(in crate::tools::dispatch) async fn impact(
     };
 
     let policy = AutoIndexPolicy::from_request(request.auto_index, request.auto_index_budget_ms);
-    let (mut engine, meta) = match service.prepare_semantic_engine(&root, policy).await {
+    let (mut engine, mut meta) = match service.prepare_semantic_engine(&root, policy).await {
         Ok(engine) => engine,
         Err(e) => {
             let meta = service.tool_meta(&root).await;
@@ -179,84 +163,14 @@ pub(in crate::tools::dispatch) async fn impact(
         }
     };
 
-    let symbol = request.symbol;
-    let detected_language = {
-        let chunks = engine.engine_mut().context_search.hybrid().chunks();
-        ContextFinderService::detect_language(chunks)
-    };
-    let language = request
-        .language
-        .as_deref()
-        .map_or(detected_language, |lang| {
-            ContextFinderService::parse_language(Some(lang))
-        });
-
-    let graph_ready = engine.engine_mut().ensure_graph(language).await.is_ok();
-
-    let mut result = if graph_ready {
-        let engine_ref = engine.engine_mut();
-        let chunks = engine_ref.context_search.hybrid().chunks();
-
-        match engine_ref.context_search.assembler() {
-            None => best_effort_text_only(symbol, chunks),
-            Some(assembler) => {
-                let graph = assembler.graph();
-                match graph.find_node(&symbol) {
-                    None => best_effort_text_only(symbol, chunks),
-                    Some(node) => {
-                        let definition = graph.get_node(node).map(|nd| SymbolLocation {
-                            file: nd.symbol.file_path.clone(),
-                            line: nd.symbol.start_line,
-                        });
-
-                        let (mut direct, mut seen_direct) = collect_direct_usages(graph, node);
-
-                        let transitive = if depth > 1 {
-                            collect_transitive_usages(graph, node, depth)
-                        } else {
-                            Vec::new()
-                        };
-
-                        let exclude_chunk_id = graph.get_node(node).map(|nd| nd.chunk_id.as_str());
-                        add_text_hits_to_direct(
-                            &mut direct,
-                            &mut seen_direct,
-                            chunks,
-                            &symbol,
-                            exclude_chunk_id,
-                        );
-
-                        let tests = collect_related_tests(graph, node);
-                        let public_api = graph.is_public_api(node);
-                        let mermaid = ContextFinderService::generate_impact_mermaid(
-                            &symbol,
-                            &direct,
-                            &transitive,
-                        );
-                        let total_usages = direct.len() + transitive.len();
-
-                        ImpactResult {
-                            symbol,
-                            definition,
-                            total_usages,
-                            files_affected: count_files_affected(&direct, &transitive),
-                            direct,
-                            transitive,
-                            tests,
-                            public_api,
-                            mermaid,
-                            meta: ToolMeta { index_state: None },
-                        }
-                    }
-                }
-            }
-        }
-    } else {
-        let chunks = engine.engine_mut().context_search.hybrid().chunks();
-        best_effort_text_only(symbol, chunks)
-    };
-
+    let (report, hint) = analyze_impact(
+        &mut engine,
+        &request.symbol,
+        request.language.as_deref(),
+        depth,
+        &mut meta,
+    )
+    .await;
     drop(engine);
-    result.meta = meta;
-    Ok(success_payload(&result))
+    Ok(success_payload(&ImpactResult { report, hint, meta }))
 }
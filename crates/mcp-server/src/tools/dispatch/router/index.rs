@@ -1,16 +1,74 @@
 use super::super::{
     current_model_id, index_path_for_model, CallToolResult, Content, ContextFinderService,
-    IndexRequest, IndexResult, McpError, QueryKind,
+    IndexLanguageStat, IndexPhaseStat, IndexRequest, IndexResult, McpError, Peer, QueryKind,
+    RoleServer,
 };
+use context_indexer::{IndexPhase, IndexProgress, IndexProgressCallback};
 use context_protocol::{DefaultBudgets, ToolNextAction};
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
 use serde_json::json;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::mpsc;
 
 use super::error::{internal_error_with_meta, invalid_request_with_meta, meta_for_request};
+
+/// Where to stream `notifications/progress` for an `index` call that carried a progress token.
+pub(in crate::tools::dispatch) struct IndexProgressSink {
+    peer: Peer<RoleServer>,
+    token: ProgressToken,
+}
+
+impl IndexProgressSink {
+    pub(in crate::tools::dispatch) const fn new(
+        peer: Peer<RoleServer>,
+        token: ProgressToken,
+    ) -> Self {
+        Self { peer, token }
+    }
+}
+
+fn progress_message(progress: &IndexProgress) -> String {
+    let mut message = match progress.phase {
+        IndexPhase::Scan => format!("scan: {} files", progress.files_scanned),
+        IndexPhase::Chunk => format!(
+            "chunk: {}/{} files",
+            progress.files_processed, progress.files_total
+        ),
+        IndexPhase::Embed => format!(
+            "embed: {}/{} chunks",
+            progress.chunks_embedded, progress.chunks_total
+        ),
+        IndexPhase::Save => "save".to_string(),
+    };
+    if let Some(eta_ms) = progress.eta_ms {
+        message.push_str(&format!(", eta {}s", eta_ms.div_ceil(1000)));
+    }
+    message
+}
+
+/// Forward indexer progress to the client until the indexer drops its callback. Progress counts
+/// files chunked plus chunks embedded; `total` grows once chunking reveals the chunk count.
+async fn forward_progress(sink: IndexProgressSink, mut rx: mpsc::UnboundedReceiver<IndexProgress>) {
+    while let Some(progress) = rx.recv().await {
+        let done = progress.files_processed + progress.chunks_embedded;
+        let total = progress.files_total + progress.chunks_total;
+        let param = ProgressNotificationParam {
+            progress_token: sink.token.clone(),
+            progress: done as f64,
+            total: (total > 0).then_some(total as f64),
+            message: Some(progress_message(&progress)),
+        };
+        // A failed send means the client went away; keep draining so the channel never backs up.
+        let _ = sink.peer.notify_progress(param).await;
+    }
+}
+
 /// Index a project
 pub(in crate::tools::dispatch) async fn index(
     service: &ContextFinderService,
     request: IndexRequest,
+    progress: Option<IndexProgressSink>,
 ) -> Result<CallToolResult, McpError> {
     let force = request.force.unwrap_or(false);
     let full = request.full.unwrap_or(false) || force;
@@ -26,6 +84,20 @@ pub(in crate::tools::dispatch) async fn index(
     };
     let meta = service.tool_meta(&canonical).await;
 
+    let graph_cache_cleared = if request.clear_graph_cache.unwrap_or(false) {
+        match service.clear_graph_cache(&canonical).await {
+            Ok(removed) => Some(removed),
+            Err(e) => {
+                return Ok(internal_error_with_meta(
+                    format!("Graph cache clear error: {e}"),
+                    meta.clone(),
+                ));
+            }
+        }
+    } else {
+        None
+    };
+
     let start = std::time::Instant::now();
 
     let primary_model_id = current_model_id().unwrap_or_else(|_| "bge-small".to_string());
@@ -83,7 +155,7 @@ pub(in crate::tools::dispatch) async fn index(
         .collect();
 
     let indexer = match context_indexer::MultiModelProjectIndexer::new(&canonical).await {
-        Ok(i) => i,
+        Ok(i) => i.with_profile(service.profile.name()),
         Err(e) => {
             return Ok(internal_error_with_meta(
                 format!("Indexer init error: {e}"),
@@ -92,8 +164,28 @@ pub(in crate::tools::dispatch) async fn index(
         }
     };
 
-    let stats = match indexer.index_models(&specs, full).await {
-        Ok(s) => s,
+    let (indexer, forwarder) = match progress {
+        Some(sink) => {
+            let (tx, rx) = mpsc::unbounded_channel();
+            let callback: IndexProgressCallback = Arc::new(move |progress: &IndexProgress| {
+                let _ = tx.send(progress.clone());
+            });
+            (
+                indexer.with_progress(callback),
+                Some(tokio::spawn(forward_progress(sink, rx))),
+            )
+        }
+        None => (indexer, None),
+    };
+    let outcome = indexer.index_models(&specs, full).await;
+    // Dropping the indexer closes the channel, so every notification is sent before the result.
+    drop(indexer);
+    if let Some(forwarder) = forwarder {
+        let _ = forwarder.await;
+    }
+
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
         Err(e) => {
             return Ok(internal_error_with_meta(
                 format!("Indexing error: {e}"),
@@ -101,6 +193,22 @@ pub(in crate::tools::dispatch) async fn index(
             ));
         }
     };
+    let failed_models: BTreeMap<String, String> = outcome
+        .failures()
+        .into_iter()
+        .map(|(model_id, err)| (model_id.to_string(), err.to_string()))
+        .collect();
+    if outcome.all_failed() {
+        let detail: Vec<String> = failed_models
+            .iter()
+            .map(|(model_id, err)| format!("{model_id}: {err}"))
+            .collect();
+        return Ok(internal_error_with_meta(
+            format!("Indexing error: {}", detail.join("; ")),
+            meta.clone(),
+        ));
+    }
+    let stats = outcome.stats;
 
     let time_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
     let index_path = index_path_for_model(&canonical, &primary_model_id);
@@ -108,29 +216,55 @@ pub(in crate::tools::dispatch) async fn index(
     let mut result = IndexResult {
         files: stats.files,
         chunks: stats.chunks,
+        chunks_reused: stats.chunks_reused,
+        docs_token_truncated: stats.docs_token_truncated,
+        languages: stats
+            .languages
+            .iter()
+            .map(|(language, stat)| {
+                (
+                    language.clone(),
+                    IndexLanguageStat {
+                        files: stat.files,
+                        lines: stat.lines,
+                        chunks: stat.chunks,
+                    },
+                )
+            })
+            .collect(),
         time_ms,
+        phases: stats
+            .phases
+            .iter()
+            .map(|timing| IndexPhaseStat {
+                phase: timing.phase.as_str().to_string(),
+                time_ms: timing.time_ms,
+            })
+            .collect(),
         index_path: index_path.to_string_lossy().to_string(),
+        graph_cache_cleared,
+        failed_models,
         next_actions: Vec::new(),
         meta: service.tool_meta(&canonical).await,
     };
     let budgets = DefaultBudgets::default();
-    result.next_actions.push(ToolNextAction {
-        tool: "repo_onboarding_pack".to_string(),
-        args: json!({
+    result.next_actions.push(ToolNextAction::new(
+        "repo_onboarding_pack",
+        json!({
             "path": root_display.clone(),
             "max_chars": budgets.repo_onboarding_pack_max_chars
         }),
-        reason: "Start with a compact repo map + key docs after indexing.".to_string(),
-    });
-    result.next_actions.push(ToolNextAction {
-        tool: "context_pack".to_string(),
-        args: json!({
+        "Start with a compact repo map + key docs after indexing.",
+    ));
+    result.next_actions.push(ToolNextAction::new(
+        "context_pack",
+        json!({
             "path": root_display.clone(),
             "query": "project overview",
             "max_chars": budgets.context_pack_max_chars
         }),
-        reason: "Build a bounded semantic overview after indexing.".to_string(),
-    });
+        "Build a bounded semantic overview after indexing.",
+    ));
 
     Ok(CallToolResult::success(vec![Content::text(
         context_protocol::serialize_json(&result).unwrap_or_default(),
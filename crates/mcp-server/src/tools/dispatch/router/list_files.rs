@@ -1,6 +1,7 @@
 use super::super::{
     compute_list_files_result, decode_list_files_cursor, finalize_list_files_budget,
-    CallToolResult, Content, ContextFinderService, ListFilesRequest, McpError, CURSOR_VERSION,
+    CallToolResult, Content, ContextFinderService, ListFilesOrder, ListFilesRequest, ListFilesSort,
+    ListFilesView, McpError, CURSOR_VERSION,
 };
 use crate::tools::schemas::ToolNextAction;
 use serde_json::json;
@@ -41,7 +42,14 @@ pub(in crate::tools::dispatch) async fn list_files(
         .filter(|s| !s.is_empty())
         .map(str::to_string);
 
-    let cursor_last_file = if let Some(cursor) = request
+    let sort = request.sort.unwrap_or_default();
+    let order = request.order.unwrap_or(match sort {
+        ListFilesSort::Path => ListFilesOrder::Asc,
+        ListFilesSort::Size | ListFilesSort::Mtime | ListFilesSort::Chunks => ListFilesOrder::Desc,
+    });
+    let include_meta = request.include_meta.unwrap_or(false);
+
+    let decoded_cursor = if let Some(cursor) = request
         .cursor
         .as_deref()
         .map(str::trim)
@@ -74,7 +82,13 @@ pub(in crate::tools::dispatch) async fn list_files(
                 meta.clone(),
             ));
         }
-        Some(decoded.last_file)
+        if decoded.sort.unwrap_or_default() != sort || decoded.order.unwrap_or_default() != order {
+            return Ok(invalid_cursor_with_meta(
+                "Invalid cursor: different sort/order",
+                meta.clone(),
+            ));
+        }
+        Some(decoded)
     } else {
         None
     };
@@ -84,7 +98,12 @@ pub(in crate::tools::dispatch) async fn list_files(
         request.file_pattern.as_deref(),
         limit,
         max_chars,
-        cursor_last_file.as_deref(),
+        decoded_cursor.as_ref(),
+        ListFilesView {
+            include_meta,
+            sort,
+            order,
+        },
     )
     .await
     {
@@ -98,17 +117,20 @@ pub(in crate::tools::dispatch) async fn list_files(
     };
     result.meta = meta.clone();
     if let Some(cursor) = result.next_cursor.clone() {
-        result.next_actions = Some(vec![ToolNextAction {
-            tool: "list_files".to_string(),
-            args: json!({
+        result.next_actions = Some(vec![ToolNextAction::new(
+            "list_files",
+            json!({
                 "path": root_display,
                 "file_pattern": normalized_file_pattern,
                 "limit": limit,
                 "max_chars": max_chars,
+                "include_meta": include_meta,
+                "sort": sort,
+                "order": order,
                 "cursor": cursor,
             }),
-            reason: "Continue list_files pagination with the next cursor.".to_string(),
-        }]);
+            "Continue list_files pagination with the next cursor.",
+        )]);
     }
     if let Err(err) = finalize_list_files_budget(&mut result) {
         let suggested = max_chars.saturating_mul(2).clamp(1, MAX_MAX_CHARS);
@@ -116,17 +138,20 @@ pub(in crate::tools::dispatch) async fn list_files(
             format!("max_chars too small for response envelope ({err:#})"),
             meta,
             Some(format!("Increase max_chars (suggested: {suggested}).")),
-            vec![ToolNextAction {
-                tool: "list_files".to_string(),
-                args: json!({
+            vec![ToolNextAction::new(
+                "list_files",
+                json!({
                     "path": root_display,
                     "file_pattern": request.file_pattern,
                     "limit": limit,
                     "max_chars": suggested,
+                    "include_meta": include_meta,
+                    "sort": sort,
+                    "order": order,
                     "cursor": request.cursor
                 }),
-                reason: "Retry list_files with a larger max_chars budget.".to_string(),
-            }],
+                "Retry list_files with a larger max_chars budget.",
+            )],
         ));
     }
 
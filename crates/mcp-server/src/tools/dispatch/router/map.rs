@@ -75,16 +75,16 @@ pub(in crate::tools::dispatch) async fn map(
     };
     result.meta = meta;
     if let Some(cursor) = result.next_cursor.clone() {
-        result.next_actions = Some(vec![ToolNextAction {
-            tool: "map".to_string(),
-            args: json!({
+        result.next_actions = Some(vec![ToolNextAction::new(
+            "map",
+            json!({
                 "path": root_display,
                 "depth": depth,
                 "limit": limit,
                 "cursor": cursor,
             }),
-            reason: "Continue map pagination with the next cursor.".to_string(),
-        }]);
+            "Continue map pagination with the next cursor.",
+        )]);
     }
 
     Ok(CallToolResult::success(vec![Content::text(
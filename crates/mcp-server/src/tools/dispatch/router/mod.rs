@@ -19,3 +19,6 @@ pub(super) mod repo_onboarding_pack;
 pub(super) mod search;
 pub(super) mod text_search;
 pub(super) mod trace;
+pub(super) mod warm;
+pub(super) mod watch;
+pub(super) mod workspace;
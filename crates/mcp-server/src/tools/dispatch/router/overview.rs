@@ -1,17 +1,11 @@
 use super::super::{
-    AutoIndexPolicy, CallToolResult, Content, ContextFinderService, GraphStats, KeyTypeInfo,
-    LayerInfo, McpError, OverviewRequest, OverviewResult, ProjectInfo,
+    AutoIndexPolicy, CallToolResult, Content, ContextFinderService, McpError, OverviewRequest,
+    OverviewResult,
 };
-use crate::tools::util::path_has_extension_ignore_ascii_case;
-use context_code_chunker::CodeChunk;
-use context_graph::CodeGraph;
-use std::collections::{HashMap, HashSet};
+use context_graph::analysis::{self, detect_language};
 use std::path::Path;
 
 use super::error::{internal_error_with_meta, invalid_request_with_meta, meta_for_request};
-const MAX_ENTRY_POINTS: usize = 10;
-const MAX_KEY_TYPES: usize = 10;
-const HOTSPOT_LIMIT: usize = 20;
 
 fn success_payload(result: &OverviewResult) -> CallToolResult {
     CallToolResult::success(vec![Content::text(
@@ -19,108 +13,11 @@ fn success_payload(result: &OverviewResult) -> CallToolResult {
     )])
 }
 
-fn compute_project_info(root: &Path, chunks: &[CodeChunk]) -> ProjectInfo {
-    let total_files: HashSet<&str> = chunks.iter().map(|c| c.file_path.as_str()).collect();
-    let total_lines: usize = chunks.iter().map(|c| c.content.lines().count()).sum();
-    let name = root.file_name().map_or_else(
+fn project_name(root: &Path) -> String {
+    root.file_name().map_or_else(
         || "unknown".to_string(),
         |s| s.to_string_lossy().to_string(),
-    );
-
-    ProjectInfo {
-        name,
-        files: total_files.len(),
-        chunks: chunks.len(),
-        lines: total_lines,
-    }
-}
-
-fn compute_layers(chunks: &[CodeChunk]) -> Vec<LayerInfo> {
-    let mut layer_files: HashMap<String, HashSet<&str>> = HashMap::new();
-    for chunk in chunks {
-        let parts: Vec<&str> = chunk.file_path.split('/').collect();
-        if parts.len() > 1 {
-            let layer = parts.first().copied().unwrap_or("root").to_string();
-            layer_files
-                .entry(layer)
-                .or_default()
-                .insert(chunk.file_path.as_str());
-        }
-    }
-
-    let mut layers: Vec<LayerInfo> = layer_files
-        .into_iter()
-        .map(|(name, files)| {
-            let role = ContextFinderService::guess_layer_role(&name);
-            LayerInfo {
-                name,
-                files: files.len(),
-                role,
-            }
-        })
-        .collect();
-
-    layers.sort_by(|a, b| b.files.cmp(&a.files));
-    layers
-}
-
-fn is_entry_point_candidate(symbol_name: &str, file_path: &str) -> bool {
-    if symbol_name == "unknown" || symbol_name.starts_with("test_") {
-        return false;
-    }
-    if file_path.contains("/tests/") || path_has_extension_ignore_ascii_case(file_path, "md") {
-        return false;
-    }
-    true
-}
-
-fn compute_entry_points(graph: &CodeGraph) -> Vec<String> {
-    let entry_nodes = graph.find_entry_points();
-    let mut entry_points: Vec<String> = entry_nodes
-        .iter()
-        .filter_map(|n| {
-            graph.get_node(*n).and_then(|nd| {
-                is_entry_point_candidate(&nd.symbol.name, &nd.symbol.file_path)
-                    .then(|| nd.symbol.name.clone())
-            })
-        })
-        .collect();
-
-    entry_points.sort();
-    entry_points.dedup();
-    entry_points.truncate(MAX_ENTRY_POINTS);
-    entry_points
-}
-
-fn compute_key_types(graph: &CodeGraph) -> Vec<KeyTypeInfo> {
-    let hotspots = graph.find_hotspots(HOTSPOT_LIMIT);
-    let mut seen_names: HashSet<String> = HashSet::new();
-
-    hotspots
-        .iter()
-        .filter_map(|(n, coupling)| {
-            graph.get_node(*n).and_then(|nd| {
-                let name = &nd.symbol.name;
-                if name == "unknown"
-                    || name == "tests"
-                    || name.starts_with("test_")
-                    || nd.symbol.file_path.contains("/tests/")
-                    || !seen_names.insert(name.clone())
-                {
-                    return None;
-                }
-
-                let symbol_type = &nd.symbol.symbol_type;
-                Some(KeyTypeInfo {
-                    name: name.clone(),
-                    kind: format!("{symbol_type:?}"),
-                    file: nd.symbol.file_path.clone(),
-                    coupling: *coupling,
-                })
-            })
-        })
-        .take(MAX_KEY_TYPES)
-        .collect()
+    )
 }
 
 /// Project architecture overview
@@ -144,16 +41,10 @@ pub(in crate::tools::dispatch) async fn overview(
         }
     };
 
-    let detected_language = {
-        let chunks = engine.engine_mut().context_search.hybrid().chunks();
-        ContextFinderService::detect_language(chunks)
-    };
-    let language = request
-        .language
-        .as_deref()
-        .map_or(detected_language, |lang| {
-            ContextFinderService::parse_language(Some(lang))
-        });
+    let language = request.language.as_deref().map_or_else(
+        || detect_language(engine.engine_mut().context_search.hybrid().chunks()),
+        |lang| ContextFinderService::parse_language(Some(lang)),
+    );
 
     if let Err(e) = engine.engine_mut().ensure_graph(language).await {
         return Ok(internal_error_with_meta(
@@ -162,7 +53,7 @@ pub(in crate::tools::dispatch) async fn overview(
         ));
     }
 
-    let result = {
+    let report = {
         let engine_ref = engine.engine_mut();
         let chunks = engine_ref.context_search.hybrid().chunks();
         let Some(assembler) = engine_ref.context_search.assembler() else {
@@ -171,26 +62,14 @@ pub(in crate::tools::dispatch) async fn overview(
                 meta.clone(),
             ));
         };
-        let graph = assembler.graph();
-
-        let project = compute_project_info(&root, chunks);
-        let layers = compute_layers(chunks);
-        let entry_points = compute_entry_points(graph);
-        let key_types = compute_key_types(graph);
-
-        let (nodes, edges) = graph.stats();
-        let graph_stats = GraphStats { nodes, edges };
-
-        OverviewResult {
-            project,
-            layers,
-            entry_points,
-            key_types,
-            graph_stats,
-            meta,
-        }
+        analysis::overview(
+            assembler.graph(),
+            project_name(&root),
+            chunks,
+            request.module_depth,
+        )
     };
 
     drop(engine);
-    Ok(success_payload(&result))
+    Ok(success_payload(&OverviewResult { report, meta }))
 }
@@ -1,17 +1,22 @@
 use super::super::{
-    compute_file_slice_result, compute_grep_context_result, compute_repo_onboarding_pack_result,
-    decode_cursor, finalize_read_pack_budget, AutoIndexPolicy, CallToolResult, Content,
-    ContextFinderService, ContextPackRequest, FileSliceCursorV1, FileSliceRequest,
-    GrepContextComputeOptions, GrepContextCursorV1, GrepContextRequest, McpError, Parameters,
-    ReadPackBudget, ReadPackIntent, ReadPackNextAction, ReadPackRequest, ReadPackResult,
-    ReadPackSection, ReadPackTruncation, RepoOnboardingPackRequest, CURSOR_VERSION,
+    chunk_id, compute_file_slice_result, compute_grep_context_result,
+    compute_repo_onboarding_pack_result, decode_cursor, diff_units, encode_cursor,
+    finalize_read_pack_budget, git_changes, read_hunk_lines, validate_since, AutoIndexPolicy,
+    CallToolResult, Content, ContextFinderService, ContextPackRequest, DiffUnit, FileSliceCursorV1,
+    FileSliceRequest, GitDiffError, GrepContextComputeOptions, GrepContextCursorV1,
+    GrepContextRequest, GrepMatcher, McpError, Parameters, ReadPackBudget, ReadPackDiffCursorV1,
+    ReadPackDiffItem, ReadPackDiffResult, ReadPackIntent, ReadPackNextAction, ReadPackRequest,
+    ReadPackResult, ReadPackSection, ReadPackSymbol, ReadPackSymbolsCursorV1,
+    ReadPackSymbolsResult, ReadPackTruncation, RepoOnboardingPackRequest, CURSOR_VERSION,
 };
+use super::super::{is_outline_glob, outline_file_symbols, symbol_outline_candidates};
 use super::error::{attach_meta, invalid_request_with, invalid_request_with_meta, tool_error};
+use context_graph::analysis::detect_language;
 use context_indexer::ToolMeta;
-use context_protocol::ToolNextAction;
-use regex::RegexBuilder;
+use context_protocol::{ErrorCode, ToolNextAction};
 use serde::Deserialize;
 use serde_json::json;
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -22,6 +27,10 @@ const MAX_MAX_CHARS: usize = 500_000;
 const DEFAULT_GREP_CONTEXT: usize = 20;
 const MAX_GREP_MATCHES: usize = 10_000;
 const MAX_GREP_HUNKS: usize = 200;
+const DEFAULT_DIFF_SINCE: &str = "HEAD~1";
+const MAX_DIFF_NEIGHBORS: usize = 3;
+const DIFF_ITEM_OVERHEAD_CHARS: usize = 160;
+const SYMBOL_ITEM_OVERHEAD_CHARS: usize = 8;
 const DEFAULT_TIMEOUT_MS: u64 = 55_000;
 const MAX_TIMEOUT_MS: u64 = 300_000;
 
@@ -33,7 +42,7 @@ struct CursorHeader {
     tool: String,
 }
 
-fn call_error(code: &'static str, message: impl Into<String>) -> CallToolResult {
+fn call_error(code: ErrorCode, message: impl Into<String>) -> CallToolResult {
     tool_error(code, message)
 }
 
@@ -104,20 +113,23 @@ fn resolve_intent(request: &ReadPackRequest) -> ToolResult<ReadPackIntent> {
     }
 
     if let Some(cursor) = trimmed_non_empty_str(request.cursor.as_deref()) {
-        let header: CursorHeader = decode_cursor(cursor)
-            .map_err(|err| call_error("invalid_cursor", format!("Invalid cursor: {err}")))?;
+        let header: CursorHeader = decode_cursor(cursor).map_err(|err| {
+            call_error(ErrorCode::InvalidCursor, format!("Invalid cursor: {err}"))
+        })?;
         if header.v != CURSOR_VERSION {
             return Err(call_error(
-                "invalid_cursor",
+                ErrorCode::InvalidCursor,
                 "Invalid cursor: wrong version",
             ));
         }
         intent = match header.tool.as_str() {
             "file_slice" => ReadPackIntent::File,
             "grep_context" => ReadPackIntent::Grep,
+            "read_pack_diff" => ReadPackIntent::Diff,
+            "read_pack_symbols" => ReadPackIntent::Symbols,
             _ => {
                 return Err(call_error(
-                    "invalid_cursor",
+                    ErrorCode::InvalidCursor,
                     "Invalid cursor: unsupported tool for read_pack",
                 ))
             }
@@ -134,6 +146,9 @@ fn resolve_intent(request: &ReadPackRequest) -> ToolResult<ReadPackIntent> {
     if trimmed_non_empty_str(request.file.as_deref()).is_some() {
         return Ok(ReadPackIntent::File);
     }
+    if trimmed_non_empty_str(request.since.as_deref()).is_some() {
+        return Ok(ReadPackIntent::Diff);
+    }
 
     Ok(ReadPackIntent::Onboarding)
 }
@@ -145,6 +160,8 @@ fn intent_label(intent: ReadPackIntent) -> &'static str {
         ReadPackIntent::Grep => "grep",
         ReadPackIntent::Query => "query",
         ReadPackIntent::Onboarding => "onboarding",
+        ReadPackIntent::Diff => "diff",
+        ReadPackIntent::Symbols => "symbols",
     }
 }
 
@@ -161,10 +178,10 @@ fn compute_min_envelope_chars(result: &ReadPackResult) -> ToolResult<usize> {
             truncated: true,
             truncation: Some(ReadPackTruncation::MaxChars),
         },
-        meta: ToolMeta { index_state: None },
+        meta: ToolMeta::default(),
     };
     finalize_read_pack_budget(&mut tmp)
-        .map_err(|err| call_error("internal", format!("Error: {err:#}")))?;
+        .map_err(|err| call_error(ErrorCode::Internal, format!("Error: {err:#}")))?;
     Ok(tmp.budget.used_chars)
 }
 
@@ -175,7 +192,7 @@ fn finalize_and_trim(
     intent: ReadPackIntent,
 ) -> ToolResult<ReadPackResult> {
     finalize_read_pack_budget(&mut result)
-        .map_err(|err| call_error("internal", format!("Error: {err:#}")))?;
+        .map_err(|err| call_error(ErrorCode::Internal, format!("Error: {err:#}")))?;
 
     if result.budget.used_chars <= ctx.max_chars {
         return Ok(result);
@@ -203,11 +220,11 @@ fn finalize_and_trim(
             return Err(invalid_request_with(
                 format!("max_chars too small for read_pack response (min_chars={min_chars})"),
                 Some(format!("Increase max_chars to at least {min_chars}.")),
-                vec![ToolNextAction {
-                    tool: "read_pack".to_string(),
-                    args: retry_args,
-                    reason: format!("Retry read_pack with max_chars >= {min_chars}."),
-                }],
+                vec![ToolNextAction::new(
+                    "read_pack",
+                    retry_args,
+                    format!("Retry read_pack with max_chars >= {min_chars}."),
+                )],
             ));
         }
     }
@@ -240,11 +257,11 @@ fn ensure_retry_action(
         .clamp(DEFAULT_MAX_CHARS, MAX_MAX_CHARS);
 
     let args = build_retry_args(ctx, request, intent, suggested_max_chars);
-    result.next_actions.push(ReadPackNextAction {
-        tool: "read_pack".to_string(),
+    result.next_actions.push(ReadPackNextAction::new(
+        "read_pack",
         args,
-        reason: "Increase max_chars to get a fuller read_pack payload.".to_string(),
-    });
+        "Increase max_chars to get a fuller read_pack payload.",
+    ));
     let _ = finalize_read_pack_budget(result);
 }
 
@@ -363,6 +380,22 @@ fn build_retry_args(
                 );
             }
         }
+        ReadPackIntent::Diff => {
+            if let Some(since) = trimmed_non_empty_str(request.since.as_deref()) {
+                args.insert(
+                    "since".to_string(),
+                    serde_json::Value::String(since.to_string()),
+                );
+            }
+        }
+        ReadPackIntent::Symbols => {
+            if let Some(file) = trimmed_non_empty_str(request.file.as_deref()) {
+                args.insert(
+                    "file".to_string(),
+                    serde_json::Value::String(file.to_string()),
+                );
+            }
+        }
         ReadPackIntent::Onboarding | ReadPackIntent::Auto => {}
     }
 
@@ -381,7 +414,9 @@ fn apply_meta_to_sections(meta: &ToolMeta, sections: &mut [ReadPackSection]) {
             ReadPackSection::RepoOnboardingPack { result } => {
                 result.meta = meta.clone();
             }
-            ReadPackSection::ContextPack { .. } => {}
+            ReadPackSection::ContextPack { .. }
+            | ReadPackSection::Diff { .. }
+            | ReadPackSection::Symbols { .. } => {}
         }
     }
 }
@@ -392,7 +427,7 @@ fn decode_file_slice_cursor(cursor: Option<&str>) -> ToolResult<Option<FileSlice
     };
 
     let decoded: FileSliceCursorV1 = decode_cursor(cursor)
-        .map_err(|err| call_error("invalid_cursor", format!("Invalid cursor: {err}")))?;
+        .map_err(|err| call_error(ErrorCode::InvalidCursor, format!("Invalid cursor: {err}")))?;
     Ok(Some(decoded))
 }
 
@@ -406,13 +441,13 @@ fn handle_file_intent(
     if let Some(decoded) = cursor_payload.as_ref() {
         if decoded.v != CURSOR_VERSION || decoded.tool != "file_slice" {
             return Err(call_error(
-                "invalid_cursor",
+                ErrorCode::InvalidCursor,
                 "Invalid cursor: wrong tool (expected file_slice)",
             ));
         }
         if decoded.root != ctx.root_display {
             return Err(call_error(
-                "invalid_cursor",
+                ErrorCode::InvalidCursor,
                 format!(
                     "Invalid cursor: different root (cursor={}, expected={})",
                     decoded.root, ctx.root_display
@@ -425,7 +460,7 @@ fn handle_file_intent(
     if let (Some(decoded), Some(requested)) = (cursor_payload.as_ref(), requested_file.as_ref()) {
         if requested != &decoded.file {
             return Err(call_error(
-                "invalid_cursor",
+                ErrorCode::InvalidCursor,
                 format!(
                     "Invalid cursor: different file (cursor={}, request={})",
                     decoded.file, requested
@@ -437,7 +472,7 @@ fn handle_file_intent(
     let file = requested_file.or_else(|| cursor_payload.as_ref().map(|c| c.file.clone()));
     let Some(file) = file else {
         return Err(call_error(
-            "missing_field",
+            ErrorCode::MissingField,
             "Error: file is required for intent=file",
         ));
     };
@@ -448,7 +483,7 @@ fn handle_file_intent(
     if let (Some(decoded), Some(requested)) = (cursor_payload.as_ref(), request.max_lines) {
         if requested != decoded.max_lines {
             return Err(call_error(
-                "invalid_cursor",
+                ErrorCode::InvalidCursor,
                 format!(
                     "Invalid cursor: different max_lines (cursor={}, request={})",
                     decoded.max_lines, requested
@@ -462,7 +497,7 @@ fn handle_file_intent(
             if ctx.inner_max_chars != decoded.max_chars {
                 let suggested = suggest_outer_max_chars(decoded.max_chars);
                 return Err(call_error(
-                    "invalid_cursor",
+                    ErrorCode::InvalidCursor,
                     format!(
                         "Invalid cursor: different max_chars (cursor={}, request_max_chars={} -> inner={}). \
 For continuation, omit max_chars or set max_chars to {}.",
@@ -484,17 +519,20 @@ For continuation, omit max_chars or set max_chars to {}.",
             path: None,
             file: file.clone(),
             start_line: request.start_line,
+            symbol: None,
+            occurrence: None,
+            context_lines: None,
             max_lines,
             max_chars: Some(file_slice_max_chars),
             cursor: request.cursor.clone(),
         },
     )
-    .map_err(|err| call_error("internal", err))?;
+    .map_err(|err| call_error(ErrorCode::Internal, err))?;
 
     if let Some(next_cursor) = slice.next_cursor.as_deref() {
-        next_actions.push(ReadPackNextAction {
-            tool: "read_pack".to_string(),
-            args: json!({
+        next_actions.push(ReadPackNextAction::new(
+            "read_pack",
+            json!({
                 "path": ctx.root_display.clone(),
                 "intent": "file",
                 "file": file,
@@ -502,8 +540,8 @@ For continuation, omit max_chars or set max_chars to {}.",
                 "max_chars": ctx.max_chars,
                 "cursor": next_cursor,
             }),
-            reason: "Continue reading the next page of the file slice.".to_string(),
-        });
+            "Continue reading the next page of the file slice.",
+        ));
     }
 
     sections.push(ReadPackSection::FileSlice { result: slice });
@@ -516,7 +554,7 @@ fn decode_grep_cursor(cursor: Option<&str>) -> ToolResult<Option<GrepContextCurs
     };
 
     let decoded: GrepContextCursorV1 = decode_cursor(cursor)
-        .map_err(|err| call_error("invalid_cursor", format!("Invalid cursor: {err}")))?;
+        .map_err(|err| call_error(ErrorCode::InvalidCursor, format!("Invalid cursor: {err}")))?;
     Ok(Some(decoded))
 }
 
@@ -525,11 +563,14 @@ fn validate_grep_cursor_tool_root(
     root_display: &str,
 ) -> ToolResult<()> {
     if decoded.v != CURSOR_VERSION || decoded.tool != "grep_context" {
-        return Err(call_error("invalid_cursor", "Invalid cursor: wrong tool"));
+        return Err(call_error(
+            ErrorCode::InvalidCursor,
+            "Invalid cursor: wrong tool",
+        ));
     }
     if decoded.root != root_display {
         return Err(call_error(
-            "invalid_cursor",
+            ErrorCode::InvalidCursor,
             "Invalid cursor: different root",
         ));
     }
@@ -551,7 +592,7 @@ fn resolve_grep_pattern(
     }
 
     Err(call_error(
-        "missing_field",
+        ErrorCode::MissingField,
         "Error: pattern is required for intent=grep",
     ))
 }
@@ -575,21 +616,24 @@ fn resolve_grep_resume(
     };
     validate_grep_cursor_tool_root(decoded, root_display)?;
 
-    if decoded.pattern != check.pattern {
+    if decoded.pattern != check.pattern
+        || !decoded.patterns.is_empty()
+        || decoded.exclude_pattern.is_some()
+    {
         return Err(call_error(
-            "invalid_cursor",
+            ErrorCode::InvalidCursor,
             "Invalid cursor: different pattern",
         ));
     }
     if decoded.file.as_ref() != check.file {
         return Err(call_error(
-            "invalid_cursor",
+            ErrorCode::InvalidCursor,
             "Invalid cursor: different file",
         ));
     }
     if decoded.file_pattern.as_ref() != check.file_pattern {
         return Err(call_error(
-            "invalid_cursor",
+            ErrorCode::InvalidCursor,
             "Invalid cursor: different file_pattern",
         ));
     }
@@ -598,7 +642,7 @@ fn resolve_grep_resume(
         || decoded.after != check.after
     {
         return Err(call_error(
-            "invalid_cursor",
+            ErrorCode::InvalidCursor,
             "Invalid cursor: different search options",
         ));
     }
@@ -626,10 +670,8 @@ async fn handle_grep_intent(
         .case_sensitive
         .or_else(|| cursor_payload.as_ref().map(|c| c.case_sensitive))
         .unwrap_or(true);
-    let regex = RegexBuilder::new(&pattern)
-        .case_insensitive(!case_sensitive)
-        .build()
-        .map_err(|err| call_error("invalid_request", format!("Invalid regex: {err}")))?;
+    let matcher = GrepMatcher::new(std::slice::from_ref(&pattern), None, case_sensitive)
+        .map_err(|err| call_error(ErrorCode::InvalidRequest, err))?;
 
     let before = request
         .before
@@ -665,6 +707,8 @@ async fn handle_grep_intent(
     let grep_request = GrepContextRequest {
         path: None,
         pattern: pattern.clone(),
+        patterns: Vec::new(),
+        exclude_pattern: None,
         file: normalized_file,
         file_pattern: normalized_file_pattern,
         context: None,
@@ -681,7 +725,7 @@ async fn handle_grep_intent(
         &ctx.root,
         &ctx.root_display,
         &grep_request,
-        &regex,
+        &matcher,
         GrepContextComputeOptions {
             case_sensitive,
             before,
@@ -694,15 +738,15 @@ async fn handle_grep_intent(
         },
     )
     .await
-    .map_err(|err| call_error("internal", format!("Error: {err:#}")))?;
+    .map_err(|err| call_error(ErrorCode::Internal, format!("Error: {err:#}")))?;
 
     if let Some(next_cursor) = result.next_cursor.as_deref() {
         let GrepContextRequest {
             file, file_pattern, ..
         } = grep_request;
-        next_actions.push(ReadPackNextAction {
-            tool: "read_pack".to_string(),
-            args: json!({
+        next_actions.push(ReadPackNextAction::new(
+            "read_pack",
+            json!({
                 "path": ctx.root_display.clone(),
                 "intent": "grep",
                 "pattern": pattern,
@@ -714,8 +758,8 @@ async fn handle_grep_intent(
                 "max_chars": ctx.max_chars,
                 "cursor": next_cursor,
             }),
-            reason: "Continue grep_context pagination (next page of hunks).".to_string(),
-        });
+            "Continue grep_context pagination (next page of hunks).",
+        ));
     }
 
     sections.push(ReadPackSection::GrepContext { result });
@@ -733,7 +777,7 @@ async fn handle_query_intent(
         .to_string();
     if query.is_empty() {
         return Err(call_error(
-            "missing_field",
+            ErrorCode::MissingField,
             "Error: query is required for intent=query",
         ));
     }
@@ -742,10 +786,13 @@ async fn handle_query_intent(
         .context_pack(Parameters(ContextPackRequest {
             path: Some(ctx.root_display.clone()),
             query,
+            workspace: None,
+            roots: None,
             language: None,
             strategy: None,
             limit: None,
             max_chars: Some(ctx.inner_max_chars),
+            max_tokens: None,
             max_related_per_primary: None,
             include_docs: request.include_docs,
             prefer_code: request.prefer_code,
@@ -753,14 +800,16 @@ async fn handle_query_intent(
             auto_index: request.auto_index,
             auto_index_budget_ms: request.auto_index_budget_ms,
             trace: Some(false),
+            models: None,
+            stale_policy: None,
         }))
         .await
-        .map_err(|err| call_error("internal", format!("Error: {err}")))?;
+        .map_err(|err| call_error(ErrorCode::Internal, format!("Error: {err}")))?;
 
     if tool_result.is_error == Some(true) {
         let message = extract_tool_error_message(&tool_result);
         return Err(call_error(
-            "internal",
+            ErrorCode::Internal,
             format!("context_pack failed: {message}"),
         ));
     }
@@ -772,7 +821,7 @@ async fn handle_query_intent(
         .map_or("", |t| t.text.as_str());
     let value: serde_json::Value = serde_json::from_str(text).map_err(|err| {
         call_error(
-            "internal",
+            ErrorCode::Internal,
             format!("Error: context_pack returned invalid JSON: {err}"),
         )
     })?;
@@ -790,6 +839,7 @@ async fn handle_onboarding_intent(
         path: Some(ctx.root_display.clone()),
         map_depth: None,
         map_limit: None,
+        sections: None,
         doc_paths: None,
         docs_limit: None,
         doc_max_lines: None,
@@ -802,7 +852,7 @@ async fn handle_onboarding_intent(
     let pack =
         compute_repo_onboarding_pack_result(&ctx.root, &ctx.root_display, &onboarding_request)
             .await
-            .map_err(|err| call_error("internal", format!("Error: {err:#}")))?;
+            .map_err(|err| call_error(ErrorCode::Internal, format!("Error: {err:#}")))?;
 
     sections.push(ReadPackSection::RepoOnboardingPack {
         result: Box::new(pack),
@@ -810,6 +860,476 @@ async fn handle_onboarding_intent(
     Ok(())
 }
 
+fn decode_diff_cursor(cursor: Option<&str>) -> ToolResult<Option<ReadPackDiffCursorV1>> {
+    let Some(cursor) = trimmed_non_empty_str(cursor) else {
+        return Ok(None);
+    };
+
+    let decoded: ReadPackDiffCursorV1 = decode_cursor(cursor)
+        .map_err(|err| call_error(ErrorCode::InvalidCursor, format!("Invalid cursor: {err}")))?;
+    Ok(Some(decoded))
+}
+
+fn resolve_diff_since(
+    requested: Option<&str>,
+    cursor_payload: Option<&ReadPackDiffCursorV1>,
+    root_display: &str,
+) -> ToolResult<String> {
+    if let Some(decoded) = cursor_payload {
+        if decoded.v != CURSOR_VERSION || decoded.tool != "read_pack_diff" {
+            return Err(call_error(
+                ErrorCode::InvalidCursor,
+                "Invalid cursor: wrong tool (expected read_pack_diff)",
+            ));
+        }
+        if decoded.root != root_display {
+            return Err(call_error(
+                ErrorCode::InvalidCursor,
+                format!(
+                    "Invalid cursor: different root (cursor={}, expected={})",
+                    decoded.root, root_display
+                ),
+            ));
+        }
+        if let Some(requested) = requested {
+            if requested != decoded.since {
+                return Err(call_error(
+                    ErrorCode::InvalidCursor,
+                    format!(
+                        "Invalid cursor: different since (cursor={}, request={})",
+                        decoded.since, requested
+                    ),
+                ));
+            }
+        }
+    }
+
+    let since = requested
+        .map(str::to_string)
+        .or_else(|| cursor_payload.map(|c| c.since.clone()))
+        .unwrap_or_else(|| DEFAULT_DIFF_SINCE.to_string());
+    validate_since(&since).map_err(|err| call_error(ErrorCode::InvalidRequest, err))?;
+    Ok(since)
+}
+
+fn git_diff_error(err: GitDiffError, root_display: &str) -> CallToolResult {
+    let message = match err {
+        GitDiffError::NotGitRepo => {
+            format!("intent=diff requires a git repository (root={root_display})")
+        }
+        GitDiffError::GitUnavailable(err) => format!("intent=diff requires git: {err}"),
+        GitDiffError::Failed(err) => {
+            return call_error(ErrorCode::InvalidRequest, format!("git diff failed: {err}"));
+        }
+    };
+    invalid_request_with(
+        message,
+        Some("No git history here; browse files with list_files instead.".to_string()),
+        vec![ToolNextAction::new(
+            "list_files",
+            json!({ "path": root_display }),
+            "List project files (diff needs git).",
+        )],
+    )
+}
+
+fn diff_item_chars(item: &ReadPackDiffItem) -> usize {
+    item.content.len() + DIFF_ITEM_OVERHEAD_CHARS
+}
+
+/// Cut `content` so the item fits `max_chars`, preferring a line break.
+fn truncate_diff_content(content: &mut String, max_chars: usize) {
+    let max_bytes = max_chars.saturating_sub(DIFF_ITEM_OVERHEAD_CHARS);
+    if content.len() <= max_bytes {
+        return;
+    }
+    let mut cut = max_bytes;
+    while !content.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    let cut = content[..cut]
+        .rfind('\n')
+        .filter(|&idx| idx > 0)
+        .unwrap_or(cut);
+    content.truncate(cut);
+}
+
+async fn diff_unit_item(ctx: &ReadPackContext, unit: &DiffUnit) -> ReadPackDiffItem {
+    match unit {
+        DiffUnit::Chunk { chunk, ranges } => ReadPackDiffItem {
+            id: chunk_id(&chunk.file_path, chunk.start_line, chunk.end_line),
+            role: "changed".to_string(),
+            file: chunk.file_path.clone(),
+            start_line: chunk.start_line,
+            end_line: chunk.end_line,
+            symbol: chunk.metadata.symbol_name.clone(),
+            chunk_type: chunk.metadata.chunk_type.map(|ct| ct.as_str().to_string()),
+            changed_lines: ranges.iter().map(|&(start, end)| [start, end]).collect(),
+            relationship: None,
+            content: chunk.content.clone(),
+        },
+        DiffUnit::Hunk {
+            file,
+            start_line,
+            end_line,
+        } => ReadPackDiffItem {
+            id: chunk_id(file, *start_line, *end_line),
+            role: "changed".to_string(),
+            file: file.clone(),
+            start_line: *start_line,
+            end_line: *end_line,
+            symbol: None,
+            chunk_type: None,
+            changed_lines: vec![[*start_line, *end_line]],
+            relationship: None,
+            content: read_hunk_lines(&ctx.root, file, *start_line, *end_line).await,
+        },
+    }
+}
+
+/// Direct graph neighbors of a changed chunk that are not themselves changed or already packed.
+fn diff_neighbors(
+    assembler: &context_graph::ContextAssembler,
+    changed_id: &str,
+    seen: &mut HashSet<String>,
+) -> Vec<ReadPackDiffItem> {
+    let Ok(assembled) =
+        assembler.assemble_for_chunk(changed_id, context_graph::AssemblyStrategy::Direct)
+    else {
+        return Vec::new();
+    };
+
+    let mut out = Vec::new();
+    for related in assembled.related_chunks {
+        if out.len() >= MAX_DIFF_NEIGHBORS {
+            break;
+        }
+        let related = context_search::RelatedContext::from(related);
+        let chunk = related.chunk;
+        let id = chunk_id(&chunk.file_path, chunk.start_line, chunk.end_line);
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        out.push(ReadPackDiffItem {
+            id,
+            role: "neighbor".to_string(),
+            file: chunk.file_path,
+            start_line: chunk.start_line,
+            end_line: chunk.end_line,
+            symbol: chunk.metadata.symbol_name,
+            chunk_type: chunk.metadata.chunk_type.map(|ct| ct.as_str().to_string()),
+            changed_lines: Vec::new(),
+            relationship: Some(related.relationship_path),
+            content: chunk.content,
+        });
+    }
+    out
+}
+
+async fn handle_diff_intent(
+    service: &ContextFinderService,
+    ctx: &ReadPackContext,
+    request: &ReadPackRequest,
+    sections: &mut Vec<ReadPackSection>,
+    next_actions: &mut Vec<ReadPackNextAction>,
+) -> ToolResult<()> {
+    let cursor_payload = decode_diff_cursor(request.cursor.as_deref())?;
+    let since = resolve_diff_since(
+        trimmed_non_empty_str(request.since.as_deref()),
+        cursor_payload.as_ref(),
+        &ctx.root_display,
+    )?;
+    let offset = cursor_payload.as_ref().map_or(0, |c| c.offset);
+
+    let changes = git_changes(&ctx.root, &since)
+        .await
+        .map_err(|err| git_diff_error(err, &ctx.root_display))?;
+
+    // The corpus maps hunks to chunks and the graph supplies neighbors; without an index the
+    // pack degrades to raw hunks.
+    let policy = AutoIndexPolicy::from_request(request.auto_index, request.auto_index_budget_ms);
+    let mut engine = match service.prepare_semantic_engine(&ctx.root, policy).await {
+        Ok((engine, _)) => Some(engine),
+        Err(err) => {
+            log::debug!("read_pack diff without semantic engine: {err:#}");
+            None
+        }
+    };
+    let units = match engine.as_mut() {
+        Some(engine) => diff_units(
+            &changes,
+            engine.engine_mut().context_search.hybrid().chunks(),
+        ),
+        None => diff_units(&changes, &[]),
+    };
+
+    let budget = ctx.inner_max_chars;
+    let mut used = 0usize;
+    let mut truncated = false;
+    let mut next_offset = None;
+    let mut changed_items: Vec<ReadPackDiffItem> = Vec::new();
+    for (idx, unit) in units.iter().enumerate().skip(offset) {
+        let mut item = diff_unit_item(ctx, unit).await;
+        if changed_items.is_empty() {
+            truncate_diff_content(&mut item.content, budget);
+        }
+        let cost = diff_item_chars(&item);
+        if used + cost > budget && !changed_items.is_empty() {
+            truncated = true;
+            next_offset = Some(idx);
+            break;
+        }
+        used += cost;
+        changed_items.push(item);
+    }
+
+    // Neighbors only fill whatever budget the changed code leaves over.
+    let mut items: Vec<ReadPackDiffItem> = Vec::with_capacity(changed_items.len());
+    let mut seen: HashSet<String> = units
+        .iter()
+        .map(|unit| match unit {
+            DiffUnit::Chunk { chunk, .. } => {
+                chunk_id(&chunk.file_path, chunk.start_line, chunk.end_line)
+            }
+            DiffUnit::Hunk {
+                file,
+                start_line,
+                end_line,
+            } => chunk_id(file, *start_line, *end_line),
+        })
+        .collect();
+    let mut assembler = None;
+    if let Some(engine) = engine.as_mut() {
+        let language = detect_language(engine.engine_mut().context_search.hybrid().chunks());
+        match engine.engine_mut().ensure_graph(language).await {
+            Ok(()) => assembler = engine.engine_mut().context_search.assembler(),
+            Err(err) => log::debug!("read_pack diff without graph neighbors: {err:#}"),
+        }
+    }
+    for item in changed_items {
+        let neighbors = match (assembler, item.role.as_str(), item.symbol.is_some()) {
+            (Some(assembler), "changed", true) => diff_neighbors(assembler, &item.id, &mut seen),
+            _ => Vec::new(),
+        };
+        items.push(item);
+        for neighbor in neighbors {
+            let cost = diff_item_chars(&neighbor);
+            if used + cost > budget {
+                truncated = true;
+                continue;
+            }
+            used += cost;
+            items.push(neighbor);
+        }
+    }
+
+    let next_cursor = match next_offset {
+        Some(offset) => Some(
+            encode_cursor(&ReadPackDiffCursorV1 {
+                v: CURSOR_VERSION,
+                tool: "read_pack_diff".to_string(),
+                root: ctx.root_display.clone(),
+                since: since.clone(),
+                offset,
+            })
+            .map_err(|err| call_error(ErrorCode::Internal, format!("Error: {err:#}")))?,
+        ),
+        None => None,
+    };
+    if let Some(next_cursor) = next_cursor.as_deref() {
+        next_actions.push(ReadPackNextAction::new(
+            "read_pack",
+            json!({
+                "path": ctx.root_display.clone(),
+                "intent": "diff",
+                "since": since.clone(),
+                "max_chars": ctx.max_chars,
+                "cursor": next_cursor,
+            }),
+            "Continue the diff pack (next page of changed code).",
+        ));
+    }
+
+    sections.push(ReadPackSection::Diff {
+        result: ReadPackDiffResult {
+            since,
+            changed_files: changes.files.len(),
+            changed_units: units.len(),
+            deleted_files: changes.deleted,
+            items,
+            truncated,
+            next_cursor,
+        },
+    });
+    Ok(())
+}
+
+fn decode_symbols_cursor(cursor: Option<&str>) -> ToolResult<Option<ReadPackSymbolsCursorV1>> {
+    let Some(cursor) = trimmed_non_empty_str(cursor) else {
+        return Ok(None);
+    };
+
+    let decoded: ReadPackSymbolsCursorV1 = decode_cursor(cursor)
+        .map_err(|err| call_error(ErrorCode::InvalidCursor, format!("Invalid cursor: {err}")))?;
+    if decoded.v != CURSOR_VERSION || decoded.tool != "read_pack_symbols" {
+        return Err(call_error(
+            ErrorCode::InvalidCursor,
+            "Invalid cursor: wrong tool (expected read_pack_symbols)",
+        ));
+    }
+    Ok(Some(decoded))
+}
+
+fn resolve_symbols_file(
+    requested: Option<&str>,
+    cursor_payload: Option<&ReadPackSymbolsCursorV1>,
+    root_display: &str,
+) -> ToolResult<String> {
+    if let Some(decoded) = cursor_payload {
+        if decoded.root != root_display {
+            return Err(call_error(
+                ErrorCode::InvalidCursor,
+                format!(
+                    "Invalid cursor: different root (cursor={}, expected={})",
+                    decoded.root, root_display
+                ),
+            ));
+        }
+        if let Some(requested) = requested {
+            if requested != decoded.file {
+                return Err(call_error(
+                    ErrorCode::InvalidCursor,
+                    format!(
+                        "Invalid cursor: different file (cursor={}, request={})",
+                        decoded.file, requested
+                    ),
+                ));
+            }
+        }
+    }
+
+    requested
+        .map(str::to_string)
+        .or_else(|| cursor_payload.map(|c| c.file.clone()))
+        .ok_or_else(|| {
+            call_error(
+                ErrorCode::MissingField,
+                "Error: file (path or glob) is required for intent=symbols",
+            )
+        })
+}
+
+fn symbol_item_chars(symbol: &ReadPackSymbol) -> usize {
+    serde_json::to_string(symbol).map_or(0, |json| json.len()) + SYMBOL_ITEM_OVERHEAD_CHARS
+}
+
+async fn handle_symbols_intent(
+    ctx: &ReadPackContext,
+    request: &ReadPackRequest,
+    sections: &mut Vec<ReadPackSection>,
+    next_actions: &mut Vec<ReadPackNextAction>,
+) -> ToolResult<()> {
+    let cursor_payload = decode_symbols_cursor(request.cursor.as_deref())?;
+    let file = resolve_symbols_file(
+        trimmed_non_empty_str(request.file.as_deref()),
+        cursor_payload.as_ref(),
+        &ctx.root_display,
+    )?;
+
+    let candidates = symbol_outline_candidates(&ctx.root, &file)
+        .await
+        .map_err(|err| call_error(ErrorCode::InvalidRequest, format!("Error: {err:#}")))?;
+    let (start_file, mut skip) = match cursor_payload.as_ref() {
+        Some(decoded) => {
+            let Some(idx) = candidates
+                .iter()
+                .position(|(display, _)| *display == decoded.resume_file)
+            else {
+                return Err(call_error(
+                    ErrorCode::InvalidCursor,
+                    format!(
+                        "Invalid cursor: resume file no longer matches ({})",
+                        decoded.resume_file
+                    ),
+                ));
+            };
+            (idx, decoded.resume_index)
+        }
+        None => (0, 0),
+    };
+
+    let single_file = !is_outline_glob(&file);
+    let budget = ctx.inner_max_chars;
+    let mut used = 0usize;
+    let mut symbols: Vec<ReadPackSymbol> = Vec::new();
+    let mut resume: Option<(String, usize)> = None;
+    'files: for (display, path) in candidates.iter().skip(start_file) {
+        let outline = match outline_file_symbols(display, path) {
+            Ok(outline) => outline,
+            // A glob outline skips files it cannot parse; a single file reports why.
+            Err(err) if single_file => {
+                return Err(call_error(
+                    ErrorCode::InvalidRequest,
+                    format!("Error: {err:#}"),
+                ));
+            }
+            Err(err) => {
+                log::debug!("read_pack symbols skipped {display}: {err:#}");
+                continue;
+            }
+        };
+        for (idx, symbol) in outline.into_iter().enumerate().skip(skip) {
+            let cost = symbol_item_chars(&symbol);
+            if used + cost > budget && !symbols.is_empty() {
+                resume = Some((display.clone(), idx));
+                break 'files;
+            }
+            used += cost;
+            symbols.push(symbol);
+        }
+        skip = 0;
+    }
+
+    let next_cursor = match resume {
+        Some((resume_file, resume_index)) => Some(
+            encode_cursor(&ReadPackSymbolsCursorV1 {
+                v: CURSOR_VERSION,
+                tool: "read_pack_symbols".to_string(),
+                root: ctx.root_display.clone(),
+                file: file.clone(),
+                resume_file,
+                resume_index,
+            })
+            .map_err(|err| call_error(ErrorCode::Internal, format!("Error: {err:#}")))?,
+        ),
+        None => None,
+    };
+    if let Some(next_cursor) = next_cursor.as_deref() {
+        next_actions.push(ReadPackNextAction::new(
+            "read_pack",
+            json!({
+                "path": ctx.root_display.clone(),
+                "intent": "symbols",
+                "file": file.clone(),
+                "max_chars": ctx.max_chars,
+                "cursor": next_cursor,
+            }),
+            "Continue the symbol outline (next page).",
+        ));
+    }
+
+    sections.push(ReadPackSection::Symbols {
+        result: ReadPackSymbolsResult {
+            file,
+            matched_files: candidates.len(),
+            symbols,
+            truncated: next_cursor.is_some(),
+            next_cursor,
+        },
+    });
+    Ok(())
+}
+
 /// Build a one-call semantic reading pack (file slice / grep context / context pack / onboarding).
 pub(in crate::tools::dispatch) async fn read_pack(
     service: &ContextFinderService,
@@ -820,7 +1340,7 @@ pub(in crate::tools::dispatch) async fn read_pack(
         Err(message) => {
             return Ok(invalid_request_with_meta(
                 message,
-                ToolMeta { index_state: None },
+                ToolMeta::default(),
                 None,
                 Vec::new(),
             ))
@@ -867,6 +1387,12 @@ pub(in crate::tools::dispatch) async fn read_pack(
             ReadPackIntent::Onboarding => {
                 handle_onboarding_intent(&ctx, &request, &mut sections).await
             }
+            ReadPackIntent::Diff => {
+                handle_diff_intent(service, &ctx, &request, &mut sections, &mut next_actions).await
+            }
+            ReadPackIntent::Symbols => {
+                handle_symbols_intent(&ctx, &request, &mut sections, &mut next_actions).await
+            }
         }
     };
     let handler_result =
@@ -940,6 +1466,7 @@ mod tests {
             file: None,
             pattern: None,
             query: None,
+            since: None,
             file_pattern: None,
             before: None,
             after: None,
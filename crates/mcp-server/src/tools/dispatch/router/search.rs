@@ -1,13 +1,356 @@
 use super::super::{
-    AutoIndexPolicy, CallToolResult, Content, ContextFinderService, McpError, SearchRequest,
-    SearchResponse, SearchResult,
+    decode_cursor, encode_cursor, gather_model_index_state, index_path_for_model, unix_ms,
+    AutoIndexPolicy, CallToolResult, Content, ContextFinderService, EngineLock, McpError,
+    SearchCursorV1, SearchRequest, SearchResponse, SearchResult, ToolMeta, WorkspaceMember,
+    CURSOR_VERSION,
 };
 
 use super::error::{
-    index_recovery_actions, internal_error_with_meta, invalid_request_with_meta, meta_for_request,
-    tool_error_envelope_with_meta,
+    index_recovery_actions, internal_error_with_meta, invalid_cursor_with_meta,
+    invalid_request_with_meta, meta_for_request, tool_error_envelope_with_meta,
 };
-use context_protocol::{DefaultBudgets, ErrorEnvelope, ToolNextAction};
+use super::workspace::{merge_member_hits, resolve_workspace_scope, WorkspaceRun};
+use context_protocol::{DefaultBudgets, ErrorCode, ErrorEnvelope, ToolNextAction};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Deepest rank a `search`/`context` cursor chain can reach; every page re-runs the ranked
+/// search over `offset + limit` hits, so the window is bounded.
+pub(super) const MAX_PAGED_HITS: usize = 200;
+
+/// Everything a `search`/`context` cursor is pinned to besides the offset.
+pub(super) struct SearchPaging<'a> {
+    pub(super) tool: &'static str,
+    pub(super) root_display: &'a str,
+    pub(super) query: &'a str,
+    pub(super) profile: String,
+    pub(super) index_mtime_ms: u64,
+    pub(super) strategy: Option<&'a str>,
+    pub(super) language: Option<&'a str>,
+}
+
+impl<'a> SearchPaging<'a> {
+    pub(super) fn new(
+        service: &ContextFinderService,
+        tool: &'static str,
+        root_display: &'a str,
+        query: &'a str,
+        index_mtime: SystemTime,
+    ) -> Self {
+        Self {
+            tool,
+            root_display,
+            query,
+            profile: service.profile.fingerprint(),
+            index_mtime_ms: unix_ms(index_mtime),
+            strategy: None,
+            language: None,
+        }
+    }
+
+    /// Offset to resume from (0 without a cursor), or the `invalid_cursor` message.
+    pub(super) fn start_offset(&self, cursor: Option<&str>) -> std::result::Result<usize, String> {
+        let Some(cursor) = cursor.map(str::trim).filter(|s| !s.is_empty()) else {
+            return Ok(0);
+        };
+        let decoded: SearchCursorV1 =
+            decode_cursor(cursor).map_err(|err| format!("Invalid cursor: {err}"))?;
+        if decoded.v != CURSOR_VERSION || decoded.tool != self.tool {
+            return Err("Invalid cursor: wrong tool".to_string());
+        }
+        if decoded.root != self.root_display {
+            return Err("Invalid cursor: different root".to_string());
+        }
+        if decoded.query != self.query {
+            return Err("Invalid cursor: different query".to_string());
+        }
+        if decoded.strategy.as_deref() != self.strategy
+            || decoded.language.as_deref() != self.language
+        {
+            return Err("Invalid cursor: different search options".to_string());
+        }
+        if decoded.profile != self.profile {
+            return Err("Invalid cursor: search profile changed; rerun without cursor".to_string());
+        }
+        if decoded.index_mtime_ms != self.index_mtime_ms {
+            return Err(
+                "Invalid cursor: index changed since the cursor was issued; rerun without cursor"
+                    .to_string(),
+            );
+        }
+        if decoded.offset >= MAX_PAGED_HITS {
+            return Err("Invalid cursor: out of range".to_string());
+        }
+        Ok(decoded.offset)
+    }
+
+    pub(super) fn encode(&self, offset: usize) -> anyhow::Result<String> {
+        encode_cursor(&SearchCursorV1 {
+            v: CURSOR_VERSION,
+            tool: self.tool.to_string(),
+            root: self.root_display.to_string(),
+            query: self.query.to_string(),
+            profile: self.profile.clone(),
+            index_mtime_ms: self.index_mtime_ms,
+            strategy: self.strategy.map(str::to_string),
+            language: self.language.map(str::to_string),
+            offset,
+        })
+    }
+}
+
+/// Slices one page out of a ranked run of `offset + limit + 1` hits; returns the page and the
+/// next offset when more hits remain inside [`MAX_PAGED_HITS`].
+pub(super) fn take_page<T>(ranked: Vec<T>, offset: usize, limit: usize) -> (Vec<T>, Option<usize>) {
+    let end = offset + limit;
+    let has_more = ranked.len() > end && end < MAX_PAGED_HITS;
+    let page = ranked.into_iter().skip(offset).take(limit).collect();
+    (page, has_more.then_some(end))
+}
+
+fn format_hit(hit: context_search::SearchResult) -> SearchResult {
+    let chunk = hit.chunk;
+    SearchResult {
+        file: chunk.file_path,
+        start_line: chunk.start_line,
+        end_line: chunk.end_line,
+        symbol: chunk.metadata.symbol_name,
+        symbol_type: chunk.metadata.chunk_type.map(|ct| ct.as_str().to_string()),
+        score: hit.score,
+        content: chunk.content,
+        indexed_at_ms: hit.freshness.map(|f| f.indexed_at_ms),
+        file_mtime_ms: hit.freshness.map(|f| f.file_mtime_ms),
+    }
+}
+
+fn oldest_result_ms(results: &[SearchResult]) -> Option<u64> {
+    results.iter().filter_map(|hit| hit.indexed_at_ms).min()
+}
+
+/// Checks an explicit `models` list against the indices built on disk for the root, loads
+/// the ones the engine was built without, and folds the staleness of every model besides the
+/// primary one into `meta.index_state` (or fails under `stale_policy="fail"`). Returns the
+/// override to install on the engine (`None` keeps the profile's expert routing).
+pub(super) async fn select_models(
+    service: &ContextFinderService,
+    engine: &mut EngineLock,
+    root: &Path,
+    root_display: &str,
+    models: Option<&[String]>,
+    stale_policy: Option<&str>,
+    meta: &mut ToolMeta,
+) -> std::result::Result<Option<Vec<String>>, CallToolResult> {
+    let mut requested: Vec<String> = Vec::new();
+    for model_id in models.unwrap_or_default() {
+        let model_id = model_id.trim();
+        if !model_id.is_empty() && !requested.iter().any(|id| id == model_id) {
+            requested.push(model_id.to_string());
+        }
+    }
+    if requested.is_empty() {
+        return Ok(None);
+    }
+
+    let fail_on_stale = match stale_policy.map(str::trim) {
+        None | Some("" | "auto" | "warn") => false,
+        Some("fail") => true,
+        Some(other) => {
+            return Err(invalid_request_with_meta(
+                format!("Error: Unknown stale_policy '{other}' (expected auto, warn or fail)"),
+                meta.clone(),
+                None,
+                Vec::new(),
+            ));
+        }
+    };
+
+    let missing: Vec<String> = requested
+        .iter()
+        .filter(|id| !index_path_for_model(root, id).exists())
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        let mut next_actions = index_recovery_actions(root_display);
+        next_actions[0].args["models"] = serde_json::json!(missing);
+        return Err(tool_error_envelope_with_meta(
+            ErrorEnvelope {
+                code: ErrorCode::IndexMissing.to_string(),
+                message: format!(
+                    "Error: No semantic index built for model(s): {}",
+                    missing.join(", ")
+                ),
+                details: None,
+                hint: Some("Index the missing models (see next_actions).".to_string()),
+                next_actions,
+            },
+            meta.clone(),
+        ));
+    }
+
+    let primary = meta
+        .index_state
+        .as_ref()
+        .map(|state| state.model_id.clone());
+    for model_id in requested
+        .iter()
+        .filter(|id| primary.as_deref() != Some(id.as_str()))
+    {
+        let extra = match gather_model_index_state(root, &service.profile, model_id.clone()).await {
+            Ok(extra) => extra,
+            Err(err) => {
+                return Err(internal_error_with_meta(
+                    format!("Error: Failed to read index state for model '{model_id}': {err:#}"),
+                    meta.clone(),
+                ));
+            }
+        };
+        if !extra.stale {
+            continue;
+        }
+        if fail_on_stale {
+            let reasons: Vec<String> = extra
+                .stale_reasons
+                .iter()
+                .filter_map(|reason| serde_json::to_value(reason).ok())
+                .filter_map(|reason| reason.as_str().map(str::to_string))
+                .collect();
+            let mut next_actions = index_recovery_actions(root_display);
+            next_actions[0].args["models"] = serde_json::json!([model_id]);
+            return Err(tool_error_envelope_with_meta(
+                ErrorEnvelope {
+                    code: ErrorCode::Stale.to_string(),
+                    message: format!(
+                        "Error: Index for model '{model_id}' is stale ({})",
+                        reasons.join(", ")
+                    ),
+                    details: None,
+                    hint: Some(
+                        "Reindex the model (see next_actions) or set stale_policy to 'warn'."
+                            .to_string(),
+                    ),
+                    next_actions,
+                },
+                meta.clone(),
+            ));
+        }
+        if let Some(state) = meta.index_state.as_mut() {
+            state.stale = true;
+            for reason in extra.stale_reasons {
+                if !state.stale_reasons.contains(&reason) {
+                    state.stale_reasons.push(reason);
+                }
+            }
+        }
+    }
+
+    for model_id in &requested {
+        if let Err(err) = engine.engine_mut().ensure_model(model_id).await {
+            return Err(internal_error_with_meta(
+                format!("Error: {err:#}"),
+                meta.clone(),
+            ));
+        }
+    }
+
+    Ok(Some(requested))
+}
+
+/// `search` over several roots: each member is searched on its own index, the rankings are
+/// merged, and result paths carry the member name as a prefix. No cursor; raise `limit` instead.
+async fn search_workspace(
+    service: &ContextFinderService,
+    request: &SearchRequest,
+    members: &[WorkspaceMember],
+    limit: usize,
+) -> CallToolResult {
+    let policy = AutoIndexPolicy::from_request(request.auto_index, request.auto_index_budget_ms);
+    let mut run = WorkspaceRun::default();
+    if request
+        .cursor
+        .as_deref()
+        .is_some_and(|c| !c.trim().is_empty())
+    {
+        return invalid_request_with_meta(
+            "Error: cursor is not supported for multi-root search",
+            run.meta(service),
+            Some("Rerun without cursor and raise limit instead.".to_string()),
+            Vec::new(),
+        );
+    }
+
+    // (member, path relative to the member root, hit)
+    let mut per_member: Vec<Vec<(&WorkspaceMember, String, SearchResult)>> = Vec::new();
+    for member in members {
+        let Some(mut engine) = run.lock_member(service, member, policy).await else {
+            continue;
+        };
+        let hits = engine
+            .engine_mut()
+            .context_search
+            .hybrid_mut()
+            .search(&request.query, limit)
+            .await;
+        drop(engine);
+        match hits {
+            Ok(hits) => per_member.push(
+                hits.into_iter()
+                    .map(|hit| {
+                        let mut hit = format_hit(hit);
+                        let rel = std::mem::take(&mut hit.file);
+                        hit.file = member.prefixed(&rel);
+                        (member, rel, hit)
+                    })
+                    .collect(),
+            ),
+            Err(err) => run.fail_last(format!("Search error: {err}")),
+        }
+    }
+    if let Some(error) = run.unusable_error(service) {
+        return error;
+    }
+
+    let mut merged = merge_member_hits(per_member, |(_, _, hit)| &mut hit.score);
+    merged.truncate(limit);
+
+    let budgets = DefaultBudgets::default();
+    let mut next_actions = vec![ToolNextAction::new(
+        "context_pack",
+        serde_json::json!({
+            "path": request.path,
+            "query": request.query,
+            "workspace": request.workspace,
+            "roots": request.roots,
+            "max_chars": budgets.context_pack_max_chars
+        }),
+        "Build a bounded semantic pack across the same roots.",
+    )];
+    if let Some((member, rel, first)) = merged.first() {
+        next_actions.push(ToolNextAction::new(
+            "read_pack",
+            serde_json::json!({
+                "path": member.display_root(),
+                "file": rel,
+                "start_line": first.start_line,
+                "max_chars": budgets.read_pack_max_chars
+            }),
+            "Open the top hit with a bounded read_pack.",
+        ));
+    }
+
+    let results: Vec<SearchResult> = merged.into_iter().map(|(_, _, hit)| hit).collect();
+    let response = SearchResponse {
+        oldest_result_ms: oldest_result_ms(&results),
+        results,
+        next_cursor: None,
+        next_actions,
+        model_timings: Vec::new(),
+        meta: run.meta(service),
+    };
+    CallToolResult::success(vec![Content::text(
+        context_protocol::serialize_json(&response).unwrap_or_default(),
+    )])
+}
+
 /// Semantic code search
 pub(in crate::tools::dispatch) async fn search(
     service: &ContextFinderService,
@@ -25,6 +368,24 @@ pub(in crate::tools::dispatch) async fn search(
         ));
     }
 
+    match resolve_workspace_scope(
+        service,
+        request.path.as_deref(),
+        request.workspace,
+        request.roots.as_deref(),
+    )
+    .await
+    {
+        Ok(Some(members)) => {
+            return Ok(search_workspace(service, &request, &members, limit).await);
+        }
+        Ok(None) => {}
+        Err(message) => {
+            let meta = meta_for_request(service, request.path.as_deref()).await;
+            return Ok(invalid_request_with_meta(message, meta, None, Vec::new()));
+        }
+    }
+
     let (root, root_display) = match service.resolve_root(request.path.as_deref()).await {
         Ok(value) => value,
         Err(message) => {
@@ -34,17 +395,15 @@ pub(in crate::tools::dispatch) async fn search(
     };
 
     let policy = AutoIndexPolicy::from_request(request.auto_index, request.auto_index_budget_ms);
-    let (mut engine, meta) = match service.prepare_semantic_engine(&root, policy).await {
+    let (mut engine, mut meta) = match service.prepare_semantic_engine(&root, policy).await {
         Ok(engine) => engine,
         Err(e) => {
             let message = format!("Error: {e}");
             let meta = service.tool_meta(&root).await;
-            if message.contains("Index not found")
-                || message.contains("No semantic indices available")
-            {
+            if ErrorCode::from_chain(&e) == Some(ErrorCode::IndexMissing) {
                 return Ok(tool_error_envelope_with_meta(
                     ErrorEnvelope {
-                        code: "index_missing".to_string(),
+                        code: ErrorCode::IndexMissing.to_string(),
                         message,
                         details: None,
                         hint: Some("Index missing — run index (see next_actions).".to_string()),
@@ -57,14 +416,40 @@ pub(in crate::tools::dispatch) async fn search(
         }
     };
 
+    let models = match select_models(
+        service,
+        &mut engine,
+        &root,
+        &root_display,
+        request.models.as_deref(),
+        request.stale_policy.as_deref(),
+        &mut meta,
+    )
+    .await
+    {
+        Ok(models) => models,
+        Err(result) => return Ok(result),
+    };
+
+    let paging = SearchPaging::new(
+        service,
+        "search",
+        &root_display,
+        &request.query,
+        engine.engine_mut().canonical_index_mtime,
+    );
+    let offset = match paging.start_offset(request.cursor.as_deref()) {
+        Ok(offset) => offset,
+        Err(message) => return Ok(invalid_cursor_with_meta(message, meta)),
+    };
+    let fetch = (offset + limit).min(MAX_PAGED_HITS) + 1;
+
     let results = {
-        match engine
-            .engine_mut()
-            .context_search
-            .hybrid_mut()
-            .search(&request.query, limit)
-            .await
-        {
+        let hybrid = engine.engine_mut().context_search.hybrid_mut();
+        hybrid.set_models(models);
+        let outcome = hybrid.search(&request.query, fetch).await;
+        hybrid.set_models(None);
+        match outcome {
             Ok(r) => r,
             Err(e) => {
                 return Ok(internal_error_with_meta(
@@ -75,49 +460,64 @@ pub(in crate::tools::dispatch) async fn search(
         }
     };
 
-    let formatted: Vec<SearchResult> = results
-        .into_iter()
-        .map(|r| {
-            let chunk = r.chunk;
-            SearchResult {
-                file: chunk.file_path,
-                start_line: chunk.start_line,
-                end_line: chunk.end_line,
-                symbol: chunk.metadata.symbol_name,
-                symbol_type: chunk.metadata.chunk_type.map(|ct| ct.as_str().to_string()),
-                score: r.score,
-                content: chunk.content,
-            }
-        })
-        .collect();
+    let model_timings = engine
+        .engine_mut()
+        .context_search
+        .hybrid()
+        .model_timings()
+        .to_vec();
+    drop(engine);
+
+    let (results, next_offset) = take_page(results, offset, limit);
+    let next_cursor = match next_offset.map(|next| paging.encode(next)).transpose() {
+        Ok(cursor) => cursor,
+        Err(err) => return Ok(internal_error_with_meta(format!("Error: {err:#}"), meta)),
+    };
+
+    let formatted: Vec<SearchResult> = results.into_iter().map(format_hit).collect();
 
     let mut next_actions = Vec::new();
+    if let Some(cursor) = next_cursor.clone() {
+        next_actions.push(ToolNextAction::new(
+            "search",
+            serde_json::json!({
+                "path": root_display.clone(),
+                "query": request.query,
+                "limit": limit,
+                "cursor": cursor,
+            }),
+            "Continue search pagination with the next cursor.",
+        ));
+    }
     let budgets = DefaultBudgets::default();
-    next_actions.push(ToolNextAction {
-        tool: "context_pack".to_string(),
-        args: serde_json::json!({
+    next_actions.push(ToolNextAction::new(
+        "context_pack",
+        serde_json::json!({
             "path": root_display.clone(),
             "query": request.query,
             "max_chars": budgets.context_pack_max_chars
         }),
-        reason: "Build a bounded semantic pack for deeper context.".to_string(),
-    });
+        "Build a bounded semantic pack for deeper context.",
+    ));
     if let Some(first) = formatted.first() {
-        next_actions.push(ToolNextAction {
-            tool: "read_pack".to_string(),
-            args: serde_json::json!({
+        next_actions.push(ToolNextAction::new(
+            "read_pack",
+            serde_json::json!({
                 "path": root_display,
                 "file": first.file.clone(),
                 "start_line": first.start_line,
                 "max_chars": budgets.read_pack_max_chars
             }),
-            reason: "Open the top hit with a bounded read_pack.".to_string(),
-        });
+            "Open the top hit with a bounded read_pack.",
+        ));
     }
 
     let response = SearchResponse {
+        oldest_result_ms: oldest_result_ms(&formatted),
         results: formatted,
+        next_cursor,
         next_actions,
+        model_timings,
         meta,
     };
 
@@ -4,12 +4,14 @@ use super::super::{
     TextSearchMatch, TextSearchRequest, TextSearchResult, CURSOR_VERSION,
 };
 use crate::tools::schemas::ToolNextAction;
+use context_search::match_in_line;
 use context_vector_store::ChunkCorpus;
 use serde_json::json;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 const MAX_FILE_BYTES: u64 = 2_000_000;
+const MAX_CONTEXT_LINES: usize = 10;
 
 use super::error::{
     attach_meta, internal_error, internal_error_with_meta, invalid_cursor,
@@ -26,6 +28,7 @@ struct TextSearchSettings<'a> {
     max_results: usize,
     case_sensitive: bool,
     whole_word: bool,
+    context_lines: usize,
 }
 
 struct TextSearchOutcome {
@@ -34,6 +37,8 @@ struct TextSearchOutcome {
     seen: HashSet<TextSearchKey>,
     scanned_files: usize,
     skipped_large_files: usize,
+    ignored_files: usize,
+    unreadable_files: usize,
     truncated: bool,
     next_state: Option<TextSearchCursorModeV1>,
 }
@@ -54,6 +59,8 @@ impl TextSearchOutcome {
             seen: HashSet::new(),
             scanned_files: 0,
             skipped_large_files: 0,
+            ignored_files: 0,
+            unreadable_files: 0,
             truncated: false,
             next_state: None,
         }
@@ -75,6 +82,23 @@ impl TextSearchOutcome {
     }
 }
 
+/// Up to `context_lines` lines on each side of `lines[idx]`.
+fn context_around(lines: &[&str], idx: usize, context_lines: usize) -> (Vec<String>, Vec<String>) {
+    if context_lines == 0 {
+        return (Vec::new(), Vec::new());
+    }
+    let before = lines[idx.saturating_sub(context_lines)..idx]
+        .iter()
+        .map(|line| (*line).to_string())
+        .collect();
+    let after_end = idx.saturating_add(1 + context_lines).min(lines.len());
+    let after = lines[idx + 1..after_end]
+        .iter()
+        .map(|line| (*line).to_string())
+        .collect();
+    (before, after)
+}
+
 fn decode_cursor_mode(
     request: &TextSearchRequest,
     root_display: &str,
@@ -232,7 +256,8 @@ fn search_in_corpus(
                 0
             };
 
-            for (offset, line_text) in chunk.content.lines().enumerate().skip(line_start) {
+            let lines: Vec<&str> = chunk.content.lines().collect();
+            for (offset, line_text) in lines.iter().copied().enumerate().skip(line_start) {
                 if outcome.matches.len() >= settings.max_results {
                     outcome.truncated = true;
                     outcome.next_state = Some(TextSearchCursorModeV1::Corpus {
@@ -243,7 +268,7 @@ fn search_in_corpus(
                     break 'outer_corpus;
                 }
 
-                let Some(col_byte) = ContextFinderService::match_in_line(
+                let Some(col_byte) = match_in_line(
                     line_text,
                     settings.pattern,
                     settings.case_sensitive,
@@ -254,11 +279,14 @@ fn search_in_corpus(
 
                 let line = chunk.start_line + offset;
                 let column = line_text[..col_byte].chars().count() + 1;
+                let (before, after) = context_around(&lines, offset, settings.context_lines);
                 let _ = outcome.push_match(TextSearchMatch {
                     file: chunk.file_path.clone(),
                     line,
                     column,
                     text: line_text.to_string(),
+                    before,
+                    after,
                 });
             }
         }
@@ -275,9 +303,10 @@ fn search_in_filesystem(
 ) -> std::result::Result<TextSearchOutcome, CallToolResult> {
     let mut outcome = TextSearchOutcome::new();
 
-    let scanner = FileScanner::new(root);
-    let mut candidates: Vec<(String, PathBuf)> = scanner
-        .scan()
+    let (files, scan_stats) = FileScanner::new(root).scan_with_stats();
+    outcome.ignored_files = scan_stats.ignored_files;
+    outcome.unreadable_files = scan_stats.unreadable_files;
+    let mut candidates: Vec<(String, PathBuf)> = files
         .into_iter()
         .filter_map(|file| normalize_relative_path(root, &file).map(|rel| (rel, file)))
         .filter(|(rel, _)| ContextFinderService::matches_file_pattern(rel, settings.file_pattern))
@@ -303,6 +332,7 @@ fn search_in_filesystem(
         outcome.scanned_files += 1;
 
         let Ok(meta) = std::fs::metadata(abs_path) else {
+            outcome.unreadable_files += 1;
             continue;
         };
         if meta.len() > MAX_FILE_BYTES {
@@ -311,13 +341,15 @@ fn search_in_filesystem(
         }
 
         let Ok(content) = std::fs::read_to_string(abs_path) else {
+            outcome.unreadable_files += 1;
             continue;
         };
 
         let first_file = file_index == start_file_index;
         let line_start = if first_file { start_line_offset } else { 0 };
 
-        for (offset, line_text) in content.lines().enumerate().skip(line_start) {
+        let lines: Vec<&str> = content.lines().collect();
+        for (offset, line_text) in lines.iter().copied().enumerate().skip(line_start) {
             if outcome.matches.len() >= settings.max_results {
                 outcome.truncated = true;
                 outcome.next_state = Some(TextSearchCursorModeV1::Filesystem {
@@ -327,7 +359,7 @@ fn search_in_filesystem(
                 break 'outer_fs;
             }
 
-            let Some(col_byte) = ContextFinderService::match_in_line(
+            let Some(col_byte) = match_in_line(
                 line_text,
                 settings.pattern,
                 settings.case_sensitive,
@@ -336,11 +368,14 @@ fn search_in_filesystem(
                 continue;
             };
             let column = line_text[..col_byte].chars().count() + 1;
+            let (before, after) = context_around(&lines, offset, settings.context_lines);
             let _ = outcome.push_match(TextSearchMatch {
                 file: rel_path.clone(),
                 line: offset + 1,
                 column,
                 text: line_text.to_string(),
+                before,
+                after,
             });
         }
     }
@@ -376,6 +411,7 @@ pub(in crate::tools::dispatch) async fn text_search(
     let max_results = request.max_results.unwrap_or(50).clamp(1, 1000);
     let case_sensitive = request.case_sensitive.unwrap_or(true);
     let whole_word = request.whole_word.unwrap_or(false);
+    let context_lines = request.context_lines.unwrap_or(0).min(MAX_CONTEXT_LINES);
     let normalized_file_pattern = file_pattern.map(str::to_string);
     let settings = TextSearchSettings {
         pattern,
@@ -383,6 +419,7 @@ pub(in crate::tools::dispatch) async fn text_search(
         max_results,
         case_sensitive,
         whole_word,
+        context_lines,
     };
 
     let cursor_mode = match decode_cursor_mode(
@@ -395,8 +432,8 @@ pub(in crate::tools::dispatch) async fn text_search(
         Err(result) => return Ok(attach_meta(result, meta.clone())),
     };
 
-    let corpus = match ContextFinderService::load_chunk_corpus(&root).await {
-        Ok(corpus) => corpus,
+    let corpus_files = match ContextFinderService::load_corpus_files(&root).await {
+        Ok(files) => files,
         Err(err) => {
             return Ok(internal_error_with_meta(
                 format!("Error: {err:#}"),
@@ -405,22 +442,48 @@ pub(in crate::tools::dispatch) async fn text_search(
         }
     };
 
-    let (source, mut outcome) = if let Some(corpus) = corpus {
+    let (source, mut outcome) = if let Some(mut corpus_files) = corpus_files {
         let (start_file_index, start_chunk_index, start_line_offset) =
             match start_indices_for_corpus(cursor_mode.as_ref()) {
                 Ok(value) => value,
                 Err(result) => return Ok(attach_meta(result, meta.clone())),
             };
-        let outcome = match search_in_corpus(
-            &corpus,
-            &settings,
-            start_file_index,
-            start_chunk_index,
-            start_line_offset,
-        ) {
-            Ok(value) => value,
-            Err(result) => return Ok(attach_meta(result, meta.clone())),
+        // Resolve the cursor against the manifest, then read only the shards holding
+        // matching files at or after it.
+        corpus_files
+            .retain(|file| ContextFinderService::matches_file_pattern(file, settings.file_pattern));
+        if start_file_index > corpus_files.len() {
+            return Ok(attach_meta(
+                invalid_cursor("Invalid cursor: out of range"),
+                meta.clone(),
+            ));
+        }
+        let remaining: HashSet<String> = corpus_files
+            .split_off(start_file_index)
+            .into_iter()
+            .collect();
+        let corpus = match ContextFinderService::load_chunk_corpus_matching(&root, |file| {
+            remaining.contains(file)
+        })
+        .await
+        {
+            Ok(Some(corpus)) => corpus,
+            Ok(None) => ChunkCorpus::new(),
+            Err(err) => {
+                return Ok(internal_error_with_meta(
+                    format!("Error: {err:#}"),
+                    meta.clone(),
+                ))
+            }
         };
+        let mut outcome =
+            match search_in_corpus(&corpus, &settings, 0, start_chunk_index, start_line_offset) {
+                Ok(value) => value,
+                Err(result) => return Ok(attach_meta(result, meta.clone())),
+            };
+        if let Some(TextSearchCursorModeV1::Corpus { file_index, .. }) = &mut outcome.next_state {
+            *file_index += start_file_index;
+        }
         ("corpus".to_string(), outcome)
     } else {
         let (start_file_index, start_line_offset) =
@@ -459,28 +522,31 @@ pub(in crate::tools::dispatch) async fn text_search(
         scanned_files: outcome.scanned_files,
         matched_files: outcome.matched_files.len(),
         skipped_large_files: outcome.skipped_large_files,
+        ignored_files: outcome.ignored_files,
+        unreadable_files: outcome.unreadable_files,
         returned: outcome.matches.len(),
         truncated: outcome.truncated,
         next_cursor,
         next_actions: None,
-        meta: context_indexer::ToolMeta { index_state: None },
+        meta: context_indexer::ToolMeta::default(),
         matches: outcome.matches,
     };
     result.meta = meta.clone();
     if let Some(cursor) = result.next_cursor.clone() {
-        result.next_actions = Some(vec![ToolNextAction {
-            tool: "text_search".to_string(),
-            args: json!({
+        result.next_actions = Some(vec![ToolNextAction::new(
+            "text_search",
+            json!({
                 "path": root_display,
                 "pattern": settings.pattern,
                 "file_pattern": normalized_file_pattern,
                 "max_results": max_results,
                 "case_sensitive": settings.case_sensitive,
                 "whole_word": settings.whole_word,
+                "context_lines": settings.context_lines,
                 "cursor": cursor,
             }),
-            reason: "Continue text_search pagination with the next cursor.".to_string(),
-        }]);
+            "Continue text_search pagination with the next cursor.",
+        )]);
     }
 
     Ok(CallToolResult::success(vec![Content::text(
@@ -490,7 +556,9 @@ pub(in crate::tools::dispatch) async fn text_search(
 
 #[cfg(test)]
 mod tests {
-    use super::{TextSearchMatch, TextSearchOutcome};
+    use super::{search_in_corpus, TextSearchMatch, TextSearchOutcome, TextSearchSettings};
+    use context_code_chunker::{ChunkMetadata, CodeChunk};
+    use context_vector_store::ChunkCorpus;
 
     #[test]
     fn text_search_dedupes_matches() {
@@ -500,6 +568,8 @@ mod tests {
             line: 1,
             column: 1,
             text: "fn main() {}".to_string(),
+            before: Vec::new(),
+            after: Vec::new(),
         };
         assert!(outcome.push_match(first));
 
@@ -508,9 +578,59 @@ mod tests {
             line: 1,
             column: 1,
             text: "fn main() {}".to_string(),
+            before: Vec::new(),
+            after: Vec::new(),
         };
         assert!(!outcome.push_match(dup));
         assert_eq!(outcome.matches.len(), 1);
         assert_eq!(outcome.matched_files.len(), 1);
     }
+
+    #[test]
+    fn corpus_matches_carry_context_from_their_chunk() {
+        let mut corpus = ChunkCorpus::new();
+        corpus.set_file_chunks(
+            "src/lib.rs".to_string(),
+            vec![
+                CodeChunk::new(
+                    "src/lib.rs".to_string(),
+                    10,
+                    14,
+                    "fn a() {\n    let x = 1;\n    target(x);\n    done();\n}".to_string(),
+                    ChunkMetadata::default(),
+                ),
+                CodeChunk::new(
+                    "src/lib.rs".to_string(),
+                    20,
+                    21,
+                    "    target(y);\n}".to_string(),
+                    ChunkMetadata::default(),
+                ),
+            ],
+        );
+        let settings = TextSearchSettings {
+            pattern: "target",
+            file_pattern: None,
+            max_results: 10,
+            case_sensitive: true,
+            whole_word: false,
+            context_lines: 2,
+        };
+
+        let Ok(outcome) = search_in_corpus(&corpus, &settings, 0, 0, 0) else {
+            panic!("search_in_corpus failed");
+        };
+        assert_eq!(outcome.matches.len(), 2);
+
+        let first = &outcome.matches[0];
+        assert_eq!((first.line, first.column), (12, 5));
+        assert_eq!(first.before, vec!["fn a() {", "    let x = 1;"]);
+        assert_eq!(first.after, vec!["    done();", "}"]);
+
+        // Context never crosses into a neighbouring chunk.
+        let second = &outcome.matches[1];
+        assert_eq!((second.line, second.column), (20, 5));
+        assert!(second.before.is_empty());
+        assert_eq!(second.after, vec!["}"]);
+    }
 }
@@ -1,8 +1,15 @@
 use super::super::{
-    AutoIndexPolicy, CallToolResult, Content, ContextFinderService, McpError, TraceRequest,
-    TraceResult, TraceStep,
+    resolve_graph_language, AutoIndexPolicy, CallToolResult, Content, ContextFinderService,
+    McpError, TraceRequest, TraceResult,
 };
-use super::error::{internal_error_with_meta, invalid_request_with_meta, meta_for_request};
+use super::error::{
+    internal_error_with_meta, invalid_request_with_meta, meta_for_request,
+    unsupported_graph_language_with_meta,
+};
+use context_graph::analysis;
+use context_graph::GraphError;
+
+const DEFAULT_MAX_PATHS: usize = 1;
 
 /// Trace call path between two symbols
 pub(in crate::tools::dispatch) async fn trace(
@@ -18,7 +25,7 @@ pub(in crate::tools::dispatch) async fn trace(
     };
 
     let policy = AutoIndexPolicy::from_request(request.auto_index, request.auto_index_budget_ms);
-    let (mut engine, meta) = match service.prepare_semantic_engine(&root, policy).await {
+    let (mut engine, mut meta) = match service.prepare_semantic_engine(&root, policy).await {
         Ok(engine) => engine,
         Err(e) => {
             let meta = service.tool_meta(&root).await;
@@ -26,14 +33,18 @@ pub(in crate::tools::dispatch) async fn trace(
         }
     };
 
-    let language = request.language.as_deref().map_or_else(
-        || {
-            ContextFinderService::detect_language(
-                engine.engine_mut().context_search.hybrid().chunks(),
-            )
-        },
-        |lang| ContextFinderService::parse_language(Some(lang)),
-    );
+    let language = match resolve_graph_language(&mut engine, request.language.as_deref(), &mut meta)
+    {
+        Ok(language) => language,
+        Err(language) => {
+            return Ok(unsupported_graph_language_with_meta(
+                &language,
+                "trace",
+                serde_json::json!({ "path": request.path, "from": request.from, "to": request.to }),
+                meta,
+            ));
+        }
+    };
 
     if let Err(e) = engine.engine_mut().ensure_graph(language).await {
         return Ok(internal_error_with_meta(
@@ -42,81 +53,37 @@ pub(in crate::tools::dispatch) async fn trace(
         ));
     }
 
-    let (found, path_steps, depth) = {
+    let report = {
         let Some(assembler) = engine.engine_mut().context_search.assembler() else {
             return Ok(internal_error_with_meta(
                 "Graph build error: missing assembler after build",
                 meta.clone(),
             ));
         };
-        let graph = assembler.graph();
-
-        // Find both symbols
-        let Some(from_node) = graph.find_node(&request.from) else {
-            return Ok(invalid_request_with_meta(
-                format!("Symbol '{}' not found", request.from),
-                meta.clone(),
-                None,
-                Vec::new(),
-            ));
-        };
+        analysis::trace(
+            assembler.graph(),
+            &request.from,
+            &request.to,
+            request.via.as_deref(),
+            request.max_paths.unwrap_or(DEFAULT_MAX_PATHS),
+        )
+    };
+    drop(engine);
 
-        let Some(to_node) = graph.find_node(&request.to) else {
+    let report = match report {
+        Ok(report) => report,
+        Err(err @ GraphError::SymbolNotFound(_)) => {
             return Ok(invalid_request_with_meta(
-                format!("Symbol '{}' not found", request.to),
-                meta.clone(),
+                err.to_string(),
+                meta,
                 None,
                 Vec::new(),
             ));
-        };
-
-        // Find path
-        let path_with_edges = graph.find_path_with_edges(from_node, to_node);
-
-        path_with_edges.map_or_else(
-            || (false, Vec::new(), 0),
-            |path| {
-                let steps: Vec<TraceStep> = path
-                    .iter()
-                    .map(|(n, rel)| {
-                        let node_data = graph.get_node(*n);
-                        let (symbol, file, line) = node_data.map_or_else(
-                            || (String::new(), String::new(), 0),
-                            |nd| {
-                                (
-                                    nd.symbol.name.clone(),
-                                    nd.symbol.file_path.clone(),
-                                    nd.symbol.start_line,
-                                )
-                            },
-                        );
-                        TraceStep {
-                            symbol,
-                            file,
-                            line,
-                            relationship: rel.map(|r| format!("{r:?}")),
-                        }
-                    })
-                    .collect();
-                let depth = steps.len().saturating_sub(1);
-                (true, steps, depth)
-            },
-        )
-    };
-
-    drop(engine);
-
-    // Generate Mermaid sequence diagram
-    let mermaid = ContextFinderService::generate_trace_mermaid(&path_steps);
-
-    let result = TraceResult {
-        found,
-        path: path_steps,
-        depth,
-        mermaid,
-        meta,
+        }
+        Err(err) => return Ok(internal_error_with_meta(format!("Error: {err}"), meta)),
     };
 
+    let result = TraceResult { report, meta };
     Ok(CallToolResult::success(vec![Content::text(
         context_protocol::serialize_json(&result).unwrap_or_default(),
     )]))
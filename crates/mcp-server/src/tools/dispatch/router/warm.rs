@@ -0,0 +1,287 @@
+use super::super::{
+    canonicalize_root_path, current_model_id, ensure_root_allowed, env_root_override,
+    find_git_root, CallToolResult, Content, ContextFinderService, McpError, PrewarmState,
+    PrewarmStatus, WarmRequest, WarmResult, WarmStage, WarmStageKind,
+};
+use context_graph::GraphLanguage;
+use context_vector_store::EmbeddingProbe;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use super::error::{invalid_request_with_meta, meta_for_request};
+
+/// How often background prewarm re-checks whether tool calls are still running.
+const PREWARM_IDLE_POLL: Duration = Duration::from_millis(50);
+
+fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Load the project's semantic engine into the engine cache (no auto-index).
+async fn warm_engine(service: &ContextFinderService, root: &Path) -> WarmStage {
+    let started = Instant::now();
+    let error = service
+        .lock_engine(root)
+        .await
+        .err()
+        .map(|err| format!("{err:#}"));
+    WarmStage {
+        stage: WarmStageKind::Engine,
+        duration_ms: elapsed_ms(started),
+        cache_hit: None,
+        error,
+    }
+}
+
+/// Load the graph from the on-disk cache, or build and cache it, inside the warm engine.
+async fn warm_graph(
+    service: &ContextFinderService,
+    root: &Path,
+    language: GraphLanguage,
+) -> WarmStage {
+    let started = Instant::now();
+    let mut cache_hit = None;
+    let error = match service.lock_engine(root).await {
+        Ok(mut engine) => {
+            let engine = engine.engine_mut();
+            cache_hit = Some(
+                engine.graph_language == Some(language)
+                    && engine.context_search.assembler().is_some(),
+            );
+            engine.ensure_graph(language).await.err()
+        }
+        Err(err) => Some(err),
+    };
+    WarmStage {
+        stage: WarmStageKind::Graph,
+        duration_ms: elapsed_ms(started),
+        cache_hit,
+        error: error.map(|err| format!("{err:#}")),
+    }
+}
+
+async fn warm_embedder() -> (WarmStage, Option<EmbeddingProbe>) {
+    let started = Instant::now();
+    let probe = match current_model_id() {
+        Ok(model_id) => context_vector_store::probe_embedding(&model_id).await,
+        Err(err) => Err(err),
+    };
+    let stage = WarmStage {
+        stage: WarmStageKind::Embedder,
+        duration_ms: elapsed_ms(started),
+        cache_hit: None,
+        error: probe.as_ref().err().map(ToString::to_string),
+    };
+    (stage, probe.ok())
+}
+
+/// Pay index load, graph build and embedder start-up ahead of the first real query
+pub(in crate::tools::dispatch) async fn warm(
+    service: &ContextFinderService,
+    request: WarmRequest,
+) -> Result<CallToolResult, McpError> {
+    let (root, root_display) = match service.resolve_root(request.path.as_deref()).await {
+        Ok(value) => value,
+        Err(message) => {
+            let meta = meta_for_request(service, request.path.as_deref()).await;
+            return Ok(invalid_request_with_meta(message, meta, None, Vec::new()));
+        }
+    };
+
+    let started = Instant::now();
+    let mut stages = vec![warm_engine(service, &root).await];
+    if let Some(language) = request.language.as_deref() {
+        let language = ContextFinderService::parse_language(Some(language));
+        stages.push(warm_graph(service, &root, language).await);
+    }
+    let mut embedding_probe = None;
+    if request.embedder.unwrap_or(false) {
+        let (stage, probe) = warm_embedder().await;
+        stages.push(stage);
+        embedding_probe = probe;
+    }
+
+    let result = WarmResult {
+        root: root_display,
+        stages,
+        total_ms: elapsed_ms(started),
+        embedding_probe,
+        meta: service.tool_meta(&root).await,
+    };
+    Ok(CallToolResult::success(vec![Content::text(
+        context_protocol::serialize_json(&result).unwrap_or_default(),
+    )]))
+}
+
+/// Startup prewarm settings from `.context-finder/config.json` (`warm` section).
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(in crate::tools::dispatch) struct PrewarmConfig {
+    /// `warm.on_start`; relative entries are resolved against the config's project root
+    pub(in crate::tools::dispatch) on_start: Vec<PathBuf>,
+    /// `warm.language`: also load or build this graph for each project
+    pub(in crate::tools::dispatch) language: Option<GraphLanguage>,
+    /// `warm.embedder`: also initialize the embedding session
+    pub(in crate::tools::dispatch) embedder: bool,
+}
+
+pub(in crate::tools::dispatch) fn parse_prewarm_config(
+    root: &Path,
+    value: &serde_json::Value,
+) -> PrewarmConfig {
+    let on_start = value
+        .pointer("/warm/on_start")
+        .and_then(serde_json::Value::as_array)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(serde_json::Value::as_str)
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| root.join(entry))
+                .collect()
+        })
+        .unwrap_or_default();
+    PrewarmConfig {
+        on_start,
+        language: value
+            .pointer("/warm/language")
+            .and_then(serde_json::Value::as_str)
+            .map(|language| ContextFinderService::parse_language(Some(language))),
+        embedder: value
+            .pointer("/warm/embedder")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false),
+    }
+}
+
+/// The project the server was launched for: `CONTEXT_FINDER_ROOT`/`CONTEXT_FINDER_PROJECT_ROOT`,
+/// else the git root of the working directory, else the working directory.
+fn launch_root() -> Option<PathBuf> {
+    if let Some((_, value)) = env_root_override() {
+        return Some(PathBuf::from(value));
+    }
+    let cwd = std::env::current_dir().ok()?;
+    Some(find_git_root(&cwd).unwrap_or(cwd))
+}
+
+async fn load_prewarm_config() -> PrewarmConfig {
+    let Some(root) = launch_root() else {
+        return PrewarmConfig::default();
+    };
+    let path = root.join(".context-finder").join("config.json");
+    let Ok(bytes) = tokio::fs::read(&path).await else {
+        return PrewarmConfig::default();
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(value) => parse_prewarm_config(&root, &value),
+        Err(err) => {
+            log::debug!("Ignoring unparsable config {}: {err}", path.display());
+            PrewarmConfig::default()
+        }
+    }
+}
+
+/// Wait until no tool call is running; returns how long that took.
+async fn wait_until_idle(service: &ContextFinderService) -> u64 {
+    let started = Instant::now();
+    while service.state.active_calls.load(Ordering::SeqCst) > 0 {
+        tokio::time::sleep(PREWARM_IDLE_POLL).await;
+    }
+    elapsed_ms(started)
+}
+
+async fn update_status(
+    service: &ContextFinderService,
+    idx: usize,
+    update: impl FnOnce(&mut PrewarmStatus),
+) {
+    if let Some(status) = service.state.prewarm.lock().await.get_mut(idx) {
+        update(status);
+    }
+}
+
+/// Prewarm the `warm.on_start` projects one stage at a time. Before each stage the task waits
+/// until no tool call is running, so it only uses idle time; a call that arrives mid-stage
+/// waits at most for that stage, and only when it needs the same project's engine.
+pub(in crate::tools::dispatch) async fn run_startup_prewarm(service: ContextFinderService) {
+    let config = load_prewarm_config().await;
+    if config.on_start.is_empty() {
+        return;
+    }
+
+    let roots: Vec<Result<PathBuf, String>> = config
+        .on_start
+        .iter()
+        .map(|path| {
+            let root = canonicalize_root_path(path)
+                .map_err(|err| format!("Invalid path {}: {err}", path.display()))?;
+            ensure_root_allowed(&root)?;
+            Ok(root)
+        })
+        .collect();
+    *service.state.prewarm.lock().await = config
+        .on_start
+        .iter()
+        .zip(&roots)
+        .map(|(path, root)| PrewarmStatus {
+            root: root.as_ref().unwrap_or(path).to_string_lossy().into_owned(),
+            state: if root.is_ok() {
+                PrewarmState::Pending
+            } else {
+                PrewarmState::Failed
+            },
+            stages: Vec::new(),
+            yielded_ms: 0,
+            error: root.as_ref().err().cloned(),
+        })
+        .collect();
+
+    for (idx, root) in roots.into_iter().enumerate() {
+        let Ok(root) = root else {
+            continue;
+        };
+        log::info!("Prewarming {}", root.display());
+
+        let mut yielded_ms = wait_until_idle(&service).await;
+        update_status(&service, idx, |status| {
+            status.state = PrewarmState::Running;
+            status.yielded_ms = yielded_ms;
+        })
+        .await;
+        let engine = warm_engine(&service, &root).await;
+        let engine_failed = engine.error.clone();
+        update_status(&service, idx, |status| status.stages.push(engine)).await;
+
+        if engine_failed.is_none() {
+            if let Some(language) = config.language {
+                yielded_ms += wait_until_idle(&service).await;
+                let graph = warm_graph(&service, &root, language).await;
+                update_status(&service, idx, |status| {
+                    status.yielded_ms = yielded_ms;
+                    status.stages.push(graph);
+                })
+                .await;
+            }
+        }
+        if config.embedder {
+            yielded_ms += wait_until_idle(&service).await;
+            let (embedder, _) = warm_embedder().await;
+            update_status(&service, idx, |status| {
+                status.yielded_ms = yielded_ms;
+                status.stages.push(embedder);
+            })
+            .await;
+        }
+
+        update_status(&service, idx, |status| {
+            status.error = status.stages.iter().find_map(|stage| stage.error.clone());
+            status.state = if status.error.is_some() {
+                PrewarmState::Failed
+            } else {
+                PrewarmState::Done
+            };
+        })
+        .await;
+    }
+}
@@ -0,0 +1,283 @@
+use super::super::{
+    current_model_id, unix_ms, CallToolResult, Content, ContextFinderService, McpError, QueryKind,
+    WatchAction, WatchConfig, WatchHealth, WatchRequest, WatchResult,
+};
+use context_indexer::{
+    read_health_snapshot, IndexerHealth, ModelIndexSpec, MultiModelProjectIndexer,
+    MultiModelStreamingIndexer, StreamingIndexerConfig, WatcherState,
+};
+use context_protocol::ToolNextAction;
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::error::{internal_error_with_meta, invalid_request_with_meta, meta_for_request};
+
+/// A streaming watcher started by the `watch` tool. Dropping it shuts the watcher down.
+pub(in crate::tools::dispatch) struct ActiveWatcher {
+    indexer: MultiModelStreamingIndexer,
+    config: WatchConfig,
+}
+
+impl ActiveWatcher {
+    pub(in crate::tools::dispatch) fn state(&self) -> WatcherState {
+        let health = self.indexer.health_snapshot();
+        WatcherState {
+            active: true,
+            indexing: health.indexing,
+            pending_events: health.pending_events,
+            last_success_unix_ms: health.last_success.map(unix_ms),
+            last_error: health.last_error,
+        }
+    }
+
+    fn health(&self) -> WatchHealth {
+        watch_health(self.indexer.health_snapshot())
+    }
+}
+
+fn watch_health(health: IndexerHealth) -> WatchHealth {
+    WatchHealth {
+        indexing: health.indexing,
+        pending_events: health.pending_events,
+        adaptive_debounce_ms: health.adaptive_debounce_ms,
+        consecutive_failures: health.consecutive_failures,
+        last_success_unix_ms: health.last_success.map(unix_ms),
+        last_error: health.last_error,
+        last_duration_ms: health.last_duration_ms,
+        p95_duration_ms: health.p95_duration_ms,
+        last_throughput_files_per_sec: health.last_throughput_files_per_sec,
+        last_index_size_bytes: health.last_index_size_bytes,
+    }
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Read watcher timings from `.context-finder/config.json` (`watch.debounce_ms`,
+/// `watch.max_batch_wait_ms`, `watch.poll_interval_ms`, `watch.max_debounce_ms`); missing or
+/// invalid keys keep defaults.
+async fn load_watch_config(root: &Path) -> StreamingIndexerConfig {
+    let mut config = StreamingIndexerConfig::default();
+    let path = root.join(".context-finder").join("config.json");
+    let Ok(bytes) = tokio::fs::read(&path).await else {
+        return config;
+    };
+    let value: serde_json::Value = match serde_json::from_slice(&bytes) {
+        Ok(value) => value,
+        Err(err) => {
+            log::debug!("Ignoring unparsable config {}: {err}", path.display());
+            return config;
+        }
+    };
+    let millis = |key: &str| {
+        value
+            .pointer(&format!("/watch/{key}"))
+            .and_then(serde_json::Value::as_u64)
+            .filter(|ms| *ms > 0)
+            .map(Duration::from_millis)
+    };
+    if let Some(debounce) = millis("debounce_ms") {
+        config.debounce = debounce;
+    }
+    if let Some(max_batch_wait) = millis("max_batch_wait_ms") {
+        config.max_batch_wait = max_batch_wait;
+    }
+    if let Some(poll_interval) = millis("poll_interval_ms") {
+        config.notify_poll_interval = poll_interval;
+    }
+    if let Some(max_debounce) = millis("max_debounce_ms") {
+        config.max_debounce = max_debounce;
+    }
+    config
+}
+
+fn watch_models(service: &ContextFinderService, experts: bool) -> Vec<String> {
+    let primary_model_id = current_model_id().unwrap_or_else(|_| "bge-small".to_string());
+    let mut models = vec![primary_model_id.clone()];
+    if experts {
+        let mut seen = HashSet::from([primary_model_id]);
+        let expert_cfg = service.profile.experts();
+        for kind in [
+            QueryKind::Identifier,
+            QueryKind::Path,
+            QueryKind::Conceptual,
+        ] {
+            for model_id in expert_cfg.semantic_models(kind) {
+                if seen.insert(model_id.clone()) {
+                    models.push(model_id.clone());
+                }
+            }
+        }
+    }
+    models
+}
+
+fn status_action(root_display: &str) -> ToolNextAction {
+    ToolNextAction::new(
+        "watch",
+        json!({ "path": root_display, "action": "status" }),
+        "Inspect the running watcher instead of starting a second one.",
+    )
+}
+
+fn start_action(root_display: &str) -> ToolNextAction {
+    ToolNextAction::new(
+        "watch",
+        json!({ "path": root_display, "action": "start" }),
+        "Start a watcher so the index follows file changes.",
+    )
+}
+
+/// Start, stop, inspect or trigger the streaming indexer for a project
+pub(in crate::tools::dispatch) async fn watch(
+    service: &ContextFinderService,
+    request: WatchRequest,
+) -> Result<CallToolResult, McpError> {
+    let (root, root_display) = match service.resolve_root(request.path.as_deref()).await {
+        Ok(value) => value,
+        Err(message) => {
+            let meta = meta_for_request(service, request.path.as_deref()).await;
+            return Ok(invalid_request_with_meta(message, meta, None, Vec::new()));
+        }
+    };
+
+    let mut result = WatchResult {
+        root: root_display.clone(),
+        action: request.action,
+        active: false,
+        stopped: None,
+        config: None,
+        health: None,
+        persisted_health: None,
+        next_actions: Vec::new(),
+        meta: Default::default(),
+    };
+
+    match request.action {
+        WatchAction::Start => {
+            // Hold the registry lock across startup so two concurrent starts cannot both win.
+            let mut watchers = service.state.watchers.lock().await;
+            if watchers.contains_key(&root) {
+                drop(watchers);
+                let meta = service.tool_meta(&root).await;
+                return Ok(invalid_request_with_meta(
+                    format!("A watcher is already running for {root_display}"),
+                    meta,
+                    Some("Use action=status to inspect it or action=stop to restart it.".into()),
+                    vec![status_action(&root_display)],
+                ));
+            }
+
+            let streaming_config = load_watch_config(&root).await;
+            let models = watch_models(service, request.experts.unwrap_or(false));
+            let templates = service.profile.embedding().clone();
+            let specs: Vec<ModelIndexSpec> = models
+                .iter()
+                .map(|model_id| ModelIndexSpec::new(model_id.clone(), templates.clone()))
+                .collect();
+
+            let started = match MultiModelProjectIndexer::new(&root).await {
+                Ok(indexer) => MultiModelStreamingIndexer::start(
+                    Arc::new(indexer.with_profile(service.profile.name())),
+                    specs,
+                    streaming_config.clone(),
+                ),
+                Err(err) => Err(err),
+            };
+            let indexer = match started {
+                Ok(indexer) => indexer,
+                Err(err) => {
+                    drop(watchers);
+                    let meta = service.tool_meta(&root).await;
+                    return Ok(internal_error_with_meta(
+                        format!("Watcher start error: {err}"),
+                        meta,
+                    ));
+                }
+            };
+            if let Err(err) = indexer.trigger("watch_start").await {
+                log::debug!("initial watcher trigger failed: {err:#}");
+            }
+
+            let active = ActiveWatcher {
+                indexer,
+                config: WatchConfig {
+                    debounce_ms: duration_ms(streaming_config.debounce),
+                    max_batch_wait_ms: duration_ms(streaming_config.max_batch_wait),
+                    poll_interval_ms: duration_ms(streaming_config.notify_poll_interval),
+                    max_debounce_ms: duration_ms(streaming_config.max_debounce),
+                    models,
+                },
+            };
+            result.active = true;
+            result.config = Some(active.config.clone());
+            result.health = Some(active.health());
+            watchers.insert(root.clone(), active);
+        }
+        WatchAction::Stop => {
+            let removed = service.state.watchers.lock().await.remove(&root);
+            result.stopped = Some(removed.is_some());
+        }
+        WatchAction::Status => {
+            let watchers = service.state.watchers.lock().await;
+            if let Some(active) = watchers.get(&root) {
+                result.active = true;
+                result.config = Some(active.config.clone());
+                result.health = Some(active.health());
+            }
+        }
+        WatchAction::Trigger => {
+            let reason = request
+                .reason
+                .as_deref()
+                .map(str::trim)
+                .filter(|reason| !reason.is_empty())
+                .unwrap_or("manual")
+                .to_string();
+            let indexer = service
+                .state
+                .watchers
+                .lock()
+                .await
+                .get(&root)
+                .map(|active| active.indexer.clone());
+            let Some(indexer) = indexer else {
+                let meta = service.tool_meta(&root).await;
+                return Ok(invalid_request_with_meta(
+                    format!("No watcher is running for {root_display}"),
+                    meta,
+                    Some(
+                        "Start one with action=start, or run `index` for a one-off refresh.".into(),
+                    ),
+                    vec![start_action(&root_display)],
+                ));
+            };
+            if let Err(err) = indexer.trigger(reason).await {
+                let meta = service.tool_meta(&root).await;
+                return Ok(internal_error_with_meta(
+                    format!("Watcher trigger error: {err}"),
+                    meta,
+                ));
+            }
+            result.active = true;
+            result.health = Some(watch_health(indexer.health_snapshot()));
+        }
+    }
+
+    match read_health_snapshot(&root).await {
+        Ok(snapshot) => result.persisted_health = snapshot,
+        Err(err) => log::debug!("health snapshot unavailable for {root_display}: {err:#}"),
+    }
+    if !result.active {
+        result.next_actions.push(start_action(&root_display));
+    }
+    result.meta = service.tool_meta(&root).await;
+
+    Ok(CallToolResult::success(vec![Content::text(
+        context_protocol::serialize_json(&result).unwrap_or_default(),
+    )]))
+}
@@ -0,0 +1,164 @@
+use super::super::{
+    ensure_root_allowed, load_workspace_members, select_workspace_members, AutoIndexPolicy,
+    CallToolResult, ContextFinderService, EngineLock, WorkspaceMember,
+};
+use context_indexer::{ToolMeta, WorkspaceRootState};
+use context_protocol::{ErrorCode, ErrorEnvelope, ToolNextAction};
+use serde_json::json;
+
+use super::error::tool_error_envelope_with_meta;
+
+/// Members a `workspace`/`roots` request covers, or `None` for an ordinary single-root call.
+/// `path` selects the workspace root whose manifest is read and against which `roots` resolve.
+pub(super) async fn resolve_workspace_scope(
+    service: &ContextFinderService,
+    path: Option<&str>,
+    workspace: Option<bool>,
+    roots: Option<&[String]>,
+) -> Result<Option<Vec<WorkspaceMember>>, String> {
+    let requested = roots.unwrap_or_default();
+    if !workspace.unwrap_or(false) && requested.is_empty() {
+        return Ok(None);
+    }
+
+    let (root, _) = service.resolve_root(path).await?;
+    let manifest = load_workspace_members(&root).map_err(|err| format!("Error: {err:#}"))?;
+    let members = select_workspace_members(&root, manifest, requested)
+        .map_err(|err| format!("Error: {err:#}"))?;
+    for member in &members {
+        ensure_root_allowed(&member.root)?;
+    }
+    Ok(Some(members))
+}
+
+/// Per-member index states gathered while one request fans out over a workspace.
+#[derive(Default)]
+pub(super) struct WorkspaceRun {
+    states: Vec<WorkspaceRootState>,
+}
+
+impl WorkspaceRun {
+    /// Lock `member`'s engine (auto-indexing it under `policy`), or record why it is left out.
+    pub(super) async fn lock_member(
+        &mut self,
+        service: &ContextFinderService,
+        member: &WorkspaceMember,
+        policy: AutoIndexPolicy,
+    ) -> Option<EngineLock> {
+        match service.prepare_semantic_engine(&member.root, policy).await {
+            Ok((engine, meta)) => {
+                self.states.push(WorkspaceRootState {
+                    name: member.name.clone(),
+                    root: member.display_root(),
+                    index_state: meta.index_state,
+                    error: None,
+                });
+                Some(engine)
+            }
+            Err(err) => {
+                let meta = service.tool_meta(&member.root).await;
+                self.states.push(WorkspaceRootState {
+                    name: member.name.clone(),
+                    root: member.display_root(),
+                    index_state: meta.index_state,
+                    error: Some(format!("{err:#}")),
+                });
+                None
+            }
+        }
+    }
+
+    /// Marks the most recently locked member as failed (its query errored after locking).
+    pub(super) fn fail_last(&mut self, error: String) {
+        if let Some(state) = self.states.last_mut() {
+            state.error = Some(error);
+        }
+    }
+
+    pub(super) fn meta(&self, service: &ContextFinderService) -> ToolMeta {
+        ToolMeta {
+            tool_stats: service.debug_tool_stats(),
+            workspace: self.states.clone(),
+            ..ToolMeta::default()
+        }
+    }
+
+    /// `index_missing` error when no member produced results; `None` if at least one did.
+    pub(super) fn unusable_error(&self, service: &ContextFinderService) -> Option<CallToolResult> {
+        if self.states.iter().any(|state| state.error.is_none()) {
+            return None;
+        }
+        let next_actions = self
+            .states
+            .iter()
+            .map(|state| {
+                ToolNextAction::new(
+                    "index",
+                    json!({ "path": state.root }),
+                    format!(
+                        "Build the semantic index for workspace root '{}'.",
+                        state.name
+                    ),
+                )
+            })
+            .collect();
+        Some(tool_error_envelope_with_meta(
+            ErrorEnvelope {
+                code: ErrorCode::IndexMissing.to_string(),
+                message: "Error: no workspace root has a usable index".to_string(),
+                details: None,
+                hint: Some("See meta.workspace for each root's index state.".to_string()),
+                next_actions,
+            },
+            self.meta(service),
+        ))
+    }
+}
+
+/// Merge per-member ranked hits into one ranking. Each member's scores are first divided by
+/// its best score, so members indexed with different score ranges compete on the same 0-1
+/// scale; ties keep member order.
+pub(super) fn merge_member_hits<T>(
+    per_member: Vec<Vec<T>>,
+    score: fn(&mut T) -> &mut f32,
+) -> Vec<T> {
+    let mut merged: Vec<(f32, T)> = Vec::new();
+    for mut hits in per_member {
+        let best = hits
+            .iter_mut()
+            .map(|hit| *score(hit))
+            .fold(f32::NEG_INFINITY, f32::max);
+        let scale = if best.is_finite() && best > 0.0 {
+            best
+        } else {
+            1.0
+        };
+        merged.extend(hits.into_iter().map(|mut hit| {
+            let normalized = *score(&mut hit) / scale;
+            *score(&mut hit) = normalized;
+            (normalized, hit)
+        }));
+    }
+    merged.sort_by(|a, b| b.0.total_cmp(&a.0));
+    merged.into_iter().map(|(_, hit)| hit).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn member_scores_are_scaled_before_merging() {
+        let merged = merge_member_hits(
+            vec![
+                vec![("a1", 0.4), ("a2", 0.2)],
+                vec![("b1", 0.9), ("b2", 0.6)],
+            ],
+            |hit| &mut hit.1,
+        );
+        let order: Vec<&str> = merged.iter().map(|hit| hit.0).collect();
+        assert_eq!(order, vec!["a1", "b1", "b2", "a2"]);
+        assert!((merged[0].1 - 1.0).abs() < f32::EPSILON);
+        assert!((merged[3].1 - 0.5).abs() < f32::EPSILON);
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{Context as AnyhowContext, Result};
+use context_code_chunker::{Chunker, ChunkerConfig};
 use context_indexer::ToolMeta;
 use sha2::{Digest, Sha256};
 use std::io::{BufRead, BufReader, Seek};
@@ -15,6 +16,32 @@ const DEFAULT_MAX_LINES: usize = 200;
 const MAX_MAX_LINES: usize = 5_000;
 const DEFAULT_MAX_CHARS: usize = 20_000;
 const MAX_MAX_CHARS: usize = 500_000;
+pub(super) const MAX_SYMBOL_FILE_BYTES: u64 = 2_000_000;
+const MAX_LISTED_SYMBOLS: usize = 20;
+
+/// One chunk per definition: no overlap, no size-driven splitting of long bodies.
+pub(super) const fn chunker_config_for_symbols() -> ChunkerConfig {
+    ChunkerConfig {
+        strategy: context_code_chunker::ChunkingStrategy::Semantic,
+        overlap: context_code_chunker::OverlapStrategy::None,
+        target_chunk_tokens: 1 << 20,
+        max_chunk_tokens: 1 << 20,
+        min_chunk_tokens: 0,
+        min_chunk_chars: 0,
+        max_chunk_chars: None,
+        overlap_chars: 0,
+        max_lines: None,
+        overlap_lines: 0,
+        min_chunk_lines: 0,
+        languages: std::collections::BTreeMap::new(),
+        include_imports: false,
+        // Needed for qualified names such as `Parser::parse`.
+        include_parent_context: true,
+        include_documentation: false,
+        max_imports_per_chunk: 0,
+        supported_languages: Vec::new(),
+    }
+}
 
 struct CursorValidation<'a> {
     root_display: &'a str,
@@ -42,14 +69,15 @@ fn decode_resume_cursor(
     request: &FileSliceRequest,
     validation: &CursorValidation<'_>,
     start_line: usize,
-) -> std::result::Result<(bool, usize, u64), String> {
+    end_line: Option<usize>,
+) -> std::result::Result<(bool, usize, u64, Option<usize>), String> {
     let Some(cursor) = request
         .cursor
         .as_deref()
         .map(str::trim)
         .filter(|s| !s.is_empty())
     else {
-        return Ok((false, start_line, 0));
+        return Ok((false, start_line, 0, end_line));
     };
 
     let decoded: FileSliceCursorV1 =
@@ -88,6 +116,7 @@ fn decode_resume_cursor(
         true,
         decoded.next_start_line.max(1),
         decoded.next_byte_offset,
+        decoded.end_line,
     ))
 }
 
@@ -95,6 +124,7 @@ fn encode_next_cursor(
     validation: &CursorValidation<'_>,
     next_start_line: usize,
     next_byte_offset: u64,
+    end_line: Option<usize>,
 ) -> std::result::Result<String, String> {
     let token = FileSliceCursorV1 {
         v: CURSOR_VERSION,
@@ -105,6 +135,7 @@ fn encode_next_cursor(
         max_chars: validation.max_chars,
         next_start_line,
         next_byte_offset,
+        end_line,
         file_size_bytes: validation.file_size_bytes,
         file_mtime_ms: validation.file_mtime_ms,
     };
@@ -112,11 +143,119 @@ fn encode_next_cursor(
     encode_cursor(&token).map_err(|err| format!("Error: {err:#}"))
 }
 
+struct SymbolQuery<'a> {
+    name: &'a str,
+    occurrence: Option<usize>,
+    context_lines: usize,
+}
+
+/// Resolves `symbol` to the 1-based inclusive line range of its definition (plus
+/// `context_lines` on each side), chunking the file the same way the index does.
+fn resolve_symbol_lines(
+    canonical_file: &Path,
+    display_file: &str,
+    file_size_bytes: u64,
+    query: &SymbolQuery<'_>,
+) -> std::result::Result<(usize, usize), String> {
+    if file_size_bytes > MAX_SYMBOL_FILE_BYTES {
+        return Err(format!(
+            "File '{display_file}' is too large to resolve symbols ({file_size_bytes} bytes); use start_line instead"
+        ));
+    }
+    let content = std::fs::read_to_string(canonical_file)
+        .map_err(|e| format!("Failed to read '{display_file}': {e}"))?;
+    let chunks = Chunker::new(chunker_config_for_symbols())
+        .chunk_str(&content, Some(display_file))
+        .map_err(|e| format!("Failed to parse '{display_file}' for symbols: {e}"))?;
+
+    // Definitions matching by short or qualified name; adjacent parts of one definition merge.
+    let mut matches: Vec<(String, usize, usize)> = Vec::new();
+    for chunk in &chunks {
+        let meta = &chunk.metadata;
+        let matched = [meta.symbol_name.as_deref(), meta.qualified_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .any(|name| name == query.name);
+        if !matched {
+            continue;
+        }
+        let label = meta
+            .qualified_name
+            .clone()
+            .or_else(|| meta.symbol_name.clone())
+            .unwrap_or_default();
+        match matches.last_mut() {
+            Some((prev, _, end)) if *prev == label && chunk.start_line <= *end + 1 => {
+                *end = (*end).max(chunk.end_line);
+            }
+            _ => matches.push((label, chunk.start_line, chunk.end_line)),
+        }
+    }
+
+    let (start, end) = match (matches.len(), query.occurrence) {
+        (0, _) => {
+            let mut available: Vec<&str> = chunks
+                .iter()
+                .filter_map(|c| c.metadata.symbol_name.as_deref())
+                .collect();
+            available.dedup();
+            let listed = available
+                .iter()
+                .take(MAX_LISTED_SYMBOLS)
+                .copied()
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(if available.is_empty() {
+                format!(
+                    "Symbol '{}' not found in '{display_file}' (no symbols detected)",
+                    query.name
+                )
+            } else {
+                format!(
+                    "Symbol '{}' not found in '{display_file}'; available: {listed}",
+                    query.name
+                )
+            });
+        }
+        (1, None | Some(1)) => (matches[0].1, matches[0].2),
+        (n, Some(occurrence)) if (1..=n).contains(&occurrence) => {
+            (matches[occurrence - 1].1, matches[occurrence - 1].2)
+        }
+        (n, occurrence) => {
+            let candidates = matches
+                .iter()
+                .enumerate()
+                .map(|(idx, (label, start, end))| {
+                    format!("#{} {label} (lines {start}-{end})", idx + 1)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(match occurrence {
+                None => format!(
+                    "Symbol '{}' is ambiguous in '{display_file}' ({n} matches: {candidates}); pass occurrence to pick one",
+                    query.name
+                ),
+                Some(occurrence) => format!(
+                    "Symbol '{}' occurrence {occurrence} is out of range in '{display_file}' ({n} matches: {candidates})",
+                    query.name
+                ),
+            });
+        }
+    };
+
+    Ok((
+        start.saturating_sub(query.context_lines).max(1),
+        end.saturating_add(query.context_lines),
+    ))
+}
+
 struct ReadSliceConfig<'a> {
     canonical_file: &'a Path,
     display_file: &'a str,
     start_line: usize,
     start_byte_offset: u64,
+    /// Last line to include (symbol slices); stopping there is not a truncation
+    end_line: Option<usize>,
     using_cursor: bool,
     max_lines: usize,
     max_chars: usize,
@@ -171,6 +310,9 @@ fn read_file_slice(cfg: &ReadSliceConfig<'_>) -> std::result::Result<ReadSliceOu
             line_no = line_no.saturating_add(1);
             continue;
         }
+        if cfg.end_line.is_some_and(|last| line_no > last) {
+            break;
+        }
 
         if returned_lines >= cfg.max_lines {
             truncated = true;
@@ -179,6 +321,7 @@ fn read_file_slice(cfg: &ReadSliceConfig<'_>) -> std::result::Result<ReadSliceOu
                 cfg.cursor_validation,
                 line_no,
                 pos_before_read,
+                cfg.end_line,
             )?);
             break;
         }
@@ -196,6 +339,7 @@ fn read_file_slice(cfg: &ReadSliceConfig<'_>) -> std::result::Result<ReadSliceOu
                 cfg.cursor_validation,
                 line_no,
                 pos_before_read,
+                cfg.end_line,
             )?);
             break;
         }
@@ -261,7 +405,31 @@ pub(super) fn compute_file_slice_result(
         .unwrap_or(DEFAULT_MAX_CHARS)
         .clamp(1, MAX_MAX_CHARS);
 
-    let start_line = request.start_line.unwrap_or(1).max(1);
+    let mut start_line = request.start_line.unwrap_or(1).max(1);
+    let mut end_line = None;
+    let symbol = request
+        .symbol
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let has_cursor = request
+        .cursor
+        .as_deref()
+        .is_some_and(|c| !c.trim().is_empty());
+    if let Some(name) = symbol.filter(|_| !has_cursor) {
+        if request.start_line.is_some() {
+            return Err("Pass either start_line or symbol, not both".to_string());
+        }
+        let query = SymbolQuery {
+            name,
+            occurrence: request.occurrence,
+            context_lines: request.context_lines.unwrap_or(0),
+        };
+        let (first, last) =
+            resolve_symbol_lines(&canonical_file, &display_file, file_size_bytes, &query)?;
+        start_line = first;
+        end_line = Some(last);
+    }
     let validation = CursorValidation {
         root_display,
         display_file: &display_file,
@@ -270,14 +438,15 @@ pub(super) fn compute_file_slice_result(
         file_size_bytes,
         file_mtime_ms,
     };
-    let (using_cursor, start_line, start_byte_offset) =
-        decode_resume_cursor(request, &validation, start_line)?;
+    let (using_cursor, start_line, start_byte_offset, end_line) =
+        decode_resume_cursor(request, &validation, start_line, end_line)?;
 
     let read_cfg = ReadSliceConfig {
         canonical_file: &canonical_file,
         display_file: &display_file,
         start_line,
         start_byte_offset,
+        end_line,
         using_cursor,
         max_lines,
         max_chars,
@@ -301,7 +470,7 @@ pub(super) fn compute_file_slice_result(
         truncation: read.truncation,
         next_cursor: read.next_cursor,
         next_actions: None,
-        meta: ToolMeta { index_state: None },
+        meta: ToolMeta::default(),
         file_size_bytes,
         file_mtime_ms,
         content_sha256,
@@ -403,10 +572,111 @@ pub(super) fn compute_onboarding_doc_slice(
         truncation,
         next_cursor: None,
         next_actions: None,
-        meta: ToolMeta { index_state: None },
+        meta: ToolMeta::default(),
         file_size_bytes,
         file_mtime_ms,
         content_sha256,
         content,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "\
+use std::fmt;
+
+/// Parses input.
+pub fn parse(input: &str) -> usize {
+    input.len()
+}
+
+pub struct Parser;
+
+impl Parser {
+    pub fn parse(&self, input: &str) -> usize {
+        input.len() + 1
+    }
+}
+";
+
+    fn request(symbol: &str) -> FileSliceRequest {
+        FileSliceRequest {
+            path: None,
+            file: "src/lib.rs".to_string(),
+            start_line: None,
+            symbol: Some(symbol.to_string()),
+            occurrence: None,
+            context_lines: None,
+            max_lines: None,
+            max_chars: None,
+            cursor: None,
+        }
+    }
+
+    fn project() -> (tempfile::TempDir, PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), SOURCE).unwrap();
+        (tmp, root)
+    }
+
+    #[test]
+    fn symbol_slices_cover_exactly_the_definition() {
+        let (_tmp, root) = project();
+
+        let result = compute_file_slice_result(&root, "root", &request("Parser::parse")).unwrap();
+        assert_eq!((result.start_line, result.end_line), (11, 13));
+        assert!(result.content.starts_with("    pub fn parse(&self"));
+        assert!(!result.truncated);
+        let expected_sha = hex_encode_lower(&Sha256::digest(result.content.as_bytes()));
+        assert_eq!(result.content_sha256, expected_sha);
+
+        let mut with_context = request("Parser::parse");
+        with_context.context_lines = Some(1);
+        let result = compute_file_slice_result(&root, "root", &with_context).unwrap();
+        assert_eq!((result.start_line, result.end_line), (10, 14));
+    }
+
+    #[test]
+    fn symbol_slice_cursor_stops_at_the_definition_end() {
+        let (_tmp, root) = project();
+
+        let mut first_page = request("Parser::parse");
+        first_page.max_lines = Some(2);
+        let first = compute_file_slice_result(&root, "root", &first_page).unwrap();
+        assert_eq!((first.start_line, first.end_line), (11, 12));
+        assert!(first.truncated);
+
+        let mut second_page = first_page;
+        second_page.cursor = first.next_cursor;
+        let second = compute_file_slice_result(&root, "root", &second_page).unwrap();
+        assert_eq!((second.start_line, second.end_line), (13, 13));
+        assert_eq!(second.content, "    }");
+        assert!(!second.truncated);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    fn symbol_errors_distinguish_missing_from_ambiguous() {
+        let (_tmp, root) = project();
+
+        let missing = compute_file_slice_result(&root, "root", &request("nope")).unwrap_err();
+        assert!(missing.contains("not found"), "{missing}");
+        assert!(missing.contains("Parser"), "{missing}");
+
+        let ambiguous = compute_file_slice_result(&root, "root", &request("parse")).unwrap_err();
+        assert!(ambiguous.contains("ambiguous"), "{ambiguous}");
+        assert!(
+            ambiguous.contains("#1") && ambiguous.contains("#2"),
+            "{ambiguous}"
+        );
+
+        let mut second = request("parse");
+        second.occurrence = Some(2);
+        let result = compute_file_slice_result(&root, "root", &second).unwrap();
+        assert_eq!(result.start_line, 11);
+    }
+}
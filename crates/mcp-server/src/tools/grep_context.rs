@@ -1,7 +1,7 @@
 use anyhow::{Context as AnyhowContext, Result};
-use context_indexer::{FileScanner, ToolMeta};
+use context_indexer::{FileScanner, ScanStats, ToolMeta};
 use context_protocol::enforce_max_chars;
-use regex::Regex;
+use regex::{Regex, RegexBuilder, RegexSet, RegexSetBuilder};
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
@@ -13,11 +13,56 @@ use super::schemas::grep_context::{
 };
 use super::ContextFinderService;
 
+/// Line matcher for grep_context: any of `includes` must match (OR semantics), and `exclude`
+/// always wins over them.
+#[derive(Debug)]
+pub(super) struct GrepMatcher {
+    includes: RegexSet,
+    exclude: Option<Regex>,
+}
+
+impl GrepMatcher {
+    pub(super) fn new(
+        patterns: &[String],
+        exclude: Option<&str>,
+        case_sensitive: bool,
+    ) -> std::result::Result<Self, String> {
+        let includes = RegexSetBuilder::new(patterns)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map_err(|err| format!("Invalid regex: {err}"))?;
+        let exclude = exclude
+            .map(|pattern| {
+                RegexBuilder::new(pattern)
+                    .case_insensitive(!case_sensitive)
+                    .build()
+                    .map_err(|err| format!("Invalid exclude_pattern regex: {err}"))
+            })
+            .transpose()?;
+        Ok(Self { includes, exclude })
+    }
+
+    fn is_multi(&self) -> bool {
+        self.includes.len() > 1
+    }
+
+    /// Indices of the patterns matching `text`, or `None` when the line is not a match.
+    fn matching_patterns(&self, text: &str) -> Option<Vec<usize>> {
+        if self.exclude.as_ref().is_some_and(|re| re.is_match(text)) {
+            return None;
+        }
+        let hits: Vec<usize> = self.includes.matches(text).into_iter().collect();
+        (!hits.is_empty()).then_some(hits)
+    }
+}
+
 #[derive(Debug, Clone)]
 struct GrepRange {
     start_line: usize,
     end_line: usize,
-    match_lines: Vec<usize>,
+    /// Matching lines with the indices of the patterns that hit them (empty for single-pattern
+    /// searches).
+    matches: Vec<(usize, Vec<usize>)>,
 }
 
 fn merge_grep_ranges(mut ranges: Vec<GrepRange>) -> Vec<GrepRange> {
@@ -36,7 +81,7 @@ fn merge_grep_ranges(mut ranges: Vec<GrepRange>) -> Vec<GrepRange> {
 
         if range.start_line <= last.end_line.saturating_add(1) {
             last.end_line = last.end_line.max(range.end_line);
-            last.match_lines.extend(range.match_lines);
+            last.matches.extend(range.matches);
             continue;
         }
 
@@ -44,8 +89,8 @@ fn merge_grep_ranges(mut ranges: Vec<GrepRange>) -> Vec<GrepRange> {
     }
 
     for range in &mut merged {
-        range.match_lines.sort_unstable();
-        range.match_lines.dedup();
+        range.matches.sort_unstable();
+        range.matches.dedup_by_key(|(line, _)| *line);
     }
 
     merged
@@ -64,7 +109,7 @@ pub(super) struct GrepContextComputeOptions<'a> {
 
 #[derive(Debug)]
 struct MatchScanResult {
-    match_lines: Vec<usize>,
+    matches: Vec<(usize, Vec<usize>)>,
     hit_match_limit: bool,
 }
 
@@ -76,6 +121,8 @@ struct GrepContextAccumulators {
     truncation: Option<GrepContextTruncation>,
     scanned_files: usize,
     matched_files: usize,
+    ignored_files: usize,
+    unreadable_files: usize,
     returned_matches: usize,
     total_matches: usize,
     next_cursor_state: Option<(String, usize)>,
@@ -90,6 +137,8 @@ impl GrepContextAccumulators {
             truncation: None,
             scanned_files: 0,
             matched_files: 0,
+            ignored_files: 0,
+            unreadable_files: 0,
             returned_matches: 0,
             total_matches: 0,
             next_cursor_state: None,
@@ -97,7 +146,7 @@ impl GrepContextAccumulators {
     }
 }
 
-fn canonicalize_request_file(root: &Path, file: &str) -> Result<(String, PathBuf)> {
+pub(super) fn canonicalize_request_file(root: &Path, file: &str) -> Result<(String, PathBuf)> {
     let canonical = root
         .join(Path::new(file))
         .canonicalize()
@@ -115,7 +164,7 @@ async fn collect_candidates(
     root: &Path,
     request: &GrepContextRequest,
     file_pattern: Option<&str>,
-) -> Result<(String, Vec<(String, PathBuf)>)> {
+) -> Result<(String, Vec<(String, PathBuf)>, ScanStats)> {
     let mut candidates: Vec<(String, PathBuf)> = Vec::new();
 
     if let Some(file) = request
@@ -126,23 +175,22 @@ async fn collect_candidates(
     {
         let (display, canonical) = canonicalize_request_file(root, file)?;
         candidates.push((display, canonical));
-        return Ok(("filesystem".to_string(), candidates));
+        return Ok(("filesystem".to_string(), candidates, ScanStats::default()));
     }
 
-    if let Some(corpus) = ContextFinderService::load_chunk_corpus(root).await? {
-        let mut files: Vec<&String> = corpus.files().keys().collect();
-        files.sort();
+    if let Some(files) = ContextFinderService::load_corpus_files(root).await? {
         for file in files {
-            if !ContextFinderService::matches_file_pattern(file, file_pattern) {
+            if !ContextFinderService::matches_file_pattern(&file, file_pattern) {
                 continue;
             }
-            candidates.push((file.clone(), root.join(file)));
+            let path = root.join(&file);
+            candidates.push((file, path));
         }
-        return Ok(("corpus".to_string(), candidates));
+        return Ok(("corpus".to_string(), candidates, ScanStats::default()));
     }
 
     let scanner = FileScanner::new(root);
-    let files = scanner.scan();
+    let (files, stats) = scanner.scan_with_stats();
     let mut rels: Vec<String> = files
         .into_iter()
         .filter_map(|p| normalize_relative_path(root, &p))
@@ -155,7 +203,7 @@ async fn collect_candidates(
         candidates.push((rel.clone(), root.join(&rel)));
     }
 
-    Ok(("filesystem".to_string(), candidates))
+    Ok(("filesystem".to_string(), candidates, stats))
 }
 
 fn ensure_resume_file_exists(
@@ -187,7 +235,7 @@ fn file_resume_line(display_file: &str, resume_file: Option<&str>, resume_line:
 
 fn scan_match_lines_for_file(
     file_path: &Path,
-    regex: &Regex,
+    matcher: &GrepMatcher,
     file_resume_line: usize,
     max_matches: usize,
     total_matches: &mut usize,
@@ -196,7 +244,7 @@ fn scan_match_lines_for_file(
     let mut reader = BufReader::new(file);
     let mut line = String::new();
     let mut line_no = 0usize;
-    let mut match_lines: Vec<usize> = Vec::new();
+    let mut matches: Vec<(usize, Vec<usize>)> = Vec::new();
     let mut hit_match_limit = false;
 
     loop {
@@ -208,10 +256,10 @@ fn scan_match_lines_for_file(
         line_no += 1;
 
         let text = line.trim_end_matches(&['\r', '\n'][..]);
-        if !regex.is_match(text) {
+        let Some(hits) = matcher.matching_patterns(text) else {
             continue;
-        }
-        match_lines.push(line_no);
+        };
+        matches.push((line_no, if matcher.is_multi() { hits } else { Vec::new() }));
         if line_no >= file_resume_line {
             *total_matches += 1;
             if *total_matches >= max_matches {
@@ -222,21 +270,25 @@ fn scan_match_lines_for_file(
     }
 
     Ok(MatchScanResult {
-        match_lines,
+        matches,
         hit_match_limit,
     })
 }
 
-fn build_ranges_from_matches(match_lines: &[usize], before: usize, after: usize) -> Vec<GrepRange> {
-    let ranges: Vec<GrepRange> = match_lines
-        .iter()
-        .map(|&ln| {
+fn build_ranges_from_matches(
+    matches: Vec<(usize, Vec<usize>)>,
+    before: usize,
+    after: usize,
+) -> Vec<GrepRange> {
+    let ranges: Vec<GrepRange> = matches
+        .into_iter()
+        .map(|(ln, hits)| {
             let start_line = ln.saturating_sub(before).max(1);
             let end_line = ln.saturating_add(after);
             GrepRange {
                 start_line,
                 end_line,
-                match_lines: vec![ln],
+                matches: vec![(ln, hits)],
             }
         })
         .collect();
@@ -254,6 +306,7 @@ fn build_hunks_for_file(
     max_chars: usize,
 ) -> bool {
     let Ok(file) = std::fs::File::open(file_path) else {
+        acc.unreadable_files += 1;
         return true;
     };
     let mut reader = BufReader::new(file);
@@ -324,8 +377,15 @@ fn build_hunks_for_file(
             return false;
         }
 
-        let mut match_lines = ranges[range_idx].match_lines.clone();
-        match_lines.retain(|&ln| ln >= range_start_line && ln <= end_line);
+        let (match_lines, mut match_patterns): (Vec<usize>, Vec<Vec<usize>>) = ranges[range_idx]
+            .matches
+            .iter()
+            .filter(|(ln, _)| *ln >= range_start_line && *ln <= end_line)
+            .cloned()
+            .unzip();
+        if match_patterns.iter().all(Vec::is_empty) {
+            match_patterns.clear();
+        }
         acc.returned_matches += match_lines.len();
 
         acc.hunks.push(GrepContextHunk {
@@ -333,6 +393,7 @@ fn build_hunks_for_file(
             start_line: range_start_line,
             end_line,
             match_lines,
+            match_patterns,
             content,
         });
 
@@ -365,6 +426,8 @@ fn build_next_cursor(
         tool: "grep_context".to_string(),
         root: root_display.to_string(),
         pattern: request.pattern.clone(),
+        patterns: request.patterns.clone(),
+        exclude_pattern: request.exclude_pattern.clone(),
         file: request
             .file
             .as_deref()
@@ -386,7 +449,7 @@ pub(super) async fn compute_grep_context_result(
     root: &Path,
     root_display: &str,
     request: &GrepContextRequest,
-    regex: &Regex,
+    matcher: &GrepMatcher,
     opts: GrepContextComputeOptions<'_>,
 ) -> Result<GrepContextResult> {
     const MAX_FILE_BYTES: u64 = 2_000_000;
@@ -404,10 +467,12 @@ pub(super) async fn compute_grep_context_result(
     let file_pattern = trimmed_non_empty_str(request.file_pattern.as_deref());
     let resume_file = trimmed_non_empty_str(resume_file);
     let resume_line = resume_line.max(1);
-    let (source, candidates) = collect_candidates(root, request, file_pattern).await?;
+    let (source, candidates, scan_stats) = collect_candidates(root, request, file_pattern).await?;
     ensure_resume_file_exists(resume_file, &candidates)?;
 
     let mut acc = GrepContextAccumulators::new();
+    acc.ignored_files = scan_stats.ignored_files;
+    acc.unreadable_files = scan_stats.unreadable_files;
     let mut started = resume_file.is_none();
     'outer_files: for (display_file, file_path) in candidates {
         if !started {
@@ -422,6 +487,7 @@ pub(super) async fn compute_grep_context_result(
         acc.scanned_files += 1;
 
         let Ok(meta) = std::fs::metadata(&file_path) else {
+            acc.unreadable_files += 1;
             continue;
         };
         if meta.len() > MAX_FILE_BYTES {
@@ -430,15 +496,16 @@ pub(super) async fn compute_grep_context_result(
 
         let Ok(scan) = scan_match_lines_for_file(
             &file_path,
-            regex,
+            matcher,
             file_resume_line,
             max_matches,
             &mut acc.total_matches,
         ) else {
+            acc.unreadable_files += 1;
             continue;
         };
 
-        if scan.match_lines.is_empty() {
+        if scan.matches.is_empty() {
             continue;
         }
         acc.matched_files += 1;
@@ -447,7 +514,7 @@ pub(super) async fn compute_grep_context_result(
             acc.truncation = Some(GrepContextTruncation::MaxMatches);
         }
 
-        let ranges = build_ranges_from_matches(&scan.match_lines, before, after);
+        let ranges = build_ranges_from_matches(scan.matches, before, after);
 
         if !build_hunks_for_file(
             &mut acc,
@@ -478,6 +545,8 @@ pub(super) async fn compute_grep_context_result(
 
     let result = GrepContextResult {
         pattern: request.pattern.clone(),
+        patterns: request.patterns.clone(),
+        exclude_pattern: request.exclude_pattern.clone(),
         source,
         file: request.file.clone(),
         file_pattern: request.file_pattern.clone(),
@@ -486,6 +555,8 @@ pub(super) async fn compute_grep_context_result(
         after,
         scanned_files: acc.scanned_files,
         matched_files: acc.matched_files,
+        ignored_files: acc.ignored_files,
+        unreadable_files: acc.unreadable_files,
         returned_matches: acc.returned_matches,
         returned_hunks: acc.hunks.len(),
         used_chars: acc.used_chars,
@@ -494,7 +565,7 @@ pub(super) async fn compute_grep_context_result(
         truncation: acc.truncation,
         next_cursor,
         next_actions: None,
-        meta: ToolMeta { index_state: None },
+        meta: ToolMeta::default(),
         hunks: acc.hunks,
     };
 
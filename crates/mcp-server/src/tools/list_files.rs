@@ -1,91 +1,160 @@
 use anyhow::{Context as AnyhowContext, Result};
-use context_indexer::{FileScanner, ToolMeta};
+use context_code_chunker::Language;
+use context_indexer::{FileScanner, ScanStats, ToolMeta};
 use context_protocol::enforce_max_chars;
+use std::cmp::Ordering;
 use std::path::Path;
+use std::time::UNIX_EPOCH;
 
 use super::cursor::{encode_cursor, CURSOR_VERSION};
 use super::paths::normalize_relative_path;
-use super::schemas::list_files::{ListFilesCursorV1, ListFilesResult, ListFilesTruncation};
+use super::schemas::list_files::{
+    ListFilesCursorV1, ListFilesEntry, ListFilesItems, ListFilesOrder, ListFilesResult,
+    ListFilesSort, ListFilesTruncation,
+};
 use super::ContextFinderService;
 
 pub(super) fn decode_list_files_cursor(cursor: &str) -> Result<ListFilesCursorV1> {
     super::cursor::decode_cursor(cursor).with_context(|| "decode list_files cursor")
 }
 
+/// How `list_files` shapes and orders its output.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct ListFilesView {
+    pub(super) include_meta: bool,
+    pub(super) sort: ListFilesSort,
+    pub(super) order: ListFilesOrder,
+}
+
+impl ListFilesView {
+    fn needs_stat(self) -> bool {
+        self.include_meta || matches!(self.sort, ListFilesSort::Size | ListFilesSort::Mtime)
+    }
+
+    fn key(self, entry: &ListFilesEntry) -> Option<u64> {
+        match self.sort {
+            ListFilesSort::Path => None,
+            ListFilesSort::Size => Some(entry.size_bytes.unwrap_or(0)),
+            ListFilesSort::Mtime => Some(entry.mtime_ms.unwrap_or(0)),
+            ListFilesSort::Chunks => Some(entry.chunk_count.unwrap_or(0) as u64),
+        }
+    }
+
+    /// Total order over `(key, path)`; ties on the key always fall back to ascending path so
+    /// cursors resume deterministically.
+    fn compare(self, a: (Option<u64>, &str), b: (Option<u64>, &str)) -> Ordering {
+        let primary = match self.sort {
+            ListFilesSort::Path => a.1.cmp(b.1),
+            _ => a.0.cmp(&b.0),
+        };
+        let primary = match self.order {
+            ListFilesOrder::Asc => primary,
+            ListFilesOrder::Desc => primary.reverse(),
+        };
+        primary.then_with(|| a.1.cmp(b.1))
+    }
+}
+
+fn file_entry(root: &Path, path: String, chunk_count: Option<usize>, stat: bool) -> ListFilesEntry {
+    let metadata = if stat {
+        std::fs::metadata(root.join(&path)).ok()
+    } else {
+        None
+    };
+    let mtime_ms = metadata
+        .as_ref()
+        .and_then(|meta| meta.modified().ok())
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64);
+    let language = Language::from_path(&path);
+    ListFilesEntry {
+        size_bytes: metadata.as_ref().map(std::fs::Metadata::len),
+        mtime_ms,
+        language: (language != Language::Unknown).then(|| language.as_str().to_string()),
+        chunk_count,
+        path,
+    }
+}
+
 pub(super) async fn compute_list_files_result(
     root: &Path,
     root_display: &str,
     file_pattern: Option<&str>,
     limit: usize,
     max_chars: usize,
-    cursor_last_file: Option<&str>,
+    cursor: Option<&ListFilesCursorV1>,
+    view: ListFilesView,
 ) -> Result<ListFilesResult> {
     let file_pattern = file_pattern.map(str::trim).filter(|s| !s.is_empty());
-    let cursor_last_file = cursor_last_file.map(str::trim).filter(|s| !s.is_empty());
 
     let mut used_chars = 0usize;
     let mut truncated = false;
     let mut truncation: Option<ListFilesTruncation> = None;
-    let mut files: Vec<String> = Vec::new();
+    let mut entries: Vec<ListFilesEntry> = Vec::new();
     let mut next_cursor: Option<String> = None;
     let source: String;
     let scanned_files: usize;
-    let mut matched: Vec<String> = Vec::new();
+    let mut scan_stats = ScanStats::default();
+    let mut matched: Vec<ListFilesEntry> = Vec::new();
 
     if let Some(corpus) = ContextFinderService::load_chunk_corpus(root).await? {
         source = "corpus".to_string();
 
-        let mut candidates: Vec<&String> = corpus.files().keys().collect();
-        candidates.sort();
+        let candidates = corpus.files();
         scanned_files = candidates.len();
 
-        for file in candidates {
+        for (file, chunks) in candidates {
             if !ContextFinderService::matches_file_pattern(file, file_pattern) {
                 continue;
             }
-            matched.push(file.clone());
+            matched.push(file_entry(
+                root,
+                file.clone(),
+                Some(chunks.len()),
+                view.needs_stat(),
+            ));
         }
     } else {
         source = "filesystem".to_string();
 
         let scanner = FileScanner::new(root);
-        let scanned_paths = scanner.scan();
+        let (scanned_paths, stats) = scanner.scan_with_stats();
+        scan_stats = stats;
         scanned_files = scanned_paths.len();
 
-        let mut candidates: Vec<String> = scanned_paths
+        for file in scanned_paths
             .into_iter()
             .filter_map(|p| normalize_relative_path(root, &p))
-            .collect();
-        candidates.sort();
-
-        for file in candidates {
+        {
             if !ContextFinderService::matches_file_pattern(&file, file_pattern) {
                 continue;
             }
-            matched.push(file);
+            matched.push(file_entry(root, file, None, view.needs_stat()));
         }
     }
 
-    let start_index = cursor_last_file.map_or(0, |last| {
-        match matched.binary_search_by(|candidate| candidate.as_str().cmp(last)) {
-            Ok(idx) => idx + 1,
-            Err(idx) => idx,
-        }
-    });
-
-    if start_index > matched.len() {
-        anyhow::bail!("Cursor is out of range for matched files");
-    }
-
-    for file in matched.iter().skip(start_index) {
-        if files.len() >= limit {
+    matched.sort_by(|a, b| view.compare((view.key(a), &a.path), (view.key(b), &b.path)));
+
+    let start_index = cursor
+        .map(|cursor| cursor.last_file.trim())
+        .filter(|last| !last.is_empty())
+        .map_or(0, |last| {
+            let last_key = cursor.and_then(|cursor| cursor.last_key);
+            matched.partition_point(|candidate| {
+                view.compare((view.key(candidate), &candidate.path), (last_key, last))
+                    != Ordering::Greater
+            })
+        });
+
+    for entry in matched.iter().skip(start_index) {
+        if entries.len() >= limit {
             truncated = true;
             truncation = Some(ListFilesTruncation::MaxItems);
             break;
         }
 
-        let file_chars = file.chars().count();
-        let extra_chars = if files.is_empty() {
+        let file_chars = entry.path.chars().count();
+        let extra_chars = if entries.is_empty() {
             file_chars
         } else {
             1 + file_chars
@@ -96,26 +165,40 @@ pub(super) async fn compute_list_files_result(
             break;
         }
 
-        files.push(file.clone());
+        entries.push(entry.clone());
         used_chars += extra_chars;
     }
 
-    if truncated && !files.is_empty() && start_index.saturating_add(files.len()) < matched.len() {
-        if let Some(last_file) = files.last() {
+    if truncated && !entries.is_empty() && start_index.saturating_add(entries.len()) < matched.len()
+    {
+        if let Some(last) = entries.last() {
+            let default_view =
+                view.sort == ListFilesSort::default() && view.order == ListFilesOrder::default();
             next_cursor = Some(encode_cursor(&ListFilesCursorV1 {
                 v: CURSOR_VERSION,
                 tool: "list_files".to_string(),
                 root: root_display.to_string(),
                 file_pattern: file_pattern.map(str::to_string),
-                last_file: last_file.clone(),
+                sort: (!default_view).then_some(view.sort),
+                order: (!default_view).then_some(view.order),
+                last_file: last.path.clone(),
+                last_key: view.key(last),
             })?);
         }
     }
 
+    let files = if view.include_meta {
+        ListFilesItems::Entries(entries)
+    } else {
+        ListFilesItems::Paths(entries.into_iter().map(|entry| entry.path).collect())
+    };
+
     Ok(ListFilesResult {
         source,
         file_pattern: file_pattern.map(str::to_string),
         scanned_files,
+        ignored_files: scan_stats.ignored_files,
+        unreadable_files: scan_stats.unreadable_files,
         returned: files.len(),
         used_chars,
         limit,
@@ -124,7 +207,7 @@ pub(super) async fn compute_list_files_result(
         truncation,
         next_cursor,
         next_actions: None,
-        meta: ToolMeta { index_state: None },
+        meta: ToolMeta::default(),
         files,
     })
 }
@@ -140,8 +223,7 @@ pub(super) fn finalize_list_files_budget(result: &mut ListFilesResult) -> Result
             inner.truncation = Some(ListFilesTruncation::MaxChars);
         },
         |inner| {
-            if !inner.files.is_empty() {
-                inner.files.pop();
+            if inner.files.pop() {
                 inner.returned = inner.files.len();
                 return true;
             }
@@ -16,6 +16,13 @@ const fn chunker_config_for_map() -> ChunkerConfig {
         target_chunk_tokens: 768,
         max_chunk_tokens: 2048,
         min_chunk_tokens: 0,
+        min_chunk_chars: 0,
+        max_chunk_chars: None,
+        overlap_chars: 0,
+        max_lines: None,
+        overlap_lines: 0,
+        min_chunk_lines: 0,
+        languages: std::collections::BTreeMap::new(),
         include_imports: false,
         include_parent_context: false,
         include_documentation: false,
@@ -254,7 +261,7 @@ pub(super) async fn compute_map_result(
         truncated,
         next_cursor,
         next_actions: None,
-        meta: ToolMeta { index_state: None },
+        meta: ToolMeta::default(),
     })
 }
 
@@ -0,0 +1,146 @@
+use context_indexer::ToolCallStats;
+use rmcp::model::CallToolResult;
+use rmcp::ErrorData as McpError;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Set to `1` to attach the per-tool stats snapshot to every `meta`.
+pub const DEBUG_METRICS_ENV: &str = "CONTEXT_FINDER_DEBUG_METRICS";
+
+/// Latency percentiles are computed over this many most recent calls per tool.
+const LATENCY_WINDOW: usize = 512;
+
+#[derive(Default)]
+struct ToolSamples {
+    calls: u64,
+    errors: u64,
+    bytes_returned: u64,
+    latencies_ms: VecDeque<u64>,
+}
+
+/// In-memory per-tool counters for the lifetime of the server process.
+#[derive(Default)]
+pub(super) struct ToolMetrics {
+    tools: Mutex<BTreeMap<String, ToolSamples>>,
+}
+
+impl ToolMetrics {
+    pub(super) fn record(
+        &self,
+        tool: &str,
+        elapsed: Duration,
+        result: &Result<CallToolResult, McpError>,
+    ) {
+        let (failed, bytes) = match result {
+            Ok(result) => (
+                result.is_error == Some(true),
+                result
+                    .content
+                    .iter()
+                    .filter_map(|content| content.as_text())
+                    .map(|text| text.text.len() as u64)
+                    .sum(),
+            ),
+            Err(_) => (true, 0),
+        };
+        let elapsed_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+
+        let mut tools = self
+            .tools
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let samples = tools.entry(tool.to_string()).or_default();
+        samples.calls += 1;
+        samples.errors += u64::from(failed);
+        samples.bytes_returned += bytes;
+        if samples.latencies_ms.len() == LATENCY_WINDOW {
+            samples.latencies_ms.pop_front();
+        }
+        samples.latencies_ms.push_back(elapsed_ms);
+    }
+
+    /// Stats for every tool called so far, sorted by tool name.
+    pub(super) fn snapshot(&self) -> Vec<ToolCallStats> {
+        let tools = self
+            .tools
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        tools
+            .iter()
+            .map(|(tool, samples)| {
+                let mut latencies: Vec<u64> = samples.latencies_ms.iter().copied().collect();
+                latencies.sort_unstable();
+                ToolCallStats {
+                    tool: tool.clone(),
+                    calls: samples.calls,
+                    errors: samples.errors,
+                    p50_ms: percentile(&latencies, 50),
+                    p95_ms: percentile(&latencies, 95),
+                    bytes_returned: samples.bytes_returned,
+                }
+            })
+            .collect()
+    }
+}
+
+pub(super) fn debug_metrics_enabled() -> bool {
+    std::env::var(DEBUG_METRICS_ENV)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Nearest-rank percentile of an ascending slice (0 when empty).
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Content;
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let sorted: Vec<u64> = (1..=20).collect();
+        assert_eq!(percentile(&sorted, 50), 10);
+        assert_eq!(percentile(&sorted, 95), 19);
+        assert_eq!(percentile(&[7], 95), 7);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn record_counts_errors_and_bytes() {
+        let metrics = ToolMetrics::default();
+        metrics.record(
+            "grep_context",
+            Duration::from_millis(5),
+            &Ok(CallToolResult::success(vec![Content::text("abcd")])),
+        );
+        metrics.record(
+            "grep_context",
+            Duration::from_millis(15),
+            &Ok(CallToolResult::error(vec![Content::text("no")])),
+        );
+        metrics.record(
+            "file_slice",
+            Duration::from_millis(1),
+            &Err(McpError::invalid_params("bad", None)),
+        );
+
+        let stats = metrics.snapshot();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].tool, "file_slice");
+        assert_eq!(stats[0].errors, 1);
+        assert_eq!(stats[1].tool, "grep_context");
+        assert_eq!(stats[1].calls, 2);
+        assert_eq!(stats[1].errors, 1);
+        assert_eq!(stats[1].bytes_returned, 6);
+        assert_eq!(stats[1].p50_ms, 5);
+        assert_eq!(stats[1].p95_ms, 15);
+    }
+}
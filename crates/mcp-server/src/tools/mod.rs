@@ -6,14 +6,18 @@
 mod batch;
 pub(crate) mod catalog;
 mod cursor;
+mod diff_pack;
 mod dispatch;
 mod file_slice;
 mod grep_context;
 mod list_files;
 mod map;
+mod metrics;
 mod paths;
 mod repo_onboarding_pack;
 mod schemas;
+mod symbols;
 mod util;
+mod workspace;
 
 pub use dispatch::ContextFinderService;
@@ -1,7 +1,93 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub fn normalize_relative_path(root: &Path, path: &Path) -> Option<String> {
     let rel = path.strip_prefix(root).ok()?;
     let rel = rel.to_string_lossy().into_owned();
     Some(rel.replace('\\', "/"))
 }
+
+pub const ALLOWED_ROOTS_ENV: &str = "CONTEXT_FINDER_ALLOWED_ROOTS";
+
+/// Project roots the server may serve, from `CONTEXT_FINDER_ALLOWED_ROOTS`
+/// (colon-separated absolute paths). Entries are canonicalized once; entries that are
+/// relative or do not exist land in `invalid` and grant nothing, so a fully broken list
+/// rejects every path instead of silently allowing all of them.
+#[derive(Debug, Clone, Default)]
+pub struct AllowedRoots {
+    pub roots: Vec<PathBuf>,
+    pub invalid: Vec<String>,
+}
+
+impl AllowedRoots {
+    /// `None` when the variable is unset or blank (no restriction).
+    pub fn from_env() -> Option<Self> {
+        let raw = std::env::var(ALLOWED_ROOTS_ENV).ok()?;
+        if raw.trim().is_empty() {
+            return None;
+        }
+        Some(Self::parse(&raw))
+    }
+
+    fn parse(raw: &str) -> Self {
+        let mut allowed = Self::default();
+        for entry in raw.split(':').map(str::trim).filter(|e| !e.is_empty()) {
+            let path = Path::new(entry);
+            match path.canonicalize() {
+                Ok(root) if path.is_absolute() => {
+                    if !allowed.roots.contains(&root) {
+                        allowed.roots.push(root);
+                    }
+                }
+                _ => allowed.invalid.push(entry.to_string()),
+            }
+        }
+        allowed
+    }
+
+    /// `root` must already be canonical, so symlinks cannot hop outside an allowed root.
+    pub fn check(&self, root: &Path) -> Result<(), String> {
+        if self.roots.iter().any(|allowed| root.starts_with(allowed)) {
+            return Ok(());
+        }
+        let allowed = self
+            .roots
+            .iter()
+            .map(|root| root.to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(":");
+        Err(format!(
+            "Path '{}' is outside the allowed roots ({ALLOWED_ROOTS_ENV}={allowed})",
+            root.display()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowed_roots_accept_nested_paths_and_reject_siblings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path().canonicalize().unwrap();
+        let allowed_dir = base.join("allowed");
+        let nested = allowed_dir.join("nested");
+        let sibling = base.join("allowed-sibling");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(&sibling).unwrap();
+
+        let raw = format!(
+            "{}:relative/dir:{}",
+            allowed_dir.display(),
+            base.join("missing").display()
+        );
+        let allowed = AllowedRoots::parse(&raw);
+        assert_eq!(allowed.roots, vec![allowed_dir.clone()]);
+        assert_eq!(allowed.invalid.len(), 2);
+
+        assert!(allowed.check(&allowed_dir).is_ok());
+        assert!(allowed.check(&nested).is_ok());
+        assert!(allowed.check(&sibling).is_err());
+        assert!(AllowedRoots::parse("relative").check(&nested).is_err());
+    }
+}
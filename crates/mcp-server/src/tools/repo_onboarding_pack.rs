@@ -1,14 +1,17 @@
 use anyhow::Result;
+use context_indexer::FileScanner;
 use context_indexer::ToolMeta;
 use context_protocol::{enforce_max_chars, finalize_used_chars};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::path::Path;
 
 use super::file_slice::compute_onboarding_doc_slice;
 use super::map::compute_map_result;
+use super::paths::normalize_relative_path;
 use super::schemas::repo_onboarding_pack::{
-    RepoOnboardingDocsReason, RepoOnboardingNextAction, RepoOnboardingPackBudget,
-    RepoOnboardingPackRequest, RepoOnboardingPackResult, RepoOnboardingPackTruncation,
+    RepoOnboardingBuildFile, RepoOnboardingDocsReason, RepoOnboardingEntryPoint,
+    RepoOnboardingNextAction, RepoOnboardingPackBudget, RepoOnboardingPackRequest,
+    RepoOnboardingPackResult, RepoOnboardingPackTruncation, RepoOnboardingSection,
 };
 use super::ContextFinderService;
 
@@ -24,10 +27,21 @@ const DEFAULT_DOC_MAX_LINES: usize = 200;
 const MAX_DOC_MAX_LINES: usize = 5_000;
 const DEFAULT_DOC_MAX_CHARS: usize = 6_000;
 const MAX_DOC_MAX_CHARS: usize = 100_000;
+const MAX_ENTRY_POINTS: usize = 20;
+const MAX_BUILD_ITEMS: usize = 40;
+
+const ALL_SECTIONS: &[RepoOnboardingSection] = &[
+    RepoOnboardingSection::Map,
+    RepoOnboardingSection::Docs,
+    RepoOnboardingSection::EntryPoints,
+    RepoOnboardingSection::Build,
+    RepoOnboardingSection::NextActions,
+];
 
 const DEFAULT_DOC_CANDIDATES: &[&str] = &[
     "AGENTS.md",
     "README.md",
+    "ARCHITECTURE.md",
     "docs/QUICK_START.md",
     "contracts/README.md",
     "docs/README.md",
@@ -46,28 +60,25 @@ pub(super) fn finalize_repo_onboarding_budget(
 fn build_next_actions(root_display: &str, has_corpus: bool) -> Vec<RepoOnboardingNextAction> {
     let mut next_actions = Vec::new();
     if !has_corpus {
-        next_actions.push(RepoOnboardingNextAction {
-            tool: "index".to_string(),
-            args: serde_json::json!({ "path": root_display }),
-            reason: "Build the semantic index (enables search/context/context_pack/impact/trace)."
-                .to_string(),
-        });
+        next_actions.push(RepoOnboardingNextAction::new(
+            "index",
+            serde_json::json!({ "path": root_display }),
+            "Build the semantic index (enables search/context/context_pack/impact/trace).",
+        ));
     }
 
-    next_actions.push(RepoOnboardingNextAction {
-        tool: "grep_context".to_string(),
-        args: serde_json::json!({
+    next_actions.push(RepoOnboardingNextAction::new(
+        "grep_context",
+        serde_json::json!({
             "path": root_display,
             "pattern": "TODO|FIXME",
             "context": 10,
             "max_hunks": 50,
         }),
-        reason: "Scan for TODO/FIXME across the repo with surrounding context hunks.".to_string(),
-    });
+        "Scan for TODO/FIXME across the repo with surrounding context hunks.",
+    ));
 
-    next_actions.push(RepoOnboardingNextAction {
-        tool: "batch".to_string(),
-        args: serde_json::json!({
+    next_actions.push(RepoOnboardingNextAction::new("batch", serde_json::json!({
             "version": 2,
             "path": root_display,
             "max_chars": 20000,
@@ -75,30 +86,91 @@ fn build_next_actions(root_display: &str, has_corpus: bool) -> Vec<RepoOnboardin
                 { "id": "docs", "tool": "list_files", "input": { "file_pattern": "*.md", "limit": 200 } },
                 { "id": "read", "tool": "file_slice", "input": { "file": { "$ref": "#/items/docs/data/files/0" }, "start_line": 1, "max_lines": 200 } }
             ]
-        }),
-        reason: "Example: chain tools in one call with `$ref` dependencies (batch v2).".to_string(),
-    });
+        }), "Example: chain tools in one call with `$ref` dependencies (batch v2)."));
 
     if has_corpus {
-        next_actions.push(RepoOnboardingNextAction {
-            tool: "context_pack".to_string(),
-            args: serde_json::json!({
+        next_actions.push(RepoOnboardingNextAction::new(
+            "context_pack",
+            serde_json::json!({
                 "path": root_display,
                 "query": "describe what you want to change",
                 "strategy": "extended",
                 "max_chars": 20000,
             }),
-            reason: "One-shot semantic onboarding pack for a concrete question.".to_string(),
-        });
+            "One-shot semantic onboarding pack for a concrete question.",
+        ));
     }
 
     next_actions
 }
 
-fn collect_doc_candidates(request: &RepoOnboardingPackRequest) -> Vec<String> {
+fn requested_sections(request: &RepoOnboardingPackRequest) -> BTreeSet<RepoOnboardingSection> {
+    match request.sections.as_deref() {
+        Some(sections) if !sections.is_empty() => sections.iter().copied().collect(),
+        _ => ALL_SECTIONS.iter().copied().collect(),
+    }
+}
+
+/// Key-doc settings from `.context-finder/config.json` (`onboarding` section, best-effort).
+#[derive(Debug, Default)]
+struct OnboardingConfig {
+    /// `onboarding.key_docs_globs`: extra files that count as key docs, matched before defaults
+    key_docs_globs: Vec<String>,
+    /// `onboarding.max_doc_bytes`: cap on the total doc content bytes bundled into the pack
+    max_doc_bytes: Option<usize>,
+}
+
+async fn load_onboarding_config(root: &Path) -> OnboardingConfig {
+    let path = root.join(".context-finder").join("config.json");
+    let Ok(bytes) = tokio::fs::read(&path).await else {
+        return OnboardingConfig::default();
+    };
+    let config: serde_json::Value = match serde_json::from_slice(&bytes) {
+        Ok(value) => value,
+        Err(err) => {
+            log::debug!("Ignoring unparsable config {}: {err}", path.display());
+            return OnboardingConfig::default();
+        }
+    };
+    let key_docs_globs = config
+        .pointer("/onboarding/key_docs_globs")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(serde_json::Value::as_str)
+        .map(str::trim)
+        .filter(|glob| !glob.is_empty())
+        .map(str::to_string)
+        .collect();
+    let max_doc_bytes = config
+        .pointer("/onboarding/max_doc_bytes")
+        .and_then(serde_json::Value::as_u64)
+        .filter(|bytes| *bytes > 0)
+        .map(|bytes| usize::try_from(bytes).unwrap_or(usize::MAX));
+    OnboardingConfig {
+        key_docs_globs,
+        max_doc_bytes,
+    }
+}
+
+fn scan_project_files(root: &Path) -> Vec<String> {
+    let mut files: Vec<String> = FileScanner::new(root)
+        .scan()
+        .iter()
+        .filter_map(|file| normalize_relative_path(root, file))
+        .collect();
+    files.sort();
+    files
+}
+
+fn collect_doc_candidates(
+    request: &RepoOnboardingPackRequest,
+    key_docs_globs: &[String],
+    project_files: &[String],
+) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut doc_candidates: Vec<String> = Vec::new();
     if let Some(custom) = request.doc_paths.as_ref() {
-        let mut seen = HashSet::new();
-        let mut doc_candidates: Vec<String> = Vec::new();
         for rel in custom {
             let rel = rel.trim();
             if rel.is_empty() {
@@ -112,12 +184,146 @@ fn collect_doc_candidates(request: &RepoOnboardingPackRequest) -> Vec<String> {
         return doc_candidates;
     }
 
-    DEFAULT_DOC_CANDIDATES
+    // Configured globs come first, in config order, so project-specific docs win the docs_limit.
+    for glob in key_docs_globs {
+        for file in project_files {
+            if ContextFinderService::matches_file_pattern(file, Some(glob))
+                && seen.insert(file.clone())
+            {
+                doc_candidates.push(file.clone());
+            }
+        }
+    }
+    for &rel in DEFAULT_DOC_CANDIDATES {
+        if seen.insert(rel.to_string()) {
+            doc_candidates.push(rel.to_string());
+        }
+    }
+    doc_candidates
+}
+
+fn entry_point_kind(file: &str) -> Option<&'static str> {
+    let (dir, name) = file.rsplit_once('/').unwrap_or(("", file));
+    let in_src = dir == "src" || dir.ends_with("/src");
+    match name {
+        "main.rs" if in_src => Some("binary"),
+        "lib.rs" if in_src => Some("library"),
+        _ if dir == "src/bin" || dir.ends_with("/src/bin") => {
+            name.ends_with(".rs").then_some("binary")
+        }
+        "main.go" => Some("binary"),
+        "__main__.py" | "main.py" | "manage.py" => Some("script"),
+        "index.js" | "index.ts" | "index.mjs" | "main.js" | "main.ts" | "server.js"
+        | "server.ts"
+            if dir.is_empty() || dir == "src" =>
+        {
+            Some("module")
+        }
+        _ => None,
+    }
+}
+
+fn collect_entry_points(project_files: &[String]) -> Vec<RepoOnboardingEntryPoint> {
+    project_files
         .iter()
-        .map(|&rel| rel.to_owned())
+        .filter_map(|file| {
+            entry_point_kind(file).map(|kind| RepoOnboardingEntryPoint {
+                file: file.clone(),
+                kind: kind.to_string(),
+            })
+        })
+        .take(MAX_ENTRY_POINTS)
         .collect()
 }
 
+fn cargo_items(content: &str) -> Option<Vec<String>> {
+    let manifest: toml::Value = toml::from_str(content).ok()?;
+    if let Some(members) = manifest
+        .get("workspace")
+        .and_then(|ws| ws.get("members"))
+        .and_then(toml::Value::as_array)
+    {
+        return Some(
+            members
+                .iter()
+                .filter_map(toml::Value::as_str)
+                .map(str::to_string)
+                .collect(),
+        );
+    }
+    let name = manifest
+        .get("package")
+        .and_then(|pkg| pkg.get("name"))
+        .and_then(toml::Value::as_str)?;
+    Some(vec![name.to_string()])
+}
+
+fn npm_items(content: &str) -> Option<Vec<String>> {
+    let manifest: serde_json::Value = serde_json::from_str(content).ok()?;
+    let scripts = manifest.get("scripts")?.as_object()?;
+    Some(scripts.keys().cloned().collect())
+}
+
+/// Explicit rule targets (`name:` at column 0), skipping special `.TARGETS` and `:=` assignments.
+fn make_items(content: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut targets = Vec::new();
+    for line in content.lines() {
+        let Some((names, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if rest.starts_with('=') || line.starts_with(|c: char| c.is_whitespace() || c == '.') {
+            continue;
+        }
+        for name in names.split_whitespace() {
+            let valid = name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'));
+            if valid && seen.insert(name.to_string()) {
+                targets.push(name.to_string());
+            }
+        }
+    }
+    targets
+}
+
+async fn collect_build_files(root: &Path) -> Vec<RepoOnboardingBuildFile> {
+    let mut build = Vec::new();
+    for (file, kind) in [
+        ("Cargo.toml", "cargo"),
+        ("package.json", "npm"),
+        ("Makefile", "make"),
+        ("makefile", "make"),
+        ("GNUmakefile", "make"),
+    ] {
+        let Ok(content) = tokio::fs::read_to_string(root.join(file)).await else {
+            continue;
+        };
+        let items = match kind {
+            "cargo" => cargo_items(&content),
+            "npm" => npm_items(&content),
+            _ => Some(make_items(&content)),
+        };
+        let Some(mut items) = items else {
+            continue;
+        };
+        items.truncate(MAX_BUILD_ITEMS);
+        build.push(RepoOnboardingBuildFile {
+            file: file.to_string(),
+            kind: kind.to_string(),
+            items,
+        });
+    }
+    build
+}
+
+fn mark_truncated(result: &mut RepoOnboardingPackResult, section: RepoOnboardingSection) {
+    if !result.truncated_sections.contains(&section) {
+        result.truncated_sections.push(section);
+        result.truncated_sections.sort();
+    }
+}
+
 fn add_docs_best_effort(
     result: &mut RepoOnboardingPackResult,
     root: &Path,
@@ -125,25 +331,38 @@ fn add_docs_best_effort(
     docs_limit: usize,
     doc_max_lines: usize,
     doc_max_chars: usize,
+    max_doc_bytes: Option<usize>,
 ) -> anyhow::Result<()> {
+    let mut doc_bytes = 0usize;
     for rel in doc_candidates {
-        if result.docs.len() >= docs_limit {
+        let remaining_bytes = max_doc_bytes.map(|cap| cap.saturating_sub(doc_bytes));
+        if result.docs.len() >= docs_limit || remaining_bytes == Some(0) {
             result.budget.truncated = true;
             result.budget.truncation = Some(RepoOnboardingPackTruncation::DocsLimit);
+            mark_truncated(result, RepoOnboardingSection::Docs);
             break;
         }
 
-        let Ok(slice) = compute_onboarding_doc_slice(root, rel, 1, doc_max_lines, doc_max_chars)
-        else {
+        let max_chars = remaining_bytes.map_or(doc_max_chars, |left| doc_max_chars.min(left));
+        let Ok(slice) = compute_onboarding_doc_slice(root, rel, 1, doc_max_lines, max_chars) else {
             continue;
         };
+        // Chars under-count multi-byte text; only the first doc may overshoot the byte cap.
+        if remaining_bytes.is_some_and(|left| slice.content.len() > left) && !result.docs.is_empty()
+        {
+            result.budget.truncated = true;
+            result.budget.truncation = Some(RepoOnboardingPackTruncation::DocsLimit);
+            mark_truncated(result, RepoOnboardingSection::Docs);
+            break;
+        }
+        doc_bytes += slice.content.len();
         result.docs.push(slice);
 
         finalize_repo_onboarding_budget(result)?;
         if result.budget.used_chars > result.budget.max_chars {
             result.budget.truncated = true;
             result.budget.truncation = Some(RepoOnboardingPackTruncation::MaxChars);
-            // Keep the current doc slice; map/next_actions can be trimmed to make room.
+            // Keep the current doc slice; other sections can be trimmed to make room.
             break;
         }
     }
@@ -164,17 +383,30 @@ fn trim_to_budget(result: &mut RepoOnboardingPackResult) -> anyhow::Result<()> {
                 inner.budget.truncation = Some(RepoOnboardingPackTruncation::MaxChars);
             },
             |inner| {
-                if !inner.map.directories.is_empty() {
-                    inner.map.directories.pop();
-                    inner.map.truncated = true;
+                if let Some(map) = inner.map.as_mut() {
+                    if !map.directories.is_empty() {
+                        map.directories.pop();
+                        map.truncated = true;
+                        mark_truncated(inner, RepoOnboardingSection::Map);
+                        return true;
+                    }
+                }
+                if inner.build.pop().is_some() {
+                    mark_truncated(inner, RepoOnboardingSection::Build);
+                    return true;
+                }
+                if inner.entry_points.pop().is_some() {
+                    mark_truncated(inner, RepoOnboardingSection::EntryPoints);
                     return true;
                 }
                 if inner.next_actions.len() > 1 {
                     inner.next_actions.pop();
+                    mark_truncated(inner, RepoOnboardingSection::NextActions);
                     return true;
                 }
                 if inner.docs.len() > min_docs {
                     inner.docs.pop();
+                    mark_truncated(inner, RepoOnboardingSection::Docs);
                     return true;
                 }
                 false
@@ -188,6 +420,7 @@ fn trim_to_budget(result: &mut RepoOnboardingPackResult) -> anyhow::Result<()> {
             if !result.docs.is_empty() {
                 result.docs.clear();
                 result.docs_reason = Some(RepoOnboardingDocsReason::MaxChars);
+                mark_truncated(result, RepoOnboardingSection::Docs);
                 enforce(result, 0)?
             } else {
                 return Err(err);
@@ -222,14 +455,46 @@ pub(super) async fn compute_repo_onboarding_pack_result(
         .unwrap_or(DEFAULT_DOC_MAX_CHARS)
         .clamp(1, MAX_DOC_MAX_CHARS);
 
-    let map = compute_map_result(root, root_display, map_depth, map_limit, 0).await?;
+    let sections = requested_sections(request);
+    let map = if sections.contains(&RepoOnboardingSection::Map) {
+        Some(compute_map_result(root, root_display, map_depth, map_limit, 0).await?)
+    } else {
+        None
+    };
 
-    let has_corpus = ContextFinderService::load_chunk_corpus(root)
+    let has_corpus = ContextFinderService::load_corpus_files(root)
         .await
         .is_ok_and(|v| v.is_some());
 
-    let next_actions = build_next_actions(root_display, has_corpus);
-    let doc_candidates = collect_doc_candidates(request);
+    let next_actions = if sections.contains(&RepoOnboardingSection::NextActions) {
+        build_next_actions(root_display, has_corpus)
+    } else {
+        Vec::new()
+    };
+    let onboarding = if sections.contains(&RepoOnboardingSection::Docs) {
+        load_onboarding_config(root).await
+    } else {
+        OnboardingConfig::default()
+    };
+    let project_files = if sections.contains(&RepoOnboardingSection::EntryPoints)
+        || (!onboarding.key_docs_globs.is_empty() && request.doc_paths.is_none())
+    {
+        scan_project_files(root)
+    } else {
+        Vec::new()
+    };
+    let doc_candidates =
+        collect_doc_candidates(request, &onboarding.key_docs_globs, &project_files);
+    let entry_points = if sections.contains(&RepoOnboardingSection::EntryPoints) {
+        collect_entry_points(&project_files)
+    } else {
+        Vec::new()
+    };
+    let build = if sections.contains(&RepoOnboardingSection::Build) {
+        collect_build_files(root).await
+    } else {
+        Vec::new()
+    };
 
     let mut result = RepoOnboardingPackResult {
         version: VERSION,
@@ -237,26 +502,36 @@ pub(super) async fn compute_repo_onboarding_pack_result(
         map,
         docs: Vec::new(),
         docs_reason: None,
+        entry_points,
+        build,
         next_actions,
+        truncated_sections: Vec::new(),
         budget: RepoOnboardingPackBudget {
             max_chars,
             used_chars: 0,
             truncated: false,
             truncation: None,
         },
-        meta: ToolMeta { index_state: None },
+        meta: ToolMeta::default(),
     };
 
-    add_docs_best_effort(
-        &mut result,
-        root,
-        &doc_candidates,
-        docs_limit,
-        doc_max_lines,
-        doc_max_chars,
-    )?;
+    let include_docs = sections.contains(&RepoOnboardingSection::Docs);
+    if include_docs {
+        add_docs_best_effort(
+            &mut result,
+            root,
+            &doc_candidates,
+            docs_limit,
+            doc_max_lines,
+            doc_max_chars,
+            onboarding.max_doc_bytes,
+        )?;
+    }
     trim_to_budget(&mut result)?;
-    if result.docs.is_empty() {
+    if result.map.as_ref().is_some_and(|map| map.truncated) {
+        mark_truncated(&mut result, RepoOnboardingSection::Map);
+    }
+    if include_docs && result.docs.is_empty() {
         result.docs_reason = Some(if docs_limit == 0 {
             RepoOnboardingDocsReason::DocsLimitZero
         } else if doc_candidates.is_empty() {
@@ -273,3 +548,67 @@ pub(super) async fn compute_repo_onboarding_pack_result(
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_points_cover_common_layouts() {
+        let files: Vec<String> = [
+            "crates/cli/src/main.rs",
+            "crates/core/src/lib.rs",
+            "crates/core/src/parser.rs",
+            "src/bin/tool.rs",
+            "cmd/server/main.go",
+            "pkg/__main__.py",
+            "index.ts",
+            "web/components/index.ts",
+        ]
+        .iter()
+        .map(|s| (*s).to_string())
+        .collect();
+
+        let found: Vec<(String, String)> = collect_entry_points(&files)
+            .into_iter()
+            .map(|ep| (ep.file, ep.kind))
+            .collect();
+        let expected: Vec<(String, String)> = [
+            ("crates/cli/src/main.rs", "binary"),
+            ("crates/core/src/lib.rs", "library"),
+            ("src/bin/tool.rs", "binary"),
+            ("cmd/server/main.go", "binary"),
+            ("pkg/__main__.py", "script"),
+            ("index.ts", "module"),
+        ]
+        .iter()
+        .map(|(f, k)| ((*f).to_string(), (*k).to_string()))
+        .collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn build_items_parse_cargo_npm_and_make() {
+        let cargo = "[workspace]\nmembers = [\"crates/a\", \"crates/b\"]\n";
+        assert_eq!(
+            cargo_items(cargo),
+            Some(vec!["crates/a".to_string(), "crates/b".to_string()])
+        );
+        assert_eq!(
+            cargo_items("[package]\nname = \"demo\"\n"),
+            Some(vec!["demo".to_string()])
+        );
+
+        let npm = r#"{"scripts": {"test": "jest", "build": "tsc"}}"#;
+        assert_eq!(
+            npm_items(npm),
+            Some(vec!["build".to_string(), "test".to_string()])
+        );
+
+        let make = ".PHONY: build test\nCC := gcc\nbuild: deps\n\tcc main.c\ntest lint:\n\t./run\n";
+        assert_eq!(
+            make_items(make),
+            vec!["build".to_string(), "test".to_string(), "lint".to_string()]
+        );
+    }
+}
@@ -27,12 +27,15 @@ pub enum BatchToolName {
 pub struct BatchRequest {
     /// Batch schema version (default: 2).
     ///
-    /// - v1: executes items sequentially, but does NOT resolve `$ref` wrappers.
-    /// - v2: resolves `$ref` wrappers (id-based JSON Pointer) against prior item results.
+    /// - v1: does NOT resolve `$ref` wrappers.
+    /// - v2: resolves `$ref` / `$refList` wrappers (id-based JSON Pointer) against prior item results.
+    ///
+    /// Consecutive items that do not refer to each other run concurrently (sharing the remaining
+    /// `max_chars` budget); results always keep the request order.
     ///
     /// Note: Batch v2 `$ref` semantics are shared with Command API batch v1 via `crates/batch-ref`.
     #[schemars(
-        description = "Batch schema version (default: 2). v1: no $ref resolution. v2: supports $ref wrappers (id-based JSON Pointer) against prior item results."
+        description = "Batch schema version (default: 2). v1: no $ref resolution. v2: supports $ref wrappers (id-based JSON Pointer) against prior item results, plus $refList with '*' wildcards (optional $slice: [start, end], $unique: true) that collects an array, and $format templates ($format: 'fn {0}(', args: [<value or $ref>]) that build strings from string/number args."
     )]
     pub version: Option<u32>,
 
@@ -54,6 +57,19 @@ pub struct BatchRequest {
     #[serde(default)]
     pub stop_on_error: bool,
 
+    /// Overall time budget for the batch in milliseconds. Items not started before it passes are
+    /// reported as `skipped`; a running item is cut off like a per-item timeout.
+    #[schemars(
+        description = "Overall time budget in milliseconds. Items not started before it passes are reported with status 'skipped'; a running item is cut off like a per-item timeout."
+    )]
+    pub deadline_ms: Option<u64>,
+
+    /// Default per-item time budget in milliseconds for items without their own `timeout_ms`.
+    #[schemars(
+        description = "Default per-item time budget in milliseconds, used by items that do not set their own timeout_ms."
+    )]
+    pub item_timeout_ms: Option<u64>,
+
     /// Batch items to execute.
     #[schemars(description = "Batch items to execute.")]
     pub items: Vec<BatchItem>,
@@ -76,8 +92,25 @@ pub struct BatchItem {
     /// In batch v2, any value position may be a `$ref` wrapper:
     /// `{ "$ref": "#/items/<id>/data/...", "$default": <optional> }`.
     /// The wrapper is recognized only when the object contains exactly `$ref` (+ optional `$default`).
+    /// `{ "$refList": "#/items/<id>/data/results/*/file", "$slice": [0, 3], "$unique": true }` collects an array.
+    /// `{ "$format": "fn {0}\\(", "args": [<value or $ref>, ...] }` interpolates string/number args
+    /// into positional `{N}` placeholders (`{{` / `}}` are literal braces).
     #[serde(default, alias = "payload")]
     pub input: serde_json::Value,
+
+    /// Optional predicate over prior item results (batch v2). When it evaluates to false the item
+    /// is not run and is reported with status `skipped`; `$ref`s to it then fall back to `$default`.
+    #[schemars(
+        description = "Optional predicate over prior results (v2): {\"exists\": ptr}, {\"empty\": ptr}, {\"eq\": [ptr, value]}, combinable with {\"not\": p}, {\"all\": [..]}, {\"any\": [..]}. False => item status 'skipped'."
+    )]
+    pub when: Option<serde_json::Value>,
+
+    /// Per-item time budget in milliseconds. An item that exceeds it is cancelled and reported as
+    /// an error with code `timeout`; `index` items run to completion and only report the timeout.
+    #[schemars(
+        description = "Per-item time budget in milliseconds. Exceeding it cancels the item and reports status 'error' with error code 'timeout' ('index' runs to completion and only reports the timeout)."
+    )]
+    pub timeout_ms: Option<u64>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq)]
@@ -85,6 +118,8 @@ pub struct BatchItem {
 pub enum BatchItemStatus {
     Ok,
     Error,
+    /// The item did not run: its `when` predicate was false or the batch deadline had passed.
+    Skipped,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema, Clone)]
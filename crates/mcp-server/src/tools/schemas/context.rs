@@ -1,4 +1,5 @@
 use context_indexer::ToolMeta;
+use context_protocol::ToolNextAction;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +15,18 @@ pub struct ContextRequest {
     )]
     pub path: Option<String>,
 
+    /// Query every member of the workspace manifest instead of a single root
+    #[schemars(
+        description = "Query every member root listed in .context-finder/workspace.json under `path`; result paths are prefixed with the member name."
+    )]
+    pub workspace: Option<bool>,
+
+    /// Member roots to query together
+    #[schemars(
+        description = "Roots to query together: workspace member names or paths relative to `path` (implies workspace mode)."
+    )]
+    pub roots: Option<Vec<String>>,
+
     /// Maximum primary results (default: 5)
     #[schemars(description = "Maximum number of primary results")]
     pub limit: Option<usize>,
@@ -37,6 +50,12 @@ pub struct ContextRequest {
     /// Auto-index time budget in milliseconds (default: 3000)
     #[schemars(description = "Auto-index time budget in milliseconds (default: 3000).")]
     pub auto_index_budget_ms: Option<u64>,
+
+    /// Opaque cursor token to continue a previous response
+    #[schemars(
+        description = "Opaque cursor token to continue a previous context response (rejected once the index changes)"
+    )]
+    pub cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -45,6 +64,13 @@ pub struct ContextResult {
     pub results: Vec<ContextHit>,
     /// Total related code found
     pub related_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub next_actions: Vec<ToolNextAction>,
+    /// Why the results came without related code, when they did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
     #[serde(default)]
     pub meta: ToolMeta,
 }
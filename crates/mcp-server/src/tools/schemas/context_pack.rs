@@ -13,6 +13,18 @@ pub struct ContextPackRequest {
     )]
     pub path: Option<String>,
 
+    /// Query every member of the workspace manifest instead of a single root
+    #[schemars(
+        description = "Query every member root listed in .context-finder/workspace.json under `path`; result paths are prefixed with the member name."
+    )]
+    pub workspace: Option<bool>,
+
+    /// Member roots to query together
+    #[schemars(
+        description = "Roots to query together: workspace member names or paths relative to `path` (implies workspace mode)."
+    )]
+    pub roots: Option<Vec<String>>,
+
     /// Maximum primary results (default: 10)
     #[schemars(description = "Maximum number of primary results")]
     pub limit: Option<usize>,
@@ -21,6 +33,12 @@ pub struct ContextPackRequest {
     #[schemars(description = "Maximum total characters in packed output")]
     pub max_chars: Option<usize>,
 
+    /// Maximum estimated tokens for packed output (optional; the tighter of the two limits wins)
+    #[schemars(
+        description = "Maximum estimated tokens in packed output (heuristic count); when max_chars is also set, the tighter bound wins"
+    )]
+    pub max_tokens: Option<usize>,
+
     /// Related chunks per primary (default: 3)
     #[schemars(description = "Maximum related chunks per primary")]
     pub max_related_per_primary: Option<usize>,
@@ -60,4 +78,16 @@ pub struct ContextPackRequest {
     /// Include debug output (adds a second MCP content block with debug JSON)
     #[schemars(description = "Include debug output as an additional response block")]
     pub trace: Option<bool>,
+
+    /// Embedding models to search and fuse (default: the profile's expert routing)
+    #[schemars(
+        description = "Embedding models to search; their rankings are fused with the profile's expert weights. Each model must already be indexed (default: route by query kind)."
+    )]
+    pub models: Option<Vec<String>>,
+
+    /// How stale indices of the requested `models` are treated: "warn" (default) or "fail"
+    #[schemars(
+        description = "How stale indices of the requested `models` (other than the primary one) are treated: 'warn' (default; proceed and report them in meta.index_state) or 'fail' (return a stale error). 'auto' behaves like 'warn'."
+    )]
+    pub stale_policy: Option<String>,
 }
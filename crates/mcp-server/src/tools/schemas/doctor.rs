@@ -1,8 +1,9 @@
-use context_indexer::ToolMeta;
+use context_indexer::{IndexProvenance, ToolCallStats, ToolMeta};
 use context_protocol::ToolNextAction;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
 
+use super::warm::PrewarmStatus;
 use crate::runtime_env;
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -12,6 +13,31 @@ pub struct DoctorRequest {
         description = "Project directory path (defaults to session root; fallback: CONTEXT_FINDER_ROOT/CONTEXT_FINDER_PROJECT_ROOT, git root, then cwd)."
     )]
     pub path: Option<String>,
+
+    /// Maximum number of chars for the serialized report
+    #[schemars(
+        description = "Maximum number of UTF-8 chars for the serialized report (default: 20000). Sections are trimmed whole-entry first: drift samples, drift, stats, models, indexes, hints."
+    )]
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorSection {
+    Stats,
+    Models,
+    Indexes,
+    Drift,
+    Hints,
+    Issues,
+    NextActions,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DoctorBudget {
+    pub max_chars: usize,
+    pub used_chars: usize,
+    pub truncated: bool,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -22,6 +48,13 @@ pub struct DoctorResult {
     pub hints: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub next_actions: Vec<ToolNextAction>,
+    /// Per-tool call count, errors, latency and bytes returned since the server started
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stats: Vec<ToolCallStats>,
+    /// Sections that lost entries to the `max_chars` budget
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub truncated_sections: Vec<DoctorSection>,
+    pub budget: DoctorBudget,
     #[serde(default)]
     pub meta: ToolMeta,
 }
@@ -33,14 +66,28 @@ pub struct DoctorEnvResult {
     pub model_manifest_exists: bool,
     pub models: Vec<DoctorModelStatus>,
     pub gpu: runtime_env::GpuEnvReport,
+    /// Device that actually ran a probe embedding with the active model (absent when the
+    /// model is not installed or the probe failed; failures are listed in `issues`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_probe: Option<context_vector_store::EmbeddingProbe>,
     pub cuda_disabled: bool,
     pub allow_cpu_fallback: bool,
+    /// Canonical roots from `CONTEXT_FINDER_ALLOWED_ROOTS` (absent when every path is allowed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_roots: Option<Vec<String>>,
+    /// Background prewarm of the `warm.on_start` projects (absent when none are configured)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prewarm: Vec<PrewarmStatus>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct DoctorModelStatus {
     pub id: String,
     pub installed: bool,
+    /// Embedding dimension declared in the manifest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimension: Option<usize>,
+    pub present_assets: Vec<String>,
     pub missing_assets: Vec<String>,
 }
 
@@ -50,10 +97,70 @@ pub struct DoctorIndexDrift {
     pub index_path: String,
     pub index_chunks: usize,
     pub corpus_chunks: usize,
+    /// Corpus chunks without a vector
     pub missing_chunks: usize,
+    /// Vectors whose chunk is no longer in the corpus
     pub extra_chunks: usize,
+    /// Vectors embedded from a document the chunk no longer renders to
+    pub stale_chunks: usize,
+    /// Vectors whose length differs from the index dimension
+    pub dimension_mismatch_chunks: usize,
     pub missing_file_samples: Vec<String>,
     pub extra_file_samples: Vec<String>,
+    /// Files with stale or misshapen vectors
+    pub stale_file_samples: Vec<String>,
+}
+
+/// One `.context-finder/indexes/<model>` directory.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DoctorIndexInfo {
+    pub model: String,
+    pub dir: String,
+    /// Total size of all files under the directory
+    pub size_bytes: u64,
+    /// `index.json` modification time (unix ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_unix_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    pub schema_compatible: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimension: Option<usize>,
+    /// The model manifest exists but lists no model for this directory
+    pub orphaned: bool,
+    /// Human-readable provenance, e.g. "index built by v0.1.0 with model bge-small on 2026-10-16"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub built_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<IndexProvenance>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DoctorGraphCacheStatus {
+    pub path: String,
+    pub size_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_mtime_ms: Option<u64>,
+    /// The cache was built against one of the current index files
+    pub fresh: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Free space next to `.context-finder` versus what a reindex may need.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DoctorDiskBudget {
+    pub dir: String,
+    /// Corpus, semantic indexes and graph cache together
+    pub index_size_bytes: u64,
+    pub free_bytes: u64,
+    /// Headroom for rewriting everything next to the old files
+    pub required_free_bytes: u64,
+    pub low: bool,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -61,6 +168,13 @@ pub struct DoctorProjectResult {
     pub root: String,
     pub corpus_path: String,
     pub has_corpus: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub corpus_schema_version: Option<u32>,
     pub indexed_models: Vec<String>,
+    pub indexes: Vec<DoctorIndexInfo>,
     pub drift: Vec<DoctorIndexDrift>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graph_cache: Option<DoctorGraphCacheStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk: Option<DoctorDiskBudget>,
 }
@@ -1,3 +1,4 @@
+use context_graph::analysis::ExplainReport;
 use context_indexer::ToolMeta;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
@@ -5,7 +6,9 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ExplainRequest {
     /// Symbol name to explain
-    #[schemars(description = "Symbol name to get detailed information about")]
+    #[schemars(
+        description = "Symbol name to get detailed information about (qualified names like 'Type::method' disambiguate)"
+    )]
     pub symbol: String,
 
     /// Project directory path
@@ -27,28 +30,18 @@ pub struct ExplainRequest {
     /// Auto-index time budget in milliseconds (default: 3000)
     #[schemars(description = "Auto-index time budget in milliseconds (default: 3000).")]
     pub auto_index_budget_ms: Option<u64>,
+
+    /// Documentation budget in characters (default: 4000)
+    #[schemars(
+        description = "Maximum characters of rendered documentation (default: 4000). Longer docs are cut and end with a truncation marker."
+    )]
+    pub doc_max_chars: Option<usize>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct ExplainResult {
-    /// Symbol name
-    pub symbol: String,
-    /// Symbol kind (function, struct, etc.)
-    pub kind: String,
-    /// File path
-    pub file: String,
-    /// Line number
-    pub line: usize,
-    /// Documentation (if available)
-    pub documentation: Option<String>,
-    /// Dependencies (what this symbol uses/calls)
-    pub dependencies: Vec<String>,
-    /// Dependents (what uses/calls this symbol)
-    pub dependents: Vec<String>,
-    /// Related tests
-    pub tests: Vec<String>,
-    /// Code content
-    pub content: String,
+    #[serde(flatten)]
+    pub report: ExplainReport,
     #[serde(default)]
     pub meta: ToolMeta,
 }
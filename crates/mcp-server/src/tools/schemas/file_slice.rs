@@ -20,6 +20,20 @@ pub struct FileSliceRequest {
     #[schemars(description = "First line to include (1-based)")]
     pub start_line: Option<usize>,
 
+    /// Slice the definition of this symbol instead of starting at `start_line`
+    #[schemars(
+        description = "Symbol to slice (matches symbol or qualified name, e.g. 'parse' or 'Parser::parse'); replaces start_line"
+    )]
+    pub symbol: Option<String>,
+
+    /// Which match to use when `symbol` is defined more than once (1-based)
+    #[schemars(description = "1-based match to pick when symbol is ambiguous (e.g. overloads)")]
+    pub occurrence: Option<usize>,
+
+    /// Extra lines around the symbol's definition (default: 0)
+    #[schemars(description = "Extra lines to include before and after the symbol (default: 0)")]
+    pub context_lines: Option<usize>,
+
     /// Maximum number of lines to return (default: 200)
     #[schemars(description = "Maximum number of lines to return (bounded)")]
     pub max_lines: Option<usize>,
@@ -43,6 +57,9 @@ pub(in crate::tools) struct FileSliceCursorV1 {
     pub(in crate::tools) max_chars: usize,
     pub(in crate::tools) next_start_line: usize,
     pub(in crate::tools) next_byte_offset: u64,
+    /// Last line of a symbol slice, so continuations stop at the same definition end
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(in crate::tools) end_line: Option<usize>,
     pub(in crate::tools) file_size_bytes: u64,
     pub(in crate::tools) file_mtime_ms: u64,
 }
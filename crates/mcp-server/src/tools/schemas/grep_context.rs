@@ -14,8 +14,23 @@ pub struct GrepContextRequest {
 
     /// Regex pattern (Rust regex syntax)
     #[schemars(description = "Regex pattern to search for (Rust regex syntax)")]
+    #[serde(default)]
     pub pattern: String,
 
+    /// Additional regex patterns; a line matches if any pattern matches (OR semantics)
+    #[schemars(
+        description = "Additional regex patterns (OR semantics); each match reports which patterns hit"
+    )]
+    #[serde(default)]
+    pub patterns: Vec<String>,
+
+    /// Regex that suppresses matches: lines matching it are never reported, even if they match
+    /// one of the search patterns
+    #[schemars(
+        description = "Regex excluding lines from matches (takes precedence over patterns)"
+    )]
+    pub exclude_pattern: Option<String>,
+
     /// Optional single file path (relative to project root)
     #[schemars(description = "Optional single file path (relative to project root)")]
     pub file: Option<String>,
@@ -64,6 +79,10 @@ pub(in crate::tools) struct GrepContextCursorV1 {
     pub(in crate::tools) tool: String,
     pub(in crate::tools) root: String,
     pub(in crate::tools) pattern: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(in crate::tools) patterns: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(in crate::tools) exclude_pattern: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(in crate::tools) file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -83,12 +102,19 @@ pub struct GrepContextHunk {
     pub start_line: usize,
     pub end_line: usize,
     pub match_lines: Vec<usize>,
+    /// Indices into `patterns` that hit each entry of `match_lines` (multi-pattern searches only).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub match_patterns: Vec<Vec<usize>>,
     pub content: String,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct GrepContextResult {
     pub pattern: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_pattern: Option<String>,
     pub source: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file: Option<String>,
@@ -99,6 +125,10 @@ pub struct GrepContextResult {
     pub after: usize,
     pub scanned_files: usize,
     pub matched_files: usize,
+    /// Filesystem scan only: entries skipped by ignore rules (an ignored directory counts once)
+    pub ignored_files: usize,
+    /// Filesystem scan only: entries that could not be opened or read as text
+    pub unreadable_files: usize,
     pub returned_matches: usize,
     pub returned_hunks: usize,
     pub used_chars: usize,
@@ -1,3 +1,4 @@
+use context_graph::analysis::ImpactReport;
 use context_indexer::ToolMeta;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
@@ -5,7 +6,9 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ImpactRequest {
     /// Symbol name to analyze
-    #[schemars(description = "Symbol name to find usages of (e.g., 'VectorStore', 'search')")]
+    #[schemars(
+        description = "Symbol name to find usages of (e.g., 'VectorStore', 'search', or a qualified 'Renderer::render')"
+    )]
     pub symbol: String,
 
     /// Project directory path
@@ -14,6 +17,18 @@ pub struct ImpactRequest {
     )]
     pub path: Option<String>,
 
+    /// Query every member of the workspace manifest instead of a single root
+    #[schemars(
+        description = "Query every member root listed in .context-finder/workspace.json under `path`; result paths are prefixed with the member name."
+    )]
+    pub workspace: Option<bool>,
+
+    /// Member roots to query together
+    #[schemars(
+        description = "Roots to query together: workspace member names or paths relative to `path` (implies workspace mode)."
+    )]
+    pub roots: Option<Vec<String>>,
+
     /// Depth of transitive usages (1=direct, 2=transitive)
     #[schemars(description = "Depth for transitive impact analysis (1-3)")]
     pub depth: Option<usize>,
@@ -35,38 +50,11 @@ pub struct ImpactRequest {
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct ImpactResult {
-    /// Symbol that was analyzed
-    pub symbol: String,
-    /// Definition location
-    pub definition: Option<SymbolLocation>,
-    /// Total usage count
-    pub total_usages: usize,
-    /// Number of files affected
-    pub files_affected: usize,
-    /// Direct usages
-    pub direct: Vec<UsageInfo>,
-    /// Transitive usages (if depth > 1)
-    pub transitive: Vec<UsageInfo>,
-    /// Related tests
-    pub tests: Vec<String>,
-    /// Is part of public API
-    pub public_api: bool,
-    /// Mermaid diagram
-    pub mermaid: String,
+    #[serde(flatten)]
+    pub report: ImpactReport,
+    /// Why the usages came without the code graph, when they did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
     #[serde(default)]
     pub meta: ToolMeta,
 }
-
-#[derive(Debug, Serialize, schemars::JsonSchema)]
-pub struct SymbolLocation {
-    pub file: String,
-    pub line: usize,
-}
-
-#[derive(Debug, Serialize, schemars::JsonSchema)]
-pub struct UsageInfo {
-    pub file: String,
-    pub line: usize,
-    pub symbol: String,
-    pub relationship: String,
-}
@@ -2,6 +2,7 @@ use context_indexer::ToolMeta;
 use context_protocol::ToolNextAction;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct IndexRequest {
@@ -28,6 +29,12 @@ pub struct IndexRequest {
     /// Full reindex (skip incremental checks)
     #[schemars(description = "Run a full reindex (skip incremental checks)")]
     pub full: Option<bool>,
+
+    /// Drop the cached code graph before indexing
+    #[schemars(
+        description = "If true, delete the on-disk code graph cache (and the in-memory graph) so the next graph tool rebuilds it"
+    )]
+    pub clear_graph_cache: Option<bool>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -36,12 +43,43 @@ pub struct IndexResult {
     pub files: usize,
     /// Number of chunks created
     pub chunks: usize,
+    /// Chunks of changed files that kept their vector because their content did not change
+    #[serde(default)]
+    pub chunks_reused: usize,
+    /// Embedded documents cut to the profile's `embedding.max_tokens`
+    #[serde(default)]
+    pub docs_token_truncated: usize,
+    /// Per-language breakdown of processed files (sums to `files`/`chunks`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub languages: BTreeMap<String, IndexLanguageStat>,
     /// Indexing time in milliseconds
     pub time_ms: u64,
+    /// Time per indexing phase (scan, chunk, embed, save), matching the progress notifications
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phases: Vec<IndexPhaseStat>,
     /// Index file path
     pub index_path: String,
+    /// Whether a graph cache file was removed (only set when `clear_graph_cache` was requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph_cache_cleared: Option<bool>,
+    /// Models whose index could not be updated, with the error; the other models were indexed
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub failed_models: BTreeMap<String, String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub next_actions: Vec<ToolNextAction>,
     #[serde(default)]
     pub meta: ToolMeta,
 }
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct IndexLanguageStat {
+    pub files: usize,
+    pub lines: usize,
+    pub chunks: usize,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct IndexPhaseStat {
+    pub phase: String,
+    pub time_ms: u64,
+}
@@ -4,6 +4,29 @@ use rmcp::schemars;
 use serde::{Deserialize, Serialize};
 
 use super::ToolNextAction;
+
+#[derive(
+    Debug, Default, Deserialize, Serialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ListFilesSort {
+    #[default]
+    Path,
+    Size,
+    Mtime,
+    Chunks,
+}
+
+#[derive(
+    Debug, Default, Deserialize, Serialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ListFilesOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ListFilesRequest {
     /// Project directory path
@@ -25,6 +48,20 @@ pub struct ListFilesRequest {
     #[schemars(description = "Maximum number of UTF-8 characters across returned file paths")]
     pub max_chars: Option<usize>,
 
+    /// Return entries as objects with size, mtime, language and chunk count (default: false)
+    #[schemars(
+        description = "Return entries as objects { path, size_bytes, mtime_ms, language, chunk_count } instead of plain paths"
+    )]
+    pub include_meta: Option<bool>,
+
+    /// Sort key applied before limit/cursor (default: path)
+    #[schemars(description = "Sort key applied before limit/cursor: path, size, mtime or chunks")]
+    pub sort: Option<ListFilesSort>,
+
+    /// Sort order (default: asc for path, desc for size/mtime/chunks)
+    #[schemars(description = "Sort order: asc or desc (default: asc for path, desc otherwise)")]
+    pub order: Option<ListFilesOrder>,
+
     /// Opaque cursor token to continue a previous response
     #[schemars(description = "Opaque cursor token to continue a previous list_files response")]
     pub cursor: Option<String>,
@@ -37,17 +74,74 @@ pub(in crate::tools) struct ListFilesCursorV1 {
     pub(in crate::tools) root: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(in crate::tools) file_pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(in crate::tools) sort: Option<ListFilesSort>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(in crate::tools) order: Option<ListFilesOrder>,
     pub(in crate::tools) last_file: String,
+    /// Sort key of `last_file` (absent for path sorting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(in crate::tools) last_key: Option<u64>,
 }
 
 pub type ListFilesTruncation = BudgetTruncation;
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+pub struct ListFilesEntry {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtime_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Number of indexed chunks (only when the chunk corpus is available)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk_count: Option<usize>,
+}
+
+/// Plain relative paths by default; metadata objects when `include_meta` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum ListFilesItems {
+    Paths(Vec<String>),
+    Entries(Vec<ListFilesEntry>),
+}
+
+impl ListFilesItems {
+    pub(in crate::tools) fn len(&self) -> usize {
+        match self {
+            Self::Paths(paths) => paths.len(),
+            Self::Entries(entries) => entries.len(),
+        }
+    }
+
+    #[cfg(test)]
+    pub(in crate::tools) fn paths(&self) -> Vec<&str> {
+        match self {
+            Self::Paths(paths) => paths.iter().map(String::as_str).collect(),
+            Self::Entries(entries) => entries.iter().map(|entry| entry.path.as_str()).collect(),
+        }
+    }
+
+    pub(in crate::tools) fn pop(&mut self) -> bool {
+        match self {
+            Self::Paths(paths) => paths.pop().is_some(),
+            Self::Entries(entries) => entries.pop().is_some(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct ListFilesResult {
     pub source: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_pattern: Option<String>,
     pub scanned_files: usize,
+    /// Filesystem scan only: entries skipped by ignore rules (an ignored directory counts once)
+    pub ignored_files: usize,
+    /// Filesystem scan only: entries whose metadata could not be read
+    pub unreadable_files: usize,
     pub returned: usize,
     pub used_chars: usize,
     pub limit: usize,
@@ -61,5 +155,5 @@ pub struct ListFilesResult {
     pub next_actions: Option<Vec<ToolNextAction>>,
     #[serde(default)]
     pub meta: ToolMeta,
-    pub files: Vec<String>,
+    pub files: ListFilesItems,
 }
@@ -16,5 +16,7 @@ pub mod repo_onboarding_pack;
 pub mod search;
 pub mod text_search;
 pub mod trace;
+pub mod warm;
+pub mod watch;
 
 pub use context_protocol::ToolNextAction;
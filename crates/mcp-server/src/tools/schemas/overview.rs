@@ -1,3 +1,4 @@
+use context_graph::analysis::OverviewReport;
 use context_indexer::ToolMeta;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
@@ -23,49 +24,18 @@ pub struct OverviewRequest {
     /// Auto-index time budget in milliseconds (default: 3000)
     #[schemars(description = "Auto-index time budget in milliseconds (default: 3000).")]
     pub auto_index_budget_ms: Option<u64>,
+
+    /// Directory depth that identifies a module in `module_graph` (default: 2)
+    #[schemars(
+        description = "Number of leading directory components that identify a module in module_graph (default: 2, e.g. crates/graph)."
+    )]
+    pub module_depth: Option<usize>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct OverviewResult {
-    /// Project info
-    pub project: ProjectInfo,
-    /// Architecture layers
-    pub layers: Vec<LayerInfo>,
-    /// Entry points
-    pub entry_points: Vec<String>,
-    /// Key types (most connected)
-    pub key_types: Vec<KeyTypeInfo>,
-    /// Graph statistics
-    pub graph_stats: GraphStats,
+    #[serde(flatten)]
+    pub report: OverviewReport,
     #[serde(default)]
     pub meta: ToolMeta,
 }
-
-#[derive(Debug, Serialize, schemars::JsonSchema)]
-pub struct ProjectInfo {
-    pub name: String,
-    pub files: usize,
-    pub chunks: usize,
-    pub lines: usize,
-}
-
-#[derive(Debug, Serialize, schemars::JsonSchema)]
-pub struct LayerInfo {
-    pub name: String,
-    pub files: usize,
-    pub role: String,
-}
-
-#[derive(Debug, Serialize, schemars::JsonSchema)]
-pub struct KeyTypeInfo {
-    pub name: String,
-    pub kind: String,
-    pub file: String,
-    pub coupling: usize,
-}
-
-#[derive(Debug, Serialize, schemars::JsonSchema)]
-pub struct GraphStats {
-    pub nodes: usize,
-    pub edges: usize,
-}
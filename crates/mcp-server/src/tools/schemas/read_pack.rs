@@ -15,6 +15,8 @@ pub enum ReadPackIntent {
     Grep,
     Query,
     Onboarding,
+    Diff,
+    Symbols,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -26,11 +28,15 @@ pub struct ReadPackRequest {
     pub path: Option<String>,
 
     /// What kind of pack to build (default: auto)
-    #[schemars(description = "What kind of pack to build (auto/file/grep/query/onboarding)")]
+    #[schemars(
+        description = "What kind of pack to build (auto/file/grep/query/onboarding/diff/symbols)"
+    )]
     pub intent: Option<ReadPackIntent>,
 
-    /// File path (relative to project root) when intent=file
-    #[schemars(description = "File path (relative to project root)")]
+    /// File path (relative to project root) when intent=file; file path or glob when intent=symbols
+    #[schemars(
+        description = "File path (relative to project root); for intent=symbols also a glob like 'src/*.rs'"
+    )]
     pub file: Option<String>,
 
     /// Regex pattern when intent=grep
@@ -41,6 +47,12 @@ pub struct ReadPackRequest {
     #[schemars(description = "Natural language query")]
     pub query: Option<String>,
 
+    /// Git ref to diff against when intent=diff (default: HEAD~1)
+    #[schemars(
+        description = "Git ref to diff the working tree against for intent=diff (branch, tag, commit or relative like 'HEAD~5'; default: HEAD~1)"
+    )]
+    pub since: Option<String>,
+
     /// Optional file path filter for grep (glob or substring)
     #[schemars(description = "Optional file path filter (glob or substring)")]
     pub file_pattern: Option<String>,
@@ -125,6 +137,92 @@ pub enum ReadPackSection {
     RepoOnboardingPack {
         result: Box<RepoOnboardingPackResult>,
     },
+    Diff {
+        result: ReadPackDiffResult,
+    },
+    Symbols {
+        result: ReadPackSymbolsResult,
+    },
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ReadPackDiffItem {
+    pub id: String,
+    /// `changed` for chunks overlapping a hunk, `neighbor` for their direct graph neighbors
+    pub role: String,
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub chunk_type: Option<String>,
+    /// Changed new-side line ranges (1-based, inclusive) inside this item
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed_lines: Vec<[usize; 2]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relationship: Option<Vec<String>>,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ReadPackDiffResult {
+    pub since: String,
+    pub changed_files: usize,
+    /// Changed units (chunks or raw hunks) across all pages
+    pub changed_units: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deleted_files: Vec<String>,
+    pub items: Vec<ReadPackDiffItem>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(in crate::tools) struct ReadPackDiffCursorV1 {
+    pub(in crate::tools) v: u32,
+    pub(in crate::tools) tool: String,
+    pub(in crate::tools) root: String,
+    pub(in crate::tools) since: String,
+    pub(in crate::tools) offset: usize,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ReadPackSymbol {
+    pub file: String,
+    pub name: String,
+    /// Qualified name (e.g. `Parser::parse`) when it differs from `name`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qualified_name: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub symbol_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ReadPackSymbolsResult {
+    /// File path or glob the outline was built for
+    pub file: String,
+    pub matched_files: usize,
+    pub symbols: Vec<ReadPackSymbol>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(in crate::tools) struct ReadPackSymbolsCursorV1 {
+    pub(in crate::tools) v: u32,
+    pub(in crate::tools) tool: String,
+    pub(in crate::tools) root: String,
+    pub(in crate::tools) file: String,
+    pub(in crate::tools) resume_file: String,
+    /// Index into `resume_file`'s outline of the first symbol not yet returned
+    pub(in crate::tools) resume_index: usize,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
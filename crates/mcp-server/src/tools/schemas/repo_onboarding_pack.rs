@@ -6,6 +6,20 @@ use serde::{Deserialize, Serialize};
 use super::file_slice::FileSliceResult;
 use super::map::MapResult;
 
+/// Parts of the onboarding pack. Under a tight `max_chars` budget they are trimmed in this
+/// order: map, build, entry points, next actions, then docs.
+#[derive(
+    Debug, Deserialize, Serialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoOnboardingSection {
+    Map,
+    Docs,
+    EntryPoints,
+    Build,
+    NextActions,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct RepoOnboardingPackRequest {
     /// Project directory path
@@ -22,7 +36,14 @@ pub struct RepoOnboardingPackRequest {
     #[schemars(description = "Limit number of map nodes returned")]
     pub map_limit: Option<usize>,
 
-    /// Optional explicit doc file paths to include (relative to project root). If omitted, uses a
+    /// Sections to include (default: all)
+    #[schemars(
+        description = "Sections to include: map, docs, entry_points, build, next_actions (default: all)"
+    )]
+    pub sections: Option<Vec<RepoOnboardingSection>>,
+
+    /// Optional explicit doc file paths to include (relative to project root). If omitted, uses
+    /// files matching `onboarding.key_docs_globs` from `.context-finder/config.json`, then a
     /// built-in prioritized list (AGENTS/README/QUICK_START/contracts/...).
     #[schemars(
         description = "Optional explicit doc file paths to include (relative to project root)"
@@ -65,6 +86,22 @@ pub enum RepoOnboardingDocsReason {
     MaxChars,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct RepoOnboardingEntryPoint {
+    pub file: String,
+    /// binary, library, script or module
+    pub kind: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct RepoOnboardingBuildFile {
+    pub file: String,
+    /// cargo, npm or make
+    pub kind: String,
+    /// Workspace members (cargo), script names (npm) or targets (make)
+    pub items: Vec<String>,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct RepoOnboardingPackBudget {
     pub max_chars: usize,
@@ -80,11 +117,19 @@ pub type RepoOnboardingNextAction = ToolNextAction;
 pub struct RepoOnboardingPackResult {
     pub version: u32,
     pub root: String,
-    pub map: MapResult,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub map: Option<MapResult>,
     pub docs: Vec<FileSliceResult>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub docs_reason: Option<RepoOnboardingDocsReason>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entry_points: Vec<RepoOnboardingEntryPoint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub build: Vec<RepoOnboardingBuildFile>,
     pub next_actions: Vec<RepoOnboardingNextAction>,
+    /// Sections that lost content to `max_chars`/limits
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub truncated_sections: Vec<RepoOnboardingSection>,
     pub budget: RepoOnboardingPackBudget,
     #[serde(default)]
     pub meta: ToolMeta,
@@ -1,5 +1,6 @@
 use context_indexer::ToolMeta;
 use context_protocol::ToolNextAction;
+use context_search::ModelTiming;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +16,18 @@ pub struct SearchRequest {
     )]
     pub path: Option<String>,
 
+    /// Query every member of the workspace manifest instead of a single root
+    #[schemars(
+        description = "Query every member root listed in .context-finder/workspace.json under `path`; result paths are prefixed with the member name."
+    )]
+    pub workspace: Option<bool>,
+
+    /// Member roots to query together
+    #[schemars(
+        description = "Roots to query together: workspace member names or paths relative to `path` (implies workspace mode)."
+    )]
+    pub roots: Option<Vec<String>>,
+
     /// Maximum results (default: 10)
     #[schemars(description = "Maximum number of results (1-50)")]
     pub limit: Option<usize>,
@@ -28,6 +41,41 @@ pub struct SearchRequest {
     /// Auto-index time budget in milliseconds (default: 3000)
     #[schemars(description = "Auto-index time budget in milliseconds (default: 3000).")]
     pub auto_index_budget_ms: Option<u64>,
+
+    /// Opaque cursor token to continue a previous response
+    #[schemars(
+        description = "Opaque cursor token to continue a previous search response (rejected once the index changes)"
+    )]
+    pub cursor: Option<String>,
+
+    /// Embedding models to search and fuse (default: the profile's expert routing)
+    #[schemars(
+        description = "Embedding models to search; their rankings are fused with the profile's expert weights. Each model must already be indexed (default: route by query kind)."
+    )]
+    pub models: Option<Vec<String>>,
+
+    /// How stale indices of the requested `models` are treated: "warn" (default) or "fail"
+    #[schemars(
+        description = "How stale indices of the requested `models` (other than the primary one) are treated: 'warn' (default; proceed and report them in meta.index_state) or 'fail' (return a stale error). 'auto' behaves like 'warn'."
+    )]
+    pub stale_policy: Option<String>,
+}
+
+/// Continuation token shared by `search` and `context`: the ranked search is re-run and the
+/// first `offset` hits are skipped, so it is only valid while the index is unchanged.
+#[derive(Debug, Serialize, Deserialize)]
+pub(in crate::tools) struct SearchCursorV1 {
+    pub(in crate::tools) v: u32,
+    pub(in crate::tools) tool: String,
+    pub(in crate::tools) root: String,
+    pub(in crate::tools) query: String,
+    pub(in crate::tools) profile: String,
+    pub(in crate::tools) index_mtime_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(in crate::tools) strategy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(in crate::tools) language: Option<String>,
+    pub(in crate::tools) offset: usize,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -46,14 +94,28 @@ pub struct SearchResult {
     pub score: f32,
     /// Code content
     pub content: String,
+    /// When the file was last indexed (unix ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indexed_at_ms: Option<u64>,
+    /// File mtime (unix ms) seen when it was indexed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_mtime_ms: Option<u64>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct SearchResponse {
     /// Search results (semantic hits)
     pub results: Vec<SearchResult>,
+    /// Earliest `indexed_at_ms` among the results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_result_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub next_actions: Vec<ToolNextAction>,
+    /// Per-model embed/search cost of the semantic stage
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub model_timings: Vec<ModelTiming>,
     #[serde(default)]
     pub meta: ToolMeta,
 }
@@ -28,10 +28,19 @@ pub struct TextSearchRequest {
     #[schemars(description = "Whether search is case-sensitive")]
     pub case_sensitive: Option<bool>,
 
-    /// Whole-word match for identifier-like patterns (default: false)
-    #[schemars(description = "If true, enforce identifier-like word boundaries")]
+    /// Whole-word match (default: false); letters of any script, digits and `_` count as word chars
+    #[schemars(
+        description = "If true, enforce word boundaries (Unicode letters/digits and '_' are word chars)"
+    )]
     pub whole_word: Option<bool>,
 
+    /// Lines of context to include before and after each match (default: 0, max: 10).
+    /// Corpus matches only take context from the chunk that contains them.
+    #[schemars(
+        description = "Lines of context before/after each match (bounded; corpus mode stays within the matching chunk)"
+    )]
+    pub context_lines: Option<usize>,
+
     /// Opaque cursor token to continue a previous response
     #[schemars(description = "Opaque cursor token to continue a previous text_search response")]
     pub cursor: Option<String>,
@@ -72,6 +81,10 @@ pub struct TextSearchResult {
     pub scanned_files: usize,
     pub matched_files: usize,
     pub skipped_large_files: usize,
+    /// Filesystem scan only: entries skipped by ignore rules (an ignored directory counts once)
+    pub ignored_files: usize,
+    /// Filesystem scan only: entries that could not be read as text
+    pub unreadable_files: usize,
     pub returned: usize,
     pub truncated: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -89,4 +102,10 @@ pub struct TextSearchMatch {
     pub line: usize,
     pub column: usize,
     pub text: String,
+    /// Lines directly before `line`, oldest first (only with `context_lines`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub before: Vec<String>,
+    /// Lines directly after `line` (only with `context_lines`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<String>,
 }
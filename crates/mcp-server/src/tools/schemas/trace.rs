@@ -1,3 +1,4 @@
+use context_graph::analysis::TraceReport;
 use context_indexer::ToolMeta;
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
@@ -31,30 +32,22 @@ pub struct TraceRequest {
     /// Auto-index time budget in milliseconds (default: 3000)
     #[schemars(description = "Auto-index time budget in milliseconds (default: 3000).")]
     pub auto_index_budget_ms: Option<u64>,
+
+    /// Maximum number of distinct paths to return (default: 1, max: 5)
+    #[schemars(
+        description = "Maximum number of distinct paths to return, cheapest first (default: 1, max: 5). Extra paths are returned in `alternates`."
+    )]
+    pub max_paths: Option<usize>,
+
+    /// Symbol every returned path must pass through
+    #[schemars(description = "Optional symbol name that every returned path must pass through")]
+    pub via: Option<String>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct TraceResult {
-    /// Whether path was found
-    pub found: bool,
-    /// Call chain path
-    pub path: Vec<TraceStep>,
-    /// Path depth
-    pub depth: usize,
-    /// Mermaid sequence diagram
-    pub mermaid: String,
+    #[serde(flatten)]
+    pub report: TraceReport,
     #[serde(default)]
     pub meta: ToolMeta,
 }
-
-#[derive(Debug, Serialize, schemars::JsonSchema)]
-pub struct TraceStep {
-    /// Symbol name
-    pub symbol: String,
-    /// File path
-    pub file: String,
-    /// Line number
-    pub line: usize,
-    /// Relationship to next step
-    pub relationship: Option<String>,
-}
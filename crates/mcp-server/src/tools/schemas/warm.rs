@@ -0,0 +1,78 @@
+use context_indexer::ToolMeta;
+use context_vector_store::EmbeddingProbe;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WarmRequest {
+    /// Project directory path
+    #[schemars(
+        description = "Project directory to warm (defaults to session root; fallback: CONTEXT_FINDER_ROOT/CONTEXT_FINDER_PROJECT_ROOT, git root, then cwd)."
+    )]
+    pub path: Option<String>,
+
+    /// Also load or build the code graph for this language
+    #[schemars(
+        description = "Also load (or build and cache) the code graph for this language: rust, python, javascript, typescript (default: no graph)"
+    )]
+    pub language: Option<String>,
+
+    /// Also initialize the embedding session
+    #[schemars(
+        description = "If true, also load the active embedding model and run a probe embedding so the ONNX session is ready (default: false)"
+    )]
+    pub embedder: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmStageKind {
+    /// Semantic indexes and chunk corpus loaded into the in-process engine
+    Engine,
+    Graph,
+    Embedder,
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct WarmStage {
+    pub stage: WarmStageKind,
+    pub duration_ms: u64,
+    /// `graph` only: the graph for this language was already held by the engine
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_hit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WarmResult {
+    pub root: String,
+    /// Requested stages in the order they ran
+    pub stages: Vec<WarmStage>,
+    pub total_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_probe: Option<EmbeddingProbe>,
+    #[serde(default)]
+    pub meta: ToolMeta,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PrewarmState {
+    Pending,
+    Running,
+    Done,
+    Failed,
+}
+
+/// Background prewarm of one `warm.on_start` project, as reported by `doctor`.
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct PrewarmStatus {
+    pub root: String,
+    pub state: PrewarmState,
+    pub stages: Vec<WarmStage>,
+    /// Time spent waiting for tool calls to finish before starting a stage
+    pub yielded_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -0,0 +1,93 @@
+use context_indexer::{HealthSnapshot, ToolMeta};
+use context_protocol::ToolNextAction;
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchAction {
+    Start,
+    Stop,
+    Status,
+    Trigger,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WatchRequest {
+    /// Project directory path
+    #[schemars(
+        description = "Project directory to watch (defaults to session root; fallback: CONTEXT_FINDER_ROOT/CONTEXT_FINDER_PROJECT_ROOT, git root, then cwd)."
+    )]
+    pub path: Option<String>,
+
+    /// What to do with the watcher
+    #[schemars(
+        description = "start: begin incremental reindexing on file changes; stop: shut the watcher down; status: watcher health plus the persisted health.json; trigger: queue an immediate reindex"
+    )]
+    pub action: WatchAction,
+
+    /// Reason recorded for a manual trigger
+    #[schemars(description = "Reason recorded in health.json for `trigger` (default: \"manual\")")]
+    pub reason: Option<String>,
+
+    /// Keep the profile's expert roster models fresh as well (start only)
+    #[schemars(
+        description = "If true, `start` also reindexes the profile's expert roster models (default: primary model only)"
+    )]
+    pub experts: Option<bool>,
+}
+
+/// Effective watcher timings (from `.context-finder/config.json` `watch`, else defaults).
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct WatchConfig {
+    pub debounce_ms: u64,
+    pub max_batch_wait_ms: u64,
+    pub poll_interval_ms: u64,
+    /// Cap for the debounce while events arrive in bursts
+    pub max_debounce_ms: u64,
+    pub models: Vec<String>,
+}
+
+/// Live health reported by the running watcher.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WatchHealth {
+    pub indexing: bool,
+    pub pending_events: usize,
+    /// Quiet period the watcher currently waits for (grows during event bursts)
+    pub adaptive_debounce_ms: u64,
+    pub consecutive_failures: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success_unix_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p95_duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_throughput_files_per_sec: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_index_size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WatchResult {
+    pub root: String,
+    pub action: WatchAction,
+    /// A watcher is running for `root` after this call
+    pub active: bool,
+    /// `stop` found and shut down a running watcher
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<WatchConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<WatchHealth>,
+    /// Last snapshot written to `.context-finder/health.json` (by this or any other process)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persisted_health: Option<HealthSnapshot>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub next_actions: Vec<ToolNextAction>,
+    #[serde(default)]
+    pub meta: ToolMeta,
+}
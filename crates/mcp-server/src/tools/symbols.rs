@@ -0,0 +1,87 @@
+use anyhow::{Context as AnyhowContext, Result};
+use context_code_chunker::Chunker;
+use context_indexer::FileScanner;
+use std::path::{Path, PathBuf};
+
+use super::file_slice::{chunker_config_for_symbols, MAX_SYMBOL_FILE_BYTES};
+use super::grep_context::canonicalize_request_file;
+use super::paths::normalize_relative_path;
+use super::schemas::read_pack::ReadPackSymbol;
+use super::ContextFinderService;
+
+pub(super) fn is_outline_glob(pattern: &str) -> bool {
+    pattern.contains('*') || pattern.contains('?')
+}
+
+/// Files an outline request covers, sorted by path: the file itself, or every project file
+/// matching a glob.
+pub(super) async fn symbol_outline_candidates(
+    root: &Path,
+    file: &str,
+) -> Result<Vec<(String, PathBuf)>> {
+    if !is_outline_glob(file) {
+        return Ok(vec![canonicalize_request_file(root, file)?]);
+    }
+
+    let mut files: Vec<String> = match ContextFinderService::load_corpus_files(root).await? {
+        Some(files) => files,
+        None => FileScanner::new(root)
+            .scan()
+            .into_iter()
+            .filter_map(|p| normalize_relative_path(root, &p))
+            .collect(),
+    };
+    files.retain(|rel| ContextFinderService::matches_file_pattern(rel, Some(file)));
+    files.sort();
+    Ok(files
+        .into_iter()
+        .map(|rel| {
+            let path = root.join(&rel);
+            (rel, path)
+        })
+        .collect())
+}
+
+/// Definitions in `path` in source order, chunked the same way `file_slice` resolves symbols;
+/// adjacent parts of one definition merge into a single entry.
+pub(super) fn outline_file_symbols(display_file: &str, path: &Path) -> Result<Vec<ReadPackSymbol>> {
+    let size = std::fs::metadata(path)
+        .with_context(|| format!("Failed to stat '{display_file}'"))?
+        .len();
+    if size > MAX_SYMBOL_FILE_BYTES {
+        anyhow::bail!("File '{display_file}' is too large to outline ({size} bytes)");
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read '{display_file}'"))?;
+    let chunks = Chunker::new(chunker_config_for_symbols())
+        .chunk_str(&content, Some(display_file))
+        .with_context(|| format!("Failed to parse '{display_file}' for symbols"))?;
+
+    let mut symbols: Vec<ReadPackSymbol> = Vec::new();
+    for chunk in chunks {
+        let meta = chunk.metadata;
+        let Some(name) = meta.symbol_name else {
+            continue;
+        };
+        let qualified_name = meta.qualified_name.filter(|qualified| *qualified != name);
+        if let Some(prev) = symbols.last_mut() {
+            if prev.name == name
+                && prev.qualified_name == qualified_name
+                && chunk.start_line <= prev.end_line + 1
+            {
+                prev.end_line = prev.end_line.max(chunk.end_line);
+                continue;
+            }
+        }
+        symbols.push(ReadPackSymbol {
+            file: display_file.to_string(),
+            name,
+            qualified_name,
+            symbol_type: meta.chunk_type.map(|ct| ct.as_str().to_string()),
+            parent: meta.parent_scope,
+            start_line: chunk.start_line,
+            end_line: chunk.end_line,
+        });
+    }
+    Ok(symbols)
+}
@@ -1,4 +1,3 @@
-use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub(super) fn unix_ms(time: SystemTime) -> u64 {
@@ -7,13 +6,6 @@ pub(super) fn unix_ms(time: SystemTime) -> u64 {
         .unwrap_or_default()
 }
 
-pub(super) fn path_has_extension_ignore_ascii_case(path: &str, ext: &str) -> bool {
-    Path::new(path)
-        .extension()
-        .and_then(|e| e.to_str())
-        .is_some_and(|e| e.eq_ignore_ascii_case(ext))
-}
-
 pub(super) fn hex_encode_lower(bytes: &[u8]) -> String {
     use std::fmt::Write;
 
@@ -0,0 +1,233 @@
+use anyhow::{Context as AnyhowContext, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Workspace manifest, stored next to the project's index in `.context-finder/`.
+pub(super) const WORKSPACE_MANIFEST: &str = "workspace.json";
+
+/// One project root taking part in a multi-root query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct WorkspaceMember {
+    /// Prefix for this member's result paths
+    pub(super) name: String,
+    /// Canonical member root
+    pub(super) root: PathBuf,
+}
+
+impl WorkspaceMember {
+    pub(super) fn display_root(&self) -> String {
+        self.root.to_string_lossy().into_owned()
+    }
+
+    /// `file` (relative to the member root) as reported in federated results.
+    pub(super) fn prefixed(&self, file: &str) -> String {
+        format!("{}/{file}", self.name)
+    }
+}
+
+#[derive(Deserialize)]
+struct WorkspaceManifest {
+    #[serde(default)]
+    members: Vec<ManifestMember>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ManifestMember {
+    Path(String),
+    Named {
+        path: String,
+        #[serde(default)]
+        name: Option<String>,
+    },
+}
+
+pub(super) fn workspace_manifest_path(root: &Path) -> PathBuf {
+    root.join(".context-finder").join(WORKSPACE_MANIFEST)
+}
+
+/// Members listed in `root`'s workspace manifest, or `None` when it has none. Member paths
+/// are resolved against `root`; a member without a `name` is named after its directory.
+pub(super) fn load_workspace_members(root: &Path) -> Result<Option<Vec<WorkspaceMember>>> {
+    let path = workspace_manifest_path(root);
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+    let manifest: WorkspaceManifest = serde_json::from_slice(&bytes)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let mut members: Vec<WorkspaceMember> = Vec::new();
+    for entry in manifest.members {
+        let (raw_path, name) = match entry {
+            ManifestMember::Path(path) => (path, None),
+            ManifestMember::Named { path, name } => (path, name),
+        };
+        let member = resolve_member(root, &raw_path, name.as_deref())?;
+        push_member(&mut members, member)?;
+    }
+    Ok(Some(members))
+}
+
+/// Members a request covers: every manifest member when `requested` is empty, otherwise each
+/// entry matched against manifest member names first and then treated as a path relative to
+/// `root`.
+pub(super) fn select_workspace_members(
+    root: &Path,
+    manifest: Option<Vec<WorkspaceMember>>,
+    requested: &[String],
+) -> Result<Vec<WorkspaceMember>> {
+    let manifest_path = workspace_manifest_path(root);
+    if requested.is_empty() {
+        let members = manifest.with_context(|| {
+            format!(
+                "No workspace manifest at {}; list member roots in it or pass `roots`",
+                manifest_path.display()
+            )
+        })?;
+        anyhow::ensure!(
+            !members.is_empty(),
+            "Workspace manifest {} lists no members",
+            manifest_path.display()
+        );
+        return Ok(members);
+    }
+
+    let manifest = manifest.unwrap_or_default();
+    let mut members: Vec<WorkspaceMember> = Vec::new();
+    for raw in requested
+        .iter()
+        .map(|raw| raw.trim())
+        .filter(|raw| !raw.is_empty())
+    {
+        let member = match manifest.iter().find(|member| member.name == raw) {
+            Some(member) => member.clone(),
+            None => {
+                let resolved = resolve_member(root, raw, None)?;
+                manifest
+                    .iter()
+                    .find(|member| member.root == resolved.root)
+                    .cloned()
+                    .unwrap_or(resolved)
+            }
+        };
+        push_member(&mut members, member)?;
+    }
+    anyhow::ensure!(!members.is_empty(), "`roots` must name at least one root");
+    Ok(members)
+}
+
+fn resolve_member(root: &Path, raw_path: &str, name: Option<&str>) -> Result<WorkspaceMember> {
+    let resolved = root
+        .join(raw_path)
+        .canonicalize()
+        .with_context(|| format!("Workspace root '{raw_path}' not found"))?;
+    let name = match name.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(name) => name.to_string(),
+        None => resolved
+            .file_name()
+            .map_or_else(|| "root".to_string(), |n| n.to_string_lossy().into_owned()),
+    };
+    anyhow::ensure!(
+        !name.contains('/') && !name.contains('\\'),
+        "Workspace member name '{name}' must not contain path separators"
+    );
+    Ok(WorkspaceMember {
+        name,
+        root: resolved,
+    })
+}
+
+/// Adds `member` unless its root is already present; two roots may not share a name.
+fn push_member(members: &mut Vec<WorkspaceMember>, member: WorkspaceMember) -> Result<()> {
+    if members.iter().any(|existing| existing.root == member.root) {
+        return Ok(());
+    }
+    anyhow::ensure!(
+        members.iter().all(|existing| existing.name != member.name),
+        "Two workspace roots are both named '{}'; give them distinct names in {WORKSPACE_MANIFEST}",
+        member.name
+    );
+    members.push(member);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> (tempfile::TempDir, PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join(".context-finder")).unwrap();
+        std::fs::create_dir_all(root.join("services/api")).unwrap();
+        std::fs::create_dir_all(root.join("libs/core")).unwrap();
+        (tmp, root)
+    }
+
+    #[test]
+    fn manifest_members_accept_names_and_bare_paths() {
+        let (_tmp, root) = workspace();
+        std::fs::write(
+            workspace_manifest_path(&root),
+            r#"{"members": [{"name": "backend", "path": "services/api"}, "libs/core", "libs/core"]}"#,
+        )
+        .unwrap();
+
+        let members = load_workspace_members(&root).unwrap().unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].name, "backend");
+        assert_eq!(members[0].root, root.join("services/api"));
+        assert_eq!(members[1].name, "core");
+        assert_eq!(members[1].prefixed("src/lib.rs"), "core/src/lib.rs");
+    }
+
+    #[test]
+    fn missing_manifest_is_none_and_requires_explicit_roots() {
+        let (_tmp, root) = workspace();
+        let manifest = load_workspace_members(&root).unwrap();
+        assert!(manifest.is_none());
+        assert!(select_workspace_members(&root, None, &[]).is_err());
+
+        let members = select_workspace_members(&root, None, &["libs/core".to_string()]).unwrap();
+        assert_eq!(members[0].name, "core");
+    }
+
+    #[test]
+    fn requested_roots_match_member_names_then_paths() {
+        let (_tmp, root) = workspace();
+        let manifest = vec![WorkspaceMember {
+            name: "backend".to_string(),
+            root: root.join("services/api"),
+        }];
+
+        let members = select_workspace_members(
+            &root,
+            Some(manifest),
+            &[
+                "backend".to_string(),
+                "services/api".to_string(),
+                "libs/core".to_string(),
+            ],
+        )
+        .unwrap();
+        let names: Vec<&str> = members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["backend", "core"]);
+    }
+
+    #[test]
+    fn duplicate_member_names_are_rejected() {
+        let (_tmp, root) = workspace();
+        std::fs::create_dir_all(root.join("other/core")).unwrap();
+        let err = select_workspace_members(
+            &root,
+            None,
+            &["libs/core".to_string(), "other/core".to_string()],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("both named 'core'"));
+    }
+}
@@ -0,0 +1,236 @@
+#![cfg(unix)]
+
+use anyhow::{Context, Result};
+use rmcp::{
+    model::{CallToolRequestParam, CallToolResult},
+    service::{RunningService, ServiceExt},
+    transport::TokioChildProcess,
+    RoleClient,
+};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::process::Command;
+
+fn locate_context_finder_mcp_bin() -> Result<PathBuf> {
+    if let Some(path) = option_env!("CARGO_BIN_EXE_context-finder-mcp") {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(target_profile_dir) = exe.parent().and_then(|p| p.parent()) {
+            let candidate = target_profile_dir.join("context-finder-mcp");
+            if candidate.exists() {
+                return Ok(candidate);
+            }
+        }
+    }
+
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let repo_root = manifest_dir
+        .ancestors()
+        .nth(2)
+        .context("failed to resolve repo root from CARGO_MANIFEST_DIR")?;
+    for rel in [
+        "target/debug/context-finder-mcp",
+        "target/release/context-finder-mcp",
+    ] {
+        let candidate = repo_root.join(rel);
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+    }
+
+    anyhow::bail!("failed to locate context-finder-mcp binary");
+}
+
+async fn call(
+    service: &RunningService<RoleClient, ()>,
+    tool: &str,
+    args: Value,
+) -> Result<(CallToolResult, Value)> {
+    let result = tokio::time::timeout(
+        Duration::from_secs(20),
+        service.call_tool(CallToolRequestParam {
+            name: tool.to_string().into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .with_context(|| format!("timeout calling {tool}"))??;
+    let json = if result.is_error == Some(true) {
+        result
+            .structured_content
+            .clone()
+            .with_context(|| format!("{tool} error without structured content"))?
+    } else {
+        let text = result
+            .content
+            .first()
+            .and_then(|c| c.as_text())
+            .map(|t| t.text.as_str())
+            .with_context(|| format!("{tool} did not return text content"))?;
+        serde_json::from_str(text).with_context(|| format!("{tool} output is not valid JSON"))?
+    };
+    Ok((result, json))
+}
+
+fn assert_rejected(tool: &str, result: &CallToolResult, json: &Value) {
+    assert_eq!(
+        result.is_error,
+        Some(true),
+        "{tool} should be rejected: {json}"
+    );
+    assert_eq!(
+        json.pointer("/error/code").and_then(Value::as_str),
+        Some("invalid_request"),
+        "{tool}: {json}"
+    );
+}
+
+#[tokio::test]
+async fn allowed_roots_reject_outside_paths_and_symlink_escapes() -> Result<()> {
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let base = tmp.path().canonicalize().context("canonicalize tempdir")?;
+    let allowed = base.join("allowed");
+    let outside = base.join("outside");
+    std::fs::create_dir_all(allowed.join("src")).context("mkdir allowed/src")?;
+    std::fs::create_dir_all(&outside).context("mkdir outside")?;
+    std::fs::write(
+        allowed.join("src").join("lib.rs"),
+        "pub fn visible() -> u32 {\n    1\n}\n",
+    )
+    .context("write lib.rs")?;
+    std::fs::write(
+        outside.join("secret.rs"),
+        "pub const TOKEN: &str = \"TOP_SECRET_VALUE\";\n",
+    )
+    .context("write secret.rs")?;
+    std::os::unix::fs::symlink(
+        outside.join("secret.rs"),
+        allowed.join("src").join("leak.rs"),
+    )
+    .context("symlink file")?;
+    std::os::unix::fs::symlink(&outside, allowed.join("leakdir")).context("symlink dir")?;
+
+    let bin = locate_context_finder_mcp_bin()?;
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env_remove("CONTEXT_FINDER_ROOT");
+    cmd.env_remove("CONTEXT_FINDER_PROJECT_ROOT");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+    cmd.env("CONTEXT_FINDER_ALLOWED_ROOTS", &allowed);
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let allowed_path = allowed.to_string_lossy().to_string();
+    let outside_path = outside.to_string_lossy().to_string();
+    let leakdir_path = allowed.join("leakdir").to_string_lossy().to_string();
+
+    // Roots outside the allowlist, directly or through a symlinked directory.
+    for path in [&outside_path, &leakdir_path] {
+        let (result, json) =
+            call(&service, "list_files", serde_json::json!({ "path": path })).await?;
+        assert_rejected("list_files", &result, &json);
+        let message = json
+            .pointer("/error/message")
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        assert!(
+            message.contains("CONTEXT_FINDER_ALLOWED_ROOTS"),
+            "{message}"
+        );
+    }
+    let (result, json) = call(
+        &service,
+        "text_search",
+        serde_json::json!({ "path": outside_path, "pattern": "TOP_SECRET_VALUE" }),
+    )
+    .await?;
+    assert_rejected("text_search", &result, &json);
+
+    // Files reached through a symlink inside the allowed root.
+    let (result, json) = call(
+        &service,
+        "file_slice",
+        serde_json::json!({ "path": allowed_path, "file": "src/leak.rs" }),
+    )
+    .await?;
+    assert_eq!(
+        result.is_error,
+        Some(true),
+        "file_slice followed a symlink: {json}"
+    );
+    let (result, json) = call(
+        &service,
+        "grep_context",
+        serde_json::json!({ "path": allowed_path, "pattern": "TOP_SECRET", "file": "src/leak.rs" }),
+    )
+    .await?;
+    assert_eq!(
+        result.is_error,
+        Some(true),
+        "grep_context followed a symlink: {json}"
+    );
+
+    // Scans of the allowed root must not surface symlinked content.
+    let (result, json) = call(
+        &service,
+        "grep_context",
+        serde_json::json!({ "path": allowed_path, "pattern": "TOP_SECRET" }),
+    )
+    .await?;
+    assert_ne!(result.is_error, Some(true), "grep_context failed: {json}");
+    assert_eq!(
+        json.get("returned_matches").and_then(Value::as_u64),
+        Some(0),
+        "{json}"
+    );
+    let (result, json) = call(
+        &service,
+        "text_search",
+        serde_json::json!({ "path": allowed_path, "pattern": "TOP_SECRET" }),
+    )
+    .await?;
+    assert_ne!(result.is_error, Some(true), "text_search failed: {json}");
+    assert_eq!(
+        json.get("returned").and_then(Value::as_u64),
+        Some(0),
+        "{json}"
+    );
+    let (result, json) = call(
+        &service,
+        "list_files",
+        serde_json::json!({ "path": allowed_path }),
+    )
+    .await?;
+    assert_ne!(result.is_error, Some(true), "list_files failed: {json}");
+    let listed = json.to_string();
+    assert!(listed.contains("src/lib.rs"), "{listed}");
+    assert!(!listed.contains("leak"), "{listed}");
+
+    let (result, json) = call(
+        &service,
+        "doctor",
+        serde_json::json!({ "path": allowed_path }),
+    )
+    .await?;
+    assert_ne!(result.is_error, Some(true), "doctor failed: {json}");
+    let roots: Vec<&str> = json
+        .pointer("/env/allowed_roots")
+        .and_then(Value::as_array)
+        .context("doctor should list allowed_roots")?
+        .iter()
+        .filter_map(Value::as_str)
+        .collect();
+    assert_eq!(roots, vec![allowed_path.as_str()]);
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
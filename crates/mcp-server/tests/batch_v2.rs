@@ -322,3 +322,241 @@ async fn batch_v2_ref_to_failed_item_data_returns_error() -> Result<()> {
     service.cancel().await.context("shutdown mcp service")?;
     Ok(())
 }
+
+#[tokio::test]
+async fn batch_v2_interpolates_format_templates() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    std::fs::write(
+        root.join("src").join("lib.rs"),
+        "// greet is documented elsewhere\npub fn greet() {}\n",
+    )
+    .context("write lib.rs")?;
+
+    let args = serde_json::json!({
+        "version": 2,
+        "path": root.to_string_lossy(),
+        "max_chars": 20000,
+        "items": [
+            { "id": "sym", "tool": "text_search", "input": { "pattern": "greet", "file_pattern": "src/*" } },
+            { "id": "def", "tool": "grep_context", "input": {
+                "pattern": { "$format": "fn {0}\\(", "args": [{ "$ref": "#/items/sym/data/pattern" }] },
+                "before": 0,
+                "after": 0
+            } }
+        ]
+    });
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "batch".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling batch")??;
+
+    assert_ne!(result.is_error, Some(true), "batch returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("batch did not return text content")?;
+    let json: Value = serde_json::from_str(text).context("batch output is not valid JSON")?;
+
+    let def_item = json
+        .get("items")
+        .and_then(Value::as_array)
+        .context("batch items missing")?
+        .iter()
+        .find(|v| v.get("id").and_then(Value::as_str) == Some("def"))
+        .context("missing def item")?;
+    assert_eq!(def_item.get("status").and_then(Value::as_str), Some("ok"));
+    let content = def_item
+        .pointer("/data/hunks/0/content")
+        .and_then(Value::as_str)
+        .context("def item missing hunk content")?;
+    assert!(content.contains("pub fn greet()"), "got: {content}");
+    assert!(!content.contains("documented elsewhere"), "got: {content}");
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_v2_skips_items_by_when_and_refs_fall_back_to_default() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    std::fs::write(root.join("src").join("a.txt"), "TARGET\n").context("write a.txt")?;
+
+    let args = serde_json::json!({
+        "version": 2,
+        "path": root.to_string_lossy(),
+        "max_chars": 20000,
+        "items": [
+            { "id": "search", "tool": "text_search", "input": { "pattern": "MISSING", "file_pattern": "src/*" } },
+            { "id": "grep", "tool": "grep_context",
+              "when": { "exists": "#/items/search/data/matches/0" },
+              "input": { "pattern": "MISSING", "before": 0, "after": 0 } },
+            { "id": "fallback", "tool": "text_search",
+              "when": { "eq": ["#/items/grep/status", "skipped"] },
+              "input": { "pattern": "TARGET", "file_pattern": "src/*" } },
+            { "id": "slice", "tool": "file_slice", "input": {
+                "file": { "$ref": "#/items/grep/data/file", "$default": "src/a.txt" },
+                "start_line": 1,
+                "max_lines": 1
+            } }
+        ]
+    });
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "batch".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling batch")??;
+
+    assert_ne!(result.is_error, Some(true), "batch returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("batch did not return text content")?;
+    let json: Value = serde_json::from_str(text).context("batch output is not valid JSON")?;
+    let items = json
+        .get("items")
+        .and_then(Value::as_array)
+        .context("batch items missing")?;
+    let item = |id: &str| {
+        items
+            .iter()
+            .find(|v| v.get("id").and_then(Value::as_str) == Some(id))
+            .with_context(|| format!("missing item {id}"))
+    };
+
+    let grep = item("grep")?;
+    assert_eq!(grep.get("status").and_then(Value::as_str), Some("skipped"));
+    assert!(grep.get("data").is_some_and(Value::is_null));
+    assert_eq!(
+        item("fallback")?.get("status").and_then(Value::as_str),
+        Some("ok")
+    );
+    let slice = item("slice")?;
+    assert_eq!(slice.get("status").and_then(Value::as_str), Some("ok"));
+    assert_eq!(
+        slice.pointer("/data/file").and_then(Value::as_str),
+        Some("src/a.txt")
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_v2_reports_item_timeouts_and_skips_items_past_deadline() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    std::fs::write(root.join("src").join("lib.rs"), "pub fn alpha() {}\n")
+        .context("write lib.rs")?;
+
+    let args = serde_json::json!({
+        "version": 2,
+        "path": root.to_string_lossy(),
+        "deadline_ms": 1,
+        "items": [
+            { "id": "idx", "tool": "index", "timeout_ms": 1, "input": {} },
+            { "id": "late", "tool": "text_search", "input": { "pattern": "alpha" } }
+        ]
+    });
+    let result = tokio::time::timeout(
+        Duration::from_secs(30),
+        service.call_tool(CallToolRequestParam {
+            name: "batch".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling batch")??;
+
+    assert_ne!(result.is_error, Some(true), "batch returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("batch did not return text content")?;
+    let json: Value = serde_json::from_str(text).context("batch output is not valid JSON")?;
+
+    let idx = json.pointer("/items/0").context("missing idx item")?;
+    assert_eq!(idx.get("status").and_then(Value::as_str), Some("error"));
+    assert_eq!(
+        idx.pointer("/error/code").and_then(Value::as_str),
+        Some("timeout")
+    );
+    assert!(
+        idx.get("data").is_some_and(|d| !d.is_null()),
+        "index should run to completion: {idx}"
+    );
+    let late = json.pointer("/items/1").context("missing late item")?;
+    assert_eq!(late.get("status").and_then(Value::as_str), Some("skipped"));
+    assert!(late
+        .get("message")
+        .and_then(Value::as_str)
+        .is_some_and(|m| m.contains("deadline_ms=1")));
+
+    let index_path = root.join(".context-finder/indexes/bge-small/index.json");
+    let index_bytes = std::fs::read(&index_path).context("index was not written")?;
+    serde_json::from_slice::<Value>(&index_bytes).context("index is not complete JSON")?;
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use anyhow::{Context, Result};
+use context_code_chunker::{Chunker, ChunkerConfig};
+use context_vector_store::ChunkCorpus;
+use rmcp::{
+    model::CallToolRequestParam,
+    service::{RoleClient, RunningService, ServiceExt},
+    transport::TokioChildProcess,
+};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::process::Command;
+
+fn locate_context_finder_mcp_bin() -> Result<PathBuf> {
+    if let Some(path) = option_env!("CARGO_BIN_EXE_context-finder-mcp") {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(target_profile_dir) = exe.parent().and_then(|p| p.parent()) {
+            let candidate = target_profile_dir.join("context-finder-mcp");
+            if candidate.exists() {
+                return Ok(candidate);
+            }
+        }
+    }
+
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let repo_root = manifest_dir
+        .ancestors()
+        .nth(2)
+        .context("failed to resolve repo root from CARGO_MANIFEST_DIR")?;
+    for rel in [
+        "target/debug/context-finder-mcp",
+        "target/release/context-finder-mcp",
+    ] {
+        let candidate = repo_root.join(rel);
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+    }
+
+    anyhow::bail!("failed to locate context-finder-mcp binary")
+}
+
+const FIXTURE: &[(&str, &str)] = &[(
+    "src/report.rs",
+    "/// Writes every row, optionally preceded by a header line.\n///\n/// Long docs are fine here; the caller controls the budget.\n///\n/// ```\n/// let mut out = Vec::new();\n/// write_report(&mut out, &[\"a\".into()], true).unwrap();\n/// ```\n#[inline]\npub fn write_report<W: std::io::Write>(\n    out: &mut W,\n    rows: &[String],\n    header: bool,\n) -> std::io::Result<usize> {\n    let mut written = 0;\n    if header {\n        written += out.write(b\"# report\\n\")?;\n    }\n    for row in rows {\n        written += out.write(row.as_bytes())?;\n    }\n    Ok(written)\n}\n",
+)];
+
+/// Write sources plus a corpus/index built with the real chunker; no embedding model needed.
+async fn write_fixture(root: &std::path::Path) -> Result<()> {
+    let chunker = Chunker::new(ChunkerConfig::default());
+    let mut corpus = ChunkCorpus::new();
+    let mut id_map = serde_json::Map::new();
+
+    for (rel, content) in FIXTURE {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().context("fixture parent")?)
+            .context("mkdir fixture dir")?;
+        std::fs::write(&path, content).context("write fixture")?;
+
+        let chunks = chunker
+            .chunk_str(content, Some(rel))
+            .context("chunk fixture")?;
+        for chunk in &chunks {
+            id_map.insert(
+                id_map.len().to_string(),
+                Value::String(format!(
+                    "{}:{}:{}",
+                    chunk.file_path, chunk.start_line, chunk.end_line
+                )),
+            );
+        }
+        corpus.set_file_chunks((*rel).to_string(), chunks);
+    }
+
+    let index_dir = root
+        .join(".context-finder")
+        .join("indexes")
+        .join("bge-small");
+    std::fs::create_dir_all(&index_dir).context("mkdir indexes")?;
+    corpus
+        .save(root.join(".context-finder").join("corpus.json"))
+        .await
+        .context("save corpus")?;
+
+    let index = serde_json::json!({
+        "schema_version": 3,
+        "dimension": 384,
+        "next_id": id_map.len(),
+        "id_map": id_map,
+        "vectors": {},
+    });
+    std::fs::write(index_dir.join("index.json"), index.to_string()).context("write index.json")?;
+    Ok(())
+}
+
+async fn call_json(
+    service: &RunningService<RoleClient, ()>,
+    tool: &str,
+    args: Value,
+) -> Result<Value> {
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: tool.to_string().into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .with_context(|| format!("timeout calling {tool}"))??;
+
+    assert_ne!(result.is_error, Some(true), "{tool} returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .with_context(|| format!("{tool} did not return text content"))?;
+    serde_json::from_str(text).with_context(|| format!("{tool} output is not valid JSON"))
+}
+
+#[tokio::test]
+async fn explain_renders_signature_docs_and_examples() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    write_fixture(root).await?;
+
+    let explain = call_json(
+        &service,
+        "explain",
+        serde_json::json!({
+            "symbol": "write_report",
+            "path": root.to_string_lossy(),
+            "language": "rust",
+            "auto_index": false,
+        }),
+    )
+    .await?;
+    let signature = explain
+        .get("signature")
+        .and_then(Value::as_str)
+        .context("signature missing")?;
+    assert!(
+        signature.starts_with("pub fn write_report<W: std::io::Write>(")
+            && signature.contains("header: bool,")
+            && signature.ends_with("-> std::io::Result<usize>"),
+        "unexpected signature: {signature:?}"
+    );
+    let documentation = explain
+        .get("documentation")
+        .and_then(Value::as_str)
+        .context("documentation missing")?;
+    assert!(
+        documentation.starts_with("Writes every row") && !documentation.contains("///"),
+        "unexpected documentation: {documentation:?}"
+    );
+    let examples: Vec<&str> = explain
+        .get("examples")
+        .and_then(Value::as_array)
+        .context("examples missing")?
+        .iter()
+        .filter_map(Value::as_str)
+        .collect();
+    assert_eq!(examples.len(), 1, "examples: {examples:?}");
+    assert!(examples[0].contains("write_report(&mut out"));
+
+    let clipped = call_json(
+        &service,
+        "explain",
+        serde_json::json!({
+            "symbol": "write_report",
+            "path": root.to_string_lossy(),
+            "language": "rust",
+            "auto_index": false,
+            "doc_max_chars": 30,
+        }),
+    )
+    .await?;
+    let documentation = clipped
+        .get("documentation")
+        .and_then(Value::as_str)
+        .context("documentation missing")?;
+    assert!(
+        documentation.starts_with("Writes every row")
+            && documentation.contains("[… documentation truncated:"),
+        "expected truncation marker, got: {documentation:?}"
+    );
+    assert!(
+        clipped
+            .get("examples")
+            .and_then(Value::as_array)
+            .is_some_and(Vec::is_empty),
+        "examples beyond the budget should be dropped"
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
@@ -257,3 +257,200 @@ async fn grep_context_can_be_case_insensitive_and_reports_max_chars_truncation()
     service.cancel().await.context("shutdown mcp service")?;
     Ok(())
 }
+
+#[tokio::test]
+async fn grep_context_merges_overlapping_hunks_across_patterns() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    std::fs::write(
+        root.join("src").join("a.txt"),
+        "one\nALPHA here\ntwo\nBETA here\nALPHA and BETA\nthree\n",
+    )
+    .context("write a.txt")?;
+    std::fs::write(root.join("src").join("b.txt"), "BETA again\n").context("write b.txt")?;
+
+    let args = serde_json::json!({
+        "path": root.to_string_lossy(),
+        "pattern": "ALPHA",
+        "patterns": ["BETA", "ALPHA"],
+        "file_pattern": "src/*",
+        "before": 1,
+        "after": 1,
+        "max_hunks": 1,
+    });
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "grep_context".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling grep_context")??;
+
+    assert_ne!(result.is_error, Some(true), "grep_context returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("grep_context did not return text content")?;
+    let json: Value =
+        serde_json::from_str(text).context("grep_context output is not valid JSON")?;
+
+    assert_eq!(
+        json.get("patterns"),
+        Some(&serde_json::json!(["ALPHA", "BETA"]))
+    );
+
+    let hunks = json
+        .get("hunks")
+        .and_then(Value::as_array)
+        .context("missing hunks array")?;
+    assert_eq!(hunks.len(), 1, "overlapping hunks must be merged once");
+    let hunk = &hunks[0];
+    assert_eq!(hunk.get("file").and_then(Value::as_str), Some("src/a.txt"));
+    assert_eq!(hunk.get("start_line").and_then(Value::as_u64), Some(1));
+    assert_eq!(hunk.get("end_line").and_then(Value::as_u64), Some(6));
+    assert_eq!(hunk.get("match_lines"), Some(&serde_json::json!([2, 4, 5])));
+    assert_eq!(
+        hunk.get("match_patterns"),
+        Some(&serde_json::json!([[0], [1], [0, 1]]))
+    );
+    assert_eq!(
+        json.get("returned_matches").and_then(Value::as_u64),
+        Some(3)
+    );
+
+    let next_args = json
+        .get("next_actions")
+        .and_then(Value::as_array)
+        .and_then(|actions| actions.first())
+        .and_then(|action| action.get("args"))
+        .context("missing next_actions continuation")?;
+    assert_eq!(
+        next_args.get("patterns"),
+        Some(&serde_json::json!(["ALPHA", "BETA"]))
+    );
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "grep_context".into(),
+            arguments: next_args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling grep_context continuation")??;
+    assert_ne!(result.is_error, Some(true), "continuation returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("continuation did not return text content")?;
+    let json: Value = serde_json::from_str(text).context("continuation is not valid JSON")?;
+    let hunks = json
+        .get("hunks")
+        .and_then(Value::as_array)
+        .context("missing hunks array")?;
+    assert_eq!(hunks.len(), 1);
+    assert_eq!(
+        hunks[0].get("file").and_then(Value::as_str),
+        Some("src/b.txt")
+    );
+    assert_eq!(
+        hunks[0].get("match_patterns"),
+        Some(&serde_json::json!([[1]]))
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn grep_context_exclude_pattern_takes_precedence() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::write(
+        root.join("lib.rs"),
+        "fn a() { todo!() }\n// todo!() in a comment\nfn b() { unimplemented!() }\n",
+    )
+    .context("write lib.rs")?;
+
+    let args = serde_json::json!({
+        "path": root.to_string_lossy(),
+        "patterns": ["todo!", "unimplemented!"],
+        "exclude_pattern": "^\\s*//",
+        "file": "lib.rs",
+        "context": 0,
+    });
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "grep_context".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling grep_context")??;
+
+    assert_ne!(result.is_error, Some(true), "grep_context returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("grep_context did not return text content")?;
+    let json: Value =
+        serde_json::from_str(text).context("grep_context output is not valid JSON")?;
+
+    assert_eq!(
+        json.get("exclude_pattern").and_then(Value::as_str),
+        Some("^\\s*//")
+    );
+    let match_lines: Vec<u64> = json
+        .get("hunks")
+        .and_then(Value::as_array)
+        .context("missing hunks array")?
+        .iter()
+        .flat_map(|hunk| {
+            hunk.get("match_lines")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default()
+        })
+        .filter_map(|v| v.as_u64())
+        .collect();
+    assert_eq!(match_lines, vec![1, 3], "excluded line must not match");
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
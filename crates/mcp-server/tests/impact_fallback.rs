@@ -257,3 +257,157 @@ async fn impact_returns_text_matches_when_symbol_is_missing_from_graph() -> Resu
     service.cancel().await.context("shutdown mcp service")?;
     Ok(())
 }
+
+/// Minimal corpus + index with one chunk per `(path, language, content)`.
+async fn write_minimal_index(root: &std::path::Path, files: &[(&str, &str, &str)]) -> Result<()> {
+    std::fs::create_dir_all(
+        root.join(".context-finder")
+            .join("indexes")
+            .join("bge-small"),
+    )
+    .context("mkdir indexes")?;
+
+    let mut corpus = ChunkCorpus::new();
+    let mut id_map = serde_json::Map::new();
+    for (idx, (path, language, content)) in files.iter().enumerate() {
+        let metadata = ChunkMetadata {
+            language: Some((*language).to_string()),
+            ..ChunkMetadata::default()
+        };
+        corpus.set_file_chunks(
+            (*path).to_string(),
+            vec![CodeChunk::new(
+                (*path).to_string(),
+                1,
+                1,
+                (*content).to_string(),
+                metadata,
+            )],
+        );
+        id_map.insert(idx.to_string(), Value::String(format!("{path}:1:1")));
+    }
+    corpus
+        .save(root.join(".context-finder").join("corpus.json"))
+        .await
+        .context("save corpus")?;
+
+    let index = serde_json::json!({
+        "schema_version": 3,
+        "dimension": 384,
+        "next_id": files.len(),
+        "id_map": id_map,
+        "vectors": {},
+    });
+    std::fs::write(
+        root.join(".context-finder")
+            .join("indexes")
+            .join("bge-small")
+            .join("index.json"),
+        index.to_string(),
+    )
+    .context("write index.json")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn impact_detects_the_graph_language_from_indexed_chunks() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let web = tempfile::tempdir().context("tempdir")?;
+    write_minimal_index(
+        web.path(),
+        &[
+            (
+                "src/app.ts",
+                "typescript",
+                "export function boot() { render(); }\n",
+            ),
+            ("src/view.ts", "typescript", "export function render() {}\n"),
+            ("README.md", "markdown", "# boot\n"),
+        ],
+    )
+    .await?;
+    let go = tempfile::tempdir().context("tempdir")?;
+    write_minimal_index(
+        go.path(),
+        &[
+            ("cmd/main.go", "go", "func main() { serve() }\n"),
+            ("cmd/serve.go", "go", "func serve() {}\n"),
+            ("tools/gen.py", "python", "def gen(): pass\n"),
+        ],
+    )
+    .await?;
+
+    let call = |root: &std::path::Path, symbol: &str| CallToolRequestParam {
+        name: "impact".into(),
+        arguments: serde_json::json!({
+            "symbol": symbol,
+            "path": root.to_string_lossy(),
+            "auto_index": false,
+        })
+        .as_object()
+        .cloned(),
+    };
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(call(web.path(), "render")),
+    )
+    .await
+    .context("timeout calling impact")??;
+    assert_ne!(result.is_error, Some(true), "impact returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("impact did not return text content")?;
+    let json: Value = serde_json::from_str(text).context("impact output is not valid JSON")?;
+    assert_eq!(json["meta"]["graph_language"], "typescript", "{json}");
+    assert_eq!(json["meta"]["detected"], true, "{json}");
+
+    // A dominant language without a graph builder still answers, from text hits alone.
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(call(go.path(), "serve")),
+    )
+    .await
+    .context("timeout calling impact")??;
+    assert_ne!(result.is_error, Some(true), "impact returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("impact did not return text content")?;
+    let json: Value = serde_json::from_str(text).context("impact output is not valid JSON")?;
+    assert_eq!(json["meta"]["graph_language"], "go", "{json}");
+    assert_eq!(json["meta"]["detected"], true, "{json}");
+    let direct = json["direct"].as_array().context("direct missing")?;
+    assert!(
+        direct
+            .iter()
+            .any(|usage| usage["file"] == "cmd/main.go" && usage["relationship"] == "TextMatch"),
+        "expected a text hit in cmd/main.go, got: {json}"
+    );
+    let hint = json["hint"].as_str().context("hint missing")?;
+    assert!(
+        hint.contains("'go' has no graph support")
+            && hint.contains("rust, python, javascript, typescript"),
+        "{hint}"
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
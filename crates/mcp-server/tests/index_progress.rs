@@ -0,0 +1,145 @@
+use anyhow::{Context, Result};
+use rmcp::{
+    model::{CallToolRequestParam, ProgressNotificationParam},
+    service::{NotificationContext, ServiceExt},
+    transport::TokioChildProcess,
+    ClientHandler, RoleClient,
+};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::process::Command;
+
+#[derive(Clone, Default)]
+struct ProgressRecorder {
+    seen: Arc<Mutex<Vec<ProgressNotificationParam>>>,
+}
+
+impl ClientHandler for ProgressRecorder {
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.seen.lock().unwrap().push(params);
+    }
+}
+
+fn locate_context_finder_mcp_bin() -> Result<PathBuf> {
+    if let Some(path) = option_env!("CARGO_BIN_EXE_context-finder-mcp") {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(target_profile_dir) = exe.parent().and_then(|p| p.parent()) {
+            let candidate = target_profile_dir.join("context-finder-mcp");
+            if candidate.exists() {
+                return Ok(candidate);
+            }
+        }
+    }
+
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let repo_root = manifest_dir
+        .ancestors()
+        .nth(2)
+        .context("failed to resolve repo root from CARGO_MANIFEST_DIR")?;
+    for rel in [
+        "target/debug/context-finder-mcp",
+        "target/release/context-finder-mcp",
+    ] {
+        let candidate = repo_root.join(rel);
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+    }
+
+    anyhow::bail!("failed to locate context-finder-mcp binary");
+}
+
+#[tokio::test]
+async fn index_streams_progress_notifications_and_reports_phases() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let recorder = ProgressRecorder::default();
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), recorder.clone().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    for idx in 0..6 {
+        std::fs::write(
+            root.join("src").join(format!("mod_{idx}.rs")),
+            format!("pub fn item_{idx}() -> usize {{\n    {idx}\n}}\n"),
+        )
+        .context("write module")?;
+    }
+
+    // rmcp attaches a progressToken to every request, so the server should stream progress.
+    let args = serde_json::json!({ "path": root.to_string_lossy() });
+    let result = tokio::time::timeout(
+        Duration::from_secs(60),
+        service.call_tool(CallToolRequestParam {
+            name: "index".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling index")??;
+
+    assert_ne!(result.is_error, Some(true), "index returned error");
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("index did not return text content")?;
+    let json: Value = serde_json::from_str(text).context("index output is not valid JSON")?;
+    let phases: Vec<&str> = json
+        .get("phases")
+        .and_then(Value::as_array)
+        .context("index result missing phases")?
+        .iter()
+        .filter_map(|phase| phase.get("phase").and_then(Value::as_str))
+        .collect();
+    for expected in ["scan", "chunk", "embed", "save"] {
+        assert!(
+            phases.contains(&expected),
+            "missing phase {expected}: {json}"
+        );
+    }
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while recorder.seen.lock().unwrap().is_empty() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let seen = recorder.seen.lock().unwrap().clone();
+    assert!(
+        !seen.is_empty(),
+        "expected at least one progress notification"
+    );
+    assert!(
+        seen.iter()
+            .any(|p| p.message.as_deref().is_some_and(|m| m.starts_with("scan:"))),
+        "expected a scan-phase notification: {seen:?}"
+    );
+    assert!(
+        seen.windows(2)
+            .all(|pair| pair[0].progress <= pair[1].progress),
+        "progress must not go backwards: {seen:?}"
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
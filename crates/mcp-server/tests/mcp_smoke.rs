@@ -79,6 +79,8 @@ async fn mcp_exposes_core_tools_and_map_has_no_side_effects() -> Result<()> {
         "context",
         "context_pack",
         "index",
+        "watch",
+        "warm",
         "text_search",
         "impact",
         "trace",
@@ -99,6 +99,7 @@ async fn start_service() -> Result<(tempfile::TempDir, RunningService<RoleClient
     let mut cmd = Command::new(bin);
     cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
     cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
     cmd.env("RUST_LOG", "warn");
     cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
 
@@ -934,3 +935,332 @@ async fn read_pack_clamps_tiny_budget_and_keeps_continuation() -> Result<()> {
     service.cancel().await.context("shutdown mcp service")?;
     Ok(())
 }
+
+fn find_index_file(dir: &std::path::Path) -> Option<PathBuf> {
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_index_file(&path) {
+                return Some(found);
+            }
+        } else if path.file_name().is_some_and(|name| name == "index.json") {
+            return Some(path);
+        }
+    }
+    None
+}
+
+#[tokio::test]
+async fn search_supports_cursor_pagination_until_index_changes() -> Result<()> {
+    let (tmp, service) = start_service().await?;
+    let root = tmp.path();
+
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    for name in ["alpha", "beta", "gamma", "delta"] {
+        std::fs::write(
+            root.join("src").join(format!("{name}.rs")),
+            format!("pub fn {name}_parser() -> u32 {{\n    parse_input(\"{name}\")\n}}\n"),
+        )
+        .with_context(|| format!("write {name}.rs"))?;
+    }
+
+    let mut cursor: Option<String> = None;
+    let mut seen = Vec::new();
+    let mut stale_cursor = None;
+    for _ in 0..10usize {
+        let args = serde_json::json!({
+            "path": root.to_string_lossy(),
+            "query": "parser parse_input",
+            "limit": 1,
+            "auto_index": true,
+            "auto_index_budget_ms": 10_000,
+            "cursor": cursor,
+        });
+        let json = call_tool_json(&service, "search", args).await?;
+        let results = json
+            .get("results")
+            .and_then(Value::as_array)
+            .context("missing results array")?;
+        assert!(results.len() <= 1, "page exceeded limit");
+        for hit in results {
+            let file = hit.get("file").and_then(Value::as_str).context("file")?;
+            let start = hit
+                .get("start_line")
+                .and_then(Value::as_u64)
+                .context("line")?;
+            seen.push(format!("{file}:{start}"));
+        }
+
+        cursor = json
+            .get("next_cursor")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        if let Some(cursor) = cursor.as_deref() {
+            assert_next_action(&json, "search", cursor)?;
+            stale_cursor = Some(cursor.to_string());
+        }
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    assert!(seen.len() > 1, "expected more than one page, got {seen:?}");
+    let mut unique = seen.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), seen.len(), "pages overlap: {seen:?}");
+
+    let stale_cursor = stale_cursor.context("no cursor issued")?;
+    let index_file =
+        find_index_file(&root.join(".context-finder")).context("index.json not found")?;
+    let bumped = std::time::SystemTime::now() + Duration::from_secs(60);
+    std::fs::File::options()
+        .write(true)
+        .open(&index_file)
+        .context("open index.json")?
+        .set_modified(bumped)
+        .context("bump index mtime")?;
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "search".into(),
+            arguments: serde_json::json!({
+                "path": root.to_string_lossy(),
+                "query": "parser parse_input",
+                "limit": 1,
+                "auto_index": false,
+                "cursor": stale_cursor,
+            })
+            .as_object()
+            .cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling search")??;
+    assert_eq!(result.is_error, Some(true));
+    let structured = result
+        .structured_content
+        .context("search error missing structured_content")?;
+    assert_eq!(
+        structured
+            .get("error")
+            .and_then(|e| e.get("code"))
+            .and_then(Value::as_str),
+        Some("invalid_cursor")
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+fn git(root: &std::path::Path, args: &[&str]) -> Result<()> {
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .context("spawn git")?;
+    anyhow::ensure!(status.success(), "git {args:?} failed");
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_pack_diff_pages_changed_hunks_with_cursor() -> Result<()> {
+    let (tmp, service) = start_service().await?;
+    let root = tmp.path();
+
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        std::fs::write(root.join(name), "base\n").context("write base file")?;
+    }
+    git(root, &["init", "-q"])?;
+    git(root, &["add", "-A"])?;
+    git(root, &["commit", "-q", "-m", "base"])?;
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        let mut content = String::from("base\n");
+        for idx in 0..30usize {
+            writeln!(
+                &mut content,
+                "{name} changed line {idx} with some padding text"
+            )
+            .expect("write line");
+        }
+        std::fs::write(root.join(name), content).context("write changed file")?;
+    }
+
+    let first = call_tool_json(
+        &service,
+        "read_pack",
+        serde_json::json!({
+            "path": root.to_string_lossy(),
+            "intent": "diff",
+            "since": "HEAD",
+            "max_chars": 4000,
+            "auto_index": false,
+        }),
+    )
+    .await?;
+    assert_eq!(first.get("intent").and_then(Value::as_str), Some("diff"));
+    let first_diff = first
+        .pointer("/sections/0/result")
+        .with_context(|| format!("missing diff section; got: {first:?}"))?;
+    assert_eq!(
+        first_diff.get("changed_files").and_then(Value::as_u64),
+        Some(3)
+    );
+    assert_eq!(
+        first_diff.pointer("/items/0/file").and_then(Value::as_str),
+        Some("a.txt")
+    );
+    assert_eq!(
+        first_diff
+            .pointer("/items/0/changed_lines/0/0")
+            .and_then(Value::as_u64),
+        Some(2)
+    );
+    let cursor = first_diff
+        .get("next_cursor")
+        .and_then(Value::as_str)
+        .with_context(|| format!("missing diff next_cursor; got: {first:?}"))?
+        .to_string();
+    assert_next_action(&first, "read_pack", &cursor)?;
+
+    let second = call_tool_json(
+        &service,
+        "read_pack",
+        serde_json::json!({
+            "path": root.to_string_lossy(),
+            "cursor": cursor,
+            "auto_index": false,
+        }),
+    )
+    .await?;
+    assert_eq!(second.get("intent").and_then(Value::as_str), Some("diff"));
+    assert_eq!(
+        second
+            .pointer("/sections/0/result/items/0/file")
+            .and_then(Value::as_str),
+        Some("b.txt")
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_pack_diff_without_git_suggests_list_files() -> Result<()> {
+    let (tmp, service) = start_service().await?;
+    let root = tmp.path();
+    std::fs::write(root.join("a.txt"), "hello\n").context("write a.txt")?;
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "read_pack".into(),
+            arguments: serde_json::json!({
+                "path": root.to_string_lossy(),
+                "intent": "diff",
+            })
+            .as_object()
+            .cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling read_pack")??;
+
+    assert_eq!(result.is_error, Some(true));
+    let structured = result
+        .structured_content
+        .context("read_pack error missing structured_content")?;
+    assert_eq!(
+        structured.pointer("/error/code").and_then(Value::as_str),
+        Some("invalid_request")
+    );
+    assert_eq!(
+        structured
+            .pointer("/error/next_actions/0/tool")
+            .and_then(Value::as_str),
+        Some("list_files")
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_pack_symbols_pages_outline_without_duplicates() -> Result<()> {
+    let (tmp, service) = start_service().await?;
+    let root = tmp.path();
+
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    let mut content = String::new();
+    for idx in 0..40usize {
+        writeln!(
+            &mut content,
+            "pub fn handler_{idx:02}(input: usize) -> usize {{\n    let doubled = input * 2;\n    let shifted = doubled + {idx};\n    shifted\n}}\n"
+        )
+        .expect("write fn");
+    }
+    std::fs::write(root.join("src").join("handlers.rs"), content).context("write handlers.rs")?;
+
+    let mut names: Vec<String> = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut pages = 0usize;
+    loop {
+        let mut args = serde_json::json!({
+            "path": root.to_string_lossy(),
+            "max_chars": 3000,
+        });
+        match cursor.as_deref() {
+            Some(cursor) => args["cursor"] = Value::String(cursor.to_string()),
+            None => {
+                args["intent"] = Value::String("symbols".to_string());
+                args["file"] = Value::String("src/*.rs".to_string());
+            }
+        }
+        let page = call_tool_json(&service, "read_pack", args).await?;
+        pages += 1;
+        assert_eq!(page.get("intent").and_then(Value::as_str), Some("symbols"));
+        let result = page
+            .pointer("/sections/0/result")
+            .with_context(|| format!("missing symbols section; got: {page:?}"))?;
+        assert_eq!(result.get("matched_files").and_then(Value::as_u64), Some(1));
+        let symbols = result
+            .get("symbols")
+            .and_then(Value::as_array)
+            .context("missing symbols")?;
+        assert!(!symbols.is_empty(), "empty symbols page: {page:?}");
+        for symbol in symbols {
+            assert_eq!(
+                symbol.get("file").and_then(Value::as_str),
+                Some("src/handlers.rs")
+            );
+            names.push(
+                symbol
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .context("symbol name missing")?
+                    .to_string(),
+            );
+        }
+
+        match result.get("next_cursor").and_then(Value::as_str) {
+            Some(next) => {
+                assert_next_action(&page, "read_pack", next)?;
+                cursor = Some(next.to_string());
+            }
+            None => break,
+        }
+        anyhow::ensure!(pages < 40, "symbols pagination did not terminate");
+    }
+
+    assert!(pages > 1, "expected the outline to span several pages");
+    let expected: Vec<String> = (0..40usize)
+        .map(|idx| format!("handler_{idx:02}"))
+        .collect();
+    assert_eq!(names, expected);
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
@@ -375,3 +375,209 @@ async fn repo_onboarding_pack_clamps_tiny_budget_and_keeps_next_actions() -> Res
     service.cancel().await.context("shutdown mcp service")?;
     Ok(())
 }
+
+#[tokio::test]
+async fn repo_onboarding_pack_honors_sections_and_key_docs_globs() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+    cmd.env("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    std::fs::write(root.join("src").join("main.rs"), "fn main() {}\n").context("write main.rs")?;
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/a\"]\n",
+    )
+    .context("write Cargo.toml")?;
+    std::fs::write(root.join("Makefile"), "build:\n\tcargo build\n").context("write Makefile")?;
+    std::fs::write(root.join("README.md"), "# Hello\n").context("write README.md")?;
+    std::fs::create_dir_all(root.join("docs").join("adr")).context("mkdir docs/adr")?;
+    std::fs::write(
+        root.join("docs").join("adr").join("0001-storage.md"),
+        "# ADR 1\n",
+    )
+    .context("write adr")?;
+    std::fs::create_dir_all(root.join(".context-finder")).context("mkdir .context-finder")?;
+    std::fs::write(
+        root.join(".context-finder").join("config.json"),
+        r#"{"onboarding": {"key_docs_globs": ["docs/adr/*.md"]}}"#,
+    )
+    .context("write config.json")?;
+
+    let args = serde_json::json!({
+        "path": root.to_string_lossy(),
+        "sections": ["docs", "entry_points", "build"],
+        "max_chars": 20000,
+        "auto_index": false
+    });
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "repo_onboarding_pack".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling repo_onboarding_pack")??;
+
+    assert_ne!(
+        result.is_error,
+        Some(true),
+        "repo_onboarding_pack returned error"
+    );
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("repo_onboarding_pack did not return text content")?;
+    let json: Value =
+        serde_json::from_str(text).context("repo_onboarding_pack output is not valid JSON")?;
+
+    assert!(json.get("map").is_none(), "map section was not requested");
+    assert_eq!(
+        json.get("next_actions")
+            .and_then(Value::as_array)
+            .map(Vec::len),
+        Some(0)
+    );
+
+    let docs: Vec<&str> = json
+        .get("docs")
+        .and_then(Value::as_array)
+        .context("missing docs array")?
+        .iter()
+        .filter_map(|d| d.get("file").and_then(Value::as_str))
+        .collect();
+    assert_eq!(docs, vec!["docs/adr/0001-storage.md", "README.md"]);
+
+    assert_eq!(
+        json.pointer("/entry_points/0/file").and_then(Value::as_str),
+        Some("src/main.rs")
+    );
+    let build = json
+        .get("build")
+        .and_then(Value::as_array)
+        .context("missing build array")?;
+    assert_eq!(
+        build
+            .iter()
+            .map(|b| b.get("kind").and_then(Value::as_str).unwrap_or(""))
+            .collect::<Vec<_>>(),
+        vec!["cargo", "make"]
+    );
+    assert_eq!(
+        build[0].pointer("/items/0").and_then(Value::as_str),
+        Some("crates/a")
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn repo_onboarding_pack_caps_total_doc_bytes() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+    cmd.env("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+
+    std::fs::create_dir_all(root.join("guides")).context("mkdir guides")?;
+    std::fs::write(
+        root.join("guides").join("intro.md"),
+        "# Intro\nStart here.\n",
+    )
+    .context("write intro.md")?;
+    std::fs::create_dir_all(root.join("notes")).context("mkdir notes")?;
+    std::fs::write(root.join("notes").join("other.md"), "# Other\n").context("write other.md")?;
+    std::fs::write(
+        root.join("README.md"),
+        format!("# Readme\n{}\n", "x".repeat(200)),
+    )
+    .context("write README.md")?;
+    std::fs::create_dir_all(root.join(".context-finder")).context("mkdir .context-finder")?;
+    std::fs::write(
+        root.join(".context-finder").join("config.json"),
+        r#"{"onboarding": {"key_docs_globs": ["guides/*.md"], "max_doc_bytes": 60}}"#,
+    )
+    .context("write config.json")?;
+
+    let args = serde_json::json!({
+        "path": root.to_string_lossy(),
+        "sections": ["docs"],
+        "auto_index": false
+    });
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        service.call_tool(CallToolRequestParam {
+            name: "repo_onboarding_pack".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling repo_onboarding_pack")??;
+
+    assert_ne!(
+        result.is_error,
+        Some(true),
+        "repo_onboarding_pack returned error"
+    );
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .context("repo_onboarding_pack did not return text content")?;
+    let json: Value =
+        serde_json::from_str(text).context("repo_onboarding_pack output is not valid JSON")?;
+
+    let docs = json
+        .get("docs")
+        .and_then(Value::as_array)
+        .context("missing docs array")?;
+    let files: Vec<&str> = docs
+        .iter()
+        .filter_map(|d| d.get("file").and_then(Value::as_str))
+        .collect();
+    assert_eq!(files.first(), Some(&"guides/intro.md"), "{json}");
+    assert!(!files.contains(&"notes/other.md"), "{files:?}");
+    let doc_bytes: usize = docs
+        .iter()
+        .filter_map(|d| d.get("content").and_then(Value::as_str))
+        .map(str::len)
+        .sum();
+    assert!(doc_bytes <= 60, "docs exceed max_doc_bytes: {doc_bytes}");
+    let readme = docs
+        .iter()
+        .find(|d| d.get("file").and_then(Value::as_str) == Some("README.md"))
+        .context("README.md should get the remaining doc bytes")?;
+    assert_eq!(readme.get("truncated"), Some(&Value::Bool(true)));
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use anyhow::{Context, Result};
+use rmcp::{model::CallToolRequestParam, service::ServiceExt, transport::TokioChildProcess};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::process::Command;
+
+fn locate_context_finder_mcp_bin() -> Result<PathBuf> {
+    if let Some(path) = option_env!("CARGO_BIN_EXE_context-finder-mcp") {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(target_profile_dir) = exe.parent().and_then(|p| p.parent()) {
+            let candidate = target_profile_dir.join("context-finder-mcp");
+            if candidate.exists() {
+                return Ok(candidate);
+            }
+        }
+    }
+
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let repo_root = manifest_dir
+        .ancestors()
+        .nth(2)
+        .context("failed to resolve repo root from CARGO_MANIFEST_DIR")?;
+    for rel in [
+        "target/debug/context-finder-mcp",
+        "target/release/context-finder-mcp",
+    ] {
+        let candidate = repo_root.join(rel);
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+    }
+
+    anyhow::bail!("failed to locate context-finder-mcp binary")
+}
+
+async fn call_json(
+    service: &rmcp::service::RunningService<rmcp::RoleClient, ()>,
+    tool: &str,
+    args: Value,
+) -> Result<(bool, Value)> {
+    let result = tokio::time::timeout(
+        Duration::from_secs(30),
+        service.call_tool(CallToolRequestParam {
+            name: tool.to_string().into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .with_context(|| format!("timeout calling {tool}"))??;
+    if result.is_error == Some(true) {
+        let structured = result
+            .structured_content
+            .with_context(|| format!("{tool} error missing structured_content"))?;
+        return Ok((true, structured));
+    }
+    let text = result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.clone())
+        .with_context(|| format!("{tool} did not return text content"))?;
+    let json: Value =
+        serde_json::from_str(&text).with_context(|| format!("{tool} output is not JSON"))?;
+    Ok((false, json))
+}
+
+#[tokio::test]
+async fn search_models_use_indices_on_disk_and_honor_stale_policy_fail() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    std::fs::write(
+        root.join("src").join("lib.rs"),
+        "/// Greets the caller by name.\npub fn greet(name: &str) -> String {\n    let greeting = format!(\"hello {name}\");\n    greeting\n}\n",
+    )
+    .context("write lib.rs")?;
+    let path = root.to_string_lossy().to_string();
+
+    // `bge-small-en-v1.5` is indexed but not part of the quality profile's roster.
+    let (is_error, index) = call_json(
+        &service,
+        "index",
+        serde_json::json!({ "path": path, "models": ["bge-small-en-v1.5"] }),
+    )
+    .await?;
+    assert!(!is_error, "index failed: {index}");
+
+    let search_args = |stale_policy: Option<&str>| {
+        let mut args = serde_json::json!({
+            "path": path,
+            "query": "greet",
+            "models": ["bge-small-en-v1.5"],
+            "auto_index": false,
+        });
+        if let Some(policy) = stale_policy {
+            args["stale_policy"] = Value::from(policy);
+        }
+        args
+    };
+
+    let (is_error, search) = call_json(&service, "search", search_args(None)).await?;
+    assert!(!is_error, "search over an on-disk model failed: {search}");
+    let timings = search["model_timings"]
+        .as_array()
+        .context("model_timings")?;
+    assert!(timings
+        .iter()
+        .any(|timing| timing["model_id"] == "bge-small-en-v1.5"));
+
+    std::fs::write(
+        root.join("src").join("lib.rs"),
+        "/// Greets the caller by name, loudly.\npub fn greet(name: &str) -> String {\n    let greeting = format!(\"HELLO {name}\");\n    greeting\n}\n",
+    )
+    .context("rewrite lib.rs")?;
+
+    let (is_error, warned) = call_json(&service, "search", search_args(None)).await?;
+    assert!(
+        !is_error,
+        "stale extra model must only warn by default: {warned}"
+    );
+    assert_eq!(warned["meta"]["index_state"]["stale"], true);
+
+    let (is_error, failed) = call_json(&service, "search", search_args(Some("fail"))).await?;
+    assert!(
+        is_error,
+        "stale_policy=fail must reject a stale model: {failed}"
+    );
+    assert_eq!(failed["error"]["code"], "stale", "{failed}");
+    assert!(failed["error"]["message"]
+        .as_str()
+        .is_some_and(|message| message.contains("bge-small-en-v1.5")));
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
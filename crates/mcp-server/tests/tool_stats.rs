@@ -0,0 +1,169 @@
+use anyhow::{Context, Result};
+use rmcp::{
+    model::{CallToolRequestParam, CallToolResult},
+    service::{RunningService, ServiceExt},
+    transport::TokioChildProcess,
+    RoleClient,
+};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::process::Command;
+
+fn locate_context_finder_mcp_bin() -> Result<PathBuf> {
+    if let Some(path) = option_env!("CARGO_BIN_EXE_context-finder-mcp") {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(target_profile_dir) = exe.parent().and_then(|p| p.parent()) {
+            let candidate = target_profile_dir.join("context-finder-mcp");
+            if candidate.exists() {
+                return Ok(candidate);
+            }
+        }
+    }
+
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let repo_root = manifest_dir
+        .ancestors()
+        .nth(2)
+        .context("failed to resolve repo root from CARGO_MANIFEST_DIR")?;
+    for rel in [
+        "target/debug/context-finder-mcp",
+        "target/release/context-finder-mcp",
+    ] {
+        let candidate = repo_root.join(rel);
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+    }
+
+    anyhow::bail!("failed to locate context-finder-mcp binary");
+}
+
+async fn start_service(debug_metrics: bool) -> Result<RunningService<RoleClient, ()>> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env_remove("CONTEXT_FINDER_DEBUG_METRICS");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+    if debug_metrics {
+        cmd.env("CONTEXT_FINDER_DEBUG_METRICS", "1");
+    }
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")?
+        .context("serve mcp client")
+}
+
+async fn call_tool(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<(CallToolResult, Value)> {
+    let result = tokio::time::timeout(
+        Duration::from_secs(30),
+        service.call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling tool")??;
+    let json = if result.is_error == Some(true) {
+        result
+            .structured_content
+            .clone()
+            .context("tool error without structured content")?
+    } else {
+        let text = result
+            .content
+            .first()
+            .and_then(|c| c.as_text())
+            .map(|t| t.text.as_str())
+            .context("tool did not return text content")?;
+        serde_json::from_str(text).context("tool output is not valid JSON")?
+    };
+    Ok((result, json))
+}
+
+fn stats_for<'a>(stats: &'a Value, tool: &str) -> Option<&'a Value> {
+    stats
+        .as_array()?
+        .iter()
+        .find(|entry| entry.get("tool").and_then(Value::as_str) == Some(tool))
+}
+
+#[tokio::test]
+async fn doctor_reports_per_tool_stats() -> Result<()> {
+    let service = start_service(false).await?;
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::write(root.join("a.txt"), "hello\n").context("write a.txt")?;
+    let path = root.to_string_lossy().to_string();
+
+    for _ in 0..2 {
+        let (result, _) =
+            call_tool(&service, "list_files", serde_json::json!({ "path": path })).await?;
+        assert_ne!(result.is_error, Some(true));
+    }
+    let (result, _) = call_tool(
+        &service,
+        "file_slice",
+        serde_json::json!({ "path": path, "file": "missing.txt" }),
+    )
+    .await?;
+    assert_eq!(result.is_error, Some(true));
+
+    let (_, doctor) = call_tool(&service, "doctor", serde_json::json!({ "path": path })).await?;
+    let stats = doctor.get("stats").context("doctor missing stats")?;
+    let list_files = stats_for(stats, "list_files").context("missing list_files stats")?;
+    assert_eq!(list_files.get("calls").and_then(Value::as_u64), Some(2));
+    assert_eq!(list_files.get("errors").and_then(Value::as_u64), Some(0));
+    assert!(
+        list_files
+            .get("bytes_returned")
+            .and_then(Value::as_u64)
+            .unwrap_or(0)
+            > 0
+    );
+    let file_slice = stats_for(stats, "file_slice").context("missing file_slice stats")?;
+    assert_eq!(file_slice.get("calls").and_then(Value::as_u64), Some(1));
+    assert_eq!(file_slice.get("errors").and_then(Value::as_u64), Some(1));
+    assert!(
+        doctor.pointer("/meta/tool_stats").is_none(),
+        "tool_stats must stay out of meta without the debug flag"
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn debug_flag_attaches_tool_stats_to_meta() -> Result<()> {
+    let service = start_service(true).await?;
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::write(root.join("a.txt"), "hello\n").context("write a.txt")?;
+    let path = root.to_string_lossy().to_string();
+
+    call_tool(&service, "list_files", serde_json::json!({ "path": path })).await?;
+    let (_, second) =
+        call_tool(&service, "list_files", serde_json::json!({ "path": path })).await?;
+    let tool_stats = second
+        .pointer("/meta/tool_stats")
+        .with_context(|| format!("missing meta.tool_stats; got: {second:?}"))?;
+    let list_files = stats_for(tool_stats, "list_files").context("missing list_files stats")?;
+    // The snapshot is taken while the second call runs, so it covers the first one.
+    assert_eq!(list_files.get("calls").and_then(Value::as_u64), Some(1));
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
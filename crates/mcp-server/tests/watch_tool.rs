@@ -0,0 +1,212 @@
+use anyhow::{Context, Result};
+use rmcp::{
+    model::{CallToolRequestParam, CallToolResult},
+    service::{RunningService, ServiceExt},
+    transport::TokioChildProcess,
+    RoleClient,
+};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::process::Command;
+
+fn locate_context_finder_mcp_bin() -> Result<PathBuf> {
+    if let Some(path) = option_env!("CARGO_BIN_EXE_context-finder-mcp") {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(target_profile_dir) = exe.parent().and_then(|p| p.parent()) {
+            let candidate = target_profile_dir.join("context-finder-mcp");
+            if candidate.exists() {
+                return Ok(candidate);
+            }
+        }
+    }
+
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let repo_root = manifest_dir
+        .ancestors()
+        .nth(2)
+        .context("failed to resolve repo root from CARGO_MANIFEST_DIR")?;
+    for rel in [
+        "target/debug/context-finder-mcp",
+        "target/release/context-finder-mcp",
+    ] {
+        let candidate = repo_root.join(rel);
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+    }
+
+    anyhow::bail!("failed to locate context-finder-mcp binary");
+}
+
+async fn call_watch(
+    service: &RunningService<RoleClient, ()>,
+    args: Value,
+) -> Result<(CallToolResult, Value)> {
+    let result = tokio::time::timeout(
+        Duration::from_secs(30),
+        service.call_tool(CallToolRequestParam {
+            name: "watch".into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling watch")??;
+    let json = if result.is_error == Some(true) {
+        result
+            .structured_content
+            .clone()
+            .context("watch error without structured content")?
+    } else {
+        let text = result
+            .content
+            .first()
+            .and_then(|c| c.as_text())
+            .map(|t| t.text.as_str())
+            .context("watch did not return text content")?;
+        serde_json::from_str(text).context("watch output is not valid JSON")?
+    };
+    Ok((result, json))
+}
+
+fn watcher_active(json: &Value) -> Option<bool> {
+    json.pointer("/meta/watcher/active")
+        .and_then(Value::as_bool)
+}
+
+#[tokio::test]
+async fn watch_start_status_trigger_stop_round_trip() -> Result<()> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    let service = tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")??;
+
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).context("mkdir src")?;
+    std::fs::write(
+        root.join("src").join("lib.rs"),
+        "pub fn answer() -> usize {\n    42\n}\n",
+    )
+    .context("write lib.rs")?;
+    std::fs::create_dir_all(root.join(".context-finder")).context("mkdir .context-finder")?;
+    std::fs::write(
+        root.join(".context-finder").join("config.json"),
+        r#"{ "watch": { "debounce_ms": 100, "max_batch_wait_ms": 400, "max_debounce_ms": 1600 } }"#,
+    )
+    .context("write config.json")?;
+    let path = root.to_string_lossy().to_string();
+
+    let (_, status) = call_watch(
+        &service,
+        serde_json::json!({ "path": path, "action": "status" }),
+    )
+    .await?;
+    assert_eq!(status.get("active"), Some(&Value::Bool(false)));
+    assert_eq!(watcher_active(&status), Some(false));
+
+    let (result, started) = call_watch(
+        &service,
+        serde_json::json!({ "path": path, "action": "start" }),
+    )
+    .await?;
+    assert_ne!(result.is_error, Some(true), "start failed: {started}");
+    assert_eq!(started.get("active"), Some(&Value::Bool(true)));
+    assert_eq!(
+        started
+            .pointer("/config/debounce_ms")
+            .and_then(Value::as_u64),
+        Some(100)
+    );
+    assert_eq!(
+        started
+            .pointer("/config/max_batch_wait_ms")
+            .and_then(Value::as_u64),
+        Some(400)
+    );
+    assert_eq!(
+        started
+            .pointer("/config/max_debounce_ms")
+            .and_then(Value::as_u64),
+        Some(1600)
+    );
+    assert_eq!(watcher_active(&started), Some(true));
+
+    let (result, duplicate) = call_watch(
+        &service,
+        serde_json::json!({ "path": path, "action": "start" }),
+    )
+    .await?;
+    assert_eq!(result.is_error, Some(true), "second start must be rejected");
+    assert_eq!(
+        duplicate.pointer("/error/code").and_then(Value::as_str),
+        Some("invalid_request")
+    );
+
+    let (result, triggered) = call_watch(
+        &service,
+        serde_json::json!({ "path": path, "action": "trigger", "reason": "test" }),
+    )
+    .await?;
+    assert_ne!(result.is_error, Some(true), "trigger failed: {triggered}");
+
+    // The watcher persists health.json after each successful cycle.
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(30);
+    let status = loop {
+        let (_, status) = call_watch(
+            &service,
+            serde_json::json!({ "path": path, "action": "status" }),
+        )
+        .await?;
+        let done = status
+            .pointer("/health/last_success_unix_ms")
+            .is_some_and(Value::is_u64)
+            && status.get("persisted_health").is_some();
+        if done || tokio::time::Instant::now() >= deadline {
+            break status;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    };
+    assert_eq!(status.get("active"), Some(&Value::Bool(true)));
+    assert!(
+        status.get("persisted_health").is_some(),
+        "expected persisted health after a watcher cycle: {status}"
+    );
+
+    let (_, stopped) = call_watch(
+        &service,
+        serde_json::json!({ "path": path, "action": "stop" }),
+    )
+    .await?;
+    assert_eq!(stopped.get("stopped"), Some(&Value::Bool(true)));
+    assert_eq!(stopped.get("active"), Some(&Value::Bool(false)));
+    assert_eq!(watcher_active(&stopped), Some(false));
+
+    let (result, orphan_trigger) = call_watch(
+        &service,
+        serde_json::json!({ "path": path, "action": "trigger" }),
+    )
+    .await?;
+    assert_eq!(result.is_error, Some(true), "trigger without a watcher");
+    assert_eq!(
+        orphan_trigger
+            .pointer("/error/next_actions/0/tool")
+            .and_then(Value::as_str),
+        Some("watch")
+    );
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
@@ -0,0 +1,263 @@
+use anyhow::{Context, Result};
+use rmcp::{
+    model::{CallToolRequestParam, CallToolResult},
+    service::{RunningService, ServiceExt},
+    transport::TokioChildProcess,
+    RoleClient,
+};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command;
+
+fn locate_context_finder_mcp_bin() -> Result<PathBuf> {
+    if let Some(path) = option_env!("CARGO_BIN_EXE_context-finder-mcp") {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(target_profile_dir) = exe.parent().and_then(|p| p.parent()) {
+            let candidate = target_profile_dir.join("context-finder-mcp");
+            if candidate.exists() {
+                return Ok(candidate);
+            }
+        }
+    }
+
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let repo_root = manifest_dir
+        .ancestors()
+        .nth(2)
+        .context("failed to resolve repo root from CARGO_MANIFEST_DIR")?;
+    for rel in [
+        "target/debug/context-finder-mcp",
+        "target/release/context-finder-mcp",
+    ] {
+        let candidate = repo_root.join(rel);
+        if candidate.exists() {
+            return Ok(candidate);
+        }
+    }
+
+    anyhow::bail!("failed to locate context-finder-mcp binary");
+}
+
+async fn start_service() -> Result<RunningService<RoleClient, ()>> {
+    let bin = locate_context_finder_mcp_bin()?;
+
+    let mut cmd = Command::new(bin);
+    cmd.env_remove("CONTEXT_FINDER_MODEL_DIR");
+    cmd.env("CONTEXT_FINDER_PROFILE", "quality");
+    cmd.env("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+    cmd.env("RUST_LOG", "warn");
+    cmd.env("CONTEXT_FINDER_DISABLE_DAEMON", "1");
+
+    let transport = TokioChildProcess::new(cmd).context("spawn mcp server")?;
+    tokio::time::timeout(Duration::from_secs(10), ().serve(transport))
+        .await
+        .context("timeout starting MCP server")?
+        .context("serve mcp client")
+}
+
+async fn call_tool(
+    service: &RunningService<RoleClient, ()>,
+    name: &str,
+    args: Value,
+) -> Result<(CallToolResult, Value)> {
+    let result = tokio::time::timeout(
+        Duration::from_secs(60),
+        service.call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: args.as_object().cloned(),
+        }),
+    )
+    .await
+    .context("timeout calling tool")??;
+    let json = if result.is_error == Some(true) {
+        result
+            .structured_content
+            .clone()
+            .context("tool error without structured content")?
+    } else {
+        let text = result
+            .content
+            .first()
+            .and_then(|c| c.as_text())
+            .map(|t| t.text.as_str())
+            .context("tool did not return text content")?;
+        serde_json::from_str(text).context("tool output is not valid JSON")?
+    };
+    Ok((result, json))
+}
+
+/// Workspace root with two member projects and a manifest naming one of them.
+fn write_workspace(root: &Path) -> Result<()> {
+    let api = root.join("services/api/src");
+    let core = root.join("libs/core/src");
+    std::fs::create_dir_all(&api).context("mkdir api")?;
+    std::fs::create_dir_all(&core).context("mkdir core")?;
+    std::fs::create_dir_all(root.join(".context-finder")).context("mkdir .context-finder")?;
+    std::fs::write(
+        api.join("lib.rs"),
+        "pub fn handle_request(body: &str) -> usize {\n    parse_config(body)\n}\n\nfn parse_config(raw: &str) -> usize {\n    raw.len()\n}\n",
+    )
+    .context("write api lib.rs")?;
+    std::fs::write(
+        core.join("lib.rs"),
+        "pub fn parse_config(raw: &str) -> Vec<String> {\n    raw.lines().map(str::to_string).collect()\n}\n",
+    )
+    .context("write core lib.rs")?;
+    std::fs::write(
+        root.join(".context-finder/workspace.json"),
+        r#"{"members": [{"name": "api", "path": "services/api"}, "libs/core"]}"#,
+    )
+    .context("write workspace.json")?;
+    Ok(())
+}
+
+fn result_files(json: &Value, list: &str, field: &str) -> Vec<String> {
+    json.get(list)
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.get(field).and_then(Value::as_str))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn workspace_search_merges_member_roots_with_prefixed_paths() -> Result<()> {
+    let service = start_service().await?;
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    write_workspace(root)?;
+    let path = root.to_string_lossy().to_string();
+
+    let (result, json) = call_tool(
+        &service,
+        "search",
+        serde_json::json!({
+            "path": path,
+            "query": "parse_config",
+            "workspace": true,
+            "limit": 20,
+            "auto_index_budget_ms": 20000,
+        }),
+    )
+    .await?;
+    assert_ne!(result.is_error, Some(true), "search failed: {json}");
+
+    let files = result_files(&json, "results", "file");
+    assert!(
+        files.iter().any(|f| f == "api/src/lib.rs"),
+        "missing api hit: {files:?}"
+    );
+    assert!(
+        files.iter().any(|f| f == "core/src/lib.rs"),
+        "missing core hit: {files:?}"
+    );
+    let scores: Vec<f64> = json["results"]
+        .as_array()
+        .context("results")?
+        .iter()
+        .filter_map(|hit| hit.get("score").and_then(Value::as_f64))
+        .collect();
+    assert!(
+        scores.windows(2).all(|w| w[0] >= w[1]),
+        "unsorted: {scores:?}"
+    );
+    assert!(
+        scores.iter().all(|s| *s <= 1.0 + 1e-6),
+        "not normalized: {scores:?}"
+    );
+
+    let states = json
+        .pointer("/meta/workspace")
+        .and_then(Value::as_array)
+        .context("meta.workspace missing")?;
+    let names: Vec<&str> = states
+        .iter()
+        .filter_map(|state| state.get("name").and_then(Value::as_str))
+        .collect();
+    assert_eq!(names, vec!["api", "core"]);
+    for state in states {
+        assert!(state.get("error").is_none(), "member failed: {state}");
+        assert_eq!(
+            state
+                .pointer("/index_state/index/exists")
+                .and_then(Value::as_bool),
+            Some(true)
+        );
+    }
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn roots_select_a_subset_of_members() -> Result<()> {
+    let service = start_service().await?;
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let root = tmp.path();
+    write_workspace(root)?;
+    let path = root.to_string_lossy().to_string();
+
+    let (result, json) = call_tool(
+        &service,
+        "context_pack",
+        serde_json::json!({
+            "path": path,
+            "query": "parse_config",
+            "roots": ["core"],
+            "max_chars": 4000,
+            "auto_index_budget_ms": 20000,
+        }),
+    )
+    .await?;
+    assert_ne!(result.is_error, Some(true), "context_pack failed: {json}");
+    let files = result_files(&json, "items", "file");
+    assert!(!files.is_empty(), "empty pack: {json}");
+    assert!(
+        files.iter().all(|f| f.starts_with("core/")),
+        "unexpected member in pack: {files:?}"
+    );
+    let states = json
+        .pointer("/meta/workspace")
+        .and_then(Value::as_array)
+        .context("meta.workspace missing")?;
+    assert_eq!(states.len(), 1);
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn workspace_flag_without_manifest_is_rejected() -> Result<()> {
+    let service = start_service().await?;
+    let tmp = tempfile::tempdir().context("tempdir")?;
+    let path = tmp.path().to_string_lossy().to_string();
+
+    let (result, json) = call_tool(
+        &service,
+        "search",
+        serde_json::json!({ "path": path, "query": "anything", "workspace": true }),
+    )
+    .await?;
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+        json.pointer("/error/code").and_then(Value::as_str),
+        Some("invalid_request"),
+        "unexpected error: {json}"
+    );
+    let message = json
+        .pointer("/error/message")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    assert!(message.contains("workspace.json"), "message: {message}");
+
+    service.cancel().await.context("shutdown mcp service")?;
+    Ok(())
+}
@@ -14,15 +14,213 @@ pub enum BudgetTruncation {
     DocsLimit,
     Timeout,
     MaxItems,
+    MaxTokens,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct ToolNextAction {
     pub tool: String,
+    /// Concrete arguments; a fully-specified action can be run as-is.
     pub args: serde_json::Value,
     pub reason: String,
+    /// Arguments with `{{name}}` placeholders the caller must fill in before running the action.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arg_template: Option<serde_json::Value>,
+    /// Placeholder names in `arg_template`, in first-seen order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_args: Vec<String>,
 }
 
+impl ToolNextAction {
+    /// A fully-specified action: `args` are ready to run.
+    #[must_use]
+    pub fn new(
+        tool: impl Into<String>,
+        args: serde_json::Value,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            tool: tool.into(),
+            args,
+            reason: reason.into(),
+            arg_template: None,
+            required_args: Vec::new(),
+        }
+    }
+
+    /// A generic action whose `template` contains `{{name}}` placeholders in string values.
+    ///
+    /// `args` keeps only the template entries without placeholders, so clients that ignore
+    /// templates still see a valid (if incomplete) argument object.
+    #[must_use]
+    pub fn templated(
+        tool: impl Into<String>,
+        template: serde_json::Value,
+        reason: impl Into<String>,
+    ) -> Self {
+        let mut required_args = Vec::new();
+        collect_placeholders(&template, &mut required_args);
+        let args = match &template {
+            serde_json::Value::Object(map) => serde_json::Value::Object(
+                map.iter()
+                    .filter(|(_, value)| {
+                        let mut names = Vec::new();
+                        collect_placeholders(value, &mut names);
+                        names.is_empty()
+                    })
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            ),
+            _ => serde_json::json!({}),
+        };
+        Self {
+            tool: tool.into(),
+            args,
+            reason: reason.into(),
+            arg_template: Some(template),
+            required_args,
+        }
+    }
+
+    /// Whether the caller must fill in placeholders before running the action.
+    #[must_use]
+    pub fn is_templated(&self) -> bool {
+        !self.required_args.is_empty()
+    }
+}
+
+fn collect_placeholders(value: &serde_json::Value, out: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(text) => {
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("{{") {
+                let after = &rest[start + 2..];
+                let Some(end) = after.find("}}") else {
+                    break;
+                };
+                let name = after[..end].trim();
+                if !name.is_empty() && !out.iter().any(|seen| seen == name) {
+                    out.push(name.to_string());
+                }
+                rest = &after[end + 2..];
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_placeholders(item, out);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values() {
+                collect_placeholders(item, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Stable machine-readable error codes, emitted as `ErrorEnvelope.code` by the CLI and MCP.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Internal,
+    InvalidRequest,
+    InvalidCursor,
+    MissingField,
+    ToolError,
+    IndexMissing,
+    IndexCorrupt,
+    ProjectNotFound,
+    FileNotFound,
+    FilesystemError,
+    ConfigError,
+    GraphLanguageMissing,
+    ModelMissing,
+    ModelManifestMissing,
+    ModelUnknown,
+    ModelNetworkError,
+    ModelDownloadRejected,
+    ModelChecksumMismatch,
+    ModelVerifyFailed,
+    Stale,
+    BudgetExceeded,
+    Cancelled,
+    Timeout,
+    QualityGateFailed,
+}
+
+impl ErrorCode {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Internal => "internal",
+            Self::InvalidRequest => "invalid_request",
+            Self::InvalidCursor => "invalid_cursor",
+            Self::MissingField => "missing_field",
+            Self::ToolError => "tool_error",
+            Self::IndexMissing => "index_missing",
+            Self::IndexCorrupt => "index_corrupt",
+            Self::ProjectNotFound => "project_not_found",
+            Self::FileNotFound => "file_not_found",
+            Self::FilesystemError => "filesystem_error",
+            Self::ConfigError => "config_error",
+            Self::GraphLanguageMissing => "graph_language_missing",
+            Self::ModelMissing => "model_missing",
+            Self::ModelManifestMissing => "model_manifest_missing",
+            Self::ModelUnknown => "model_unknown",
+            Self::ModelNetworkError => "model_network_error",
+            Self::ModelDownloadRejected => "model_download_rejected",
+            Self::ModelChecksumMismatch => "model_checksum_mismatch",
+            Self::ModelVerifyFailed => "model_verify_failed",
+            Self::Stale => "stale",
+            Self::BudgetExceeded => "budget_exceeded",
+            Self::Cancelled => "cancelled",
+            Self::Timeout => "timeout",
+            Self::QualityGateFailed => "quality_gate_failed",
+        }
+    }
+
+    /// The first non-[`ErrorCode::Internal`] code carried by a [`CodedError`] in `err`'s chain.
+    #[must_use]
+    pub fn from_chain(err: &anyhow::Error) -> Option<Self> {
+        err.chain()
+            .filter_map(|cause| cause.downcast_ref::<CodedError>())
+            .map(|coded| coded.code)
+            .find(|code| *code != Self::Internal)
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error message tagged with its [`ErrorCode`], for errors that travel as `anyhow::Error`.
+/// Displays as the bare message, so wrapping an existing error does not change its text.
+#[derive(Debug, Clone)]
+pub struct CodedError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl CodedError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for CodedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CodedError {}
+
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct ErrorEnvelope {
     pub code: String,
@@ -84,15 +282,38 @@ pub struct Capabilities {
     pub start_route: ToolNextAction,
 }
 
-pub fn finalize_used_chars<T: Serialize>(
+/// Rough token count for budgeting: a run of word characters costs one token per four
+/// characters, any other non-whitespace character costs one, and whitespace is free.
+#[must_use]
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0usize;
+    let mut word_len = 0usize;
+    for ch in text.chars() {
+        if ch.is_alphanumeric() || ch == '_' {
+            word_len += 1;
+            continue;
+        }
+        tokens += word_len.div_ceil(4);
+        word_len = 0;
+        if !ch.is_whitespace() {
+            tokens += 1;
+        }
+    }
+    tokens + word_len.div_ceil(4)
+}
+
+/// Serialized size of `value` under `measure`, after writing that size back into `value`
+/// via `set_used` (repeated until the reported number no longer changes the size).
+fn finalize_used<T: Serialize>(
     value: &mut T,
     mut set_used: impl FnMut(&mut T, usize),
+    measure: fn(&str) -> usize,
 ) -> Result<usize> {
     let mut used = 0usize;
     for _ in 0..8 {
         set_used(value, used);
         let raw = serde_json::to_string(value)?;
-        let next = raw.chars().count();
+        let next = measure(&raw);
         if next == used {
             set_used(value, next);
             return Ok(next);
@@ -103,25 +324,213 @@ pub fn finalize_used_chars<T: Serialize>(
     Ok(used)
 }
 
-pub fn enforce_max_chars<T: Serialize>(
+fn enforce_max<T: Serialize>(
     value: &mut T,
-    max_chars: usize,
+    max: usize,
     mut set_used: impl FnMut(&mut T, usize),
     mut on_truncate: impl FnMut(&mut T),
     mut shrink: impl FnMut(&mut T) -> bool,
+    measure: fn(&str) -> usize,
+    unit: &str,
 ) -> Result<usize> {
     loop {
-        let used = finalize_used_chars(value, |inner, used| set_used(inner, used))?;
-        if used <= max_chars {
+        let used = finalize_used(value, |inner, used| set_used(inner, used), measure)?;
+        if used <= max {
             return Ok(used);
         }
         on_truncate(value);
         if !shrink(value) {
-            anyhow::bail!("budget exceeded (used_chars={used}, max_chars={max_chars})");
+            anyhow::bail!("budget exceeded (used_{unit}={used}, max_{unit}={max})");
+        }
+    }
+}
+
+pub fn finalize_used_chars<T: Serialize>(
+    value: &mut T,
+    set_used: impl FnMut(&mut T, usize),
+) -> Result<usize> {
+    finalize_used(value, set_used, |raw| raw.chars().count())
+}
+
+pub fn enforce_max_chars<T: Serialize>(
+    value: &mut T,
+    max_chars: usize,
+    set_used: impl FnMut(&mut T, usize),
+    on_truncate: impl FnMut(&mut T),
+    shrink: impl FnMut(&mut T) -> bool,
+) -> Result<usize> {
+    enforce_max(
+        value,
+        max_chars,
+        set_used,
+        on_truncate,
+        shrink,
+        |raw| raw.chars().count(),
+        "chars",
+    )
+}
+
+/// [`finalize_used_chars`] measured in [`estimate_tokens`].
+pub fn finalize_used_tokens<T: Serialize>(
+    value: &mut T,
+    set_used: impl FnMut(&mut T, usize),
+) -> Result<usize> {
+    finalize_used(value, set_used, estimate_tokens)
+}
+
+/// [`enforce_max_chars`] measured in [`estimate_tokens`].
+pub fn enforce_max_tokens<T: Serialize>(
+    value: &mut T,
+    max_tokens: usize,
+    set_used: impl FnMut(&mut T, usize),
+    on_truncate: impl FnMut(&mut T),
+    shrink: impl FnMut(&mut T) -> bool,
+) -> Result<usize> {
+    enforce_max(
+        value,
+        max_tokens,
+        set_used,
+        on_truncate,
+        shrink,
+        estimate_tokens,
+        "tokens",
+    )
+}
+
+/// Suffix that marks a string shortened by [`truncate_largest_string`].
+pub const TRUNCATED_MARKER: &str = "...truncated";
+
+/// Strings at or below this many chars are never shortened (the marker would not save space).
+const MIN_TRUNCATABLE_CHARS: usize = 64;
+
+/// Halves the longest string in `value` and appends [`TRUNCATED_MARKER`], as a `shrink` step
+/// for [`enforce_max_chars`] that keeps partial content instead of dropping a whole item.
+/// Returns `false` once no string is long enough to shrink further.
+pub fn truncate_largest_string(value: &mut serde_json::Value) -> bool {
+    fn content_chars(text: &str) -> usize {
+        text.strip_suffix(TRUNCATED_MARKER)
+            .unwrap_or(text)
+            .chars()
+            .count()
+    }
+
+    fn longest(value: &serde_json::Value) -> usize {
+        match value {
+            serde_json::Value::String(text) => content_chars(text),
+            serde_json::Value::Array(items) => items.iter().map(longest).max().unwrap_or(0),
+            serde_json::Value::Object(map) => map.values().map(longest).max().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn shorten(value: &mut serde_json::Value, target: usize) -> bool {
+        match value {
+            serde_json::Value::String(text) if content_chars(text) == target => {
+                let keep: String = text.chars().take(target / 2).collect();
+                *text = keep + TRUNCATED_MARKER;
+                true
+            }
+            serde_json::Value::Array(items) => items.iter_mut().any(|item| shorten(item, target)),
+            serde_json::Value::Object(map) => map.values_mut().any(|item| shorten(item, target)),
+            _ => false,
         }
     }
+
+    let target = longest(value);
+    target > MIN_TRUNCATABLE_CHARS && shorten(value, target)
 }
 
 pub fn serialize_json<T: Serialize>(value: &T) -> Result<String> {
     serde_json::to_string(value).map_err(Into::into)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templated_action_lists_required_args() {
+        let action = ToolNextAction::templated(
+            "search",
+            serde_json::json!({
+                "query": "{{symbol}} usages",
+                "path": "{{path}}",
+                "filters": ["{{symbol}}"],
+                "limit": 5,
+            }),
+            "Search usages of the symbol you care about.",
+        );
+        assert!(action.is_templated());
+        assert_eq!(action.required_args, vec!["symbol", "path"]);
+
+        let value = serde_json::to_value(&action).unwrap();
+        assert_eq!(
+            value["required_args"],
+            serde_json::json!(["symbol", "path"])
+        );
+        assert_eq!(value["arg_template"]["query"], "{{symbol}} usages");
+        assert_eq!(value["args"], serde_json::json!({"limit": 5}));
+
+        let back: ToolNextAction = serde_json::from_value(value).unwrap();
+        assert_eq!(back.required_args, action.required_args);
+    }
+
+    #[test]
+    fn concrete_action_omits_template_fields() {
+        let action = ToolNextAction::new("index", serde_json::json!({"path": "."}), "Build it.");
+        assert!(!action.is_templated());
+        let value = serde_json::to_value(&action).unwrap();
+        assert!(value.get("arg_template").is_none());
+        assert!(value.get("required_args").is_none());
+
+        let legacy: ToolNextAction =
+            serde_json::from_str(r#"{"tool":"index","args":{},"reason":"r"}"#).unwrap();
+        assert!(legacy.arg_template.is_none() && legacy.required_args.is_empty());
+    }
+
+    #[test]
+    fn truncate_largest_string_keeps_a_marked_prefix() {
+        let mut value = serde_json::json!({
+            "file": "src/big.rs",
+            "content": "x".repeat(1000),
+            "items": [{ "content": "y".repeat(400) }],
+        });
+
+        assert!(truncate_largest_string(&mut value));
+        let content = value["content"].as_str().unwrap();
+        assert_eq!(content, format!("{}{TRUNCATED_MARKER}", "x".repeat(500)));
+        assert_eq!(value["items"][0]["content"].as_str().unwrap().len(), 400);
+
+        while truncate_largest_string(&mut value) {}
+        assert_eq!(value["file"], "src/big.rs");
+        assert!(value["content"]
+            .as_str()
+            .unwrap()
+            .ends_with(TRUNCATED_MARKER));
+        assert!(value["content"].as_str().unwrap().len() <= 64 + TRUNCATED_MARKER.len());
+    }
+
+    #[test]
+    fn truncate_largest_string_lets_enforce_max_chars_keep_partial_content() {
+        #[derive(Serialize)]
+        struct Out {
+            used: usize,
+            data: serde_json::Value,
+        }
+        let mut out = Out {
+            used: 0,
+            data: serde_json::json!({ "content": "z".repeat(5000) }),
+        };
+        let used = enforce_max_chars(
+            &mut out,
+            600,
+            |inner, used| inner.used = used,
+            |_| {},
+            |inner| truncate_largest_string(&mut inner.data),
+        )
+        .unwrap();
+        assert!(used <= 600);
+        let content = out.data["content"].as_str().unwrap();
+        assert!(content.starts_with("zzz") && content.ends_with(TRUNCATED_MARKER));
+    }
+}
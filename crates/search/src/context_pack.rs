@@ -1,10 +1,15 @@
 use context_indexer::ToolMeta;
-use context_protocol::{BudgetTruncation, ToolNextAction};
+use context_protocol::{estimate_tokens, BudgetTruncation, ToolNextAction};
+use context_vector_store::SearchResult;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
 pub const CONTEXT_PACK_VERSION: u32 = 1;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Most omitted-item stubs a pack lists; `budget.omitted_items` keeps the full count.
+pub const MAX_OMITTED_STUBS: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct ContextPackOutput {
     pub version: u32,
     pub query: String,
@@ -12,13 +17,19 @@ pub struct ContextPackOutput {
     pub profile: String,
     pub items: Vec<ContextPackItem>,
     pub budget: ContextPackBudget,
+    /// Candidates left out to fit the budget, in pack order (at most [`MAX_OMITTED_STUBS`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub omitted: Vec<ContextPackOmitted>,
+    /// Earliest `indexed_at_ms` among the packed items
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oldest_result_ms: Option<u64>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub next_actions: Vec<ToolNextAction>,
     #[serde(default)]
     pub meta: ToolMeta,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, schemars::JsonSchema)]
 pub struct ContextPackItem {
     pub id: String,
     pub role: String,
@@ -36,14 +47,457 @@ pub struct ContextPackItem {
     pub relationship: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub distance: Option<usize>,
+    /// Budget bucket (first relationship hop) a related item was counted against;
+    /// only set when the pack was built with an explicit related budget.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    /// When the item's file was last indexed (unix ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexed_at_ms: Option<u64>,
+    /// File mtime (unix ms) seen when it was indexed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_mtime_ms: Option<u64>,
+}
+
+/// Placeholder for a primary or related item that existed but did not fit the pack.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, schemars::JsonSchema)]
+pub struct ContextPackOmitted {
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub role: String,
+    pub omitted: bool,
+    pub reason: String,
+}
+
+impl ContextPackOmitted {
+    #[must_use]
+    pub fn budget(item: &ContextPackItem) -> Self {
+        Self {
+            file: item.file.clone(),
+            start_line: item.start_line,
+            end_line: item.end_line,
+            symbol: item.symbol.clone(),
+            role: item.role.clone(),
+            omitted: true,
+            reason: "budget".to_string(),
+        }
+    }
+}
+
+/// Candidates left out while packing: every one is counted, the first few keep a stub.
+#[derive(Debug, Default)]
+pub struct OmittedItems {
+    stubs: Vec<ContextPackOmitted>,
+    count: usize,
+}
+
+impl OmittedItems {
+    pub fn push(&mut self, item: &ContextPackItem) {
+        self.count += 1;
+        if self.stubs.len() < MAX_OMITTED_STUBS {
+            self.stubs.push(ContextPackOmitted::budget(item));
+        }
+    }
+
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    #[must_use]
+    pub fn into_stubs(self) -> Vec<ContextPackOmitted> {
+        self.stubs
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, schemars::JsonSchema)]
 pub struct ContextPackBudget {
     pub max_chars: usize,
     pub used_chars: usize,
     pub truncated: bool,
     pub dropped_items: usize,
+    /// Primary and related candidates left out to fit the budget (see `omitted`)
+    #[serde(default)]
+    pub omitted_items: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub truncation: Option<BudgetTruncation>,
+    /// Token limit the pack was built under, when the caller set one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+    /// Estimated tokens of the serialized pack; reported only with `max_tokens`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub used_tokens: Option<usize>,
+}
+
+/// Allowance for the JSON fields wrapped around each item's content.
+const ITEM_OVERHEAD_CHARS: usize = 128;
+const ITEM_OVERHEAD_TOKENS: usize = 32;
+
+#[must_use]
+pub fn estimate_item_chars(item: &ContextPackItem) -> usize {
+    let imports: usize = item.imports.iter().map(|s| s.len() + 1).sum();
+    item.content.len() + imports + ITEM_OVERHEAD_CHARS
+}
+
+#[must_use]
+pub fn estimate_item_tokens(item: &ContextPackItem) -> usize {
+    let imports: usize = item.imports.iter().map(|s| estimate_tokens(s) + 1).sum();
+    estimate_tokens(&item.content) + imports + ITEM_OVERHEAD_TOKENS
+}
+
+/// Running cost of a pack while items are added greedily: a char limit plus an optional
+/// token limit, where whichever is tighter stops the pack.
+#[derive(Debug, Clone, Copy)]
+pub struct PackBudget {
+    max_chars: usize,
+    max_tokens: Option<usize>,
+    used_chars: usize,
+    used_tokens: usize,
+}
+
+impl PackBudget {
+    #[must_use]
+    pub const fn new(max_chars: usize, max_tokens: Option<usize>) -> Self {
+        Self {
+            max_chars,
+            max_tokens,
+            used_chars: 0,
+            used_tokens: 0,
+        }
+    }
+
+    #[must_use]
+    pub const fn used_chars(&self) -> usize {
+        self.used_chars
+    }
+
+    /// Charge `item` if it fits both limits; otherwise report the limit it would break.
+    pub fn try_add(&mut self, item: &ContextPackItem) -> Result<(), BudgetTruncation> {
+        let chars = self.used_chars.saturating_add(estimate_item_chars(item));
+        if chars > self.max_chars {
+            return Err(BudgetTruncation::MaxChars);
+        }
+        let tokens = self.used_tokens.saturating_add(estimate_item_tokens(item));
+        if self.max_tokens.is_some_and(|max| tokens > max) {
+            return Err(BudgetTruncation::MaxTokens);
+        }
+        self.used_chars = chars;
+        self.used_tokens = tokens;
+        Ok(())
+    }
+
+    /// Re-charge a packed item growing from `packed` to `grown`; false (and nothing
+    /// charged) when the growth would break either limit.
+    pub fn try_grow(&mut self, packed: &ContextPackItem, grown: &ContextPackItem) -> bool {
+        let chars = (self.used_chars - estimate_item_chars(packed)) + estimate_item_chars(grown);
+        let tokens =
+            (self.used_tokens - estimate_item_tokens(packed)) + estimate_item_tokens(grown);
+        if chars > self.max_chars || self.max_tokens.is_some_and(|max| tokens > max) {
+            return false;
+        }
+        self.used_chars = chars;
+        self.used_tokens = tokens;
+        true
+    }
+
+    #[must_use]
+    pub fn finish(
+        self,
+        truncation: Option<BudgetTruncation>,
+        dropped_items: usize,
+        omitted_items: usize,
+    ) -> ContextPackBudget {
+        ContextPackBudget {
+            max_chars: self.max_chars,
+            used_chars: self.used_chars,
+            truncated: truncation.is_some(),
+            dropped_items,
+            omitted_items,
+            truncation,
+            max_tokens: self.max_tokens,
+            used_tokens: self.max_tokens.map(|_| self.used_tokens),
+        }
+    }
+}
+
+impl ContextPackItem {
+    #[must_use]
+    pub const fn line_span(&self) -> usize {
+        self.end_line.saturating_sub(self.start_line) + 1
+    }
+
+    /// Grow this item to the union of both spans (`min(start)..max(end)`), stitching the
+    /// content from both, while keeping this item's rank: role, score and relationship stay
+    /// as packed. Symbol metadata follows whichever span is larger.
+    pub fn absorb_span(&mut self, other: Self) {
+        let start_line = self.start_line.min(other.start_line);
+        let end_line = self.end_line.max(other.end_line);
+        let own: Vec<&str> = self.content.split('\n').collect();
+        let theirs: Vec<&str> = other.content.split('\n').collect();
+        let line_at = |line: usize| {
+            if (self.start_line..=self.end_line).contains(&line) {
+                own.get(line - self.start_line).copied()
+            } else {
+                theirs.get(line - other.start_line).copied()
+            }
+        };
+        let content = (start_line..=end_line)
+            .filter_map(line_at)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if other.line_span() > self.line_span() {
+            self.symbol = other.symbol;
+            self.chunk_type = other.chunk_type;
+            self.imports = other.imports;
+        }
+        self.id = format!("{}:{start_line}:{end_line}", self.file);
+        self.start_line = start_line;
+        self.end_line = end_line;
+        self.content = content;
+    }
+}
+
+impl ContextPackOutput {
+    /// Drop the item at `idx` to shrink the pack, leaving a stub in its place while there is
+    /// room for one.
+    pub fn omit_item(&mut self, idx: usize) {
+        let item = self.items.remove(idx);
+        self.budget.dropped_items += 1;
+        self.budget.omitted_items += 1;
+        if self.omitted.len() < MAX_OMITTED_STUBS {
+            self.omitted.push(ContextPackOmitted::budget(&item));
+        }
+        self.oldest_result_ms = oldest_indexed_at_ms(&self.items);
+    }
+}
+
+/// Pack order within one kind of result: higher score first, then path and span, so the
+/// same candidates always produce the same pack.
+#[must_use]
+pub fn pack_order(a: &SearchResult, b: &SearchResult) -> Ordering {
+    b.score
+        .total_cmp(&a.score)
+        .then_with(|| a.chunk.file_path.cmp(&b.chunk.file_path))
+        .then_with(|| a.chunk.start_line.cmp(&b.chunk.start_line))
+        .then_with(|| a.chunk.end_line.cmp(&b.chunk.end_line))
+}
+
+/// Earliest `indexed_at_ms` of `items`, if any of them carry one.
+#[must_use]
+pub fn oldest_indexed_at_ms(items: &[ContextPackItem]) -> Option<u64> {
+    items.iter().filter_map(|item| item.indexed_at_ms).min()
+}
+
+/// Index of an already packed item from the same file whose line span overlaps `candidate`.
+#[must_use]
+pub fn find_overlapping_item(
+    items: &[ContextPackItem],
+    candidate: &ContextPackItem,
+) -> Option<usize> {
+    items.iter().position(|item| {
+        item.file == candidate.file
+            && item.start_line <= candidate.end_line
+            && candidate.start_line <= item.end_line
+    })
+}
+
+/// Fold `item` into a packed item from the same file whose span overlaps it, growing that
+/// item to the union of both spans (if the growth fits the budget). Returns the item back when
+/// nothing overlaps.
+pub fn merge_overlapping_span(
+    items: &mut [ContextPackItem],
+    item: ContextPackItem,
+    budget: &mut PackBudget,
+) -> Option<ContextPackItem> {
+    let Some(idx) = find_overlapping_item(items, &item) else {
+        return Some(item);
+    };
+    let packed = &mut items[idx];
+    if item.start_line < packed.start_line || item.end_line > packed.end_line {
+        let mut grown = packed.clone();
+        grown.absorb_span(item);
+        if budget.try_grow(packed, &grown) {
+            *packed = grown;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, file: &str, start_line: usize, end_line: usize) -> ContextPackItem {
+        ContextPackItem {
+            id: id.to_string(),
+            role: "primary".to_string(),
+            file: file.to_string(),
+            start_line,
+            end_line,
+            symbol: None,
+            chunk_type: None,
+            score: 1.0,
+            imports: Vec::new(),
+            content: String::new(),
+            relationship: None,
+            distance: None,
+            bucket: None,
+            indexed_at_ms: None,
+            file_mtime_ms: None,
+        }
+    }
+
+    #[test]
+    fn token_limit_stops_packing_before_char_limit() {
+        let mut dense = item("dense", "src/a.rs", 1, 1);
+        dense.content = "a(b[c]{d});".repeat(40);
+        let cost = estimate_item_tokens(&dense);
+
+        let mut chars_only = PackBudget::new(100_000, None);
+        let mut both = PackBudget::new(100_000, Some(cost * 2 + 1));
+        for _ in 0..2 {
+            assert!(chars_only.try_add(&dense).is_ok());
+            assert!(both.try_add(&dense).is_ok());
+        }
+        assert!(chars_only.try_add(&dense).is_ok());
+        assert_eq!(both.try_add(&dense), Err(BudgetTruncation::MaxTokens));
+
+        let budget = both.finish(Some(BudgetTruncation::MaxTokens), 1, 1);
+        assert_eq!(budget.used_tokens, Some(cost * 2));
+        assert_eq!(budget.used_chars, estimate_item_chars(&dense) * 2);
+        assert!(chars_only.finish(None, 0, 0).used_tokens.is_none());
+    }
+
+    #[test]
+    fn omitted_items_count_everything_but_cap_stubs() {
+        let mut omitted = OmittedItems::default();
+        for idx in 0..MAX_OMITTED_STUBS + 3 {
+            omitted.push(&item(&idx.to_string(), &format!("src/{idx}.rs"), 1, 2));
+        }
+        assert_eq!(omitted.count(), MAX_OMITTED_STUBS + 3);
+        let stubs = omitted.into_stubs();
+        assert_eq!(stubs.len(), MAX_OMITTED_STUBS);
+        assert_eq!(stubs[0].file, "src/0.rs");
+        assert!(stubs[0].omitted);
+        assert_eq!(stubs[0].reason, "budget");
+
+        let mut output = ContextPackOutput {
+            version: CONTEXT_PACK_VERSION,
+            query: "q".to_string(),
+            model_id: "m".to_string(),
+            profile: "general".to_string(),
+            items: vec![item("a", "src/a.rs", 1, 2), item("b", "src/b.rs", 3, 4)],
+            budget: PackBudget::new(1_000, None).finish(None, 0, 0),
+            omitted: Vec::new(),
+            oldest_result_ms: None,
+            next_actions: Vec::new(),
+            meta: ToolMeta::default(),
+        };
+        output.omit_item(1);
+        assert_eq!(output.items.len(), 1);
+        assert_eq!(output.budget.dropped_items, 1);
+        assert_eq!(output.budget.omitted_items, 1);
+        assert_eq!(
+            (output.omitted[0].start_line, output.omitted[0].end_line),
+            (3, 4)
+        );
+    }
+
+    #[test]
+    fn pack_order_breaks_score_ties_by_path_then_span() {
+        let result = |score: f32, file: &str, start: usize, end: usize| SearchResult {
+            id: format!("{file}:{start}:{end}"),
+            chunk: context_code_chunker::CodeChunk::new(
+                file.to_string(),
+                start,
+                end,
+                String::new(),
+                context_code_chunker::ChunkMetadata::default(),
+            ),
+            score,
+            freshness: None,
+        };
+        let mut results = [
+            result(0.5, "src/b.rs", 1, 9),
+            result(0.5, "src/a.rs", 4, 6),
+            result(0.9, "src/z.rs", 1, 1),
+            result(0.5, "src/a.rs", 4, 5),
+        ];
+        results.sort_by(pack_order);
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "src/z.rs:1:1",
+                "src/a.rs:4:5",
+                "src/a.rs:4:6",
+                "src/b.rs:1:9"
+            ]
+        );
+    }
+
+    #[test]
+    fn overlap_requires_same_file_and_shared_lines() {
+        let items = vec![item("a", "src/a.rs", 10, 20), item("b", "src/b.rs", 1, 5)];
+        assert_eq!(
+            find_overlapping_item(&items, &item("c", "src/a.rs", 12, 14)),
+            Some(0)
+        );
+        assert_eq!(
+            find_overlapping_item(&items, &item("d", "src/a.rs", 20, 30)),
+            Some(0)
+        );
+        assert_eq!(
+            find_overlapping_item(&items, &item("e", "src/a.rs", 21, 30)),
+            None
+        );
+        assert_eq!(
+            find_overlapping_item(&items, &item("f", "src/c.rs", 1, 5)),
+            None
+        );
+    }
+
+    #[test]
+    fn absorb_span_keeps_rank() {
+        let mut kept = item("src/a.rs:12:14", "src/a.rs", 12, 14);
+        kept.score = 0.9;
+        let mut larger = item("src/a.rs:10:30", "src/a.rs", 10, 30);
+        larger.role = "related".to_string();
+        larger.content = (10..=30)
+            .map(|line| format!("l{line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        kept.absorb_span(larger);
+        assert_eq!(kept.id, "src/a.rs:10:30");
+        assert_eq!((kept.start_line, kept.end_line), (10, 30));
+        assert_eq!(kept.line_span(), 21);
+        assert_eq!(kept.role, "primary");
+        assert!(kept.content.starts_with("l10\nl11\n"));
+        assert!((kept.score - 0.9).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn absorb_span_unions_partial_overlaps() {
+        let mut kept = item("src/a.rs:10:13", "src/a.rs", 10, 13);
+        kept.content = "a10\na11\na12\na13".to_string();
+        let mut tail = item("src/a.rs:12:15", "src/a.rs", 12, 15);
+        tail.content = "b12\nb13\nb14\nb15".to_string();
+
+        let mut items = vec![kept];
+        let mut budget = PackBudget::new(10_000, None);
+        for packed in &items {
+            budget.try_add(packed).unwrap();
+        }
+        assert!(merge_overlapping_span(&mut items, tail, &mut budget).is_none());
+
+        let merged = &items[0];
+        assert_eq!(merged.id, "src/a.rs:10:15");
+        assert_eq!((merged.start_line, merged.end_line), (10, 15));
+        assert_eq!(merged.content, "a10\na11\na12\na13\nb14\nb15");
+        assert_eq!(budget.used_chars, estimate_item_chars(merged));
+    }
 }
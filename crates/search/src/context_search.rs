@@ -1,17 +1,37 @@
 use crate::error::Result;
 use crate::hybrid::HybridSearch;
 use context_code_chunker::CodeChunk;
-use context_graph::{AssemblyStrategy, ContextAssembler, GraphBuilder, GraphLanguage};
+use context_graph::{
+    AssemblyStrategy, ContextAssembler, GraphBuilder, GraphLanguage, RelatedChunk,
+};
 use context_vector_store::SearchResult;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+static GRAPH_BUILDS: AtomicU64 = AtomicU64::new(0);
+
+/// Number of code graphs built by [`ContextSearch::build_graph`] in this process
+#[must_use]
+pub fn graph_build_count() -> u64 {
+    GRAPH_BUILDS.load(Ordering::Relaxed)
+}
 
 /// Context-aware search with automatic related code assembly
 ///
 /// This is the flagship feature for AI agents - automatic context gathering
+///
+/// The assembler is read-only once built, so several instances (e.g. one per concurrent
+/// request) can share one graph via [`ContextSearch::with_shared_assembler`].
 pub struct ContextSearch {
     hybrid: HybridSearch,
-    assembler: Option<ContextAssembler>,
+    assembler: Option<Arc<ContextAssembler>>,
 }
 
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ContextAssembler>();
+};
+
 /// Enriched search result with automatically assembled context
 #[derive(Debug, Clone)]
 pub struct EnrichedResult {
@@ -32,10 +52,24 @@ pub struct EnrichedResult {
 pub struct RelatedContext {
     pub chunk: CodeChunk,
     pub relationship_path: Vec<String>,
+    /// Symbols visited from the primary result to this chunk, in order (primary excluded)
+    pub symbol_path: Vec<String>,
     pub distance: usize,
     pub relevance_score: f32,
 }
 
+impl From<RelatedChunk> for RelatedContext {
+    fn from(rc: RelatedChunk) -> Self {
+        Self {
+            symbol_path: rc.symbol_path(),
+            relationship_path: rc.relationship.iter().map(|r| format!("{r:?}")).collect(),
+            chunk: rc.chunk,
+            distance: rc.distance,
+            relevance_score: rc.relevance_score,
+        }
+    }
+}
+
 impl ContextSearch {
     /// Create new context-aware search (without graph initially)
     pub const fn new(hybrid: HybridSearch) -> Result<Self> {
@@ -45,15 +79,32 @@ impl ContextSearch {
         })
     }
 
-    /// Inject a pre-built graph/assembler (used by caching layers)
-    pub fn set_assembler(&mut self, assembler: ContextAssembler) {
+    /// Reuse an assembler built elsewhere instead of building a graph for this instance
+    #[must_use]
+    pub fn with_shared_assembler(mut self, assembler: Arc<ContextAssembler>) -> Self {
         self.assembler = Some(assembler);
+        self
+    }
+
+    /// Inject a pre-built graph/assembler (used by caching layers); the profile's
+    /// `path_weights` apply to its ranking
+    pub fn set_assembler(&mut self, mut assembler: ContextAssembler) {
+        if let Some(weight) = self.hybrid.profile().related_path_weight() {
+            assembler.set_path_weight(weight);
+        }
+        self.assembler = Some(Arc::new(assembler));
     }
 
     /// Borrow the current assembler (if available)
     #[must_use]
-    pub const fn assembler(&self) -> Option<&ContextAssembler> {
-        self.assembler.as_ref()
+    pub fn assembler(&self) -> Option<&ContextAssembler> {
+        self.assembler.as_deref()
+    }
+
+    /// Handle to the current assembler for sharing with other instances
+    #[must_use]
+    pub fn shared_assembler(&self) -> Option<Arc<ContextAssembler>> {
+        self.assembler.clone()
     }
 
     /// Build code graph for context assembly
@@ -66,6 +117,7 @@ impl ContextSearch {
 
         let mut builder = GraphBuilder::new(language)?;
         let graph = builder.build(&chunks)?;
+        GRAPH_BUILDS.fetch_add(1, Ordering::Relaxed);
 
         log::info!(
             "Code graph built: {} nodes, {} edges",
@@ -74,14 +126,17 @@ impl ContextSearch {
         );
 
         // Create assembler with the graph (doesn't need to clone)
-        let assembler = ContextAssembler::new(graph);
+        let mut assembler = ContextAssembler::new(graph);
+        if let Some(weight) = self.hybrid.profile().related_path_weight() {
+            assembler.set_path_weight(weight);
+        }
         let graph_stats = (
             assembler.get_stats().total_nodes,
             assembler.get_stats().total_edges,
         );
 
         // Store assembler (which owns the graph)
-        self.assembler = Some(assembler);
+        self.assembler = Some(Arc::new(assembler));
 
         // We'll get graph info from assembler when needed
         log::info!(
@@ -96,18 +151,32 @@ impl ContextSearch {
     /// Search with automatic context assembly (flagship feature)
     ///
     /// Returns search results with related code automatically gathered
-    #[allow(clippy::similar_names)]
     pub async fn search_with_context(
         &mut self,
         query: &str,
         limit: usize,
         strategy: AssemblyStrategy,
+    ) -> Result<Vec<EnrichedResult>> {
+        let assembler = self.assembler.clone();
+        self.search_with_assembler(query, limit, strategy, assembler.as_deref())
+            .await
+    }
+
+    /// Same as [`Self::search_with_context`], but enriches results with the given pre-built
+    /// assembler (or none) instead of this instance's own graph
+    #[allow(clippy::similar_names)]
+    pub async fn search_with_assembler(
+        &mut self,
+        query: &str,
+        limit: usize,
+        strategy: AssemblyStrategy,
+        assembler: Option<&ContextAssembler>,
     ) -> Result<Vec<EnrichedResult>> {
         // Perform hybrid search
         let results = self.hybrid.search(query, limit).await?;
 
         // If no graph, return non-enriched results
-        let Some(assembler) = &self.assembler else {
+        let Some(assembler) = assembler else {
             log::warn!("No graph available, returning non-enriched results");
             return Ok(results
                 .into_iter()
@@ -131,16 +200,7 @@ impl ContextSearch {
                     let related = assembled
                         .related_chunks
                         .into_iter()
-                        .map(|rc| RelatedContext {
-                            chunk: rc.chunk,
-                            relationship_path: rc
-                                .relationship
-                                .iter()
-                                .map(|r| format!("{r:?}"))
-                                .collect(),
-                            distance: rc.distance,
-                            relevance_score: rc.relevance_score,
-                        })
+                        .map(RelatedContext::from)
                         .collect();
 
                     enriched.push(EnrichedResult {
@@ -1,3 +1,4 @@
+use context_protocol::ErrorCode;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, SearchError>;
@@ -16,3 +17,15 @@ pub enum SearchError {
     #[error("{0}")]
     Other(String),
 }
+
+impl SearchError {
+    #[must_use]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::VectorStoreError(err) => err.code(),
+            Self::GraphError(err) => err.code(),
+            Self::EmptyQuery => ErrorCode::InvalidRequest,
+            Self::Other(_) => ErrorCode::Internal,
+        }
+    }
+}
@@ -1,28 +1,74 @@
+use crate::profile::SearchProfile;
 use crate::query_classifier::QueryWeights;
 use context_code_chunker::{ChunkType, CodeChunk};
 use std::collections::HashMap;
 
+/// Per-source multipliers applied to each ranking's RRF contribution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FusionWeights {
+    pub semantic: f32,
+    pub fuzzy: f32,
+}
+
+impl Default for FusionWeights {
+    fn default() -> Self {
+        Self {
+            semantic: 1.0,
+            fuzzy: 1.0,
+        }
+    }
+}
+
 /// Reciprocal Rank Fusion for combining multiple rankings
 pub struct RRFFusion {
     /// RRF constant k (typically 60)
     k: f32,
 
+    /// Added to `k` in [`Self::fuse_adaptive`] to flatten the tail of each ranking
+    adaptive_k_offset: f32,
+
     /// Weights for each ranking source
-    semantic_weight: f32,
-    fuzzy_weight: f32,
+    weights: FusionWeights,
 }
 
 impl RRFFusion {
+    pub const DEFAULT_K: f32 = 60.0;
+    pub const DEFAULT_ADAPTIVE_K_OFFSET: f32 = 40.0;
+
     #[must_use]
-    pub const fn new(semantic_weight: f32, fuzzy_weight: f32, k: f32) -> Self {
+    pub const fn new(k: f32, weights: FusionWeights) -> Self {
         Self {
             k,
-            semantic_weight,
-            fuzzy_weight,
+            adaptive_k_offset: Self::DEFAULT_ADAPTIVE_K_OFFSET,
+            weights,
         }
     }
 
-    /// Fuse semantic and fuzzy results using weights, logging the context
+    #[must_use]
+    pub const fn with_adaptive_k_offset(mut self, offset: f32) -> Self {
+        self.adaptive_k_offset = offset;
+        self
+    }
+
+    /// Fusion tuned by the profile's `fusion` section.
+    #[must_use]
+    pub const fn from_profile(profile: &SearchProfile) -> Self {
+        let config = profile.fusion();
+        Self::new(config.k, config.weights).with_adaptive_k_offset(config.adaptive_k_offset)
+    }
+
+    #[must_use]
+    pub const fn k(&self) -> f32 {
+        self.k
+    }
+
+    #[must_use]
+    pub const fn weights(&self) -> FusionWeights {
+        self.weights
+    }
+
+    /// Fuse semantic and fuzzy results using per-query weights (scaled by the source weights),
+    /// logging the context
     #[must_use]
     pub fn fuse_adaptive(
         &self,
@@ -32,7 +78,7 @@ impl RRFFusion {
         fuzzy_results: &[(usize, f32)],
     ) -> Vec<(usize, f32)> {
         // Increase k for fuzzy part to suppress noisy neighbors
-        let adaptive_k = self.k + 40.0;
+        let adaptive_k = self.k + self.adaptive_k_offset;
         log::debug!(
             "Adaptive weights for '{}': semantic={:.1}%, fuzzy={:.1}%",
             query,
@@ -43,8 +89,8 @@ impl RRFFusion {
         Self::fuse_with_weights(
             semantic_results,
             fuzzy_results,
-            weights.semantic,
-            weights.fuzzy,
+            weights.semantic * self.weights.semantic,
+            weights.fuzzy * self.weights.fuzzy,
             adaptive_k,
         )
     }
@@ -63,12 +109,30 @@ impl RRFFusion {
         Self::fuse_with_weights(
             semantic_results,
             fuzzy_results,
-            self.semantic_weight,
-            self.fuzzy_weight,
+            self.weights.semantic,
+            self.weights.fuzzy,
             self.k,
         )
     }
 
+    /// Fuse any number of rankings (e.g. one per embedding model), each scaled by its weight.
+    ///
+    /// Only ranks count; returns unique indices by decreasing fused score, ties broken by index.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fuse_rankings(&self, rankings: &[(Vec<usize>, f32)]) -> Vec<usize> {
+        let mut scores: HashMap<usize, f32> = HashMap::new();
+        for (ranking, weight) in rankings {
+            for (rank, idx) in ranking.iter().enumerate() {
+                *scores.entry(*idx).or_insert(0.0) += weight / (self.k + rank as f32 + 1.0);
+            }
+        }
+
+        let mut fused: Vec<(usize, f32)> = scores.into_iter().collect();
+        fused.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        fused.into_iter().map(|(idx, _)| idx).collect()
+    }
+
     /// Fuse with explicit weights
     #[allow(clippy::cast_precision_loss)]
     fn fuse_with_weights(
@@ -102,7 +166,59 @@ impl RRFFusion {
 
 impl Default for RRFFusion {
     fn default() -> Self {
-        Self::new(0.8, 0.2, 60.0)
+        Self::new(Self::DEFAULT_K, FusionWeights::default())
+    }
+}
+
+/// Multipliers applied by [`AstBooster`]; the defaults are the built-in heuristics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AstBoostConfig {
+    /// Per chunk type; types not listed fall back to `ChunkType::priority() / 100`
+    pub chunk_types: HashMap<ChunkType, f32>,
+    /// Chunks that carry documentation
+    pub documented: f32,
+    /// Chunks with context imports or a parent scope
+    pub with_context: f32,
+    /// Paths containing `/tests/`, `/test/` or `/agents/`
+    pub tests: f32,
+    /// Scripts, docker, db, deploy and infra paths
+    pub infra: f32,
+    /// Shared library paths (`packages/utils`, `/utils`, `src/lib`)
+    pub library: f32,
+}
+
+impl Default for AstBoostConfig {
+    fn default() -> Self {
+        let chunk_types = [
+            (ChunkType::Function, 1.18),
+            (ChunkType::Method, 1.18),
+            (ChunkType::Struct, 1.05),
+            (ChunkType::Class, 1.05),
+            (ChunkType::Enum, 1.05),
+            (ChunkType::Interface, 1.05),
+            (ChunkType::Variable, 0.9),
+            (ChunkType::Const, 0.9),
+        ]
+        .into_iter()
+        .collect();
+        Self {
+            chunk_types,
+            documented: 1.1,
+            with_context: 1.05,
+            tests: 0.6,
+            infra: 0.7,
+            library: 1.25,
+        }
+    }
+}
+
+impl AstBoostConfig {
+    #[must_use]
+    pub fn chunk_type_boost(&self, chunk_type: ChunkType) -> f32 {
+        self.chunk_types
+            .get(&chunk_type)
+            .copied()
+            .unwrap_or_else(|| f32::from(chunk_type.priority()) / 100.0)
     }
 }
 
@@ -114,26 +230,36 @@ impl AstBooster {
     /// Functions/Methods get higher boost than variables
     #[must_use]
     pub fn boost(chunks: &[CodeChunk], results: Vec<(usize, f32)>) -> Vec<(usize, f32)> {
+        Self::boost_with(&AstBoostConfig::default(), chunks, results)
+    }
+
+    /// Boost scores using explicit (e.g. profile-provided) rules
+    #[must_use]
+    pub fn boost_with(
+        config: &AstBoostConfig,
+        chunks: &[CodeChunk],
+        results: Vec<(usize, f32)>,
+    ) -> Vec<(usize, f32)> {
         results
             .into_iter()
             .map(|(idx, score)| {
-                let boost = chunks.get(idx).map_or(1.0, Self::compute_boost);
+                let boost = chunks
+                    .get(idx)
+                    .map_or(1.0, |chunk| Self::compute_boost(config, chunk));
                 (idx, score * boost)
             })
             .collect()
     }
 
-    fn compute_boost(chunk: &CodeChunk) -> f32 {
-        let type_boost = chunk.metadata.chunk_type.map_or(1.0, |ct| match ct {
-            ChunkType::Function | ChunkType::Method => 1.18,
-            ChunkType::Struct | ChunkType::Class | ChunkType::Enum | ChunkType::Interface => 1.05,
-            ChunkType::Variable | ChunkType::Const => 0.9,
-            _ => f32::from(ct.priority()) / 100.0,
-        });
+    fn compute_boost(config: &AstBoostConfig, chunk: &CodeChunk) -> f32 {
+        let type_boost = chunk
+            .metadata
+            .chunk_type
+            .map_or(1.0, |ct| config.chunk_type_boost(ct));
 
         // Additional boost for chunks with documentation
         let doc_boost = if chunk.metadata.documentation.is_some() {
-            1.1
+            config.documented
         } else {
             1.0
         };
@@ -142,7 +268,7 @@ impl AstBooster {
         let context_boost = if !chunk.metadata.context_imports.is_empty()
             || chunk.metadata.parent_scope.is_some()
         {
-            1.05
+            config.with_context
         } else {
             1.0
         };
@@ -150,7 +276,7 @@ impl AstBooster {
         let path = chunk.file_path.to_lowercase();
         let mut path_boost = 1.0;
         if path.contains("/agents/") || path.contains("/tests/") || path.contains("/test/") {
-            path_boost *= 0.6;
+            path_boost *= config.tests;
         }
         if path.contains("/scripts/")
             || path.contains("docker")
@@ -158,10 +284,10 @@ impl AstBooster {
             || path.contains("/deploy/")
             || path.contains("/infra/")
         {
-            path_boost *= 0.7;
+            path_boost *= config.infra;
         }
         if path.contains("packages/utils") || path.contains("/utils") || path.contains("src/lib") {
-            path_boost *= 1.25;
+            path_boost *= config.library;
         }
 
         type_boost * doc_boost * context_boost * path_boost
@@ -195,7 +321,13 @@ mod tests {
     #[test]
     fn test_rrf_weights() {
         // Heavy semantic weight
-        let fusion_semantic = RRFFusion::new(0.9, 0.1, 60.0);
+        let fusion_semantic = RRFFusion::new(
+            60.0,
+            FusionWeights {
+                semantic: 0.9,
+                fuzzy: 0.1,
+            },
+        );
 
         let semantic = vec![(0, 0.9)];
         let fuzzy = vec![(1, 0.9)];
@@ -206,6 +338,44 @@ mod tests {
         assert_eq!(fused[0].0, 0);
     }
 
+    #[test]
+    fn raising_semantic_weight_breaks_tie_toward_semantic_item() {
+        // Item 0 leads the semantic ranking, item 1 leads the fuzzy one: a tie at equal weights.
+        let semantic = vec![(0, 0.9), (1, 0.5)];
+        let fuzzy = vec![(1, 0.9), (0, 0.5)];
+        let weights = QueryWeights {
+            semantic: 0.5,
+            fuzzy: 0.5,
+            candidate_multiplier: 1,
+        };
+
+        let balanced = RRFFusion::default().fuse_adaptive("q", &weights, &semantic, &fuzzy);
+        assert!((balanced[0].1 - balanced[1].1).abs() < f32::EPSILON);
+
+        let semantic_heavy = RRFFusion::new(
+            RRFFusion::DEFAULT_K,
+            FusionWeights {
+                semantic: 2.0,
+                fuzzy: 1.0,
+            },
+        );
+        let fused = semantic_heavy.fuse_adaptive("q", &weights, &semantic, &fuzzy);
+        assert_eq!(fused[0].0, 0);
+        assert!(fused[0].1 > fused[1].1);
+    }
+
+    #[test]
+    fn weighted_rankings_favor_the_heavier_model() {
+        // Each model puts a different chunk first: equal weights tie, a heavier model wins.
+        let rankings = |weight_a: f32| vec![(vec![0, 1], weight_a), (vec![1, 0], 1.0)];
+        let fusion = RRFFusion::default();
+
+        assert_eq!(fusion.fuse_rankings(&rankings(1.0)), vec![0, 1]);
+        assert_eq!(fusion.fuse_rankings(&rankings(0.5)), vec![1, 0]);
+        assert_eq!(fusion.fuse_rankings(&rankings(2.0)), vec![0, 1]);
+        assert!(fusion.fuse_rankings(&[]).is_empty());
+    }
+
     #[test]
     fn test_ast_boosting() {
         let chunks = vec![
@@ -231,4 +401,36 @@ mod tests {
         // Function should have higher score than variable after boosting
         assert!(boosted[0].1 > boosted[1].1 || boosted[0].0 == 0);
     }
+
+    #[test]
+    fn penalized_tests_drop_below_equal_function() {
+        let function = |path: &str| {
+            CodeChunk::new(
+                path.to_string(),
+                1,
+                10,
+                "fn handle() {}".to_string(),
+                ChunkMetadata::default().chunk_type(ChunkType::Function),
+            )
+        };
+        let chunks = vec![
+            function("app/tests/handler.rs"),
+            function("app/src/handler.rs"),
+        ];
+        let results = vec![(0, 0.5), (1, 0.5)];
+
+        let neutral = AstBoostConfig {
+            tests: 1.0,
+            ..AstBoostConfig::default()
+        };
+        let boosted = AstBooster::boost_with(&neutral, &chunks, results.clone());
+        assert!((boosted[0].1 - boosted[1].1).abs() < f32::EPSILON);
+
+        let penalized = AstBoostConfig {
+            tests: 0.8,
+            ..AstBoostConfig::default()
+        };
+        let boosted = AstBooster::boost_with(&penalized, &chunks, results);
+        assert!(boosted[0].1 < boosted[1].1);
+    }
 }
@@ -1,30 +1,87 @@
 use context_code_chunker::CodeChunk;
-use nucleo_matcher::{pattern::Pattern, Matcher};
+use nucleo_matcher::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Matcher, Utf32String};
+
+/// How a query is matched against paths, symbol names and content previews.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FuzzyMatcher {
+    /// Subsequence matching with an extra boost when a symbol or file name starts with the query.
+    PrefixBoost,
+    /// The query must appear as a contiguous substring.
+    Substring,
+    /// Characters may match with gaps (classic fuzzy matching).
+    #[default]
+    Subsequence,
+}
+
+/// Tuning knobs for [`FuzzySearch`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuzzyConfig {
+    pub matcher: FuzzyMatcher,
+    /// Minimum normalized score (0-1) a match needs to be returned.
+    pub threshold: f32,
+    /// Added to the normalized score of prefix hits in [`FuzzyMatcher::PrefixBoost`] mode.
+    pub prefix_boost: f32,
+}
+
+impl Default for FuzzyConfig {
+    fn default() -> Self {
+        Self {
+            matcher: FuzzyMatcher::default(),
+            threshold: 0.0,
+            prefix_boost: 0.25,
+        }
+    }
+}
 
 /// Fuzzy search for code chunks using nucleo-matcher
 pub struct FuzzySearch {
     matcher: Matcher,
+    config: FuzzyConfig,
 }
 
 impl FuzzySearch {
     #[must_use]
     pub fn new() -> Self {
+        Self::with_config(FuzzyConfig::default())
+    }
+
+    #[must_use]
+    pub fn with_config(config: FuzzyConfig) -> Self {
         Self {
             matcher: Matcher::new(nucleo_matcher::Config::DEFAULT),
+            config,
         }
     }
 
+    #[must_use]
+    pub const fn config(&self) -> &FuzzyConfig {
+        &self.config
+    }
+
     /// Search chunks by fuzzy matching against paths and symbol names
-    /// Returns (`chunk_index`, score) sorted by score descending
-    #[allow(clippy::cast_precision_loss)]
+    /// Returns (`chunk_index`, score) sorted by score descending.
+    ///
+    /// Scores are normalized to 0-1 against the score the query gets when matched against
+    /// itself, so they are comparable across queries (an exact symbol hit is always 1.0).
     pub fn search(&mut self, query: &str, chunks: &[CodeChunk], limit: usize) -> Vec<(usize, f32)> {
-        let pattern = Pattern::parse(
-            query,
-            nucleo_matcher::pattern::CaseMatching::Smart,
-            nucleo_matcher::pattern::Normalization::Smart,
-        );
+        let pattern = match self.config.matcher {
+            FuzzyMatcher::Substring => Pattern::new(
+                query,
+                CaseMatching::Smart,
+                Normalization::Smart,
+                AtomKind::Substring,
+            ),
+            FuzzyMatcher::PrefixBoost | FuzzyMatcher::Subsequence => {
+                Pattern::parse(query, CaseMatching::Smart, Normalization::Smart)
+            }
+        };
+        let Some(ideal_score) = self.ideal_score(&pattern) else {
+            return Vec::new();
+        };
+        let prefix = query.trim().to_lowercase();
 
-        let mut scored: Vec<(usize, u32, bool)> = chunks
+        let mut scored: Vec<(usize, f32, bool)> = chunks
             .iter()
             .enumerate()
             .filter_map(|(idx, chunk)| {
@@ -35,11 +92,11 @@ impl FuzzySearch {
                     .is_some_and(|name| name.eq_ignore_ascii_case(query));
 
                 // Try matching against multiple targets
-                let path_haystack = nucleo_matcher::Utf32String::from(chunk.file_path.as_str());
+                let path_haystack = Utf32String::from(chunk.file_path.as_str());
                 let path_score = pattern.score(path_haystack.slice(..), &mut self.matcher);
 
                 let symbol_score = chunk.metadata.symbol_name.as_ref().and_then(|name| {
-                    let symbol_haystack = nucleo_matcher::Utf32String::from(name.as_str());
+                    let symbol_haystack = Utf32String::from(name.as_str());
                     pattern.score(symbol_haystack.slice(..), &mut self.matcher)
                 });
 
@@ -53,7 +110,7 @@ impl FuzzySearch {
                 } else {
                     &chunk.content
                 };
-                let content_haystack = nucleo_matcher::Utf32String::from(content_preview);
+                let content_haystack = Utf32String::from(content_preview);
                 let content_score = pattern.score(content_haystack.slice(..), &mut self.matcher);
 
                 // Take best score
@@ -62,34 +119,62 @@ impl FuzzySearch {
                     .flatten()
                     .max()?;
 
-                Some((idx, best_score, exact_symbol))
+                let score = if exact_symbol {
+                    1.0
+                } else {
+                    #[allow(clippy::cast_precision_loss)]
+                    let mut normalized = (best_score as f32 / ideal_score).min(1.0);
+                    if self.config.matcher == FuzzyMatcher::PrefixBoost
+                        && !prefix.is_empty()
+                        && starts_with_prefix(chunk, &prefix)
+                    {
+                        normalized = (normalized + self.config.prefix_boost).min(1.0);
+                    }
+                    normalized
+                };
+                (score >= self.config.threshold).then_some((idx, score, exact_symbol))
             })
             .collect();
 
-        #[allow(clippy::cast_precision_loss)]
-        let max_score = scored
-            .iter()
-            .map(|(_, score, _)| *score as f32)
-            .fold(0.0f32, f32::max);
-
         // Sort by exact symbol match first, then by score descending
-        scored.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| b.1.cmp(&a.1)));
+        scored.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| b.1.total_cmp(&a.1)));
         scored.truncate(limit);
 
         scored
             .into_iter()
-            .map(|(idx, score, exact_symbol)| {
-                let normalized = if exact_symbol {
-                    1.0
-                } else if max_score > 0.0 {
-                    score as f32 / max_score
-                } else {
-                    0.0
-                };
-                (idx, normalized)
-            })
+            .map(|(idx, score, _)| (idx, score))
             .collect()
     }
+
+    /// Score of the query matched against itself: the best any haystack can do.
+    #[allow(clippy::cast_precision_loss)]
+    fn ideal_score(&mut self, pattern: &Pattern) -> Option<f32> {
+        let ideal: u32 = pattern
+            .atoms
+            .iter()
+            .map(|atom| {
+                atom.score(atom.needle_text(), &mut self.matcher)
+                    .map_or(0, u32::from)
+            })
+            .sum();
+        (ideal > 0).then_some(ideal as f32)
+    }
+}
+
+/// Whether the chunk's symbol name or file name starts with `prefix` (lowercase).
+fn starts_with_prefix(chunk: &CodeChunk, prefix: &str) -> bool {
+    let file_name = chunk
+        .file_path
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(&chunk.file_path);
+    chunk
+        .metadata
+        .symbol_name
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(file_name))
+        .any(|name| name.to_lowercase().starts_with(prefix))
 }
 
 impl Default for FuzzySearch {
@@ -161,6 +246,88 @@ mod tests {
         assert!((results[0].1 - 1.0).abs() < f32::EPSILON);
     }
 
+    #[test]
+    fn test_prefix_match_outranks_scattered_subsequence() {
+        let chunks = vec![
+            create_chunk(
+                "src/a.rs",
+                "parse_config_value",
+                "fn parse_config_value() {}",
+            ),
+            create_chunk("src/b.rs", "load_config", "fn load_config() {}"),
+            create_chunk("src/c.rs", "config_loader", "fn config_loader() {}"),
+        ];
+
+        for matcher in [FuzzyMatcher::Subsequence, FuzzyMatcher::PrefixBoost] {
+            let mut fuzzy = FuzzySearch::with_config(FuzzyConfig {
+                matcher,
+                ..FuzzyConfig::default()
+            });
+            let results = fuzzy.search("conf", &chunks, 5);
+            assert_eq!(results[0].0, 2, "{matcher:?}: {results:?}");
+        }
+
+        // "pcv" only matches parse_config_value as a scattered subsequence
+        let mut fuzzy = FuzzySearch::new();
+        let prefix = fuzzy.search("conf", &chunks, 5)[0].1;
+        let scattered = fuzzy.search("pcv", &chunks, 5);
+        assert_eq!(scattered.len(), 1);
+        assert!(scattered[0].1 < prefix, "{scattered:?} vs {prefix}");
+    }
+
+    #[test]
+    fn test_substring_matcher_rejects_gaps() {
+        let mut fuzzy = FuzzySearch::with_config(FuzzyConfig {
+            matcher: FuzzyMatcher::Substring,
+            ..FuzzyConfig::default()
+        });
+        let chunks = vec![create_chunk(
+            "src/a.rs",
+            "parse_config_value",
+            "fn parse_config_value() {}",
+        )];
+
+        assert!(fuzzy.search("pcv", &chunks, 5).is_empty());
+        assert_eq!(fuzzy.search("config", &chunks, 5).len(), 1);
+    }
+
+    #[test]
+    fn test_scores_are_normalized_and_thresholded() {
+        let chunks = vec![
+            create_chunk("src/api/handler.rs", "process", "fn process() {}"),
+            create_chunk("src/main.rs", "main", "fn main() {}"),
+            create_chunk("tests/api_test.rs", "api", "fn api() {}"),
+            create_chunk("src/apple_pie.rs", "bake", "fn bake() {}"),
+        ];
+
+        for matcher in [
+            FuzzyMatcher::PrefixBoost,
+            FuzzyMatcher::Substring,
+            FuzzyMatcher::Subsequence,
+        ] {
+            let mut fuzzy = FuzzySearch::with_config(FuzzyConfig {
+                matcher,
+                prefix_boost: 0.9,
+                ..FuzzyConfig::default()
+            });
+            for query in ["api", "ap", "src", "p"] {
+                for (_, score) in fuzzy.search(query, &chunks, 10) {
+                    assert!((0.0..=1.0).contains(&score), "{matcher:?} {query}: {score}");
+                }
+            }
+        }
+
+        let mut all = FuzzySearch::new();
+        let mut strict = FuzzySearch::with_config(FuzzyConfig {
+            threshold: 0.99,
+            ..FuzzyConfig::default()
+        });
+        let all_hits = all.search("ap", &chunks, 10);
+        let strict_hits = strict.search("ap", &chunks, 10);
+        assert!(strict_hits.len() < all_hits.len());
+        assert!(strict_hits.iter().all(|(_, score)| *score >= 0.99));
+    }
+
     #[test]
     fn test_fuzzy_typo_tolerance() {
         let mut fuzzy = FuzzySearch::new();
@@ -35,27 +35,35 @@ impl HybridSearch {
             store,
             chunks,
             fuzzy: FuzzySearch::new(),
-            fusion: RRFFusion::default(),
+            fusion: RRFFusion::from_profile(&profile),
             expander: QueryExpander::new(),
             profile,
         })
     }
     /// Search with full hybrid strategy: semantic + fuzzy + RRF + AST boost
     pub async fn search(&mut self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-        if query.trim().is_empty() {
+        let ParsedQuery {
+            text: query,
+            phrases,
+            excluded,
+        } = parse_query(query);
+        let query = query.as_str();
+        if query.is_empty() {
             return Err(SearchError::EmptyQuery);
         }
 
-        log::debug!("Hybrid search: query='{query}', limit={limit}");
+        log::debug!(
+            "Hybrid search: query='{query}', phrases={phrases:?}, excluded={excluded:?}, limit={limit}"
+        );
 
         // Expand query with synonyms and variants
         let expanded_query = self.expander.expand_to_query(query);
         log::debug!("Expanded query: '{expanded_query}'");
 
-        let weights = QueryClassifier::weights(query);
+        let query_type = self.profile.classify_query(query);
+        let weights = QueryClassifier::weights_for(query_type, query);
         let candidate_pool = Self::candidate_pool(limit, weights.candidate_multiplier);
         let tokens = query_tokens(query);
-        let query_type = QueryClassifier::classify(query);
         let query_kind = match query_type {
             QueryType::Identifier => QueryKind::Identifier,
             QueryType::Path => QueryKind::Path,
@@ -120,7 +128,7 @@ impl HybridSearch {
             &self.profile,
             &self.chunks,
             &tokens,
-            AstBooster::boost(&self.chunks, fused_scores),
+            AstBooster::boost_with(self.profile.ast_boost(), &self.chunks, fused_scores),
             &semantic_map,
             &fuzzy_map,
         );
@@ -129,23 +137,24 @@ impl HybridSearch {
         let mut final_results: Vec<SearchResult> = boosted_scores
             .into_iter()
             .filter_map(|(idx, score)| {
-                self.chunks.get(idx).map(|chunk| {
-                    let id = format!(
-                        "{}:{}:{}",
-                        chunk.file_path, chunk.start_line, chunk.end_line
-                    );
-                    let weight = match query_type {
-                        QueryType::Conceptual => self.profile.path_weight(&chunk.file_path),
-                        QueryType::Identifier | QueryType::Path => {
-                            self.profile.path_boost_weight(&chunk.file_path)
-                        }
-                    };
-                    let penalized = score * weight;
-                    SearchResult {
-                        chunk: chunk.clone(),
-                        score: penalized,
-                        id,
+                let chunk = self.chunks.get(idx)?;
+                let exclusion = exclusion_factor(chunk, &excluded)?;
+                let id = format!(
+                    "{}:{}:{}",
+                    chunk.file_path, chunk.start_line, chunk.end_line
+                );
+                let weight = match query_type {
+                    QueryType::Conceptual => self.profile.path_weight(&chunk.file_path),
+                    QueryType::Identifier | QueryType::Path => {
+                        self.profile.path_boost_weight(&chunk.file_path)
                     }
+                };
+                let penalized = score * weight * exclusion * phrase_factor(chunk, &phrases);
+                Some(SearchResult {
+                    chunk: chunk.clone(),
+                    score: penalized,
+                    id,
+                    freshness: None,
                 })
             })
             .collect();
@@ -177,9 +186,12 @@ impl HybridSearch {
             return Ok(vec![]);
         }
 
+        let parsed: Vec<ParsedQuery> = queries.iter().map(|q| parse_query(q)).collect();
+        let queries: Vec<&str> = parsed.iter().map(|p| p.text.as_str()).collect();
+
         // Check for empty queries
-        for query in queries {
-            if query.trim().is_empty() {
+        for query in &queries {
+            if query.is_empty() {
                 return Err(SearchError::EmptyQuery);
             }
         }
@@ -192,7 +204,7 @@ impl HybridSearch {
 
         let query_weights: Vec<QueryWeights> = queries
             .iter()
-            .map(|q| QueryClassifier::weights(q))
+            .map(|q| QueryClassifier::weights_for(self.profile.classify_query(q), q))
             .collect();
         let tokens: Vec<Vec<String>> = queries.iter().map(|q| query_tokens(q)).collect();
         let max_multiplier = query_weights
@@ -242,7 +254,7 @@ impl HybridSearch {
         for (i, query) in queries.iter().enumerate() {
             let semantic_results = &semantic_results_batch[i];
             let weights = query_weights[i];
-            let query_type = QueryClassifier::classify(query);
+            let query_type = self.profile.classify_query(query);
 
             // Convert semantic results to (chunk_idx, score)
             let semantic_scores: Vec<(usize, f32)> = semantic_results
@@ -274,7 +286,7 @@ impl HybridSearch {
                 &self.profile,
                 &self.chunks,
                 &tokens[i],
-                AstBooster::boost(&self.chunks, fused_scores),
+                AstBooster::boost_with(self.profile.ast_boost(), &self.chunks, fused_scores),
                 &semantic_map,
                 &fuzzy_map,
             );
@@ -284,6 +296,7 @@ impl HybridSearch {
                 .into_iter()
                 .filter_map(|(idx, score)| {
                     self.chunks.get(idx).and_then(|chunk| {
+                        let exclusion = exclusion_factor(chunk, &parsed[i].excluded)?;
                         has_query_overlap(chunk, &tokens[i]).then(|| {
                             let id = format!(
                                 "{}:{}:{}",
@@ -295,11 +308,15 @@ impl HybridSearch {
                                     self.profile.path_boost_weight(&chunk.file_path)
                                 }
                             };
-                            let penalized = score * weight;
+                            let penalized = score
+                                * weight
+                                * exclusion
+                                * phrase_factor(chunk, &parsed[i].phrases);
                             SearchResult {
                                 chunk: chunk.clone(),
                                 score: penalized,
                                 id,
+                                freshness: None,
                             }
                         })
                     })
@@ -332,20 +349,27 @@ impl HybridSearch {
         query: &str,
         limit: usize,
     ) -> Result<Vec<SearchResult>> {
-        if query.trim().is_empty() {
+        let ParsedQuery {
+            text: query,
+            excluded,
+            ..
+        } = parse_query(query);
+        if query.is_empty() {
             return Err(SearchError::EmptyQuery);
         }
 
-        let query_kind = match QueryClassifier::classify(query) {
+        let query_kind = match self.profile.classify_query(&query) {
             QueryType::Identifier => QueryKind::Identifier,
             QueryType::Path => QueryKind::Path,
             QueryType::Conceptual => QueryKind::Conceptual,
         };
-        let embedding_query = self.profile.embedding().render_query(query_kind, query)?;
-        self.store
+        let embedding_query = self.profile.embedding().render_query(query_kind, &query)?;
+        let mut results = self
+            .store
             .search_with_embedding_text(&embedding_query, limit)
-            .await
-            .map_err(Into::into)
+            .await?;
+        results.retain(|result| exclusion_factor(&result.chunk, &excluded).is_some());
+        Ok(results)
     }
 
     /// Get chunk by ID
@@ -363,6 +387,16 @@ impl HybridSearch {
         &self.chunks
     }
 
+    #[must_use]
+    pub const fn profile(&self) -> &SearchProfile {
+        &self.profile
+    }
+
+    /// Replace the query expander (e.g. one extended with project synonyms)
+    pub fn set_query_expander(&mut self, expander: QueryExpander) {
+        self.expander = expander;
+    }
+
     /// Normalize scores to 0-1 range using min-max normalization
     pub fn normalize_scores(results: &mut [SearchResult]) {
         const MIN_DELTA: f32 = 1e-6;
@@ -452,9 +486,179 @@ impl HybridSearch {
     }
 }
 
+/// Score multiplier for a chunk whose content (but not path or symbol) contains an excluded term.
+const EXCLUDED_CONTENT_PENALTY: f32 = 0.2;
+
+/// Score multiplier for a chunk whose content contains every quoted phrase; a chunk matching
+/// only some of them gets a proportional share of the boost.
+const PHRASE_MATCH_BOOST: f32 = 2.0;
+
+/// A query with its operators split off.
+pub struct ParsedQuery {
+    /// What gets embedded and matched: the query without `-term` words and quote marks, so
+    /// quoted words still count as ordinary terms.
+    pub text: String,
+    /// `"quoted"` phrases, lowercased with whitespace collapsed.
+    pub phrases: Vec<String>,
+    /// `-term` exclusions, lowercased and deduplicated.
+    pub excluded: Vec<String>,
+}
+
+/// Split the `-term` exclusions and `"quoted"` phrases off `query`. Words inside quotes are
+/// never exclusions.
+#[must_use]
+pub fn parse_query(query: &str) -> ParsedQuery {
+    let (query, excluded) = split_exclusions(query);
+    let (text, phrases) = split_phrases(&query);
+    ParsedQuery {
+        text,
+        phrases,
+        excluded,
+    }
+}
+
+/// The term a `-term` query word excludes, if `word` is one. The term must start with a letter
+/// or `_`, so a lone `-`, `--flag` or `-1` stays part of the query.
+fn exclusion_term(word: &str) -> Option<&str> {
+    let term = word.strip_prefix('-')?;
+    term.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        .then_some(term)
+}
+
+/// Split `-term` exclusion words off `query`: the query text without them, and the excluded
+/// terms lowercased and deduplicated. Words inside a quoted phrase are kept.
+fn split_exclusions(query: &str) -> (String, Vec<String>) {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut excluded: Vec<String> = Vec::new();
+    let mut in_phrase = false;
+    for word in query.split_whitespace() {
+        let quoted = in_phrase || word.starts_with('"');
+        if word.matches('"').count() % 2 == 1 {
+            in_phrase = !in_phrase;
+        }
+        match exclusion_term(word).filter(|_| !quoted) {
+            Some(term) => excluded.push(term.to_lowercase()),
+            None => kept.push(word),
+        }
+    }
+    excluded.sort();
+    excluded.dedup();
+    (kept.join(" "), excluded)
+}
+
+/// Lowercase `text` and collapse its whitespace, so phrases match across line breaks.
+fn normalize_phrase(text: &str) -> String {
+    text.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split `"quoted"` phrases off `query`: the query text with the quote marks removed (the
+/// phrase words stay in it), and the normalized, deduplicated phrases. An unmatched quote is
+/// dropped and the words after it stay ordinary terms.
+fn split_phrases(query: &str) -> (String, Vec<String>) {
+    let mut text = String::with_capacity(query.len());
+    let mut phrases = Vec::new();
+    let mut rest = query;
+    while let Some(open) = rest.find('"') {
+        text.push_str(&rest[..open]);
+        text.push(' ');
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('"') else {
+            rest = after;
+            break;
+        };
+        let phrase = normalize_phrase(&after[..close]);
+        if !phrase.is_empty() {
+            phrases.push(phrase);
+        }
+        text.push_str(&after[..close]);
+        text.push(' ');
+        rest = &after[close + 1..];
+    }
+    text.push_str(rest);
+    phrases.sort();
+    phrases.dedup();
+    (
+        text.split_whitespace().collect::<Vec<_>>().join(" "),
+        phrases,
+    )
+}
+
+/// Score factor for `chunk` under the quoted `phrases`: up to [`PHRASE_MATCH_BOOST`] by the
+/// share of phrases its content contains verbatim (ignoring case and whitespace runs).
+pub(crate) fn phrase_factor(chunk: &CodeChunk, phrases: &[String]) -> f32 {
+    if phrases.is_empty() {
+        return 1.0;
+    }
+    let content = normalize_phrase(&chunk.content);
+    let matched = phrases
+        .iter()
+        .filter(|phrase| content.contains(phrase.as_str()))
+        .count();
+    1.0 + (PHRASE_MATCH_BOOST - 1.0) * matched as f32 / phrases.len() as f32
+}
+
+/// Score factor for `chunk` under the `excluded` terms: `None` drops it (a path segment or
+/// symbol word matches a term), [`EXCLUDED_CONTENT_PENALTY`] demotes it (only its content
+/// does). Terms match whole words, so `-test` keeps `latest.rs` and `attest()`.
+pub(crate) fn exclusion_factor(chunk: &CodeChunk, excluded: &[String]) -> Option<f32> {
+    if excluded.is_empty() {
+        return Some(1.0);
+    }
+    let terms: Vec<Vec<String>> = excluded
+        .iter()
+        .map(|term| identifier_words(term))
+        .filter(|words| !words.is_empty())
+        .collect();
+    let matches = |text: &str| {
+        let words = identifier_words(text);
+        terms
+            .iter()
+            .any(|term| words.windows(term.len()).any(|run| run == term.as_slice()))
+    };
+    let symbol = chunk.metadata.symbol_name.as_deref().unwrap_or_default();
+    if matches(&chunk.file_path) || matches(symbol) {
+        return None;
+    }
+    if matches(&chunk.content) {
+        return Some(EXCLUDED_CONTENT_PENALTY);
+    }
+    Some(1.0)
+}
+
+/// Lowercased words of `text`: alphanumeric runs split at `camelCase` boundaries, so path
+/// segments, file stems and `snake_case` or `PascalCase` identifiers all yield their parts.
+fn identifier_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for run in text.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = run.chars().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (prev, cur) = (chars[i - 1], chars[i]);
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if cur.is_uppercase()
+                && (prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower))
+            {
+                words.push(chars[start..i].iter().collect::<String>().to_lowercase());
+                start = i;
+            }
+        }
+        if start < chars.len() {
+            words.push(chars[start..].iter().collect::<String>().to_lowercase());
+        }
+    }
+    words
+}
+
+/// Lowercased words of `query` worth matching (three or more ASCII alphanumerics); `-term`
+/// exclusion words are skipped.
 pub(crate) fn query_tokens(query: &str) -> Vec<String> {
     let mut tokens: Vec<String> = query
-        .split(|c: char| !c.is_ascii_alphanumeric())
+        .split_whitespace()
+        .filter(|word| exclusion_term(word).is_none())
+        .flat_map(|word| word.split(|c: char| !c.is_ascii_alphanumeric()))
         .filter_map(|raw| {
             let token = raw.trim().to_ascii_lowercase();
             if token.len() < 3 {
@@ -605,4 +809,87 @@ mod tests {
         assert!(has_query_overlap(&chunk, &tokens));
         assert!(!has_query_overlap(&missing, &tokens));
     }
+
+    #[test]
+    fn dash_words_become_exclusions() {
+        let (query, excluded) = split_exclusions("auth  -tests - handler --verbose -1 -Mock");
+        assert_eq!(query, "auth - handler --verbose -1");
+        assert_eq!(excluded, vec!["mock".to_string(), "tests".to_string()]);
+        assert_eq!(
+            query_tokens("auth -tests handler"),
+            vec!["auth".to_string(), "handler".to_string()]
+        );
+        assert_eq!(query_tokens("auth - x"), vec!["auth".to_string()]);
+
+        let excluded = vec!["tests".to_string()];
+        let in_path = create_test_chunk("tests/auth.rs", 1, "login", "fn login() {}");
+        let in_content = create_test_chunk("src/auth.rs", 1, "login", "// see tests");
+        let clean = create_test_chunk("src/auth.rs", 20, "logout", "fn logout() {}");
+        assert_eq!(exclusion_factor(&in_path, &excluded), None);
+        assert_eq!(
+            exclusion_factor(&in_content, &excluded),
+            Some(EXCLUDED_CONTENT_PENALTY)
+        );
+        assert_eq!(exclusion_factor(&clean, &excluded), Some(1.0));
+    }
+
+    #[test]
+    fn exclusions_match_whole_path_segments_and_identifier_words() {
+        let excluded = vec!["test".to_string()];
+        let factor = |path: &str, symbol: &str, content: &str| {
+            exclusion_factor(&create_test_chunk(path, 1, symbol, content), &excluded)
+        };
+        assert_eq!(
+            factor("src/latest.rs", "attest", "fn attest() {}"),
+            Some(1.0)
+        );
+        assert_eq!(factor("src/auth_test.rs", "login", "fn login() {}"), None);
+        assert_eq!(factor("src/auth.test.ts", "login", "fn login() {}"), None);
+        assert_eq!(
+            factor("src/auth.rs", "TestClient", "struct TestClient;"),
+            None
+        );
+        assert_eq!(factor("src/auth.rs", "run_test", "fn run_test() {}"), None);
+        assert_eq!(
+            factor("src/auth.rs", "login", "// covered by the contest test"),
+            Some(EXCLUDED_CONTENT_PENALTY)
+        );
+        assert_eq!(
+            factor("src/auth.rs", "login", "// the contest is protested"),
+            Some(1.0)
+        );
+
+        let excluded = vec!["http_client".to_string()];
+        let chunk = create_test_chunk("src/net.rs", 1, "HTTPClient", "struct HTTPClient;");
+        assert_eq!(exclusion_factor(&chunk, &excluded), None);
+        assert_eq!(
+            identifier_words("src/HTTPServer2Go/parseUtf8Data.rs"),
+            vec!["src", "http", "server2", "go", "parse", "utf8", "data", "rs"]
+        );
+    }
+
+    #[test]
+    fn quoted_phrases_are_split_before_tokenization() {
+        let parsed = parse_query(r#"backoff "Retry  the request" -tests "skip -cache" "open"#);
+        assert_eq!(parsed.text, "backoff Retry the request skip -cache open");
+        assert_eq!(
+            parsed.phrases,
+            vec!["retry the request".to_string(), "skip -cache".to_string()]
+        );
+        assert_eq!(parsed.excluded, vec!["tests".to_string()]);
+
+        let phrases = parsed.phrases;
+        let both = create_test_chunk(
+            "src/a.rs",
+            1,
+            "send",
+            "// Retry the\n    request, but skip -cache hits",
+        );
+        let one = create_test_chunk("src/b.rs", 1, "send", "retry the request now");
+        let scattered = create_test_chunk("src/c.rs", 1, "send", "the request may retry");
+        assert!((phrase_factor(&both, &phrases) - PHRASE_MATCH_BOOST).abs() < f32::EPSILON);
+        assert!((phrase_factor(&one, &phrases) - 1.5).abs() < f32::EPSILON);
+        assert!((phrase_factor(&scattered, &phrases) - 1.0).abs() < f32::EPSILON);
+        assert!((phrase_factor(&scattered, &[]) - 1.0).abs() < f32::EPSILON);
+    }
 }
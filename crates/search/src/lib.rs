@@ -8,20 +8,29 @@ mod multi;
 pub mod profile;
 mod rerank;
 mod task_pack;
+mod text_match;
 pub use context_vector_store::SearchResult;
 mod query_classifier;
 mod query_expansion;
 
 pub use context_pack::{
-    ContextPackBudget, ContextPackItem, ContextPackOutput, CONTEXT_PACK_VERSION,
+    estimate_item_chars, estimate_item_tokens, find_overlapping_item, merge_overlapping_span,
+    oldest_indexed_at_ms, pack_order, ContextPackBudget, ContextPackItem, ContextPackOmitted,
+    ContextPackOutput, OmittedItems, PackBudget, CONTEXT_PACK_VERSION, MAX_OMITTED_STUBS,
 };
-pub use context_search::{ContextSearch, EnrichedResult, RelatedContext};
+pub use context_search::{graph_build_count, ContextSearch, EnrichedResult, RelatedContext};
 pub use error::{Result, SearchError};
-pub use fusion::{AstBooster, RRFFusion};
-pub use fuzzy::FuzzySearch;
-pub use hybrid::HybridSearch;
-pub use multi::{MultiModelContextSearch, MultiModelHybridSearch};
-pub use profile::{Bm25Config, MatchKind, RerankConfig, SearchProfile, Thresholds};
+pub use fusion::{AstBoostConfig, AstBooster, FusionWeights, RRFFusion};
+pub use fuzzy::{FuzzyConfig, FuzzyMatcher, FuzzySearch};
+pub use hybrid::{parse_query, HybridSearch, ParsedQuery};
+pub use multi::{ModelTiming, MultiModelContextSearch, MultiModelHybridSearch};
+pub use profile::{
+    Bm25Config, ClassifierRule, FusionConfig, MatchKind, PathWeight, RerankConfig, SearchProfile,
+    Thresholds,
+};
 pub use query_classifier::{QueryClassifier, QueryType, QueryWeights};
 pub use query_expansion::QueryExpander;
-pub use task_pack::{NextAction, NextActionKind, TaskPackItem, TaskPackOutput, TASK_PACK_VERSION};
+pub use task_pack::{
+    graph_next_actions, NextAction, NextActionKind, TaskPackItem, TaskPackOutput, TASK_PACK_VERSION,
+};
+pub use text_match::{is_word_char, match_in_line};
@@ -1,21 +1,42 @@
 use crate::error::{Result, SearchError};
 use crate::fusion::{AstBooster, RRFFusion};
 use crate::fuzzy::FuzzySearch;
+use crate::hybrid::{exclusion_factor, parse_query, phrase_factor, ParsedQuery};
 use crate::profile::SearchProfile;
-use crate::query_classifier::{QueryClassifier, QueryType};
+use crate::query_classifier::{QueryClassifier, QueryType, QueryWeights};
 use crate::query_expansion::QueryExpander;
 use crate::rerank::rerank_candidates;
 use context_code_chunker::CodeChunk;
 use context_graph::{AssemblyStrategy, ContextAssembler, GraphBuilder, GraphLanguage};
-use context_vector_store::ChunkCorpus;
 use context_vector_store::ModelRegistry;
+use context_vector_store::{ChunkCorpus, ChunkFreshness};
 use context_vector_store::{QueryKind, SearchResult, VectorIndex};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// Semantic pool multiplier while a path scope is active.
+const SCOPED_SEMANTIC_OVERFETCH: usize = 4;
 
 struct SemanticSource {
     index: VectorIndex,
 }
 
+/// What one embedding model's semantic stage cost in the last search.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ModelTiming {
+    pub model_id: String,
+    /// Embedding the query
+    pub embed_ms: u64,
+    /// Vector lookup in the model's index
+    pub search_ms: u64,
+    /// Multiplier applied to the model's ranking when fusing
+    pub weight: f32,
+    /// The query vector came from the query embedding cache (`embed_ms` is then ~0)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cache_hit: bool,
+}
+
 /// Hybrid search combining semantic (multi-model) + fuzzy + RRF fusion.
 ///
 /// This searcher keeps the same output shape as `HybridSearch`, but uses multiple semantic experts
@@ -24,10 +45,17 @@ pub struct MultiModelHybridSearch {
     sources: HashMap<String, SemanticSource>,
     chunks: Vec<CodeChunk>,
     chunk_id_to_idx: HashMap<String, usize>,
+    /// Per chunk, when its file was indexed (only known with a chunk corpus)
+    freshness: Vec<Option<ChunkFreshness>>,
     rejected: Vec<bool>,
+    scoped: bool,
     fuzzy: FuzzySearch,
     fusion: RRFFusion,
     expander: QueryExpander,
+    query_type_override: Option<QueryType>,
+    /// Models to search instead of routing by `profile.experts()`
+    models_override: Option<Vec<String>>,
+    model_timings: Vec<ModelTiming>,
     profile: SearchProfile,
     registry: ModelRegistry,
 }
@@ -89,6 +117,7 @@ impl MultiModelHybridSearch {
                     .to_string(),
             ));
         }
+        let freshness = vec![None; chunks.len()];
         let rejected: Vec<bool> = chunks
             .iter()
             .map(|c| profile.is_rejected(&c.file_path))
@@ -98,10 +127,15 @@ impl MultiModelHybridSearch {
             sources: by_id,
             chunks,
             chunk_id_to_idx,
+            freshness,
             rejected,
+            scoped: false,
             fuzzy: FuzzySearch::new(),
-            fusion: RRFFusion::default(),
+            fusion: RRFFusion::from_profile(&profile),
             expander: QueryExpander::new(),
+            query_type_override: None,
+            models_override: None,
+            model_timings: Vec::new(),
             profile,
             registry,
         })
@@ -151,6 +185,10 @@ impl MultiModelHybridSearch {
                 "Chunk corpus is empty or does not match indexed chunk ids".to_string(),
             ));
         }
+        let freshness = chunks
+            .iter()
+            .map(|c| corpus.file_freshness(&c.file_path))
+            .collect();
 
         let rejected: Vec<bool> = chunks
             .iter()
@@ -161,10 +199,15 @@ impl MultiModelHybridSearch {
             sources: by_id,
             chunks,
             chunk_id_to_idx,
+            freshness,
             rejected,
+            scoped: false,
             fuzzy: FuzzySearch::new(),
-            fusion: RRFFusion::default(),
+            fusion: RRFFusion::from_profile(&profile),
             expander: QueryExpander::new(),
+            query_type_override: None,
+            models_override: None,
+            model_timings: Vec::new(),
             profile,
             registry,
         })
@@ -175,25 +218,120 @@ impl MultiModelHybridSearch {
         &self.chunks
     }
 
+    #[must_use]
+    pub const fn profile(&self) -> &SearchProfile {
+        &self.profile
+    }
+
+    /// When the file of `chunks()[idx]` was indexed, if the chunk corpus recorded it.
+    #[must_use]
+    pub fn chunk_freshness(&self, idx: usize) -> Option<ChunkFreshness> {
+        self.freshness.get(idx).copied().flatten()
+    }
+
+    /// Restrict every search stage to chunks whose path satisfies `allowed`. The scope
+    /// intersects with profile rejection: chunks the profile rejects stay excluded. Returns
+    /// how many chunks are still searchable.
+    pub fn restrict_paths(&mut self, allowed: impl Fn(&str) -> bool) -> usize {
+        for (idx, chunk) in self.chunks.iter().enumerate() {
+            if !allowed(&chunk.file_path) {
+                self.rejected[idx] = true;
+            }
+        }
+        self.scoped = true;
+        self.rejected.iter().filter(|rejected| !**rejected).count()
+    }
+
+    /// Replace the query expander (e.g. one extended with project synonyms)
+    pub fn set_query_expander(&mut self, expander: QueryExpander) {
+        self.expander = expander;
+    }
+
+    /// Force a query type instead of auto-classifying each query (`None` restores auto mode)
+    pub fn set_query_type(&mut self, query_type: Option<QueryType>) {
+        self.query_type_override = query_type;
+    }
+
+    /// Add (or replace) the semantic index of `model_id`, e.g. a model indexed on disk that
+    /// the engine was not built with. The canonical chunk order is unchanged.
+    pub fn add_source(&mut self, model_id: String, index: VectorIndex) {
+        self.sources.insert(model_id, SemanticSource { index });
+    }
+
+    /// Search exactly `models` (rank-fused, weighted by `experts.weights`) instead of routing
+    /// by the profile's experts; `None` restores routing. A model without a loaded index fails
+    /// the search.
+    pub fn set_models(&mut self, models: Option<Vec<String>>) {
+        self.models_override = models;
+    }
+
+    /// Models the last `search` embedded the query with and what each cost; empty when a
+    /// direct path or symbol match answered it.
+    #[must_use]
+    pub fn model_timings(&self) -> &[ModelTiming] {
+        &self.model_timings
+    }
+
+    /// Models whose query vector the last `search` took from the query embedding cache.
+    #[must_use]
+    pub fn query_embedding_cache_hits(&self) -> usize {
+        self.model_timings
+            .iter()
+            .filter(|timing| timing.cache_hit)
+            .count()
+    }
+
+    /// The type `search` will use for `query`: the override if set, otherwise the profile's
+    /// `classifier_rules` and then the built-in classifier.
+    #[must_use]
+    pub fn query_type(&self, query: &str) -> QueryType {
+        self.query_type_override
+            .unwrap_or_else(|| self.profile.classify_query(&parse_query(query).text))
+    }
+
+    /// Fusion weights and candidate multiplier `search` will use for `query`.
+    #[must_use]
+    pub fn query_weights(&self, query: &str) -> QueryWeights {
+        let query = parse_query(query).text;
+        QueryClassifier::weights_for(self.query_type(&query), &query)
+    }
+
     pub async fn search(&mut self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-        let query = query.trim();
+        let ParsedQuery {
+            text: query,
+            phrases,
+            excluded,
+        } = parse_query(query);
+        let query = query.as_str();
         if query.is_empty() {
             return Err(SearchError::EmptyQuery);
         }
+        self.model_timings.clear();
 
-        if let Some(results) = self.try_direct_file_path(query, limit) {
+        // Direct matches answer the query unless exclusions remove every hit.
+        let unexcluded = |mut results: Vec<SearchResult>| {
+            results.retain(|result| exclusion_factor(&result.chunk, &excluded).is_some());
+            (!results.is_empty()).then_some(results)
+        };
+        if let Some(results) = self.try_direct_file_path(query, limit).and_then(unexcluded) {
             return Ok(results);
         }
 
         if let Some(anchor) = Self::extract_symbol_anchor(query) {
             if anchor != query {
-                if let Some(results) = self.try_direct_symbol_match(&anchor, limit) {
+                if let Some(results) = self
+                    .try_direct_symbol_match(&anchor, limit)
+                    .and_then(unexcluded)
+                {
                     return Ok(results);
                 }
             }
         }
 
-        if let Some(results) = self.try_direct_symbol_match(query, limit) {
+        if let Some(results) = self
+            .try_direct_symbol_match(query, limit)
+            .and_then(unexcluded)
+        {
             return Ok(results);
         }
 
@@ -201,10 +339,10 @@ impl MultiModelHybridSearch {
         let expanded_query = self.expander.expand_to_query(query);
         let anchor = Self::extract_symbol_anchor(query).map(|a| self.expander.expand_to_query(&a));
 
-        let weights = QueryClassifier::weights(query);
+        let query_type = self.query_type(query);
+        let weights = self.query_weights(query);
         let candidate_pool = candidate_pool(limit, weights.candidate_multiplier);
         let tokens = crate::hybrid::query_tokens(query);
-        let query_type = QueryClassifier::classify(query);
         let query_kind = match query_type {
             QueryType::Identifier => QueryKind::Identifier,
             QueryType::Path => QueryKind::Path,
@@ -221,9 +359,10 @@ impl MultiModelHybridSearch {
             .render_query(query_kind, embedding_base)?;
 
         // 1) Multi-model semantic search (rank-fused), keeping per-chunk max cosine for rerank.
-        let (semantic_rank, semantic_map) = self
+        let (semantic_rank, semantic_map, model_timings) = self
             .semantic_search_multi(query, query_kind, &embedding_query, candidate_pool)
             .await?;
+        self.model_timings = model_timings;
 
         // 2) Fuzzy search (path/symbol matching)
         let min_fuzzy = self.profile.min_fuzzy_score();
@@ -249,7 +388,7 @@ impl MultiModelHybridSearch {
             &self.profile,
             &self.chunks,
             &tokens,
-            AstBooster::boost(&self.chunks, fused_scores),
+            AstBooster::boost_with(self.profile.ast_boost(), &self.chunks, fused_scores),
             &semantic_map,
             &fuzzy_map,
         );
@@ -258,23 +397,24 @@ impl MultiModelHybridSearch {
         let mut final_results: Vec<SearchResult> = boosted_scores
             .into_iter()
             .filter_map(|(idx, score)| {
-                self.chunks.get(idx).map(|chunk| {
-                    let id = format!(
-                        "{}:{}:{}",
-                        chunk.file_path, chunk.start_line, chunk.end_line
-                    );
-                    let weight = match query_type {
-                        QueryType::Conceptual => self.profile.path_weight(&chunk.file_path),
-                        QueryType::Identifier | QueryType::Path => {
-                            self.profile.path_boost_weight(&chunk.file_path)
-                        }
-                    };
-                    let penalized = score * weight;
-                    SearchResult {
-                        chunk: chunk.clone(),
-                        score: penalized,
-                        id,
+                let chunk = self.chunks.get(idx)?;
+                let exclusion = exclusion_factor(chunk, &excluded)?;
+                let id = format!(
+                    "{}:{}:{}",
+                    chunk.file_path, chunk.start_line, chunk.end_line
+                );
+                let weight = match query_type {
+                    QueryType::Conceptual => self.profile.path_weight(&chunk.file_path),
+                    QueryType::Identifier | QueryType::Path => {
+                        self.profile.path_boost_weight(&chunk.file_path)
                     }
+                };
+                let penalized = score * weight * exclusion * phrase_factor(chunk, &phrases);
+                Some(SearchResult {
+                    chunk: chunk.clone(),
+                    score: penalized,
+                    id,
+                    freshness: self.freshness[idx],
                 })
             })
             .collect();
@@ -290,7 +430,7 @@ impl MultiModelHybridSearch {
     }
 
     fn try_direct_file_path(&self, query: &str, limit: usize) -> Option<Vec<SearchResult>> {
-        if !matches!(QueryClassifier::classify(query), QueryType::Path) {
+        if !matches!(self.query_type(query), QueryType::Path) {
             return None;
         }
 
@@ -357,7 +497,12 @@ impl MultiModelHybridSearch {
                 );
                 #[allow(clippy::cast_precision_loss)]
                 let score = (rank as f32).mul_add(-1e-3, 1.0).max(0.0);
-                Some(SearchResult { chunk, score, id })
+                Some(SearchResult {
+                    chunk,
+                    score,
+                    id,
+                    freshness: self.freshness[idx],
+                })
             })
             .collect();
 
@@ -365,7 +510,7 @@ impl MultiModelHybridSearch {
     }
 
     fn try_direct_symbol_match(&self, query: &str, limit: usize) -> Option<Vec<SearchResult>> {
-        if !matches!(QueryClassifier::classify(query), QueryType::Identifier) {
+        if !matches!(self.query_type(query), QueryType::Identifier) {
             return None;
         }
         if query.split_whitespace().count() != 1 {
@@ -414,7 +559,12 @@ impl MultiModelHybridSearch {
                 );
                 #[allow(clippy::cast_precision_loss)]
                 let score = (rank as f32).mul_add(-1e-3, 1.0).max(0.0);
-                Some(SearchResult { chunk, score, id })
+                Some(SearchResult {
+                    chunk,
+                    score,
+                    id,
+                    freshness: self.freshness[idx],
+                })
             })
             .collect();
 
@@ -427,22 +577,23 @@ impl MultiModelHybridSearch {
         query_kind: QueryKind,
         embedding_query: &str,
         limit: usize,
-    ) -> Result<(Vec<(usize, f32)>, HashMap<usize, f32>)> {
-        let desired_models = self.profile.experts().semantic_models(query_kind);
-        let mut models: Vec<&str> = desired_models
-            .iter()
-            .map(String::as_str)
-            .filter(|id| self.sources.contains_key(*id))
-            .collect();
-
-        if models.is_empty() {
-            // Fallback: use any available model index to avoid hard-failing.
-            let mut available: Vec<&str> = self.sources.keys().map(String::as_str).collect();
-            available.sort_unstable();
-            if let Some(first) = available.first().copied() {
-                models.push(first);
+    ) -> Result<(Vec<(usize, f32)>, HashMap<usize, f32>, Vec<ModelTiming>)> {
+        let models: Vec<&str> = if let Some(explicit) = &self.models_override {
+            let missing: Vec<&str> = explicit
+                .iter()
+                .map(String::as_str)
+                .filter(|id| !self.sources.contains_key(*id))
+                .collect();
+            if !missing.is_empty() {
+                return Err(SearchError::Other(format!(
+                    "No semantic index loaded for model(s): {}",
+                    missing.join(", ")
+                )));
             }
-        }
+            explicit.iter().map(String::as_str).collect()
+        } else {
+            self.routed_models(raw_query, query_kind)
+        };
 
         if models.is_empty() {
             return Err(SearchError::Other(
@@ -450,30 +601,34 @@ impl MultiModelHybridSearch {
             ));
         }
 
-        if query_kind == QueryKind::Conceptual && models.len() <= 2 {
-            models = pick_single_conceptual_model(&models, raw_query);
-        }
-
         // Embed queries per model first so we can run index search without holding any locks.
-        let mut embeds: Vec<(&str, Vec<f32>)> = Vec::with_capacity(models.len());
+        let mut embeds: Vec<(&str, Vec<f32>, u64, bool)> = Vec::with_capacity(models.len());
         for &model_id in &models {
-            embeds.push((
-                model_id,
-                self.registry.embed(model_id, embedding_query).await?,
-            ));
+            let started = Instant::now();
+            let (query_vec, cache_hit) =
+                self.registry.embed_query(model_id, embedding_query).await?;
+            embeds.push((model_id, query_vec, elapsed_ms(started), cache_hit));
         }
 
         // Rank lists per model (idx order) + max cosine map for rerank thresholds.
-        let mut per_model_ranks: Vec<Vec<usize>> = Vec::with_capacity(models.len());
+        let mut per_model_ranks: Vec<(Vec<usize>, f32)> = Vec::with_capacity(models.len());
         let mut semantic_max: HashMap<usize, f32> = HashMap::new();
+        let mut timings: Vec<ModelTiming> = Vec::with_capacity(models.len());
 
-        for (model_id, query_vec) in embeds {
+        for (model_id, query_vec, embed_ms, cache_hit) in embeds {
             let Some(source) = self.sources.get(model_id) else {
                 continue;
             };
-
-            // Search by vector; map ids back to canonical chunk indices.
-            let hits = source.index.search_ids_by_vector(&query_vec, limit)?;
+            let started = Instant::now();
+
+            // Search by vector; map ids back to canonical chunk indices. A path scope discards
+            // out-of-scope hits after the lookup, so fetch a wider pool to keep `limit` filled.
+            let fetch = if self.scoped {
+                limit.saturating_mul(SCOPED_SEMANTIC_OVERFETCH)
+            } else {
+                limit
+            };
+            let hits = source.index.search_ids_by_vector(&query_vec, fetch)?;
             let mut rank = Vec::new();
             let mut seen: HashSet<usize> = HashSet::new();
             for (chunk_id, score) in hits {
@@ -493,24 +648,56 @@ impl MultiModelHybridSearch {
                     .or_insert(score);
             }
 
-            per_model_ranks.push(rank);
+            let weight = self.profile.experts().semantic_weight(query_kind, model_id);
+            timings.push(ModelTiming {
+                model_id: model_id.to_string(),
+                embed_ms,
+                search_ms: elapsed_ms(started),
+                weight,
+                cache_hit,
+            });
+            per_model_ranks.push((rank, weight));
         }
 
         if per_model_ranks.is_empty() {
-            return Ok((Vec::new(), semantic_max));
+            return Ok((Vec::new(), semantic_max, timings));
         }
 
-        // Fuse per-model rankings using RRF (rank-only), then use the fused order for the semantic
-        // list passed into the main fusion stage. Scores are ignored by RRF; we keep cosine values
-        // for downstream thresholding via `semantic_max`.
-        let fused = fuse_rrf(&per_model_ranks, 60.0);
+        // Fuse per-model rankings using weighted RRF (rank-only), then use the fused order for
+        // the semantic list passed into the main fusion stage. Scores are ignored by RRF; we keep
+        // cosine values for downstream thresholding via `semantic_max`.
+        let fused = self.fusion.fuse_rankings(&per_model_ranks);
         let semantic_rank: Vec<(usize, f32)> = fused
             .into_iter()
             .filter_map(|idx| semantic_max.get(&idx).copied().map(|score| (idx, score)))
             .take(limit)
             .collect();
 
-        Ok((semantic_rank, semantic_max))
+        Ok((semantic_rank, semantic_max, timings))
+    }
+
+    /// Models the profile's experts route `query_kind` to, limited to loaded indices.
+    fn routed_models(&self, raw_query: &str, query_kind: QueryKind) -> Vec<&str> {
+        let desired_models = self.profile.experts().semantic_models(query_kind);
+        let mut models: Vec<&str> = desired_models
+            .iter()
+            .map(String::as_str)
+            .filter(|id| self.sources.contains_key(*id))
+            .collect();
+
+        if models.is_empty() {
+            // Fallback: use any available model index to avoid hard-failing.
+            let mut available: Vec<&str> = self.sources.keys().map(String::as_str).collect();
+            available.sort_unstable();
+            if let Some(first) = available.first().copied() {
+                models.push(first);
+            }
+        }
+
+        if query_kind == QueryKind::Conceptual && models.len() <= 2 {
+            models = pick_single_conceptual_model(&models, raw_query);
+        }
+        models
     }
 
     fn extract_symbol_anchor(query: &str) -> Option<String> {
@@ -610,7 +797,11 @@ impl MultiModelContextSearch {
         })
     }
 
-    pub fn set_assembler(&mut self, assembler: ContextAssembler) {
+    /// Inject a pre-built graph/assembler; the profile's `path_weights` apply to its ranking
+    pub fn set_assembler(&mut self, mut assembler: ContextAssembler) {
+        if let Some(weight) = self.hybrid.profile().related_path_weight() {
+            assembler.set_path_weight(weight);
+        }
         self.assembler = Some(assembler);
     }
 
@@ -619,11 +810,16 @@ impl MultiModelContextSearch {
         self.assembler.as_ref()
     }
 
+    /// Forget the graph, so `search_with_context` returns results without related chunks.
+    pub fn clear_assembler(&mut self) {
+        self.assembler = None;
+    }
+
     pub fn build_graph(&mut self, language: GraphLanguage) -> Result<()> {
         let chunks: Vec<CodeChunk> = self.hybrid.chunks().to_vec();
         let mut builder = GraphBuilder::new(language)?;
         let graph = builder.build(&chunks)?;
-        self.assembler = Some(ContextAssembler::new(graph));
+        self.set_assembler(ContextAssembler::new(graph));
         Ok(())
     }
 
@@ -665,16 +861,7 @@ impl MultiModelContextSearch {
                     let related = assembled
                         .related_chunks
                         .into_iter()
-                        .map(|rc| crate::context_search::RelatedContext {
-                            chunk: rc.chunk,
-                            relationship_path: rc
-                                .relationship
-                                .iter()
-                                .map(|r| format!("{r:?}"))
-                                .collect(),
-                            distance: rc.distance,
-                            relevance_score: rc.relevance_score,
-                        })
+                        .map(crate::context_search::RelatedContext::from)
                         .collect();
                     enriched.push(crate::context_search::EnrichedResult {
                         total_lines: assembled.total_lines,
@@ -767,28 +954,15 @@ fn filter_fuzzy(scores: Vec<(usize, f32)>, rejected: &[bool], min_score: f32) ->
         .collect()
 }
 
-/// Return a list of unique indices ordered by decreasing fused RRF score.
-fn fuse_rrf(rankings: &[Vec<usize>], k: f32) -> Vec<usize> {
-    let mut scores: HashMap<usize, f32> = HashMap::new();
-
-    for ranking in rankings {
-        for (rank, idx) in ranking.iter().enumerate() {
-            #[allow(clippy::cast_precision_loss)]
-            let score = 1.0 / (k + rank as f32 + 1.0);
-            *scores.entry(*idx).or_insert(0.0) += score;
-        }
-    }
-
-    let mut fused: Vec<(usize, f32)> = scores.into_iter().collect();
-    fused.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
-    fused.into_iter().map(|(idx, _)| idx).collect()
+fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use context_code_chunker::{ChunkMetadata, ChunkType, CodeChunk};
-    use context_vector_store::StoredChunk;
+    use context_vector_store::{QueryEmbeddingCache, StoredChunk};
     use serde::Serialize;
     use std::path::PathBuf;
     use tempfile::TempDir;
@@ -898,6 +1072,186 @@ mod tests {
         assert_eq!(results[0].id, "a.rs:1:2");
     }
 
+    #[tokio::test]
+    async fn excluded_terms_remove_matching_chunks() {
+        let model_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join("models");
+        let registry = ModelRegistry::new_stub(model_dir).unwrap();
+
+        let tmp = TempDir::new().unwrap();
+        let chunks = vec![
+            chunk("src/alpha.rs", "alpha"),
+            chunk("tests/alpha.rs", "alpha"),
+            chunk("src/beta.rs", "beta"),
+        ];
+        let idx = write_index(&tmp, &registry, "bge-small", "small.json", chunks)
+            .await
+            .unwrap();
+        let sources = vec![("bge-small".to_string(), idx)];
+        let mut search =
+            MultiModelHybridSearch::new(sources, SearchProfile::general(), registry).unwrap();
+        search.set_models(Some(vec!["bge-small".to_string()]));
+
+        let ids = |results: &[SearchResult]| -> Vec<String> {
+            results.iter().map(|result| result.id.clone()).collect()
+        };
+        let plain = ids(&search.search("alpha", 10).await.unwrap());
+        assert!(
+            plain.contains(&"tests/alpha.rs:1:2".to_string()),
+            "{plain:?}"
+        );
+
+        let excluded = ids(&search.search("alpha -tests", 10).await.unwrap());
+        assert!(
+            !excluded.contains(&"tests/alpha.rs:1:2".to_string()),
+            "{excluded:?}"
+        );
+        assert_eq!(excluded[0], "src/alpha.rs:1:2");
+    }
+
+    #[tokio::test]
+    async fn quoted_phrase_outranks_scattered_words() {
+        let model_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join("models");
+        let registry = ModelRegistry::new_stub(model_dir).unwrap();
+
+        // Same words and length, so only the phrase boost separates them; the scattered chunk
+        // comes first to win any rank tie.
+        let tmp = TempDir::new().unwrap();
+        let chunks = vec![
+            chunk("src/scattered.rs", "request after the backoff retry"),
+            chunk("src/phrase.rs", "retry the request after backoff"),
+        ];
+        let idx = write_index(&tmp, &registry, "bge-small", "small.json", chunks)
+            .await
+            .unwrap();
+        let sources = vec![("bge-small".to_string(), idx)];
+        let mut search =
+            MultiModelHybridSearch::new(sources, SearchProfile::general(), registry).unwrap();
+        search.set_models(Some(vec!["bge-small".to_string()]));
+
+        let results = search
+            .search("\"retry the request\" backoff", 10)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|result| result.id.as_str()).collect();
+        assert_eq!(ids, vec!["src/phrase.rs:1:2", "src/scattered.rs:1:2"]);
+    }
+
+    #[tokio::test]
+    async fn repeated_queries_reuse_cached_query_embeddings() {
+        let model_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join("models");
+        let registry = ModelRegistry::new_stub(model_dir)
+            .unwrap()
+            .with_query_embedding_cache(Some(QueryEmbeddingCache::new(8)));
+
+        let tmp = TempDir::new().unwrap();
+        let chunks = vec![chunk("a.rs", "alpha"), chunk("b.rs", "beta")];
+        let idx = write_index(&tmp, &registry, "bge-small", "small.json", chunks)
+            .await
+            .unwrap();
+
+        let sources = vec![("bge-small".to_string(), idx)];
+        let profile = SearchProfile::general();
+        let mut search = MultiModelHybridSearch::new(sources, profile, registry).unwrap();
+        search.set_models(Some(vec!["bge-small".to_string()]));
+
+        let first = search.search("alpha", 3).await.unwrap();
+        assert_eq!(search.query_embedding_cache_hits(), 0);
+        assert!(!search.model_timings()[0].cache_hit);
+
+        let second = search.search("alpha", 3).await.unwrap();
+        assert_eq!(search.query_embedding_cache_hits(), 1);
+        assert!(search.model_timings()[0].cache_hit);
+        assert_eq!(first[0].id, second[0].id);
+    }
+
+    #[tokio::test]
+    async fn explicit_models_are_all_searched_with_profile_weights() {
+        let model_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join("models");
+        let registry = ModelRegistry::new_stub(model_dir).unwrap();
+
+        let tmp = TempDir::new().unwrap();
+        let chunks = vec![chunk("a.rs", "alpha"), chunk("b.rs", "beta")];
+        let idx_small = write_index(&tmp, &registry, "bge-small", "small.json", chunks.clone())
+            .await
+            .unwrap();
+        let idx_base = write_index(&tmp, &registry, "bge-base", "base.json", chunks)
+            .await
+            .unwrap();
+        let sources = vec![
+            ("bge-small".to_string(), idx_small),
+            ("bge-base".to_string(), idx_base),
+        ];
+        let profile = SearchProfile::from_bytes(
+            "weighted",
+            br#"{ "experts": { "weights": { "default": { "bge-base": 2.0 } } } }"#,
+            Some("general"),
+        )
+        .unwrap();
+        let mut search = MultiModelHybridSearch::new(sources, profile, registry).unwrap();
+
+        search.set_models(Some(vec!["bge-small".to_string(), "bge-base".to_string()]));
+        let results = search.search("alpha", 3).await.unwrap();
+        assert_eq!(results[0].id, "a.rs:1:2");
+        let timings = search.model_timings();
+        let models: Vec<&str> = timings.iter().map(|t| t.model_id.as_str()).collect();
+        assert_eq!(models, vec!["bge-small", "bge-base"]);
+        assert!((timings[0].weight - 1.0).abs() < f32::EPSILON);
+        assert!((timings[1].weight - 2.0).abs() < f32::EPSILON);
+
+        search.set_models(Some(vec!["multilingual-e5-small".to_string()]));
+        let err = search.search("alpha", 3).await.unwrap_err();
+        assert!(err.to_string().contains("multilingual-e5-small"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn restricted_paths_exclude_chunks_from_every_stage() {
+        let model_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join("models");
+        let registry = ModelRegistry::new_stub(model_dir).unwrap();
+
+        let tmp = TempDir::new().unwrap();
+        let chunks = vec![
+            chunk("crates/search/src/a.rs", "alpha"),
+            chunk("crates/cli/src/b.rs", "alpha beta"),
+        ];
+
+        let idx_small = write_index(&tmp, &registry, "bge-small", "small.json", chunks.clone())
+            .await
+            .unwrap();
+        let idx_base = write_index(&tmp, &registry, "bge-base", "base.json", chunks)
+            .await
+            .unwrap();
+
+        let sources = vec![
+            ("bge-small".to_string(), idx_small),
+            ("bge-base".to_string(), idx_base),
+        ];
+        let profile = SearchProfile::general();
+        let mut search = MultiModelHybridSearch::new(sources, profile, registry).unwrap();
+        let remaining = search.restrict_paths(|path| path.starts_with("crates/cli/"));
+        assert_eq!(remaining, 1);
+
+        let results = search.search("alpha", 5).await.unwrap();
+        assert!(!results.is_empty());
+        assert!(results
+            .iter()
+            .all(|r| r.chunk.file_path == "crates/cli/src/b.rs"));
+
+        let direct = search.search("crates/search/src/a.rs", 5).await.unwrap();
+        assert!(direct
+            .iter()
+            .all(|r| r.chunk.file_path != "crates/search/src/a.rs"));
+    }
+
     #[tokio::test]
     async fn path_queries_return_direct_file_hits() {
         let model_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -1,14 +1,22 @@
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 
+use crate::fusion::{AstBoostConfig, FusionWeights, RRFFusion};
+use crate::query_classifier::{QueryClassifier, QueryType};
 use anyhow::{anyhow, Context, Result};
+use context_code_chunker::ChunkType;
+use context_graph::PathWeightFn;
 use context_vector_store::{EmbeddingTemplates, ModelRegistry, QueryKind};
 use globset::{GlobBuilder, GlobMatcher};
-use serde::Deserialize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 const BUILTIN_GENERAL: &str = include_str!("../../../profiles/general.json");
 const BUILTIN_FAST: &str = include_str!("../../../profiles/fast.json");
 const BUILTIN_QUALITY: &str = include_str!("../../../profiles/quality.json");
 const BUILTIN_TARGETED_VENORUS: &str = include_str!("../../../profiles/targeted/venorus.json");
+const MAX_PATH_WEIGHT: f32 = 5.0;
 
 #[derive(Clone, Debug)]
 pub struct SearchProfile {
@@ -16,18 +24,35 @@ pub struct SearchProfile {
     #[allow(dead_code)]
     description: Option<String>,
     paths: PathRules,
+    path_weights: Vec<PathWeight>,
+    classifier_rules: Vec<ClassifierRule>,
     rerank: RerankConfig,
+    fusion: FusionConfig,
+    ast_boost: AstBoostConfig,
     graph_nodes: GraphNodesConfig,
     embedding: EmbeddingTemplates,
     experts: ExpertsConfig,
+    /// Merged source configuration, kept so [`SearchProfile::to_path`] can write it back
+    raw: RawProfile,
 }
 
 #[derive(Clone, Debug)]
 pub struct ExpertsConfig {
     semantic: SemanticExpertsConfig,
+    weights: SemanticWeightsConfig,
     graph_nodes: Vec<String>,
 }
 
+/// Per-model multipliers for each model's ranking when multi-model results are rank-fused,
+/// by query kind; a kind without an entry for the model falls back to `default`, then 1.0.
+#[derive(Clone, Debug, Default)]
+struct SemanticWeightsConfig {
+    default: HashMap<String, f32>,
+    identifier: HashMap<String, f32>,
+    path: HashMap<String, f32>,
+    conceptual: HashMap<String, f32>,
+}
+
 #[derive(Clone, Debug)]
 struct SemanticExpertsConfig {
     default: Vec<String>,
@@ -54,6 +79,7 @@ impl Default for ExpertsConfig {
         let graph_nodes = semantic.default.clone();
         Self {
             semantic,
+            weights: SemanticWeightsConfig::default(),
             graph_nodes,
         }
     }
@@ -93,6 +119,15 @@ impl ExpertsConfig {
                 .unwrap_or(default);
         }
 
+        if let Some(weights) = raw.weights {
+            cfg.weights = SemanticWeightsConfig {
+                default: weights.default.unwrap_or_default(),
+                identifier: weights.identifier.unwrap_or_default(),
+                path: weights.path.unwrap_or_default(),
+                conceptual: weights.conceptual.unwrap_or_default(),
+            };
+        }
+
         if let Some(graph_nodes) = raw.graph_nodes {
             cfg.graph_nodes = graph_nodes
                 .default
@@ -124,6 +159,20 @@ impl ExpertsConfig {
             &self.semantic.conceptual,
         )?;
         validate_model_list(&registry, "experts.graph_nodes.default", &self.graph_nodes)?;
+        for (kind, weights) in [
+            ("default", &self.weights.default),
+            ("identifier", &self.weights.identifier),
+            ("path", &self.weights.path),
+            ("conceptual", &self.weights.conceptual),
+        ] {
+            for (model_id, &weight) in weights {
+                let field = format!("experts.weights.{kind}.{model_id}");
+                ensure_non_negative(&field, weight)?;
+                registry
+                    .dimension(model_id)
+                    .with_context(|| format!("{field} unknown model_id '{model_id}'"))?;
+            }
+        }
 
         Ok(())
     }
@@ -141,6 +190,21 @@ impl ExpertsConfig {
     pub fn graph_node_models(&self) -> &[String] {
         &self.graph_nodes
     }
+
+    /// Multiplier for `model_id`'s ranking when fusing multi-model results for `kind` queries.
+    #[must_use]
+    pub fn semantic_weight(&self, kind: QueryKind, model_id: &str) -> f32 {
+        let by_kind = match kind {
+            QueryKind::Identifier => &self.weights.identifier,
+            QueryKind::Path => &self.weights.path,
+            QueryKind::Conceptual => &self.weights.conceptual,
+        };
+        by_kind
+            .get(model_id)
+            .or_else(|| self.weights.default.get(model_id))
+            .copied()
+            .unwrap_or(1.0)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -158,6 +222,24 @@ struct WeightedMatcher {
     weight: f32,
 }
 
+/// A `path_weights` entry: paths matching `glob` get their score multiplied by `weight`.
+/// Only the first matching entry applies.
+#[derive(Clone, Debug)]
+pub struct PathWeight {
+    pub glob: String,
+    pub weight: f32,
+    matcher: Matcher,
+}
+
+/// A `classifier_rules` entry: queries matching `pattern` are ranked as `query_type` instead of
+/// being classified by the built-in heuristics. Only the first matching rule applies.
+#[derive(Clone, Debug)]
+pub struct ClassifierRule {
+    pub pattern: String,
+    pub query_type: QueryType,
+    regex: Regex,
+}
+
 #[derive(Clone, Debug)]
 struct MustHitRule {
     matcher: Matcher,
@@ -172,7 +254,7 @@ struct Matcher {
     glob: Option<GlobMatcher>,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 #[derive(Default)]
 pub enum MatchKind {
@@ -183,7 +265,7 @@ pub enum MatchKind {
     Glob,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawProfile {
     #[serde(default)]
     schema_version: Option<u32>,
@@ -191,8 +273,16 @@ struct RawProfile {
     description: Option<String>,
     #[serde(default)]
     paths: RawPathRules,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    path_weights: Vec<RawPathWeight>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    classifier_rules: Vec<RawClassifierRule>,
     rerank: Option<RawRerankConfig>,
     #[serde(default)]
+    fusion: Option<RawFusionConfig>,
+    #[serde(default)]
+    ast_boost: Option<RawAstBoostConfig>,
+    #[serde(default)]
     must_hit: Vec<RawMustHitRule>,
     #[serde(default)]
     graph_nodes: Option<RawGraphNodesConfig>,
@@ -202,16 +292,18 @@ struct RawProfile {
     experts: Option<RawExpertsConfig>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawExpertsConfig {
     schema_version: Option<u32>,
     #[serde(default)]
     semantic: Option<RawSemanticExpertsConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    weights: Option<RawSemanticWeightsConfig>,
     #[serde(default)]
     graph_nodes: Option<RawGraphNodeExpertsConfig>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawSemanticExpertsConfig {
     default: Option<Vec<String>>,
     identifier: Option<Vec<String>>,
@@ -219,21 +311,30 @@ struct RawSemanticExpertsConfig {
     conceptual: Option<Vec<String>>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct RawSemanticWeightsConfig {
+    default: Option<HashMap<String, f32>>,
+    identifier: Option<HashMap<String, f32>>,
+    path: Option<HashMap<String, f32>>,
+    conceptual: Option<HashMap<String, f32>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawGraphNodeExpertsConfig {
     default: Option<Vec<String>>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawEmbeddingConfig {
     schema_version: Option<u32>,
     max_chars: Option<usize>,
+    max_tokens: Option<usize>,
     query: Option<RawQueryTemplates>,
     document: Option<RawDocumentTemplates>,
     graph_node: Option<RawGraphNodeTemplates>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawQueryTemplates {
     default: Option<String>,
     identifier: Option<String>,
@@ -241,7 +342,7 @@ struct RawQueryTemplates {
     conceptual: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawDocumentTemplates {
     default: Option<String>,
     code: Option<String>,
@@ -250,12 +351,12 @@ struct RawDocumentTemplates {
     test: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawGraphNodeTemplates {
     default: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawPathRules {
     #[serde(default)]
     boost: Vec<RawWeightedRule>,
@@ -267,7 +368,7 @@ struct RawPathRules {
     noise: Vec<RawRule>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 struct RawWeightedRule {
     pattern: String,
     #[serde(default)]
@@ -286,7 +387,20 @@ impl Default for RawWeightedRule {
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct RawPathWeight {
+    glob: String,
+    weight: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct RawClassifierRule {
+    pattern: String,
+    #[serde(rename = "type")]
+    query_type: QueryType,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 struct RawRule {
     pattern: String,
     #[serde(default)]
@@ -302,7 +416,7 @@ impl Default for RawRule {
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawMustHitRule {
     pattern: String,
     #[serde(default)]
@@ -313,7 +427,7 @@ struct RawMustHitRule {
     boost: f32,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawRerankConfig {
     thresholds: Option<RawThresholds>,
     bm25: Option<RawBm25>,
@@ -321,20 +435,20 @@ struct RawRerankConfig {
     must_hit: Option<RawRerankMustHit>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawThresholds {
     min_fuzzy_score: Option<f32>,
     min_semantic_score: Option<f32>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawBm25 {
     k1: Option<f32>,
     b: Option<f32>,
     window: Option<usize>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawBoosts {
     path: Option<f32>,
     symbol: Option<f32>,
@@ -342,7 +456,7 @@ struct RawBoosts {
     bm25: Option<f32>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct RawRerankMustHit {
     base_bonus: Option<f32>,
 }
@@ -357,6 +471,7 @@ pub struct RerankConfig {
 
 #[derive(Clone, Debug)]
 pub struct Thresholds {
+    /// Compared against normalized fuzzy scores (0-1, see [`crate::FuzzySearch::search`]).
     pub min_fuzzy_score: f32,
     pub min_semantic_score: f32,
 }
@@ -398,6 +513,89 @@ impl Default for RerankMustHit {
     }
 }
 
+/// RRF constants used when fusing semantic and fuzzy rankings (see [`RRFFusion`]).
+#[derive(Clone, Copy, Debug)]
+pub struct FusionConfig {
+    pub k: f32,
+    pub adaptive_k_offset: f32,
+    pub weights: FusionWeights,
+}
+
+impl Default for FusionConfig {
+    fn default() -> Self {
+        Self {
+            k: RRFFusion::DEFAULT_K,
+            adaptive_k_offset: RRFFusion::DEFAULT_ADAPTIVE_K_OFFSET,
+            weights: FusionWeights::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+struct RawFusionConfig {
+    k: Option<f32>,
+    adaptive_k_offset: Option<f32>,
+    semantic_weight: Option<f32>,
+    fuzzy_weight: Option<f32>,
+}
+
+/// Additive adjustments to the default [`AstBoostConfig`] multipliers.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct RawAstBoostConfig {
+    #[serde(default)]
+    chunk_types: std::collections::BTreeMap<String, f32>,
+    documented: Option<f32>,
+    with_context: Option<f32>,
+    tests: Option<f32>,
+    infra: Option<f32>,
+    library: Option<f32>,
+}
+
+const CHUNK_TYPES: [ChunkType; 14] = [
+    ChunkType::Function,
+    ChunkType::Method,
+    ChunkType::Class,
+    ChunkType::Struct,
+    ChunkType::Enum,
+    ChunkType::Interface,
+    ChunkType::Module,
+    ChunkType::Impl,
+    ChunkType::Type,
+    ChunkType::Const,
+    ChunkType::Variable,
+    ChunkType::Import,
+    ChunkType::Comment,
+    ChunkType::Other,
+];
+
+fn build_ast_boost(raw: Option<RawAstBoostConfig>) -> Result<AstBoostConfig> {
+    let mut config = AstBoostConfig::default();
+    let Some(raw) = raw else {
+        return Ok(config);
+    };
+
+    for (name, delta) in raw.chunk_types {
+        let chunk_type = CHUNK_TYPES
+            .into_iter()
+            .find(|ct| ct.as_str() == name)
+            .ok_or_else(|| anyhow!("ast_boost.chunk_types: unknown chunk type '{name}'"))?;
+        let boost = config.chunk_type_boost(chunk_type) + delta;
+        config.chunk_types.insert(chunk_type, boost.max(0.0));
+    }
+    for (delta, slot) in [
+        (raw.documented, &mut config.documented),
+        (raw.with_context, &mut config.with_context),
+        (raw.tests, &mut config.tests),
+        (raw.infra, &mut config.infra),
+        (raw.library, &mut config.library),
+    ] {
+        if let Some(delta) = delta {
+            *slot = (*slot + delta).max(0.0);
+        }
+    }
+    Ok(config)
+}
+
 #[derive(Clone, Debug)]
 pub struct GraphNodesConfig {
     pub enabled: bool,
@@ -417,7 +615,7 @@ impl Default for GraphNodesConfig {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
 struct RawGraphNodesConfig {
     enabled: Option<bool>,
     weight: Option<f32>,
@@ -464,6 +662,45 @@ impl SearchProfile {
         Self::from_bytes(profile_name, &bytes, base)
     }
 
+    /// Loads a standalone profile (e.g. one written by [`Self::to_path`]). Unlike
+    /// [`Self::from_file`] it is not layered over `general`; the file stem is the fallback name.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read profile file {}", path.display()))?;
+        let fallback_name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("custom");
+        Self::from_bytes(fallback_name, &bytes, None)
+    }
+
+    /// Writes the profile as pretty JSON with every rerank, fusion and graph-node setting
+    /// spelled out, so the file loads back to the same profile via [`Self::from_path`].
+    pub fn to_path(&self, path: &Path) -> Result<()> {
+        let mut raw = self.raw.clone();
+        raw.schema_version = Some(1);
+        raw.name = Some(self.name.clone());
+        raw.rerank = Some(self.rerank.to_raw());
+        raw.fusion = Some(self.fusion.to_raw());
+        raw.graph_nodes = Some(self.graph_nodes.to_raw());
+
+        // Going through a string keeps the short decimal form of f32 fields.
+        let compact = serde_json::to_string(&raw).context("Failed to serialize profile")?;
+        let mut value: serde_json::Value =
+            serde_json::from_str(&compact).context("Failed to serialize profile")?;
+        strip_nulls(&mut value);
+        let mut json =
+            serde_json::to_string_pretty(&value).context("Failed to serialize profile")?;
+        json.push('\n');
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write profile file {}", path.display()))
+    }
+
     pub fn from_bytes(profile_name: &str, bytes: &[u8], base: Option<&str>) -> Result<Self> {
         let raw = parse_raw(bytes).with_context(|| {
             format!("Profile '{profile_name}' is not valid JSON/TOML configuration")
@@ -482,10 +719,30 @@ impl SearchProfile {
         &self.name
     }
 
+    /// Stable hex digest of the profile name and merged configuration (FNV-1a over the
+    /// serialized settings), identical across processes for the same profile.
+    #[must_use]
+    pub fn fingerprint(&self) -> String {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+        let config = serde_json::to_vec(&self.raw).unwrap_or_default();
+        let hash = self
+            .name
+            .as_bytes()
+            .iter()
+            .chain([0u8].iter())
+            .chain(config.iter())
+            .fold(FNV_OFFSET, |hash, &byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+            });
+        format!("{hash:016x}")
+    }
+
     #[must_use]
     pub fn path_boost_weight(&self, path: &str) -> f32 {
         let lower = path.to_ascii_lowercase();
-        let mut weight = 1.0;
+        let mut weight = self.directory_weight_lower(&lower);
         for rule in &self.paths.boost {
             if rule.matcher.matches(&lower) {
                 weight *= rule.weight;
@@ -497,7 +754,7 @@ impl SearchProfile {
     #[must_use]
     pub fn path_weight(&self, path: &str) -> f32 {
         let lower = path.to_ascii_lowercase();
-        let mut weight = 1.0;
+        let mut weight = self.directory_weight_lower(&lower);
         for rule in &self.paths.boost {
             if rule.matcher.matches(&lower) {
                 weight *= rule.weight;
@@ -511,6 +768,76 @@ impl SearchProfile {
         weight
     }
 
+    /// Multiplier from the first `path_weights` entry whose glob matches `path` (1.0 if none).
+    #[must_use]
+    pub fn directory_weight(&self, path: &str) -> f32 {
+        self.directory_weight_lower(&path.to_ascii_lowercase())
+    }
+
+    fn directory_weight_lower(&self, lower: &str) -> f32 {
+        first_path_weight(&self.path_weights, lower)
+    }
+
+    /// `path_weights` as a relevance multiplier for the graph's related chunks, or `None`
+    /// when the profile sets none.
+    #[must_use]
+    pub fn related_path_weight(&self) -> Option<PathWeightFn> {
+        if self.path_weights.is_empty() {
+            return None;
+        }
+        let weights = self.path_weights.clone();
+        Some(Arc::new(move |path: &str| {
+            first_path_weight(&weights, &path.to_ascii_lowercase())
+        }))
+    }
+
+    #[must_use]
+    pub fn path_weights(&self) -> &[PathWeight] {
+        &self.path_weights
+    }
+
+    /// The query type for ranking `query`: the first `classifier_rules` match, otherwise the
+    /// built-in [`QueryClassifier`].
+    #[must_use]
+    pub fn classify_query(&self, query: &str) -> QueryType {
+        self.classifier_rule(query)
+            .map_or_else(|| QueryClassifier::classify(query), |rule| rule.query_type)
+    }
+
+    /// The first `classifier_rules` entry whose pattern matches the trimmed `query`.
+    #[must_use]
+    pub fn classifier_rule(&self, query: &str) -> Option<&ClassifierRule> {
+        let query = query.trim();
+        self.classifier_rules
+            .iter()
+            .find(|rule| rule.regex.is_match(query))
+    }
+
+    #[must_use]
+    pub fn classifier_rules(&self) -> &[ClassifierRule] {
+        &self.classifier_rules
+    }
+
+    /// `path_weights` entries whose globs overlap an earlier entry, one message per pair. They
+    /// are valid (the first match wins) but usually a sign of misordered rules. Overlap is
+    /// detected when one glob matches the other's pattern or a path built from it.
+    #[must_use]
+    pub fn path_weight_overlaps(&self) -> Vec<String> {
+        let mut overlaps = Vec::new();
+        for (later_idx, later) in self.path_weights.iter().enumerate() {
+            for (earlier_idx, earlier) in self.path_weights[..later_idx].iter().enumerate() {
+                if globs_overlap(&earlier.matcher, &later.matcher) {
+                    overlaps.push(format!(
+                        "path_weights[{later_idx}] `{}` overlaps path_weights[{earlier_idx}] `{}`; \
+                         paths matching both get the earlier weight ({})",
+                        later.glob, earlier.glob, earlier.weight
+                    ));
+                }
+            }
+        }
+        overlaps
+    }
+
     #[must_use]
     pub fn is_rejected(&self, path: &str) -> bool {
         let lower = path.to_ascii_lowercase();
@@ -539,6 +866,16 @@ impl SearchProfile {
         &self.rerank
     }
 
+    #[must_use]
+    pub const fn fusion(&self) -> &FusionConfig {
+        &self.fusion
+    }
+
+    #[must_use]
+    pub const fn ast_boost(&self) -> &AstBoostConfig {
+        &self.ast_boost
+    }
+
     #[must_use]
     pub const fn graph_nodes(&self) -> &GraphNodesConfig {
         &self.graph_nodes
@@ -576,6 +913,7 @@ impl SearchProfile {
     }
 
     fn from_raw(raw: RawProfile, fallback_name: &str) -> Result<Self> {
+        let source = raw.clone();
         if let Some(schema_version) = raw.schema_version {
             if schema_version != 1 {
                 return Err(anyhow!(
@@ -589,8 +927,18 @@ impl SearchProfile {
             .filter(|n| !n.trim().is_empty())
             .unwrap_or_else(|| fallback_name.to_string());
         let description = raw.description;
-        let paths = PathRules::from_raw(raw.paths, raw.must_hit)?;
-        let rerank = RerankConfig::from_raw(raw.rerank);
+        let paths = PathRules::from_raw(raw.paths, raw.must_hit)
+            .with_context(|| format!("Invalid path rules for profile '{name}'"))?;
+        let path_weights = build_path_weights(raw.path_weights)
+            .with_context(|| format!("Invalid path_weights for profile '{name}'"))?;
+        let classifier_rules = build_classifier_rules(raw.classifier_rules)
+            .with_context(|| format!("Invalid classifier_rules for profile '{name}'"))?;
+        let rerank = RerankConfig::from_raw(raw.rerank)
+            .with_context(|| format!("Invalid rerank config for profile '{name}'"))?;
+        let fusion = FusionConfig::from_raw(raw.fusion)
+            .with_context(|| format!("Invalid fusion config for profile '{name}'"))?;
+        let ast_boost = build_ast_boost(raw.ast_boost)
+            .with_context(|| format!("Invalid ast_boost config for profile '{name}'"))?;
         let graph_nodes = GraphNodesConfig::from_raw(raw.graph_nodes)?;
         let embedding = build_embedding_templates(raw.embedding)
             .with_context(|| format!("Invalid embedding template config for profile '{name}'"))?;
@@ -601,14 +949,48 @@ impl SearchProfile {
             name,
             description,
             paths,
+            path_weights,
+            classifier_rules,
             rerank,
+            fusion,
+            ast_boost,
             graph_nodes,
             embedding,
             experts,
+            raw: source,
         })
     }
 }
 
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+fn ensure_non_negative(field: &str, value: f32) -> Result<()> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{field} must be a non-negative number (got {value})"
+        ))
+    }
+}
+
+fn ensure_unit_interval(field: &str, value: f32) -> Result<()> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(anyhow!("{field} must be in [0.0, 1.0] (got {value})"))
+    }
+}
+
 fn validate_model_list(registry: &ModelRegistry, path: &str, models: &[String]) -> Result<()> {
     if models.is_empty() {
         return Err(anyhow!("{path} must not be empty"));
@@ -635,6 +1017,7 @@ fn build_embedding_templates(raw: Option<RawEmbeddingConfig>) -> Result<Embeddin
     if let Some(max_chars) = raw.max_chars {
         templates.max_chars = max_chars;
     }
+    templates.max_tokens = raw.max_tokens;
 
     if let Some(query) = raw.query {
         if let Some(default) = query.default {
@@ -693,13 +1076,22 @@ impl GraphNodesConfig {
             max_neighbors_per_relation,
         })
     }
+
+    const fn to_raw(&self) -> RawGraphNodesConfig {
+        RawGraphNodesConfig {
+            enabled: Some(self.enabled),
+            weight: Some(self.weight),
+            top_k: Some(self.top_k),
+            max_neighbors_per_relation: Some(self.max_neighbors_per_relation),
+        }
+    }
 }
 
 impl PathRules {
     fn from_raw(paths: RawPathRules, must_hit: Vec<RawMustHitRule>) -> Result<Self> {
         Ok(Self {
-            boost: build_weighted_matchers(paths.boost)?,
-            penalty: build_weighted_matchers(paths.penalty)?,
+            boost: build_weighted_matchers("paths.boost", paths.boost)?,
+            penalty: build_weighted_matchers("paths.penalty", paths.penalty)?,
             reject: build_matchers(paths.reject)?,
             noise: build_matchers(paths.noise)?,
             must_hit: build_must_hit(must_hit)?,
@@ -707,14 +1099,102 @@ impl PathRules {
     }
 }
 
+impl FusionConfig {
+    fn from_raw(raw: Option<RawFusionConfig>) -> Result<Self> {
+        let defaults = Self::default();
+        let raw = raw.unwrap_or_default();
+        let config = Self {
+            k: raw.k.unwrap_or(defaults.k),
+            adaptive_k_offset: raw.adaptive_k_offset.unwrap_or(defaults.adaptive_k_offset),
+            weights: FusionWeights {
+                semantic: raw.semantic_weight.unwrap_or(defaults.weights.semantic),
+                fuzzy: raw.fuzzy_weight.unwrap_or(defaults.weights.fuzzy),
+            },
+        };
+        for (field, value) in [
+            ("k", config.k),
+            ("adaptive_k_offset", config.adaptive_k_offset),
+            ("semantic_weight", config.weights.semantic),
+            ("fuzzy_weight", config.weights.fuzzy),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(anyhow!(
+                    "fusion.{field} must be a non-negative number (got {value})"
+                ));
+            }
+        }
+        if config.k + config.adaptive_k_offset <= 0.0 {
+            return Err(anyhow!("fusion.k must be positive"));
+        }
+        Ok(config)
+    }
+
+    const fn to_raw(self) -> RawFusionConfig {
+        RawFusionConfig {
+            k: Some(self.k),
+            adaptive_k_offset: Some(self.adaptive_k_offset),
+            semantic_weight: Some(self.weights.semantic),
+            fuzzy_weight: Some(self.weights.fuzzy),
+        }
+    }
+}
+
 impl RerankConfig {
-    fn from_raw(raw: Option<RawRerankConfig>) -> Self {
+    fn from_raw(raw: Option<RawRerankConfig>) -> Result<Self> {
         let raw = raw.unwrap_or_default();
-        Self {
+        let config = Self {
             thresholds: merge_thresholds(raw.thresholds),
             bm25: merge_bm25(raw.bm25),
             boosts: merge_boosts(raw.boosts),
             must_hit: merge_rerank_must_hit(raw.must_hit),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure_unit_interval(
+            "rerank.thresholds.min_fuzzy_score",
+            self.thresholds.min_fuzzy_score,
+        )?;
+        ensure_unit_interval(
+            "rerank.thresholds.min_semantic_score",
+            self.thresholds.min_semantic_score,
+        )?;
+        ensure_non_negative("rerank.bm25.k1", self.bm25.k1)?;
+        ensure_unit_interval("rerank.bm25.b", self.bm25.b)?;
+        for (field, value) in [
+            ("rerank.boosts.path", self.boosts.path),
+            ("rerank.boosts.symbol", self.boosts.symbol),
+            ("rerank.boosts.yaml_path", self.boosts.yaml_path),
+            ("rerank.boosts.bm25", self.boosts.bm25),
+            ("rerank.must_hit.base_bonus", self.must_hit.base_bonus),
+        ] {
+            ensure_non_negative(field, value)?;
+        }
+        Ok(())
+    }
+
+    fn to_raw(&self) -> RawRerankConfig {
+        RawRerankConfig {
+            thresholds: Some(RawThresholds {
+                min_fuzzy_score: Some(self.thresholds.min_fuzzy_score),
+                min_semantic_score: Some(self.thresholds.min_semantic_score),
+            }),
+            bm25: Some(RawBm25 {
+                k1: Some(self.bm25.k1),
+                b: Some(self.bm25.b),
+                window: Some(self.bm25.window),
+            }),
+            boosts: Some(RawBoosts {
+                path: Some(self.boosts.path),
+                symbol: Some(self.boosts.symbol),
+                yaml_path: Some(self.boosts.yaml_path),
+                bm25: Some(self.boosts.bm25),
+            }),
+            must_hit: Some(RawRerankMustHit {
+                base_bonus: Some(self.must_hit.base_bonus),
+            }),
         }
     }
 }
@@ -746,12 +1226,13 @@ impl Matcher {
     }
 }
 
-fn build_weighted_matchers(raw: Vec<RawWeightedRule>) -> Result<Vec<WeightedMatcher>> {
+fn build_weighted_matchers(field: &str, raw: Vec<RawWeightedRule>) -> Result<Vec<WeightedMatcher>> {
     let mut matchers = Vec::with_capacity(raw.len());
-    for rule in raw {
+    for (idx, rule) in raw.into_iter().enumerate() {
         if rule.pattern.trim().is_empty() {
             continue;
         }
+        ensure_non_negative(&format!("{field}[{idx}].weight"), rule.weight)?;
         let matcher = Matcher::new(rule.kind, &rule.pattern)?;
         matchers.push(WeightedMatcher {
             matcher,
@@ -761,6 +1242,103 @@ fn build_weighted_matchers(raw: Vec<RawWeightedRule>) -> Result<Vec<WeightedMatc
     Ok(matchers)
 }
 
+fn build_path_weights(raw: Vec<RawPathWeight>) -> Result<Vec<PathWeight>> {
+    let mut weights = Vec::with_capacity(raw.len());
+    for (idx, rule) in raw.into_iter().enumerate() {
+        let field = format!("path_weights[{idx}]");
+        if rule.glob.trim().is_empty() {
+            return Err(anyhow!("{field}.glob must not be empty"));
+        }
+        if !(0.0..=MAX_PATH_WEIGHT).contains(&rule.weight) {
+            return Err(anyhow!(
+                "{field}.weight must be in [0.0, {MAX_PATH_WEIGHT:.1}] (got {})",
+                rule.weight
+            ));
+        }
+        let matcher = Matcher::new(MatchKind::Glob, &rule.glob)
+            .with_context(|| format!("{field}.glob `{}`", rule.glob))?;
+        weights.push(PathWeight {
+            glob: rule.glob,
+            weight: rule.weight,
+            matcher,
+        });
+    }
+    Ok(weights)
+}
+
+fn build_classifier_rules(raw: Vec<RawClassifierRule>) -> Result<Vec<ClassifierRule>> {
+    let mut rules = Vec::with_capacity(raw.len());
+    for (idx, rule) in raw.into_iter().enumerate() {
+        let field = format!("classifier_rules[{idx}]");
+        if rule.pattern.trim().is_empty() {
+            return Err(anyhow!("{field}.pattern must not be empty"));
+        }
+        let regex = Regex::new(&rule.pattern)
+            .with_context(|| format!("{field}.pattern `{}`", rule.pattern))?;
+        rules.push(ClassifierRule {
+            pattern: rule.pattern,
+            query_type: rule.query_type,
+            regex,
+        });
+    }
+    Ok(rules)
+}
+
+fn first_path_weight(weights: &[PathWeight], lower: &str) -> f32 {
+    weights
+        .iter()
+        .find(|rule| rule.matcher.matches(lower))
+        .map_or(1.0, |rule| rule.weight)
+}
+
+fn globs_overlap(a: &Matcher, b: &Matcher) -> bool {
+    a.matches(&b.needle)
+        || b.matches(&a.needle)
+        || a.matches(&glob_example(&b.needle))
+        || b.matches(&glob_example(&a.needle))
+}
+
+/// A concrete path matched by `glob`: each wildcard, class or alternation replaced by one
+/// plausible choice.
+fn glob_example(glob: &str) -> String {
+    let mut out = String::with_capacity(glob.len());
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' | '?' => {
+                while chars.peek() == Some(&'*') {
+                    chars.next();
+                }
+                out.push('x');
+            }
+            '[' => {
+                let first = chars.next().filter(|c| *c != '!' && *c != '^');
+                out.push(first.unwrap_or('x'));
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            '{' => {
+                for c in chars.by_ref() {
+                    if c == ',' || c == '}' {
+                        break;
+                    }
+                    out.push(c);
+                }
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
 fn build_matchers(raw: Vec<RawRule>) -> Result<Vec<Matcher>> {
     let mut matchers = Vec::with_capacity(raw.len());
     for rule in raw {
@@ -774,10 +1352,11 @@ fn build_matchers(raw: Vec<RawRule>) -> Result<Vec<Matcher>> {
 
 fn build_must_hit(raw: Vec<RawMustHitRule>) -> Result<Vec<MustHitRule>> {
     let mut rules = Vec::with_capacity(raw.len());
-    for rule in raw {
+    for (idx, rule) in raw.into_iter().enumerate() {
         if rule.pattern.trim().is_empty() {
             continue;
         }
+        ensure_non_negative(&format!("must_hit[{idx}].boost"), rule.boost)?;
         let matcher = Matcher::new(rule.kind, &rule.pattern)?;
         let tokens: Vec<String> = rule
             .tokens
@@ -817,6 +1396,12 @@ fn merge_raw_profiles(mut base: RawProfile, overlay: RawProfile) -> RawProfile {
     must_hit.extend(base.must_hit);
     must_hit.extend(overlay.must_hit);
 
+    // First match wins, so the overlay's entries go first to take precedence over the base.
+    let mut path_weights = overlay.path_weights;
+    path_weights.extend(base.path_weights);
+    let mut classifier_rules = overlay.classifier_rules;
+    classifier_rules.extend(base.classifier_rules);
+
     let rerank = match (base.rerank.take(), overlay.rerank) {
         (Some(base_cfg), Some(overlay_cfg)) => Some(merge_rerank(Some(base_cfg), overlay_cfg)),
         (Some(base_cfg), None) => Some(base_cfg),
@@ -824,6 +1409,35 @@ fn merge_raw_profiles(mut base: RawProfile, overlay: RawProfile) -> RawProfile {
         (None, None) => None,
     };
 
+    let fusion = match (base.fusion.take(), overlay.fusion) {
+        (Some(base_cfg), Some(overlay_cfg)) => Some(RawFusionConfig {
+            k: overlay_cfg.k.or(base_cfg.k),
+            adaptive_k_offset: overlay_cfg.adaptive_k_offset.or(base_cfg.adaptive_k_offset),
+            semantic_weight: overlay_cfg.semantic_weight.or(base_cfg.semantic_weight),
+            fuzzy_weight: overlay_cfg.fuzzy_weight.or(base_cfg.fuzzy_weight),
+        }),
+        (Some(base_cfg), None) => Some(base_cfg),
+        (None, Some(overlay_cfg)) => Some(overlay_cfg),
+        (None, None) => None,
+    };
+
+    let ast_boost = match (base.ast_boost.take(), overlay.ast_boost) {
+        (Some(mut base_cfg), Some(overlay_cfg)) => {
+            base_cfg.chunk_types.extend(overlay_cfg.chunk_types);
+            Some(RawAstBoostConfig {
+                chunk_types: base_cfg.chunk_types,
+                documented: overlay_cfg.documented.or(base_cfg.documented),
+                with_context: overlay_cfg.with_context.or(base_cfg.with_context),
+                tests: overlay_cfg.tests.or(base_cfg.tests),
+                infra: overlay_cfg.infra.or(base_cfg.infra),
+                library: overlay_cfg.library.or(base_cfg.library),
+            })
+        }
+        (Some(base_cfg), None) => Some(base_cfg),
+        (None, Some(overlay_cfg)) => Some(overlay_cfg),
+        (None, None) => None,
+    };
+
     let graph_nodes = match (base.graph_nodes.take(), overlay.graph_nodes) {
         (Some(base_cfg), Some(overlay_cfg)) => Some(merge_graph_nodes_raw(base_cfg, overlay_cfg)),
         (Some(base_cfg), None) => Some(base_cfg),
@@ -853,8 +1467,12 @@ fn merge_raw_profiles(mut base: RawProfile, overlay: RawProfile) -> RawProfile {
         name: overlay.name,
         description: overlay.description.or_else(|| base.description.take()),
         paths,
+        path_weights,
+        classifier_rules,
         must_hit,
         rerank,
+        fusion,
+        ast_boost,
         graph_nodes,
         embedding,
         experts,
@@ -871,6 +1489,16 @@ fn merge_experts_raw(mut base: RawExpertsConfig, overlay: RawExpertsConfig) -> R
         (None, None) => None,
     };
 
+    base.weights = match (base.weights.take(), overlay.weights) {
+        (Some(base_w), Some(overlay_w)) => Some(RawSemanticWeightsConfig {
+            default: overlay_w.default.or(base_w.default),
+            identifier: overlay_w.identifier.or(base_w.identifier),
+            path: overlay_w.path.or(base_w.path),
+            conceptual: overlay_w.conceptual.or(base_w.conceptual),
+        }),
+        (base_w, overlay_w) => overlay_w.or(base_w),
+    };
+
     base.graph_nodes = match (base.graph_nodes.take(), overlay.graph_nodes) {
         (Some(base_g), Some(overlay_g)) => Some(merge_graph_node_experts_raw(base_g, overlay_g)),
         (Some(base_g), None) => Some(base_g),
@@ -906,6 +1534,7 @@ fn merge_embedding_raw(
 ) -> RawEmbeddingConfig {
     base.schema_version = overlay.schema_version.or(base.schema_version);
     base.max_chars = overlay.max_chars.or(base.max_chars);
+    base.max_tokens = overlay.max_tokens.or(base.max_tokens);
 
     base.query = match (base.query.take(), overlay.query) {
         (Some(base_q), Some(overlay_q)) => Some(merge_query_templates_raw(base_q, overlay_q)),
@@ -1160,7 +1789,11 @@ fn validate_profile_value(value: &serde_json::Value) -> Result<()> {
             "name",
             "description",
             "paths",
+            "path_weights",
+            "classifier_rules",
             "rerank",
+            "fusion",
+            "ast_boost",
             "must_hit",
             "graph_nodes",
             "embedding",
@@ -1216,6 +1849,34 @@ fn validate_profile_value(value: &serde_json::Value) -> Result<()> {
         }
     }
 
+    // path_weights[]
+    if let Some(arr) = root.get("path_weights").and_then(array_at) {
+        for (idx, item) in arr.iter().enumerate() {
+            if let Some(obj) = object_at(item) {
+                validate_object_keys(
+                    &mut unknown,
+                    obj,
+                    &format!("path_weights[{idx}]"),
+                    &["glob", "weight"],
+                );
+            }
+        }
+    }
+
+    // classifier_rules[]
+    if let Some(arr) = root.get("classifier_rules").and_then(array_at) {
+        for (idx, item) in arr.iter().enumerate() {
+            if let Some(obj) = object_at(item) {
+                validate_object_keys(
+                    &mut unknown,
+                    obj,
+                    &format!("classifier_rules[{idx}]"),
+                    &["pattern", "type"],
+                );
+            }
+        }
+    }
+
     // must_hit[]
     if let Some(arr) = root.get("must_hit").and_then(array_at) {
         for (idx, item) in arr.iter().enumerate() {
@@ -1230,6 +1891,33 @@ fn validate_profile_value(value: &serde_json::Value) -> Result<()> {
         }
     }
 
+    // fusion.*
+    if let Some(fusion) = root.get("fusion").and_then(object_at) {
+        validate_object_keys(
+            &mut unknown,
+            fusion,
+            "fusion",
+            &["k", "adaptive_k_offset", "semantic_weight", "fuzzy_weight"],
+        );
+    }
+
+    // ast_boost.*
+    if let Some(ast_boost) = root.get("ast_boost").and_then(object_at) {
+        validate_object_keys(
+            &mut unknown,
+            ast_boost,
+            "ast_boost",
+            &[
+                "chunk_types",
+                "documented",
+                "with_context",
+                "tests",
+                "infra",
+                "library",
+            ],
+        );
+    }
+
     // graph_nodes.*
     if let Some(graph_nodes) = root.get("graph_nodes").and_then(object_at) {
         validate_object_keys(
@@ -1249,6 +1937,7 @@ fn validate_profile_value(value: &serde_json::Value) -> Result<()> {
             &[
                 "schema_version",
                 "max_chars",
+                "max_tokens",
                 "query",
                 "document",
                 "graph_node",
@@ -1281,7 +1970,7 @@ fn validate_profile_value(value: &serde_json::Value) -> Result<()> {
             &mut unknown,
             experts,
             "experts",
-            &["schema_version", "semantic", "graph_nodes"],
+            &["schema_version", "semantic", "weights", "graph_nodes"],
         );
         if let Some(semantic) = experts.get("semantic").and_then(object_at) {
             validate_object_keys(
@@ -1291,6 +1980,14 @@ fn validate_profile_value(value: &serde_json::Value) -> Result<()> {
                 &["default", "identifier", "path", "conceptual"],
             );
         }
+        if let Some(weights) = experts.get("weights").and_then(object_at) {
+            validate_object_keys(
+                &mut unknown,
+                weights,
+                "experts.weights",
+                &["default", "identifier", "path", "conceptual"],
+            );
+        }
         if let Some(graph_nodes) = experts.get("graph_nodes").and_then(object_at) {
             validate_object_keys(
                 &mut unknown,
@@ -1442,6 +2139,151 @@ mod tests {
         assert!((rerank.must_hit.base_bonus - 12.0).abs() < f32::EPSILON);
     }
 
+    #[test]
+    fn fusion_config_from_profile_overrides_defaults() {
+        let profile = SearchProfile::from_bytes(
+            "custom",
+            br#"{ "fusion": { "k": 30, "semantic_weight": 1.5 } }"#,
+            Some("general"),
+        )
+        .unwrap();
+
+        let fusion = profile.fusion();
+        assert!((fusion.k - 30.0).abs() < f32::EPSILON);
+        assert!((fusion.weights.semantic - 1.5).abs() < f32::EPSILON);
+        assert!((fusion.weights.fuzzy - 1.0).abs() < f32::EPSILON);
+        assert!(
+            (fusion.adaptive_k_offset - RRFFusion::DEFAULT_ADAPTIVE_K_OFFSET).abs() < f32::EPSILON
+        );
+
+        let err =
+            SearchProfile::from_bytes("bad", br#"{ "fusion": { "fuzzy_weight": -1 } }"#, None)
+                .unwrap_err();
+        assert!(format!("{err:#}").contains("fusion.fuzzy_weight"));
+    }
+
+    #[test]
+    fn expert_weights_fall_back_from_kind_to_default() {
+        let profile = SearchProfile::from_bytes(
+            "custom",
+            br#"{ "experts": { "weights": {
+                "default": { "bge-small": 0.5 },
+                "identifier": { "bge-small": 2.0 }
+            } } }"#,
+            Some("general"),
+        )
+        .unwrap();
+
+        let experts = profile.experts();
+        let weight = |kind| experts.semantic_weight(kind, "bge-small");
+        assert!((weight(QueryKind::Identifier) - 2.0).abs() < f32::EPSILON);
+        assert!((weight(QueryKind::Conceptual) - 0.5).abs() < f32::EPSILON);
+        assert!((experts.semantic_weight(QueryKind::Path, "bge-base") - 1.0).abs() < f32::EPSILON);
+
+        let err = SearchProfile::from_bytes(
+            "bad",
+            br#"{ "experts": { "weights": { "path": { "bge-small": -1 } } } }"#,
+            None,
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("experts.weights.path.bge-small"));
+    }
+
+    #[test]
+    fn ast_boost_adjustments_apply_on_top_of_defaults() {
+        let profile = SearchProfile::from_bytes(
+            "custom",
+            br#"{ "ast_boost": { "chunk_types": { "function": 0.1 }, "tests": -0.2 } }"#,
+            Some("general"),
+        )
+        .unwrap();
+
+        let defaults = AstBoostConfig::default();
+        let boost = profile.ast_boost();
+        assert!((boost.chunk_type_boost(ChunkType::Function) - 1.28).abs() < 1e-6);
+        assert!((boost.tests - (defaults.tests - 0.2)).abs() < 1e-6);
+        assert!((boost.library - defaults.library).abs() < f32::EPSILON);
+
+        let err = SearchProfile::from_bytes(
+            "bad",
+            br#"{ "ast_boost": { "chunk_types": { "lambda": 0.1 } } }"#,
+            None,
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("unknown chunk type 'lambda'"));
+    }
+
+    #[test]
+    fn profile_round_trips_through_path() {
+        let profile = SearchProfile::from_bytes(
+            "tuned",
+            br#"{
+                "description": "Tuned for this repo",
+                "paths": { "boost": [{ "pattern": "Crates/", "kind": "prefix", "weight": 1.7 }] },
+                "rerank": { "thresholds": { "min_fuzzy_score": 0.35 }, "bm25": { "k1": 1.6 } },
+                "fusion": { "fuzzy_weight": 0.8 },
+                "ast_boost": { "chunk_types": { "struct": 0.2 } }
+            }"#,
+            Some("general"),
+        )
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles").join("saved.json");
+        profile.to_path(&path).unwrap();
+        let loaded = SearchProfile::from_path(&path).unwrap();
+
+        assert_eq!(loaded.name(), "tuned");
+        for file in ["crates/search/src/lib.rs", "src/main.rs", "docs/README.md"] {
+            assert!((loaded.path_weight(file) - profile.path_weight(file)).abs() < f32::EPSILON);
+        }
+        assert!(loaded.is_noise("vendor/lib.rs") == profile.is_noise("vendor/lib.rs"));
+
+        let (saved, original) = (loaded.rerank_config(), profile.rerank_config());
+        assert!((saved.thresholds.min_fuzzy_score - 0.35).abs() < f32::EPSILON);
+        assert!(
+            (saved.thresholds.min_semantic_score - original.thresholds.min_semantic_score).abs()
+                < f32::EPSILON
+        );
+        assert!((saved.bm25.k1 - 1.6).abs() < f32::EPSILON);
+        assert!((saved.bm25.b - original.bm25.b).abs() < f32::EPSILON);
+        assert_eq!(saved.bm25.window, original.bm25.window);
+        assert!((saved.boosts.symbol - original.boosts.symbol).abs() < f32::EPSILON);
+        assert!((saved.must_hit.base_bonus - original.must_hit.base_bonus).abs() < f32::EPSILON);
+        assert!((loaded.fusion().weights.fuzzy - 0.8).abs() < f32::EPSILON);
+        assert!(
+            (loaded.ast_boost().chunk_type_boost(ChunkType::Struct)
+                - profile.ast_boost().chunk_type_boost(ChunkType::Struct))
+            .abs()
+                < f32::EPSILON
+        );
+        assert_eq!(
+            loaded.embedding().query.default,
+            profile.embedding().query.default
+        );
+    }
+
+    #[test]
+    fn profile_rejects_out_of_range_threshold() {
+        let err = SearchProfile::from_bytes(
+            "bad",
+            br#"{ "rerank": { "thresholds": { "min_fuzzy_score": 1.5 } } }"#,
+            Some("general"),
+        )
+        .unwrap_err();
+        let msg = format!("{err:#}");
+        assert!(msg.contains("rerank.thresholds.min_fuzzy_score"), "{msg}");
+        assert!(msg.contains("[0.0, 1.0]"), "{msg}");
+
+        let err = SearchProfile::from_bytes(
+            "bad",
+            br#"{ "paths": { "penalty": [{ "pattern": "docs/", "weight": -0.5 }] } }"#,
+            None,
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("paths.penalty[0].weight"));
+    }
+
     #[test]
     fn must_hit_matches_tokens_and_path() {
         let profile = SearchProfile::from_bytes(
@@ -1543,4 +2385,118 @@ mod tests {
         assert!(out.len() <= 256);
         assert_eq!(out, format!("query:{}", "a".repeat(250)));
     }
+
+    #[test]
+    fn path_weights_apply_the_first_matching_glob() {
+        let profile = SearchProfile::from_bytes(
+            "tuned",
+            br#"{
+                "path_weights": [
+                    { "glob": "crates/core/**", "weight": 2.0 },
+                    { "glob": "crates/**", "weight": 1.2 },
+                    { "glob": "examples/**", "weight": 0.3 }
+                ]
+            }"#,
+            None,
+        )
+        .unwrap();
+
+        assert!((profile.directory_weight("crates/core/src/lib.rs") - 2.0).abs() < f32::EPSILON);
+        assert!((profile.directory_weight("Crates/cli/main.rs") - 1.2).abs() < f32::EPSILON);
+        assert!((profile.directory_weight("examples/demo.rs") - 0.3).abs() < f32::EPSILON);
+        assert!((profile.directory_weight("src/lib.rs") - 1.0).abs() < f32::EPSILON);
+        assert!((profile.path_weight("examples/demo.rs") - 0.3).abs() < f32::EPSILON);
+        assert!((profile.path_boost_weight("crates/core/a.rs") - 2.0).abs() < f32::EPSILON);
+
+        let overlaps = profile.path_weight_overlaps();
+        assert_eq!(overlaps.len(), 1, "{overlaps:?}");
+        assert!(overlaps[0].starts_with("path_weights[1] `crates/**` overlaps path_weights[0]"));
+    }
+
+    #[test]
+    fn path_weights_are_validated() {
+        for (json, needle) in [
+            (
+                r#"{ "path_weights": [{ "glob": "src/**", "weight": 5.5 }] }"#,
+                "path_weights[0].weight must be in [0.0, 5.0]",
+            ),
+            (
+                r#"{ "path_weights": [{ "glob": "src/[", "weight": 1.0 }] }"#,
+                "path_weights[0].glob `src/[`",
+            ),
+            (
+                r#"{ "path_weights": [{ "glob": " ", "weight": 1.0 }] }"#,
+                "path_weights[0].glob must not be empty",
+            ),
+            (
+                r#"{ "path_weights": [{ "glob": "src/**", "weight": 1.0, "kind": "glob" }] }"#,
+                "path_weights[0].kind",
+            ),
+        ] {
+            let err = SearchProfile::from_bytes("bad", json.as_bytes(), None).unwrap_err();
+            assert!(format!("{err:#}").contains(needle), "{err:#}");
+        }
+    }
+
+    #[test]
+    fn overlay_path_weights_take_precedence_over_the_base() {
+        let base =
+            parse_raw(br#"{ "path_weights": [{ "glob": "src/**", "weight": 0.5 }] }"#).unwrap();
+        let overlay =
+            parse_raw(br#"{ "path_weights": [{ "glob": "src/**", "weight": 3.0 }] }"#).unwrap();
+        let profile = SearchProfile::from_raw(merge_raw_profiles(base, overlay), "merged").unwrap();
+
+        assert!((profile.directory_weight("src/lib.rs") - 3.0).abs() < f32::EPSILON);
+        assert_eq!(profile.path_weight_overlaps().len(), 1);
+        assert_eq!(glob_example("src/{a,b}/[xy]*.rs"), "src/a/xx.rs");
+    }
+
+    #[test]
+    fn classifier_rules_run_before_the_builtin_heuristics() {
+        let base = parse_raw(
+            br#"{ "classifier_rules": [{ "pattern": "^[A-Z][a-z]+[A-Z]", "type": "identifier" }] }"#,
+        )
+        .unwrap();
+        let overlay = parse_raw(
+            br#"{ "classifier_rules": [{ "pattern": "^(?i)PayFlow\\b", "type": "conceptual" }] }"#,
+        )
+        .unwrap();
+        let profile = SearchProfile::from_raw(merge_raw_profiles(base, overlay), "merged").unwrap();
+
+        assert_eq!(profile.classify_query(" PayFlow "), QueryType::Conceptual);
+        assert_eq!(
+            profile
+                .classifier_rule("payflow refunds")
+                .map(|r| r.pattern.as_str()),
+            Some("^(?i)PayFlow\\b")
+        );
+        assert_eq!(
+            profile.classify_query("HybridSearch"),
+            QueryType::Identifier
+        );
+        assert_eq!(profile.classify_query("src/lib.rs"), QueryType::Path);
+        assert!(profile.classifier_rule("src/lib.rs").is_none());
+        assert_eq!(
+            SearchProfile::general().classify_query("PayFlow"),
+            QueryType::Identifier
+        );
+
+        for (json, needle) in [
+            (
+                r#"{ "classifier_rules": [{ "pattern": "(", "type": "path" }] }"#,
+                "classifier_rules[0].pattern `(`",
+            ),
+            (
+                r#"{ "classifier_rules": [{ "pattern": "x", "type": "symbol" }] }"#,
+                "symbol",
+            ),
+            (
+                r#"{ "classifier_rules": [{ "pattern": "x", "type": "path", "kind": "regex" }] }"#,
+                "classifier_rules[0].kind",
+            ),
+        ] {
+            let err = SearchProfile::from_bytes("bad", json.as_bytes(), None).unwrap_err();
+            assert!(format!("{err:#}").contains(needle), "{err:#}");
+        }
+    }
 }
@@ -1,4 +1,8 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum QueryType {
     /// Looks like a symbol/function/class name
     Identifier,
@@ -8,7 +12,33 @@ pub enum QueryType {
     Conceptual,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl QueryType {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Identifier => "identifier",
+            Self::Path => "path",
+            Self::Conceptual => "conceptual",
+        }
+    }
+}
+
+impl FromStr for QueryType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "identifier" => Ok(Self::Identifier),
+            "path" => Ok(Self::Path),
+            "conceptual" => Ok(Self::Conceptual),
+            other => Err(format!(
+                "Unknown query type '{other}' (expected identifier, path or conceptual)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, schemars::JsonSchema)]
 pub struct QueryWeights {
     pub semantic: f32,
     pub fuzzy: f32,
@@ -120,7 +150,13 @@ impl QueryClassifier {
 
     #[must_use]
     pub fn weights(query: &str) -> QueryWeights {
-        match Self::classify(query) {
+        Self::weights_for(Self::classify(query), query)
+    }
+
+    /// Fusion weights for an already-decided query type (e.g. a caller override).
+    #[must_use]
+    pub fn weights_for(query_type: QueryType, query: &str) -> QueryWeights {
+        match query_type {
             // Exact/symbol queries should favor fuzzy matches for top-1 precision
             QueryType::Identifier => QueryWeights::new(0.1, 0.9, 3),
             QueryType::Path => QueryWeights::new(0.15, 0.85, 4),
@@ -220,6 +256,29 @@ mod tests {
         assert!(w_concept_long.semantic > w_concept_long.fuzzy);
     }
 
+    #[test]
+    fn classify_exposes_identifier_vs_conceptual() {
+        let ident = QueryClassifier::classify("parse_config");
+        let concept = QueryClassifier::classify("where is the config parsed");
+        assert_eq!(ident, QueryType::Identifier);
+        assert_eq!(concept, QueryType::Conceptual);
+        assert_eq!(ident.as_str(), "identifier");
+        assert_eq!(concept.as_str(), "conceptual");
+    }
+
+    #[test]
+    fn forced_query_type_overrides_classification() {
+        let forced = QueryClassifier::weights_for(QueryType::Conceptual, "HybridSearch");
+        let auto = QueryClassifier::weights("HybridSearch");
+        assert!(forced.semantic > forced.fuzzy);
+        assert!(auto.fuzzy > auto.semantic);
+
+        assert_eq!("PATH".parse::<QueryType>(), Ok(QueryType::Path));
+        assert!("symbol".parse::<QueryType>().is_err());
+        let parsed: QueryType = serde_json::from_str("\"identifier\"").unwrap();
+        assert_eq!(parsed, QueryType::Identifier);
+    }
+
     #[test]
     fn docs_intent_detects_common_doc_queries() {
         assert!(QueryClassifier::is_docs_intent("README.md"));
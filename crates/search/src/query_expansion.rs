@@ -1,6 +1,13 @@
 use std::collections::HashMap;
 
+/// Upper bound on the expanded term list (original query + tokens + synonyms)
+const MAX_EXPANSIONS: usize = 15;
+
+/// Upper bound on user-supplied synonyms kept per term
+pub const MAX_USER_SYNONYMS_PER_TERM: usize = 8;
+
 /// Query expander for code search with domain-specific synonyms
+#[derive(Debug, Clone)]
 pub struct QueryExpander {
     /// Synonym dictionary: term -> [synonyms]
     synonyms: HashMap<String, Vec<String>>,
@@ -170,6 +177,40 @@ impl QueryExpander {
         Self { synonyms }
     }
 
+    /// Create expander with built-in synonyms extended by a user dictionary
+    /// (e.g. team jargon like "dao" -> ["repository"]).
+    ///
+    /// Keys are matched case-insensitively; user terms are appended after the
+    /// built-in ones and capped at [`MAX_USER_SYNONYMS_PER_TERM`] per key.
+    #[must_use]
+    pub fn with_synonyms(map: HashMap<String, Vec<String>>) -> Self {
+        let mut expander = Self::new();
+        let mut keys: Vec<_> = map.into_iter().collect();
+        keys.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (term, extra) in keys {
+            let term = term.trim().to_lowercase();
+            if term.is_empty() {
+                continue;
+            }
+            let entry = expander.synonyms.entry(term.clone()).or_default();
+            let mut added = 0usize;
+            for syn in extra {
+                if added >= MAX_USER_SYNONYMS_PER_TERM {
+                    break;
+                }
+                let syn = syn.trim().to_string();
+                if syn.is_empty() || syn.eq_ignore_ascii_case(&term) || entry.contains(&syn) {
+                    continue;
+                }
+                entry.push(syn);
+                added += 1;
+            }
+        }
+
+        expander
+    }
+
     /// Expand query with synonyms and variants
     #[must_use]
     pub fn expand(&self, query: &str) -> Vec<String> {
@@ -198,7 +239,7 @@ impl QueryExpander {
         }
 
         // Limit expansion to avoid too many variants
-        expansions.truncate(15);
+        expansions.truncate(MAX_EXPANSIONS);
 
         expansions
     }
@@ -310,6 +351,34 @@ mod tests {
         assert!(expansions.contains(&"similarity".to_string()));
     }
 
+    #[test]
+    fn user_synonyms_merge_with_defaults_and_stay_bounded() {
+        let mut map = HashMap::new();
+        map.insert("DAO".to_string(), vec!["repository".to_string()]);
+        map.insert(
+            "error".to_string(),
+            (0..20).map(|i| format!("custom_err_{i}")).collect(),
+        );
+        let expander = QueryExpander::with_synonyms(map);
+
+        let expanded = expander.expand_to_query("user dao");
+        assert!(expanded.split(' ').any(|t| t == "repository"));
+
+        // Built-in synonyms survive the merge; user extras are capped per term.
+        let error_syns = &expander.synonyms["error"];
+        assert!(error_syns.contains(&"Result".to_string()));
+        assert_eq!(
+            error_syns
+                .iter()
+                .filter(|s| s.starts_with("custom_err_"))
+                .count(),
+            MAX_USER_SYNONYMS_PER_TERM
+        );
+
+        let expansions = expander.expand("error dao handling");
+        assert!(expansions.len() <= MAX_EXPANSIONS);
+    }
+
     #[test]
     #[allow(clippy::similar_names)]
     fn test_expand_to_query() {
@@ -1,9 +1,16 @@
 use crate::{ContextPackBudget, ContextPackItem};
+use context_graph::{AssemblyBudget, AssemblyStrategy, ContextAssembler, RelationshipType};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 pub const TASK_PACK_VERSION: u32 = 1;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Primary items whose graph neighbours are turned into next actions.
+const GRAPH_ACTION_PRIMARIES: usize = 3;
+const DEPENDENT_SCORE: f32 = 0.45;
+const DEPENDENCY_SCORE: f32 = 0.4;
+
+#[derive(Debug, Serialize, Deserialize, Clone, schemars::JsonSchema)]
 pub struct TaskPackItem {
     #[serde(flatten)]
     pub item: ContextPackItem,
@@ -11,28 +18,40 @@ pub struct TaskPackItem {
     pub why: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, schemars::JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum NextActionKind {
     OpenFile,
     Run,
     Query,
     UpdateContract,
+    /// Trace callers and dependents of the symbol in `query`
+    Impact,
+    /// Exact-text search for the pattern in `query`
+    GrepContext,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, schemars::JsonSchema)]
 pub struct NextAction {
     pub kind: NextActionKind,
     pub reason: String,
+    /// Predicted usefulness in 0-1; `next_actions` is sorted by it, best first
+    #[serde(default)]
+    pub score: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file: Option<String>,
+    /// Line range in `file` to open, when the action points at a specific symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub command: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub query: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, schemars::JsonSchema)]
 pub struct TaskPackOutput {
     pub version: u32,
     pub intent: String,
@@ -44,3 +63,223 @@ pub struct TaskPackOutput {
     pub next_actions: Vec<NextAction>,
     pub budget: ContextPackBudget,
 }
+
+impl NextAction {
+    fn open_span(
+        reason: String,
+        score: f32,
+        file: &str,
+        start_line: usize,
+        end_line: usize,
+    ) -> Self {
+        Self {
+            kind: NextActionKind::OpenFile,
+            reason,
+            score,
+            file: Some(file.to_string()),
+            start_line: Some(start_line),
+            end_line: Some(end_line),
+            command: None,
+            query: None,
+        }
+    }
+}
+
+/// Follow-ups grounded in the code graph: for the top primary items, their direct dependents
+/// (incoming edges) and dependencies (direct assembly under `budget`, as context_pack uses)
+/// that live outside the primary files, as `open_file` actions with exact line ranges. A
+/// primary whose dependents do not all fit also gets an `impact` action. At most `max_actions`
+/// actions, one per file, in a deterministic order.
+#[must_use]
+pub fn graph_next_actions(
+    assembler: &ContextAssembler,
+    budget: &AssemblyBudget,
+    items: &[ContextPackItem],
+    max_actions: usize,
+) -> Vec<NextAction> {
+    let graph = assembler.graph();
+    let primaries: Vec<&ContextPackItem> =
+        items.iter().filter(|item| item.role == "primary").collect();
+    let mut suggested: HashSet<String> = primaries.iter().map(|item| item.file.clone()).collect();
+    let mut actions = Vec::new();
+
+    for primary in primaries.iter().take(GRAPH_ACTION_PRIMARIES) {
+        let Some(node) = graph.find_nodes_by_chunk(&primary.id).first().copied() else {
+            continue;
+        };
+        let Some(symbol) = graph.get_node(node).map(|n| n.symbol.name.clone()) else {
+            continue;
+        };
+
+        let mut dependents: Vec<(RelationshipType, &context_graph::Symbol)> = graph
+            .get_all_usages(node)
+            .into_iter()
+            .filter_map(|(idx, rel)| graph.get_node(idx).map(|n| (rel, &n.symbol)))
+            .collect();
+        dependents.sort_by(|a, b| {
+            a.0.relevance_rank()
+                .cmp(&b.0.relevance_rank())
+                .then_with(|| a.1.file_path.cmp(&b.1.file_path))
+                .then_with(|| a.1.start_line.cmp(&b.1.start_line))
+        });
+        let mut outside = 0usize;
+        let mut shown = 0usize;
+        for (rel, dependent) in dependents {
+            if primaries.iter().any(|p| p.file == dependent.file_path) {
+                continue;
+            }
+            outside += 1;
+            if actions.len() >= max_actions || !suggested.insert(dependent.file_path.clone()) {
+                continue;
+            }
+            shown += 1;
+            actions.push(NextAction::open_span(
+                format!(
+                    "`{}` depends on `{symbol}` ({rel:?}); check it when changing `{symbol}`",
+                    dependent.name
+                ),
+                DEPENDENT_SCORE,
+                &dependent.file_path,
+                dependent.start_line,
+                dependent.end_line,
+            ));
+        }
+        if outside > shown && actions.len() < max_actions {
+            actions.push(NextAction {
+                kind: NextActionKind::Impact,
+                reason: format!("`{symbol}` has {outside} dependents outside the pack"),
+                score: DEPENDENT_SCORE,
+                file: None,
+                start_line: None,
+                end_line: None,
+                command: None,
+                query: Some(symbol.clone()),
+            });
+        }
+
+        let Ok(assembled) =
+            assembler.assemble_for_chunk_with_budget(&primary.id, AssemblyStrategy::Direct, budget)
+        else {
+            continue;
+        };
+        for related in assembled.related_chunks {
+            if actions.len() >= max_actions {
+                break;
+            }
+            let chunk = &related.chunk;
+            if !suggested.insert(chunk.file_path.clone()) {
+                continue;
+            }
+            let name = related
+                .symbol_path()
+                .pop()
+                .unwrap_or_else(|| chunk.file_path.clone());
+            actions.push(NextAction::open_span(
+                format!("`{symbol}` depends on `{name}` ({:?})", related.bucket),
+                DEPENDENCY_SCORE,
+                &chunk.file_path,
+                chunk.start_line,
+                chunk.end_line,
+            ));
+        }
+    }
+
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_code_chunker::{ChunkMetadata, CodeChunk};
+    use context_graph::{CodeGraph, GraphEdge, GraphNode, Symbol, SymbolType};
+
+    fn node(name: &str, file: &str, start_line: usize, end_line: usize) -> GraphNode {
+        GraphNode {
+            symbol: Symbol {
+                name: name.to_string(),
+                qualified_name: None,
+                file_path: file.to_string(),
+                start_line,
+                end_line,
+                symbol_type: SymbolType::Function,
+            },
+            chunk_id: format!("{file}:{start_line}:{end_line}"),
+            chunk: Some(CodeChunk::new(
+                file.to_string(),
+                start_line,
+                end_line,
+                format!("fn {name}() {{}}"),
+                ChunkMetadata::default(),
+            )),
+            signature: None,
+            documentation: None,
+        }
+    }
+
+    fn edge(relationship: RelationshipType) -> GraphEdge {
+        GraphEdge {
+            relationship,
+            weight: 1.0,
+        }
+    }
+
+    fn primary(id: &str, file: &str) -> ContextPackItem {
+        ContextPackItem {
+            id: id.to_string(),
+            role: "primary".to_string(),
+            file: file.to_string(),
+            start_line: 1,
+            end_line: 3,
+            symbol: None,
+            chunk_type: None,
+            score: 1.0,
+            imports: Vec::new(),
+            content: String::new(),
+            relationship: None,
+            distance: None,
+            bucket: None,
+            indexed_at_ms: None,
+            file_mtime_ms: None,
+        }
+    }
+
+    #[test]
+    fn graph_actions_point_at_neighbours_outside_the_primaries() {
+        let mut graph = CodeGraph::new();
+        let tool = graph.add_node(node("register_tool", "src/tools/catalog.rs", 10, 20));
+        let router = graph.add_node(node("route", "src/dispatch/router.rs", 5, 9));
+        let schema = graph.add_node(node("ToolSchema", "src/tools/schemas.rs", 1, 4));
+        let same_file = graph.add_node(node("helper", "src/tools/catalog.rs", 30, 32));
+        graph.add_edge(router, tool, edge(RelationshipType::Calls));
+        graph.add_edge(tool, schema, edge(RelationshipType::Uses));
+        graph.add_edge(same_file, tool, edge(RelationshipType::Calls));
+        let assembler = ContextAssembler::new(graph);
+
+        let items = vec![primary(
+            "src/tools/catalog.rs:10:20",
+            "src/tools/catalog.rs",
+        )];
+        let actions = graph_next_actions(&assembler, &AssemblyBudget::default(), &items, 5);
+
+        let opened: Vec<(&str, Option<usize>, Option<usize>)> = actions
+            .iter()
+            .map(|a| (a.file.as_deref().unwrap(), a.start_line, a.end_line))
+            .collect();
+        assert_eq!(
+            opened,
+            vec![
+                ("src/dispatch/router.rs", Some(5), Some(9)),
+                ("src/tools/schemas.rs", Some(1), Some(4)),
+            ]
+        );
+        assert!(actions
+            .iter()
+            .all(|a| matches!(a.kind, NextActionKind::OpenFile)));
+        assert!(actions[0]
+            .reason
+            .contains("`route` depends on `register_tool`"));
+
+        let capped = graph_next_actions(&assembler, &AssemblyBudget::default(), &items, 1);
+        assert_eq!(capped.len(), 1);
+    }
+}
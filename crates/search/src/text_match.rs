@@ -0,0 +1,93 @@
+//! Line matching shared by the CLI and MCP `text_search` implementations.
+
+/// Word characters for whole-word matching: Unicode letters and digits plus `_`, so
+/// identifiers such as `größe` or `имя` are words too.
+#[must_use]
+pub fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Byte offset of the first occurrence of `pattern` in `line`.
+///
+/// Case-insensitive matching folds each char (Unicode-aware) without rewriting the line, so the
+/// offset always points into `line` itself. With `whole_word`, an edge of the match that is a
+/// word char must not touch another word char — the `\b` rule, applied only where the pattern
+/// itself starts or ends with a word char.
+#[must_use]
+pub fn match_in_line(
+    line: &str,
+    pattern: &str,
+    case_sensitive: bool,
+    whole_word: bool,
+) -> Option<usize> {
+    if pattern.is_empty() {
+        return None;
+    }
+    let check_start = pattern.chars().next().is_some_and(is_word_char);
+    let check_end = pattern.chars().next_back().is_some_and(is_word_char);
+    let at_boundary = |start: usize, end: usize| {
+        if !whole_word {
+            return true;
+        }
+        let left_ok = !check_start || !line[..start].chars().next_back().is_some_and(is_word_char);
+        let right_ok = !check_end || !line[end..].chars().next().is_some_and(is_word_char);
+        left_ok && right_ok
+    };
+
+    if case_sensitive {
+        return line
+            .match_indices(pattern)
+            .map(|(idx, _)| idx)
+            .find(|&idx| at_boundary(idx, idx + pattern.len()));
+    }
+    line.char_indices()
+        .filter_map(|(start, _)| {
+            caseless_match_end(&line[start..], pattern).map(|len| (start, len))
+        })
+        .find(|&(start, len)| at_boundary(start, start + len))
+        .map(|(start, _)| start)
+}
+
+/// Byte length of the prefix of `text` that equals `pattern` ignoring case, if any.
+fn caseless_match_end(text: &str, pattern: &str) -> Option<usize> {
+    let mut text_chars = text.char_indices();
+    for expected in pattern.chars() {
+        let (_, actual) = text_chars.next()?;
+        if actual != expected && !actual.to_lowercase().eq(expected.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(text_chars.next().map_or(text.len(), |(idx, _)| idx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_word_uses_unicode_word_boundaries() {
+        let line = "let größe = maxgröße + größe_alt;";
+        assert_eq!(match_in_line(line, "größe", true, true), Some(4));
+        assert_eq!(match_in_line("maxgröße + x", "größe", true, true), None);
+        assert_eq!(match_in_line("größeé", "größe", true, true), None);
+        assert_eq!(match_in_line("x = имя;", "имя", true, true), Some(4));
+        assert_eq!(match_in_line("x = имяФайла;", "имя", true, true), None);
+        assert_eq!(match_in_line("x = имяФайла;", "имя", true, false), Some(4));
+    }
+
+    #[test]
+    fn case_insensitive_offsets_point_into_the_original_line() {
+        let line = "ß; let ÜBER = über;";
+        assert_eq!(match_in_line(line, "über", false, true), Some(8));
+        assert_eq!(&line[8..13], "ÜBER");
+        assert_eq!(match_in_line("ÜBERall", "über", false, true), None);
+    }
+
+    #[test]
+    fn boundaries_only_apply_to_word_edges_of_the_pattern() {
+        assert_eq!(match_in_line("a::b", "::b", true, true), Some(1));
+        assert_eq!(match_in_line("a::bc", "::b", true, true), None);
+        assert_eq!(match_in_line("foo_bar", "bar", true, true), None);
+        assert_eq!(match_in_line("anything", "", true, true), None);
+    }
+}
@@ -16,6 +16,7 @@ fn make_result(id: &str, score: f32) -> SearchResult {
                 ..ChunkMetadata::default()
             },
         ),
+        freshness: None,
     }
 }
 
@@ -0,0 +1,76 @@
+use context_code_chunker::{ChunkMetadata, ChunkType, CodeChunk};
+use context_graph::{AssemblyStrategy, GraphLanguage};
+use context_search::{graph_build_count, ContextSearch, HybridSearch};
+use context_vector_store::VectorStore;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn rust_chunk(path: &str, symbol: &str, content: &str) -> CodeChunk {
+    CodeChunk::new(
+        path.to_string(),
+        1,
+        content.lines().count(),
+        content.to_string(),
+        ChunkMetadata::default()
+            .chunk_type(ChunkType::Function)
+            .symbol_name(symbol),
+    )
+}
+
+async fn hybrid(dir: &TempDir, name: &str) -> HybridSearch {
+    let chunks = vec![
+        rust_chunk(
+            "src/parse.rs",
+            "parse_config",
+            "fn parse_config(raw: &str) -> Config {\n    validate_config(raw)\n}",
+        ),
+        rust_chunk(
+            "src/validate.rs",
+            "validate_config",
+            "fn validate_config(raw: &str) -> Config {\n    Config::default()\n}",
+        ),
+    ];
+    let store_path = dir
+        .path()
+        .join(".context-finder")
+        .join("indexes")
+        .join(name);
+    let mut store = VectorStore::new(store_path).unwrap();
+    store.add_chunks(chunks.clone()).await.unwrap();
+    HybridSearch::new(store, chunks).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn concurrent_searches_share_one_graph() {
+    std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+    let dir = TempDir::new().unwrap();
+    let builds_before = graph_build_count();
+
+    let mut first = ContextSearch::new(hybrid(&dir, "first.json").await).unwrap();
+    first.build_graph(GraphLanguage::Rust).unwrap();
+    let shared = first.shared_assembler().unwrap();
+    let mut second = ContextSearch::new(hybrid(&dir, "second.json").await)
+        .unwrap()
+        .with_shared_assembler(Arc::clone(&shared));
+    assert!(second.has_graph());
+
+    let handles = [
+        tokio::spawn(async move {
+            first
+                .search_with_context("parse_config", 5, AssemblyStrategy::Extended)
+                .await
+        }),
+        tokio::spawn(async move {
+            second
+                .search_with_context("validate_config", 5, AssemblyStrategy::Extended)
+                .await
+        }),
+    ];
+    for handle in handles {
+        let results = handle.await.unwrap().unwrap();
+        assert!(!results.is_empty());
+    }
+
+    assert_eq!(graph_build_count() - builds_before, 1);
+    assert_eq!(Arc::strong_count(&shared), 1);
+}
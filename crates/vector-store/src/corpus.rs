@@ -1,20 +1,114 @@
 use crate::error::{Result, VectorStoreError};
+use crate::types::ChunkFreshness;
 use context_code_chunker::CodeChunk;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
-pub const CHUNK_CORPUS_SCHEMA_VERSION: u32 = 1;
+/// Version 2 splits the corpus into a manifest plus one shard per directory; version 1
+/// (a single JSON blob) is still read and migrated on the next save.
+pub const CHUNK_CORPUS_SCHEMA_VERSION: u32 = 2;
+const LEGACY_CHUNK_CORPUS_SCHEMA_VERSION: u32 = 1;
+
+#[must_use]
+pub const fn is_supported_corpus_schema_version(version: u32) -> bool {
+    version == CHUNK_CORPUS_SCHEMA_VERSION || version == LEGACY_CHUNK_CORPUS_SCHEMA_VERSION
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct ChunkCorpus {
     files: BTreeMap<String, Vec<CodeChunk>>,
+    freshness: BTreeMap<String, ChunkFreshness>,
+    /// Shards (directories) changed since the corpus was loaded
+    dirty: BTreeSet<String>,
+    /// Loaded by [`ChunkCorpus::load_matching`]; saving it would drop the other shards
+    partial: bool,
+    symbols: SymbolIndex,
+}
+
+/// Where the chunks defining each symbol live: `(file, position in its chunk list)`, so
+/// lookups return chunks in file order.
+#[derive(Debug, Clone, Default)]
+struct SymbolIndex {
+    by_name: HashMap<String, BTreeSet<(String, usize)>>,
+    by_qualified_name: HashMap<String, BTreeSet<(String, usize)>>,
+}
+
+impl SymbolIndex {
+    fn insert(&mut self, file: &str, chunks: &[CodeChunk]) {
+        for (pos, chunk) in chunks.iter().enumerate() {
+            let meta = &chunk.metadata;
+            if let Some(name) = &meta.symbol_name {
+                self.by_name
+                    .entry(name.clone())
+                    .or_default()
+                    .insert((file.to_string(), pos));
+            }
+            if let Some(name) = &meta.qualified_name {
+                self.by_qualified_name
+                    .entry(name.clone())
+                    .or_default()
+                    .insert((file.to_string(), pos));
+            }
+        }
+    }
+
+    fn remove(&mut self, file: &str, chunks: &[CodeChunk]) {
+        for (pos, chunk) in chunks.iter().enumerate() {
+            let meta = &chunk.metadata;
+            let entry = (file.to_string(), pos);
+            if let Some(name) = &meta.symbol_name {
+                remove_entry(&mut self.by_name, name, &entry);
+            }
+            if let Some(name) = &meta.qualified_name {
+                remove_entry(&mut self.by_qualified_name, name, &entry);
+            }
+        }
+    }
+}
+
+fn remove_entry(
+    map: &mut HashMap<String, BTreeSet<(String, usize)>>,
+    name: &str,
+    entry: &(String, usize),
+) {
+    if let Some(entries) = map.get_mut(name) {
+        entries.remove(entry);
+        if entries.is_empty() {
+            map.remove(name);
+        }
+    }
 }
 
+/// `corpus.json`: which files live in which shard. A legacy (v1) corpus keeps every
+/// chunk inline under `files` instead.
 #[derive(Debug, Serialize, Deserialize)]
-struct PersistedChunkCorpus {
+struct PersistedCorpusManifest {
     schema_version: u32,
+    /// Bumped by every save that rewrites a shard, so the manifest mtime tracks shard edits
+    #[serde(default)]
+    generation: u64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    shards: BTreeMap<String, ShardEntry>,
+    #[serde(default, skip_serializing)]
+    files: BTreeMap<String, Vec<CodeChunk>>,
+    /// Absent for corpora written before index timestamps were recorded
+    #[serde(default, skip_serializing)]
+    freshness: BTreeMap<String, ChunkFreshness>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ShardEntry {
+    /// Shard file name inside the shard directory
+    file: String,
+    files: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedShard {
     files: BTreeMap<String, Vec<CodeChunk>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    freshness: BTreeMap<String, ChunkFreshness>,
 }
 
 impl ChunkCorpus {
@@ -24,43 +118,195 @@ impl ChunkCorpus {
     }
 
     pub async fn load(path: impl AsRef<Path>) -> Result<Self> {
-        let path = path.as_ref();
-        let bytes = tokio::fs::read(path).await?;
-        let persisted: PersistedChunkCorpus = serde_json::from_slice(&bytes)?;
-        if persisted.schema_version != CHUNK_CORPUS_SCHEMA_VERSION {
-            return Err(VectorStoreError::EmbeddingError(format!(
-                "Unsupported chunk corpus schema_version {} (expected {CHUNK_CORPUS_SCHEMA_VERSION})",
-                persisted.schema_version
-            )));
-        }
-        Ok(Self {
-            files: persisted.files,
+        Self::load_matching(path, |_| true).await.map(|mut corpus| {
+            corpus.partial = false;
+            corpus
         })
     }
 
+    /// Load only the shards holding a file `keep` accepts, and of those only the kept
+    /// files. The result is read-only: [`ChunkCorpus::save`] refuses partial corpora.
+    pub async fn load_matching(
+        path: impl AsRef<Path>,
+        keep: impl Fn(&str) -> bool,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let manifest = read_manifest(path).await?;
+        let mut corpus = Self {
+            partial: true,
+            ..Self::default()
+        };
+
+        if manifest.schema_version == LEGACY_CHUNK_CORPUS_SCHEMA_VERSION {
+            // Every shard is new on disk, so the next save writes them all.
+            corpus.dirty = manifest.files.keys().map(|file| shard_key(file)).collect();
+            corpus.files = manifest.files;
+            corpus.freshness = manifest.freshness;
+            corpus.files.retain(|file, _| keep(file));
+            corpus.freshness.retain(|file, _| keep(file));
+            corpus.rebuild_symbols();
+            return Ok(corpus);
+        }
+
+        let dir = shard_dir(path);
+        for entry in manifest.shards.values() {
+            if !entry.files.iter().any(|file| keep(file)) {
+                continue;
+            }
+            let shard_path = dir.join(&entry.file);
+            let bytes = tokio::fs::read(&shard_path).await.map_err(|err| {
+                VectorStoreError::EmbeddingError(format!(
+                    "Failed to read corpus shard {}: {err}",
+                    shard_path.display()
+                ))
+            })?;
+            let shard: PersistedShard = serde_json::from_slice(&bytes)?;
+            for (file, chunks) in shard.files {
+                if keep(&file) {
+                    corpus.files.insert(file, chunks);
+                }
+            }
+            for (file, freshness) in shard.freshness {
+                if keep(&file) {
+                    corpus.freshness.insert(file, freshness);
+                }
+            }
+        }
+        corpus.rebuild_symbols();
+        Ok(corpus)
+    }
+
+    fn rebuild_symbols(&mut self) {
+        let mut symbols = SymbolIndex::default();
+        for (file, chunks) in &self.files {
+            symbols.insert(file, chunks);
+        }
+        self.symbols = symbols;
+    }
+
+    /// Every file recorded in the corpus at `path`, sorted, read from the manifest alone
+    /// (a legacy corpus has to be parsed in full).
+    pub async fn list_files(path: impl AsRef<Path>) -> Result<Vec<String>> {
+        let manifest = read_manifest(path.as_ref()).await?;
+        if manifest.schema_version == LEGACY_CHUNK_CORPUS_SCHEMA_VERSION {
+            return Ok(manifest.files.into_keys().collect());
+        }
+        let mut files: Vec<String> = manifest
+            .shards
+            .into_values()
+            .flat_map(|entry| entry.files)
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Write the shards changed since load (all of them after a legacy load), then the
+    /// manifest if the shard layout changed, then drop shard files the manifest no longer
+    /// references. Editing one file rewrites only its directory's shard.
     pub async fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        if self.partial {
+            return Err(VectorStoreError::EmbeddingError(
+                "Refusing to save a partially loaded chunk corpus".to_string(),
+            ));
+        }
         let path = path.as_ref().to_path_buf();
-        if let Some(parent) = path.parent() {
-            tokio::fs::create_dir_all(parent).await?;
+        let dir = shard_dir(&path);
+        tokio::fs::create_dir_all(&dir).await?;
+
+        let mut by_shard: BTreeMap<String, Vec<&String>> = BTreeMap::new();
+        for file in self.files.keys() {
+            by_shard.entry(shard_key(file)).or_default().push(file);
         }
-        let persisted = PersistedChunkCorpus {
+
+        let previous_manifest = tokio::fs::read(&path).await.ok();
+        let previous_generation = previous_manifest
+            .as_deref()
+            .and_then(|bytes| serde_json::from_slice::<PersistedCorpusManifest>(bytes).ok())
+            .map_or(0, |manifest| manifest.generation);
+        let mut manifest = PersistedCorpusManifest {
             schema_version: CHUNK_CORPUS_SCHEMA_VERSION,
-            files: self.files.clone(),
+            generation: previous_generation,
+            shards: BTreeMap::new(),
+            files: BTreeMap::new(),
+            freshness: BTreeMap::new(),
         };
-        let bytes = serde_json::to_vec_pretty(&persisted)?;
-        let tmp = path.with_extension("json.tmp");
-        tokio::fs::write(&tmp, bytes).await?;
-        tokio::fs::rename(&tmp, &path).await?;
+        for (key, files) in by_shard {
+            let entry = ShardEntry {
+                file: shard_file_name(&key),
+                files: files.iter().map(|file| (*file).clone()).collect(),
+            };
+            let shard_path = dir.join(&entry.file);
+            if self.dirty.contains(&key) || !shard_path.exists() {
+                let shard = PersistedShard {
+                    files: files
+                        .iter()
+                        .map(|file| ((*file).clone(), self.files[*file].clone()))
+                        .collect(),
+                    freshness: files
+                        .iter()
+                        .filter_map(|file| Some(((*file).clone(), *self.freshness.get(*file)?)))
+                        .collect(),
+                };
+                write_atomic(&shard_path, &serde_json::to_vec(&shard)?).await?;
+                manifest.generation = previous_generation + 1;
+            }
+            manifest.shards.insert(key, entry);
+        }
+        // Readers key caches off the manifest mtime alone, so a shard-only edit still rewrites it.
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+        if previous_manifest.as_deref() != Some(manifest_bytes.as_slice()) {
+            write_atomic(&path, &manifest_bytes).await?;
+        }
+
+        let live: HashSet<&str> = manifest
+            .shards
+            .values()
+            .map(|entry| entry.file.as_str())
+            .collect();
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            if !live.contains(name.to_string_lossy().as_ref()) {
+                let _ = tokio::fs::remove_file(entry.path()).await;
+            }
+        }
         Ok(())
     }
 
     pub fn set_file_chunks(&mut self, file_path: String, chunks: Vec<CodeChunk>) {
+        self.dirty.insert(shard_key(&file_path));
+        if let Some(old) = self.files.get(&file_path) {
+            self.symbols.remove(&file_path, old);
+        }
+        self.symbols.insert(&file_path, &chunks);
         self.files.insert(file_path, chunks);
     }
 
+    /// Record when the chunks of `file_path` were indexed (all chunks of a file are
+    /// indexed together, so this is also each chunk's freshness).
+    pub fn set_file_freshness(&mut self, file_path: String, freshness: ChunkFreshness) {
+        self.dirty.insert(shard_key(&file_path));
+        self.freshness.insert(file_path, freshness);
+    }
+
+    #[must_use]
+    pub fn file_freshness(&self, file_path: &str) -> Option<ChunkFreshness> {
+        self.freshness.get(file_path).copied()
+    }
+
     pub fn purge_missing_files(&mut self, live_files: &HashSet<String>) -> usize {
         let before = self.files.len();
-        self.files.retain(|path, _| live_files.contains(path));
+        let dirty = &mut self.dirty;
+        let symbols = &mut self.symbols;
+        self.files.retain(|path, chunks| {
+            let live = live_files.contains(path);
+            if !live {
+                dirty.insert(shard_key(path));
+                symbols.remove(path, chunks);
+            }
+            live
+        });
+        self.freshness.retain(|path, _| live_files.contains(path));
         before.saturating_sub(self.files.len())
     }
 
@@ -73,6 +319,26 @@ impl ChunkCorpus {
             .find(|chunk| chunk.start_line == start_line && chunk.end_line == end_line)
     }
 
+    /// Chunks whose `symbol_name` is `name`, in file order.
+    #[must_use]
+    pub fn find_by_symbol(&self, name: &str) -> Vec<&CodeChunk> {
+        self.resolve_symbols(self.symbols.by_name.get(name))
+    }
+
+    /// Chunks whose `qualified_name` is `name` (e.g. `Type::method`), in file order.
+    #[must_use]
+    pub fn find_by_qualified_name(&self, name: &str) -> Vec<&CodeChunk> {
+        self.resolve_symbols(self.symbols.by_qualified_name.get(name))
+    }
+
+    fn resolve_symbols(&self, entries: Option<&BTreeSet<(String, usize)>>) -> Vec<&CodeChunk> {
+        entries
+            .into_iter()
+            .flatten()
+            .filter_map(|(file, pos)| self.files.get(file)?.get(*pos))
+            .collect()
+    }
+
     #[must_use]
     pub fn file_count(&self) -> usize {
         self.files.len()
@@ -82,6 +348,16 @@ impl ChunkCorpus {
     pub const fn files(&self) -> &BTreeMap<String, Vec<CodeChunk>> {
         &self.files
     }
+
+    /// Files that [`Self::purge_missing_files`] would remove, in path order.
+    #[must_use]
+    pub fn missing_files(&self, live_files: &HashSet<String>) -> Vec<String> {
+        self.files
+            .keys()
+            .filter(|file| !live_files.contains(*file))
+            .cloned()
+            .collect()
+    }
 }
 
 #[must_use]
@@ -89,7 +365,67 @@ pub fn corpus_path_for_project_root(root: &Path) -> PathBuf {
     root.join(".context-finder").join("corpus.json")
 }
 
-fn parse_chunk_id(chunk_id: &str) -> Option<(String, usize, usize)> {
+/// Bytes on disk of the corpus at `path`: the manifest plus its shard directory.
+#[must_use]
+pub fn corpus_size_bytes(path: &Path) -> u64 {
+    let manifest = std::fs::metadata(path).map_or(0, |meta| meta.len());
+    let shards = std::fs::read_dir(shard_dir(path)).map_or(0, |entries| {
+        entries
+            .flatten()
+            .filter_map(|entry| entry.metadata().ok())
+            .map(|meta| meta.len())
+            .sum()
+    });
+    manifest + shards
+}
+
+async fn read_manifest(path: &Path) -> Result<PersistedCorpusManifest> {
+    let bytes = tokio::fs::read(path).await?;
+    let manifest: PersistedCorpusManifest = serde_json::from_slice(&bytes)?;
+    if !is_supported_corpus_schema_version(manifest.schema_version) {
+        return Err(VectorStoreError::EmbeddingError(format!(
+            "Unsupported chunk corpus schema_version {} (expected {CHUNK_CORPUS_SCHEMA_VERSION})",
+            manifest.schema_version
+        )));
+    }
+    Ok(manifest)
+}
+
+async fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+/// `.context-finder/corpus.json` keeps its shards in `.context-finder/corpus.shards/`.
+fn shard_dir(manifest_path: &Path) -> PathBuf {
+    manifest_path.with_extension("shards")
+}
+
+/// Files are sharded by their parent directory (`""` for the project root).
+fn shard_key(file_path: &str) -> String {
+    file_path
+        .rsplit_once('/')
+        .map_or_else(String::new, |(dir, _)| dir.to_string())
+}
+
+fn shard_file_name(key: &str) -> String {
+    format!("{:016x}.json", fnv1a64(key.as_bytes()))
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 14_695_981_039_346_656_037;
+    const PRIME: u64 = 1_099_511_628_211;
+    let mut hash = OFFSET;
+    for b in bytes {
+        hash ^= u64::from(*b);
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash
+}
+
+pub(crate) fn parse_chunk_id(chunk_id: &str) -> Option<(String, usize, usize)> {
     let mut parts = chunk_id.rsplitn(3, ':');
     let end_line = parts.next()?.parse::<usize>().ok()?;
     let start_line = parts.next()?.parse::<usize>().ok()?;
@@ -124,6 +460,11 @@ mod tests {
             vec![chunk("a.rs", 1, 2, "alpha"), chunk("a.rs", 3, 4, "beta")],
         );
         corpus.set_file_chunks("b.rs".to_string(), vec![chunk("b.rs", 10, 12, "gamma")]);
+        let freshness = ChunkFreshness {
+            indexed_at_ms: 2_000,
+            file_mtime_ms: 1_000,
+        };
+        corpus.set_file_freshness("a.rs".to_string(), freshness);
         corpus.save(&path).await.unwrap();
 
         let loaded = ChunkCorpus::load(&path).await.unwrap();
@@ -133,5 +474,249 @@ mod tests {
             Some("alpha")
         );
         assert!(loaded.get_chunk("missing.rs:1:2").is_none());
+        assert_eq!(loaded.file_freshness("a.rs"), Some(freshness));
+        assert_eq!(loaded.file_freshness("b.rs"), None);
+    }
+
+    #[tokio::test]
+    async fn symbol_lookups_follow_loads_and_updates() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("corpus.json");
+        let symbol = |file: &str, start: usize, name: &str, qualified: &str| {
+            CodeChunk::new(
+                file.to_string(),
+                start,
+                start + 2,
+                format!("fn {name}() {{}}"),
+                ChunkMetadata {
+                    qualified_name: Some(qualified.to_string()),
+                    ..ChunkMetadata::default().symbol_name(name)
+                },
+            )
+        };
+
+        let mut corpus = ChunkCorpus::new();
+        corpus.set_file_chunks(
+            "src/a.rs".to_string(),
+            vec![
+                symbol("src/a.rs", 1, "new", "Parser::new"),
+                chunk("src/a.rs", 4, 5, "// plain"),
+                symbol("src/a.rs", 7, "parse", "Parser::parse"),
+            ],
+        );
+        corpus.set_file_chunks(
+            "src/b.rs".to_string(),
+            vec![symbol("src/b.rs", 1, "new", "Lexer::new")],
+        );
+        corpus.save(&path).await.unwrap();
+
+        let mut loaded = ChunkCorpus::load(&path).await.unwrap();
+        let lines = |chunks: Vec<&CodeChunk>| -> Vec<(String, usize)> {
+            chunks
+                .into_iter()
+                .map(|c| (c.file_path.clone(), c.start_line))
+                .collect()
+        };
+        assert_eq!(
+            lines(loaded.find_by_symbol("new")),
+            vec![("src/a.rs".to_string(), 1), ("src/b.rs".to_string(), 1)]
+        );
+        assert_eq!(
+            lines(loaded.find_by_qualified_name("Parser::parse")),
+            vec![("src/a.rs".to_string(), 7)]
+        );
+        assert!(loaded.find_by_symbol("missing").is_empty());
+        assert!(loaded.find_by_qualified_name("parse").is_empty());
+
+        loaded.set_file_chunks(
+            "src/a.rs".to_string(),
+            vec![symbol("src/a.rs", 3, "parse", "Parser::parse")],
+        );
+        assert_eq!(
+            lines(loaded.find_by_symbol("new")),
+            vec![("src/b.rs".to_string(), 1)]
+        );
+        assert_eq!(
+            lines(loaded.find_by_symbol("parse")),
+            vec![("src/a.rs".to_string(), 3)]
+        );
+
+        loaded.purge_missing_files(&HashSet::from(["src/a.rs".to_string()]));
+        assert!(loaded.find_by_symbol("new").is_empty());
+        assert!(loaded.find_by_qualified_name("Lexer::new").is_empty());
+    }
+
+    #[tokio::test]
+    async fn legacy_corpus_is_read_and_migrated_on_save() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("corpus.json");
+        let legacy = serde_json::json!({
+            "schema_version": 1,
+            "files": {
+                "src/a.rs": [chunk("src/a.rs", 1, 2, "alpha")],
+                "README.md": [chunk("README.md", 1, 1, "readme")],
+            },
+        });
+        tokio::fs::write(&path, serde_json::to_vec(&legacy).unwrap())
+            .await
+            .unwrap();
+
+        let corpus = ChunkCorpus::load(&path).await.unwrap();
+        assert_eq!(corpus.file_count(), 2);
+        corpus.save(&path).await.unwrap();
+
+        let manifest: serde_json::Value =
+            serde_json::from_slice(&tokio::fs::read(&path).await.unwrap()).unwrap();
+        assert_eq!(manifest["schema_version"], CHUNK_CORPUS_SCHEMA_VERSION);
+        assert!(manifest.get("files").is_none());
+        assert_eq!(manifest["shards"].as_object().unwrap().len(), 2);
+        let reloaded = ChunkCorpus::load(&path).await.unwrap();
+        assert_eq!(
+            reloaded
+                .get_chunk("src/a.rs:1:2")
+                .map(|c| c.content.as_str()),
+            Some("alpha")
+        );
+    }
+
+    #[tokio::test]
+    async fn save_rewrites_only_dirty_shards() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("corpus.json");
+        let mut corpus = ChunkCorpus::new();
+        corpus.set_file_chunks("a/x.rs".to_string(), vec![chunk("a/x.rs", 1, 1, "x")]);
+        corpus.set_file_chunks("b/y.rs".to_string(), vec![chunk("b/y.rs", 1, 1, "y")]);
+        corpus.save(&path).await.unwrap();
+
+        // Tamper with shard `b` on disk: a save that touches only `a` must leave it alone.
+        let b_shard = shard_dir(&path).join(shard_file_name("b"));
+        let tampered = PersistedShard {
+            files: BTreeMap::from([("b/y.rs".to_string(), vec![chunk("b/y.rs", 1, 1, "on disk")])]),
+            freshness: BTreeMap::new(),
+        };
+        tokio::fs::write(&b_shard, serde_json::to_vec(&tampered).unwrap())
+            .await
+            .unwrap();
+
+        let mut corpus = ChunkCorpus::load(&path).await.unwrap();
+        corpus.set_file_chunks("a/x.rs".to_string(), vec![chunk("a/x.rs", 1, 2, "x2")]);
+        corpus.purge_missing_files(&HashSet::from(["a/x.rs".to_string(), "b/y.rs".to_string()]));
+        corpus.save(&path).await.unwrap();
+
+        let reloaded = ChunkCorpus::load(&path).await.unwrap();
+        assert_eq!(
+            reloaded.get_chunk("b/y.rs:1:1").map(|c| c.content.as_str()),
+            Some("on disk")
+        );
+        assert!(reloaded.get_chunk("a/x.rs:1:2").is_some());
+
+        let mut corpus = reloaded;
+        corpus.purge_missing_files(&HashSet::from(["a/x.rs".to_string()]));
+        corpus.save(&path).await.unwrap();
+        assert!(!b_shard.exists(), "emptied shard should be removed");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn editing_one_file_rewrites_only_its_shard() {
+        use std::os::unix::fs::MetadataExt;
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("corpus.json");
+        let mut corpus = ChunkCorpus::new();
+        for file in ["a/x.rs", "a/y.rs", "b/z.rs", "top.rs"] {
+            corpus.set_file_chunks(file.to_string(), vec![chunk(file, 1, 1, file)]);
+        }
+        corpus.save(&path).await.unwrap();
+
+        // `write_atomic` renames a fresh file into place, so a rewritten file gets a new inode.
+        let inode = |path: &Path| std::fs::metadata(path).unwrap().ino();
+        let shard = |key: &str| shard_dir(&path).join(shard_file_name(key));
+        let before: Vec<u64> = [path.clone(), shard("a"), shard("b"), shard("")]
+            .iter()
+            .map(|p| inode(p))
+            .collect();
+
+        let mut corpus = ChunkCorpus::load(&path).await.unwrap();
+        corpus.set_file_chunks("a/y.rs".to_string(), vec![chunk("a/y.rs", 1, 2, "edited")]);
+        corpus.save(&path).await.unwrap();
+
+        assert_ne!(inode(&path), before[0], "manifest not rewritten");
+        assert_ne!(inode(&shard("a")), before[1], "edited shard not rewritten");
+        assert_eq!(inode(&shard("b")), before[2]);
+        assert_eq!(inode(&shard("")), before[3]);
+        let reloaded = ChunkCorpus::load(&path).await.unwrap();
+        assert_eq!(
+            reloaded.get_chunk("a/y.rs:1:2").map(|c| c.content.as_str()),
+            Some("edited")
+        );
+
+        // A save with nothing dirty leaves the manifest alone.
+        let after_edit = inode(&path);
+        reloaded.save(&path).await.unwrap();
+        assert_eq!(inode(&path), after_edit, "clean save rewrote the manifest");
+    }
+
+    #[tokio::test]
+    async fn load_matching_reads_only_needed_shards() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("corpus.json");
+        let mut corpus = ChunkCorpus::new();
+        corpus.set_file_chunks("a/x.rs".to_string(), vec![chunk("a/x.rs", 1, 1, "x")]);
+        corpus.set_file_chunks("b/y.rs".to_string(), vec![chunk("b/y.rs", 1, 1, "y")]);
+        corpus.save(&path).await.unwrap();
+
+        tokio::fs::remove_file(shard_dir(&path).join(shard_file_name("b")))
+            .await
+            .unwrap();
+        let partial = ChunkCorpus::load_matching(&path, |file| file.starts_with("a/"))
+            .await
+            .unwrap();
+        assert_eq!(partial.file_count(), 1);
+        assert!(partial.save(&path).await.is_err());
+        assert!(ChunkCorpus::load(&path).await.is_err());
+        assert_eq!(
+            ChunkCorpus::list_files(&path).await.unwrap(),
+            vec!["a/x.rs".to_string(), "b/y.rs".to_string()]
+        );
+    }
+
+    /// Synthetic large corpus: a filtered load opens only the shard holding the kept files.
+    #[tokio::test]
+    async fn filtered_load_of_large_corpus_opens_one_shard() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("corpus.json");
+        let body = "let value = compute(input);\n".repeat(20);
+        let mut corpus = ChunkCorpus::new();
+        for dir in 0..200 {
+            for file in 0..10 {
+                let name = format!("crate{dir}/src/file{file}.rs");
+                let chunks = (0..5)
+                    .map(|i| chunk(&name, i * 20 + 1, i * 20 + 20, &body))
+                    .collect();
+                corpus.set_file_chunks(name, chunks);
+            }
+        }
+        corpus.save(&path).await.unwrap();
+        assert_eq!(ChunkCorpus::load(&path).await.unwrap().file_count(), 2_000);
+
+        // Remove every other shard: a load that opened one of them would fail.
+        let kept = shard_file_name(&shard_key("crate7/src/file0.rs"));
+        let mut entries = tokio::fs::read_dir(shard_dir(&path)).await.unwrap();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            if entry.file_name().to_str() != Some(kept.as_str()) {
+                tokio::fs::remove_file(entry.path()).await.unwrap();
+            }
+        }
+
+        let filtered = ChunkCorpus::load_matching(&path, |file| file.starts_with("crate7/"))
+            .await
+            .unwrap();
+        assert_eq!(filtered.file_count(), 10);
+        assert!(filtered
+            .files()
+            .keys()
+            .all(|file| file.starts_with("crate7/src/")));
+        assert!(ChunkCorpus::load(&path).await.is_err());
     }
 }
@@ -0,0 +1,263 @@
+//! Explain how a vector index disagrees with the chunk corpus it was built from.
+
+use crate::corpus::parse_chunk_id;
+use crate::error::Result;
+use crate::store::{fnv1a64, PersistedEntry, VectorStore};
+use crate::templates::EmbeddingTemplates;
+use crate::ChunkCorpus;
+use context_code_chunker::CodeChunk;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Drifted chunk ids of one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FileDrift {
+    pub file: String,
+    /// In the corpus, but without a vector
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unembedded: Vec<String>,
+    /// Vectors whose chunk is no longer in the corpus
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub orphaned: Vec<String>,
+    /// Vectors whose length differs from the index dimension
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dimension_mismatch: Vec<String>,
+    /// Vectors embedded from a document the chunk no longer renders to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stale: Vec<String>,
+}
+
+impl FileDrift {
+    fn new(file: &str) -> Self {
+        Self {
+            file: file.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Drifted chunk counts, summed over every file.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+pub struct DriftTotals {
+    pub unembedded: usize,
+    pub orphaned: usize,
+    pub dimension_mismatch: usize,
+    pub stale: usize,
+}
+
+impl DriftTotals {
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.unembedded + self.orphaned + self.dimension_mismatch + self.stale
+    }
+}
+
+/// Result of [`reconcile`]: where the index and the corpus disagree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DriftReport {
+    pub dimension: usize,
+    pub corpus_chunks: usize,
+    pub index_chunks: usize,
+    pub totals: DriftTotals,
+    /// Only files with drift, sorted by path
+    pub files: Vec<FileDrift>,
+    /// Whether stored document hashes were compared; `false` when the index was built with a
+    /// token budget that cannot be reproduced without the model tokenizer
+    pub stale_checked: bool,
+}
+
+impl DriftReport {
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.totals.total() == 0
+    }
+}
+
+/// Compare a loaded store with `corpus`.
+///
+/// The store only keeps vectors whose chunk is in its own corpus and whose length matches its
+/// dimension, so against the corpus it was loaded with this reports unembedded and stale
+/// chunks; use [`reconcile_index_file`] to also see orphaned and misshapen vectors.
+#[must_use]
+pub fn reconcile(corpus: &ChunkCorpus, store: &VectorStore) -> DriftReport {
+    let entries: Vec<PersistedEntry> = store
+        .stored_chunks()
+        .map(|stored| PersistedEntry {
+            id: stored.id.clone(),
+            vector_len: stored.vector.len(),
+            doc_hash: stored.doc_hash,
+        })
+        .collect();
+    build_report(corpus, store.dimension(), entries, true, |chunk| {
+        store.doc_hash_of(chunk).ok()
+    })
+}
+
+/// Compare the persisted `index.json` at `index_path` with `corpus`, without loading the
+/// embedding model. Document hashes are checked against the templates in the index's
+/// `meta.json`.
+pub async fn reconcile_index_file(corpus: &ChunkCorpus, index_path: &Path) -> Result<DriftReport> {
+    let (dimension, entries) = VectorStore::load_persisted_entries(index_path).await?;
+    let templates: EmbeddingTemplates = VectorStore::load_persisted_templates(index_path).await;
+    let stale_checked = templates.max_tokens.is_none();
+    Ok(build_report(
+        corpus,
+        dimension,
+        entries,
+        stale_checked,
+        |chunk| {
+            templates
+                .render_doc_chunk_with_tokenizer(chunk, None)
+                .ok()
+                .map(|(doc, _)| fnv1a64(doc.as_bytes()))
+        },
+    ))
+}
+
+fn build_report(
+    corpus: &ChunkCorpus,
+    dimension: usize,
+    entries: Vec<PersistedEntry>,
+    stale_checked: bool,
+    doc_hash: impl Fn(&CodeChunk) -> Option<u64>,
+) -> DriftReport {
+    let mut files: BTreeMap<String, FileDrift> = BTreeMap::new();
+    let mut indexed: HashSet<String> = HashSet::with_capacity(entries.len());
+    let index_chunks = entries.len();
+
+    for entry in entries {
+        let Some(chunk) = corpus.get_chunk(&entry.id) else {
+            let file =
+                parse_chunk_id(&entry.id).map_or_else(|| entry.id.clone(), |(file, ..)| file);
+            file_drift(&mut files, &file).orphaned.push(entry.id);
+            continue;
+        };
+        indexed.insert(entry.id.clone());
+        if entry.vector_len != dimension {
+            file_drift(&mut files, &chunk.file_path)
+                .dimension_mismatch
+                .push(entry.id);
+        } else if stale_checked
+            && entry.doc_hash != 0
+            && doc_hash(chunk).is_some_and(|hash| hash != entry.doc_hash)
+        {
+            file_drift(&mut files, &chunk.file_path)
+                .stale
+                .push(entry.id);
+        }
+    }
+
+    let mut corpus_chunks = 0usize;
+    for (file, chunks) in corpus.files() {
+        for chunk in chunks {
+            corpus_chunks += 1;
+            let id = format!(
+                "{}:{}:{}",
+                chunk.file_path, chunk.start_line, chunk.end_line
+            );
+            if !indexed.contains(&id) {
+                file_drift(&mut files, file).unembedded.push(id);
+            }
+        }
+    }
+
+    let mut totals = DriftTotals::default();
+    let files: Vec<FileDrift> = files
+        .into_values()
+        .map(|mut file| {
+            file.orphaned.sort();
+            file.dimension_mismatch.sort();
+            file.stale.sort();
+            totals.unembedded += file.unembedded.len();
+            totals.orphaned += file.orphaned.len();
+            totals.dimension_mismatch += file.dimension_mismatch.len();
+            totals.stale += file.stale.len();
+            file
+        })
+        .collect();
+
+    DriftReport {
+        dimension,
+        corpus_chunks,
+        index_chunks,
+        totals,
+        files,
+        stale_checked,
+    }
+}
+
+fn file_drift<'a>(files: &'a mut BTreeMap<String, FileDrift>, file: &str) -> &'a mut FileDrift {
+    files
+        .entry(file.to_string())
+        .or_insert_with(|| FileDrift::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_code_chunker::ChunkMetadata;
+
+    fn chunk(file: &str, start: usize, end: usize, text: &str) -> CodeChunk {
+        CodeChunk::new(
+            file.to_string(),
+            start,
+            end,
+            text.to_string(),
+            ChunkMetadata::default(),
+        )
+    }
+
+    fn entry(id: &str, vector_len: usize, doc_hash: u64) -> PersistedEntry {
+        PersistedEntry {
+            id: id.to_string(),
+            vector_len,
+            doc_hash,
+        }
+    }
+
+    #[test]
+    fn build_report_classifies_every_kind_of_drift() {
+        let mut corpus = ChunkCorpus::new();
+        corpus.set_file_chunks(
+            "a.rs".to_string(),
+            vec![chunk("a.rs", 1, 2, "alpha"), chunk("a.rs", 4, 5, "beta")],
+        );
+        corpus.set_file_chunks("c.rs".to_string(), vec![chunk("c.rs", 1, 3, "gamma")]);
+        corpus.set_file_chunks("d.rs".to_string(), vec![chunk("d.rs", 1, 1, "delta")]);
+        let hash = |chunk: &CodeChunk| Some(fnv1a64(chunk.content.as_bytes()));
+
+        let entries = vec![
+            entry("a.rs:1:2", 4, fnv1a64(b"alpha")),
+            entry("a.rs:4:5", 4, fnv1a64(b"old beta")),
+            entry("b.rs:1:1", 4, 7),
+            entry("d.rs:1:1", 3, 0),
+        ];
+        let report = build_report(&corpus, 4, entries, true, hash);
+
+        assert_eq!(report.corpus_chunks, 4);
+        assert_eq!(report.index_chunks, 4);
+        assert_eq!(
+            report.totals,
+            DriftTotals {
+                unembedded: 1,
+                orphaned: 1,
+                dimension_mismatch: 1,
+                stale: 1,
+            }
+        );
+        let files: Vec<&str> = report.files.iter().map(|f| f.file.as_str()).collect();
+        assert_eq!(files, vec!["a.rs", "b.rs", "c.rs", "d.rs"]);
+        assert_eq!(report.files[0].stale, vec!["a.rs:4:5"]);
+        assert_eq!(report.files[1].orphaned, vec!["b.rs:1:1"]);
+        assert_eq!(report.files[2].unembedded, vec!["c.rs:1:3"]);
+        assert_eq!(report.files[3].dimension_mismatch, vec!["d.rs:1:1"]);
+
+        let entries = vec![entry("a.rs:4:5", 4, fnv1a64(b"old beta"))];
+        let report = build_report(&corpus, 4, entries, false, hash);
+        assert_eq!(report.totals.stale, 0);
+        assert_eq!(report.totals.unembedded, 3);
+    }
+}
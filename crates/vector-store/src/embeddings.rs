@@ -1,4 +1,6 @@
 use crate::error::{Result, VectorStoreError};
+use crate::query_cache::QueryEmbeddingCache;
+use crate::templates::TokenCounter;
 use ndarray::{Array, Axis, Dimension, Ix2, Ix3};
 use once_cell::sync::OnceCell;
 use ort::execution_providers::{
@@ -8,7 +10,7 @@ use ort::session::{builder::GraphOptimizationLevel, Input, Session, SessionInput
 use ort::tensor::TensorElementType;
 use ort::value::{DynTensor, Tensor};
 use ort::Error as OrtError;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::env;
 use std::fmt::Display;
@@ -16,6 +18,7 @@ use std::path::{Component, Path, PathBuf};
 #[cfg(test)]
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
+use std::time::Instant;
 use tokenizers::{Encoding, PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
 use tokio::task::spawn_blocking;
 
@@ -38,13 +41,17 @@ impl EmbeddingMode {
             ))),
         }
     }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Stub => "stub",
+        }
+    }
 }
 
 pub fn current_embedding_mode_id() -> Result<&'static str> {
-    match EmbeddingMode::from_env()? {
-        EmbeddingMode::Fast => Ok("fast"),
-        EmbeddingMode::Stub => Ok("stub"),
-    }
+    Ok(EmbeddingMode::from_env()?.as_str())
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -122,12 +129,47 @@ struct ModelAssets {
     tokenizer_path: PathBuf,
 }
 
+/// Execution provider an embedding backend runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingDevice {
+    Cuda,
+    Cpu,
+    /// Hash embeddings from `CONTEXT_FINDER_EMBEDDING_MODE=stub`; no model runs
+    Stub,
+}
+
+impl EmbeddingDevice {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Cuda => "cuda",
+            Self::Cpu => "cpu",
+            Self::Stub => "stub",
+        }
+    }
+}
+
+/// One short text embedded with a model, recording where it actually ran.
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct EmbeddingProbe {
+    pub model_id: String,
+    pub device: EmbeddingDevice,
+    pub dimension: usize,
+    /// Session creation time; near zero when the model was already loaded in this process
+    pub load_ms: u64,
+    /// Wall time of the probe embedding itself
+    pub embed_ms: u64,
+}
+
 struct OrtBackend {
     session: Mutex<Session>,
     tokenizer: Tokenizer,
+    token_counter: Arc<ModelTokenCounter>,
     max_length: usize,
     max_batch: usize,
     dimension: usize,
+    device: EmbeddingDevice,
 }
 
 #[derive(Clone)]
@@ -135,6 +177,8 @@ struct StubBackend {
     dimension: usize,
     #[cfg(test)]
     batch_calls: Arc<AtomicUsize>,
+    #[cfg(test)]
+    embedded_texts: Arc<AtomicUsize>,
 }
 
 impl StubBackend {
@@ -149,12 +193,18 @@ impl StubBackend {
             dimension,
             #[cfg(test)]
             batch_calls: Arc::new(AtomicUsize::new(0)),
+            #[cfg(test)]
+            embedded_texts: Arc::new(AtomicUsize::new(0)),
         }
     }
 
     fn embed_batch(&self, texts: &[String]) -> Vec<Vec<f32>> {
         #[cfg(test)]
-        self.batch_calls.fetch_add(1, Ordering::Relaxed);
+        {
+            self.batch_calls.fetch_add(1, Ordering::Relaxed);
+            self.embedded_texts
+                .fetch_add(texts.len(), Ordering::Relaxed);
+        }
         texts
             .iter()
             .map(|text| stub_embed(text, self.dimension))
@@ -165,6 +215,11 @@ impl StubBackend {
     fn batch_calls(&self) -> usize {
         self.batch_calls.load(Ordering::Relaxed)
     }
+
+    #[cfg(test)]
+    fn embedded_texts(&self) -> usize {
+        self.embedded_texts.load(Ordering::Relaxed)
+    }
 }
 
 static BACKENDS: OnceCell<Mutex<BackendCache<OrtBackend>>> = OnceCell::new();
@@ -536,7 +591,7 @@ impl OrtBackend {
     fn new(spec: &ModelSpec, model_dir: &Path) -> Result<Self> {
         let assets = spec.assets_in(model_dir);
         if !assets.model_path.exists() || !assets.tokenizer_path.exists() {
-            return Err(VectorStoreError::EmbeddingError(format!(
+            return Err(VectorStoreError::ModelMissing(format!(
                 "Model files for '{}' are missing. Expected ONNX at {} and tokenizer at {}. Run `context-finder install-models` to download them into ./models (or set CONTEXT_FINDER_MODEL_DIR).",
                 spec.id,
                 assets.model_path.display(),
@@ -546,6 +601,7 @@ impl OrtBackend {
 
         let mut tokenizer = Tokenizer::from_file(&assets.tokenizer_path)
             .map_err(|e| VectorStoreError::EmbeddingError(format!("Tokenizer load failed: {e}")))?;
+        let token_counter = Arc::new(ModelTokenCounter::new(tokenizer.clone())?);
         tokenizer.with_padding(Some(PaddingParams {
             strategy: PaddingStrategy::BatchLongest,
             ..PaddingParams::default()
@@ -559,7 +615,7 @@ impl OrtBackend {
                 VectorStoreError::EmbeddingError(format!("Tokenizer truncation failed: {e}"))
             })?;
 
-        let providers = build_execution_providers()?;
+        let (providers, device) = build_execution_providers()?;
         let session_builder =
             Session::builder().map_err(|e| VectorStoreError::EmbeddingError(format!("{e}")))?;
         let session = session_builder
@@ -579,19 +635,22 @@ impl OrtBackend {
             })?;
 
         log::info!(
-            "Loaded ONNX model '{}' (dim {}, max_length {}, batch {})",
+            "Loaded ONNX model '{}' (dim {}, max_length {}, batch {}, device {:?})",
             spec.id,
             spec.dimension,
             spec.max_length,
-            spec.max_batch
+            spec.max_batch,
+            device
         );
 
         Ok(Self {
             session: Mutex::new(session),
             tokenizer,
+            token_counter,
             max_length: spec.max_length,
             max_batch: spec.max_batch,
             dimension: spec.dimension,
+            device,
         })
     }
 
@@ -877,10 +936,16 @@ fn allow_cpu_fallback() -> bool {
         .unwrap_or(false)
 }
 
-fn build_execution_providers() -> Result<Vec<ExecutionProviderDispatch>> {
+fn build_execution_providers() -> Result<(Vec<ExecutionProviderDispatch>, EmbeddingDevice)> {
+    let cpu = || {
+        (
+            vec![CPUExecutionProvider::default().build()],
+            EmbeddingDevice::Cpu,
+        )
+    };
     if is_cuda_disabled() {
         if allow_cpu_fallback() {
-            return Ok(vec![CPUExecutionProvider::default().build()]);
+            return Ok(cpu());
         }
         return Err(VectorStoreError::EmbeddingError(
             "CUDA is disabled (ORT_DISABLE_CUDA/ORT_USE_CUDA), but CPU fallback is not allowed. Set CONTEXT_FINDER_ALLOW_CPU=1 to allow CPU embeddings."
@@ -889,11 +954,11 @@ fn build_execution_providers() -> Result<Vec<ExecutionProviderDispatch>> {
     }
 
     match build_cuda_ep() {
-        Ok(cuda) => Ok(vec![cuda]),
+        Ok(cuda) => Ok((vec![cuda], EmbeddingDevice::Cuda)),
         Err(err) => {
             if allow_cpu_fallback() {
                 log::warn!("CUDA EP unavailable, falling back to CPU embeddings: {err}");
-                Ok(vec![CPUExecutionProvider::default().build()])
+                Ok(cpu())
             } else {
                 Err(VectorStoreError::EmbeddingError(format!(
                     "CUDA execution provider is unavailable: {err}. Run with CONTEXT_FINDER_ALLOW_CPU=1 to allow CPU embeddings."
@@ -1000,6 +1065,55 @@ fn zero_tensor(shape: &ndarray::IxDyn, input: &Input) -> Result<DynTensor> {
     Ok(tensor)
 }
 
+/// The model tokenizer without padding or truncation, so counts reflect the full text.
+struct ModelTokenCounter {
+    tokenizer: Tokenizer,
+}
+
+impl ModelTokenCounter {
+    fn new(mut tokenizer: Tokenizer) -> Result<Self> {
+        tokenizer.with_padding(None);
+        tokenizer.with_truncation(None).map_err(|e| {
+            VectorStoreError::EmbeddingError(format!("Tokenizer truncation failed: {e}"))
+        })?;
+        Ok(Self { tokenizer })
+    }
+
+    fn encode(&self, text: &str) -> Result<Encoding> {
+        self.tokenizer
+            .encode(text, true)
+            .map_err(|e| VectorStoreError::EmbeddingError(format!("Tokenization failed: {e}")))
+    }
+}
+
+impl TokenCounter for ModelTokenCounter {
+    fn count_tokens(&self, text: &str) -> Result<usize> {
+        Ok(self.encode(text)?.len())
+    }
+
+    fn token_prefix_len(&self, text: &str, max_tokens: usize) -> Result<usize> {
+        let encoding = self.encode(text)?;
+        if encoding.len() <= max_tokens {
+            return Ok(text.len());
+        }
+        let special = encoding
+            .get_special_tokens_mask()
+            .iter()
+            .filter(|&&mask| mask == 1)
+            .count();
+        let keep = max_tokens.saturating_sub(special);
+        Ok(encoding
+            .get_offsets()
+            .iter()
+            .zip(encoding.get_special_tokens_mask())
+            .filter(|(_, mask)| **mask == 0)
+            .take(keep)
+            .map(|(&(_, end), _)| end)
+            .max()
+            .unwrap_or(0))
+    }
+}
+
 /// Embedding model for semantic search running on ONNX Runtime CUDA
 pub struct EmbeddingModel {
     backend: EmbeddingBackend,
@@ -1119,6 +1233,25 @@ impl EmbeddingModel {
         self.dimension
     }
 
+    #[must_use]
+    pub fn device(&self) -> EmbeddingDevice {
+        match &self.backend {
+            EmbeddingBackend::Ort(backend) => backend.device,
+            EmbeddingBackend::Stub(_) => EmbeddingDevice::Stub,
+        }
+    }
+
+    /// The model tokenizer as a [`TokenCounter`]; `None` for the stub embedder.
+    #[must_use]
+    pub fn token_counter(&self) -> Option<Arc<dyn TokenCounter>> {
+        match &self.backend {
+            EmbeddingBackend::Ort(backend) => {
+                Some(Arc::clone(&backend.token_counter) as Arc<dyn TokenCounter>)
+            }
+            EmbeddingBackend::Stub(_) => None,
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn stub_batch_calls(&self) -> Option<usize> {
         match &self.backend {
@@ -1127,6 +1260,14 @@ impl EmbeddingModel {
         }
     }
 
+    #[cfg(test)]
+    pub(crate) fn stub_embedded_texts(&self) -> Option<usize> {
+        match &self.backend {
+            EmbeddingBackend::Stub(stub) => Some(stub.embedded_texts()),
+            EmbeddingBackend::Ort(_) => None,
+        }
+    }
+
     pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
         let mut embeddings = self.embed_batch(vec![text]).await?;
         embeddings
@@ -1169,11 +1310,46 @@ impl EmbeddingModel {
     }
 }
 
+/// Loads `model_id` (or reuses its cached session) and embeds one short text, reporting the
+/// execution provider that ran it. Fails like indexing would, e.g. when CUDA is unavailable
+/// and CPU fallback is not allowed.
+pub async fn probe_embedding(model_id: &str) -> Result<EmbeddingProbe> {
+    let started = Instant::now();
+    let model = EmbeddingModel::new_for_model(model_id)?;
+    let load_ms = started.elapsed().as_millis() as u64;
+
+    let started = Instant::now();
+    let embedding = model.embed("fn probe() -> bool { true }").await?;
+    let embed_ms = started.elapsed().as_millis() as u64;
+    if embedding.len() != model.dimension() {
+        return Err(VectorStoreError::EmbeddingError(format!(
+            "Probe embedding has {} dimensions, expected {}",
+            embedding.len(),
+            model.dimension()
+        )));
+    }
+
+    Ok(EmbeddingProbe {
+        model_id: ModelId::from_raw(model_id).to_string(),
+        device: model.device(),
+        dimension: embedding.len(),
+        load_ms,
+        embed_ms,
+    })
+}
+
 /// Returns the normalized embedding model id for the current process environment.
 pub fn current_model_id() -> Result<String> {
     Ok(ModelId::from_env().to_string())
 }
 
+/// Identity of the backend that embeds for `model_id`: aliases of one model (e.g.
+/// `bge-small-en-v1.5` and `bge-small`) share it, and so produce identical vectors.
+#[must_use]
+pub fn embedding_backend_id(model_id: &str) -> String {
+    ModelId::from_raw(model_id).to_string()
+}
+
 /// A multi-model registry that keeps a small LRU of hot ONNX Runtime sessions.
 ///
 /// Design goals:
@@ -1185,6 +1361,7 @@ pub struct ModelRegistry {
     mode: EmbeddingMode,
     specs: HashMap<ModelId, ModelSpec>,
     cache: Mutex<BackendCache<OrtBackend>>,
+    query_embeddings: Option<QueryEmbeddingCache>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -1198,7 +1375,8 @@ impl ModelRegistry {
         let mode = EmbeddingMode::from_env()?;
         let dir = model_dir();
         let capacity = backend_cache_capacity_from_env();
-        Self::new(dir, mode, capacity)
+        Ok(Self::new(dir, mode, capacity)?
+            .with_query_embedding_cache(QueryEmbeddingCache::shared()))
     }
 
     pub fn new_fast(model_dir: PathBuf, capacity: usize) -> Result<Self> {
@@ -1216,9 +1394,18 @@ impl ModelRegistry {
             mode,
             specs,
             cache: Mutex::new(BackendCache::new(capacity)),
+            query_embeddings: None,
         })
     }
 
+    /// Serve [`Self::embed_query`] from `cache` when the query was embedded before; `None`
+    /// disables it. [`Self::from_env`] uses [`QueryEmbeddingCache::shared`].
+    #[must_use]
+    pub fn with_query_embedding_cache(mut self, cache: Option<QueryEmbeddingCache>) -> Self {
+        self.query_embeddings = cache;
+        self
+    }
+
     #[must_use]
     pub fn available_models(&self) -> Vec<String> {
         let mut ids: Vec<String> = self.specs.keys().map(ToString::to_string).collect();
@@ -1239,6 +1426,24 @@ impl ModelRegistry {
             .ok_or_else(|| VectorStoreError::EmbeddingError("Empty embedding result".to_string()))
     }
 
+    /// Embed a rendered query, reusing the query embedding cache. The flag is `true` when the
+    /// vector came from the cache and the model was not run.
+    pub async fn embed_query(&self, model_id: &str, text: &str) -> Result<(Vec<f32>, bool)> {
+        let key = ModelId::from_raw(model_id).to_string();
+        if let Some(vector) = self
+            .query_embeddings
+            .as_ref()
+            .and_then(|cache| cache.get(self.mode.as_str(), &key, text))
+        {
+            return Ok((vector, true));
+        }
+        let vector = self.embed(model_id, text).await?;
+        if let Some(cache) = self.query_embeddings.as_ref() {
+            cache.insert(self.mode.as_str(), &key, text, vector.clone());
+        }
+        Ok((vector, false))
+    }
+
     pub async fn embed_batch(&self, model_id: &str, texts: Vec<&str>) -> Result<Vec<Vec<f32>>> {
         if texts.is_empty() {
             return Ok(vec![]);
@@ -1526,6 +1731,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn embedding_probe_serializes_device_in_snake_case() {
+        let probe = EmbeddingProbe {
+            model_id: "bge-small".to_string(),
+            device: EmbeddingDevice::Cuda,
+            dimension: 384,
+            load_ms: 812,
+            embed_ms: 3,
+        };
+        let value = serde_json::to_value(&probe).expect("serialize probe");
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "model_id": "bge-small",
+                "device": "cuda",
+                "dimension": 384,
+                "load_ms": 812,
+                "embed_ms": 3
+            })
+        );
+        let back: EmbeddingProbe = serde_json::from_value(value).expect("deserialize probe");
+        assert_eq!(back.device, EmbeddingDevice::Cuda);
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires ONNX model download"]
+    async fn probe_reports_the_device_that_ran_the_embedding() {
+        let model_id = current_model_id().unwrap();
+        let probe = probe_embedding(&model_id).await.unwrap();
+        assert_eq!(probe.model_id, model_id);
+        assert!(matches!(
+            probe.device,
+            EmbeddingDevice::Cuda | EmbeddingDevice::Cpu
+        ));
+        assert!(probe.dimension > 0);
+    }
+
     #[tokio::test]
     #[ignore = "Requires ONNX model download and CUDA runtime"]
     async fn test_embed_single() {
@@ -1734,4 +1976,37 @@ mod tests {
         assert_eq!(out[1], stub_embed("world", 12));
         assert_eq!(out[2], stub_embed("again", 8));
     }
+
+    #[tokio::test]
+    async fn query_embedding_cache_is_not_shared_across_backends() {
+        let dir = TempDir::new("context_finder_query_cache_backends");
+        let manifest = r#"
+{
+  "schema_version": 1,
+  "models": [
+    {"id":"m1","dimension":8,"max_length":16,"max_batch":2,"assets":[]}
+  ]
+}
+"#;
+        std::fs::write(dir.path.join("manifest.json"), manifest).expect("write manifest");
+        let cache = QueryEmbeddingCache::new(8);
+
+        let stub = ModelRegistry::new_stub(dir.path.clone())
+            .expect("create stub registry")
+            .with_query_embedding_cache(Some(cache.clone()));
+        let (vector, cached) = stub.embed_query("m1", "hello").await.expect("stub embed");
+        assert!(!cached);
+        assert_eq!(vector, stub_embed("hello", 8));
+        assert!(matches!(
+            stub.embed_query("m1", "hello").await,
+            Ok((_, true))
+        ));
+
+        // The ONNX backend has no model assets here, so only a (wrong) cache hit could succeed.
+        let fast = ModelRegistry::new_fast(dir.path.clone(), 1)
+            .expect("create fast registry")
+            .with_query_embedding_cache(Some(cache.clone()));
+        assert!(fast.embed_query("m1", "hello").await.is_err());
+        assert_eq!(cache.hits(), 1);
+    }
 }
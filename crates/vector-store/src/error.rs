@@ -1,3 +1,4 @@
+use context_protocol::ErrorCode;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, VectorStoreError>;
@@ -7,6 +8,10 @@ pub enum VectorStoreError {
     #[error("Embedding error: {0}")]
     EmbeddingError(String),
 
+    /// Model files are not on disk (same message prefix as [`Self::EmbeddingError`])
+    #[error("Embedding error: {0}")]
+    ModelMissing(String),
+
     #[error("Index error: {0}")]
     IndexError(String),
 
@@ -25,3 +30,20 @@ pub enum VectorStoreError {
     #[error("{0}")]
     Other(String),
 }
+
+impl VectorStoreError {
+    #[must_use]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::IoError(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                ErrorCode::FileNotFound
+            }
+            Self::IoError(_) => ErrorCode::FilesystemError,
+            Self::SerializationError(_) | Self::InvalidDimension { .. } => ErrorCode::IndexCorrupt,
+            Self::ModelMissing(_) => ErrorCode::ModelMissing,
+            Self::EmbeddingError(_) | Self::IndexError(_) | Self::NotFound(_) | Self::Other(_) => {
+                ErrorCode::Internal
+            }
+        }
+    }
+}
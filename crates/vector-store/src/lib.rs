@@ -51,31 +51,41 @@
 //! ```
 
 mod corpus;
+mod drift;
 mod embedding_cache;
 mod embeddings;
 mod error;
 mod graph_node_store;
 mod hnsw_index;
+mod query_cache;
 mod store;
 mod templates;
 mod types;
 
-pub use corpus::{corpus_path_for_project_root, ChunkCorpus, CHUNK_CORPUS_SCHEMA_VERSION};
-pub use embeddings::current_model_id;
+pub use corpus::{
+    corpus_path_for_project_root, corpus_size_bytes, is_supported_corpus_schema_version,
+    ChunkCorpus, CHUNK_CORPUS_SCHEMA_VERSION,
+};
+pub use drift::{reconcile, reconcile_index_file, DriftReport, DriftTotals, FileDrift};
 pub use embeddings::model_dir;
 pub use embeddings::EmbeddingModel;
+pub use embeddings::{current_model_id, embedding_backend_id};
+pub use embeddings::{probe_embedding, EmbeddingDevice, EmbeddingProbe};
 pub use embeddings::{EmbedRequest, ModelRegistry};
 pub use error::{Result, VectorStoreError};
 pub use graph_node_store::{
     GraphNodeDoc, GraphNodeHit, GraphNodeStore, GraphNodeStoreMeta, GRAPH_NODE_STORE_SCHEMA_VERSION,
 };
+pub use query_cache::QueryEmbeddingCache;
 pub use store::VectorIndex;
-pub use store::VectorStore;
+pub use store::{is_supported_index_schema_version, VECTOR_STORE_SCHEMA_VERSION};
+pub use store::{ReplaceStats, VectorStore};
 pub use templates::{
     classify_document_kind, classify_path_kind, DocumentKind, EmbeddingTemplates,
-    GraphNodeTemplates, QueryKind, QueryTemplates, EMBEDDING_TEMPLATES_SCHEMA_VERSION,
+    GraphNodeTemplates, QueryKind, QueryTemplates, RenderedPreview, RenderedQuery, RenderedText,
+    TokenCounter, EMBEDDING_TEMPLATES_SCHEMA_VERSION,
 };
-pub use types::{SearchResult, StoredChunk};
+pub use types::{ChunkFreshness, SearchResult, StoredChunk};
 
 // Re-export code chunker types for convenience
 pub use context_code_chunker::{ChunkMetadata, ChunkType, CodeChunk};
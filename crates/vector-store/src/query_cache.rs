@@ -0,0 +1,135 @@
+use crate::types::SearchResult;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+/// `(normalized query, limit)`
+pub(crate) type QueryKey = (String, usize);
+
+/// `(embedding mode, model id, rendered query)`
+type EmbeddingKey = (String, String, String);
+
+const DEFAULT_QUERY_EMBEDDING_CACHE_CAPACITY: usize = 256;
+
+/// Bounded map that evicts the least recently used entry and counts lookups it served.
+pub(crate) struct Lru<K, V> {
+    capacity: usize,
+    entries: HashMap<K, V>,
+    lru: VecDeque<K>,
+    hits: u64,
+}
+
+/// In-memory LRU of search results for a [`crate::VectorStore`]. The owning store clears it on
+/// every mutation, so entries always describe the current contents.
+pub(crate) type QueryCache = Lru<QueryKey, Vec<SearchResult>>;
+
+impl QueryCache {
+    /// Collapse whitespace so trivially different spellings of a query share an entry.
+    pub(crate) fn key(query: &str, limit: usize) -> QueryKey {
+        (
+            query.split_whitespace().collect::<Vec<_>>().join(" "),
+            limit,
+        )
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Lru<K, V> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            lru: VecDeque::new(),
+            hits: 0,
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.entries.get(key)?.clone();
+        self.touch(key);
+        self.hits += 1;
+        Some(value)
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        self.entries.insert(key.clone(), value);
+        self.touch(&key);
+        while self.entries.len() > self.capacity {
+            let Some(victim) = self.lru.pop_back() else {
+                break;
+            };
+            self.entries.remove(&victim);
+        }
+    }
+
+    /// Drop every entry; the hit counter is kept.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.lru.clear();
+    }
+
+    pub(crate) const fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    fn touch(&mut self, key: &K) {
+        if let Some(pos) = self.lru.iter().position(|k| k == key) {
+            self.lru.remove(pos);
+        }
+        self.lru.push_front(key.clone());
+    }
+}
+
+/// LRU of query vectors keyed by `(embedding mode, model id, rendered query)`. A vector depends
+/// only on the backend that produced it (ONNX or stub), the model and the text, so unlike the per-store result cache it survives index mutations and can
+/// be shared by every store and [`crate::ModelRegistry`] in the process; clones share entries.
+#[derive(Clone)]
+pub struct QueryEmbeddingCache {
+    inner: Arc<Mutex<Lru<EmbeddingKey, Vec<f32>>>>,
+}
+
+impl QueryEmbeddingCache {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Lru::new(capacity))),
+        }
+    }
+
+    /// The process-wide cache, sized by `CONTEXT_FINDER_QUERY_EMBEDDING_CACHE` (default 256
+    /// queries); `None` when that is `0`.
+    #[must_use]
+    pub fn shared() -> Option<Self> {
+        static SHARED: OnceLock<Option<QueryEmbeddingCache>> = OnceLock::new();
+        SHARED
+            .get_or_init(|| {
+                let capacity = std::env::var("CONTEXT_FINDER_QUERY_EMBEDDING_CACHE")
+                    .ok()
+                    .and_then(|v| v.trim().parse::<usize>().ok())
+                    .unwrap_or(DEFAULT_QUERY_EMBEDDING_CACHE_CAPACITY);
+                (capacity > 0).then(|| Self::new(capacity))
+            })
+            .clone()
+    }
+
+    /// Lookups served from the cache since it was created.
+    #[must_use]
+    pub fn hits(&self) -> u64 {
+        self.lock().hits()
+    }
+
+    pub(crate) fn get(&self, mode: &str, model_id: &str, text: &str) -> Option<Vec<f32>> {
+        self.lock().get(&Self::key(mode, model_id, text))
+    }
+
+    pub(crate) fn insert(&self, mode: &str, model_id: &str, text: &str, vector: Vec<f32>) {
+        self.lock().insert(Self::key(mode, model_id, text), vector);
+    }
+
+    fn key(mode: &str, model_id: &str, text: &str) -> EmbeddingKey {
+        (mode.to_string(), model_id.to_string(), text.to_string())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru<EmbeddingKey, Vec<f32>>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -1,16 +1,20 @@
+use crate::drift::DriftReport;
 use crate::embedding_cache::EmbeddingCache;
-use crate::embeddings::EmbeddingModel;
+use crate::embeddings::{embedding_backend_id, EmbeddingModel};
 use crate::error::Result;
 use crate::hnsw_index::HnswIndex;
-use crate::templates::{DocumentTemplates, EmbeddingTemplates};
+use crate::query_cache::{QueryCache, QueryEmbeddingCache, QueryKey};
+use crate::templates::{DocumentTemplates, EmbeddingTemplates, QueryKind, TokenCounter};
 use crate::types::{SearchResult, StoredChunk};
 use crate::ChunkCorpus;
 use context_code_chunker::CodeChunk;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 
 pub struct VectorStore {
     chunks: HashMap<String, StoredChunk>,
@@ -21,10 +25,19 @@ pub struct VectorStore {
     id_map: HashMap<usize, String>, // numeric_id -> string_id mapping
     reverse_id_map: HashMap<String, usize>, // string_id -> numeric_id mapping
     model_id: String,
+    /// Embedding cache key: aliases of one model share cached vectors
+    backend_id: String,
     embedding_mode: String,
     dimension: usize,
     templates: EmbeddingTemplates,
+    token_counter: Option<Arc<dyn TokenCounter>>,
+    /// Documents cut to `templates.max_tokens` since this store was opened
+    docs_token_truncated: AtomicUsize,
+    /// Documents run through the embedding model (cache misses) since this store was opened
+    docs_embedded: AtomicUsize,
     embedding_cache: EmbeddingCache,
+    query_cache: Option<Mutex<QueryCache>>,
+    query_embeddings: Option<QueryEmbeddingCache>,
 }
 
 /// Read-only view of a persisted `VectorStore` that can perform similarity search given query
@@ -36,7 +49,13 @@ pub struct VectorIndex {
     dimension: usize,
 }
 
-const VECTOR_STORE_SCHEMA_VERSION: u32 = 3;
+pub const VECTOR_STORE_SCHEMA_VERSION: u32 = 3;
+
+/// Whether `index.json` files with this `schema_version` can still be loaded (current + legacy v1).
+#[must_use]
+pub const fn is_supported_index_schema_version(version: u32) -> bool {
+    version == VECTOR_STORE_SCHEMA_VERSION || version == 1
+}
 
 #[derive(Serialize, Deserialize)]
 struct PersistedVectorStoreV3 {
@@ -54,6 +73,22 @@ struct PersistedVectorEntryV3 {
     doc_hash: u64,
 }
 
+/// How [`VectorStore::replace_file_chunks`] obtained the vectors of the new chunks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplaceStats {
+    /// Chunks that went through the embedder (or the embedding cache)
+    pub embedded: usize,
+    /// Chunks that kept the vector of an identical previous chunk of the same file
+    pub reused: usize,
+}
+
+/// A persisted vector as read from `index.json`, without attaching its chunk.
+pub(crate) struct PersistedEntry {
+    pub id: String,
+    pub vector_len: usize,
+    pub doc_hash: u64,
+}
+
 struct PersistedStoreData {
     chunks: HashMap<String, StoredChunk>,
     id_map_raw: HashMap<usize, String>,
@@ -173,6 +208,7 @@ impl VectorIndex {
                     chunk: stored.chunk.clone(),
                     score,
                     id: stored.id.clone(),
+                    freshness: None,
                 });
             }
         }
@@ -225,8 +261,9 @@ impl VectorStore {
         templates: EmbeddingTemplates,
     ) -> Result<Self> {
         log::info!("Initializing VectorStore at {}", path.as_ref().display());
-        templates.validate()?;
         let embedder = EmbeddingModel::new_for_model(model_id)?;
+        let token_counter = embedder.token_counter();
+        templates.validate_with_tokenizer(token_counter.as_deref())?;
         let embedding_mode = crate::embeddings::current_embedding_mode_id()?.to_string();
         let dimension = embedder.dimension();
         let index = HnswIndex::new(dimension);
@@ -240,14 +277,71 @@ impl VectorStore {
             id_map: HashMap::new(),
             reverse_id_map: HashMap::new(),
             model_id: model_id.to_string(),
+            backend_id: embedding_backend_id(model_id),
             embedding_mode,
             dimension,
             templates,
+            token_counter,
+            docs_token_truncated: AtomicUsize::new(0),
+            docs_embedded: AtomicUsize::new(0),
             embedding_cache: EmbeddingCache::for_store_path(path.as_ref()),
+            query_cache: None,
+            query_embeddings: None,
         })
     }
 
     /// Add chunks with batch embedding for efficiency
+    /// Keep the results of up to `capacity` recent `(query, limit)` searches in memory, so a
+    /// repeated query skips embedding and the index scan. Any mutation clears the cache;
+    /// `capacity == 0` disables it.
+    #[must_use]
+    pub fn with_query_cache(mut self, capacity: usize) -> Self {
+        self.query_cache = (capacity > 0).then(|| Mutex::new(QueryCache::new(capacity)));
+        self
+    }
+
+    /// Reuse query vectors from `cache` (keyed by model id and query text), so a repeated query
+    /// skips the embedding model even after the store changed; `None` disables it.
+    #[must_use]
+    pub fn with_query_embedding_cache(mut self, cache: Option<QueryEmbeddingCache>) -> Self {
+        self.query_embeddings = cache;
+        self
+    }
+
+    /// Searches served from the query cache since it was enabled.
+    #[must_use]
+    pub fn query_cache_hits(&self) -> u64 {
+        self.query_cache.as_ref().map_or(0, |cache| {
+            cache.lock().unwrap_or_else(PoisonError::into_inner).hits()
+        })
+    }
+
+    fn cached_results(&self, key: &QueryKey) -> Option<Vec<SearchResult>> {
+        self.query_cache
+            .as_ref()?
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+    }
+
+    fn cache_results(&self, key: QueryKey, results: &[SearchResult]) {
+        if let Some(cache) = self.query_cache.as_ref() {
+            cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(key, results.to_vec());
+        }
+    }
+
+    fn invalidate_query_cache(&mut self) {
+        if let Some(cache) = self.query_cache.as_mut() {
+            cache
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clear();
+        }
+    }
+
     pub async fn add_chunks(&mut self, chunks: Vec<CodeChunk>) -> Result<()> {
         if chunks.is_empty() {
             return Ok(());
@@ -255,17 +349,109 @@ impl VectorStore {
 
         log::info!("Adding {} chunks to store", chunks.len());
 
-        // Render embedding input with templates (deterministic + bounded)
+        let (rendered, doc_hashes) = self.render_doc_chunks(&chunks)?;
+        let vectors = self.embed_rendered_docs(&rendered, &doc_hashes).await?;
+        self.insert_chunks(chunks, vectors, doc_hashes)?;
+
+        log::info!("Successfully added chunks. Total: {}", self.chunks.len());
+        Ok(())
+    }
+
+    /// Replace all chunks of `file_path` with `chunks`. A new chunk whose rendered document
+    /// hash matches one of the file's previous chunks keeps that chunk's vector, even if its
+    /// lines moved; only new or edited chunks are embedded.
+    pub async fn replace_file_chunks(
+        &mut self,
+        file_path: &str,
+        chunks: Vec<CodeChunk>,
+    ) -> Result<ReplaceStats> {
+        // doc_hash 0 marks entries persisted before hashes were recorded.
+        let previous: HashMap<u64, Vec<f32>> = self
+            .chunks
+            .values()
+            .filter(|stored| stored.chunk.file_path == file_path && stored.doc_hash != 0)
+            .map(|stored| (stored.doc_hash, stored.vector.clone()))
+            .collect();
+        self.remove_chunks_for_file(file_path);
+        if chunks.is_empty() {
+            return Ok(ReplaceStats::default());
+        }
+
+        let (rendered, doc_hashes) = self.render_doc_chunks(&chunks)?;
+        let mut vectors: Vec<Option<Vec<f32>>> = doc_hashes
+            .iter()
+            .map(|doc_hash| previous.get(doc_hash).cloned())
+            .collect();
+        let miss_indices: Vec<usize> = (0..vectors.len())
+            .filter(|&idx| vectors[idx].is_none())
+            .collect();
+        let miss_docs: Vec<String> = miss_indices
+            .iter()
+            .map(|&idx| rendered[idx].clone())
+            .collect();
+        let miss_hashes: Vec<u64> = miss_indices.iter().map(|&idx| doc_hashes[idx]).collect();
+        let embedded = self.embed_rendered_docs(&miss_docs, &miss_hashes).await?;
+        for (idx, vector) in miss_indices.iter().zip(embedded) {
+            vectors[*idx] = Some(vector);
+        }
+        let vectors = vectors.into_iter().flatten().collect();
+
+        let stats = ReplaceStats {
+            embedded: miss_indices.len(),
+            reused: chunks.len() - miss_indices.len(),
+        };
+        log::debug!(
+            "Replaced chunks of {file_path}: {} embedded, {} reused",
+            stats.embedded,
+            stats.reused
+        );
+        self.insert_chunks(chunks, vectors, doc_hashes)?;
+        Ok(stats)
+    }
+
+    /// Render embedding input with templates (deterministic + bounded) and hash it.
+    fn render_doc_chunks(&self, chunks: &[CodeChunk]) -> Result<(Vec<String>, Vec<u64>)> {
         let mut rendered = Vec::with_capacity(chunks.len());
         let mut doc_hashes = Vec::with_capacity(chunks.len());
-        for chunk in &chunks {
-            let doc = self.templates.render_doc_chunk(chunk)?;
+        for chunk in chunks {
+            let doc = self.render_doc_chunk(chunk)?;
             doc_hashes.push(fnv1a64(doc.as_bytes()));
             rendered.push(doc);
         }
-        let vectors = self.embed_rendered_docs(&rendered, &doc_hashes).await?;
+        Ok((rendered, doc_hashes))
+    }
 
-        // Store chunks with their vectors
+    fn render_doc_chunk(&self, chunk: &CodeChunk) -> Result<String> {
+        let (doc, token_truncated) = self
+            .templates
+            .render_doc_chunk_with_tokenizer(chunk, self.token_counter.as_deref())?;
+        if token_truncated {
+            self.docs_token_truncated.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(doc)
+    }
+
+    /// Documents cut to the templates' `max_tokens` since this store was created or loaded.
+    #[must_use]
+    pub fn docs_token_truncated(&self) -> usize {
+        self.docs_token_truncated.load(Ordering::Relaxed)
+    }
+
+    /// Documents embedded by the model since this store was created or loaded; vectors taken
+    /// from the embedding cache (including ones another alias of the model computed) don't count.
+    #[must_use]
+    pub fn docs_embedded(&self) -> usize {
+        self.docs_embedded.load(Ordering::Relaxed)
+    }
+
+    /// Store chunks with their vectors
+    fn insert_chunks(
+        &mut self,
+        chunks: Vec<CodeChunk>,
+        vectors: Vec<Vec<f32>>,
+        doc_hashes: Vec<u64>,
+    ) -> Result<()> {
+        self.invalidate_query_cache();
         for ((chunk, vector), doc_hash) in chunks
             .into_iter()
             .zip(vectors.into_iter())
@@ -299,8 +485,6 @@ impl VectorStore {
             };
             self.chunks.insert(id, stored);
         }
-
-        log::info!("Successfully added chunks. Total: {}", self.chunks.len());
         Ok(())
     }
 
@@ -323,7 +507,7 @@ impl VectorStore {
                 .embedding_cache
                 .get_vector(
                     &self.embedding_mode,
-                    &self.model_id,
+                    &self.backend_id,
                     template_hash,
                     *doc_hash,
                     self.dimension,
@@ -338,6 +522,8 @@ impl VectorStore {
         }
 
         if !miss_indices.is_empty() {
+            self.docs_embedded
+                .fetch_add(miss_indices.len(), Ordering::Relaxed);
             let embedded = self.embedder.embed_batch(miss_texts).await?;
             for (idx, vector) in miss_indices.into_iter().zip(embedded.into_iter()) {
                 let doc_hash = doc_hashes[idx];
@@ -345,7 +531,7 @@ impl VectorStore {
                     .embedding_cache
                     .put_vector(
                         &self.embedding_mode,
-                        &self.model_id,
+                        &self.backend_id,
                         template_hash,
                         doc_hash,
                         &vector,
@@ -369,6 +555,33 @@ impl VectorStore {
         Ok(out)
     }
 
+    /// Embed `text` as a query: rendered with the conceptual query template, then run through
+    /// this store's model, so the vector is comparable with the stored chunk vectors.
+    pub async fn embed_query(&self, text: &str) -> Result<Vec<f32>> {
+        if text.trim().is_empty() {
+            return Err(crate::VectorStoreError::EmbeddingError(
+                "Cannot embed an empty query".to_string(),
+            ));
+        }
+        let rendered = self.templates.render_query(QueryKind::Conceptual, text)?;
+        self.embedder.embed(&rendered).await
+    }
+
+    /// Embed `texts` verbatim with this store's model, one vector per text in input order.
+    pub async fn embed_texts(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Err(crate::VectorStoreError::EmbeddingError(
+                "No texts to embed".to_string(),
+            ));
+        }
+        if let Some(idx) = texts.iter().position(|text| text.trim().is_empty()) {
+            return Err(crate::VectorStoreError::EmbeddingError(format!(
+                "Cannot embed an empty text (index {idx})"
+            )));
+        }
+        self.embedder.embed_batch(texts.to_vec()).await
+    }
+
     /// Search for similar chunks using semantic similarity
     pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
         self.search_with_embedding_text(query, limit).await
@@ -382,26 +595,20 @@ impl VectorStore {
         limit: usize,
     ) -> Result<Vec<SearchResult>> {
         log::debug!("Searching semantic index (limit: {limit})");
-
-        // Embed query
-        let query_vector = self.embedder.embed(embedding_text).await?;
-
-        // Search HNSW index
-        let neighbors = self.index.search(&query_vector, limit)?;
-
-        // Convert to SearchResult
-        let mut results = Vec::new();
-        for (chunk_id, score) in neighbors {
-            // Find chunk by numeric id
-            if let Some(stored) = self.find_chunk_by_numeric_id(chunk_id) {
-                results.push(SearchResult {
-                    chunk: stored.chunk.clone(),
-                    score,
-                    id: stored.id.clone(),
-                });
-            }
+        let key = QueryCache::key(embedding_text, limit);
+        if let Some(results) = self.cached_results(&key) {
+            log::debug!("Query cache hit ({} results)", results.len());
+            return Ok(results);
         }
 
+        let query_vector = self
+            .embed_query_texts(&[embedding_text])
+            .await?
+            .pop()
+            .unwrap_or_default();
+        let results = self.results_for_vector(&query_vector, limit)?;
+        self.cache_results(key, &results);
+
         log::debug!("Found {} results", results.len());
         Ok(results)
     }
@@ -433,35 +640,113 @@ impl VectorStore {
             limit
         );
 
-        // Batch embed all queries (much more efficient)
-        let query_vectors = self.embedder.embed_batch(embedding_texts.to_vec()).await?;
-
-        // Search for each query vector
-        let mut all_results = Vec::with_capacity(embedding_texts.len());
-        for (i, query_vector) in query_vectors.iter().enumerate() {
-            log::debug!("Searching query {}/{}", i + 1, embedding_texts.len());
-
-            let neighbors = self.index.search(query_vector, limit)?;
+        let keys: Vec<QueryKey> = embedding_texts
+            .iter()
+            .map(|text| QueryCache::key(text, limit))
+            .collect();
+        let mut all_results: Vec<Option<Vec<SearchResult>>> =
+            keys.iter().map(|key| self.cached_results(key)).collect();
+        let miss_indices: Vec<usize> = (0..all_results.len())
+            .filter(|&idx| all_results[idx].is_none())
+            .collect();
 
-            let mut results = Vec::new();
-            for (chunk_id, score) in neighbors {
-                if let Some(stored) = self.find_chunk_by_numeric_id(chunk_id) {
-                    results.push(SearchResult {
-                        chunk: stored.chunk.clone(),
-                        score,
-                        id: stored.id.clone(),
-                    });
+        // Queries that normalize to the same key share one embedding and one index scan.
+        let mut unique: Vec<usize> = Vec::new();
+        for &idx in &miss_indices {
+            if !unique.iter().any(|&first| keys[first] == keys[idx]) {
+                unique.push(idx);
+            }
+        }
+        if !unique.is_empty() {
+            let unique_texts: Vec<&str> = unique.iter().map(|&idx| embedding_texts[idx]).collect();
+            let query_vectors = self.embed_query_texts(&unique_texts).await?;
+
+            for (&first, query_vector) in unique.iter().zip(query_vectors.iter()) {
+                log::debug!("Searching query {}/{}", first + 1, embedding_texts.len());
+                let results = self.results_for_vector(query_vector, limit)?;
+                self.cache_results(keys[first].clone(), &results);
+                for &idx in &miss_indices {
+                    if keys[idx] == keys[first] {
+                        all_results[idx] = Some(results.clone());
+                    }
                 }
             }
-
-            all_results.push(results);
         }
 
         log::debug!(
-            "Batch search completed: {} queries processed",
-            embedding_texts.len()
+            "Batch search completed: {} queries processed ({} from cache)",
+            embedding_texts.len(),
+            embedding_texts.len() - miss_indices.len()
         );
-        Ok(all_results)
+        Ok(all_results
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect())
+    }
+
+    /// Query vectors for `texts` in input order: duplicates are embedded once, and vectors in the
+    /// query embedding cache skip the model entirely.
+    async fn embed_query_texts(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let cache = self.query_embeddings.as_ref();
+        let mut vectors: Vec<Option<Vec<f32>>> = texts
+            .iter()
+            .map(|text| {
+                cache.and_then(|cache| cache.get(&self.embedding_mode, &self.model_id, text))
+            })
+            .collect();
+        let mut pending: Vec<&str> = Vec::new();
+        for (text, vector) in texts.iter().zip(&vectors) {
+            if vector.is_none() && !pending.contains(text) {
+                pending.push(text);
+            }
+        }
+
+        if !pending.is_empty() {
+            let embedded = self.embedder.embed_batch(pending.clone()).await?;
+            for (text, vector) in pending.iter().zip(embedded) {
+                for (slot, _) in vectors
+                    .iter_mut()
+                    .zip(texts)
+                    .filter(|(slot, candidate)| slot.is_none() && *candidate == text)
+                {
+                    *slot = Some(vector.clone());
+                }
+                if let Some(cache) = cache {
+                    cache.insert(&self.embedding_mode, &self.model_id, text, vector);
+                }
+            }
+        }
+
+        vectors
+            .into_iter()
+            .map(|vector| {
+                vector.ok_or_else(|| {
+                    crate::VectorStoreError::EmbeddingError(
+                        "Missing query embedding after cache/embed".to_string(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn results_for_vector(&self, query_vector: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
+        // Search HNSW index
+        let neighbors = self.index.search(query_vector, limit)?;
+
+        // Convert to SearchResult
+        let mut results = Vec::new();
+        for (chunk_id, score) in neighbors {
+            // Find chunk by numeric id
+            if let Some(stored) = self.find_chunk_by_numeric_id(chunk_id) {
+                results.push(SearchResult {
+                    chunk: stored.chunk.clone(),
+                    score,
+                    id: stored.id.clone(),
+                    freshness: None,
+                });
+            }
+        }
+        Ok(results)
     }
 
     /// Find chunk by numeric ID using `id_map`
@@ -497,6 +782,12 @@ impl VectorStore {
         self.chunks.is_empty()
     }
 
+    /// Embedding dimension of the stored vectors
+    #[must_use]
+    pub const fn dimension(&self) -> usize {
+        self.dimension
+    }
+
     /// Remove all chunks belonging to a single file path (relative path, e.g. `src/lib.rs`).
     /// Returns the number of removed chunks.
     pub fn remove_chunks_for_file(&mut self, file_path: &str) -> usize {
@@ -521,6 +812,18 @@ impl VectorStore {
         removed
     }
 
+    /// Files that [`Self::purge_missing_files`] would remove, in path order.
+    #[must_use]
+    pub fn missing_files(&self, live_files: &HashSet<String>) -> Vec<String> {
+        let missing: BTreeSet<&String> = self
+            .chunks
+            .values()
+            .map(|stored| &stored.chunk.file_path)
+            .filter(|file| !live_files.contains(*file))
+            .collect();
+        missing.into_iter().cloned().collect()
+    }
+
     /// Drop chunks whose `chunk.file_path` is not present in `live_files`.
     /// Returns the number of removed chunks.
     pub fn purge_missing_files(&mut self, live_files: &HashSet<String>) -> usize {
@@ -545,10 +848,49 @@ impl VectorStore {
         removed
     }
 
+    /// Every stored chunk, in no particular order.
+    pub(crate) fn stored_chunks(&self) -> impl Iterator<Item = &StoredChunk> {
+        self.chunks.values()
+    }
+
+    /// Hash of the document `chunk` renders to with this store's templates; comparable to
+    /// [`StoredChunk::doc_hash`].
+    pub(crate) fn doc_hash_of(&self, chunk: &CodeChunk) -> Result<u64> {
+        let (doc, _) = self
+            .templates
+            .render_doc_chunk_with_tokenizer(chunk, self.token_counter.as_deref())?;
+        Ok(fnv1a64(doc.as_bytes()))
+    }
+
+    /// Bring the files listed in `report` back in line with `corpus`: files still in the
+    /// corpus get their chunks replaced (unchanged chunks keep their vectors), files gone from
+    /// the corpus lose their chunks. Does not save.
+    pub async fn repair_drift(
+        &mut self,
+        corpus: &ChunkCorpus,
+        report: &DriftReport,
+    ) -> Result<ReplaceStats> {
+        let mut stats = ReplaceStats::default();
+        for file in &report.files {
+            match corpus.files().get(&file.file) {
+                Some(chunks) => {
+                    let replaced = self.replace_file_chunks(&file.file, chunks.clone()).await?;
+                    stats.embedded += replaced.embedded;
+                    stats.reused += replaced.reused;
+                }
+                None => {
+                    self.remove_chunks_for_file(&file.file);
+                }
+            }
+        }
+        Ok(stats)
+    }
+
     fn remove_chunk_id(&mut self, id: &str) -> bool {
         if self.chunks.remove(id).is_none() {
             return false;
         }
+        self.invalidate_query_cache();
 
         if let Some(numeric_id) = self.reverse_id_map.remove(id) {
             self.id_map.remove(&numeric_id);
@@ -592,7 +934,7 @@ impl VectorStore {
         self.save_meta().await?;
         if let Some(max_bytes) = embed_cache_max_bytes_from_env() {
             self.embedding_cache
-                .prune_model_dir(&self.embedding_mode, &self.model_id, max_bytes)
+                .prune_model_dir(&self.embedding_mode, &self.backend_id, max_bytes)
                 .await;
         }
         log::info!("VectorStore saved successfully");
@@ -654,8 +996,9 @@ impl VectorStore {
         let embedder = EmbeddingModel::new_for_model(model_id)?;
         let embedding_mode = crate::embeddings::current_embedding_mode_id()?.to_string();
         let dimension = embedder.dimension();
+        let token_counter = embedder.token_counter();
         let templates = {
-            templates.validate()?;
+            templates.validate_with_tokenizer(token_counter.as_deref())?;
             templates
         };
 
@@ -669,6 +1012,7 @@ impl VectorStore {
             }
         }
 
+        let chunks = Self::drop_misshapen_vectors(path, chunks, stored_dimension);
         let (id_map, reverse_id_map) = Self::repair_id_maps(&chunks, id_map_raw);
 
         let next_id: usize = id_map.keys().max().copied().map_or(stored_next_id, |id| {
@@ -695,10 +1039,16 @@ impl VectorStore {
             id_map,
             reverse_id_map,
             model_id: model_id.to_string(),
+            backend_id: embedding_backend_id(model_id),
             embedding_mode,
             dimension,
             templates,
+            token_counter,
+            docs_token_truncated: AtomicUsize::new(0),
+            docs_embedded: AtomicUsize::new(0),
             embedding_cache: EmbeddingCache::for_store_path(path),
+            query_cache: None,
+            query_embeddings: None,
         };
 
         store
@@ -802,6 +1152,77 @@ impl VectorStore {
         })
     }
 
+    /// Drop vectors whose length differs from the dimension the store was saved with; they
+    /// cannot go into the HNSW index and are re-embedded by [`Self::repair_drift`].
+    fn drop_misshapen_vectors(
+        path: &Path,
+        mut chunks: HashMap<String, StoredChunk>,
+        stored_dimension: usize,
+    ) -> HashMap<String, StoredChunk> {
+        let before = chunks.len();
+        chunks.retain(|_, stored| stored.vector.len() == stored_dimension);
+        let dropped = before - chunks.len();
+        if dropped > 0 {
+            log::warn!(
+                "VectorStore {}: dropped {dropped} vectors whose length differs from dimension {stored_dimension}",
+                path.display()
+            );
+        }
+        chunks
+    }
+
+    /// Read the persisted vectors of `index.json` as `(id, vector length, doc_hash)` entries,
+    /// together with the stored dimension. Nothing is embedded and the corpus is not consulted.
+    pub(crate) async fn load_persisted_entries(
+        path: &Path,
+    ) -> Result<(usize, Vec<PersistedEntry>)> {
+        let data = tokio::fs::read_to_string(path).await?;
+        let save_data: serde_json::Value = serde_json::from_str(&data)?;
+        let schema_version = save_data
+            .get("schema_version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(1);
+
+        if schema_version == u64::from(VECTOR_STORE_SCHEMA_VERSION) {
+            let persisted: PersistedVectorStoreV3 = serde_json::from_value(save_data)?;
+            let entries = persisted
+                .vectors
+                .into_iter()
+                .map(|(id, entry)| PersistedEntry {
+                    id,
+                    vector_len: entry.vector.len(),
+                    doc_hash: entry.doc_hash,
+                })
+                .collect();
+            Ok((persisted.dimension, entries))
+        } else if schema_version == 1 {
+            let data = Self::load_v1_store_data(&save_data)?;
+            let mut entries: Vec<PersistedEntry> = data
+                .chunks
+                .into_iter()
+                .map(|(id, stored)| PersistedEntry {
+                    id,
+                    vector_len: stored.vector.len(),
+                    doc_hash: stored.doc_hash,
+                })
+                .collect();
+            entries.sort_by(|a, b| a.id.cmp(&b.id));
+            Ok((data.stored_dimension, entries))
+        } else {
+            Err(crate::VectorStoreError::EmbeddingError(format!(
+                "Unsupported VectorStore schema_version {schema_version}"
+            )))
+        }
+    }
+
+    /// Templates recorded in the store's `meta.json` (defaults when absent).
+    pub(crate) async fn load_persisted_templates(path: &Path) -> EmbeddingTemplates {
+        load_meta_info(path)
+            .await
+            .map(|meta| meta.templates)
+            .unwrap_or_default()
+    }
+
     fn repair_id_maps(
         chunks: &HashMap<String, StoredChunk>,
         id_map_raw: HashMap<usize, String>,
@@ -894,7 +1315,7 @@ impl VectorStore {
         let mut rendered = Vec::with_capacity(entries.len());
         let mut doc_hashes = Vec::with_capacity(entries.len());
         for (_, chunk) in &entries {
-            let doc = self.templates.render_doc_chunk(chunk)?;
+            let doc = self.render_doc_chunk(chunk)?;
             doc_hashes.push(fnv1a64(doc.as_bytes()));
             rendered.push(doc);
         }
@@ -942,6 +1363,7 @@ impl VectorStore {
             embedding_mode: self.embedding_mode.clone(),
             dimension: self.dimension,
             max_chars: self.templates.max_chars,
+            max_tokens: self.templates.max_tokens,
             doc_templates: self.templates.document.clone(),
             doc_template_hash: self.templates.doc_template_hash(),
         };
@@ -974,6 +1396,8 @@ struct StoreMetaV2 {
     embedding_mode: String,
     dimension: usize,
     max_chars: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<usize>,
     doc_templates: DocumentTemplates,
     doc_template_hash: u64,
 }
@@ -1012,6 +1436,7 @@ async fn load_meta_info(store_path: &Path) -> Option<StoreMetaInfo> {
                 if v2.schema_version == STORE_META_SCHEMA_VERSION {
                     let templates = EmbeddingTemplates {
                         max_chars: v2.max_chars,
+                        max_tokens: v2.max_tokens,
                         document: v2.doc_templates,
                         ..EmbeddingTemplates::default()
                     };
@@ -1039,7 +1464,7 @@ async fn load_meta_info(store_path: &Path) -> Option<StoreMetaInfo> {
     }
 }
 
-fn fnv1a64(bytes: &[u8]) -> u64 {
+pub(crate) fn fnv1a64(bytes: &[u8]) -> u64 {
     const OFFSET: u64 = 14_695_981_039_346_656_037;
     const PRIME: u64 = 1_099_511_628_211;
     let mut hash = OFFSET;
@@ -1094,6 +1519,42 @@ mod tests {
         assert!(!results.is_empty());
     }
 
+    #[tokio::test]
+    async fn embed_texts_is_deterministic_for_identical_texts() {
+        std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+        let temp_dir = TempDir::new().unwrap();
+        let store =
+            VectorStore::new_for_model(temp_dir.path().join("store.json"), "bge-small").unwrap();
+
+        let vectors = store
+            .embed_texts(&["fn parse_config() {}", "fn parse_config() {}"])
+            .await
+            .unwrap();
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[0].len(), store.dimension());
+        let similarity = EmbeddingModel::cosine_similarity(&vectors[0], &vectors[1]);
+        assert!(similarity > 0.999, "similarity {similarity}");
+
+        let query = store.embed_query("parse config").await.unwrap();
+        assert_eq!(query.len(), store.dimension());
+    }
+
+    #[tokio::test]
+    async fn embed_api_rejects_empty_input() {
+        std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+        let temp_dir = TempDir::new().unwrap();
+        let store =
+            VectorStore::new_for_model(temp_dir.path().join("store.json"), "bge-small").unwrap();
+
+        assert!(store.embed_texts(&[]).await.is_err());
+        assert!(store.embed_texts(&["fn a() {}", "  "]).await.is_err());
+        assert!(store.embed_query("").await.is_err());
+
+        let vectors = store.embed_texts(&["fn a() {}"]).await.unwrap();
+        assert_eq!(vectors.len(), 1);
+        assert_eq!(vectors[0].len(), store.dimension());
+    }
+
     #[tokio::test]
     async fn add_chunks_uses_embedding_cache_in_stub_mode() {
         std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
@@ -1132,4 +1593,170 @@ mod tests {
             "expected cache hit to avoid embedding call"
         );
     }
+
+    #[tokio::test]
+    async fn replace_file_chunks_only_embeds_edited_chunks() {
+        std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+        std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODEL", "bge-small");
+
+        let tmp = TempDir::new().unwrap();
+        let context_dir = tmp.path().join(".context-finder");
+        let store_path = context_dir.join("indexes/bge-small/index.json");
+        tokio::fs::create_dir_all(store_path.parent().unwrap())
+            .await
+            .unwrap();
+
+        let mut store = VectorStore::new_for_model(&store_path, "bge-small").unwrap();
+        store
+            .add_chunks(vec![
+                create_test_chunk("lib.rs", "fn alpha() {}", 1),
+                create_test_chunk("lib.rs", "fn beta() {}", 20),
+                create_test_chunk("lib.rs", "fn gamma() {}", 40),
+                create_test_chunk("other.rs", "fn alpha() {}", 1),
+            ])
+            .await
+            .unwrap();
+        let gamma_vector = store.get_chunk("lib.rs:40:50").unwrap().vector.clone();
+
+        // Reuse must not depend on the on-disk embedding cache.
+        tokio::fs::remove_dir_all(context_dir.join("cache"))
+            .await
+            .unwrap();
+
+        // `beta` is edited and grows by five lines, which shifts `gamma` down.
+        let stats = store
+            .replace_file_chunks(
+                "lib.rs",
+                vec![
+                    create_test_chunk("lib.rs", "fn alpha() {}", 1),
+                    create_test_chunk("lib.rs", "fn beta() { edited(); }", 20),
+                    create_test_chunk("lib.rs", "fn gamma() {}", 45),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            ReplaceStats {
+                embedded: 1,
+                reused: 2
+            }
+        );
+        assert_eq!(store.embedder.stub_batch_calls(), Some(2));
+        assert_eq!(
+            store.chunk_ids(),
+            vec![
+                "lib.rs:1:11",
+                "lib.rs:20:30",
+                "lib.rs:45:55",
+                "other.rs:1:11"
+            ]
+        );
+        assert!(store.get_chunk("lib.rs:40:50").is_none());
+        assert_eq!(
+            store.get_chunk("lib.rs:45:55").unwrap().vector,
+            gamma_vector
+        );
+    }
+
+    #[tokio::test]
+    async fn query_cache_serves_repeated_queries_until_mutation() {
+        std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+        std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODEL", "bge-small");
+
+        let tmp = TempDir::new().unwrap();
+        let store_path = tmp
+            .path()
+            .join(".context-finder/indexes/bge-small/index.json");
+        tokio::fs::create_dir_all(store_path.parent().unwrap())
+            .await
+            .unwrap();
+
+        let mut store = VectorStore::new_for_model(&store_path, "bge-small")
+            .unwrap()
+            .with_query_cache(8);
+        store
+            .add_chunks(vec![
+                create_test_chunk("lib.rs", "fn alpha() {}", 1),
+                create_test_chunk("lib.rs", "fn beta() {}", 20),
+            ])
+            .await
+            .unwrap();
+
+        let first = store.search("alpha function", 5).await.unwrap();
+        assert_eq!(store.query_cache_hits(), 0);
+        let second = store.search("  alpha   function ", 5).await.unwrap();
+        assert_eq!(store.query_cache_hits(), 1);
+        let ids = |results: &[SearchResult]| -> Vec<String> {
+            results.iter().map(|result| result.id.clone()).collect()
+        };
+        assert_eq!(ids(&first), ids(&second));
+
+        let batch = store
+            .search_batch(&["alpha function", "beta function"], 5)
+            .await
+            .unwrap();
+        assert_eq!(store.query_cache_hits(), 2);
+        assert_eq!(ids(&batch[0]), ids(&first));
+
+        store
+            .add_chunks(vec![create_test_chunk("other.rs", "fn gamma() {}", 1)])
+            .await
+            .unwrap();
+        let after = store.search("alpha function", 5).await.unwrap();
+        assert_eq!(store.query_cache_hits(), 2);
+        assert_eq!(after.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn search_batch_embeds_identical_queries_once() {
+        std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODE", "stub");
+        std::env::set_var("CONTEXT_FINDER_EMBEDDING_MODEL", "bge-small");
+
+        let tmp = TempDir::new().unwrap();
+        let store_path = tmp
+            .path()
+            .join(".context-finder/indexes/bge-small/index.json");
+        tokio::fs::create_dir_all(store_path.parent().unwrap())
+            .await
+            .unwrap();
+
+        let embeddings = QueryEmbeddingCache::new(8);
+        let mut store = VectorStore::new_for_model(&store_path, "bge-small")
+            .unwrap()
+            .with_query_embedding_cache(Some(embeddings.clone()));
+        store
+            .add_chunks(vec![
+                create_test_chunk("lib.rs", "fn alpha() {}", 1),
+                create_test_chunk("lib.rs", "fn beta() {}", 20),
+            ])
+            .await
+            .unwrap();
+        let calls = store.embedder.stub_batch_calls().unwrap();
+        let texts = store.embedder.stub_embedded_texts().unwrap();
+
+        let batch = store
+            .search_batch(&["alpha function", "alpha function"], 5)
+            .await
+            .unwrap();
+        assert_eq!(store.embedder.stub_batch_calls(), Some(calls + 1));
+        assert_eq!(store.embedder.stub_embedded_texts(), Some(texts + 1));
+        let ids = |results: &[SearchResult]| -> Vec<String> {
+            results.iter().map(|result| result.id.clone()).collect()
+        };
+        assert_eq!(batch.len(), 2);
+        assert!(!batch[0].is_empty());
+        assert_eq!(ids(&batch[0]), ids(&batch[1]));
+
+        // Query vectors outlive index mutations: only the new chunk is embedded.
+        store
+            .add_chunks(vec![create_test_chunk("other.rs", "fn gamma() {}", 1)])
+            .await
+            .unwrap();
+        let texts = store.embedder.stub_embedded_texts().unwrap();
+        let after = store.search("alpha function", 5).await.unwrap();
+        assert_eq!(store.embedder.stub_embedded_texts(), Some(texts));
+        assert_eq!(embeddings.hits(), 1);
+        assert_eq!(after.len(), 3);
+    }
 }
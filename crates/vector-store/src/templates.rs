@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 
 pub const EMBEDDING_TEMPLATES_SCHEMA_VERSION: u32 = 1;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum QueryKind {
     Identifier,
     Path,
@@ -12,6 +13,8 @@ pub enum QueryKind {
 }
 
 impl QueryKind {
+    pub const ALL: [Self; 3] = [Self::Identifier, Self::Path, Self::Conceptual];
+
     #[must_use]
     pub const fn as_str(self) -> &'static str {
         match self {
@@ -22,7 +25,7 @@ impl QueryKind {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DocumentKind {
     Code,
@@ -51,6 +54,9 @@ pub struct EmbeddingTemplates {
     pub schema_version: u32,
     #[serde(default = "default_max_chars")]
     pub max_chars: usize,
+    /// Cut rendered documents to this many model tokens (needs the model tokenizer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
     #[serde(default)]
     pub query: QueryTemplates,
     #[serde(default)]
@@ -72,6 +78,7 @@ impl Default for EmbeddingTemplates {
         Self {
             schema_version: EMBEDDING_TEMPLATES_SCHEMA_VERSION,
             max_chars: default_max_chars(),
+            max_tokens: None,
             query: QueryTemplates::default(),
             document: DocumentTemplates::default(),
             graph_node: GraphNodeTemplates::default(),
@@ -139,6 +146,52 @@ fn default_template() -> String {
     "{text}".to_string()
 }
 
+/// Counts tokens the way the embedding model does, for `max_tokens` truncation.
+pub trait TokenCounter: Send + Sync {
+    /// Tokens the model sees for `text`, special tokens included.
+    fn count_tokens(&self, text: &str) -> Result<usize>;
+
+    /// Byte length of the longest prefix of `text` that ends on a token boundary and
+    /// counts at most `max_tokens` (special tokens included).
+    fn token_prefix_len(&self, text: &str, max_tokens: usize) -> Result<usize>;
+}
+
+/// Sections of a rendered document given up, in order, to fit `max_tokens`.
+#[derive(Clone, Copy, Default)]
+struct DocTrim {
+    drop_imports: bool,
+    drop_related_paths: bool,
+    text_len: Option<usize>,
+}
+
+/// One template render, cut to `max_chars` like the text that gets embedded.
+#[derive(Clone, Debug, Serialize, schemars::JsonSchema)]
+pub struct RenderedText {
+    pub text: String,
+    /// Length of the full render before the cut (bytes, the unit `max_chars` is enforced in)
+    pub chars: usize,
+    pub truncated: bool,
+}
+
+#[derive(Clone, Debug, Serialize, schemars::JsonSchema)]
+pub struct RenderedQuery {
+    pub kind: QueryKind,
+    #[serde(flatten)]
+    pub rendered: RenderedText,
+}
+
+/// What the templates turn a chunk (and optionally a query) into, see
+/// [`EmbeddingTemplates::preview`].
+#[derive(Clone, Debug, Serialize, schemars::JsonSchema)]
+pub struct RenderedPreview {
+    /// Picks the document template (`document.<kind>`, else `document.default`)
+    pub doc_kind: DocumentKind,
+    pub max_chars: usize,
+    pub document: RenderedText,
+    /// The query rendered for every [`QueryKind`]; empty without a query
+    pub queries: Vec<RenderedQuery>,
+}
+
 impl EmbeddingTemplates {
     pub fn validate(&self) -> Result<()> {
         if self.schema_version != EMBEDDING_TEMPLATES_SCHEMA_VERSION {
@@ -155,6 +208,14 @@ impl EmbeddingTemplates {
             )));
         }
 
+        if let Some(max_tokens) = self.max_tokens {
+            if !(16..=32_768).contains(&max_tokens) {
+                return Err(VectorStoreError::EmbeddingError(format!(
+                    "embedding.max_tokens must be in [16, 32768] (got {max_tokens})"
+                )));
+            }
+        }
+
         for template in self.all_templates() {
             validate_template_placeholders(template)?;
         }
@@ -162,6 +223,18 @@ impl EmbeddingTemplates {
         Ok(())
     }
 
+    /// [`Self::validate`] for templates that will render with `tokenizer`: `max_tokens` is
+    /// only usable when the embedding model exposes its tokenizer.
+    pub fn validate_with_tokenizer(&self, tokenizer: Option<&dyn TokenCounter>) -> Result<()> {
+        self.validate()?;
+        if self.max_tokens.is_some() && tokenizer.is_none() {
+            return Err(VectorStoreError::EmbeddingError(
+                "embedding.max_tokens needs the embedding model's tokenizer, which the current embedding mode does not provide".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     #[must_use]
     pub fn doc_template_hash(&self) -> u64 {
         use std::fmt::Write as _;
@@ -173,6 +246,9 @@ impl EmbeddingTemplates {
             "schema_version={}\nmax_chars={}",
             self.schema_version, self.max_chars
         );
+        if let Some(max_tokens) = self.max_tokens {
+            let _ = writeln!(&mut repr, "max_tokens={max_tokens}");
+        }
         let _ = writeln!(&mut repr, "doc.default={}", self.document.default);
         let _ = writeln!(
             &mut repr,
@@ -206,7 +282,51 @@ impl EmbeddingTemplates {
         fnv1a64(repr.as_bytes())
     }
 
+    /// Render `chunk` and, when given, `query` for every query kind without embedding
+    /// anything, reporting how each render compares to `max_chars`.
+    pub fn preview(&self, chunk: &CodeChunk, query: Option<&str>) -> Result<RenderedPreview> {
+        let document = self.bounded(|max_chars| self.render_doc_chunk_within(chunk, max_chars))?;
+        let queries = match query {
+            Some(query) => QueryKind::ALL
+                .into_iter()
+                .map(|kind| {
+                    let rendered =
+                        self.bounded(|max_chars| self.render_query_within(kind, query, max_chars))?;
+                    Ok(RenderedQuery { kind, rendered })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        Ok(RenderedPreview {
+            doc_kind: classify_document_kind(chunk),
+            max_chars: self.max_chars,
+            document,
+            queries,
+        })
+    }
+
+    fn bounded(&self, render: impl Fn(usize) -> Result<String>) -> Result<RenderedText> {
+        let full = render(usize::MAX)?;
+        let chars = full.len();
+        if chars <= self.max_chars {
+            return Ok(RenderedText {
+                text: full,
+                chars,
+                truncated: false,
+            });
+        }
+        Ok(RenderedText {
+            text: render(self.max_chars)?,
+            chars,
+            truncated: true,
+        })
+    }
+
     pub fn render_query(&self, kind: QueryKind, text: &str) -> Result<String> {
+        self.render_query_within(kind, text, self.max_chars)
+    }
+
+    fn render_query_within(&self, kind: QueryKind, text: &str, max_chars: usize) -> Result<String> {
         let template = match kind {
             QueryKind::Identifier => self.query.identifier.as_deref(),
             QueryKind::Path => self.query.path.as_deref(),
@@ -214,7 +334,7 @@ impl EmbeddingTemplates {
         }
         .unwrap_or(self.query.default.as_str());
 
-        render_template(template, self.max_chars, |key| match key {
+        render_template(template, max_chars, |key| match key {
             "text" => Some(text),
             "query_kind" => Some(kind.as_str()),
             _ => None,
@@ -222,6 +342,69 @@ impl EmbeddingTemplates {
     }
 
     pub fn render_doc_chunk(&self, chunk: &CodeChunk) -> Result<String> {
+        self.render_doc_chunk_within(chunk, self.max_chars)
+    }
+
+    /// [`Self::render_doc_chunk`], then fit the document into `max_tokens` (when both it and
+    /// `tokenizer` are set): drop `{imports}`, then `{related_paths}`, then cut `{text}` at a
+    /// token boundary. Returns whether the token budget cut anything.
+    pub fn render_doc_chunk_with_tokenizer(
+        &self,
+        chunk: &CodeChunk,
+        tokenizer: Option<&dyn TokenCounter>,
+    ) -> Result<(String, bool)> {
+        let doc = self.render_doc_chunk(chunk)?;
+        let (Some(max_tokens), Some(tokenizer)) = (self.max_tokens, tokenizer) else {
+            return Ok((doc, false));
+        };
+        if tokenizer.count_tokens(&doc)? <= max_tokens {
+            return Ok((doc, false));
+        }
+
+        let mut trim = DocTrim {
+            drop_imports: true,
+            ..DocTrim::default()
+        };
+        let doc = self.render_doc_chunk_trimmed(chunk, self.max_chars, trim)?;
+        if tokenizer.count_tokens(&doc)? <= max_tokens {
+            return Ok((doc, true));
+        }
+        trim.drop_related_paths = true;
+        let mut doc = self.render_doc_chunk_trimmed(chunk, self.max_chars, trim)?;
+        let mut tokens = tokenizer.count_tokens(&doc)?;
+
+        // Tokenization is close to additive, so cutting `{text}` by the excess converges
+        // in a step or two; every step strictly shortens the text.
+        let mut text_len = chunk.content.len();
+        while tokens > max_tokens && text_len > 0 {
+            let text = &chunk.content[..text_len];
+            let keep = tokenizer
+                .count_tokens(text)?
+                .saturating_sub(tokens - max_tokens);
+            text_len = tokenizer.token_prefix_len(text, keep)?.min(text_len - 1);
+            text_len = utf8_prefix(text, text_len).len();
+            trim.text_len = Some(text_len);
+            doc = self.render_doc_chunk_trimmed(chunk, self.max_chars, trim)?;
+            tokens = tokenizer.count_tokens(&doc)?;
+        }
+        if tokens > max_tokens {
+            // The template alone is over budget.
+            let keep = tokenizer.token_prefix_len(&doc, max_tokens)?;
+            doc.truncate(utf8_prefix(&doc, keep).len());
+        }
+        Ok((doc, true))
+    }
+
+    fn render_doc_chunk_within(&self, chunk: &CodeChunk, max_chars: usize) -> Result<String> {
+        self.render_doc_chunk_trimmed(chunk, max_chars, DocTrim::default())
+    }
+
+    fn render_doc_chunk_trimmed(
+        &self,
+        chunk: &CodeChunk,
+        max_chars: usize,
+        trim: DocTrim,
+    ) -> Result<String> {
         let kind = classify_document_kind(chunk);
         let template = match kind {
             DocumentKind::Code => self.document.code.as_deref(),
@@ -252,15 +435,27 @@ impl EmbeddingTemplates {
         bundle_tags.dedup();
         let bundle_joined = bundle_tags.join(", ");
 
+        let text = trim
+            .text_len
+            .map_or(chunk.content.as_str(), |len| &chunk.content[..len]);
+
         let mut related = chunk.metadata.related_paths.clone();
         related.sort();
         related.dedup();
-        let related_joined = related.join(", ");
+        let related_joined = if trim.drop_related_paths {
+            String::new()
+        } else {
+            related.join(", ")
+        };
 
         let mut imports = chunk.metadata.context_imports.clone();
         imports.sort();
         imports.dedup();
-        let imports_joined = imports.join("\n");
+        let imports_joined = if trim.drop_imports {
+            String::new()
+        } else {
+            imports.join("\n")
+        };
 
         let chunk_id = format!(
             "{}:{}:{}",
@@ -269,8 +464,8 @@ impl EmbeddingTemplates {
         let start_line = chunk.start_line.to_string();
         let end_line = chunk.end_line.to_string();
 
-        render_template(template, self.max_chars, |key| match key {
-            "text" => Some(chunk.content.as_str()),
+        render_template(template, max_chars, |key| match key {
+            "text" => Some(text),
             "path" => Some(chunk.file_path.as_str()),
             "language" => Some(language),
             "chunk_type" => Some(chunk_type),
@@ -572,3 +767,126 @@ fn fnv1a64(bytes: &[u8]) -> u64 {
     }
     hash
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_code_chunker::ChunkMetadata;
+
+    #[test]
+    fn preview_reports_kind_and_truncation() {
+        let mut templates = EmbeddingTemplates {
+            max_chars: 256,
+            ..EmbeddingTemplates::default()
+        };
+        templates.document.code = Some("{path}\n{text}".to_string());
+        templates.query.identifier = Some("symbol: {text}".to_string());
+
+        let chunk = CodeChunk::new(
+            "src/lib.rs".to_string(),
+            1,
+            40,
+            "x".repeat(300),
+            ChunkMetadata::default(),
+        );
+        let preview = templates.preview(&chunk, Some("parse_config")).unwrap();
+
+        assert_eq!(preview.doc_kind, DocumentKind::Code);
+        assert_eq!(preview.max_chars, 256);
+        assert_eq!(preview.document.chars, "src/lib.rs\n".len() + 300);
+        assert!(preview.document.truncated);
+        assert_eq!(
+            preview.document.text,
+            templates.render_doc_chunk(&chunk).unwrap()
+        );
+
+        let kinds: Vec<QueryKind> = preview.queries.iter().map(|q| q.kind).collect();
+        assert_eq!(kinds, QueryKind::ALL);
+        assert_eq!(preview.queries[0].rendered.text, "symbol: parse_config");
+        assert_eq!(preview.queries[1].rendered.text, "parse_config");
+        assert!(!preview.queries[0].rendered.truncated);
+
+        let preview = templates.preview(&chunk, None).unwrap();
+        assert!(preview.queries.is_empty());
+    }
+
+    /// One token per whitespace-separated word, plus `[CLS]`/`[SEP]`.
+    struct WordCounter;
+
+    impl TokenCounter for WordCounter {
+        fn count_tokens(&self, text: &str) -> Result<usize> {
+            Ok(text.split_whitespace().count() + 2)
+        }
+
+        fn token_prefix_len(&self, text: &str, max_tokens: usize) -> Result<usize> {
+            let words = max_tokens.saturating_sub(2);
+            let mut end = 0;
+            for (taken, (start, word)) in text
+                .split_whitespace()
+                .map(|word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+                .enumerate()
+            {
+                if taken == words {
+                    break;
+                }
+                end = start + word.len();
+            }
+            Ok(end)
+        }
+    }
+
+    #[test]
+    fn max_tokens_drops_imports_and_related_paths_before_text() {
+        let templates = EmbeddingTemplates {
+            max_tokens: Some(16),
+            document: DocumentTemplates {
+                code: Some("{text}\n{related_paths}\n{imports}".to_string()),
+                ..DocumentTemplates::default()
+            },
+            ..EmbeddingTemplates::default()
+        };
+        assert!(templates.validate_with_tokenizer(None).is_err());
+        assert!(templates
+            .validate_with_tokenizer(Some(&WordCounter))
+            .is_ok());
+
+        let metadata = ChunkMetadata {
+            context_imports: vec!["use a;".into(), "use b;".into(), "use c;".into()],
+            related_paths: vec!["src/a.rs".into(), "src/b.rs".into()],
+            ..ChunkMetadata::default()
+        };
+        let chunk = CodeChunk::new(
+            "src/lib.rs".to_string(),
+            1,
+            3,
+            "fn main ( ) { run ( ) ; }".to_string(),
+            metadata,
+        );
+
+        let (doc, cut) = templates
+            .render_doc_chunk_with_tokenizer(&chunk, Some(&WordCounter))
+            .unwrap();
+        assert!(cut);
+        assert_eq!(doc, "fn main ( ) { run ( ) ; }\nsrc/a.rs, src/b.rs\n");
+
+        let chunk = CodeChunk::new(
+            "src/lib.rs".to_string(),
+            1,
+            3,
+            "word ".repeat(40),
+            chunk.metadata,
+        );
+        let (doc, cut) = templates
+            .render_doc_chunk_with_tokenizer(&chunk, Some(&WordCounter))
+            .unwrap();
+        assert!(cut);
+        assert_eq!(WordCounter.count_tokens(&doc).unwrap(), 16);
+        assert!(!doc.contains("src/a.rs") && !doc.contains("use a;"));
+
+        let (doc, cut) = templates
+            .render_doc_chunk_with_tokenizer(&chunk, None)
+            .unwrap();
+        assert!(!cut);
+        assert_eq!(doc, templates.render_doc_chunk(&chunk).unwrap());
+    }
+}
@@ -15,4 +15,13 @@ pub struct SearchResult {
     pub chunk: CodeChunk,
     pub score: f32,
     pub id: String,
+    /// When the chunk's file was indexed; `None` when the index predates timestamps
+    pub freshness: Option<ChunkFreshness>,
+}
+
+/// When a file's chunks were indexed, and the file mtime the indexer saw at that point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkFreshness {
+    pub indexed_at_ms: u64,
+    pub file_mtime_ms: u64,
 }